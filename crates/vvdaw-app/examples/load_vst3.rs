@@ -127,8 +127,14 @@ fn main() -> Result<()> {
                 AudioEvent::Stopped => {
                     println!("→ Audio processing STOPPED");
                 }
-                AudioEvent::EngineInitialized { sample_rate } => {
-                    println!("→ Audio engine initialized at {sample_rate}Hz");
+                AudioEvent::EngineInitialized {
+                    sample_rate,
+                    output_channels,
+                    device_name,
+                } => {
+                    println!(
+                        "→ Audio engine initialized at {sample_rate}Hz, {output_channels} channels on {device_name}"
+                    );
                 }
                 AudioEvent::PeakLevel { channel, level } => {
                     peak_count += 1;
@@ -137,6 +143,12 @@ fn main() -> Result<()> {
                         println!("  Peak [ch {channel}]: {level:.4}");
                     }
                 }
+                AudioEvent::RealtimePriority { granted, detail } => {
+                    println!("→ Real-time priority granted: {granted} ({detail})");
+                }
+                AudioEvent::CpuAffinity { pinned, detail } => {
+                    println!("→ Audio thread pinned: {pinned} ({detail})");
+                }
                 AudioEvent::Error(msg) => {
                     eprintln!("✗ Audio error: {msg}");
                 }
@@ -146,8 +158,62 @@ fn main() -> Result<()> {
                 AudioEvent::NodeRemoved { node_id } => {
                     println!("→ Node {node_id} removed from audio graph");
                 }
-                AudioEvent::WaveformSample { .. } => {
-                    // Ignore waveform samples in this example
+                AudioEvent::NodeBypassChanged { node_id, bypassed } => {
+                    println!("→ Node {node_id} bypassed: {bypassed}");
+                }
+                AudioEvent::NodeSoloChanged { node_id, solo } => {
+                    println!("→ Node {node_id} soloed: {solo}");
+                }
+                AudioEvent::ParameterChanged {
+                    node_id,
+                    param_id,
+                    value,
+                } => {
+                    println!("→ Node {node_id} parameter {param_id} edited: {value}");
+                }
+                AudioEvent::PlaybackRateChanged { node_id, rate } => {
+                    println!("→ Node {node_id} playback rate: {rate}x");
+                }
+                AudioEvent::GraphBypassChanged { bypassed } => {
+                    println!("→ Graph bypassed: {bypassed}");
+                }
+                AudioEvent::InputMonitorChanged { enabled } => {
+                    println!("→ Input monitoring: {enabled}");
+                }
+                AudioEvent::NodeReloaded { node_id } => {
+                    println!("→ Node {node_id} reloaded");
+                }
+                AudioEvent::NodeMoved { node_id, before } => {
+                    println!("→ Node {node_id} moved before {before:?}");
+                }
+                AudioEvent::NodeMeter { node_id, peak, rms } => {
+                    println!("→ Node {node_id} level: peak {peak:.4}, rms {rms:.4}");
+                }
+                AudioEvent::LatencyChanged { frames } => {
+                    println!("→ Graph latency: {frames} frames");
+                }
+                AudioEvent::Xrun { count } => {
+                    println!("→ Xrun detected ({count} so far)");
+                }
+                AudioEvent::DspLoad { fraction } => {
+                    println!("→ DSP load: {:.0}%", fraction * 100.0);
+                }
+                AudioEvent::NodeCpu { node_id, fraction } => {
+                    println!("→ Node {node_id} CPU: {:.0}%", fraction * 100.0);
+                }
+                AudioEvent::Clip { channel, count } => {
+                    println!("→ Channel {channel} clipped ({count} samples)");
+                }
+                AudioEvent::PositionChanged { position } => {
+                    println!("→ Position moved to frame {position}");
+                }
+                AudioEvent::RecordingStopped { frames_written } => {
+                    println!("→ Recording stopped after {frames_written} frames");
+                }
+                AudioEvent::WaveformSample { .. }
+                | AudioEvent::StereoCorrelation { .. }
+                | AudioEvent::Spectrum { .. } => {
+                    // Ignore visualization data in this example
                 }
             }
         }
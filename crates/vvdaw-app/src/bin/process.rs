@@ -1,17 +1,53 @@
 //! Offline WAV file processor
 //!
-//! Processes WAV files through VST3 plugins in offline mode (non-real-time).
+//! Processes WAV files through VST3 or CLAP plugins in offline mode (non-real-time).
 //! This is useful for testing, validation, and batch processing.
+//!
+//! `--plugin builtin:<name>` selects a built-in processor (e.g. `builtin:delay`)
+//! instead of a VST3 bundle, and a `.clap` path loads a CLAP plugin.
+//!
+//! `--midi <file.mid>` plays a standard MIDI file into the plugin, so
+//! instruments can be rendered. Without `--input` the MIDI part is rendered
+//! over silence.
+//!
+//! `--mix <0.0-1.0>` blends the processed output with the original input,
+//! with the processed signal moved earlier by the plugin latency so the two
+//! line up.
+//!
+//! `--normalize` scales the processed output so its peak reaches
+//! `--normalize-db` (default -1 dBFS) before it is written.
+//!
+//! `--preset <file.vstpreset>` loads a VST3 preset (e.g. a factory preset)
+//! into the plugin before any `--param` values are applied.
+//!
+//! `--automate "ParamName@0s=0.0,2s=1.0,linear"` moves a parameter along a
+//! breakpoint curve (linear or hold) while rendering, e.g. for fades and
+//! filter sweeps. The value is updated at the start of every block.
+//!
+//! Rendering continues past the end of the input for as long as the plugin
+//! (or session graph) says its tail lasts, so reverbs and delays ring out.
+//! `--tail <seconds>` overrides the reported length.
+//!
+//! `--oversample <2|4|8>` runs the plugin (or session graph) at that multiple
+//! of the file's rate, then filters and downsamples the result, so distortion
+//! and saturation don't alias. The output keeps the input's rate and timing.
 
 use anyhow::{Context, Result};
 use clap::Parser;
 use hound::{WavReader, WavWriter};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use vvdaw_audio::builtin;
+use vvdaw_audio::automation::AutomationLane;
 use vvdaw_audio::graph::{AudioGraph, PluginSource};
+use vvdaw_audio::midi_file::MidiSequence;
+use vvdaw_audio::oversample::Oversampler;
+use vvdaw_audio::render::{self, RenderOptions};
 use vvdaw_audio::session::Session;
-use vvdaw_plugin::{AudioBuffer, EventBuffer, Plugin};
+use vvdaw_audio::wav_progress::wav_open_error;
+use vvdaw_audio::{AudioConfig, builtin};
+use vvdaw_clap::ClapLoader;
+use vvdaw_core::{Frames, SampleRate, db_to_linear, linear_to_db};
+use vvdaw_plugin::{INFINITE_TAIL, ParameterInfo, Plugin};
 use vvdaw_vst3::MultiProcessPlugin;
 
 /// Maximum block size (same as `AudioGraph::MAX_BLOCK_SIZE`)
@@ -20,20 +56,29 @@ const MAX_BLOCK_SIZE: usize = 8192;
 /// Maximum supported channels
 const MAX_CHANNELS: usize = 8;
 
+/// `--plugin` prefix selecting a built-in processor instead of a VST3 bundle
+const BUILTIN_SCHEME: &str = "builtin:";
+
+/// Seconds rendered after the last MIDI event so notes can release
+const MIDI_TAIL_SECONDS: u32 = 2;
+
+/// Seconds rendered after the input for plugins whose tail never ends
+const INFINITE_TAIL_SECONDS: u32 = 10;
+
 /// Offline WAV file processor
 #[derive(Parser, Debug)]
 #[command(name = "vvdaw-process")]
-#[command(about = "Process WAV files through VST3 plugins", long_about = None)]
+#[command(about = "Process WAV files through VST3 or CLAP plugins", long_about = None)]
 struct Args {
     /// Input WAV file
-    #[arg(short, long, required_unless_present_any = ["inspect", "save_session"])]
+    #[arg(short, long, required_unless_present_any = ["inspect", "save_session", "midi"])]
     input: Option<PathBuf>,
 
     /// Output WAV file
     #[arg(short, long, required_unless_present_any = ["inspect", "save_session"])]
     output: Option<PathBuf>,
 
-    /// VST3 plugin path (.vst3 bundle)
+    /// Plugin path (.vst3 bundle or .clap), or `builtin:<name>` for a built-in processor
     #[arg(short, long, required_unless_present_any = ["inspect", "session"])]
     plugin: Option<PathBuf>,
 
@@ -41,8 +86,8 @@ struct Args {
     #[arg(short, long, default_value_t = 512)]
     block_size: usize,
 
-    /// Sample rate for session saving (default: 48000 Hz)
-    /// Only used with --save-session when no input file is specified
+    /// Sample rate for session saving and MIDI-only rendering (default: 48000 Hz)
+    /// Only used when no input file is specified
     #[arg(long, default_value_t = 48000)]
     sample_rate: u32,
 
@@ -53,6 +98,18 @@ struct Args {
     #[arg(long = "param")]
     params: Vec<String>,
 
+    /// VST3 preset (.vstpreset) to load into the plugin before processing
+    /// Applied before any --param values
+    #[arg(long, conflicts_with = "session")]
+    preset: Option<PathBuf>,
+
+    /// Automate a parameter over time (format: "ParamName@0s=0.0,2s=1.0,linear")
+    /// Breakpoints are <seconds>=<value> from the start of the file, optionally
+    /// followed by the interpolation ("linear", the default, or "hold").
+    /// Can be specified multiple times for different parameters.
+    #[arg(long, conflicts_with = "session", value_parser = parse_automation)]
+    automate: Vec<AutomationLane>,
+
     /// Load session file (.ron) instead of specifying plugin
     #[arg(short, long, conflicts_with = "plugin")]
     session: Option<PathBuf>,
@@ -61,11 +118,151 @@ struct Args {
     #[arg(long)]
     save_session: Option<PathBuf>,
 
+    /// Dry/wet mix: 0.0 = input unchanged, 1.0 = fully processed (default)
+    /// Plugin latency is compensated so dry and wet stay aligned
+    #[arg(long, value_parser = parse_mix)]
+    mix: Option<f32>,
+
+    /// Scale the output so its peak sample reaches --normalize-db
+    #[arg(long)]
+    normalize: bool,
+
+    /// Peak level for --normalize in dBFS (default: -1.0, must be <= 0)
+    #[arg(
+        long,
+        default_value_t = -1.0,
+        allow_negative_numbers = true,
+        value_parser = parse_normalize_db
+    )]
+    normalize_db: f32,
+
+    /// Standard MIDI file to play into the plugin (e.g. to render an instrument)
+    /// Output is extended past the last event so notes can release
+    #[arg(long)]
+    midi: Option<PathBuf>,
+
+    /// Seconds of silence to keep rendering after the input ends
+    /// Defaults to the tail reported by the plugin (or the session's plugins)
+    #[arg(long, value_parser = parse_tail)]
+    tail: Option<f64>,
+
+    /// Process at 2, 4 or 8 times the sample rate to avoid aliasing
+    /// Worth it for distortion and saturation; costs that many times the CPU
+    #[arg(long, value_parser = parse_oversample)]
+    oversample: Option<usize>,
+
     /// Inspect plugin parameters and info (don't process audio)
-    #[arg(long, conflicts_with_all = ["input", "output", "session"])]
+    #[arg(long, conflicts_with_all = ["input", "output", "session", "midi"])]
     inspect: bool,
 }
 
+/// Parse `--mix`, which must be between 0.0 and 1.0
+fn parse_mix(value: &str) -> Result<f32, String> {
+    let mix: f32 = value
+        .parse()
+        .map_err(|_| format!("'{value}' is not a number"))?;
+    if (0.0..=1.0).contains(&mix) {
+        Ok(mix)
+    } else {
+        Err(format!("{mix} is outside 0.0-1.0"))
+    }
+}
+
+/// Parse `--tail`, which must be a non-negative number of seconds
+fn parse_tail(value: &str) -> Result<f64, String> {
+    let seconds: f64 = value
+        .parse()
+        .map_err(|_| format!("'{value}' is not a number"))?;
+    if seconds.is_finite() && seconds >= 0.0 {
+        Ok(seconds)
+    } else {
+        Err(format!("{seconds} is not a length in seconds"))
+    }
+}
+
+/// Parse `--oversample`, which must be 2, 4 or 8
+fn parse_oversample(value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(factor @ (2 | 4 | 8)) => Ok(factor),
+        _ => Err(format!(
+            "'{value}' is not an oversampling factor (2, 4 or 8)"
+        )),
+    }
+}
+
+/// Upsample `samples` for `--oversample`, returning them with the rate to render at
+///
+/// Without an oversampler the samples are rendered at `sample_rate` as they are.
+fn oversample_input(
+    oversampler: Option<&Oversampler>,
+    samples: Vec<f32>,
+    channel_count: usize,
+    sample_rate: SampleRate,
+) -> Result<(Vec<f32>, SampleRate)> {
+    let Some(oversampler) = oversampler else {
+        return Ok((samples, sample_rate));
+    };
+    let render_rate = u32::try_from(oversampler.factor())
+        .ok()
+        .and_then(|factor| sample_rate.checked_mul(factor))
+        .context("Oversampled rate is out of range")?;
+    tracing::info!(
+        "Oversampling {}x: rendering at {render_rate} Hz",
+        oversampler.factor()
+    );
+    Ok((oversampler.upsample(&samples, channel_count), render_rate))
+}
+
+/// Bring output rendered by [`oversample_input`] back to the file's rate
+fn downsample_output(
+    oversampler: Option<&Oversampler>,
+    samples: Vec<f32>,
+    channel_count: usize,
+) -> Vec<f32> {
+    match oversampler {
+        Some(oversampler) => oversampler.downsample(&samples, channel_count),
+        None => samples,
+    }
+}
+
+/// Frames to render after the input: `--tail` if given, else the `reported` tail
+///
+/// An endless reported tail is cut off after [`INFINITE_TAIL_SECONDS`].
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Non-negative seconds
+fn tail_frames(reported: Frames, tail: Option<f64>, sample_rate: SampleRate) -> Frames {
+    let frames = match tail {
+        Some(seconds) => (seconds * f64::from(sample_rate)).round() as Frames,
+        None if reported == INFINITE_TAIL => {
+            tracing::warn!(
+                "Plugin tail never ends, rendering {INFINITE_TAIL_SECONDS}s (set --tail to change)"
+            );
+            (INFINITE_TAIL_SECONDS * sample_rate) as Frames
+        }
+        None => reported,
+    };
+    if frames > 0 {
+        tracing::info!("Rendering {frames} frames of tail after the input");
+    }
+    frames
+}
+
+/// Parse an `--automate` breakpoint curve
+fn parse_automation(value: &str) -> Result<AutomationLane, String> {
+    value.parse().map_err(|e| format!("{e}"))
+}
+
+/// Parse `--normalize-db`, which must be at or below 0 dBFS
+fn parse_normalize_db(value: &str) -> Result<f32, String> {
+    let db: f32 = value
+        .parse()
+        .map_err(|_| format!("'{value}' is not a number"))?;
+    if db.is_finite() && db <= 0.0 {
+        Ok(db)
+    } else {
+        Err(format!("{value} must be a level at or below 0 dBFS"))
+    }
+}
+
 fn main() -> Result<()> {
     // Initialize tracing
     tracing_subscriber::registry()
@@ -94,13 +291,49 @@ fn main() -> Result<()> {
     }
 }
 
+/// Load the plugin named by `--plugin`
+///
+/// `builtin:<name>` creates a built-in processor and `.clap` paths load a CLAP
+/// plugin in-process; anything else is treated as a VST3 bundle path and
+/// spawned in a subprocess. Also returns the source to record when the plugin
+/// is added to a graph.
+fn load_plugin(plugin_path: &Path) -> Result<(Box<dyn Plugin>, PluginSource)> {
+    if let Some(name) = plugin_path
+        .to_str()
+        .and_then(|path| path.strip_prefix(BUILTIN_SCHEME))
+    {
+        let plugin = builtin::create_builtin(name)
+            .with_context(|| format!("Unknown built-in processor: {name}"))?;
+        let source = PluginSource::Builtin {
+            name: name.to_string(),
+        };
+        return Ok((plugin, source));
+    }
+
+    if plugin_path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("clap"))
+    {
+        let plugin = ClapLoader::load(plugin_path).context("Failed to load CLAP plugin")?;
+        let source = PluginSource::Clap {
+            path: plugin_path.to_path_buf(),
+        };
+        return Ok((Box::new(plugin), source));
+    }
+
+    let plugin =
+        MultiProcessPlugin::spawn(plugin_path).context("Failed to spawn plugin subprocess")?;
+    let source = PluginSource::Vst3 {
+        path: plugin_path.to_path_buf(),
+    };
+    Ok((Box::new(plugin), source))
+}
+
 /// Inspect plugin parameters and information
-fn inspect_plugin(plugin_path: &PathBuf) -> Result<()> {
+fn inspect_plugin(plugin_path: &Path) -> Result<()> {
     println!("Inspecting plugin: {}\n", plugin_path.display());
 
-    // Load VST3 plugin
-    let mut plugin =
-        MultiProcessPlugin::spawn(plugin_path).context("Failed to spawn plugin subprocess")?;
+    let (mut plugin, _) = load_plugin(plugin_path)?;
 
     let info = plugin.info();
 
@@ -138,12 +371,25 @@ fn inspect_plugin(plugin_path: &PathBuf) -> Result<()> {
                 "        Range:   {:.3} to {:.3}",
                 param.min_value, param.max_value
             );
-            println!("        Default: {:.3}", param.default_value);
+            if param.is_discrete() {
+                println!(
+                    "        Steps:   {} ({} positions)",
+                    param.step_count,
+                    param.step_count + 1
+                );
+            }
+            println!(
+                "        Default: {}",
+                display_value(plugin.as_ref(), param.id, param.default_value)
+            );
 
             // Try to get current value
             match plugin.get_parameter(param.id) {
                 Ok(value) => {
-                    println!("        Current: {value:.3}");
+                    println!(
+                        "        Current: {}",
+                        display_value(plugin.as_ref(), param.id, value)
+                    );
                 }
                 Err(e) => {
                     println!("        Current: <unavailable> ({e})");
@@ -156,6 +402,17 @@ fn inspect_plugin(plugin_path: &PathBuf) -> Result<()> {
     Ok(())
 }
 
+/// Format a parameter value for `--inspect`
+///
+/// Shows the plugin's own display text next to the raw value when it has
+/// one (e.g. `-6.0 dB (0.750)`), otherwise just the raw value.
+fn display_value(plugin: &dyn Plugin, id: u32, value: f32) -> String {
+    match plugin.parameter_display(id, value) {
+        Ok(text) if !text.trim().is_empty() => format!("{} ({value:.3})", text.trim()),
+        _ => format!("{value:.3}"),
+    }
+}
+
 /// Apply parameter settings to plugin
 fn apply_parameters(plugin: &mut dyn Plugin, param_specs: &[String]) -> Result<()> {
     // Get all available parameters
@@ -193,7 +450,19 @@ fn apply_parameters(plugin: &mut dyn Plugin, param_specs: &[String]) -> Result<(
             );
         }
 
-        let clamped_value = value.clamp(param.min_value, param.max_value);
+        // Clamp, and snap switches/mode selectors to their nearest position
+        let clamped_value = param.constrain(value);
+        if param.is_discrete()
+            && (clamped_value - value.clamp(param.min_value, param.max_value)).abs() > f32::EPSILON
+        {
+            tracing::info!(
+                "Parameter '{}' has {} steps, snapping {:.3} to {:.3}",
+                param_name,
+                param.step_count,
+                value,
+                clamped_value
+            );
+        }
 
         // Set the parameter
         plugin
@@ -211,6 +480,41 @@ fn apply_parameters(plugin: &mut dyn Plugin, param_specs: &[String]) -> Result<(
     Ok(())
 }
 
+/// Match `--automate` lanes to the plugin's parameters
+///
+/// Each parameter may only be automated once.
+fn resolve_automation<'a>(
+    plugin: &dyn Plugin,
+    lanes: &'a [AutomationLane],
+) -> Result<Vec<(ParameterInfo, &'a AutomationLane)>> {
+    let parameters = plugin.parameters();
+    let mut automation: Vec<(ParameterInfo, &AutomationLane)> = Vec::with_capacity(lanes.len());
+
+    for lane in lanes {
+        let param = parameters
+            .iter()
+            .find(|p| p.name == lane.parameter)
+            .with_context(|| format!("Parameter '{}' not found in plugin", lane.parameter))?;
+        if automation
+            .iter()
+            .any(|(existing, _)| existing.id == param.id)
+        {
+            anyhow::bail!("Parameter '{}' is automated more than once", lane.parameter);
+        }
+
+        tracing::info!(
+            "Automating '{}' (ID {}) with {} breakpoint(s), {:?}",
+            lane.parameter,
+            param.id,
+            lane.points().len(),
+            lane.interpolation
+        );
+        automation.push((param.clone(), lane));
+    }
+
+    Ok(automation)
+}
+
 /// Save session mode - configure plugin and save session without processing
 fn save_session_mode(args: &Args) -> Result<()> {
     let plugin_path = args
@@ -238,8 +542,7 @@ fn save_session_mode(args: &Args) -> Result<()> {
     tracing::info!("Block size: {} frames", args.block_size);
 
     // Load plugin
-    let mut plugin =
-        MultiProcessPlugin::spawn(plugin_path).context("Failed to spawn plugin subprocess")?;
+    let (mut plugin, source) = load_plugin(plugin_path)?;
 
     plugin
         .initialize(args.sample_rate, args.block_size)
@@ -250,17 +553,14 @@ fn save_session_mode(args: &Args) -> Result<()> {
     // Apply parameters if specified
     if !args.params.is_empty() {
         tracing::info!("Setting {} parameter(s)...", args.params.len());
-        apply_parameters(&mut plugin, &args.params)?;
+        apply_parameters(plugin.as_mut(), &args.params)?;
     }
 
     // Create graph with single plugin
     let mut graph = AudioGraph::with_config(args.sample_rate, args.block_size);
-    let source = PluginSource::Vst3 {
-        path: plugin_path.clone(),
-    };
 
     graph
-        .add_node(Box::new(plugin), source)
+        .add_node(plugin, source)
         .context("Failed to add plugin to graph")?;
 
     // Create and save session
@@ -279,10 +579,6 @@ fn save_session_mode(args: &Args) -> Result<()> {
 
 /// Process audio using a session file
 fn process_with_session(args: &Args) -> Result<()> {
-    let input = args
-        .input
-        .as_ref()
-        .context("--input required for processing")?;
     let output = args
         .output
         .as_ref()
@@ -290,15 +586,9 @@ fn process_with_session(args: &Args) -> Result<()> {
     let session_path = args.session.as_ref().context("--session path required")?;
 
     tracing::info!("vvdaw-process: Processing with session");
-    tracing::info!("Input:   {}", input.display());
     tracing::info!("Output:  {}", output.display());
     tracing::info!("Session: {}", session_path.display());
 
-    // Validate input file exists
-    if !input.exists() {
-        anyhow::bail!("Input file does not exist: {}", input.display());
-    }
-
     // Load session
     let session = Session::load(session_path).context("Failed to load session")?;
 
@@ -319,19 +609,10 @@ fn process_with_session(args: &Args) -> Result<()> {
         );
     }
 
-    // Read input WAV
-    tracing::info!("Reading input WAV file...");
-    let mut reader = WavReader::open(input)
-        .with_context(|| format!("Failed to open input file: {}", input.display()))?;
-
-    let spec = reader.spec();
-    tracing::info!(
-        "Input format: {} Hz, {} channels, {} bits, {:?}",
-        spec.sample_rate,
-        spec.channels,
-        spec.bits_per_sample,
-        spec.sample_format
-    );
+    let (samples, spec) = match args.input.as_ref() {
+        Some(input) => read_input(input)?,
+        None => (Vec::new(), midi_render_spec(session.sample_rate)),
+    };
 
     // Validate WAV sample rate matches session
     if spec.sample_rate != session.sample_rate {
@@ -342,19 +623,27 @@ fn process_with_session(args: &Args) -> Result<()> {
         );
     }
 
-    // Validate channel count
     let channel_count = spec.channels as usize;
-    if channel_count > MAX_CHANNELS {
-        anyhow::bail!("WAV file has {channel_count} channels, maximum supported is {MAX_CHANNELS}");
-    }
-
-    // Read samples
-    let samples = read_wav_samples(&mut reader, &spec)?;
+    let oversampler = args.oversample.map(Oversampler::new);
+    let (samples, render_rate) = oversample_input(
+        oversampler.as_ref(),
+        samples,
+        channel_count,
+        spec.sample_rate,
+    )?;
+    let midi = load_midi(args, render_rate)?;
+    let samples = pad_for_midi(samples, channel_count, midi.as_ref(), render_rate);
 
     // Reconstruct graph from session
+    // Offline, the session's own rate and block size are the "engine" config
     tracing::info!("Reconstructing audio graph from session...");
+    let render_config = AudioConfig {
+        sample_rate: session.sample_rate,
+        block_size: session.block_size,
+        ..AudioConfig::default()
+    };
     let mut graph = session
-        .to_graph(|plugin_spec| {
+        .to_graph(&render_config, |plugin_spec| {
             use vvdaw_audio::session::PluginSpec;
             match plugin_spec {
                 PluginSpec::Builtin { name, .. } => {
@@ -368,16 +657,40 @@ fn process_with_session(args: &Args) -> Result<()> {
                         .map_err(|e| format!("Failed to spawn plugin: {e}"))?;
                     Ok(Box::new(plugin) as Box<dyn Plugin>)
                 }
+                PluginSpec::Clap { path, .. } => {
+                    tracing::info!("  Loading CLAP plugin: {}", path.display());
+                    let plugin = ClapLoader::load(path)
+                        .map_err(|e| format!("Failed to load CLAP plugin: {e}"))?;
+                    Ok(Box::new(plugin) as Box<dyn Plugin>)
+                }
             }
         })
         .context("Failed to reconstruct graph from session")?;
 
     tracing::info!("Graph reconstructed with {} node(s)", graph.nodes().count());
 
+    if render_rate != session.sample_rate {
+        let failures = graph.set_config(render_rate, session.block_size);
+        if let Some((node_id, e)) = failures.first() {
+            anyhow::bail!("Node {node_id} failed to initialize at {render_rate} Hz: {e}");
+        }
+    }
+
     // Process audio (use session's block_size, not args)
     tracing::info!("Processing audio...");
-    let output_samples =
-        process_audio_with_graph(&samples, channel_count, &mut graph, session.block_size);
+    let tail = tail_frames(graph.tail(), args.tail, render_rate);
+    let options = RenderOptions {
+        midi: midi.as_ref(),
+        tail,
+        mix: args.mix,
+        ..RenderOptions::new(graph.sample_rate(), session.block_size)
+    };
+    let output_samples = render::render_graph(&mut graph, &samples, channel_count, &options);
+    let mut output_samples = downsample_output(oversampler.as_ref(), output_samples, channel_count);
+
+    if args.normalize {
+        normalize(&mut output_samples, args.normalize_db);
+    }
 
     // Write output
     tracing::info!("Writing output WAV file...");
@@ -391,10 +704,6 @@ fn process_with_session(args: &Args) -> Result<()> {
 
 /// Process audio file through single plugin (original mode)
 fn process_with_plugin(args: &Args) -> Result<()> {
-    let input = args
-        .input
-        .as_ref()
-        .context("--input required for processing")?;
     let output = args
         .output
         .as_ref()
@@ -405,7 +714,6 @@ fn process_with_plugin(args: &Args) -> Result<()> {
         .context("--plugin required when not using --session")?;
 
     tracing::info!("vvdaw-process: Offline WAV processor");
-    tracing::info!("Input:  {}", input.display());
     tracing::info!("Output: {}", output.display());
     tracing::info!("Plugin: {}", plugin_path.display());
     tracing::info!("Block size: {} frames", args.block_size);
@@ -419,44 +727,35 @@ fn process_with_plugin(args: &Args) -> Result<()> {
         );
     }
 
-    // Validate input file exists
-    if !input.exists() {
-        anyhow::bail!("Input file does not exist: {}", input.display());
-    }
-
-    // Read input WAV file
-    tracing::info!("Reading input WAV file...");
-    let mut reader = WavReader::open(input)
-        .with_context(|| format!("Failed to open input file: {}", input.display()))?;
-
-    let spec = reader.spec();
-    tracing::info!(
-        "Input format: {} Hz, {} channels, {} bits, {:?}",
-        spec.sample_rate,
-        spec.channels,
-        spec.bits_per_sample,
-        spec.sample_format
-    );
+    let (samples, spec) = match args.input.as_ref() {
+        Some(input) => read_input(input)?,
+        None => (Vec::new(), midi_render_spec(args.sample_rate)),
+    };
 
-    // Validate channel count
     let channel_count = spec.channels as usize;
-    if channel_count > MAX_CHANNELS {
-        anyhow::bail!("WAV file has {channel_count} channels, maximum supported is {MAX_CHANNELS}");
-    }
-
-    // Read samples
-    let samples = read_wav_samples(&mut reader, &spec)?;
+    let oversampler = args.oversample.map(Oversampler::new);
+    let (samples, render_rate) = oversample_input(
+        oversampler.as_ref(),
+        samples,
+        channel_count,
+        spec.sample_rate,
+    )?;
+    let midi = load_midi(args, render_rate)?;
+    let samples = pad_for_midi(samples, channel_count, midi.as_ref(), render_rate);
     let frame_count = samples.len() / channel_count;
 
-    tracing::info!("Read {} frames ({} samples)", frame_count, samples.len());
+    tracing::info!(
+        "Rendering {} frames ({} samples)",
+        frame_count,
+        samples.len()
+    );
 
-    // Load VST3 plugin
-    tracing::info!("Loading VST3 plugin...");
-    let mut plugin =
-        MultiProcessPlugin::spawn(plugin_path).context("Failed to spawn plugin subprocess")?;
+    // Load plugin
+    tracing::info!("Loading plugin...");
+    let (mut plugin, _) = load_plugin(plugin_path)?;
 
     plugin
-        .initialize(spec.sample_rate, args.block_size)
+        .initialize(render_rate, args.block_size)
         .context("Failed to initialize plugin")?;
 
     tracing::info!("Plugin loaded: {}", plugin.info().name);
@@ -466,15 +765,38 @@ fn process_with_plugin(args: &Args) -> Result<()> {
         plugin.output_channels()
     );
 
+    if let Some(preset) = &args.preset {
+        tracing::info!("Loading preset: {}", preset.display());
+        plugin
+            .load_preset(preset)
+            .with_context(|| format!("Failed to load preset {}", preset.display()))?;
+    }
+
     // Apply parameter settings
     if !args.params.is_empty() {
         tracing::info!("Setting {} parameter(s)...", args.params.len());
-        apply_parameters(&mut plugin, &args.params)?;
+        apply_parameters(plugin.as_mut(), &args.params)?;
     }
 
+    let automation = resolve_automation(plugin.as_ref(), &args.automate)?;
+    let tail = tail_frames(plugin.tail_samples(), args.tail, render_rate);
+
     // Process audio in blocks
     tracing::info!("Processing audio...");
-    let output_samples = process_audio(&samples, channel_count, &mut plugin, args.block_size)?;
+    let options = RenderOptions {
+        midi: midi.as_ref(),
+        automation: &automation,
+        tail,
+        mix: args.mix,
+        ..RenderOptions::new(render_rate, args.block_size)
+    };
+    let output_samples = render::render_plugin(plugin.as_mut(), &samples, channel_count, &options)
+        .context("Failed to process audio")?;
+    let mut output_samples = downsample_output(oversampler.as_ref(), output_samples, channel_count);
+
+    if args.normalize {
+        normalize(&mut output_samples, args.normalize_db);
+    }
 
     // Write output WAV
     tracing::info!("Writing output WAV file...");
@@ -485,6 +807,78 @@ fn process_with_plugin(args: &Args) -> Result<()> {
     Ok(())
 }
 
+/// Open the input WAV file and read its samples as f32
+fn read_input(input: &Path) -> Result<(Vec<f32>, hound::WavSpec)> {
+    tracing::info!("Input:  {}", input.display());
+
+    // Validate input file exists
+    if !input.exists() {
+        anyhow::bail!("Input file does not exist: {}", input.display());
+    }
+
+    tracing::info!("Reading input WAV file...");
+    let mut reader = WavReader::open(input)
+        .map_err(|e| anyhow::anyhow!("{}: {}", input.display(), wav_open_error(e)))?;
+
+    let spec = reader.spec();
+    tracing::info!(
+        "Input format: {} Hz, {} channels, {} bits, {:?}",
+        spec.sample_rate,
+        spec.channels,
+        spec.bits_per_sample,
+        spec.sample_format
+    );
+
+    // Validate channel count
+    let channel_count = spec.channels as usize;
+    if channel_count > MAX_CHANNELS {
+        anyhow::bail!("WAV file has {channel_count} channels, maximum supported is {MAX_CHANNELS}");
+    }
+
+    let samples = read_wav_samples(&mut reader, &spec)?;
+    Ok((samples, spec))
+}
+
+/// Output format when rendering MIDI without an input file (stereo, 32-bit float)
+const fn midi_render_spec(sample_rate: u32) -> hound::WavSpec {
+    hound::WavSpec {
+        channels: 2,
+        sample_rate,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    }
+}
+
+/// Load `--midi`, if given, timed at `sample_rate`
+fn load_midi(args: &Args, sample_rate: u32) -> Result<Option<MidiSequence>> {
+    let Some(path) = args.midi.as_ref() else {
+        return Ok(None);
+    };
+
+    tracing::info!("MIDI:   {}", path.display());
+    let sequence = MidiSequence::load(path, sample_rate)
+        .with_context(|| format!("Failed to load MIDI file: {}", path.display()))?;
+    tracing::info!("Loaded {} note events", sequence.len());
+
+    Ok(Some(sequence))
+}
+
+/// Extend `samples` with silence so the MIDI part plays out, plus a release tail
+fn pad_for_midi(
+    mut samples: Vec<f32>,
+    channel_count: usize,
+    midi: Option<&MidiSequence>,
+    sample_rate: u32,
+) -> Vec<f32> {
+    if let Some(midi) = midi {
+        let frames = midi.end_frame() as usize + (MIDI_TAIL_SECONDS * sample_rate) as usize;
+        if samples.len() < frames * channel_count {
+            samples.resize(frames * channel_count, 0.0);
+        }
+    }
+    samples
+}
+
 /// Read WAV samples and convert to f32
 fn read_wav_samples(
     reader: &mut WavReader<std::io::BufReader<std::fs::File>>,
@@ -501,6 +895,12 @@ fn read_wav_samples(
         hound::SampleFormat::Int => {
             tracing::info!("Reading integer samples and converting to float...");
             match spec.bits_per_sample {
+                // 8-bit WAV is unsigned, but hound shifts it to signed (-128..127)
+                8 => reader
+                    .samples::<i8>()
+                    .map(|s| s.map(|sample| f32::from(sample) / 128.0))
+                    .collect::<Result<Vec<_>, _>>()
+                    .context("Failed to read 8-bit samples")?,
                 16 => reader
                     .samples::<i16>()
                     .map(|s| s.map(|sample| f32::from(sample) / f32::from(i16::MAX)))
@@ -526,149 +926,29 @@ fn read_wav_samples(
     Ok(samples)
 }
 
-/// Process audio through an `AudioGraph` in offline mode
-fn process_audio_with_graph(
-    input_samples: &[f32],
-    channel_count: usize,
-    graph: &mut AudioGraph,
-    block_size: usize,
-) -> Vec<f32> {
-    let frame_count = input_samples.len() / channel_count;
-    let mut output_samples = vec![0.0_f32; input_samples.len()];
-
-    // Deinterleave input samples into per-channel buffers
-    let mut input_buffers: Vec<Vec<f32>> = vec![vec![0.0; block_size]; channel_count];
-    let mut output_buffers: Vec<Vec<f32>> = vec![vec![0.0; block_size]; channel_count];
-
-    let mut frames_processed = 0;
-    while frames_processed < frame_count {
-        let frames_remaining = frame_count - frames_processed;
-        let current_block_size = frames_remaining.min(block_size);
-
-        // Deinterleave input block
-        for frame in 0..current_block_size {
-            let sample_offset = (frames_processed + frame) * channel_count;
-            for (ch, buf) in input_buffers.iter_mut().enumerate() {
-                buf[frame] = input_samples
-                    .get(sample_offset + ch)
-                    .copied()
-                    .unwrap_or(0.0);
-            }
-        }
-
-        // Clear remaining samples in block if not full
-        if current_block_size < block_size {
-            for buf in &mut input_buffers {
-                buf[current_block_size..].fill(0.0);
-            }
-        }
-
-        // Prepare input/output references (take only current_block_size)
-        let input_refs: Vec<&[f32]> = input_buffers
-            .iter()
-            .map(|buf| &buf[..current_block_size])
-            .collect();
-        let mut output_refs: Vec<&mut [f32]> = output_buffers
-            .iter_mut()
-            .map(|buf| &mut buf[..current_block_size])
-            .collect();
-
-        // Process through graph
-        graph.process(&input_refs, &mut output_refs);
-
-        // Interleave output block
-        for frame in 0..current_block_size {
-            let sample_offset = (frames_processed + frame) * channel_count;
-            for (ch, buf) in output_buffers.iter().enumerate() {
-                if sample_offset + ch < output_samples.len() {
-                    output_samples[sample_offset + ch] = buf[frame];
-                }
-            }
-        }
-
-        frames_processed += current_block_size;
-
-        if frames_processed % (block_size * 100) == 0 {
-            tracing::debug!("Processed {}/{} frames", frames_processed, frame_count);
-        }
+/// Scale `samples` so the peak absolute sample reaches `target_db` dBFS
+///
+/// Runs on the float output, before `write_wav` quantizes it. Silence (peak 0)
+/// is left untouched.
+fn normalize(samples: &mut [f32], target_db: f32) {
+    let peak = samples
+        .iter()
+        .fold(0.0_f32, |peak, sample| peak.max(sample.abs()));
+
+    if peak == 0.0 {
+        tracing::warn!("Output is silent, skipping normalization");
+        return;
     }
 
-    tracing::info!("Processed {} frames total", frames_processed);
-    output_samples
-}
-
-/// Process audio through the plugin in offline mode
-fn process_audio(
-    input_samples: &[f32],
-    channel_count: usize,
-    plugin: &mut dyn Plugin,
-    block_size: usize,
-) -> Result<Vec<f32>> {
-    let frame_count = input_samples.len() / channel_count;
-    let mut output_samples = vec![0.0_f32; input_samples.len()];
-
-    // Deinterleave input samples into per-channel buffers
-    let mut input_buffers: Vec<Vec<f32>> = vec![vec![0.0; block_size]; channel_count];
-    let mut output_buffers: Vec<Vec<f32>> = vec![vec![0.0; block_size]; channel_count];
-
-    let event_buffer = EventBuffer::new();
-
-    let mut frames_processed = 0;
-    while frames_processed < frame_count {
-        let frames_remaining = frame_count - frames_processed;
-        let current_block_size = frames_remaining.min(block_size);
-
-        // Deinterleave input block
-        for frame in 0..current_block_size {
-            let sample_offset = (frames_processed + frame) * channel_count;
-            for (ch, buf) in input_buffers.iter_mut().enumerate() {
-                buf[frame] = input_samples
-                    .get(sample_offset + ch)
-                    .copied()
-                    .unwrap_or(0.0);
-            }
-        }
-
-        // Clear remaining samples in block if not full
-        if current_block_size < block_size {
-            for buf in &mut input_buffers {
-                buf[current_block_size..].fill(0.0);
-            }
-        }
-
-        // Prepare input/output references
-        let input_refs: Vec<&[f32]> = input_buffers.iter().map(Vec::as_slice).collect();
-        let mut output_refs: Vec<&mut [f32]> =
-            output_buffers.iter_mut().map(Vec::as_mut_slice).collect();
-
-        let mut audio = AudioBuffer {
-            inputs: &input_refs,
-            outputs: &mut output_refs,
-            frames: current_block_size,
-        };
-
-        // Process through plugin
-        plugin.process(&mut audio, &event_buffer)?;
-
-        // Interleave output block
-        for frame in 0..current_block_size {
-            let sample_offset = (frames_processed + frame) * channel_count;
-            for (ch, buf) in output_buffers.iter().enumerate() {
-                if sample_offset + ch < output_samples.len() {
-                    output_samples[sample_offset + ch] = buf[frame];
-                }
-            }
-        }
-
-        frames_processed += current_block_size;
+    let gain = db_to_linear(target_db) / peak;
+    tracing::info!(
+        "Normalizing peak {:.2} dBFS to {target_db:.2} dBFS (gain {gain:.3})",
+        linear_to_db(peak)
+    );
 
-        if frames_processed % (block_size * 100) == 0 {
-            tracing::debug!("Processed {}/{} frames", frames_processed, frame_count);
-        }
+    for sample in samples {
+        *sample *= gain;
     }
-
-    tracing::info!("Processed {} frames total", frames_processed);
-    Ok(output_samples)
 }
 
 /// Write interleaved samples to WAV file
@@ -686,6 +966,15 @@ fn write_wav(path: &PathBuf, samples: &[f32], spec: hound::WavSpec) -> Result<()
         }
         hound::SampleFormat::Int => {
             match spec.bits_per_sample {
+                8 => {
+                    for &sample in samples {
+                        // hound stores i8 as unsigned 8-bit on disk
+                        let int_sample = (sample.clamp(-1.0, 1.0) * f32::from(i8::MAX)) as i8;
+                        writer
+                            .write_sample(int_sample)
+                            .context("Failed to write 8-bit sample")?;
+                    }
+                }
                 16 => {
                     for &sample in samples {
                         let int_sample = (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16;
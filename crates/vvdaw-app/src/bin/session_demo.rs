@@ -6,9 +6,9 @@ use anyhow::{Context, Result};
 use clap::Parser;
 use std::path::PathBuf;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use vvdaw_audio::builtin;
 use vvdaw_audio::graph::{AudioGraph, PluginSource};
 use vvdaw_audio::session::{PluginSpec, Session};
+use vvdaw_audio::{AudioConfig, builtin};
 use vvdaw_plugin::Plugin;
 use vvdaw_vst3::MultiProcessPlugin;
 
@@ -42,7 +42,12 @@ fn main() -> Result<()> {
 
     // Step 1: Create an audio graph
     println!("Step 1: Creating audio graph...");
-    let mut graph = AudioGraph::with_config(48000, 512);
+    let config = AudioConfig {
+        sample_rate: 48000,
+        block_size: 512,
+        ..AudioConfig::default()
+    };
+    let mut graph = AudioGraph::with_config(config.sample_rate, config.block_size);
 
     // Step 2: Load VST3 plugin
     println!(
@@ -93,8 +98,11 @@ fn main() -> Result<()> {
 
     // Step 6: Reconstruct graph from session
     println!("\nStep 6: Reconstructing audio graph from session...");
+    loaded_session
+        .verify_compatible(&config)
+        .context("Session doesn't match the engine")?;
     let reconstructed_graph = loaded_session
-        .to_graph(|spec| match spec {
+        .to_graph(&config, |spec| match spec {
             PluginSpec::Builtin { name, .. } => {
                 println!("  Loading built-in: {name}");
                 builtin::create_builtin(name)
@@ -106,6 +114,12 @@ fn main() -> Result<()> {
                     .map_err(|e| format!("Failed to spawn plugin: {e}"))?;
                 Ok(Box::new(plugin) as Box<dyn vvdaw_plugin::Plugin>)
             }
+            PluginSpec::Clap { path, .. } => {
+                println!("  Loading CLAP plugin from {}", path.display());
+                let plugin = vvdaw_clap::ClapLoader::load(path)
+                    .map_err(|e| format!("Failed to load CLAP plugin: {e}"))?;
+                Ok(Box::new(plugin) as Box<dyn vvdaw_plugin::Plugin>)
+            }
         })
         .context("Failed to reconstruct graph from session")?;
 
@@ -113,7 +127,7 @@ fn main() -> Result<()> {
     println!("  Nodes in graph: {}", reconstructed_graph.nodes().count());
     println!(
         "  Connections in graph: {}",
-        reconstructed_graph.connections().count()
+        reconstructed_graph.connections().len()
     );
 
     println!("\n=== Demo Complete ===");
@@ -5,25 +5,65 @@
 use anyhow::Result;
 use bevy::prelude::*;
 use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use vvdaw_audio::{AudioConfig, AudioEngine};
 use vvdaw_comms::create_channels;
 use vvdaw_ui::VvdawUiPlugin;
 
+mod ui_config;
+
+use ui_config::{UiConfig, UiConfigPlugin};
+
 /// Visual Virtual DAW - An experimental 3D audio workstation
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// UI mode to use
-    #[arg(short, long, value_enum, default_value_t = UiMode::ThreeD)]
-    ui: UiMode,
+    /// UI mode to use (defaults to the one used last, or 3d)
+    #[arg(short, long, value_enum)]
+    ui: Option<UiMode>,
+
+    /// Number of output channels to drive (1 = mono, 2 = stereo, 4 = quad, ...)
+    #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u16).range(1..))]
+    output_channels: u16,
+
+    /// Run the audio thread at real-time priority (falls back to normal priority)
+    /// On Linux this needs an rtprio limit, e.g. membership of the `audio`
+    /// group or `@audio - rtprio 95` in /etc/security/limits.conf.
+    #[arg(long)]
+    realtime: bool,
+
+    /// Pin the audio thread to this CPU core (Linux and Windows)
+    /// Keeps it off cores busy with other work; combine with --realtime for
+    /// the lowest buffer sizes.
+    #[arg(long, value_name = "CORE")]
+    cpu: Option<usize>,
+
+    /// Extra directory to scan for VST3 plugins, before the standard ones
+    /// Can be specified multiple times. Directories listed in the VST3_PATH
+    /// environment variable are scanned as well.
+    #[arg(long = "vst3-path", value_name = "DIR")]
+    vst3_paths: Vec<PathBuf>,
 
     /// Optional WAV file to load and visualize (only used in 3D mode)
     wav_file: Option<String>,
 }
 
-#[derive(Debug, Clone, Copy, ValueEnum)]
+impl Args {
+    /// Audio configuration for the engine, from the defaults and these arguments
+    fn audio_config(&self) -> AudioConfig {
+        AudioConfig {
+            output_channels: usize::from(self.output_channels),
+            realtime_priority: self.realtime,
+            cpu_affinity: self.cpu,
+            ..AudioConfig::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
 enum UiMode {
     /// 2D traditional UI with file browser and playback controls
     #[value(name = "2d")]
@@ -46,18 +86,37 @@ fn main() -> Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    tracing::info!("Starting vvdaw with UI mode: {:?}", args.ui);
+    // Window geometry, last UI and recent files from the previous run
+    let config_path = UiConfig::default_path();
+    let mut config = config_path
+        .as_deref()
+        .map_or_else(UiConfig::default, UiConfig::load);
+    config.forget_missing_files();
+    let ui_mode = args.ui.or(config.ui_mode).unwrap_or(UiMode::ThreeD);
+
+    tracing::info!("Starting vvdaw with UI mode: {:?}", ui_mode);
+
+    vvdaw_vst3::Vst3Loader::set_extra_search_paths(args.vst3_paths.clone());
 
-    match args.ui {
-        UiMode::TwoD => run_2d_ui(&args)?,
-        UiMode::ThreeD => run_3d_ui(&args),
+    let config_plugin = config_path.map(|path| UiConfigPlugin {
+        config,
+        path,
+        mode: ui_mode,
+    });
+    if config_plugin.is_none() {
+        tracing::warn!("No config directory found - UI state won't be saved");
+    }
+
+    match ui_mode {
+        UiMode::TwoD => run_2d_ui(&args, config_plugin)?,
+        UiMode::ThreeD => run_3d_ui(&args, config_plugin),
     }
 
     Ok(())
 }
 
 /// Run the application with 2D UI
-fn run_2d_ui(args: &Args) -> Result<()> {
+fn run_2d_ui(args: &Args, config_plugin: Option<UiConfigPlugin>) -> Result<()> {
     if args.wav_file.is_some() {
         tracing::warn!("WAV file argument is only used in 3D mode, ignoring");
     }
@@ -66,7 +125,7 @@ fn run_2d_ui(args: &Args) -> Result<()> {
     let (ui_channels, audio_channels) = create_channels(256);
 
     // Create audio configuration
-    let audio_config = AudioConfig::default();
+    let audio_config = args.audio_config();
     tracing::info!("Audio config: {:?}", audio_config);
 
     // Create and start audio engine
@@ -85,21 +144,40 @@ fn run_2d_ui(args: &Args) -> Result<()> {
     // When App::run() returns or the process exits, the `engine` variable
     // goes out of scope and its Drop impl is called, which stops the audio
     // stream. This ensures proper cleanup in all exit scenarios.
-    App::new()
-        .add_plugins(
-            DefaultPlugins
-                .set(WindowPlugin {
-                    primary_window: Some(Window {
-                        title: "VVDAW - 2D UI".to_string(),
-                        resolution: (800, 600).into(),
-                        ..default()
-                    }),
-                    ..default()
-                })
-                .disable::<bevy::log::LogPlugin>(), // Disable Bevy's LogPlugin - we initialize tracing manually
-        )
-        .add_plugins(VvdawUiPlugin::new(ui_channels))
-        .run();
+    let mut window = Window {
+        title: "VVDAW - 2D UI".to_string(),
+        resolution: (800, 600).into(),
+        ..default()
+    };
+    let mut ui_plugin = VvdawUiPlugin::new(ui_channels);
+    if let Some(config_plugin) = &config_plugin {
+        let config = &config_plugin.config;
+        if let Some(geometry) = config.window(UiMode::TwoD) {
+            geometry.apply(&mut window);
+        }
+        ui_plugin = ui_plugin.with_history(
+            config.recent_file_strings(),
+            config
+                .last_file
+                .as_ref()
+                .map(|file| file.display().to_string()),
+        );
+    }
+
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(window),
+                ..default()
+            })
+            .disable::<bevy::log::LogPlugin>(), // Disable Bevy's LogPlugin - we initialize tracing manually
+    )
+    .add_plugins(ui_plugin);
+    if let Some(config_plugin) = config_plugin {
+        app.add_plugins(config_plugin);
+    }
+    app.run();
 
     tracing::info!("Bevy app exited - audio engine will be cleaned up");
 
@@ -110,7 +188,7 @@ fn run_2d_ui(args: &Args) -> Result<()> {
 }
 
 /// Run the application with 3D highway UI
-fn run_3d_ui(args: &Args) {
+fn run_3d_ui(args: &Args, config_plugin: Option<UiConfigPlugin>) {
     if args.wav_file.is_some() {
         tracing::warn!("WAV file argument ignored in 3D mode - use File > Load WAV menu instead");
     }
@@ -121,10 +199,12 @@ fn run_3d_ui(args: &Args) {
     println!("  W/A/S/D     - Move forward/left/back/right");
     println!("  Q/E         - Move up/down");
     println!("  Shift       - Speed boost");
+    println!("  -/=         - Slower/faster camera");
     println!("  Right Mouse - Look around");
+    println!("  Left Click  - Jump to position on the highway");
     println!("  Space       - Play/Pause");
     println!("  X           - Stop");
-    println!("  Tab         - Toggle camera mode");
+    println!("  Tab         - Next marker (Shift+Tab: previous)");
     println!("  Ctrl+O      - Load WAV file");
     println!("  Esc         - Exit");
     println!();
@@ -135,7 +215,7 @@ fn run_3d_ui(args: &Args) {
     let (ui_channels, audio_channels) = create_channels(256);
 
     // Create audio configuration
-    let audio_config = AudioConfig::default();
+    let audio_config = args.audio_config();
     tracing::info!("Audio config: {:?}", audio_config);
 
     // Create and start audio engine
@@ -149,7 +229,29 @@ fn run_3d_ui(args: &Args) {
 
     // Create and run Bevy app with 3D UI
     // Pass UI channels so systems can communicate with audio thread
-    vvdaw_ui_3d::create_app(ui_channels).run();
+    let mut window = vvdaw_ui_3d::default_window();
+    if let Some(geometry) = config_plugin
+        .as_ref()
+        .and_then(|config_plugin| config_plugin.config.window(UiMode::ThreeD))
+    {
+        geometry.apply(&mut window);
+    }
+    let mut app = vvdaw_ui_3d::create_app_with_window(ui_channels, window);
+    if let Some(config_plugin) = config_plugin {
+        let config = &config_plugin.config;
+        app.insert_resource(vvdaw_ui_3d::recent_files::RecentFiles::restored(
+            config.recent_files.clone(),
+            config.last_file.clone(),
+        ));
+        app.insert_resource(config.camera.clone());
+        app.add_plugins(config_plugin);
+    }
+    app.run();
 
-    tracing::info!("Bevy app exited - audio engine will be cleaned up");
+    // The UI has already sent Shutdown - stopping joins the stream thread
+    // (bounded by a timeout) and drops the graph, deactivating plugins
+    tracing::info!("Bevy app exited - stopping audio engine");
+    if let Err(e) = engine.stop() {
+        tracing::error!("Error stopping audio engine: {e}");
+    }
 }
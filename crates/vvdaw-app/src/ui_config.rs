@@ -0,0 +1,301 @@
+//! UI state remembered between runs
+//!
+//! [`UiConfig`] holds what a user would otherwise set up again on every
+//! start: each UI's window size and position, which UI ran last, the files
+//! loaded recently, and the 3D camera's speed and sensitivity. It's read
+//! from `ui.ron` in the platform's config directory at startup and written
+//! back when the app exits cleanly (see [`UiConfigPlugin`]).
+
+use anyhow::{Context, Result};
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowPosition};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use vvdaw_core::paths::{self, UserDir};
+use vvdaw_ui_3d::camera::CameraSettings;
+
+use crate::UiMode;
+
+/// Name of the config file inside the vvdaw config directory
+const CONFIG_FILE: &str = "ui.ron";
+
+/// Size and position of a window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    /// Logical width in pixels
+    pub width: u32,
+    /// Logical height in pixels
+    pub height: u32,
+    /// Top-left corner on the desktop, if the window manager reported it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<(i32, i32)>,
+}
+
+impl WindowGeometry {
+    /// The geometry `window` has now
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Window sizes are small and positive
+    pub fn of(window: &Window) -> Self {
+        Self {
+            width: window.resolution.width() as u32,
+            height: window.resolution.height() as u32,
+            position: match window.position {
+                WindowPosition::At(position) => Some((position.x, position.y)),
+                _ => None,
+            },
+        }
+    }
+
+    /// Give `window` this size and position
+    pub fn apply(&self, window: &mut Window) {
+        window.resolution = (self.width, self.height).into();
+        if let Some((x, y)) = self.position {
+            window.position = WindowPosition::At(IVec2::new(x, y));
+        }
+    }
+}
+
+/// UI state saved between runs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiConfig {
+    /// UI used last, for when `--ui` isn't given
+    pub ui_mode: Option<UiMode>,
+    /// Window geometry of the 2D UI
+    pub window_2d: Option<WindowGeometry>,
+    /// Window geometry of the 3D UI
+    pub window_3d: Option<WindowGeometry>,
+    /// File loaded last, reopened on start
+    pub last_file: Option<PathBuf>,
+    /// Files loaded recently, in the order they were first loaded
+    pub recent_files: Vec<PathBuf>,
+    /// 3D camera movement and look settings
+    pub camera: CameraSettings,
+}
+
+impl UiConfig {
+    /// Standard location of the config file for the current platform
+    ///
+    /// - macOS: `~/Library/Application Support/vvdaw/ui.ron`
+    /// - Windows: `%APPDATA%\vvdaw\ui.ron`
+    /// - Linux: `$XDG_CONFIG_HOME/vvdaw/ui.ron` (default `~/.config`)
+    ///
+    /// Returns `None` if the home directory can't be determined.
+    pub fn default_path() -> Option<PathBuf> {
+        paths::user_dir(UserDir::Config).map(|dir| dir.join(CONFIG_FILE))
+    }
+
+    /// Read a config file
+    ///
+    /// Never fails: a missing or unreadable file gives the defaults, so a
+    /// broken config can't stop the app from starting.
+    pub fn load(path: &Path) -> Self {
+        let ron_string = match std::fs::read_to_string(path) {
+            Ok(ron_string) => ron_string,
+            Err(e) => {
+                tracing::debug!("No UI config at {}: {e}", path.display());
+                return Self::default();
+            }
+        };
+
+        ron::from_str(&ron_string).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring corrupt UI config {}: {e}", path.display());
+            Self::default()
+        })
+    }
+
+    /// Write the config file, creating its directory if needed
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+
+        let ron_string = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .context("Failed to serialize UI config")?;
+        std::fs::write(path, ron_string)
+            .with_context(|| format!("Failed to write UI config {}", path.display()))
+    }
+
+    /// Drop recent files that have been moved or deleted since they were loaded
+    pub fn forget_missing_files(&mut self) {
+        self.recent_files.retain(|file| file.is_file());
+    }
+
+    /// Saved geometry of `mode`'s window
+    pub const fn window(&self, mode: UiMode) -> Option<&WindowGeometry> {
+        match mode {
+            UiMode::TwoD => self.window_2d.as_ref(),
+            UiMode::ThreeD => self.window_3d.as_ref(),
+        }
+    }
+
+    /// Recent files as the 2D UI lists them
+    pub fn recent_file_strings(&self) -> Vec<String> {
+        self.recent_files
+            .iter()
+            .map(|file| file.display().to_string())
+            .collect()
+    }
+}
+
+/// Plugin that keeps a [`UiConfig`] up to date while the UI runs and saves it
+/// on exit
+///
+/// The window is gone by the time `AppExit` is sent when it's closed, so its
+/// geometry is recorded whenever it changes rather than read at exit.
+pub struct UiConfigPlugin {
+    pub config: UiConfig,
+    /// File the config is saved to
+    pub path: PathBuf,
+    /// The UI being run
+    pub mode: UiMode,
+}
+
+/// Resource holding the config as it will be saved
+#[derive(Resource)]
+struct SavedUiConfig {
+    config: UiConfig,
+    path: PathBuf,
+    mode: UiMode,
+}
+
+impl Plugin for UiConfigPlugin {
+    fn build(&self, app: &mut App) {
+        let mut config = self.config.clone();
+        config.ui_mode = Some(self.mode);
+        app.insert_resource(SavedUiConfig {
+            config,
+            path: self.path.clone(),
+            mode: self.mode,
+        });
+
+        app.add_systems(Last, (track_window_geometry, save_on_exit).chain());
+        match self.mode {
+            UiMode::TwoD => app.add_systems(Last, track_files_2d.before(save_on_exit)),
+            UiMode::ThreeD => app.add_systems(
+                Last,
+                (track_files_3d, track_camera_settings).before(save_on_exit),
+            ),
+        };
+    }
+}
+
+/// Record the primary window's geometry when it changes
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn track_window_geometry(
+    windows: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    mut saved: ResMut<SavedUiConfig>,
+) {
+    let Ok(window) = windows.single() else {
+        return;
+    };
+    let geometry = Some(WindowGeometry::of(window));
+    match saved.mode {
+        UiMode::TwoD => saved.config.window_2d = geometry,
+        UiMode::ThreeD => saved.config.window_3d = geometry,
+    }
+}
+
+/// Record the 2D UI's file history when it changes
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn track_files_2d(file_state: Res<vvdaw_ui::FilePathState>, mut saved: ResMut<SavedUiConfig>) {
+    if !file_state.is_changed() {
+        return;
+    }
+    saved.config.recent_files = file_state.loaded_files.iter().map(PathBuf::from).collect();
+    saved.config.last_file = file_state.last_file().map(PathBuf::from);
+}
+
+/// Record the 3D UI's recent files when they change
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn track_files_3d(
+    recent_files: Res<vvdaw_ui_3d::recent_files::RecentFiles>,
+    mut saved: ResMut<SavedUiConfig>,
+) {
+    if !recent_files.is_changed() {
+        return;
+    }
+    saved.config.recent_files.clone_from(&recent_files.files);
+    saved.config.last_file = recent_files.last_file().map(Path::to_path_buf);
+}
+
+/// Record the 3D camera settings when they change
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn track_camera_settings(settings: Res<CameraSettings>, mut saved: ResMut<SavedUiConfig>) {
+    if settings.is_changed() {
+        saved.config.camera.clone_from(&settings);
+    }
+}
+
+/// Write the config file when the app exits
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn save_on_exit(mut exit_events: MessageReader<AppExit>, saved: Res<SavedUiConfig>) {
+    if exit_events.read().next().is_none() {
+        return;
+    }
+
+    tracing::info!("Saving UI config to {}", saved.path.display());
+    if let Err(e) = saved.config.save(&saved.path) {
+        tracing::warn!("{e:#}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_round_trips_through_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vvdaw").join(CONFIG_FILE);
+        assert_eq!(UiConfig::load(&path), UiConfig::default());
+
+        let config = UiConfig {
+            ui_mode: Some(UiMode::TwoD),
+            window_2d: Some(WindowGeometry {
+                width: 1024,
+                height: 768,
+                position: Some((40, -20)),
+            }),
+            window_3d: None,
+            last_file: Some(PathBuf::from("/music/b.wav")),
+            recent_files: vec![PathBuf::from("/music/a.wav"), PathBuf::from("/music/b.wav")],
+            camera: CameraSettings {
+                move_speed: 80.0,
+                invert_y: true,
+                ..CameraSettings::default()
+            },
+        };
+        config.save(&path).unwrap();
+        assert_eq!(UiConfig::load(&path), config);
+        assert_eq!(config.window(UiMode::ThreeD), None);
+    }
+
+    #[test]
+    fn test_missing_fields_and_corrupt_files_give_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+
+        std::fs::write(&path, "(ui_mode: Some(ThreeD))").unwrap();
+        let config = UiConfig::load(&path);
+        assert_eq!(config.ui_mode, Some(UiMode::ThreeD));
+        assert!(config.recent_files.is_empty());
+
+        std::fs::write(&path, "not ron").unwrap();
+        assert_eq!(UiConfig::load(&path), UiConfig::default());
+    }
+
+    #[test]
+    fn test_missing_recent_files_are_forgotten() {
+        let dir = tempfile::tempdir().unwrap();
+        let kept = dir.path().join("kept.wav");
+        std::fs::write(&kept, b"RIFF").unwrap();
+
+        let mut config = UiConfig {
+            recent_files: vec![dir.path().join("deleted.wav"), kept.clone()],
+            ..UiConfig::default()
+        };
+        config.forget_missing_files();
+        assert_eq!(config.recent_files, [kept]);
+    }
+}
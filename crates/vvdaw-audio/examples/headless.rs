@@ -0,0 +1,153 @@
+//! Example: Run the realtime engine headless, without any UI.
+//!
+//! This example drives the engine the way the apps do, from a plain thread:
+//! 1. Creating the communication channels
+//! 2. Starting the engine and waiting for `EngineInitialized`
+//! 3. Adding a built-in node
+//! 4. Playing for a few seconds while reporting engine events
+//! 5. Shutting down cleanly
+//!
+//! Usage:
+//!   `cargo run -p vvdaw-audio --example headless -- [seconds]`
+//!
+//! The graph is a single gain node fed by the system input, so with live input
+//! enabled you hear (and see the load of) your input device.
+
+use anyhow::{Context, Result, bail};
+use std::env;
+use std::thread;
+use std::time::{Duration, Instant};
+use vvdaw_audio::{AudioConfig, AudioEngine, builtin};
+use vvdaw_comms::{AudioCommand, AudioEvent, UiChannels, create_channels, send_node};
+
+/// How often the event queue is drained
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How long to wait for the engine to answer a command
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+fn main() -> Result<()> {
+    let seconds: u64 = match env::args().nth(1) {
+        Some(arg) => arg
+            .parse()
+            .context("Duration must be a whole number of seconds")?,
+        None => 3,
+    };
+
+    println!("\n=== Headless Engine Example ===\n");
+
+    // Step 1: Channels - the engine gets the audio half, we keep the UI half
+    println!("[1/5] Creating communication channels...");
+    let (mut ui_channels, audio_channels) = create_channels(256);
+
+    // Step 2: Start the engine
+    println!("[2/5] Starting audio engine...");
+    let mut engine = AudioEngine::new(AudioConfig::default());
+    engine
+        .start(audio_channels)
+        .context("Failed to start audio engine")?;
+    wait_for(&mut ui_channels, |event| {
+        if let AudioEvent::EngineInitialized {
+            sample_rate,
+            output_channels,
+            device_name,
+        } = event
+        {
+            println!("✓ Running at {sample_rate}Hz, {output_channels} channels on {device_name}");
+            true
+        } else {
+            false
+        }
+    })?;
+
+    // Step 3: Add a node. Graph edits must happen while stopped.
+    println!("[3/5] Adding a gain node...");
+    let gain = builtin::create_builtin("gain").context("gain processor exists")?;
+    if send_node(
+        &mut ui_channels.command_tx,
+        &ui_channels.plugin_tx,
+        gain,
+        None,
+    )
+    .is_err()
+    {
+        bail!("Failed to queue the gain node");
+    }
+    wait_for(&mut ui_channels, |event| {
+        if let AudioEvent::NodeAdded { node_id } = event {
+            println!("✓ Node {node_id} added");
+            true
+        } else {
+            false
+        }
+    })?;
+
+    // Step 4: Play, reporting what the engine tells us
+    println!("[4/5] Playing for {seconds}s...\n");
+    send(&mut ui_channels, AudioCommand::SetInputMonitor(true))?;
+    send(&mut ui_channels, AudioCommand::SetProfiling(true))?;
+    send(&mut ui_channels, AudioCommand::Start)?;
+
+    let deadline = Instant::now() + Duration::from_secs(seconds);
+    while Instant::now() < deadline {
+        while let Ok(event) = ui_channels.event_rx.pop() {
+            match event {
+                AudioEvent::DspLoad { fraction } => {
+                    println!("→ DSP load: {:.1}%", fraction * 100.0);
+                }
+                AudioEvent::NodeCpu { node_id, fraction } => {
+                    println!("  node {node_id}: {:.1}%", fraction * 100.0);
+                }
+                AudioEvent::Xrun { count } => println!("→ Xrun ({count} so far)"),
+                AudioEvent::Clip { channel, count } => {
+                    println!("→ Channel {channel} clipped ({count} samples)");
+                }
+                AudioEvent::LatencyChanged { frames } => {
+                    println!("→ Graph latency: {frames} frames");
+                }
+                AudioEvent::Error(msg) => eprintln!("✗ Audio error: {msg}"),
+                _ => {}
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    // Step 5: Shut down - wait for the ack so the callback is idle, then stop
+    println!("\n[5/5] Shutting down...");
+    send(&mut ui_channels, AudioCommand::Shutdown)?;
+    wait_for(&mut ui_channels, |event| {
+        matches!(event, AudioEvent::Stopped)
+    })?;
+    engine.stop()?;
+    println!("✓ Engine stopped");
+
+    Ok(())
+}
+
+/// Push a command, failing if the queue is full
+fn send(ui_channels: &mut UiChannels, command: AudioCommand) -> Result<()> {
+    ui_channels
+        .command_tx
+        .push(command)
+        .map_err(|_| anyhow::anyhow!("Command queue full"))
+}
+
+/// Drain events until `accept` returns true, reporting errors on the way
+fn wait_for(
+    ui_channels: &mut UiChannels,
+    mut accept: impl FnMut(AudioEvent) -> bool,
+) -> Result<()> {
+    let deadline = Instant::now() + REPLY_TIMEOUT;
+    while Instant::now() < deadline {
+        while let Ok(event) = ui_channels.event_rx.pop() {
+            if let AudioEvent::Error(msg) = &event {
+                eprintln!("✗ Audio error: {msg}");
+            }
+            if accept(event) {
+                return Ok(());
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    bail!("Timed out waiting for the audio engine")
+}
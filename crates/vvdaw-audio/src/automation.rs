@@ -0,0 +1,266 @@
+//! Parameter automation curves for offline rendering.
+//!
+//! An [`AutomationLane`] moves one parameter through a list of breakpoints
+//! over time, e.g. a fade or a filter sweep. Lanes are written as
+//!
+//! ```text
+//! ParamName@0s=0.0,2s=1.0,linear
+//! ```
+//!
+//! Each breakpoint is `<seconds>=<value>` (the `s` suffix is optional) with
+//! times relative to the start of the render. The optional last item picks
+//! the [`Interpolation`] between breakpoints (linear by default). Before the
+//! first breakpoint the lane holds its first value, after the last one its
+//! last value.
+
+use std::str::FromStr;
+use vvdaw_core::SampleRate;
+
+/// How values move from one breakpoint to the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// Straight line between breakpoints
+    #[default]
+    Linear,
+    /// Keep each breakpoint's value until the next one (steps)
+    Hold,
+}
+
+impl FromStr for Interpolation {
+    type Err = AutomationError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "linear" => Ok(Self::Linear),
+            "hold" => Ok(Self::Hold),
+            other => Err(AutomationError::UnknownInterpolation(other.to_string())),
+        }
+    }
+}
+
+/// A parameter value at a point in time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Breakpoint {
+    /// Seconds from the start of the render
+    pub time: f64,
+    /// Parameter value in the parameter's own range
+    pub value: f32,
+}
+
+/// Breakpoint curve for one parameter
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutomationLane {
+    /// Name of the automated parameter
+    pub parameter: String,
+    /// Breakpoints in time order (never empty)
+    points: Vec<Breakpoint>,
+    /// Curve between breakpoints
+    pub interpolation: Interpolation,
+}
+
+impl AutomationLane {
+    /// Create a lane from breakpoints, which must be in time order
+    pub fn new(
+        parameter: impl Into<String>,
+        points: Vec<Breakpoint>,
+        interpolation: Interpolation,
+    ) -> Result<Self, AutomationError> {
+        let parameter = parameter.into();
+        if parameter.is_empty() {
+            return Err(AutomationError::Syntax(
+                "missing parameter name before '@'".to_string(),
+            ));
+        }
+        if points.is_empty() {
+            return Err(AutomationError::NoBreakpoints(parameter));
+        }
+        if let Some(pair) = points.windows(2).find(|pair| pair[1].time < pair[0].time) {
+            return Err(AutomationError::OutOfOrder {
+                parameter,
+                time: pair[1].time,
+            });
+        }
+
+        Ok(Self {
+            parameter,
+            points,
+            interpolation,
+        })
+    }
+
+    /// Breakpoints in time order
+    pub fn points(&self) -> &[Breakpoint] {
+        &self.points
+    }
+
+    /// Value of the curve at `frame`, timed at `sample_rate`
+    #[allow(clippy::cast_possible_truncation)] // Interpolated between two f32 values
+    #[allow(clippy::cast_precision_loss)] // Frames are far below 2^52
+    pub fn value_at(&self, frame: u64, sample_rate: SampleRate) -> f32 {
+        let time = if sample_rate == 0 {
+            0.0
+        } else {
+            frame as f64 / f64::from(sample_rate)
+        };
+
+        // Index of the first breakpoint after `time`
+        let next = self.points.partition_point(|point| point.time <= time);
+        let Some(previous) = next.checked_sub(1).map(|index| self.points[index]) else {
+            return self.points[0].value;
+        };
+        let Some(next) = self.points.get(next) else {
+            return previous.value;
+        };
+
+        match self.interpolation {
+            Interpolation::Hold => previous.value,
+            Interpolation::Linear => {
+                let position = (time - previous.time) / (next.time - previous.time);
+                let delta = f64::from(next.value) - f64::from(previous.value);
+                position.mul_add(delta, f64::from(previous.value)) as f32
+            }
+        }
+    }
+}
+
+impl FromStr for AutomationLane {
+    type Err = AutomationError;
+
+    /// Parse `ParamName@0s=0.0,2s=1.0,linear`
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (parameter, curve) = text.rsplit_once('@').ok_or_else(|| {
+            AutomationError::Syntax(format!("'{text}' is missing '@' after the parameter name"))
+        })?;
+
+        let mut points = Vec::new();
+        let mut interpolation = Interpolation::default();
+        let mut items = curve.split(',').map(str::trim).peekable();
+        while let Some(item) = items.next() {
+            let Some((time, value)) = item.split_once('=') else {
+                // Only the last item may be the interpolation mode
+                if items.peek().is_some() {
+                    return Err(AutomationError::Syntax(format!(
+                        "'{item}' is not a <seconds>=<value> breakpoint"
+                    )));
+                }
+                interpolation = item.parse()?;
+                break;
+            };
+            points.push(parse_breakpoint(time, value)?);
+        }
+
+        Self::new(parameter.trim(), points, interpolation)
+    }
+}
+
+/// Parse one `<seconds>=<value>` breakpoint
+fn parse_breakpoint(time: &str, value: &str) -> Result<Breakpoint, AutomationError> {
+    let seconds = time.trim();
+    let seconds = seconds.strip_suffix('s').unwrap_or(seconds);
+    let time: f64 = seconds
+        .trim()
+        .parse()
+        .ok()
+        .filter(|time: &f64| time.is_finite() && *time >= 0.0)
+        .ok_or_else(|| AutomationError::Syntax(format!("'{time}' is not a time in seconds")))?;
+    let value: f32 = value
+        .trim()
+        .parse()
+        .ok()
+        .filter(|value: &f32| value.is_finite())
+        .ok_or_else(|| AutomationError::Syntax(format!("'{value}' is not a parameter value")))?;
+
+    Ok(Breakpoint { time, value })
+}
+
+/// Errors in an automation lane
+#[derive(Debug, thiserror::Error)]
+pub enum AutomationError {
+    /// The lane text is malformed
+    #[error("Invalid automation: {0}")]
+    Syntax(String),
+
+    /// The interpolation mode isn't supported
+    #[error("Unknown interpolation '{0}' (expected 'linear' or 'hold')")]
+    UnknownInterpolation(String),
+
+    /// The lane has no breakpoints
+    #[error("Automation for '{0}' has no breakpoints")]
+    NoBreakpoints(String),
+
+    /// A breakpoint comes before the previous one
+    #[error("Automation for '{parameter}' goes back in time at {time}s")]
+    OutOfOrder { parameter: String, time: f64 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lane() {
+        let lane: AutomationLane = "Cutoff@0s=0.0, 2s=1.0, 2.5=0.25,hold".parse().unwrap();
+        assert_eq!(lane.parameter, "Cutoff");
+        assert_eq!(lane.interpolation, Interpolation::Hold);
+        assert_eq!(
+            lane.points(),
+            &[
+                Breakpoint {
+                    time: 0.0,
+                    value: 0.0
+                },
+                Breakpoint {
+                    time: 2.0,
+                    value: 1.0
+                },
+                Breakpoint {
+                    time: 2.5,
+                    value: 0.25
+                },
+            ]
+        );
+
+        // Linear is the default, and names may contain '@'
+        let lane: AutomationLane = "Send @ Bus@1s=-6".parse().unwrap();
+        assert_eq!(lane.parameter, "Send @ Bus");
+        assert_eq!(lane.interpolation, Interpolation::Linear);
+    }
+
+    #[test]
+    fn test_invalid_lanes_are_rejected() {
+        for text in [
+            "Cutoff",
+            "@0s=1.0",
+            "Cutoff@",
+            "Cutoff@linear",
+            "Cutoff@0s=0.0,cubic",
+            "Cutoff@linear,1s=1.0",
+            "Cutoff@-1s=0.0",
+            "Cutoff@0s=abc",
+            "Cutoff@2s=0.0,1s=1.0",
+        ] {
+            assert!(text.parse::<AutomationLane>().is_err(), "accepted '{text}'");
+        }
+    }
+
+    #[test]
+    fn test_linear_interpolation() {
+        let lane: AutomationLane = "Gain@1s=0.0,3s=1.0".parse().unwrap();
+        // Holds the first value before the first breakpoint
+        assert!(lane.value_at(0, 1000).abs() < 1e-6);
+        assert!((lane.value_at(2000, 1000) - 0.5).abs() < 1e-6);
+        assert!((lane.value_at(2500, 1000) - 0.75).abs() < 1e-6);
+        // Holds the last value after the last breakpoint
+        assert!((lane.value_at(10_000, 1000) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_hold_interpolation_and_jumps() {
+        let lane: AutomationLane = "Mode@0s=0,1s=1,1s=3,2s=2,hold".parse().unwrap();
+        assert!(lane.value_at(999, 1000).abs() < 1e-6);
+        // Two breakpoints at the same time jump straight to the later one
+        assert!((lane.value_at(1000, 1000) - 3.0).abs() < 1e-6);
+        assert!((lane.value_at(1999, 1000) - 3.0).abs() < 1e-6);
+        assert!((lane.value_at(2000, 1000) - 2.0).abs() < 1e-6);
+    }
+}
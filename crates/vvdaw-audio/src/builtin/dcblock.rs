@@ -0,0 +1,245 @@
+//! DC blocker - one-pole high-pass for removing DC offset.
+
+use std::f64::consts::TAU;
+use std::sync::atomic::{AtomicU32, Ordering};
+use vvdaw_core::SampleRate;
+use vvdaw_plugin::{AudioBuffer, EventBuffer, ParameterInfo, Plugin, PluginError, PluginInfo};
+
+/// Cutoff parameter ID
+pub const PARAM_CUTOFF: u32 = 0;
+
+/// Cutoff range (Hz) - low enough to leave bass alone
+const MIN_CUTOFF_HZ: f32 = 5.0;
+const MAX_CUTOFF_HZ: f32 = 20.0;
+const DEFAULT_CUTOFF_HZ: f32 = 10.0;
+
+/// Per-channel filter state
+#[derive(Debug, Clone, Copy, Default)]
+struct DcState {
+    /// Previous input sample
+    x1: f64,
+    /// Previous output sample
+    y1: f64,
+}
+
+impl DcState {
+    /// Filter one sample: `y[n] = x[n] - x[n-1] + pole * y[n-1]`
+    fn tick(&mut self, pole: f64, input: f64) -> f64 {
+        let output = pole.mul_add(self.y1, input - self.x1);
+        self.x1 = input;
+        self.y1 = output;
+        output
+    }
+}
+
+/// Pole of a one-pole high-pass with `cutoff` Hz at `sample_rate`
+fn pole(sample_rate: SampleRate, cutoff: f32) -> f64 {
+    (-TAU * f64::from(cutoff) / f64::from(sample_rate)).exp()
+}
+
+/// DC offset remover (stereo)
+///
+/// A one-pole high-pass with its cutoff (5-20 Hz) well below anything
+/// audible, so it takes out DC offset - which wastes headroom and skews
+/// meters - and leaves the music alone. Put it first in a chain to clean up
+/// recordings before anything else sees them.
+pub struct DcBlocker {
+    /// Cutoff (Hz) stored as f32 bits in an atomic (for thread-safe access)
+    cutoff: AtomicU32,
+    /// Filter pole for the current cutoff and sample rate
+    pole: f64,
+    /// Filter state per channel (stereo)
+    state: [DcState; 2],
+    sample_rate: SampleRate,
+    info: PluginInfo,
+}
+
+impl Default for DcBlocker {
+    fn default() -> Self {
+        Self {
+            cutoff: AtomicU32::new(DEFAULT_CUTOFF_HZ.to_bits()),
+            pole: pole(48000, DEFAULT_CUTOFF_HZ),
+            state: [DcState::default(); 2],
+            sample_rate: 48000,
+            info: PluginInfo {
+                name: "DC Blocker".to_string(),
+                vendor: "vvdaw".to_string(),
+                version: "1.0.0".to_string(),
+                unique_id: "vvdaw.builtin.dcblock".to_string(),
+                category: None,
+            },
+        }
+    }
+}
+
+impl DcBlocker {
+    /// Get the current cutoff (thread-safe)
+    fn get_cutoff(&self) -> f32 {
+        f32::from_bits(self.cutoff.load(Ordering::Acquire))
+    }
+}
+
+impl Plugin for DcBlocker {
+    fn info(&self) -> &PluginInfo {
+        &self.info
+    }
+
+    fn initialize(
+        &mut self,
+        sample_rate: SampleRate,
+        _max_block_size: usize,
+    ) -> Result<(), PluginError> {
+        self.sample_rate = sample_rate;
+        self.pole = pole(sample_rate, self.get_cutoff());
+        self.state = [DcState::default(); 2];
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        audio: &mut AudioBuffer,
+        _events: &EventBuffer,
+    ) -> Result<(), PluginError> {
+        // Ensure we have exactly stereo input and output
+        if audio.inputs.len() != 2 || audio.outputs.len() != 2 {
+            return Err(PluginError::ProcessingFailed(format!(
+                "DC blocker requires exactly 2 inputs and 2 outputs (stereo), got {} and {}",
+                audio.inputs.len(),
+                audio.outputs.len()
+            )));
+        }
+
+        // Validate buffer lengths
+        for ch in 0..2 {
+            if audio.inputs[ch].len() < audio.frames || audio.outputs[ch].len() < audio.frames {
+                return Err(PluginError::ProcessingFailed(format!(
+                    "Channel {ch} buffers are shorter than {} frames",
+                    audio.frames
+                )));
+            }
+        }
+
+        for (ch, state) in self.state.iter_mut().enumerate() {
+            for i in 0..audio.frames {
+                let output = state.tick(self.pole, f64::from(audio.inputs[ch][i]));
+                audio.outputs[ch][i] = output as f32;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn set_parameter(&mut self, id: u32, value: f32) -> Result<(), PluginError> {
+        if id != PARAM_CUTOFF {
+            return Err(PluginError::InvalidParameter(format!(
+                "Unknown parameter ID: {id}"
+            )));
+        }
+
+        let cutoff = value.clamp(MIN_CUTOFF_HZ, MAX_CUTOFF_HZ);
+        self.cutoff.store(cutoff.to_bits(), Ordering::Release);
+        self.pole = pole(self.sample_rate, cutoff);
+        Ok(())
+    }
+
+    fn get_parameter(&self, id: u32) -> Result<f32, PluginError> {
+        if id != PARAM_CUTOFF {
+            return Err(PluginError::InvalidParameter(format!(
+                "Unknown parameter ID: {id}"
+            )));
+        }
+        Ok(self.get_cutoff())
+    }
+
+    fn parameters(&self) -> Vec<ParameterInfo> {
+        vec![ParameterInfo {
+            id: PARAM_CUTOFF,
+            name: "Cutoff".to_string(),
+            min_value: MIN_CUTOFF_HZ,
+            max_value: MAX_CUTOFF_HZ,
+            default_value: DEFAULT_CUTOFF_HZ,
+            step_count: 0,
+        }]
+    }
+
+    fn input_channels(&self) -> usize {
+        2 // Stereo
+    }
+
+    fn output_channels(&self) -> usize {
+        2 // Stereo
+    }
+
+    fn deactivate(&mut self) {
+        // Clear filter history so reactivation starts from silence
+        self.state = [DcState::default(); 2];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run a constant stereo signal through the blocker, returning the left output
+    fn run(processor: &mut DcBlocker, level: f32, frames: usize) -> Vec<f32> {
+        let input = vec![level; frames];
+        let mut left_out = vec![0.0; frames];
+        let mut right_out = vec![0.0; frames];
+
+        let inputs: Vec<&[f32]> = vec![&input, &input];
+        let mut outputs: Vec<&mut [f32]> = vec![&mut left_out, &mut right_out];
+
+        let mut audio = AudioBuffer {
+            inputs: &inputs,
+            outputs: &mut outputs,
+            frames,
+        };
+
+        processor.process(&mut audio, &EventBuffer::new()).unwrap();
+        assert_eq!(left_out, right_out);
+        left_out
+    }
+
+    #[test]
+    fn test_dc_offset_settles_to_zero() {
+        let mut processor = DcBlocker::default();
+        processor.initialize(48000, 512).unwrap();
+
+        // The step passes through at first, then decays away
+        let output = run(&mut processor, 0.5, 48000);
+        assert!((output[0] - 0.5).abs() < 1e-6);
+        assert!(output[47999].abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_higher_cutoff_settles_faster() {
+        let mut slow = DcBlocker::default();
+        slow.initialize(48000, 512).unwrap();
+        slow.set_parameter(PARAM_CUTOFF, MIN_CUTOFF_HZ).unwrap();
+        let mut fast = DcBlocker::default();
+        fast.initialize(48000, 512).unwrap();
+        fast.set_parameter(PARAM_CUTOFF, MAX_CUTOFF_HZ).unwrap();
+
+        let slow_out = run(&mut slow, 0.5, 4800);
+        let fast_out = run(&mut fast, 0.5, 4800);
+        assert!(fast_out[4799] < slow_out[4799]);
+    }
+
+    #[test]
+    fn test_cutoff_parameter() {
+        let mut processor = DcBlocker::default();
+        assert_eq!(
+            processor.get_parameter(PARAM_CUTOFF).unwrap(),
+            DEFAULT_CUTOFF_HZ
+        );
+
+        processor.set_parameter(PARAM_CUTOFF, 100.0).unwrap();
+        assert_eq!(
+            processor.get_parameter(PARAM_CUTOFF).unwrap(),
+            MAX_CUTOFF_HZ
+        );
+
+        assert!(processor.set_parameter(1, 10.0).is_err());
+        assert!(processor.get_parameter(1).is_err());
+    }
+}
@@ -0,0 +1,434 @@
+//! Delay processor - stereo feedback delay.
+
+use super::smoothing::SmoothedValue;
+use std::sync::atomic::{AtomicU32, Ordering};
+use vvdaw_core::SampleRate;
+use vvdaw_plugin::{AudioBuffer, EventBuffer, ParameterInfo, Plugin, PluginError, PluginInfo};
+
+/// Parameter IDs
+const PARAM_TIME: u32 = 0;
+const PARAM_FEEDBACK: u32 = 1;
+const PARAM_MIX: u32 = 2;
+
+/// Longest delay the ring buffer is sized for (seconds)
+const MAX_DELAY_SECONDS: f32 = 2.0;
+
+/// Delay time range (milliseconds)
+const MIN_TIME_MS: f32 = 1.0;
+const MAX_TIME_MS: f32 = MAX_DELAY_SECONDS * 1000.0;
+
+/// Feedback is capped below 1.0 so the echoes always decay
+const MAX_FEEDBACK: f32 = 0.99;
+
+/// Time constant for delay time smoothing (seconds)
+///
+/// Jumping the read head causes an audible click; gliding it over ~50 ms
+/// gives a short pitch bend instead, like a tape delay.
+const TIME_SMOOTHING_SECONDS: f32 = 0.05;
+
+const DEFAULT_TIME_MS: f32 = 250.0;
+const DEFAULT_FEEDBACK: f32 = 0.35;
+const DEFAULT_MIX: f32 = 0.5;
+
+/// Stereo feedback delay
+///
+/// Parameters:
+/// - Time: delay time in milliseconds (1 - 2000)
+/// - Feedback: amount of delayed signal fed back (0.0 - 0.99)
+/// - Mix: wet/dry balance (0.0 = dry only, 1.0 = wet only)
+///
+/// The ring buffer is allocated in `initialize()` for the maximum delay at the
+/// current sample rate, so `process()` never allocates. Delay time changes are
+/// smoothed per sample and read with linear interpolation.
+pub struct DelayProcessor {
+    /// Delay time in milliseconds, stored as f32 bits
+    time_ms: AtomicU32,
+    /// Feedback amount, stored as f32 bits
+    feedback: AtomicU32,
+    /// Wet/dry mix, stored as f32 bits
+    mix: AtomicU32,
+    /// Ring buffer per channel (stereo), empty until initialized
+    buffers: [Vec<f32>; 2],
+    /// Next write index into the ring buffers
+    write_pos: usize,
+    /// Delay time in samples, gliding toward the Time parameter
+    current_delay: SmoothedValue,
+    sample_rate: SampleRate,
+    info: PluginInfo,
+}
+
+impl Default for DelayProcessor {
+    fn default() -> Self {
+        Self {
+            time_ms: AtomicU32::new(DEFAULT_TIME_MS.to_bits()),
+            feedback: AtomicU32::new(DEFAULT_FEEDBACK.to_bits()),
+            mix: AtomicU32::new(DEFAULT_MIX.to_bits()),
+            buffers: [Vec::new(), Vec::new()],
+            write_pos: 0,
+            current_delay: SmoothedValue::new(TIME_SMOOTHING_SECONDS),
+            sample_rate: 48000,
+            info: PluginInfo {
+                name: "Delay".to_string(),
+                vendor: "vvdaw".to_string(),
+                version: "1.0.0".to_string(),
+                unique_id: "vvdaw.builtin.delay".to_string(),
+                category: None,
+            },
+        }
+    }
+}
+
+impl DelayProcessor {
+    /// Get the delay time in milliseconds (thread-safe)
+    fn time_ms(&self) -> f32 {
+        f32::from_bits(self.time_ms.load(Ordering::Acquire))
+    }
+
+    /// Get the feedback amount (thread-safe)
+    fn feedback(&self) -> f32 {
+        f32::from_bits(self.feedback.load(Ordering::Acquire))
+    }
+
+    /// Get the wet/dry mix (thread-safe)
+    fn mix(&self) -> f32 {
+        f32::from_bits(self.mix.load(Ordering::Acquire))
+    }
+
+    /// Target delay time in samples for the current Time parameter
+    fn target_delay(&self) -> f32 {
+        self.time_ms() * self.sample_rate as f32 / 1000.0
+    }
+
+    /// Read `delay` samples behind the write head with linear interpolation
+    fn read(buffer: &[f32], write_pos: usize, delay: f32) -> f32 {
+        let len = buffer.len();
+        let whole = delay.floor();
+        let frac = delay - whole;
+        // Never read the slot about to be written (delay 0), and stay inside
+        // the buffer, which has headroom for the older interpolation tap
+        let whole = (whole as usize).clamp(1, len - 2);
+
+        let newer = buffer[(write_pos + len - whole) % len];
+        let older = buffer[(write_pos + len - whole - 1) % len];
+        (older - newer).mul_add(frac, newer)
+    }
+}
+
+impl Plugin for DelayProcessor {
+    fn info(&self) -> &PluginInfo {
+        &self.info
+    }
+
+    fn initialize(
+        &mut self,
+        sample_rate: SampleRate,
+        _max_block_size: usize,
+    ) -> Result<(), PluginError> {
+        self.sample_rate = sample_rate;
+
+        // Longest delay plus headroom for the interpolated read
+        let capacity = (MAX_DELAY_SECONDS * sample_rate as f32).ceil() as usize + 2;
+        self.buffers = [vec![0.0; capacity], vec![0.0; capacity]];
+        self.write_pos = 0;
+
+        self.current_delay.set_sample_rate(sample_rate);
+        self.current_delay.reset_to(self.target_delay());
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        audio: &mut AudioBuffer,
+        _events: &EventBuffer,
+    ) -> Result<(), PluginError> {
+        // Ensure we have exactly stereo input and output
+        if audio.inputs.len() != 2 {
+            return Err(PluginError::ProcessingFailed(format!(
+                "Delay processor requires exactly 2 inputs (stereo), got {}",
+                audio.inputs.len()
+            )));
+        }
+        if audio.outputs.len() != 2 {
+            return Err(PluginError::ProcessingFailed(format!(
+                "Delay processor requires exactly 2 outputs (stereo), got {}",
+                audio.outputs.len()
+            )));
+        }
+
+        // Validate buffer lengths
+        for ch in 0..2 {
+            if audio.inputs[ch].len() < audio.frames {
+                return Err(PluginError::ProcessingFailed(format!(
+                    "Input channel {} has {} samples, need at least {}",
+                    ch,
+                    audio.inputs[ch].len(),
+                    audio.frames
+                )));
+            }
+            if audio.outputs[ch].len() < audio.frames {
+                return Err(PluginError::ProcessingFailed(format!(
+                    "Output channel {} has {} samples, need at least {}",
+                    ch,
+                    audio.outputs[ch].len(),
+                    audio.frames
+                )));
+            }
+        }
+
+        if self.buffers[0].is_empty() {
+            return Err(PluginError::ProcessingFailed(
+                "Delay processor not initialized".to_string(),
+            ));
+        }
+
+        let target = self.target_delay();
+        let feedback = self.feedback();
+        let mix = self.mix();
+        let len = self.buffers[0].len();
+
+        for i in 0..audio.frames {
+            // Glide the read head toward the target delay time
+            let delay = self.current_delay.next(target);
+
+            for ch in 0..2 {
+                let input = audio.inputs[ch][i];
+                let delayed = Self::read(&self.buffers[ch], self.write_pos, delay);
+
+                self.buffers[ch][self.write_pos] = delayed.mul_add(feedback, input);
+                audio.outputs[ch][i] = (delayed - input).mul_add(mix, input);
+            }
+
+            self.write_pos = (self.write_pos + 1) % len;
+        }
+
+        Ok(())
+    }
+
+    fn set_parameter(&mut self, id: u32, value: f32) -> Result<(), PluginError> {
+        match id {
+            PARAM_TIME => {
+                let clamped = value.clamp(MIN_TIME_MS, MAX_TIME_MS);
+                self.time_ms.store(clamped.to_bits(), Ordering::Release);
+                Ok(())
+            }
+            PARAM_FEEDBACK => {
+                let clamped = value.clamp(0.0, MAX_FEEDBACK);
+                self.feedback.store(clamped.to_bits(), Ordering::Release);
+                Ok(())
+            }
+            PARAM_MIX => {
+                let clamped = value.clamp(0.0, 1.0);
+                self.mix.store(clamped.to_bits(), Ordering::Release);
+                Ok(())
+            }
+            _ => Err(PluginError::InvalidParameter(format!(
+                "Unknown parameter ID: {id}"
+            ))),
+        }
+    }
+
+    fn get_parameter(&self, id: u32) -> Result<f32, PluginError> {
+        match id {
+            PARAM_TIME => Ok(self.time_ms()),
+            PARAM_FEEDBACK => Ok(self.feedback()),
+            PARAM_MIX => Ok(self.mix()),
+            _ => Err(PluginError::InvalidParameter(format!(
+                "Unknown parameter ID: {id}"
+            ))),
+        }
+    }
+
+    fn parameters(&self) -> Vec<ParameterInfo> {
+        vec![
+            ParameterInfo {
+                id: PARAM_TIME,
+                name: "Time".to_string(),
+                min_value: MIN_TIME_MS,
+                max_value: MAX_TIME_MS,
+                default_value: DEFAULT_TIME_MS,
+                step_count: 0,
+            },
+            ParameterInfo {
+                id: PARAM_FEEDBACK,
+                name: "Feedback".to_string(),
+                min_value: 0.0,
+                max_value: MAX_FEEDBACK,
+                default_value: DEFAULT_FEEDBACK,
+                step_count: 0,
+            },
+            ParameterInfo {
+                id: PARAM_MIX,
+                name: "Mix".to_string(),
+                min_value: 0.0,
+                max_value: 1.0,
+                default_value: DEFAULT_MIX,
+                step_count: 0,
+            },
+        ]
+    }
+
+    fn input_channels(&self) -> usize {
+        2 // Stereo
+    }
+
+    fn output_channels(&self) -> usize {
+        2 // Stereo
+    }
+
+    fn deactivate(&mut self) {
+        // Silence the echoes so reactivation doesn't replay stale audio
+        for buffer in &mut self.buffers {
+            buffer.fill(0.0);
+        }
+        self.write_pos = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Process one stereo block, returning the left output channel
+    fn run(processor: &mut DelayProcessor, input: &[f32]) -> Vec<f32> {
+        let frames = input.len();
+        let mut left_out = vec![0.0; frames];
+        let mut right_out = vec![0.0; frames];
+
+        let inputs: Vec<&[f32]> = vec![input, input];
+        let mut outputs: Vec<&mut [f32]> = vec![&mut left_out, &mut right_out];
+
+        let mut audio = AudioBuffer {
+            inputs: &inputs,
+            outputs: &mut outputs,
+            frames,
+        };
+
+        processor.process(&mut audio, &EventBuffer::new()).unwrap();
+        left_out
+    }
+
+    /// A unit impulse followed by silence
+    fn impulse(frames: usize) -> Vec<f32> {
+        let mut input = vec![0.0; frames];
+        input[0] = 1.0;
+        input
+    }
+
+    #[test]
+    fn test_delay_parameters() {
+        let processor = DelayProcessor::default();
+        let params = processor.parameters();
+
+        assert_eq!(params.len(), 3);
+        assert_eq!(params[0].name, "Time");
+        assert_eq!(params[1].name, "Feedback");
+        assert_eq!(params[2].name, "Mix");
+        assert_eq!(processor.get_parameter(PARAM_TIME).unwrap(), 250.0);
+    }
+
+    #[test]
+    fn test_delay_buffer_sized_at_initialize() {
+        let mut processor = DelayProcessor::default();
+        assert!(processor.buffers[0].is_empty());
+
+        processor.initialize(48000, 512).unwrap();
+        assert!(processor.buffers[0].len() >= 96_000);
+        assert_eq!(processor.buffers[0].len(), processor.buffers[1].len());
+    }
+
+    #[test]
+    fn test_impulse_is_delayed() {
+        let mut processor = DelayProcessor::default();
+        processor.set_parameter(PARAM_TIME, 10.0).unwrap(); // 480 samples at 48 kHz
+        processor.set_parameter(PARAM_FEEDBACK, 0.0).unwrap();
+        processor.set_parameter(PARAM_MIX, 1.0).unwrap();
+        processor.initialize(48000, 512).unwrap();
+
+        let output = run(&mut processor, &impulse(1024));
+
+        assert!((output[480] - 1.0).abs() < 1e-6);
+        let elsewhere: f32 = output
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != 480)
+            .map(|(_, s)| s.abs())
+            .sum();
+        assert!(elsewhere < 1e-6);
+    }
+
+    #[test]
+    fn test_feedback_repeats_decay() {
+        let mut processor = DelayProcessor::default();
+        processor.set_parameter(PARAM_TIME, 10.0).unwrap();
+        processor.set_parameter(PARAM_FEEDBACK, 0.5).unwrap();
+        processor.set_parameter(PARAM_MIX, 1.0).unwrap();
+        processor.initialize(48000, 512).unwrap();
+
+        let output = run(&mut processor, &impulse(1500));
+
+        assert!((output[480] - 1.0).abs() < 1e-6);
+        assert!((output[960] - 0.5).abs() < 1e-6);
+        assert!((output[1440] - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_dry_mix_passes_input() {
+        let mut processor = DelayProcessor::default();
+        processor.set_parameter(PARAM_MIX, 0.0).unwrap();
+        processor.initialize(48000, 512).unwrap();
+
+        let input: Vec<f32> = (0..256).map(|i| (i as f32 * 0.1).sin()).collect();
+        let output = run(&mut processor, &input);
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn test_time_change_is_smoothed() {
+        let mut processor = DelayProcessor::default();
+        processor.set_parameter(PARAM_TIME, 10.0).unwrap();
+        processor.initialize(48000, 512).unwrap();
+        assert!((processor.current_delay.current() - 480.0).abs() < 1e-3);
+
+        processor.set_parameter(PARAM_TIME, 20.0).unwrap();
+        run(&mut processor, &[0.0; 64]);
+
+        // Moving toward 960 samples, but nowhere near it after 64 samples
+        assert!(processor.current_delay.current() > 480.0);
+        assert!(processor.current_delay.current() < 600.0);
+    }
+
+    #[test]
+    fn test_delay_parameter_clamping() {
+        let mut processor = DelayProcessor::default();
+
+        processor.set_parameter(PARAM_TIME, 10_000.0).unwrap();
+        assert_eq!(processor.get_parameter(PARAM_TIME).unwrap(), MAX_TIME_MS);
+
+        processor.set_parameter(PARAM_FEEDBACK, 1.5).unwrap();
+        assert_eq!(processor.get_parameter(PARAM_FEEDBACK).unwrap(), 0.99);
+    }
+
+    #[test]
+    fn test_process_before_initialize_fails() {
+        let mut processor = DelayProcessor::default();
+        let input = [0.0; 16];
+        let mut left_out = [0.0; 16];
+        let mut right_out = [0.0; 16];
+        let inputs: Vec<&[f32]> = vec![&input, &input];
+        let mut outputs: Vec<&mut [f32]> = vec![&mut left_out, &mut right_out];
+        let mut audio = AudioBuffer {
+            inputs: &inputs,
+            outputs: &mut outputs,
+            frames: 16,
+        };
+
+        assert!(processor.process(&mut audio, &EventBuffer::new()).is_err());
+    }
+
+    #[test]
+    fn test_invalid_parameter() {
+        let mut processor = DelayProcessor::default();
+        assert!(processor.set_parameter(3, 0.0).is_err());
+        assert!(processor.get_parameter(999).is_err());
+    }
+}
@@ -0,0 +1,487 @@
+//! EQ processor - 3-band parametric equalizer.
+
+use std::f64::consts::TAU;
+use std::sync::atomic::{AtomicU32, Ordering};
+use vvdaw_core::SampleRate;
+use vvdaw_plugin::{AudioBuffer, EventBuffer, ParameterInfo, Plugin, PluginError, PluginInfo};
+
+/// Number of EQ bands
+const BAND_COUNT: usize = 3;
+
+/// Parameters per band (frequency, gain, Q)
+const PARAMS_PER_BAND: usize = 3;
+
+/// Shape of an EQ band
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BandShape {
+    LowShelf,
+    Peaking,
+    HighShelf,
+}
+
+/// Static description of a band: shape, name, and parameter ranges
+struct BandSpec {
+    shape: BandShape,
+    name: &'static str,
+    min_freq: f32,
+    max_freq: f32,
+    default_freq: f32,
+    default_q: f32,
+}
+
+const BANDS: [BandSpec; BAND_COUNT] = [
+    BandSpec {
+        shape: BandShape::LowShelf,
+        name: "Low",
+        min_freq: 20.0,
+        max_freq: 1_000.0,
+        default_freq: 100.0,
+        default_q: 0.707,
+    },
+    BandSpec {
+        shape: BandShape::Peaking,
+        name: "Mid",
+        min_freq: 100.0,
+        max_freq: 10_000.0,
+        default_freq: 1_000.0,
+        default_q: 1.0,
+    },
+    BandSpec {
+        shape: BandShape::HighShelf,
+        name: "High",
+        min_freq: 1_000.0,
+        max_freq: 20_000.0,
+        default_freq: 8_000.0,
+        default_q: 0.707,
+    },
+];
+
+/// Gain range for every band (dB)
+const MIN_GAIN_DB: f32 = -24.0;
+const MAX_GAIN_DB: f32 = 24.0;
+
+/// Q range for every band
+const MIN_Q: f32 = 0.1;
+const MAX_Q: f32 = 10.0;
+
+/// Biquad filter coefficients (normalized so a0 = 1)
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Coefficients {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+}
+
+impl Coefficients {
+    /// Pass-through filter
+    const IDENTITY: Self = Self {
+        b0: 1.0,
+        b1: 0.0,
+        b2: 0.0,
+        a1: 0.0,
+        a2: 0.0,
+    };
+
+    /// Compute coefficients using the RBJ Audio EQ Cookbook formulas
+    fn design(shape: BandShape, sample_rate: SampleRate, freq: f32, gain_db: f32, q: f32) -> Self {
+        let sample_rate = f64::from(sample_rate);
+        // Keep the center frequency safely below Nyquist
+        let freq = f64::from(freq).min(sample_rate * 0.49);
+        let a = 10.0_f64.powf(f64::from(gain_db) / 40.0);
+        let w0 = TAU * freq / sample_rate;
+        let (sin_w0, cos_w0) = w0.sin_cos();
+        let alpha = sin_w0 / (2.0 * f64::from(q));
+
+        let (b0, b1, b2, a0, a1, a2) = match shape {
+            BandShape::Peaking => (
+                alpha.mul_add(a, 1.0),
+                -2.0 * cos_w0,
+                (-alpha).mul_add(a, 1.0),
+                alpha / a + 1.0,
+                -2.0 * cos_w0,
+                1.0 - alpha / a,
+            ),
+            BandShape::LowShelf => {
+                let sqrt_a_alpha = 2.0 * a.sqrt() * alpha;
+                (
+                    a * ((a - 1.0).mul_add(-cos_w0, a + 1.0) + sqrt_a_alpha),
+                    2.0 * a * (a + 1.0).mul_add(-cos_w0, a - 1.0),
+                    a * ((a - 1.0).mul_add(-cos_w0, a + 1.0) - sqrt_a_alpha),
+                    (a - 1.0).mul_add(cos_w0, a + 1.0) + sqrt_a_alpha,
+                    -2.0 * (a + 1.0).mul_add(cos_w0, a - 1.0),
+                    (a - 1.0).mul_add(cos_w0, a + 1.0) - sqrt_a_alpha,
+                )
+            }
+            BandShape::HighShelf => {
+                let sqrt_a_alpha = 2.0 * a.sqrt() * alpha;
+                (
+                    a * ((a - 1.0).mul_add(cos_w0, a + 1.0) + sqrt_a_alpha),
+                    -2.0 * a * (a + 1.0).mul_add(cos_w0, a - 1.0),
+                    a * ((a - 1.0).mul_add(cos_w0, a + 1.0) - sqrt_a_alpha),
+                    (a - 1.0).mul_add(-cos_w0, a + 1.0) + sqrt_a_alpha,
+                    2.0 * (a + 1.0).mul_add(-cos_w0, a - 1.0),
+                    (a - 1.0).mul_add(-cos_w0, a + 1.0) - sqrt_a_alpha,
+                )
+            }
+        };
+
+        Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        }
+    }
+}
+
+/// Per-channel filter state (transposed direct form II)
+#[derive(Debug, Clone, Copy, Default)]
+struct BiquadState {
+    z1: f64,
+    z2: f64,
+}
+
+impl BiquadState {
+    /// Filter one sample
+    fn tick(&mut self, c: &Coefficients, input: f64) -> f64 {
+        let output = c.b0.mul_add(input, self.z1);
+        self.z1 = c.b1.mul_add(input, (-c.a1).mul_add(output, self.z2));
+        self.z2 = c.b2.mul_add(input, -c.a2 * output);
+        output
+    }
+}
+
+/// 3-band parametric EQ (low shelf, peaking mid, high shelf)
+///
+/// Each band exposes frequency (Hz), gain (dB) and Q. Parameter IDs are
+/// `band * 3 + {0: frequency, 1: gain, 2: Q}`, with bands ordered low, mid, high.
+///
+/// Coefficients are computed in `initialize()` and recomputed whenever a band's
+/// parameter changes, so `process()` only runs the filters.
+pub struct EqProcessor {
+    /// Parameter values stored as f32 bits, indexed by parameter ID
+    params: [AtomicU32; BAND_COUNT * PARAMS_PER_BAND],
+    /// Current coefficients for each band
+    coefficients: [Coefficients; BAND_COUNT],
+    /// Filter state per band, per channel (stereo)
+    state: [[BiquadState; 2]; BAND_COUNT],
+    sample_rate: SampleRate,
+    info: PluginInfo,
+}
+
+impl Default for EqProcessor {
+    fn default() -> Self {
+        let params = std::array::from_fn(|id| {
+            let band = &BANDS[id / PARAMS_PER_BAND];
+            let value = match id % PARAMS_PER_BAND {
+                0 => band.default_freq,
+                1 => 0.0, // Flat
+                _ => band.default_q,
+            };
+            AtomicU32::new(value.to_bits())
+        });
+
+        let mut processor = Self {
+            params,
+            coefficients: [Coefficients::IDENTITY; BAND_COUNT],
+            state: [[BiquadState::default(); 2]; BAND_COUNT],
+            sample_rate: 48000,
+            info: PluginInfo {
+                name: "EQ".to_string(),
+                vendor: "vvdaw".to_string(),
+                version: "1.0.0".to_string(),
+                unique_id: "vvdaw.builtin.eq".to_string(),
+                category: None,
+            },
+        };
+        processor.update_all_coefficients();
+        processor
+    }
+}
+
+impl EqProcessor {
+    /// Get a parameter value by ID (thread-safe)
+    fn param(&self, id: usize) -> f32 {
+        f32::from_bits(self.params[id].load(Ordering::Acquire))
+    }
+
+    /// Recompute coefficients for one band from its current parameters
+    fn update_coefficients(&mut self, band: usize) {
+        let base = band * PARAMS_PER_BAND;
+        self.coefficients[band] = Coefficients::design(
+            BANDS[band].shape,
+            self.sample_rate,
+            self.param(base),
+            self.param(base + 1),
+            self.param(base + 2),
+        );
+    }
+
+    /// Recompute coefficients for every band
+    fn update_all_coefficients(&mut self) {
+        for band in 0..BAND_COUNT {
+            self.update_coefficients(band);
+        }
+    }
+
+    /// Valid range for a parameter ID
+    fn param_range(id: usize) -> (f32, f32) {
+        let band = &BANDS[id / PARAMS_PER_BAND];
+        match id % PARAMS_PER_BAND {
+            0 => (band.min_freq, band.max_freq),
+            1 => (MIN_GAIN_DB, MAX_GAIN_DB),
+            _ => (MIN_Q, MAX_Q),
+        }
+    }
+}
+
+impl Plugin for EqProcessor {
+    fn info(&self) -> &PluginInfo {
+        &self.info
+    }
+
+    fn initialize(
+        &mut self,
+        sample_rate: SampleRate,
+        _max_block_size: usize,
+    ) -> Result<(), PluginError> {
+        self.sample_rate = sample_rate;
+        self.update_all_coefficients();
+        self.state = [[BiquadState::default(); 2]; BAND_COUNT];
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        audio: &mut AudioBuffer,
+        _events: &EventBuffer,
+    ) -> Result<(), PluginError> {
+        // Ensure we have exactly stereo input and output
+        if audio.inputs.len() != 2 {
+            return Err(PluginError::ProcessingFailed(format!(
+                "EQ processor requires exactly 2 inputs (stereo), got {}",
+                audio.inputs.len()
+            )));
+        }
+        if audio.outputs.len() != 2 {
+            return Err(PluginError::ProcessingFailed(format!(
+                "EQ processor requires exactly 2 outputs (stereo), got {}",
+                audio.outputs.len()
+            )));
+        }
+
+        // Validate buffer lengths
+        for ch in 0..2 {
+            if audio.inputs[ch].len() < audio.frames {
+                return Err(PluginError::ProcessingFailed(format!(
+                    "Input channel {} has {} samples, need at least {}",
+                    ch,
+                    audio.inputs[ch].len(),
+                    audio.frames
+                )));
+            }
+            if audio.outputs[ch].len() < audio.frames {
+                return Err(PluginError::ProcessingFailed(format!(
+                    "Output channel {} has {} samples, need at least {}",
+                    ch,
+                    audio.outputs[ch].len(),
+                    audio.frames
+                )));
+            }
+        }
+
+        // Run the bands in series on each channel
+        for ch in 0..2 {
+            for i in 0..audio.frames {
+                let mut sample = f64::from(audio.inputs[ch][i]);
+                for (coefficients, state) in self.coefficients.iter().zip(&mut self.state) {
+                    sample = state[ch].tick(coefficients, sample);
+                }
+                audio.outputs[ch][i] = sample as f32;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn set_parameter(&mut self, id: u32, value: f32) -> Result<(), PluginError> {
+        let index = id as usize;
+        if index >= self.params.len() {
+            return Err(PluginError::InvalidParameter(format!(
+                "Unknown parameter ID: {id}"
+            )));
+        }
+
+        let (min, max) = Self::param_range(index);
+        self.params[index].store(value.clamp(min, max).to_bits(), Ordering::Release);
+        self.update_coefficients(index / PARAMS_PER_BAND);
+        Ok(())
+    }
+
+    fn get_parameter(&self, id: u32) -> Result<f32, PluginError> {
+        let index = id as usize;
+        if index >= self.params.len() {
+            return Err(PluginError::InvalidParameter(format!(
+                "Unknown parameter ID: {id}"
+            )));
+        }
+        Ok(self.param(index))
+    }
+
+    fn parameters(&self) -> Vec<ParameterInfo> {
+        BANDS
+            .iter()
+            .enumerate()
+            .flat_map(|(band_index, band)| {
+                let base = (band_index * PARAMS_PER_BAND) as u32;
+                [
+                    ParameterInfo {
+                        id: base,
+                        name: format!("{} Freq", band.name),
+                        min_value: band.min_freq,
+                        max_value: band.max_freq,
+                        default_value: band.default_freq,
+                        step_count: 0,
+                    },
+                    ParameterInfo {
+                        id: base + 1,
+                        name: format!("{} Gain", band.name),
+                        min_value: MIN_GAIN_DB,
+                        max_value: MAX_GAIN_DB,
+                        default_value: 0.0,
+                        step_count: 0,
+                    },
+                    ParameterInfo {
+                        id: base + 2,
+                        name: format!("{} Q", band.name),
+                        min_value: MIN_Q,
+                        max_value: MAX_Q,
+                        default_value: band.default_q,
+                        step_count: 0,
+                    },
+                ]
+            })
+            .collect()
+    }
+
+    fn input_channels(&self) -> usize {
+        2 // Stereo
+    }
+
+    fn output_channels(&self) -> usize {
+        2 // Stereo
+    }
+
+    fn deactivate(&mut self) {
+        // Clear filter history so reactivation starts from silence
+        self.state = [[BiquadState::default(); 2]; BAND_COUNT];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run a constant stereo signal through the EQ and return the final output sample
+    fn settle(processor: &mut EqProcessor, level: f32) -> f32 {
+        let input = vec![level; 4096];
+        let mut left_out = vec![0.0; 4096];
+        let mut right_out = vec![0.0; 4096];
+
+        let inputs: Vec<&[f32]> = vec![&input, &input];
+        let mut outputs: Vec<&mut [f32]> = vec![&mut left_out, &mut right_out];
+
+        let mut audio = AudioBuffer {
+            inputs: &inputs,
+            outputs: &mut outputs,
+            frames: 4096,
+        };
+
+        processor.process(&mut audio, &EventBuffer::new()).unwrap();
+        left_out[4095]
+    }
+
+    #[test]
+    fn test_eq_parameters() {
+        let processor = EqProcessor::default();
+        let params = processor.parameters();
+
+        assert_eq!(params.len(), 9);
+        assert_eq!(params[0].name, "Low Freq");
+        assert_eq!(params[4].name, "Mid Gain");
+        assert_eq!(params[8].name, "High Q");
+
+        for param in &params {
+            assert_eq!(
+                processor.get_parameter(param.id).unwrap(),
+                param.default_value
+            );
+        }
+    }
+
+    #[test]
+    fn test_eq_flat_is_transparent() {
+        let mut processor = EqProcessor::default();
+        processor.initialize(48000, 512).unwrap();
+
+        let output = settle(&mut processor, 0.5);
+        assert!((output - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_low_shelf_boosts_dc() {
+        let mut processor = EqProcessor::default();
+        processor.initialize(48000, 512).unwrap();
+        processor.set_parameter(1, 6.0).unwrap(); // Low Gain +6 dB
+
+        // A shelf's gain applies fully at DC: +6 dB ≈ ×1.995
+        let output = settle(&mut processor, 0.25);
+        let expected = 0.25 * 1.995;
+        assert!((output - expected).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_high_shelf_leaves_dc_alone() {
+        let mut processor = EqProcessor::default();
+        processor.initialize(48000, 512).unwrap();
+        processor.set_parameter(7, -12.0).unwrap(); // High Gain -12 dB
+
+        let output = settle(&mut processor, 0.5);
+        assert!((output - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_set_parameter_recomputes_coefficients() {
+        let mut processor = EqProcessor::default();
+        processor.initialize(48000, 512).unwrap();
+        let flat = processor.coefficients[1];
+
+        processor.set_parameter(4, 9.0).unwrap(); // Mid Gain
+        assert_ne!(processor.coefficients[1], flat);
+        assert_eq!(
+            processor.coefficients[0],
+            EqProcessor::default().coefficients[0]
+        );
+    }
+
+    #[test]
+    fn test_eq_parameter_clamping() {
+        let mut processor = EqProcessor::default();
+
+        processor.set_parameter(1, 100.0).unwrap();
+        assert_eq!(processor.get_parameter(1).unwrap(), MAX_GAIN_DB);
+
+        processor.set_parameter(6, 5.0).unwrap(); // High Freq below its range
+        assert_eq!(processor.get_parameter(6).unwrap(), 1_000.0);
+    }
+
+    #[test]
+    fn test_invalid_parameter() {
+        let mut processor = EqProcessor::default();
+        assert!(processor.set_parameter(9, 0.0).is_err());
+        assert!(processor.get_parameter(999).is_err());
+    }
+}
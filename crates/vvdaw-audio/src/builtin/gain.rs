@@ -1,26 +1,51 @@
 //! Gain processor - simple volume control.
 
+use super::smoothing::SmoothedValue;
 use std::sync::atomic::{AtomicU32, Ordering};
-use vvdaw_core::SampleRate;
+use vvdaw_core::{SampleRate, db_to_linear, linear_to_db};
 use vvdaw_plugin::{AudioBuffer, EventBuffer, ParameterInfo, Plugin, PluginError, PluginInfo};
 
+/// Linear gain parameter ID
+pub const PARAM_GAIN: u32 = 0;
+
+/// Gain in decibels parameter ID (the same gain as [`PARAM_GAIN`])
+pub const PARAM_GAIN_DB: u32 = 1;
+
+/// Gain range in decibels - the minimum stands for `-inf` (silence)
+pub const MIN_GAIN_DB: f32 = -60.0;
+pub const MAX_GAIN_DB: f32 = 12.0;
+
+/// Largest linear gain (+12 dB)
+const MAX_GAIN: f32 = 3.981_072;
+
+/// Time constant for gain smoothing (seconds)
+///
+/// Short enough that a fader move still feels immediate, long enough to
+/// take the zipper noise out of a sweep.
+const GAIN_SMOOTHING_SECONDS: f32 = 0.01;
+
 /// Simple gain/volume processor
 ///
 /// Multiplies all audio samples by a gain factor.
 /// Real-time safe using atomic operations for parameter changes.
+/// Gain changes are smoothed per sample (see [`GAIN_SMOOTHING_SECONDS`]).
 ///
 /// ## Parameter Range
 ///
-/// Gain: 0.0 to 2.0 (linear)
-/// - 0.0 = silence (−∞ dB)
-/// - 1.0 = unity gain (0 dB, default)
-/// - 2.0 = double amplitude (+6 dB)
+/// Gain (dB, [`PARAM_GAIN_DB`]): -60 to +12 dB
+/// - -60 dB = silence (treated as −∞ dB)
+/// - 0 dB = unity gain (default)
+/// - +12 dB = about 4x amplitude
 ///
-/// The range allows for both attenuation and moderate boost.
-/// Limited to 2.0 to prevent excessive clipping in typical use.
+/// The same gain is also settable as linear amplitude ([`PARAM_GAIN`],
+/// 0.0 to ~3.98), which older sessions and the channel strip use. Only the
+/// dB parameter is listed in [`Plugin::parameters`], so sliders and
+/// `--inspect` show decibels.
 pub struct GainProcessor {
     /// Gain value stored as f32 bits in an atomic (for thread-safe access)
     gain: AtomicU32,
+    /// Gain actually applied, ramping toward the Gain parameter
+    smoothed_gain: SmoothedValue,
     sample_rate: SampleRate,
     info: PluginInfo,
 }
@@ -30,12 +55,14 @@ impl Default for GainProcessor {
         Self {
             // Default gain: 1.0 (unity, 0 dB)
             gain: AtomicU32::new(1.0_f32.to_bits()),
+            smoothed_gain: SmoothedValue::new(GAIN_SMOOTHING_SECONDS),
             sample_rate: 48000,
             info: PluginInfo {
                 name: "Gain".to_string(),
                 vendor: "vvdaw".to_string(),
                 version: "1.0.0".to_string(),
                 unique_id: "vvdaw.builtin.gain".to_string(),
+                category: None,
             },
         }
     }
@@ -51,6 +78,11 @@ impl GainProcessor {
     fn set_gain(&self, value: f32) {
         self.gain.store(value.to_bits(), Ordering::Release);
     }
+
+    /// Current gain in dB, floored at [`MIN_GAIN_DB`]
+    fn get_gain_db(&self) -> f32 {
+        linear_to_db(self.get_gain()).max(MIN_GAIN_DB)
+    }
 }
 
 impl Plugin for GainProcessor {
@@ -64,6 +96,7 @@ impl Plugin for GainProcessor {
         _max_block_size: usize,
     ) -> Result<(), PluginError> {
         self.sample_rate = sample_rate;
+        self.smoothed_gain.set_sample_rate(sample_rate);
         Ok(())
     }
 
@@ -72,7 +105,7 @@ impl Plugin for GainProcessor {
         audio: &mut AudioBuffer,
         _events: &EventBuffer,
     ) -> Result<(), PluginError> {
-        let gain = self.get_gain();
+        let target = self.get_gain();
 
         // Ensure we have exactly 2 inputs and 2 outputs (stereo)
         if audio.inputs.len() != 2 {
@@ -108,9 +141,10 @@ impl Plugin for GainProcessor {
             }
         }
 
-        // Copy input to output and apply gain
-        for ch in 0..2 {
-            for i in 0..audio.frames {
+        // Copy input to output and apply gain, gliding toward the target
+        for i in 0..audio.frames {
+            let gain = self.smoothed_gain.next(target);
+            for ch in 0..2 {
                 audio.outputs[ch][i] = audio.inputs[ch][i] * gain;
             }
         }
@@ -120,11 +154,20 @@ impl Plugin for GainProcessor {
 
     fn set_parameter(&mut self, id: u32, value: f32) -> Result<(), PluginError> {
         match id {
-            0 => {
-                // Gain parameter: 0.0 to 2.0 (0 dB to +6 dB range)
-                // Clamp to prevent extreme values
-                let clamped = value.clamp(0.0, 2.0);
-                self.set_gain(clamped);
+            PARAM_GAIN => {
+                // Linear gain: 0.0 to +12 dB
+                self.set_gain(value.clamp(0.0, MAX_GAIN));
+                Ok(())
+            }
+            PARAM_GAIN_DB => {
+                // The bottom of the range is silence, not -60 dB
+                let db = value.clamp(MIN_GAIN_DB, MAX_GAIN_DB);
+                let db = if db <= MIN_GAIN_DB {
+                    f32::NEG_INFINITY
+                } else {
+                    db
+                };
+                self.set_gain(db_to_linear(db).min(MAX_GAIN));
                 Ok(())
             }
             _ => Err(PluginError::InvalidParameter(format!(
@@ -135,7 +178,8 @@ impl Plugin for GainProcessor {
 
     fn get_parameter(&self, id: u32) -> Result<f32, PluginError> {
         match id {
-            0 => Ok(self.get_gain()),
+            PARAM_GAIN => Ok(self.get_gain()),
+            PARAM_GAIN_DB => Ok(self.get_gain_db()),
             _ => Err(PluginError::InvalidParameter(format!(
                 "Unknown parameter ID: {id}"
             ))),
@@ -144,14 +188,26 @@ impl Plugin for GainProcessor {
 
     fn parameters(&self) -> Vec<ParameterInfo> {
         vec![ParameterInfo {
-            id: 0,
+            id: PARAM_GAIN_DB,
             name: "Gain".to_string(),
-            min_value: 0.0,
-            max_value: 2.0,
-            default_value: 1.0,
+            min_value: MIN_GAIN_DB,
+            max_value: MAX_GAIN_DB,
+            default_value: 0.0,
+            step_count: 0,
         }]
     }
 
+    fn parameter_display(&self, id: u32, value: f32) -> Result<String, PluginError> {
+        match id {
+            PARAM_GAIN => Ok(format!("{:.1} dB", linear_to_db(value))),
+            PARAM_GAIN_DB if value <= MIN_GAIN_DB => Ok("-inf dB".to_string()),
+            PARAM_GAIN_DB => Ok(format!("{value:.1} dB")),
+            _ => Err(PluginError::InvalidParameter(format!(
+                "Unknown parameter ID: {id}"
+            ))),
+        }
+    }
+
     fn input_channels(&self) -> usize {
         2 // Stereo
     }
@@ -192,15 +248,47 @@ mod tests {
     fn test_gain_clamping() {
         let mut processor = GainProcessor::default();
 
-        // Try to set gain beyond max
+        // Try to set gain beyond max (+12 dB)
         processor.set_parameter(0, 10.0).unwrap();
-        assert_eq!(processor.get_parameter(0).unwrap(), 2.0); // Should be clamped
+        assert_eq!(processor.get_parameter(0).unwrap(), MAX_GAIN); // Should be clamped
 
         // Try to set negative gain
         processor.set_parameter(0, -1.0).unwrap();
         assert_eq!(processor.get_parameter(0).unwrap(), 0.0); // Should be clamped
     }
 
+    #[test]
+    fn test_gain_in_db() {
+        let mut processor = GainProcessor::default();
+        assert_eq!(processor.get_parameter(PARAM_GAIN_DB).unwrap(), 0.0);
+
+        processor.set_parameter(PARAM_GAIN_DB, -6.0).unwrap();
+        assert!((processor.get_parameter(PARAM_GAIN).unwrap() - 0.501).abs() < 1e-3);
+        assert!((processor.get_parameter(PARAM_GAIN_DB).unwrap() + 6.0).abs() < 1e-4);
+
+        processor.set_parameter(PARAM_GAIN_DB, 100.0).unwrap();
+        assert_eq!(processor.get_parameter(PARAM_GAIN_DB).unwrap(), MAX_GAIN_DB);
+
+        // The bottom of the range is true silence
+        processor.set_parameter(PARAM_GAIN_DB, MIN_GAIN_DB).unwrap();
+        assert_eq!(processor.get_parameter(PARAM_GAIN).unwrap(), 0.0);
+        assert_eq!(processor.get_parameter(PARAM_GAIN_DB).unwrap(), MIN_GAIN_DB);
+        assert_eq!(
+            processor
+                .parameter_display(PARAM_GAIN_DB, MIN_GAIN_DB)
+                .unwrap(),
+            "-inf dB"
+        );
+
+        // Linear gain reads back in dB too
+        processor.set_parameter(PARAM_GAIN, 2.0).unwrap();
+        assert!((processor.get_parameter(PARAM_GAIN_DB).unwrap() - 6.021).abs() < 1e-3);
+
+        let params = processor.parameters();
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0].id, PARAM_GAIN_DB);
+    }
+
     #[test]
     fn test_gain_processing() {
         let mut processor = GainProcessor::default();
@@ -236,6 +324,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_gain_changes_are_smoothed() {
+        let mut processor = GainProcessor::default();
+        processor.initialize(48000, 512).unwrap();
+        processor.set_parameter(0, 0.0).unwrap();
+
+        let input = vec![1.0; 512];
+        let inputs: Vec<&[f32]> = vec![&input, &input];
+        let events = EventBuffer::new();
+        let process = |processor: &mut GainProcessor| {
+            let mut output_l = vec![0.0; 512];
+            let mut output_r = vec![0.0; 512];
+            let mut outputs: Vec<&mut [f32]> = vec![&mut output_l, &mut output_r];
+            let mut audio = AudioBuffer {
+                inputs: &inputs,
+                outputs: &mut outputs,
+                frames: 512,
+            };
+            processor.process(&mut audio, &events).unwrap();
+            assert_eq!(output_l, output_r);
+            output_l
+        };
+
+        // Silent at 0, then swept to 1 - the gain must ramp, not jump
+        assert!(process(&mut processor).iter().all(|&sample| sample == 0.0));
+        processor.set_parameter(0, 1.0).unwrap();
+        let sweep = process(&mut processor);
+
+        assert!(sweep[0] > 0.0 && sweep[0] < 0.01);
+        for pair in sweep.windows(2) {
+            assert!(pair[1] >= pair[0], "gain went backwards");
+            assert!(pair[1] - pair[0] < 0.01, "click in the ramp");
+        }
+        // One time constant (480 frames at 48 kHz) gets ~63% of the way
+        assert!((sweep[479] - 0.632).abs() < 0.01);
+
+        // Settles on the target
+        for _ in 0..20 {
+            process(&mut processor);
+        }
+        assert!(process(&mut processor).iter().all(|&sample| sample == 1.0));
+    }
+
     #[test]
     fn test_invalid_parameter() {
         let mut processor = GainProcessor::default();
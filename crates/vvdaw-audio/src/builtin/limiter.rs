@@ -0,0 +1,510 @@
+//! Limiter processor - stereo-linked lookahead brickwall limiter.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use vvdaw_core::{Frames, SampleRate, db_to_linear};
+use vvdaw_plugin::{AudioBuffer, EventBuffer, ParameterInfo, Plugin, PluginError, PluginInfo};
+
+/// Parameter IDs
+const PARAM_THRESHOLD: u32 = 0;
+const PARAM_RELEASE: u32 = 1;
+
+/// Threshold range (dBFS)
+const MIN_THRESHOLD_DB: f32 = -24.0;
+const MAX_THRESHOLD_DB: f32 = 0.0;
+
+/// Release time range (milliseconds)
+const MIN_RELEASE_MS: f32 = 1.0;
+const MAX_RELEASE_MS: f32 = 1000.0;
+
+const DEFAULT_THRESHOLD_DB: f32 = -1.0;
+const DEFAULT_RELEASE_MS: f32 = 100.0;
+
+/// Lookahead time (seconds)
+///
+/// Fixed rather than a parameter: it sets the latency the host compensates
+/// for, which must not change while the graph is running.
+const LOOKAHEAD_SECONDS: f32 = 0.005;
+
+/// Lookahead in frames for a sample rate (at least one frame)
+fn lookahead_frames(sample_rate: SampleRate) -> Frames {
+    ((LOOKAHEAD_SECONDS * sample_rate as f32).round() as Frames).max(1)
+}
+
+/// Running minimum over the last `window` values (monotonic queue)
+///
+/// Storage is allocated up front, so `push` is O(1) amortized and never
+/// allocates.
+struct SlidingMin {
+    values: Vec<f32>,
+    indices: Vec<u64>,
+    head: usize,
+    len: usize,
+    counter: u64,
+}
+
+impl SlidingMin {
+    fn new(window: usize) -> Self {
+        Self {
+            values: vec![0.0; window],
+            indices: vec![0; window],
+            head: 0,
+            len: 0,
+            counter: 0,
+        }
+    }
+
+    /// Forget all values (keeps the allocation)
+    fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+        self.counter = 0;
+    }
+
+    /// Add a value and return the minimum of the current window
+    fn push(&mut self, value: f32) -> f32 {
+        let window = self.values.len();
+
+        // Drop the oldest entry once it leaves the window
+        if self.len > 0 && self.indices[self.head] + window as u64 <= self.counter {
+            self.head = (self.head + 1) % window;
+            self.len -= 1;
+        }
+
+        // Drop entries that can never be the minimum again
+        while self.len > 0 {
+            let back = (self.head + self.len - 1) % window;
+            if self.values[back] < value {
+                break;
+            }
+            self.len -= 1;
+        }
+
+        let slot = (self.head + self.len) % window;
+        self.values[slot] = value;
+        self.indices[slot] = self.counter;
+        self.len += 1;
+        self.counter += 1;
+
+        self.values[self.head]
+    }
+}
+
+/// Stereo-linked lookahead brickwall limiter
+///
+/// Parameters:
+/// - Threshold: output ceiling in dBFS (-24 to 0)
+/// - Release: time for gain reduction to recover, in milliseconds (1 - 1000)
+///
+/// Audio is delayed by a fixed 5 ms lookahead (reported via `latency()`), so
+/// gain reduction can ramp down before a peak reaches the output instead of
+/// clipping it. Both channels share one gain so the stereo image is preserved.
+///
+/// Intended as the last node before system output, catching the overshoot
+/// from additive mixing in [`AudioGraph::process`](crate::graph::AudioGraph::process).
+pub struct LimiterProcessor {
+    /// Threshold in dBFS, stored as f32 bits
+    threshold_db: AtomicU32,
+    /// Release time in milliseconds, stored as f32 bits
+    release_ms: AtomicU32,
+    /// Lookahead (and latency) in frames
+    lookahead: Frames,
+    /// Delay line per channel (stereo), `lookahead` frames long
+    delay: [Vec<f32>; 2],
+    delay_pos: usize,
+    /// Minimum required gain over the lookahead window
+    window_min: SlidingMin,
+    /// Recent window minima, averaged to ramp the gain down smoothly
+    ramp: Vec<f32>,
+    ramp_pos: usize,
+    ramp_sum: f64,
+    /// Current gain after release smoothing
+    envelope: f32,
+    sample_rate: SampleRate,
+    info: PluginInfo,
+}
+
+impl Default for LimiterProcessor {
+    fn default() -> Self {
+        let sample_rate = 48000;
+        let lookahead = lookahead_frames(sample_rate);
+        Self {
+            threshold_db: AtomicU32::new(DEFAULT_THRESHOLD_DB.to_bits()),
+            release_ms: AtomicU32::new(DEFAULT_RELEASE_MS.to_bits()),
+            lookahead,
+            delay: [Vec::new(), Vec::new()],
+            delay_pos: 0,
+            window_min: SlidingMin::new(lookahead + 1),
+            ramp: Vec::new(),
+            ramp_pos: 0,
+            ramp_sum: 0.0,
+            envelope: 1.0,
+            sample_rate,
+            info: PluginInfo {
+                name: "Limiter".to_string(),
+                vendor: "vvdaw".to_string(),
+                version: "1.0.0".to_string(),
+                unique_id: "vvdaw.builtin.limiter".to_string(),
+                category: None,
+            },
+        }
+    }
+}
+
+impl LimiterProcessor {
+    /// Get the threshold in dBFS (thread-safe)
+    fn threshold_db(&self) -> f32 {
+        f32::from_bits(self.threshold_db.load(Ordering::Acquire))
+    }
+
+    /// Get the release time in milliseconds (thread-safe)
+    fn release_ms(&self) -> f32 {
+        f32::from_bits(self.release_ms.load(Ordering::Acquire))
+    }
+
+    /// Reset the delay line and gain state to silence / unity gain
+    fn reset(&mut self) {
+        for channel in &mut self.delay {
+            channel.fill(0.0);
+        }
+        self.delay_pos = 0;
+        self.window_min.clear();
+        self.ramp.fill(1.0);
+        self.ramp_pos = 0;
+        self.ramp_sum = self.ramp.len() as f64;
+        self.envelope = 1.0;
+    }
+}
+
+impl Plugin for LimiterProcessor {
+    fn info(&self) -> &PluginInfo {
+        &self.info
+    }
+
+    fn initialize(
+        &mut self,
+        sample_rate: SampleRate,
+        _max_block_size: usize,
+    ) -> Result<(), PluginError> {
+        self.sample_rate = sample_rate;
+        self.lookahead = lookahead_frames(sample_rate);
+
+        self.delay = [vec![0.0; self.lookahead], vec![0.0; self.lookahead]];
+        self.window_min = SlidingMin::new(self.lookahead + 1);
+        self.ramp = vec![1.0; self.lookahead + 1];
+        self.reset();
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        audio: &mut AudioBuffer,
+        _events: &EventBuffer,
+    ) -> Result<(), PluginError> {
+        // Ensure we have exactly stereo input and output
+        if audio.inputs.len() != 2 {
+            return Err(PluginError::ProcessingFailed(format!(
+                "Limiter processor requires exactly 2 inputs (stereo), got {}",
+                audio.inputs.len()
+            )));
+        }
+        if audio.outputs.len() != 2 {
+            return Err(PluginError::ProcessingFailed(format!(
+                "Limiter processor requires exactly 2 outputs (stereo), got {}",
+                audio.outputs.len()
+            )));
+        }
+
+        // Validate buffer lengths
+        for ch in 0..2 {
+            if audio.inputs[ch].len() < audio.frames {
+                return Err(PluginError::ProcessingFailed(format!(
+                    "Input channel {} has {} samples, need at least {}",
+                    ch,
+                    audio.inputs[ch].len(),
+                    audio.frames
+                )));
+            }
+            if audio.outputs[ch].len() < audio.frames {
+                return Err(PluginError::ProcessingFailed(format!(
+                    "Output channel {} has {} samples, need at least {}",
+                    ch,
+                    audio.outputs[ch].len(),
+                    audio.frames
+                )));
+            }
+        }
+
+        if self.delay[0].is_empty() {
+            return Err(PluginError::ProcessingFailed(
+                "Limiter processor not initialized".to_string(),
+            ));
+        }
+
+        let threshold = db_to_linear(self.threshold_db());
+        let release = (-1000.0 / (self.release_ms() * self.sample_rate as f32)).exp();
+        let ramp_len = self.ramp.len() as f64;
+
+        for i in 0..audio.frames {
+            let left = audio.inputs[0][i];
+            let right = audio.inputs[1][i];
+
+            // Stereo-linked: the louder channel sets the gain for both
+            let peak = left.abs().max(right.abs());
+            let required = if peak > threshold {
+                threshold / peak
+            } else {
+                1.0
+            };
+
+            // Averaging the window minimum over the same span ramps the gain
+            // down across the lookahead while never exceeding what any sample
+            // in the window requires
+            let window_min = self.window_min.push(required);
+            self.ramp_sum += f64::from(window_min) - f64::from(self.ramp[self.ramp_pos]);
+            self.ramp[self.ramp_pos] = window_min;
+            self.ramp_pos = (self.ramp_pos + 1) % self.ramp.len();
+            let target = (self.ramp_sum / ramp_len) as f32;
+
+            // Instant attack (already ramped), exponential release
+            self.envelope = if target < self.envelope {
+                target
+            } else {
+                (self.envelope - target).mul_add(release, target)
+            };
+
+            let delayed_left = self.delay[0][self.delay_pos];
+            let delayed_right = self.delay[1][self.delay_pos];
+            self.delay[0][self.delay_pos] = left;
+            self.delay[1][self.delay_pos] = right;
+            self.delay_pos = (self.delay_pos + 1) % self.lookahead;
+
+            // The clamp only guards against rounding in the gain ramp
+            audio.outputs[0][i] = (delayed_left * self.envelope).clamp(-threshold, threshold);
+            audio.outputs[1][i] = (delayed_right * self.envelope).clamp(-threshold, threshold);
+        }
+
+        Ok(())
+    }
+
+    fn set_parameter(&mut self, id: u32, value: f32) -> Result<(), PluginError> {
+        match id {
+            PARAM_THRESHOLD => {
+                let clamped = value.clamp(MIN_THRESHOLD_DB, MAX_THRESHOLD_DB);
+                self.threshold_db
+                    .store(clamped.to_bits(), Ordering::Release);
+                Ok(())
+            }
+            PARAM_RELEASE => {
+                let clamped = value.clamp(MIN_RELEASE_MS, MAX_RELEASE_MS);
+                self.release_ms.store(clamped.to_bits(), Ordering::Release);
+                Ok(())
+            }
+            _ => Err(PluginError::InvalidParameter(format!(
+                "Unknown parameter ID: {id}"
+            ))),
+        }
+    }
+
+    fn get_parameter(&self, id: u32) -> Result<f32, PluginError> {
+        match id {
+            PARAM_THRESHOLD => Ok(self.threshold_db()),
+            PARAM_RELEASE => Ok(self.release_ms()),
+            _ => Err(PluginError::InvalidParameter(format!(
+                "Unknown parameter ID: {id}"
+            ))),
+        }
+    }
+
+    fn parameters(&self) -> Vec<ParameterInfo> {
+        vec![
+            ParameterInfo {
+                id: PARAM_THRESHOLD,
+                name: "Threshold".to_string(),
+                min_value: MIN_THRESHOLD_DB,
+                max_value: MAX_THRESHOLD_DB,
+                default_value: DEFAULT_THRESHOLD_DB,
+                step_count: 0,
+            },
+            ParameterInfo {
+                id: PARAM_RELEASE,
+                name: "Release".to_string(),
+                min_value: MIN_RELEASE_MS,
+                max_value: MAX_RELEASE_MS,
+                default_value: DEFAULT_RELEASE_MS,
+                step_count: 0,
+            },
+        ]
+    }
+
+    fn input_channels(&self) -> usize {
+        2 // Stereo
+    }
+
+    fn output_channels(&self) -> usize {
+        2 // Stereo
+    }
+
+    fn deactivate(&mut self) {
+        self.reset();
+    }
+
+    fn latency(&self) -> Frames {
+        self.lookahead
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Process stereo input in blocks, returning both output channels
+    fn run(
+        processor: &mut LimiterProcessor,
+        left: &[f32],
+        right: &[f32],
+        block_size: usize,
+    ) -> (Vec<f32>, Vec<f32>) {
+        let mut left_out = vec![0.0; left.len()];
+        let mut right_out = vec![0.0; right.len()];
+
+        for start in (0..left.len()).step_by(block_size) {
+            let end = (start + block_size).min(left.len());
+            let inputs: Vec<&[f32]> = vec![&left[start..end], &right[start..end]];
+            let mut outputs: Vec<&mut [f32]> =
+                vec![&mut left_out[start..end], &mut right_out[start..end]];
+
+            let mut audio = AudioBuffer {
+                inputs: &inputs,
+                outputs: &mut outputs,
+                frames: end - start,
+            };
+            processor.process(&mut audio, &EventBuffer::new()).unwrap();
+        }
+
+        (left_out, right_out)
+    }
+
+    /// A sine at `amplitude`
+    fn sine(amplitude: f32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .map(|i| amplitude * (i as f32 * 0.05).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_limiter_parameters() {
+        let processor = LimiterProcessor::default();
+        let params = processor.parameters();
+
+        assert_eq!(params.len(), 2);
+        assert_eq!(params[0].name, "Threshold");
+        assert_eq!(params[1].name, "Release");
+        assert_eq!(
+            processor.get_parameter(PARAM_THRESHOLD).unwrap(),
+            DEFAULT_THRESHOLD_DB
+        );
+    }
+
+    #[test]
+    fn test_limiter_reports_lookahead_latency() {
+        let mut processor = LimiterProcessor::default();
+        processor.initialize(48000, 512).unwrap();
+        assert_eq!(processor.latency(), 240);
+
+        processor.initialize(96000, 512).unwrap();
+        assert_eq!(processor.latency(), 480);
+    }
+
+    #[test]
+    fn test_loud_signal_never_exceeds_threshold() {
+        let mut processor = LimiterProcessor::default();
+        processor.initialize(48000, 512).unwrap();
+
+        let input = sine(2.0, 48000);
+        let (left, right) = run(&mut processor, &input, &input, 512);
+
+        let threshold = 10.0_f32.powf(DEFAULT_THRESHOLD_DB / 20.0);
+        for sample in left.iter().chain(&right) {
+            assert!(sample.abs() <= threshold, "{sample} exceeds {threshold}");
+        }
+    }
+
+    #[test]
+    fn test_transient_never_exceeds_threshold() {
+        let mut processor = LimiterProcessor::default();
+        processor.initialize(48000, 512).unwrap();
+        processor.set_parameter(PARAM_THRESHOLD, -6.0).unwrap();
+        processor.set_parameter(PARAM_RELEASE, 5.0).unwrap();
+
+        // Quiet passage, a sudden 2.0 burst, then quiet again
+        let mut input = sine(0.1, 4800);
+        input[1000..1100].iter_mut().for_each(|s| *s = 2.0);
+        let (left, _) = run(&mut processor, &input, &input, 64);
+
+        let threshold = 10.0_f32.powf(-6.0 / 20.0);
+        assert!(left.iter().all(|s| s.abs() <= threshold));
+    }
+
+    #[test]
+    fn test_quiet_signal_is_delayed_unchanged() {
+        let mut processor = LimiterProcessor::default();
+        processor.initialize(48000, 512).unwrap();
+
+        let input = sine(0.5, 2048);
+        let (left, _) = run(&mut processor, &input, &input, 512);
+
+        let latency = processor.latency();
+        assert!(left[..latency].iter().all(|s| *s == 0.0));
+        for (output, expected) in left[latency..].iter().zip(&input) {
+            assert!((output - expected).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_gain_reduction_is_stereo_linked() {
+        let mut processor = LimiterProcessor::default();
+        processor.initialize(48000, 512).unwrap();
+
+        // Only the left channel is hot, but both must be reduced equally
+        let left = vec![2.0; 4800];
+        let right = vec![0.5; 4800];
+        let (left_out, right_out) = run(&mut processor, &left, &right, 512);
+
+        let last = 4799;
+        let expected_ratio = 0.5 / 2.0;
+        assert!((right_out[last] / left_out[last] - expected_ratio).abs() < 1e-4);
+        assert!(right_out[last] < 0.5);
+    }
+
+    #[test]
+    fn test_gain_recovers_after_release() {
+        let mut processor = LimiterProcessor::default();
+        processor.initialize(48000, 512).unwrap();
+        processor.set_parameter(PARAM_RELEASE, 10.0).unwrap();
+
+        let mut input = vec![2.0; 480];
+        input.extend(std::iter::repeat_n(0.5, 9600));
+        let (left, _) = run(&mut processor, &input, &input, 512);
+
+        // 200 ms after the burst the gain is back at unity
+        assert!((left[10_079] - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_sliding_min_window() {
+        let mut window = SlidingMin::new(3);
+        assert_eq!(window.push(0.5), 0.5);
+        assert_eq!(window.push(0.8), 0.5);
+        assert_eq!(window.push(0.9), 0.5);
+        assert_eq!(window.push(1.0), 0.8); // 0.5 left the window
+        assert_eq!(window.push(0.2), 0.2);
+        assert_eq!(window.push(1.0), 0.2);
+    }
+
+    #[test]
+    fn test_invalid_parameter() {
+        let mut processor = LimiterProcessor::default();
+        assert!(processor.set_parameter(2, 0.0).is_err());
+        assert!(processor.get_parameter(999).is_err());
+    }
+}
@@ -54,6 +54,7 @@ impl Default for MixerProcessor {
                 vendor: "vvdaw".to_string(),
                 version: "1.0.0".to_string(),
                 unique_id: "vvdaw.builtin.mixer".to_string(),
+                category: None,
             },
         }
     }
@@ -214,6 +215,7 @@ impl Plugin for MixerProcessor {
                 min_value: 0.0,
                 max_value: 2.0,
                 default_value: 1.0,
+                step_count: 0,
             },
             ParameterInfo {
                 id: 1,
@@ -221,6 +223,7 @@ impl Plugin for MixerProcessor {
                 min_value: 0.0,
                 max_value: 2.0,
                 default_value: 1.0,
+                step_count: 0,
             },
             ParameterInfo {
                 id: 2,
@@ -228,6 +231,7 @@ impl Plugin for MixerProcessor {
                 min_value: 0.0,
                 max_value: 2.0,
                 default_value: 1.0,
+                step_count: 0,
             },
         ]
     }
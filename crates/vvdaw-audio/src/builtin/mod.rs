@@ -4,10 +4,17 @@
 //! They implement the `Plugin` trait just like external VST3/CLAP plugins,
 //! but have zero overhead (no IPC, no FFI, just direct vtable dispatch).
 
+pub mod dcblock;
+pub mod delay;
+pub mod eq;
 pub mod gain;
+pub mod limiter;
 pub mod mixer;
+pub mod ms;
 pub mod pan;
 pub mod sampler;
+mod smoothing;
+pub mod streaming_sampler;
 
 use vvdaw_plugin::Plugin;
 
@@ -24,8 +31,14 @@ use vvdaw_plugin::Plugin;
 /// ```
 pub fn create_builtin(name: &str) -> Option<Box<dyn Plugin>> {
     match name {
+        "dcblock" => Some(Box::new(dcblock::DcBlocker::default())),
+        "delay" => Some(Box::new(delay::DelayProcessor::default())),
+        "eq" => Some(Box::new(eq::EqProcessor::default())),
         "gain" => Some(Box::new(gain::GainProcessor::default())),
+        "limiter" => Some(Box::new(limiter::LimiterProcessor::default())),
         "mixer" => Some(Box::new(mixer::MixerProcessor::default())),
+        "ms_decode" => Some(Box::new(ms::MsDecode::default())),
+        "ms_encode" => Some(Box::new(ms::MsEncode::default())),
         "pan" => Some(Box::new(pan::PanProcessor::default())),
         _ => None,
     }
@@ -35,18 +48,48 @@ pub fn create_builtin(name: &str) -> Option<Box<dyn Plugin>> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_create_dcblock() {
+        let plugin = create_builtin("dcblock");
+        assert!(plugin.is_some());
+    }
+
+    #[test]
+    fn test_create_delay() {
+        let plugin = create_builtin("delay");
+        assert!(plugin.is_some());
+    }
+
+    #[test]
+    fn test_create_eq() {
+        let plugin = create_builtin("eq");
+        assert!(plugin.is_some());
+    }
+
     #[test]
     fn test_create_gain() {
         let plugin = create_builtin("gain");
         assert!(plugin.is_some());
     }
 
+    #[test]
+    fn test_create_limiter() {
+        let plugin = create_builtin("limiter");
+        assert!(plugin.is_some());
+    }
+
     #[test]
     fn test_create_mixer() {
         let plugin = create_builtin("mixer");
         assert!(plugin.is_some());
     }
 
+    #[test]
+    fn test_create_ms() {
+        assert!(create_builtin("ms_encode").is_some());
+        assert!(create_builtin("ms_decode").is_some());
+    }
+
     #[test]
     fn test_create_pan() {
         let plugin = create_builtin("pan");
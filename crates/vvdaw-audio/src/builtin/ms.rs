@@ -0,0 +1,238 @@
+//! Mid/side processors - convert between left/right and mid/side stereo.
+
+use std::f32::consts::FRAC_1_SQRT_2;
+use vvdaw_core::SampleRate;
+use vvdaw_plugin::{AudioBuffer, EventBuffer, ParameterInfo, Plugin, PluginError, PluginInfo};
+
+/// Mid/side encoder
+///
+/// Converts a left/right signal to mid/side:
+/// - Mid (output 0) = (L + R) / √2
+/// - Side (output 1) = (L − R) / √2
+///
+/// Put M/S-only processing (e.g. gain on the side channel to widen the
+/// image) between this and an [`MsDecode`] node. The √2 scaling makes the
+/// pair lossless, with no level change through a round trip.
+pub struct MsEncode {
+    sample_rate: SampleRate,
+    info: PluginInfo,
+}
+
+/// Mid/side decoder
+///
+/// Converts a mid/side signal back to left/right, reversing [`MsEncode`]:
+/// - Left (output 0) = (M + S) / √2
+/// - Right (output 1) = (M − S) / √2
+pub struct MsDecode {
+    sample_rate: SampleRate,
+    info: PluginInfo,
+}
+
+impl Default for MsEncode {
+    fn default() -> Self {
+        Self {
+            sample_rate: 48000,
+            info: PluginInfo {
+                name: "M/S Encode".to_string(),
+                vendor: "vvdaw".to_string(),
+                version: "1.0.0".to_string(),
+                unique_id: "vvdaw.builtin.ms_encode".to_string(),
+                category: None,
+            },
+        }
+    }
+}
+
+impl Default for MsDecode {
+    fn default() -> Self {
+        Self {
+            sample_rate: 48000,
+            info: PluginInfo {
+                name: "M/S Decode".to_string(),
+                vendor: "vvdaw".to_string(),
+                version: "1.0.0".to_string(),
+                unique_id: "vvdaw.builtin.ms_decode".to_string(),
+                category: None,
+            },
+        }
+    }
+}
+
+/// Sum and difference of a stereo pair, each scaled by 1/√2
+///
+/// Encoding and decoding are the same operation: applied twice, it gives
+/// back the original pair.
+fn sum_difference(audio: &mut AudioBuffer, name: &str) -> Result<(), PluginError> {
+    // Ensure we have exactly stereo input and output
+    if audio.inputs.len() != 2 {
+        return Err(PluginError::ProcessingFailed(format!(
+            "{name} requires exactly 2 inputs (stereo), got {}",
+            audio.inputs.len()
+        )));
+    }
+    if audio.outputs.len() != 2 {
+        return Err(PluginError::ProcessingFailed(format!(
+            "{name} requires exactly 2 outputs (stereo), got {}",
+            audio.outputs.len()
+        )));
+    }
+
+    // Validate buffer lengths
+    for ch in 0..2 {
+        if audio.inputs[ch].len() < audio.frames {
+            return Err(PluginError::ProcessingFailed(format!(
+                "Input channel {} has {} samples, need at least {}",
+                ch,
+                audio.inputs[ch].len(),
+                audio.frames
+            )));
+        }
+        if audio.outputs[ch].len() < audio.frames {
+            return Err(PluginError::ProcessingFailed(format!(
+                "Output channel {} has {} samples, need at least {}",
+                ch,
+                audio.outputs[ch].len(),
+                audio.frames
+            )));
+        }
+    }
+
+    for i in 0..audio.frames {
+        let a = audio.inputs[0][i];
+        let b = audio.inputs[1][i];
+        audio.outputs[0][i] = (a + b) * FRAC_1_SQRT_2;
+        audio.outputs[1][i] = (a - b) * FRAC_1_SQRT_2;
+    }
+
+    Ok(())
+}
+
+/// `Plugin` impl shared by the encoder and decoder, which have no parameters
+macro_rules! impl_ms_plugin {
+    ($processor:ty) => {
+        impl Plugin for $processor {
+            fn info(&self) -> &PluginInfo {
+                &self.info
+            }
+
+            fn initialize(
+                &mut self,
+                sample_rate: SampleRate,
+                _max_block_size: usize,
+            ) -> Result<(), PluginError> {
+                self.sample_rate = sample_rate;
+                Ok(())
+            }
+
+            fn process(
+                &mut self,
+                audio: &mut AudioBuffer,
+                _events: &EventBuffer,
+            ) -> Result<(), PluginError> {
+                sum_difference(audio, &self.info.name)
+            }
+
+            fn set_parameter(&mut self, id: u32, _value: f32) -> Result<(), PluginError> {
+                Err(PluginError::InvalidParameter(format!(
+                    "Unknown parameter ID: {id}"
+                )))
+            }
+
+            fn get_parameter(&self, id: u32) -> Result<f32, PluginError> {
+                Err(PluginError::InvalidParameter(format!(
+                    "Unknown parameter ID: {id}"
+                )))
+            }
+
+            fn parameters(&self) -> Vec<ParameterInfo> {
+                Vec::new()
+            }
+
+            fn input_channels(&self) -> usize {
+                2 // Stereo
+            }
+
+            fn output_channels(&self) -> usize {
+                2 // Stereo
+            }
+
+            fn deactivate(&mut self) {
+                // Nothing to clean up
+            }
+        }
+    };
+}
+
+impl_ms_plugin!(MsEncode);
+impl_ms_plugin!(MsDecode);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run one block through `plugin`, returning both output channels
+    fn run(plugin: &mut dyn Plugin, left: &[f32], right: &[f32]) -> (Vec<f32>, Vec<f32>) {
+        let mut left_out = vec![0.0; left.len()];
+        let mut right_out = vec![0.0; right.len()];
+
+        let inputs: Vec<&[f32]> = vec![left, right];
+        let mut outputs: Vec<&mut [f32]> = vec![&mut left_out, &mut right_out];
+        let mut audio = AudioBuffer {
+            inputs: &inputs,
+            outputs: &mut outputs,
+            frames: left.len(),
+        };
+        plugin.process(&mut audio, &EventBuffer::new()).unwrap();
+
+        (left_out, right_out)
+    }
+
+    #[test]
+    fn test_encode_mid_and_side() {
+        let mut encode = MsEncode::default();
+        encode.initialize(48000, 512).unwrap();
+
+        // Mono (L = R) is all mid, opposite polarity (L = -R) is all side
+        let (mid, side) = run(&mut encode, &[1.0, 0.5], &[1.0, -0.5]);
+        assert!((mid[0] - std::f32::consts::SQRT_2).abs() < 1e-6);
+        assert!(side[0].abs() < 1e-6);
+        assert!(mid[1].abs() < 1e-6);
+        assert!((side[1] - FRAC_1_SQRT_2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_round_trip_reproduces_input() {
+        let mut encode = MsEncode::default();
+        let mut decode = MsDecode::default();
+        encode.initialize(48000, 512).unwrap();
+        decode.initialize(48000, 512).unwrap();
+
+        let left: Vec<f32> = (0..256).map(|i| (i as f32 * 0.1).sin()).collect();
+        let right: Vec<f32> = (0..256).map(|i| (i as f32 * 0.37).cos() * 0.5).collect();
+
+        let (mid, side) = run(&mut encode, &left, &right);
+        let (left_out, right_out) = run(&mut decode, &mid, &side);
+
+        for (output, input) in left_out.iter().zip(&left) {
+            assert!((output - input).abs() < 1e-6);
+        }
+        for (output, input) in right_out.iter().zip(&right) {
+            assert!((output - input).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_requires_stereo() {
+        let mut encode = MsEncode::default();
+        let input = [0.0; 4];
+        let mut output = [0.0; 4];
+        let inputs: Vec<&[f32]> = vec![&input];
+        let mut outputs: Vec<&mut [f32]> = vec![&mut output];
+        let mut audio = AudioBuffer {
+            inputs: &inputs,
+            outputs: &mut outputs,
+            frames: 4,
+        };
+        assert!(encode.process(&mut audio, &EventBuffer::new()).is_err());
+    }
+}
@@ -5,7 +5,39 @@ use std::sync::atomic::{AtomicU32, Ordering};
 use vvdaw_core::SampleRate;
 use vvdaw_plugin::{AudioBuffer, EventBuffer, ParameterInfo, Plugin, PluginError, PluginInfo};
 
-/// Stereo balance processor using constant-power panning
+/// Parameter IDs
+pub const PARAM_PAN: u32 = 0;
+pub const PARAM_LAW: u32 = 1;
+
+/// How much the center position attenuates each channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanLaw {
+    /// -3 dB center, sine/cosine gains (L² + R² = 1, constant loudness)
+    EqualPower,
+    /// -4.5 dB center, halfway between equal-power and linear
+    Compromise,
+    /// -6 dB center, gains fall off in a straight line (L + R = 1)
+    Linear,
+}
+
+impl PanLaw {
+    /// All laws, in Pan Law parameter order
+    const ALL: [Self; 3] = [Self::EqualPower, Self::Compromise, Self::Linear];
+
+    /// Law selected by a Pan Law parameter value (rounded, clamped to the range)
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Clamped to 0-2
+    fn from_parameter(value: f32) -> Self {
+        Self::ALL[value.round().clamp(0.0, 2.0) as usize]
+    }
+
+    /// Pan Law parameter value selecting this law
+    #[allow(clippy::cast_precision_loss)] // Index below 3
+    fn to_parameter(self) -> f32 {
+        Self::ALL.iter().position(|&law| law == self).unwrap_or(0) as f32
+    }
+}
+
+/// Stereo balance processor with a selectable pan law
 ///
 /// This is a **stereo balance control**, not a mono-to-stereo panner. It adjusts
 /// the relative levels of the left and right channels of an existing stereo signal.
@@ -13,7 +45,8 @@ use vvdaw_plugin::{AudioBuffer, EventBuffer, ParameterInfo, Plugin, PluginError,
 /// ## Behavior
 ///
 /// - **Pan = -1.0 (Full Left)**: Left channel at full volume (1.0), right channel silent (0.0)
-/// - **Pan = 0.0 (Center)**: Both channels at ~0.707 gain (constant-power center)
+/// - **Pan = 0.0 (Center)**: Both channels at ~0.707 gain (constant-power center,
+///   or 0.595 / 0.5 under the -4.5 dB and linear [`PanLaw`]s)
 /// - **Pan = +1.0 (Full Right)**: Left channel silent (0.0), right channel at full volume (1.0)
 ///
 /// Each output channel is affected independently:
@@ -21,7 +54,8 @@ use vvdaw_plugin::{AudioBuffer, EventBuffer, ParameterInfo, Plugin, PluginError,
 /// - Right output = Right input × `right_gain`
 ///
 /// This maintains the stereo image of the input signal while shifting the balance.
-/// Constant-power panning ensures L² + R² = 1, maintaining perceived loudness.
+/// Constant-power panning (the default Pan Law) ensures L² + R² = 1, maintaining
+/// perceived loudness.
 ///
 /// ## Not Implemented
 ///
@@ -31,6 +65,8 @@ use vvdaw_plugin::{AudioBuffer, EventBuffer, ParameterInfo, Plugin, PluginError,
 pub struct PanProcessor {
     /// Pan position stored as f32 bits (-1.0 = full left, 0.0 = center, 1.0 = full right)
     pan: AtomicU32,
+    /// Pan Law parameter value stored as f32 bits (see [`PanLaw`])
+    law: AtomicU32,
     sample_rate: SampleRate,
     info: PluginInfo,
 }
@@ -40,12 +76,14 @@ impl Default for PanProcessor {
         Self {
             // Default pan: 0.0 (center)
             pan: AtomicU32::new(0.0_f32.to_bits()),
+            law: AtomicU32::new(PanLaw::EqualPower.to_parameter().to_bits()),
             sample_rate: 48000,
             info: PluginInfo {
                 name: "Pan".to_string(),
                 vendor: "vvdaw".to_string(),
                 version: "1.0.0".to_string(),
                 unique_id: "vvdaw.builtin.pan".to_string(),
+                category: None,
             },
         }
     }
@@ -62,24 +100,43 @@ impl PanProcessor {
         self.pan.store(value.to_bits(), Ordering::Release);
     }
 
-    /// Calculate constant-power pan gains
+    /// Get the current pan law (thread-safe)
+    fn get_law(&self) -> PanLaw {
+        PanLaw::from_parameter(f32::from_bits(self.law.load(Ordering::Acquire)))
+    }
+
+    /// Set the pan law (thread-safe)
+    fn set_law(&self, law: PanLaw) {
+        self.law
+            .store(law.to_parameter().to_bits(), Ordering::Release);
+    }
+
+    /// Calculate pan gains under `law`
     ///
-    /// Returns (`left_gain`, `right_gain`) using constant-power law:
+    /// Returns (`left_gain`, `right_gain`), e.g. for the constant-power law:
     /// - pan = -1.0: (1.0, 0.0) - full left
     /// - pan =  0.0: (0.707, 0.707) - center
     /// - pan =  1.0: (0.0, 1.0) - full right
-    fn calculate_gains(pan: f32) -> (f32, f32) {
+    fn calculate_gains(pan: f32, law: PanLaw) -> (f32, f32) {
         // Convert pan from [-1, 1] to [0, 1]
         let pan_normalized = (pan + 1.0) * 0.5;
+        let linear = (1.0 - pan_normalized, pan_normalized);
 
         // Convert to angle [0, π/2]
         let angle = pan_normalized * FRAC_PI_2;
 
         // Constant-power panning using cos/sin
-        let left_gain = angle.cos();
-        let right_gain = angle.sin();
-
-        (left_gain, right_gain)
+        let equal_power = (angle.cos(), angle.sin());
+
+        match law {
+            PanLaw::EqualPower => equal_power,
+            PanLaw::Linear => linear,
+            // Geometric mean of the two: -4.5 dB at the center
+            PanLaw::Compromise => (
+                (linear.0 * equal_power.0).sqrt(),
+                (linear.1 * equal_power.1).sqrt(),
+            ),
+        }
     }
 }
 
@@ -103,7 +160,7 @@ impl Plugin for PanProcessor {
         _events: &EventBuffer,
     ) -> Result<(), PluginError> {
         let pan = self.get_pan();
-        let (left_gain, right_gain) = Self::calculate_gains(pan);
+        let (left_gain, right_gain) = Self::calculate_gains(pan, self.get_law());
 
         // Ensure we have exactly stereo input and output
         if audio.inputs.len() != 2 {
@@ -142,7 +199,7 @@ impl Plugin for PanProcessor {
         let left_in = audio.inputs[0];
         let right_in = audio.inputs[1];
 
-        // Apply constant-power panning (stereo balance)
+        // Apply the pan law gains (stereo balance)
         // Left output gets left input with left gain, right output gets right input with right gain
         for i in 0..audio.frames {
             audio.outputs[0][i] = left_in[i] * left_gain;
@@ -154,12 +211,16 @@ impl Plugin for PanProcessor {
 
     fn set_parameter(&mut self, id: u32, value: f32) -> Result<(), PluginError> {
         match id {
-            0 => {
+            PARAM_PAN => {
                 // Pan parameter: -1.0 (left) to 1.0 (right)
                 let clamped = value.clamp(-1.0, 1.0);
                 self.set_pan(clamped);
                 Ok(())
             }
+            PARAM_LAW => {
+                self.set_law(PanLaw::from_parameter(value));
+                Ok(())
+            }
             _ => Err(PluginError::InvalidParameter(format!(
                 "Unknown parameter ID: {id}"
             ))),
@@ -168,7 +229,8 @@ impl Plugin for PanProcessor {
 
     fn get_parameter(&self, id: u32) -> Result<f32, PluginError> {
         match id {
-            0 => Ok(self.get_pan()),
+            PARAM_PAN => Ok(self.get_pan()),
+            PARAM_LAW => Ok(self.get_law().to_parameter()),
             _ => Err(PluginError::InvalidParameter(format!(
                 "Unknown parameter ID: {id}"
             ))),
@@ -176,13 +238,25 @@ impl Plugin for PanProcessor {
     }
 
     fn parameters(&self) -> Vec<ParameterInfo> {
-        vec![ParameterInfo {
-            id: 0,
-            name: "Pan".to_string(),
-            min_value: -1.0,
-            max_value: 1.0,
-            default_value: 0.0,
-        }]
+        vec![
+            ParameterInfo {
+                id: PARAM_PAN,
+                name: "Pan".to_string(),
+                min_value: -1.0,
+                max_value: 1.0,
+                default_value: 0.0,
+                step_count: 0,
+            },
+            // 0 = equal-power (-3 dB), 1 = compromise (-4.5 dB), 2 = linear (-6 dB)
+            ParameterInfo {
+                id: PARAM_LAW,
+                name: "Pan Law".to_string(),
+                min_value: 0.0,
+                max_value: 2.0,
+                default_value: PanLaw::EqualPower.to_parameter(),
+                step_count: 2,
+            },
+        ]
     }
 
     fn input_channels(&self) -> usize {
@@ -241,7 +315,7 @@ mod tests {
     #[test]
     fn test_constant_power_gains() {
         // At center, both gains should be ~0.707 (sqrt(0.5))
-        let (left, right) = PanProcessor::calculate_gains(0.0);
+        let (left, right) = PanProcessor::calculate_gains(0.0, PanLaw::EqualPower);
         assert!((left - 0.707).abs() < 0.01);
         assert!((right - 0.707).abs() < 0.01);
 
@@ -250,12 +324,12 @@ mod tests {
         assert!((power - 1.0).abs() < 0.01);
 
         // At full left
-        let (left, right) = PanProcessor::calculate_gains(-1.0);
+        let (left, right) = PanProcessor::calculate_gains(-1.0, PanLaw::EqualPower);
         assert!((left - 1.0).abs() < 0.01);
         assert!(right.abs() < 0.01);
 
         // At full right
-        let (left, right) = PanProcessor::calculate_gains(1.0);
+        let (left, right) = PanProcessor::calculate_gains(1.0, PanLaw::EqualPower);
         assert!(left.abs() < 0.01);
         assert!((right - 1.0).abs() < 0.01);
     }
@@ -287,7 +361,7 @@ mod tests {
         processor.process(&mut audio, &events).unwrap();
 
         // At center pan, both channels should be at ~0.707 gain
-        let (left_gain, right_gain) = PanProcessor::calculate_gains(0.0);
+        let (left_gain, right_gain) = PanProcessor::calculate_gains(0.0, PanLaw::EqualPower);
         let expected_left = 1.0 * left_gain; // 1.0 * 0.707
         let expected_right = 0.5 * right_gain; // 0.5 * 0.707
 
@@ -396,7 +470,7 @@ mod tests {
         processor.process(&mut audio, &events).unwrap();
 
         // At mid-left, left channel should be stronger than right
-        let (left_gain, right_gain) = PanProcessor::calculate_gains(-0.5);
+        let (left_gain, right_gain) = PanProcessor::calculate_gains(-0.5, PanLaw::EqualPower);
         let expected_left = 1.0 * left_gain;
         let expected_right = 0.2 * right_gain;
 
@@ -439,7 +513,7 @@ mod tests {
         processor.process(&mut audio, &events).unwrap();
 
         // At mid-right, right channel should be stronger than left
-        let (left_gain, right_gain) = PanProcessor::calculate_gains(0.5);
+        let (left_gain, right_gain) = PanProcessor::calculate_gains(0.5, PanLaw::EqualPower);
         let expected_left = 0.3 * left_gain;
         let expected_right = 0.9 * right_gain;
 
@@ -455,6 +529,64 @@ mod tests {
         assert!(right_gain > left_gain);
     }
 
+    #[test]
+    fn test_pan_law_parameter() {
+        let mut processor = PanProcessor::default();
+        assert_eq!(processor.get_law(), PanLaw::EqualPower);
+
+        processor.set_parameter(PARAM_LAW, 2.0).unwrap();
+        assert_eq!(processor.get_law(), PanLaw::Linear);
+        assert_eq!(processor.get_parameter(PARAM_LAW).unwrap(), 2.0);
+
+        // Snapped to the nearest law and clamped to the range
+        processor.set_parameter(PARAM_LAW, 0.8).unwrap();
+        assert_eq!(processor.get_law(), PanLaw::Compromise);
+        processor.set_parameter(PARAM_LAW, 7.0).unwrap();
+        assert_eq!(processor.get_law(), PanLaw::Linear);
+
+        let law = &processor.parameters()[1];
+        assert!(law.is_discrete());
+        assert_eq!(law.step_count, 2);
+    }
+
+    #[test]
+    fn test_pan_law_center_levels() {
+        // A mono source (same signal on both sides) panned to the center
+        let mut processor = PanProcessor::default();
+        processor.initialize(48000, 512).unwrap();
+
+        let source = vec![1.0; 64];
+        let events = EventBuffer::new();
+        for (law, expected) in [(0.0, 0.707), (1.0, 0.595), (2.0, 0.5)] {
+            processor.set_parameter(PARAM_LAW, law).unwrap();
+
+            let mut left_out = vec![0.0; 64];
+            let mut right_out = vec![0.0; 64];
+            let inputs: Vec<&[f32]> = vec![&source, &source];
+            let mut outputs: Vec<&mut [f32]> = vec![&mut left_out, &mut right_out];
+            let mut audio = AudioBuffer {
+                inputs: &inputs,
+                outputs: &mut outputs,
+                frames: 64,
+            };
+            processor.process(&mut audio, &events).unwrap();
+
+            for sample in left_out.iter().chain(&right_out) {
+                assert!((*sample - expected).abs() < 0.001, "law {law}: {sample}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_pan_laws_meet_at_the_sides() {
+        for law in PanLaw::ALL {
+            let (left, right) = PanProcessor::calculate_gains(-1.0, law);
+            assert!((left - 1.0).abs() < 1e-6 && right.abs() < 1e-6);
+            let (left, right) = PanProcessor::calculate_gains(1.0, law);
+            assert!(left.abs() < 1e-6 && (right - 1.0).abs() < 1e-6);
+        }
+    }
+
     #[test]
     fn test_invalid_parameter() {
         let mut processor = PanProcessor::default();
@@ -1,12 +1,151 @@
 //! Sample playback processor - plays loaded audio files.
 
-use vvdaw_core::SampleRate;
-use vvdaw_plugin::{AudioBuffer, EventBuffer, Plugin, PluginError, PluginInfo};
+use vvdaw_core::{Frames, SampleRate, db_to_linear};
+use vvdaw_plugin::{
+    AudioBuffer, Event, EventBuffer, ParameterInfo, Plugin, PluginError, PluginInfo,
+};
+
+/// Varispeed playback rate parameter (see [`Plugin::set_playback_rate`])
+pub const PARAM_PLAYBACK_RATE: u32 = 0;
+
+/// Trigger mode parameter (see [`PlaybackMode`])
+pub const PARAM_PLAYBACK_MODE: u32 = 1;
+
+/// Velocity-to-gain curve parameter (see [`VelocityCurve`])
+pub const PARAM_VELOCITY_CURVE: u32 = 2;
+
+/// Maximum number of overlapping voices parameter (1 to [`MAX_VOICES`])
+pub const PARAM_POLYPHONY: u32 = 3;
+
+/// Fastest varispeed rate, in either direction
+pub const MAX_PLAYBACK_RATE: f32 = 4.0;
+
+/// Most voices a sampler can stack
+pub const MAX_VOICES: usize = 16;
+
+/// Level range covered by [`VelocityCurve::Exponential`], from full velocity down
+const VELOCITY_RANGE_DB: f32 = 40.0;
+
+/// How the sampler responds to note events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlaybackMode {
+    /// Note on plays the whole sample. A monophonic one-shot ignores further
+    /// notes until it ends
+    #[default]
+    OneShot,
+    /// Note on starts playback, which wraps around (the loop region if set,
+    /// otherwise the whole sample) until note off
+    Loop,
+    /// Note on starts playback, note off stops it
+    Gate,
+}
+
+impl PlaybackMode {
+    /// All modes, in Playback Mode parameter order
+    const ALL: [Self; 3] = [Self::OneShot, Self::Loop, Self::Gate];
+
+    /// Mode selected by a Playback Mode parameter value (rounded, clamped to the range)
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Clamped to 0-2
+    fn from_parameter(value: f32) -> Self {
+        Self::ALL[value.round().clamp(0.0, 2.0) as usize]
+    }
+
+    /// Playback Mode parameter value selecting this mode
+    #[allow(clippy::cast_precision_loss)] // Index below 3
+    fn to_parameter(self) -> f32 {
+        Self::ALL.iter().position(|&mode| mode == self).unwrap_or(0) as f32
+    }
+}
+
+/// How note velocity maps to a triggered voice's gain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VelocityCurve {
+    /// Gain equals velocity
+    #[default]
+    Linear,
+    /// Velocity maps linearly onto decibels (a 40 dB range), so soft hits
+    /// fall off the way they sound on an acoustic kit
+    Exponential,
+}
+
+impl VelocityCurve {
+    /// Gain for a note velocity (0.0-1.0)
+    #[must_use]
+    pub fn gain(self, velocity: f32) -> f32 {
+        let velocity = velocity.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => velocity,
+            Self::Exponential => db_to_linear(VELOCITY_RANGE_DB * (velocity - 1.0)),
+        }
+    }
+
+    /// Curve selected by a Velocity Curve parameter value (0 = linear)
+    fn from_parameter(value: f32) -> Self {
+        if value.round() >= 1.0 {
+            Self::Exponential
+        } else {
+            Self::Linear
+        }
+    }
+
+    /// Velocity Curve parameter value selecting this curve
+    const fn to_parameter(self) -> f32 {
+        match self {
+            Self::Linear => 0.0,
+            Self::Exponential => 1.0,
+        }
+    }
+}
+
+/// One playhead through the sample
+#[derive(Debug, Clone, Copy)]
+struct Voice {
+    /// Playback position in frames, fractional for interpolated reads
+    position: f64,
+    /// Output gain (from the triggering note's velocity)
+    gain: f32,
+    /// Note that triggered the voice, `None` for the timeline playhead
+    note: Option<u8>,
+    /// Whether the voice sounds (false when idle or released)
+    active: bool,
+    /// Trigger order, for stealing the oldest voice
+    started: u64,
+}
+
+impl Voice {
+    const IDLE: Self = Self {
+        position: 0.0,
+        gain: 1.0,
+        note: None,
+        active: false,
+        started: 0,
+    };
+}
 
 /// Sample playback processor
 ///
 /// Plays back pre-loaded audio samples (e.g., from WAV files).
-/// Plays once and outputs silence after reaching the end.
+/// Plays once and outputs silence after reaching the end, unless a loop
+/// region is set (see [`Plugin::set_loop`]), in which case playback wraps
+/// gaplessly from the loop end back to its start.
+///
+/// Supports tape-style scrubbing (see [`Plugin::scrub`]): the playhead is
+/// fractional, so scrub rates other than 1.0 (including reverse) are read
+/// with linear interpolation between neighbouring frames.
+///
+/// The same interpolation drives varispeed playback (see
+/// [`Plugin::set_playback_rate`], also exposed as the "Playback Rate"
+/// parameter): pitch follows speed, 0.0 holds the playhead, and negative rates
+/// play backward. Scrubs and seeks return to the varispeed rate afterwards.
+///
+/// `Event::NoteOn`/`NoteOff` trigger the sample like a drum pad, at their
+/// sample offset, according to the [`PlaybackMode`] (any note triggers; a note
+/// on with zero velocity counts as a note off). Each note plays on its own
+/// voice at a gain set by its velocity through the [`VelocityCurve`]; up to
+/// the Polyphony parameter's worth of voices stack, after which a new note
+/// steals the oldest one. A new sampler - or one that's been seeked or
+/// scrubbed - plays a single full-level voice from its playhead without
+/// waiting for a note, so timeline playback works without any events.
 ///
 /// # Real-Time Safety
 ///
@@ -14,6 +153,7 @@ use vvdaw_plugin::{AudioBuffer, EventBuffer, Plugin, PluginError, PluginInfo};
 /// deterministic deallocation. When this processor is dropped (e.g., node removed
 /// from graph), the boxed slice deallocates predictably, avoiding potential
 /// non-deterministic Arc reference count decrements in the audio callback.
+/// Voices live in a fixed inline array, so triggering never allocates.
 pub struct SamplerProcessor {
     /// Audio samples (interleaved stereo: [L, R, L, R, ...])
     ///
@@ -22,8 +162,24 @@ pub struct SamplerProcessor {
     /// - No reference counting overhead
     /// - Real-time safe cleanup when node is removed
     samples: Box<[f32]>,
-    /// Current playback position (in frames, not samples)
-    position: usize,
+    /// Playheads; only the first `polyphony` are used
+    voices: [Voice; MAX_VOICES],
+    /// Voices that may sound at once
+    polyphony: usize,
+    /// Triggers so far, stamped on voices to find the oldest
+    triggers: u64,
+    /// Frames advanced per output frame (1.0 = normal, negative = reverse)
+    rate: f64,
+    /// Varispeed rate that `rate` returns to when not scrubbing
+    playback_rate: f64,
+    /// Output frames left in the current scrub window (0 = normal playback)
+    scrub_remaining: Frames,
+    /// Loop region in frames (start, exclusive end), `None` when not looping
+    loop_region: Option<(u64, u64)>,
+    /// How note events trigger playback
+    mode: PlaybackMode,
+    /// How note velocity maps to voice gain
+    velocity_curve: VelocityCurve,
     /// Sample rate of the loaded audio
     audio_sample_rate: SampleRate,
     /// Engine sample rate
@@ -39,14 +195,26 @@ impl SamplerProcessor {
     /// * `samples` - Interleaved stereo audio data [L, R, L, R, ...]
     /// * `sample_rate` - Sample rate of the loaded audio
     ///
+    /// The audio should already be at the engine's sample rate (see
+    /// [`crate::resample::resample_stereo`]); a mismatch plays at the wrong
+    /// speed and pitch.
+    ///
     /// # Real-Time Safety
     ///
     /// Converts `Vec<f32>` to `Box<[f32]>` for deterministic memory management.
     /// The conversion happens here (UI thread), not in the audio callback.
     pub fn new(samples: Vec<f32>, sample_rate: SampleRate) -> Self {
-        Self {
+        let mut processor = Self {
             samples: samples.into_boxed_slice(),
-            position: 0,
+            voices: [Voice::IDLE; MAX_VOICES],
+            polyphony: 1,
+            triggers: 0,
+            rate: 1.0,
+            playback_rate: 1.0,
+            scrub_remaining: 0,
+            loop_region: None,
+            mode: PlaybackMode::OneShot,
+            velocity_curve: VelocityCurve::Linear,
             audio_sample_rate: sample_rate,
             engine_sample_rate: 48000, // Will be updated in initialize()
             info: PluginInfo {
@@ -54,14 +222,216 @@ impl SamplerProcessor {
                 vendor: "vvdaw".to_string(),
                 version: "1.0.0".to_string(),
                 unique_id: "vvdaw.builtin.sampler".to_string(),
+                category: None,
             },
+        };
+        processor.place_playhead(0.0);
+        processor
+    }
+
+    /// Set how note events trigger playback
+    pub const fn set_mode(&mut self, mode: PlaybackMode) {
+        self.mode = mode;
+    }
+
+    /// How note events trigger playback
+    pub const fn mode(&self) -> PlaybackMode {
+        self.mode
+    }
+
+    /// Set how note velocity maps to voice gain
+    pub const fn set_velocity_curve(&mut self, curve: VelocityCurve) {
+        self.velocity_curve = curve;
+    }
+
+    /// How note velocity maps to voice gain
+    pub const fn velocity_curve(&self) -> VelocityCurve {
+        self.velocity_curve
+    }
+
+    /// Set how many voices may sound at once (clamped to 1-[`MAX_VOICES`])
+    ///
+    /// Voices beyond a lowered limit are silenced.
+    pub fn set_polyphony(&mut self, polyphony: usize) {
+        self.polyphony = polyphony.clamp(1, MAX_VOICES);
+        for voice in &mut self.voices[self.polyphony..] {
+            voice.active = false;
         }
     }
 
+    /// How many voices may sound at once
+    pub const fn polyphony(&self) -> usize {
+        self.polyphony
+    }
+
     /// Get the number of frames in the loaded audio
     fn frame_count(&self) -> usize {
         self.samples.len() / 2 // Divide by 2 for stereo
     }
+
+    /// Read a stereo frame at a fractional position using linear interpolation
+    ///
+    /// Returns `None` outside the loaded audio (before the start or past the end).
+    fn frame_at(&self, position: f64) -> Option<(f32, f32)> {
+        if position < 0.0 {
+            return None;
+        }
+
+        let frame_count = self.frame_count();
+        let index = position as usize;
+        if index >= frame_count {
+            return None;
+        }
+
+        let next = (index + 1).min(frame_count - 1);
+        let frac = (position - index as f64) as f32;
+
+        let (l0, r0) = (self.samples[index * 2], self.samples[index * 2 + 1]);
+        let (l1, r1) = (self.samples[next * 2], self.samples[next * 2 + 1]);
+        Some(((l1 - l0).mul_add(frac, l0), (r1 - r0).mul_add(frac, r0)))
+    }
+
+    /// Region playback wraps around: the loop region, or the whole sample
+    /// in [`PlaybackMode::Loop`]
+    fn wrap_region(&self) -> Option<(u64, u64)> {
+        self.loop_region
+            .or_else(|| (self.mode == PlaybackMode::Loop).then_some((0, self.frame_count() as u64)))
+    }
+
+    /// Whether a voice is still making (or about to make) sound
+    fn is_sounding(&self, voice: &Voice) -> bool {
+        voice.active && self.frame_at(voice.position).is_some()
+    }
+
+    /// Replace all voices with a single full-level playhead at `position`
+    ///
+    /// Timeline moves (seek, scrub) act on "the" playhead, so any notes still
+    /// ringing are cut.
+    fn place_playhead(&mut self, position: f64) {
+        self.voices = [Voice::IDLE; MAX_VOICES];
+        self.voices[0] = Voice {
+            position,
+            active: true,
+            ..Voice::IDLE
+        };
+    }
+
+    /// Start a voice from the top (the end when playing in reverse)
+    fn note_on(&mut self, note: u8, velocity: f32) {
+        let voices = &self.voices[..self.polyphony];
+        let slot = match voices.iter().position(|voice| !self.is_sounding(voice)) {
+            Some(free) => free,
+            // A monophonic one-shot still sounding ignores retriggers
+            None if self.mode == PlaybackMode::OneShot && self.polyphony == 1 => return,
+            // Steal the oldest voice
+            None => voices
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, voice)| voice.started)
+                .map_or(0, |(index, _)| index),
+        };
+
+        self.triggers += 1;
+        self.voices[slot] = Voice {
+            position: if self.playback_rate < 0.0 {
+                self.frame_count().saturating_sub(1) as f64
+            } else {
+                0.0
+            },
+            gain: self.velocity_curve.gain(velocity),
+            note: Some(note),
+            active: true,
+            started: self.triggers,
+        };
+        self.rate = self.playback_rate;
+        self.scrub_remaining = 0;
+    }
+
+    /// Release the voices playing `note` (one-shots play on)
+    fn note_off(&mut self, note: u8) {
+        if self.mode == PlaybackMode::OneShot {
+            return;
+        }
+        for voice in &mut self.voices {
+            if voice.note == Some(note) {
+                voice.active = false;
+            }
+        }
+    }
+
+    /// Apply the note events landing on frame `frame` of a `frames`-long block
+    ///
+    /// Offsets past the end of the block land on its last frame.
+    fn apply_events(&mut self, events: &EventBuffer, frame: usize, frames: usize) {
+        let lands_here = |offset: u32| (offset as usize).min(frames - 1) == frame;
+        for event in &events.events {
+            match *event {
+                Event::NoteOn {
+                    note,
+                    velocity,
+                    sample_offset,
+                    ..
+                } if lands_here(sample_offset) => {
+                    if velocity > 0.0 {
+                        self.note_on(note, velocity);
+                    } else {
+                        self.note_off(note);
+                    }
+                }
+                Event::NoteOff {
+                    note,
+                    sample_offset,
+                    ..
+                } if lands_here(sample_offset) => {
+                    self.note_off(note);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Read one output frame from `voice` and move it along
+    ///
+    /// Returns the voice's (gain-scaled) contribution, silence when it's
+    /// released or has run off either end.
+    fn advance_voice(&self, voice: &mut Voice, wrap_region: Option<(u64, u64)>) -> (f32, f32) {
+        if !voice.active {
+            return (0.0, 0.0);
+        }
+
+        let Some((left, right)) = self.frame_at(voice.position) else {
+            // Only move back toward the audio, so reversing direction
+            // resumes playback right away
+            let returning = if voice.position < 0.0 {
+                self.rate > 0.0
+            } else {
+                self.rate < 0.0
+            };
+            if returning {
+                voice.position += self.rate;
+            }
+            return (0.0, 0.0);
+        };
+
+        // Advance position (backwards when scrubbing in reverse)
+        let previous = voice.position;
+        voice.position += self.rate;
+
+        // Wrap when crossing the loop end going forward (or its start going
+        // backward). Playheads that start outside the region (e.g. after a
+        // seek) play on untouched.
+        if let Some((start, end)) = wrap_region {
+            let (start, end) = (start as f64, end as f64);
+            if self.rate > 0.0 && previous < end && voice.position >= end {
+                voice.position = start + (voice.position - end) % (end - start);
+            } else if self.rate < 0.0 && previous >= start && voice.position < start {
+                let back = (start - voice.position) % (end - start);
+                voice.position = if back > 0.0 { end - back } else { start };
+            }
+        }
+
+        (left * voice.gain, right * voice.gain)
+    }
 }
 
 impl Plugin for SamplerProcessor {
@@ -76,28 +446,12 @@ impl Plugin for SamplerProcessor {
     ) -> Result<(), PluginError> {
         self.engine_sample_rate = sample_rate;
 
-        // ⚠️ LIMITATION: No sample rate conversion implemented
-        //
-        // If the loaded audio file's sample rate doesn't match the engine sample rate,
-        // playback will be at the wrong speed:
-        // - 44.1kHz file on 48kHz engine → plays ~8.8% faster, pitch shifted up
-        // - 48kHz file on 44.1kHz engine → plays ~8.2% slower, pitch shifted down
-        //
-        // WORKAROUNDS (for production use):
-        // 1. Resample audio files offline before loading (using ffmpeg, sox, etc.)
-        // 2. Implement real-time sample rate conversion (SRC):
-        //    - Linear interpolation (fast, lower quality)
-        //    - libsamplerate / rubberband (high quality, more CPU)
-        //    - sinc resampling (best quality, highest CPU cost)
-        // 3. Load-time resampling in the UI thread before sending to audio thread
-        //
-        // For this POC, we accept the limitation because:
-        // - Most modern audio is 44.1kHz or 48kHz
-        // - Sample rate conversion is complex and CPU-intensive
-        // - Users can easily resample files offline if needed
+        // Samples are played one frame per output frame, so the audio must
+        // already be at the engine rate - loaders convert with
+        // `resample::resample_stereo` before building the sampler
         if self.audio_sample_rate != sample_rate {
             tracing::warn!(
-                "Sample rate mismatch: audio is {}Hz, engine is {}Hz. Playback speed will be incorrect (~{:.1}%).",
+                "Sample rate mismatch: audio is {}Hz, engine is {}Hz. Playback speed will be incorrect (~{:.1}%). Resample with vvdaw_audio::resample::resample_stereo when loading.",
                 self.audio_sample_rate,
                 sample_rate,
                 ((f64::from(sample_rate) / f64::from(self.audio_sample_rate) - 1.0) * 100.0).abs()
@@ -118,7 +472,7 @@ impl Plugin for SamplerProcessor {
     fn process(
         &mut self,
         audio: &mut AudioBuffer,
-        _events: &EventBuffer,
+        events: &EventBuffer,
     ) -> Result<(), PluginError> {
         // Validate we have 2 outputs (stereo)
         if audio.outputs.len() != 2 {
@@ -151,22 +505,30 @@ impl Plugin for SamplerProcessor {
             return Ok(());
         }
 
-        // Output samples (stop at end, no looping)
+        // Output samples (stop at either end unless looping), mixing the voices
+        let wrap_region = self.wrap_region();
         for i in 0..audio.frames {
-            // Check if we've reached the end
-            if self.position >= frame_count {
-                // Output silence for remaining samples
-                audio.outputs[0][i] = 0.0;
-                audio.outputs[1][i] = 0.0;
-            } else {
-                let sample_pos = self.position * 2; // Convert to sample index (interleaved stereo)
+            if !events.events.is_empty() {
+                self.apply_events(events, i, audio.frames);
+            }
 
-                // Output left and right channels
-                audio.outputs[0][i] = self.samples[sample_pos]; // Left
-                audio.outputs[1][i] = self.samples[sample_pos + 1]; // Right
+            let (mut left, mut right) = (0.0, 0.0);
+            for index in 0..self.polyphony {
+                let mut voice = self.voices[index];
+                let (voice_left, voice_right) = self.advance_voice(&mut voice, wrap_region);
+                self.voices[index] = voice;
+                left += voice_left;
+                right += voice_right;
+            }
+            audio.outputs[0][i] = left;
+            audio.outputs[1][i] = right;
 
-                // Advance position
-                self.position += 1;
+            // Scrub window finished - resume playback from where we stopped
+            if self.scrub_remaining > 0 {
+                self.scrub_remaining -= 1;
+                if self.scrub_remaining == 0 {
+                    self.rate = self.playback_rate;
+                }
             }
         }
 
@@ -174,24 +536,128 @@ impl Plugin for SamplerProcessor {
     }
 
     fn get_parameter(&self, id: u32) -> Result<f32, PluginError> {
-        Err(PluginError::InvalidParameter(format!(
-            "Sampler has no parameter with id {id}"
-        )))
+        match id {
+            PARAM_PLAYBACK_RATE => Ok(self.playback_rate as f32),
+            PARAM_PLAYBACK_MODE => Ok(self.mode.to_parameter()),
+            PARAM_VELOCITY_CURVE => Ok(self.velocity_curve.to_parameter()),
+            PARAM_POLYPHONY => Ok(self.polyphony as f32),
+            _ => Err(PluginError::InvalidParameter(format!(
+                "Sampler has no parameter with id {id}"
+            ))),
+        }
     }
 
-    fn set_parameter(&mut self, id: u32, _value: f32) -> Result<(), PluginError> {
-        Err(PluginError::InvalidParameter(format!(
-            "Sampler has no parameter with id {id}"
-        )))
+    fn set_parameter(&mut self, id: u32, value: f32) -> Result<(), PluginError> {
+        match id {
+            PARAM_PLAYBACK_RATE => {
+                self.set_playback_rate(value);
+                Ok(())
+            }
+            PARAM_PLAYBACK_MODE => {
+                self.set_mode(PlaybackMode::from_parameter(value));
+                Ok(())
+            }
+            PARAM_VELOCITY_CURVE => {
+                self.set_velocity_curve(VelocityCurve::from_parameter(value));
+                Ok(())
+            }
+            PARAM_POLYPHONY => {
+                self.set_polyphony(value.round().max(1.0) as usize);
+                Ok(())
+            }
+            _ => Err(PluginError::InvalidParameter(format!(
+                "Sampler has no parameter with id {id}"
+            ))),
+        }
     }
 
-    fn parameters(&self) -> Vec<vvdaw_plugin::ParameterInfo> {
-        vec![] // No parameters for now
+    fn parameters(&self) -> Vec<ParameterInfo> {
+        vec![
+            ParameterInfo {
+                id: PARAM_PLAYBACK_RATE,
+                name: "Playback Rate".to_string(),
+                min_value: -MAX_PLAYBACK_RATE,
+                max_value: MAX_PLAYBACK_RATE,
+                default_value: 1.0,
+                step_count: 0,
+            },
+            // 0 = one-shot, 1 = loop, 2 = gate
+            ParameterInfo {
+                id: PARAM_PLAYBACK_MODE,
+                name: "Playback Mode".to_string(),
+                min_value: 0.0,
+                max_value: 2.0,
+                default_value: PlaybackMode::OneShot.to_parameter(),
+                step_count: 2,
+            },
+            // 0 = linear, 1 = exponential
+            ParameterInfo {
+                id: PARAM_VELOCITY_CURVE,
+                name: "Velocity Curve".to_string(),
+                min_value: 0.0,
+                max_value: 1.0,
+                default_value: VelocityCurve::Linear.to_parameter(),
+                step_count: 1,
+            },
+            ParameterInfo {
+                id: PARAM_POLYPHONY,
+                name: "Polyphony".to_string(),
+                min_value: 1.0,
+                max_value: MAX_VOICES as f32,
+                default_value: 1.0,
+                step_count: MAX_VOICES as u32 - 1,
+            },
+        ]
     }
 
     fn deactivate(&mut self) {
-        // Reset playback position on deactivation
-        self.position = 0;
+        // Reset playback position on deactivation (the varispeed rate is a
+        // parameter, so it stays)
+        self.place_playhead(0.0);
+        self.rate = self.playback_rate;
+        self.scrub_remaining = 0;
+    }
+
+    fn scrub(&mut self, frame: u64, rate: f32, window: Frames) {
+        self.place_playhead(frame as f64);
+        self.rate = f64::from(rate);
+        self.scrub_remaining = window;
+    }
+
+    fn set_loop(&mut self, start: u64, end: u64, enabled: bool) -> Option<(u64, u64)> {
+        // Clamp to the loaded audio; an empty region (including start == end)
+        // disables looping rather than wrapping in place forever
+        let end = end.min(self.frame_count() as u64);
+        self.loop_region = (enabled && start < end).then_some((start, end));
+        self.loop_region
+    }
+
+    fn seek(&mut self, frame: u64) -> Option<u64> {
+        // Clamp past-the-end seeks to the last frame
+        let last_frame = self.frame_count().saturating_sub(1) as u64;
+        let landed = frame.min(last_frame);
+
+        self.place_playhead(landed as f64);
+        self.rate = self.playback_rate;
+        self.scrub_remaining = 0;
+
+        Some(landed)
+    }
+
+    fn set_playback_rate(&mut self, rate: f32) -> Option<f32> {
+        // NaN would poison the playhead for good
+        let rate = if rate.is_nan() {
+            1.0
+        } else {
+            rate.clamp(-MAX_PLAYBACK_RATE, MAX_PLAYBACK_RATE)
+        };
+
+        self.playback_rate = f64::from(rate);
+        // A scrub in progress keeps its own rate until its window ends
+        if self.scrub_remaining == 0 {
+            self.rate = self.playback_rate;
+        }
+        Some(rate)
     }
 }
 
@@ -260,4 +726,379 @@ mod tests {
             assert_eq!(output_r[i], 0.0);
         }
     }
+
+    /// Build a sampler whose frame `n` is `(n, -n)` so positions are easy to read back
+    fn ramp_sampler(frames: usize) -> SamplerProcessor {
+        let audio_data = (0..frames).flat_map(|n| [n as f32, -(n as f32)]).collect();
+        let mut sampler = SamplerProcessor::new(audio_data, 48000);
+        sampler.initialize(48000, 512).unwrap();
+        sampler
+    }
+
+    #[test]
+    fn test_sampler_reverse_scrub_reads_backward() {
+        let mut sampler = ramp_sampler(10);
+        sampler.scrub(5, -1.0, 4);
+
+        let mut output_l = vec![0.0; 4];
+        let mut output_r = vec![0.0; 4];
+        let mut audio = AudioBuffer {
+            inputs: &[],
+            outputs: &mut [&mut output_l, &mut output_r],
+            frames: 4,
+        };
+
+        sampler
+            .process(&mut audio, &EventBuffer::default())
+            .unwrap();
+
+        // Reads frames 5, 4, 3, 2 - backward from the scrub position
+        assert_eq!(output_l, vec![5.0, 4.0, 3.0, 2.0]);
+        assert_eq!(output_r, vec![-5.0, -4.0, -3.0, -2.0]);
+    }
+
+    #[test]
+    fn test_sampler_scrub_interpolates_fractional_rate() {
+        let mut sampler = ramp_sampler(10);
+        sampler.scrub(4, -0.5, 4);
+
+        let mut output_l = vec![0.0; 4];
+        let mut output_r = vec![0.0; 4];
+        let mut audio = AudioBuffer {
+            inputs: &[],
+            outputs: &mut [&mut output_l, &mut output_r],
+            frames: 4,
+        };
+
+        sampler
+            .process(&mut audio, &EventBuffer::default())
+            .unwrap();
+
+        // Half-speed reverse lands between frames - linearly interpolated
+        assert_eq!(output_l, vec![4.0, 3.5, 3.0, 2.5]);
+    }
+
+    #[test]
+    fn test_sampler_scrub_window_resumes_normal_rate() {
+        let mut sampler = ramp_sampler(10);
+        sampler.scrub(3, -1.0, 2);
+
+        let mut output_l = vec![0.0; 4];
+        let mut output_r = vec![0.0; 4];
+        let mut audio = AudioBuffer {
+            inputs: &[],
+            outputs: &mut [&mut output_l, &mut output_r],
+            frames: 4,
+        };
+
+        sampler
+            .process(&mut audio, &EventBuffer::default())
+            .unwrap();
+
+        // Two reverse frames, then forward at normal speed from where scrubbing stopped
+        assert_eq!(output_l, vec![3.0, 2.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_sampler_reverse_scrub_stops_at_start() {
+        let mut sampler = ramp_sampler(10);
+        sampler.scrub(1, -1.0, 4);
+
+        let mut output_l = vec![999.0; 4];
+        let mut output_r = vec![999.0; 4];
+        let mut audio = AudioBuffer {
+            inputs: &[],
+            outputs: &mut [&mut output_l, &mut output_r],
+            frames: 4,
+        };
+
+        sampler
+            .process(&mut audio, &EventBuffer::default())
+            .unwrap();
+
+        // Silence once the playhead runs off the start
+        assert_eq!(output_l, vec![1.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_sampler_seek_moves_playhead() {
+        let mut sampler = ramp_sampler(10);
+        assert_eq!(sampler.seek(6), Some(6));
+
+        let mut output_l = vec![0.0; 3];
+        let mut output_r = vec![0.0; 3];
+        let mut audio = AudioBuffer {
+            inputs: &[],
+            outputs: &mut [&mut output_l, &mut output_r],
+            frames: 3,
+        };
+
+        sampler
+            .process(&mut audio, &EventBuffer::default())
+            .unwrap();
+
+        assert_eq!(output_l, vec![6.0, 7.0, 8.0]);
+    }
+
+    #[test]
+    fn test_sampler_seek_past_end_clamps_to_last_frame() {
+        let mut sampler = ramp_sampler(10);
+        assert_eq!(sampler.seek(1_000_000), Some(9));
+
+        // Empty sampler must not underflow
+        let mut empty = SamplerProcessor::new(vec![], 48000);
+        assert_eq!(empty.seek(42), Some(0));
+    }
+
+    #[test]
+    fn test_sampler_loop_wraps_gaplessly() {
+        let mut sampler = ramp_sampler(10);
+        assert_eq!(sampler.set_loop(2, 5, true), Some((2, 5)));
+        sampler.seek(3);
+
+        let mut output_l = vec![0.0; 7];
+        let mut output_r = vec![0.0; 7];
+        let mut audio = AudioBuffer {
+            inputs: &[],
+            outputs: &mut [&mut output_l, &mut output_r],
+            frames: 7,
+        };
+
+        sampler
+            .process(&mut audio, &EventBuffer::default())
+            .unwrap();
+
+        // Frame 5 is never played - the end is exclusive
+        assert_eq!(output_l, vec![3.0, 4.0, 2.0, 3.0, 4.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_sampler_loop_edge_cases() {
+        let mut sampler = ramp_sampler(10);
+
+        // Empty region disables looping
+        assert_eq!(sampler.set_loop(4, 4, true), None);
+
+        // End past the buffer clamps to its length
+        assert_eq!(sampler.set_loop(6, 1_000, true), Some((6, 10)));
+
+        // Disabling clears the region
+        assert_eq!(sampler.set_loop(6, 8, false), None);
+    }
+
+    /// Run `frames` frames through the sampler and return the left channel
+    fn play_left(sampler: &mut SamplerProcessor, frames: usize) -> Vec<f32> {
+        let mut output_l = vec![999.0; frames];
+        let mut output_r = vec![999.0; frames];
+        let mut audio = AudioBuffer {
+            inputs: &[],
+            outputs: &mut [&mut output_l, &mut output_r],
+            frames,
+        };
+        sampler
+            .process(&mut audio, &EventBuffer::default())
+            .unwrap();
+        output_l
+    }
+
+    #[test]
+    fn test_sampler_playback_rate_interpolates() {
+        let mut sampler = ramp_sampler(10);
+        assert_eq!(sampler.set_playback_rate(1.5), Some(1.5));
+        assert_eq!(play_left(&mut sampler, 4), vec![0.0, 1.5, 3.0, 4.5]);
+
+        assert_eq!(sampler.set_playback_rate(0.25), Some(0.25));
+        assert_eq!(play_left(&mut sampler, 3), vec![6.0, 6.25, 6.5]);
+
+        // Same control through the parameter interface, clamped to its range
+        sampler.set_parameter(PARAM_PLAYBACK_RATE, 100.0).unwrap();
+        assert_eq!(
+            sampler.get_parameter(PARAM_PLAYBACK_RATE).unwrap(),
+            MAX_PLAYBACK_RATE
+        );
+    }
+
+    #[test]
+    fn test_sampler_zero_rate_holds() {
+        let mut sampler = ramp_sampler(10);
+        sampler.seek(3);
+        sampler.set_playback_rate(0.0);
+        assert_eq!(play_left(&mut sampler, 3), vec![3.0, 3.0, 3.0]);
+    }
+
+    #[test]
+    fn test_sampler_negative_rate_plays_backward() {
+        let mut sampler = ramp_sampler(4);
+        assert_eq!(play_left(&mut sampler, 5), vec![0.0, 1.0, 2.0, 3.0, 0.0]);
+
+        // Reversing after running off the end comes back in on the next frame
+        sampler.set_playback_rate(-1.0);
+        assert_eq!(play_left(&mut sampler, 3), vec![0.0, 3.0, 2.0]);
+
+        // Loops wrap in reverse too
+        sampler.set_loop(1, 3, true);
+        sampler.seek(2);
+        assert_eq!(sampler.rate, -1.0);
+        assert_eq!(play_left(&mut sampler, 4), vec![2.0, 1.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn test_sampler_scrub_returns_to_playback_rate() {
+        let mut sampler = ramp_sampler(10);
+        sampler.set_playback_rate(2.0);
+        sampler.scrub(5, -1.0, 2);
+
+        // Rate changes during a scrub wait for its window to end
+        sampler.set_playback_rate(0.5);
+        assert_eq!(play_left(&mut sampler, 4), vec![5.0, 4.0, 3.0, 3.5]);
+    }
+
+    /// Note event at `sample_offset` (velocity 0 for a note off)
+    fn note(velocity: f32, sample_offset: u32) -> Event {
+        Event::NoteOn {
+            channel: 0,
+            note: 36,
+            velocity,
+            sample_offset,
+        }
+    }
+
+    /// Play `frames` frames with `events` and return the left channel
+    fn play_events(sampler: &mut SamplerProcessor, frames: usize, events: Vec<Event>) -> Vec<f32> {
+        let mut output_l = vec![999.0; frames];
+        let mut output_r = vec![999.0; frames];
+        let mut audio = AudioBuffer {
+            inputs: &[],
+            outputs: &mut [&mut output_l, &mut output_r],
+            frames,
+        };
+        sampler
+            .process(&mut audio, &EventBuffer { events })
+            .unwrap();
+        output_l
+    }
+
+    #[test]
+    fn test_sampler_one_shot_ignores_retrigger() {
+        let mut sampler = ramp_sampler(4);
+        sampler.seek(3);
+        play_left(&mut sampler, 2); // Run off the end
+
+        // Triggers at its offset; the retrigger and note off are ignored
+        let events = vec![note(1.0, 1), note(1.0, 3), note(0.0, 2)];
+        assert_eq!(
+            play_events(&mut sampler, 6, events),
+            vec![0.0, 0.0, 1.0, 2.0, 3.0, 0.0]
+        );
+
+        // Done - the next trigger restarts it
+        assert_eq!(
+            play_events(&mut sampler, 2, vec![note(1.0, 0)]),
+            vec![0.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn test_sampler_gate_stops_on_release() {
+        let mut sampler = ramp_sampler(10);
+        sampler.set_parameter(PARAM_PLAYBACK_MODE, 2.0).unwrap();
+        assert_eq!(sampler.mode(), PlaybackMode::Gate);
+
+        let events = vec![
+            note(1.0, 0),
+            Event::NoteOff {
+                channel: 0,
+                note: 36,
+                sample_offset: 3,
+            },
+        ];
+        assert_eq!(
+            play_events(&mut sampler, 5, events),
+            vec![0.0, 1.0, 2.0, 0.0, 0.0]
+        );
+        // Stays silent until the next note
+        assert_eq!(play_left(&mut sampler, 2), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_sampler_loop_mode_wraps_while_held() {
+        let mut sampler = ramp_sampler(3);
+        sampler.set_mode(PlaybackMode::Loop);
+        assert_eq!(sampler.get_parameter(PARAM_PLAYBACK_MODE).unwrap(), 1.0);
+
+        assert_eq!(
+            play_events(&mut sampler, 7, vec![note(1.0, 0), note(0.0, 6)]),
+            vec![0.0, 1.0, 2.0, 0.0, 1.0, 2.0, 0.0]
+        );
+    }
+
+    /// Note on for `note` at `sample_offset`
+    fn hit(note: u8, velocity: f32, sample_offset: u32) -> Event {
+        Event::NoteOn {
+            channel: 0,
+            note,
+            velocity,
+            sample_offset,
+        }
+    }
+
+    /// Sampler holding a constant 1.0 (both channels), done with its timeline playback
+    fn pad_sampler(frames: usize, polyphony: usize) -> SamplerProcessor {
+        let mut sampler = SamplerProcessor::new(vec![1.0; frames * 2], 48000);
+        sampler.initialize(48000, 512).unwrap();
+        sampler
+            .set_parameter(PARAM_POLYPHONY, polyphony as f32)
+            .unwrap();
+        sampler.seek(frames as u64);
+        play_left(&mut sampler, 1);
+        sampler
+    }
+
+    #[test]
+    fn test_velocity_curves() {
+        assert_eq!(VelocityCurve::Linear.gain(0.5), 0.5);
+        assert!((VelocityCurve::Exponential.gain(1.0) - 1.0).abs() < 1e-6);
+        // Half velocity is half the 40 dB range down
+        assert!((VelocityCurve::Exponential.gain(0.5) - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_sampler_velocity_scales_output() {
+        let mut sampler = pad_sampler(8, 1);
+        assert_eq!(
+            play_events(&mut sampler, 2, vec![hit(36, 0.5, 0)]),
+            vec![0.5, 0.5]
+        );
+
+        sampler.set_parameter(PARAM_VELOCITY_CURVE, 1.0).unwrap();
+        assert_eq!(sampler.velocity_curve(), VelocityCurve::Exponential);
+        sampler.set_mode(PlaybackMode::Gate); // Retrigger the held voice
+        let output = play_events(&mut sampler, 1, vec![hit(36, 0.5, 0)]);
+        assert!((output[0] - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_sampler_voices_stack() {
+        let mut sampler = pad_sampler(4, 2);
+        assert_eq!(sampler.polyphony(), 2);
+
+        let events = vec![hit(36, 0.5, 0), hit(38, 0.25, 2)];
+        assert_eq!(
+            play_events(&mut sampler, 6, events),
+            vec![0.5, 0.5, 0.75, 0.75, 0.25, 0.25]
+        );
+    }
+
+    #[test]
+    fn test_sampler_steals_oldest_voice() {
+        let mut sampler = pad_sampler(8, 2);
+
+        // The third hit takes over the first (0.5) voice
+        let events = vec![hit(36, 0.5, 0), hit(38, 0.25, 1), hit(40, 0.125, 2)];
+        assert_eq!(play_events(&mut sampler, 3, events), vec![0.5, 0.75, 0.375]);
+
+        // Lowering polyphony silences the voices above the limit
+        sampler.set_polyphony(1);
+        assert_eq!(play_left(&mut sampler, 1), vec![0.125]);
+    }
 }
@@ -0,0 +1,127 @@
+//! Per-sample parameter smoothing shared by the built-in processors.
+
+use vvdaw_core::SampleRate;
+
+/// Distance from the target at which a smoothed value snaps onto it
+///
+/// A one-pole filter only approaches its target, so without this a value
+/// heading for 0.0 would crawl through denormals forever. (Values near larger
+/// targets stall on f32 rounding instead, which `next()` also catches.)
+const SNAP_DISTANCE: f32 = 1e-6;
+
+/// One-pole smoother gliding a parameter toward its target value
+///
+/// Jumping a gain straight to a new value puts a step in the waveform, heard
+/// as a click - or as "zipper noise" while a control is swept. Calling
+/// [`next`](Self::next) once per sample with the parameter's target ramps the
+/// value instead, reaching ~63% of a change after the time constant.
+///
+/// The first value after [`reset`](Self::reset) is taken as-is, since nothing
+/// has been heard yet to ramp from.
+#[derive(Debug, Clone, Copy)]
+pub struct SmoothedValue {
+    /// Time constant in seconds
+    time: f32,
+    /// Per-sample coefficient for `time` at the current sample rate
+    coefficient: f32,
+    /// Value after the last `next()` call
+    current: f32,
+    /// Whether `current` has been set since the last reset
+    primed: bool,
+}
+
+impl SmoothedValue {
+    /// Smoother with a time constant of `time` seconds
+    ///
+    /// Call [`set_sample_rate`](Self::set_sample_rate) before use - until then
+    /// every change is applied immediately.
+    pub const fn new(time: f32) -> Self {
+        Self {
+            time,
+            coefficient: 0.0,
+            current: 0.0,
+            primed: false,
+        }
+    }
+
+    /// Recompute the coefficient for `sample_rate` and [`reset`](Self::reset)
+    #[allow(clippy::cast_precision_loss)] // Sample rates are far below 2^24
+    pub fn set_sample_rate(&mut self, sample_rate: SampleRate) {
+        let samples = self.time * sample_rate as f32;
+        self.coefficient = if samples > 0.0 {
+            (-1.0 / samples).exp()
+        } else {
+            0.0
+        };
+        self.reset();
+    }
+
+    /// Jump to the next target given to [`next`](Self::next) instead of ramping
+    pub const fn reset(&mut self) {
+        self.primed = false;
+    }
+
+    /// Start ramping from `value` rather than jumping to the next target
+    pub const fn reset_to(&mut self, value: f32) {
+        self.current = value;
+        self.primed = true;
+    }
+
+    /// Value after the last [`next`](Self::next) call
+    #[cfg(test)]
+    pub const fn current(&self) -> f32 {
+        self.current
+    }
+
+    /// Advance one sample toward `target` and return the smoothed value
+    ///
+    /// REAL-TIME SAFE: Arithmetic only.
+    pub fn next(&mut self, target: f32) -> f32 {
+        if !self.primed {
+            self.primed = true;
+            self.current = target;
+            return target;
+        }
+
+        let next = (self.current - target).mul_add(self.coefficient, target);
+        // Snap once close enough, or once rounding stops the value moving
+        self.current = if (next - target).abs() < SNAP_DISTANCE || next == self.current {
+            target
+        } else {
+            next
+        };
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_value_is_not_ramped() {
+        let mut value = SmoothedValue::new(0.01);
+        value.set_sample_rate(48000);
+        assert_eq!(value.next(0.5), 0.5);
+
+        // After a reset the next target is taken as-is again
+        assert!(value.next(1.0) < 0.51);
+        value.reset();
+        assert_eq!(value.next(1.0), 1.0);
+    }
+
+    #[test]
+    fn test_ramp_follows_time_constant() {
+        let mut value = SmoothedValue::new(0.01);
+        value.set_sample_rate(1000);
+        value.next(0.0);
+
+        // 63% of the way after one time constant, settled after a few more
+        let after_time_constant = (0..10).map(|_| value.next(1.0)).last().unwrap();
+        assert!((after_time_constant - (1.0 - (-1.0_f32).exp())).abs() < 1e-3);
+        for _ in 0..200 {
+            value.next(1.0);
+        }
+        assert_eq!(value.next(1.0), 1.0);
+    }
+}
@@ -0,0 +1,519 @@
+//! Streaming sample playback - plays WAV files too large to load into memory.
+
+use hound::{SampleFormat, WavReader};
+use rtrb::{Consumer, Producer, RingBuffer};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use vvdaw_core::SampleRate;
+use vvdaw_plugin::{AudioBuffer, EventBuffer, Plugin, PluginError, PluginInfo};
+
+/// Files larger than this should be streamed rather than loaded into a
+/// [`super::sampler::SamplerProcessor`]
+pub const STREAMING_THRESHOLD_BYTES: u64 = 500 * 1024 * 1024;
+
+/// Frames per chunk handed from the reader thread to the audio thread
+const CHUNK_FRAMES: usize = 1024;
+
+/// Chunks buffered ahead of the playhead (~1.4s at 48kHz)
+const RING_CHUNKS: usize = 64;
+
+/// How long the reader thread sleeps when the buffer is full or the file ended
+const READER_IDLE: Duration = Duration::from_millis(2);
+
+/// A block of decoded stereo audio
+struct Chunk {
+    /// Seek generation the chunk was read for (stale chunks are discarded)
+    generation: u64,
+    /// Valid frames in `samples`
+    frames: usize,
+    /// Interleaved stereo [L, R, L, R, ...]
+    samples: [f32; CHUNK_FRAMES * 2],
+}
+
+impl Chunk {
+    const fn empty(generation: u64) -> Self {
+        Self {
+            generation,
+            frames: 0,
+            samples: [0.0; CHUNK_FRAMES * 2],
+        }
+    }
+}
+
+/// Seek requests from the audio thread to the reader thread
+struct SeekRequest {
+    /// Bumped on every seek; the reader repositions when it changes
+    generation: AtomicU64,
+    /// Frame to read from for the current generation
+    frame: AtomicU64,
+}
+
+/// Streaming sample playback processor
+///
+/// Plays a WAV file straight from disk: a background thread keeps a
+/// `hound::WavReader` open and decodes ahead of the playhead into a lock-free
+/// ring buffer, so memory use stays constant however long the file is. Mono
+/// files are played on both channels, and files with more than two channels
+/// play their first two.
+///
+/// Seeking (see [`Plugin::seek`]) repositions the reader; playback resumes as
+/// soon as the first chunk from the new position arrives. If the reader falls
+/// behind, the sampler outputs silence and counts an underrun (see
+/// [`StreamingSampler::underruns`]).
+///
+/// Unlike [`super::sampler::SamplerProcessor`], scrubbing and loop regions
+/// are not supported, and the file is not resampled - it should already be at
+/// the engine's sample rate.
+///
+/// # Real-Time Safety
+///
+/// `process()` and `seek()` only pop from (or drain) the ring buffer and store
+/// atomics. The reader thread exits on its own once the sampler is dropped,
+/// so dropping it never joins a thread.
+pub struct StreamingSampler {
+    /// Decoded chunks from the reader thread
+    consumer: Consumer<Chunk>,
+    /// Shared with the reader thread
+    seek_request: Arc<SeekRequest>,
+    /// Chunk currently being played
+    chunk: Chunk,
+    /// Next frame to play within `chunk`
+    chunk_index: usize,
+    /// Current seek generation
+    generation: u64,
+    /// Frame of the file that plays next
+    position: u64,
+    /// Total frames in the file
+    frame_count: u64,
+    /// Blocks that ran out of buffered audio before the end of the file
+    underruns: u64,
+    /// Sample rate of the file
+    audio_sample_rate: SampleRate,
+    /// Plugin info
+    info: PluginInfo,
+}
+
+impl StreamingSampler {
+    /// Open a WAV file and start streaming it from the beginning
+    ///
+    /// Spawns the reader thread, which starts filling the buffer immediately.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, PluginError> {
+        let path = path.as_ref();
+        let reader = WavReader::open(path)
+            .map_err(|e| PluginError::FormatError(crate::wav_progress::wav_open_error(e)))?;
+
+        let spec = reader.spec();
+        if spec.channels == 0 {
+            return Err(PluginError::FormatError(
+                "WAV file has no channels".to_string(),
+            ));
+        }
+        if spec.sample_format == SampleFormat::Int
+            && (spec.bits_per_sample == 0 || spec.bits_per_sample > 32)
+        {
+            return Err(PluginError::FormatError(format!(
+                "Unsupported bit depth: {} bits (supported: 1-32)",
+                spec.bits_per_sample
+            )));
+        }
+
+        let frame_count = u64::from(reader.duration());
+        tracing::info!(
+            "Streaming {} ({frame_count} frames, {} channels, {}Hz)",
+            path.display(),
+            spec.channels,
+            spec.sample_rate
+        );
+
+        let (producer, consumer) = RingBuffer::new(RING_CHUNKS);
+        let seek_request = Arc::new(SeekRequest {
+            generation: AtomicU64::new(0),
+            frame: AtomicU64::new(0),
+        });
+
+        let thread_request = Arc::clone(&seek_request);
+        std::thread::Builder::new()
+            .name("vvdaw-wav-stream".to_string())
+            .spawn(move || read_loop(reader, producer, &thread_request))
+            .map_err(|e| {
+                PluginError::InitializationFailed(format!("Failed to spawn reader thread: {e}"))
+            })?;
+
+        Ok(Self {
+            consumer,
+            seek_request,
+            chunk: Chunk::empty(0),
+            chunk_index: 0,
+            generation: 0,
+            position: 0,
+            frame_count,
+            underruns: 0,
+            audio_sample_rate: spec.sample_rate,
+            info: PluginInfo {
+                name: "Streaming Sampler".to_string(),
+                vendor: "vvdaw".to_string(),
+                version: "1.0.0".to_string(),
+                unique_id: "vvdaw.builtin.streaming_sampler".to_string(),
+                category: None,
+            },
+        })
+    }
+
+    /// Total frames in the file
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// Sample rate of the file
+    pub fn sample_rate(&self) -> SampleRate {
+        self.audio_sample_rate
+    }
+
+    /// Number of blocks where the reader fell behind and silence was played
+    pub fn underruns(&self) -> u64 {
+        self.underruns
+    }
+
+    /// Next stereo frame, or `None` if nothing is buffered
+    fn next_frame(&mut self) -> Option<(f32, f32)> {
+        while self.chunk_index >= self.chunk.frames {
+            let chunk = self.consumer.pop().ok()?;
+            if chunk.generation == self.generation {
+                self.chunk = chunk;
+                self.chunk_index = 0;
+            }
+        }
+
+        let index = self.chunk_index * 2;
+        self.chunk_index += 1;
+        Some((self.chunk.samples[index], self.chunk.samples[index + 1]))
+    }
+}
+
+impl Plugin for StreamingSampler {
+    fn info(&self) -> &PluginInfo {
+        &self.info
+    }
+
+    fn initialize(
+        &mut self,
+        sample_rate: SampleRate,
+        _max_block_size: usize,
+    ) -> Result<(), PluginError> {
+        if self.audio_sample_rate != sample_rate {
+            tracing::warn!(
+                "Sample rate mismatch: streamed audio is {}Hz, engine is {}Hz. Playback speed will be incorrect.",
+                self.audio_sample_rate,
+                sample_rate
+            );
+        }
+        Ok(())
+    }
+
+    fn input_channels(&self) -> usize {
+        0
+    }
+
+    fn output_channels(&self) -> usize {
+        2
+    }
+
+    fn process(
+        &mut self,
+        audio: &mut AudioBuffer,
+        _events: &EventBuffer,
+    ) -> Result<(), PluginError> {
+        if audio.outputs.len() != 2 {
+            return Err(PluginError::ProcessingFailed(format!(
+                "Streaming sampler requires exactly 2 outputs (stereo), got {}",
+                audio.outputs.len()
+            )));
+        }
+
+        let mut starved = false;
+        for i in 0..audio.frames {
+            let frame = if self.position < self.frame_count {
+                self.next_frame()
+            } else {
+                None
+            };
+
+            if let Some((left, right)) = frame {
+                audio.outputs[0][i] = left;
+                audio.outputs[1][i] = right;
+                self.position += 1;
+            } else {
+                starved |= self.position < self.frame_count;
+                audio.outputs[0][i] = 0.0;
+                audio.outputs[1][i] = 0.0;
+            }
+        }
+
+        if starved {
+            self.underruns += 1;
+        }
+
+        Ok(())
+    }
+
+    fn get_parameter(&self, id: u32) -> Result<f32, PluginError> {
+        Err(PluginError::InvalidParameter(format!(
+            "Streaming sampler has no parameter with id {id}"
+        )))
+    }
+
+    fn set_parameter(&mut self, id: u32, _value: f32) -> Result<(), PluginError> {
+        Err(PluginError::InvalidParameter(format!(
+            "Streaming sampler has no parameter with id {id}"
+        )))
+    }
+
+    fn parameters(&self) -> Vec<vvdaw_plugin::ParameterInfo> {
+        vec![]
+    }
+
+    fn deactivate(&mut self) {
+        // Rewind, like SamplerProcessor
+        self.seek(0);
+    }
+
+    fn seek(&mut self, frame: u64) -> Option<u64> {
+        // Clamp past-the-end seeks to the last frame
+        let landed = frame.min(self.frame_count.saturating_sub(1));
+
+        self.generation += 1;
+        self.seek_request.frame.store(landed, Ordering::Release);
+        self.seek_request
+            .generation
+            .store(self.generation, Ordering::Release);
+
+        // Drop everything buffered for the old position (no copies)
+        if let Ok(stale) = self.consumer.read_chunk(self.consumer.slots()) {
+            stale.commit_all();
+        }
+        self.chunk_index = self.chunk.frames;
+        self.position = landed;
+
+        Some(landed)
+    }
+}
+
+/// Reader thread: decode chunks ahead of the playhead until the sampler is dropped
+fn read_loop(
+    mut reader: WavReader<BufReader<File>>,
+    mut producer: Producer<Chunk>,
+    seek_request: &SeekRequest,
+) {
+    let spec = reader.spec();
+    let channels = usize::from(spec.channels);
+    // 8-bit WAV is unsigned, but hound shifts it to signed (-128..127), so
+    // every integer depth scales by 2^(bits - 1)
+    let scale = 1.0 / 2.0_f32.powi(i32::from(spec.bits_per_sample) - 1);
+
+    let mut generation = 0;
+    let mut at_end = false;
+
+    while !producer.is_abandoned() {
+        let requested = seek_request.generation.load(Ordering::Acquire);
+        if requested != generation {
+            generation = requested;
+            let frame = seek_request.frame.load(Ordering::Acquire);
+            at_end = !matches!(
+                u32::try_from(frame).map(|frame| reader.seek(frame)),
+                Ok(Ok(()))
+            );
+            if at_end {
+                tracing::error!("Failed to seek streamed WAV to frame {frame}");
+            }
+        }
+
+        if at_end || producer.is_full() {
+            std::thread::sleep(READER_IDLE);
+            continue;
+        }
+
+        let mut chunk = Chunk::empty(generation);
+        let result = match spec.sample_format {
+            SampleFormat::Float => fill_chunk(reader.samples::<f32>(), channels, &mut chunk),
+            SampleFormat::Int => fill_chunk(
+                reader
+                    .samples::<i32>()
+                    .map(|sample| sample.map(|value| value as f32 * scale)),
+                channels,
+                &mut chunk,
+            ),
+        };
+
+        if let Err(e) = result {
+            tracing::error!("Failed to read streamed WAV: {e}");
+            at_end = true;
+        } else if chunk.frames < CHUNK_FRAMES {
+            at_end = true;
+        }
+
+        if chunk.frames > 0 {
+            // Can't fail: only this thread pushes, and we checked for space
+            let _ = producer.push(chunk);
+        }
+    }
+}
+
+/// Decode up to `CHUNK_FRAMES` frames into `chunk`, mapping channels to stereo
+fn fill_chunk(
+    samples: impl Iterator<Item = Result<f32, hound::Error>>,
+    channels: usize,
+    chunk: &mut Chunk,
+) -> Result<(), hound::Error> {
+    for (i, sample) in samples.take(CHUNK_FRAMES * channels).enumerate() {
+        let value = sample?;
+        let (frame, channel) = (i / channels, i % channels);
+        match (channels, channel) {
+            // Mono: same signal on both sides
+            (1, _) => {
+                chunk.samples[frame * 2] = value;
+                chunk.samples[frame * 2 + 1] = value;
+            }
+            (_, 0 | 1) => chunk.samples[frame * 2 + channel] = value,
+            _ => {}
+        }
+        if channel == channels - 1 {
+            chunk.frames = frame + 1;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    /// Write a WAV whose frame `n` is `(n, -n) / 32768` at 16 bits
+    fn write_ramp(path: &Path, frames: usize, channels: u16) {
+        let spec = hound::WavSpec {
+            channels,
+            sample_rate: 48000,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+        for n in 0..frames {
+            writer.write_sample(n as i16).unwrap();
+            if channels > 1 {
+                writer.write_sample(-(n as i16)).unwrap();
+            }
+        }
+        writer.finalize().unwrap();
+    }
+
+    /// Wait until the reader thread has buffered `chunks` chunks for the current position
+    fn wait_for_buffer(sampler: &mut StreamingSampler, chunks: usize) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            // Discard chunks read before the last seek, as process() would
+            while sampler
+                .consumer
+                .peek()
+                .is_ok_and(|chunk| chunk.generation != sampler.generation)
+            {
+                let _ = sampler.consumer.pop();
+            }
+            if sampler.consumer.slots() >= chunks {
+                return;
+            }
+            assert!(
+                Instant::now() < deadline,
+                "reader thread never filled buffer"
+            );
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    /// Process `frames` frames and return both channels scaled back to frame numbers
+    fn play(sampler: &mut StreamingSampler, frames: usize) -> (Vec<f32>, Vec<f32>) {
+        let mut output_l = vec![999.0; frames];
+        let mut output_r = vec![999.0; frames];
+        let mut audio = AudioBuffer {
+            inputs: &[],
+            outputs: &mut [&mut output_l, &mut output_r],
+            frames,
+        };
+        sampler
+            .process(&mut audio, &EventBuffer::default())
+            .unwrap();
+
+        let to_frames = |channel: Vec<f32>| channel.iter().map(|s| s * 32768.0).collect();
+        (to_frames(output_l), to_frames(output_r))
+    }
+
+    #[test]
+    fn test_streaming_plays_across_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ramp.wav");
+        write_ramp(&path, 3000, 2);
+
+        let mut sampler = StreamingSampler::open(&path).unwrap();
+        sampler.initialize(48000, 512).unwrap();
+        assert_eq!(sampler.frame_count(), 3000);
+        wait_for_buffer(&mut sampler, 3);
+
+        let expected: Vec<f32> = (0..3000).map(|n| n as f32).collect();
+        let mut left = Vec::new();
+        for _ in 0..6 {
+            let (block_l, block_r) = play(&mut sampler, 500);
+            assert_eq!(block_r[0], -block_l[0]);
+            left.extend(block_l);
+        }
+        assert_eq!(left, expected);
+        assert_eq!(sampler.underruns(), 0);
+
+        // Silence after the end, without counting underruns
+        let (tail, _) = play(&mut sampler, 10);
+        assert!(tail.iter().all(|&s| s == 0.0));
+        assert_eq!(sampler.underruns(), 0);
+    }
+
+    #[test]
+    fn test_streaming_seek_repositions_reader() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ramp.wav");
+        write_ramp(&path, 5000, 2);
+
+        let mut sampler = StreamingSampler::open(&path).unwrap();
+        wait_for_buffer(&mut sampler, 1);
+        play(&mut sampler, 100);
+
+        assert_eq!(sampler.seek(4321), Some(4321));
+        wait_for_buffer(&mut sampler, 1);
+        let (left, _) = play(&mut sampler, 4);
+        assert_eq!(left, vec![4321.0, 4322.0, 4323.0, 4324.0]);
+
+        // Past-the-end seeks land on the last frame
+        assert_eq!(sampler.seek(1_000_000), Some(4999));
+        wait_for_buffer(&mut sampler, 1);
+        let (left, _) = play(&mut sampler, 2);
+        assert_eq!(left, vec![4999.0, 0.0]);
+    }
+
+    #[test]
+    fn test_streaming_mono_plays_on_both_channels() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mono.wav");
+        write_ramp(&path, 10, 1);
+
+        let mut sampler = StreamingSampler::open(&path).unwrap();
+        wait_for_buffer(&mut sampler, 1);
+        let (left, right) = play(&mut sampler, 3);
+        assert_eq!(left, vec![0.0, 1.0, 2.0]);
+        assert_eq!(right, left);
+    }
+
+    #[test]
+    fn test_streaming_missing_file() {
+        assert!(StreamingSampler::open("/nonexistent/file.wav").is_err());
+    }
+}
@@ -1,16 +1,154 @@
 //! Audio engine - manages audio thread and cpal integration.
 
+use crate::fade::SoftMute;
+use crate::graph::{MAX_BLOCK_SIZE, stereo_correlation};
+use crate::input::LiveInput;
+use crate::load::{CallbackWatchdog, DspLoad};
+use crate::recorder::{RECORD_BUFFER_SECONDS, Recorder};
+use crate::scope::ScopeTap;
+use crate::spectrum::SpectrumTap;
 use crate::{AudioConfig, AudioGraph};
 use anyhow::{Context, Result};
 use cpal::Stream;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use smallvec::SmallVec;
-use vvdaw_comms::{AudioChannels, AudioCommand, AudioEvent};
+use std::ops::RangeInclusive;
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use vvdaw_comms::{AudioChannels, AudioCommand, AudioEvent, DEFAULT_SCOPE_DECIMATION};
+use vvdaw_core::{Frames, SampleRate, Transport};
+use vvdaw_plugin::EventBuffer;
+
+/// Length of audio played for each `Scrub` command
+///
+/// Long enough to bridge the gap between UI frames (~16ms at 60fps) so
+/// continuous scrubbing is gapless, short enough to fall silent promptly
+/// when scrubbing stops.
+const SCRUB_WINDOW_SECONDS: f64 = 0.05;
+
+/// Sample rates offered by [`AudioEngine::supported_configs`]
+///
+/// Devices report continuous ranges; these are the common rates picked out of
+/// them (plus each range's own bounds).
+const STANDARD_SAMPLE_RATES: [SampleRate; 6] = [44100, 48000, 88200, 96000, 176_400, 192_000];
+
+/// How long [`AudioEngine::stop`] waits for the stream thread to finish
+///
+/// Closing an audio device can block in the driver. Past this we give up and
+/// leave the thread behind rather than hang the caller (e.g. app exit).
+pub const STOP_TIMEOUT: Duration = Duration::from_secs(2);
 
 /// The audio engine manages the audio thread and cpal stream
+///
+/// The engine has no UI dependencies - any thread holding the other ends of
+/// its channels can drive it, so it embeds in CLI tools and tests as easily as
+/// in the Bevy apps. See `examples/headless.rs` for a complete host.
+///
+/// # Channel contract
+///
+/// Create the channels with [`vvdaw_comms::create_channels`] and hand the
+/// [`AudioChannels`] half to [`AudioEngine::start`]. The [`vvdaw_comms::UiChannels`]
+/// half stays with the controlling thread, which:
+///
+/// - **Sends commands** on `command_tx`. They are applied at the start of the
+///   next audio callback, in order. Commands that allocate (adding, removing
+///   or reconnecting nodes, ...) are refused with [`AudioEvent::Error`] while
+///   playing - send `Stop` first.
+/// - **Sends plugins** on `plugin_tx`, each paired with the command that
+///   consumes it (`AddNode`, `ReplaceNode`, `ReloadNode`). Use
+///   [`vvdaw_comms::send_node`]/[`vvdaw_comms::send_reload`] so a plugin is
+///   never queued without its command.
+/// - **Drains events** from `event_rx` regularly. The queue is bounded and the
+///   audio thread never waits: when it is full, new events are dropped.
+///   [`AudioEvent::EngineInitialized`] arrives first, with the sample rate and
+///   channel count actually in use.
+/// - **Reads the scope** from `scope_rx` whenever it likes (optional).
+///
+/// To shut down, send [`AudioCommand::Shutdown`], wait for its
+/// [`AudioEvent::Stopped`] ack, then call [`AudioEngine::stop`] (dropping the
+/// engine also stops it).
 pub struct AudioEngine {
     config: AudioConfig,
-    stream: Option<Stream>,
+    stream_thread: Option<StreamThread>,
+}
+
+/// Thread owning the running cpal stream
+struct StreamThread {
+    /// Signals the thread to stop and drop the stream
+    stop_tx: mpsc::SyncSender<StreamControl>,
+    handle: JoinHandle<()>,
+}
+
+/// Request to the stream thread
+enum StreamControl {
+    /// Close the stream and exit
+    Stop,
+    /// Close the stream and reopen it with a new sample rate and block size
+    ///
+    /// Sent by the audio callback for [`AudioCommand::SetConfig`], handing
+    /// back everything the next stream needs.
+    Restart {
+        sample_rate: SampleRate,
+        block_size: Frames,
+        state: Box<StreamState>,
+    },
+}
+
+/// Everything that outlives a single cpal stream
+///
+/// Owned by the audio callback while its stream runs, and handed back to the
+/// stream thread (boxed, so it's moved rather than freed on the audio thread)
+/// when the stream is reopened with new settings.
+struct StreamState {
+    channels: AudioChannels,
+    graph: AudioGraph,
+    recorder: Recorder,
+    spectrum: SpectrumTap,
+    scope: ScopeTap,
+    /// Where the next stream picks up playback
+    resume: Resume,
+}
+
+impl StreamState {
+    fn new(
+        config: &AudioConfig,
+        channels: AudioChannels,
+        recorder: Recorder,
+        spectrum: SpectrumTap,
+    ) -> Self {
+        let mut graph = AudioGraph::with_config(config.sample_rate, config.block_size);
+        graph.set_double_precision(config.double_precision);
+
+        Self {
+            channels,
+            graph,
+            recorder,
+            spectrum,
+            // Decimated copy of the output for the UI's oscilloscope
+            scope: ScopeTap::new(DEFAULT_SCOPE_DECIMATION),
+            resume: Resume {
+                transport: Transport::new(config.sample_rate),
+                frame_position: 0,
+                playback_rate: 1.0,
+                loop_region: None,
+                graph_bypassed: false,
+                input_monitor: false,
+            },
+        }
+    }
+}
+
+/// Playback state carried from one stream to the next
+#[derive(Debug, Clone, Copy)]
+struct Resume {
+    /// Tempo, musical position and whether playback is running
+    transport: Transport,
+    frame_position: u64,
+    playback_rate: f64,
+    loop_region: Option<(u64, u64)>,
+    graph_bypassed: bool,
+    input_monitor: bool,
 }
 
 impl AudioEngine {
@@ -18,64 +156,306 @@ impl AudioEngine {
     pub fn new(config: AudioConfig) -> Self {
         Self {
             config,
-            stream: None,
+            stream_thread: None,
+        }
+    }
+
+    /// Names of the available output devices, for `AudioConfig::device_name`
+    ///
+    /// Empty if the host can't enumerate its devices.
+    pub fn list_devices() -> Vec<String> {
+        let host = cpal::default_host();
+        match host.output_devices() {
+            Ok(devices) => devices.filter_map(|device| device.name().ok()).collect(),
+            Err(e) => {
+                tracing::warn!("Failed to enumerate output devices: {e}");
+                Vec::new()
+            }
         }
     }
 
     /// Start the audio engine with the provided communication channels
-    #[allow(clippy::too_many_lines)] // Audio callback is complex by nature
-    pub fn start(&mut self, mut channels: AudioChannels) -> Result<()> {
+    ///
+    /// The cpal stream is built and owned by a dedicated thread (streams aren't
+    /// `Send` on every platform), which keeps it alive until [`AudioEngine::stop`]
+    /// and reopens it for [`AudioCommand::SetConfig`].
+    pub fn start(&mut self, channels: AudioChannels) -> Result<()> {
         tracing::info!("Audio engine starting with config: {:?}", self.config);
 
+        let mut config = self.config.clone();
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<()>>();
+        // Room for a restart request from the callback plus stop()
+        let (stop_tx, control_rx) = mpsc::sync_channel::<StreamControl>(2);
+        let control_tx = stop_tx.clone();
+
+        let handle = std::thread::Builder::new()
+            .name("vvdaw-audio-stream".to_string())
+            .spawn(move || {
+                let buffer_samples =
+                    config.sample_rate as usize * config.output_channels * RECORD_BUFFER_SECONDS;
+                let (recorder, recorder_thread) = match Recorder::spawn(buffer_samples) {
+                    Ok(recorder) => recorder,
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e));
+                        return;
+                    }
+                };
+                let (spectrum, spectrum_thread) = match SpectrumTap::spawn() {
+                    Ok(spectrum) => spectrum,
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e));
+                        return;
+                    }
+                };
+                let state = Box::new(StreamState::new(&config, channels, recorder, spectrum));
+                let mut streams = match Self::build_stream(&config, state, control_tx.clone()) {
+                    Ok(streams) => Some(streams),
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e));
+                        return;
+                    }
+                };
+                let _ = ready_tx.send(Ok(()));
+                tracing::info!("Audio stream started");
+
+                // Park until stop() is requested, reopening the stream whenever
+                // the callback hands its state back for new settings
+                while let Ok(StreamControl::Restart {
+                    sample_rate,
+                    block_size,
+                    mut state,
+                }) = control_rx.recv()
+                {
+                    close_streams(streams.take());
+                    config = Self::reconfigure(&config, sample_rate, block_size, &mut state);
+                    match Self::build_stream(&config, state, control_tx.clone()) {
+                        Ok(reopened) => {
+                            streams = Some(reopened);
+                            tracing::info!("Audio stream restarted");
+                        }
+                        Err(e) => {
+                            // The channels went down with the failed stream
+                            tracing::error!("Failed to reopen audio stream: {e:#}");
+                            break;
+                        }
+                    }
+                }
+
+                // Dropping the stream drops the callback and its graph, so
+                // plugins are deactivated and released here
+                close_streams(streams.take());
+                // The recorder went with the callback - wait for it to finish the file
+                if recorder_thread.join().is_err() {
+                    tracing::error!("Recorder thread panicked");
+                }
+                if spectrum_thread.join().is_err() {
+                    tracing::error!("Spectrum analyzer thread panicked");
+                }
+                tracing::info!("Audio stream stopped");
+            })
+            .context("Failed to spawn audio stream thread")?;
+
+        ready_rx
+            .recv()
+            .context("Audio stream thread exited during startup")??;
+
+        self.stream_thread = Some(StreamThread { stop_tx, handle });
+        Ok(())
+    }
+
+    /// Apply a [`AudioCommand::SetConfig`] request to the graph
+    ///
+    /// Returns the config to reopen the stream with: the requested one, or
+    /// `config` unchanged (with the reason reported) if the device can't run
+    /// it. Plugins that fail to reinitialize are reported by node ID.
+    fn reconfigure(
+        config: &AudioConfig,
+        sample_rate: SampleRate,
+        block_size: Frames,
+        state: &mut StreamState,
+    ) -> AudioConfig {
+        let requested = AudioConfig {
+            sample_rate,
+            block_size,
+            ..config.clone()
+        };
+        if let Err(reason) = Self::check_config(&requested) {
+            let _ = state.channels.event_tx.push(AudioEvent::Error(format!(
+                "Cannot switch to {sample_rate}Hz with {block_size} frames: {reason}"
+            )));
+            return config.clone();
+        }
+
+        tracing::info!("Switching to {sample_rate}Hz with {block_size} frames");
+        for (node_id, e) in state.graph.set_config(sample_rate, block_size) {
+            let _ = state.channels.event_tx.push(AudioEvent::Error(format!(
+                "Node {node_id} failed to reinitialize at {sample_rate}Hz: {e}"
+            )));
+        }
+
+        // The analyzer's hop and the musical position both depend on the rate
+        let spectrum_config = state.spectrum.config();
+        state.spectrum.configure(spectrum_config, sample_rate);
+        let transport = &mut state.resume.transport;
+        transport.sample_rate = sample_rate;
+        transport.locate(transport.project_time_samples);
+
+        requested
+    }
+
+    /// Why the engine can't run `config` on its output device, if it can't
+    fn check_config(config: &AudioConfig) -> Result<(), String> {
+        if config.sample_rate == 0 {
+            return Err("the sample rate must be above 0".to_string());
+        }
+        if config.block_size == 0 || config.block_size > MAX_BLOCK_SIZE {
+            return Err(format!(
+                "the block size must be between 1 and {MAX_BLOCK_SIZE} frames"
+            ));
+        }
+
+        let host = cpal::default_host();
+        let device = Self::select_output_device(&host, config.device_name.as_deref())
+            .map_err(|e| e.to_string())?;
+        let device_name = device.name().map_err(|e| e.to_string())?;
+        check_device(&device, &device_name, config)
+    }
+
+    /// Configurations the selected output device can run
+    ///
+    /// One entry per supported sample rate and channel count, based on this
+    /// engine's config (device, priority, ...). `block_size_range` holds the
+    /// device's buffer size limits, and `block_size` is the configured size
+    /// clamped into them. Empty if the device can't be opened or queried.
+    pub fn supported_configs(&self) -> Vec<AudioConfig> {
+        let host = cpal::default_host();
+        let device = match Self::select_output_device(&host, self.config.device_name.as_deref()) {
+            Ok(device) => device,
+            Err(e) => {
+                tracing::warn!("Cannot list supported configs: {e}");
+                return Vec::new();
+            }
+        };
+
+        match device.supported_output_configs() {
+            Ok(ranges) => configs_from_ranges(&self.config, &ranges.collect::<Vec<_>>()),
+            Err(e) => {
+                tracing::warn!("Cannot list supported configs: {e}");
+                Vec::new()
+            }
+        }
+    }
+
+    /// The output device called `name`, or the host's default device
+    ///
+    /// Falls back to the default (with a warning) when no device has that name.
+    fn select_output_device(host: &cpal::Host, name: Option<&str>) -> Result<cpal::Device> {
+        if let Some(device) = name.and_then(|name| find_device(host.output_devices(), name)) {
+            return Ok(device);
+        }
+        host.default_output_device()
+            .context("No output device available")
+    }
+
+    /// The input device called `name`, or the host's default input device
+    ///
+    /// Falls back to the default (with a warning) when no device has that name.
+    fn select_input_device(host: &cpal::Host, name: Option<&str>) -> Result<cpal::Device> {
+        if let Some(device) = name.and_then(|name| find_device(host.input_devices(), name)) {
+            return Ok(device);
+        }
+        host.default_input_device()
+            .context("No input device available")
+    }
+
+    /// Build the live input stream and start it capturing
+    ///
+    /// Runs separately from the output stream (the devices may differ), with a
+    /// ring buffer carrying the captured audio across. Returns `None` when
+    /// `input_channels` is 0.
+    fn build_input_stream(
+        host: &cpal::Host,
+        config: &AudioConfig,
+        sample_rate: SampleRate,
+    ) -> Result<Option<(Stream, LiveInput)>> {
+        if config.input_channels == 0 {
+            return Ok(None);
+        }
+
+        let device = Self::select_input_device(host, config.input_device_name.as_deref())?;
+        tracing::info!("Using input device: {}", device.name()?);
+
+        // The ring absorbs differences in block size, so take the device's default
+        let stream_config = cpal::StreamConfig {
+            channels: config.input_channels as u16,
+            sample_rate: cpal::SampleRate(sample_rate),
+            buffer_size: cpal::BufferSize::Default,
+        };
+
+        let (mut capture, live) =
+            crate::input::live_input(config.input_channels, config.block_size);
+        let stream = device.build_input_stream(
+            &stream_config,
+            move |data: &[f32], _: &cpal::InputCallbackInfo| capture.push_block(data),
+            move |err| {
+                tracing::error!("Audio input stream error: {}", err);
+            },
+            None,
+        )?;
+        stream.play()?;
+
+        Ok(Some((stream, live)))
+    }
+
+    /// Build the cpal output stream (and live input stream, if any) and start them
+    #[allow(clippy::too_many_lines)] // Audio callback is complex by nature
+    fn build_stream(
+        config: &AudioConfig,
+        mut state: Box<StreamState>,
+        control_tx: mpsc::SyncSender<StreamControl>,
+    ) -> Result<(Stream, Option<Stream>)> {
+        anyhow::ensure!(
+            config.output_channels > 0,
+            "AudioConfig::output_channels must be at least 1"
+        );
+
         // Get the default host
         let host = cpal::default_host();
         tracing::debug!("Using audio host: {}", host.id().name());
 
-        // Get the default output device
-        let device = host
-            .default_output_device()
-            .context("No output device available")?;
-        tracing::info!("Using output device: {}", device.name()?);
-
-        // Get the device's default config to see what sample rate it actually supports
-        let device_config = device
-            .default_output_config()
-            .context("Failed to get default output config")?;
-        let device_sample_rate = device_config.sample_rate().0;
-
-        tracing::info!(
-            "Device default sample rate: {}Hz (requested: {}Hz)",
-            device_sample_rate,
-            self.config.sample_rate
-        );
+        let device = Self::select_output_device(&host, config.device_name.as_deref())?;
+        let device_name = device.name()?;
+        tracing::info!("Using output device: {}", device_name);
 
-        // Use the device's sample rate if it differs from our request
-        let actual_sample_rate = if device_sample_rate == self.config.sample_rate {
-            self.config.sample_rate
-        } else {
-            tracing::warn!(
-                "Using device sample rate {}Hz instead of requested {}Hz to avoid resampling issues",
-                device_sample_rate,
-                self.config.sample_rate
-            );
-            device_sample_rate
-        };
+        // Refuse configurations the device can't run rather than silently
+        // substituting its defaults
+        if let Err(message) = check_device(&device, &device_name, config) {
+            let _ = state
+                .channels
+                .event_tx
+                .push(AudioEvent::Error(message.clone()));
+            anyhow::bail!(message);
+        }
+        let actual_sample_rate = config.sample_rate;
 
         // Configure the output stream with the actual sample rate
-        let config = cpal::StreamConfig {
-            channels: self.config.output_channels as u16,
+        let stream_config = cpal::StreamConfig {
+            channels: config.output_channels as u16,
             sample_rate: cpal::SampleRate(actual_sample_rate),
-            buffer_size: cpal::BufferSize::Fixed(self.config.block_size as u32),
+            buffer_size: cpal::BufferSize::Fixed(config.block_size as u32),
         };
 
-        tracing::info!("Final stream config: {:?}", config);
+        tracing::info!("Final stream config: {:?}", stream_config);
 
         // Send EngineInitialized event to UI with actual sample rate
-        // This must happen BEFORE channels is moved into the audio callback closure
-        if channels
+        // This must happen BEFORE the state is moved into the audio callback closure
+        if state
+            .channels
             .event_tx
             .push(AudioEvent::EngineInitialized {
                 sample_rate: actual_sample_rate,
+                output_channels: config.output_channels,
+                device_name,
             })
             .is_err()
         {
@@ -85,22 +465,91 @@ impl AudioEngine {
             );
         }
 
-        // Create the audio graph with proper configuration
-        let mut graph = AudioGraph::with_config(config.sample_rate.0, self.config.block_size);
+        // Live input runs on its own stream. Playback works without it, so a
+        // device that can't capture is reported but not fatal.
+        let (input_stream, mut live_input) =
+            match Self::build_input_stream(&host, config, actual_sample_rate) {
+                Ok(Some((stream, input))) => (Some(stream), Some(input)),
+                Ok(None) => (None, None),
+                Err(e) => {
+                    tracing::warn!("Live input unavailable: {e:#}");
+                    let _ = state
+                        .channels
+                        .event_tx
+                        .push(AudioEvent::Error(format!("Live input unavailable: {e:#}")));
+                    (None, None)
+                }
+            };
+
+        // Pick up where the previous stream (if any) left off
+        let resume = state.resume;
+
+        // Set by SetInputMonitor - live input is fed to the graph while playing
+        let mut input_monitor = resume.input_monitor;
+
+        // Callback health reported to the UI
+        let mut watchdog = CallbackWatchdog::new();
+        let mut dsp_load = DspLoad::new();
+        let mut xrun_count: u64 = 0;
+        let seconds_per_frame = 1.0 / f64::from(actual_sample_rate);
 
         // Flag to track if we're running
-        let mut is_running = false;
+        let mut is_running = resume.transport.is_playing;
+
+        // Fades the output in on Start and out on Stop, so neither clicks
+        let mut soft_mute = SoftMute::new(actual_sample_rate);
+        if is_running {
+            soft_mute.fade_in();
+        }
+
+        // Set by Shutdown - the callback outputs silence from then on
+        let mut shut_down = false;
 
         // Frame position counter for waveform synchronization
-        let mut frame_position: u64 = 0;
+        let mut frame_position = resume.frame_position;
+
+        // Varispeed rate of the samplers the position follows, and the
+        // sub-frame remainder it leaves between buffers
+        let mut playback_rate = resume.playback_rate;
+        let mut position_fraction: f64 = 0.0;
+
+        // Tempo and musical position handed to plugins, kept in step with frame_position
+        let mut transport = resume.transport;
+
+        // Live playback has no note source yet - Vec::new() doesn't allocate
+        let no_events = EventBuffer::new();
+
+        // Active loop region, mirrored from the graph so the waveform position
+        // wraps along with the sampler's playhead
+        let mut loop_region = resume.loop_region;
+
+        // Set by SetGraphBypass - input goes straight to the output, skipping the graph
+        let mut graph_bypassed = resume.graph_bypassed;
+
+        // Graph latency last reported to the UI
+        let mut reported_latency: usize = 0;
+
+        // Frames left to process for the current scrub window (processed even when stopped)
+        let mut scrub_frames_remaining: usize = 0;
+        let scrub_window = (f64::from(actual_sample_rate) * SCRUB_WINDOW_SECONDS) as usize;
+
+        // Real-time scheduling and CPU affinity must be requested from the callback
+        // thread itself, since cpal owns that thread. Done once, on the first callback.
+        let mut realtime_pending = config.realtime_priority;
+        let realtime_block_size = config.block_size;
+        let mut affinity_pending = config.cpu_affinity;
 
         // Pre-allocate de-interleaved buffers for audio processing
         // IMPORTANT: Pre-allocated to max block size to avoid allocations in audio callback
-        let num_channels = config.channels as usize;
-        let max_frames = self.config.block_size;
+        let num_channels = stream_config.channels as usize;
+        let max_frames = config.block_size;
         let mut channel_buffers_in: Vec<Vec<f32>> = vec![vec![0.0; max_frames]; num_channels];
         let mut channel_buffers_out: Vec<Vec<f32>> = vec![vec![0.0; max_frames]; num_channels];
 
+        // Taken back out by SetConfig, leaving the callback silent until the
+        // stream thread drops it
+        let mut handoff = Some(state);
+
         // Create the audio callback
         // SAFETY: The closure takes ownership of all captured variables (move semantics).
         // Each variable is accessed exclusively by this callback thread, preventing data races.
@@ -109,26 +558,247 @@ impl AudioEngine {
         // - `graph`, `is_running`, `phase`: Owned exclusively by this closure
         // - No shared mutable state accessed from multiple threads
         let stream = device.build_output_stream(
-            &config,
+            &stream_config,
             move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                let Some(state) = handoff.as_mut() else {
+                    data.fill(0.0);
+                    return;
+                };
+                let StreamState {
+                    channels,
+                    graph,
+                    recorder,
+                    spectrum,
+                    scope,
+                    ..
+                } = &mut **state;
+
+                // REAL-TIME SAFE: Reading the clock is a vDSO call on the
+                // platforms we target, not a real system call
+                let callback_start = Instant::now();
+                let period =
+                    Duration::from_secs_f64((data.len() / num_channels) as f64 * seconds_per_frame);
+                // Counted after commands, which may have just started playback
+                let late = watchdog.callback(callback_start, period);
+
+                // NOT REAL-TIME SAFE, but runs only once before steady-state processing:
+                // elevation is a system call. Failure is reported to the UI thread,
+                // which logs the warning - we keep running at normal priority.
+                if realtime_pending {
+                    realtime_pending = false;
+                    let (granted, detail) = match crate::realtime::promote_current_thread(
+                        actual_sample_rate,
+                        realtime_block_size,
+                    ) {
+                        Ok(achieved) => (true, achieved),
+                        Err(reason) => (false, reason),
+                    };
+                    let _ = channels
+                        .event_tx
+                        .push(AudioEvent::RealtimePriority { granted, detail });
+                }
+                // NOT REAL-TIME SAFE either, and also only once
+                if let Some(cpu) = affinity_pending.take() {
+                    let (pinned, detail) = match crate::realtime::pin_current_thread(cpu) {
+                        Ok(achieved) => (true, achieved),
+                        Err(reason) => (false, reason),
+                    };
+                    let _ = channels
+                        .event_tx
+                        .push(AudioEvent::CpuAffinity { pinned, detail });
+                }
+
+                // After Shutdown, stay silent and ignore commands until the
+                // stream is dropped
+                if shut_down {
+                    data.fill(0.0);
+                    return;
+                }
+
+                // Set by SetConfig - the stream is reopened with these settings
+                let mut restart = None;
+
                 // Process commands from UI thread (non-blocking)
                 while let Ok(cmd) = channels.command_rx.pop() {
                     match cmd {
+                        AudioCommand::Shutdown => {
+                            // REAL-TIME SAFE: Only flips flags - the graph is dropped
+                            // with the stream, off the audio thread
+                            recorder.stop();
+                            is_running = false;
+                            shut_down = true;
+                            let _ = channels.event_tx.push(AudioEvent::Stopped);
+                            data.fill(0.0);
+                            return;
+                        }
                         AudioCommand::Start => {
                             // REAL-TIME SAFE: No tracing in audio callback
                             is_running = true;
+                            soft_mute.fade_in();
+                            watchdog.reset();
+                            transport.is_playing = true;
                             // Note: If event queue is full, we drop the event rather than block.
                             // This is acceptable in real-time audio - we cannot wait.
                             let _ = channels.event_tx.push(AudioEvent::Started);
                         }
                         AudioCommand::Stop => {
                             // REAL-TIME SAFE: No tracing in audio callback
+                            // The output keeps going until the fade-out is silent
                             is_running = false;
+                            soft_mute.fade_out();
+                            transport.is_playing = false;
                             let _ = channels.event_tx.push(AudioEvent::Stopped);
                         }
-                        AudioCommand::SetParameter(_node_id, _param_id, _value) => {
-                            // REAL-TIME SAFE: No tracing in audio callback
-                            // TODO: Set parameter on node via graph
+                        AudioCommand::SetParameter(node_id, param_id, value) => {
+                            // REAL-TIME SAFE: No tracing in audio callback; a node
+                            // removed since the UI sent the value is skipped
+                            graph.apply_parameter(node_id, param_id, value);
+                        }
+                        AudioCommand::SetParametersBulk => {
+                            // REAL-TIME SAFE: The batch is stored inline (no heap) and the
+                            // channel is bounded, so receiving and dropping it never allocates
+                            if let Ok(batch) = channels.param_batch_rx.try_recv() {
+                                graph.apply_parameter_batch(&batch);
+                            }
+                        }
+                        AudioCommand::Scrub { frame, rate } => {
+                            // REAL-TIME SAFE: Only repositions playheads, no graph mutation
+                            graph.scrub(frame, rate, scrub_window);
+                            scrub_frames_remaining = scrub_window;
+                        }
+                        AudioCommand::Seek(frame) => {
+                            // REAL-TIME SAFE: Only repositions playheads, no graph mutation
+                            // Keep the waveform stream's position in step with the playheads
+                            frame_position = graph.seek(frame);
+                            position_fraction = 0.0;
+                            transport.locate(frame_position);
+                            scrub_frames_remaining = 0;
+                            let _ = channels.event_tx.push(AudioEvent::PositionChanged {
+                                position: frame_position,
+                            });
+                        }
+                        AudioCommand::SetLoop {
+                            start,
+                            end,
+                            enabled,
+                        } => {
+                            // REAL-TIME SAFE: Only updates loop points, no graph mutation
+                            loop_region = graph.set_loop(start, end, enabled);
+                        }
+                        AudioCommand::SetScopeDecimation(decimation) => {
+                            // REAL-TIME SAFE: Only updates counters
+                            scope.set_decimation(decimation);
+                        }
+                        AudioCommand::SetSpectrum(spectrum_config) => {
+                            // REAL-TIME SAFE: Only queues a request for the analyzer thread
+                            match spectrum_config.and_then(|config| config.node_id) {
+                                Some(node_id) if graph.node(node_id).is_none() => {
+                                    let _ = channels.event_tx.push(AudioEvent::Error(format!(
+                                        "Cannot analyze node {node_id}: not found"
+                                    )));
+                                }
+                                _ => spectrum.configure(spectrum_config, actual_sample_rate),
+                            }
+                        }
+                        AudioCommand::StartRecording(path) => {
+                            // REAL-TIME SAFE: The path is moved to the writer thread
+                            recorder.start(path, actual_sample_rate, num_channels);
+                        }
+                        AudioCommand::StopRecording => {
+                            // REAL-TIME SAFE: Only queues a request for the writer thread
+                            recorder.stop();
+                        }
+                        AudioCommand::SetTempo(bpm) => {
+                            // REAL-TIME SAFE: Only updates the transport
+                            transport.set_tempo(f64::from(bpm));
+                        }
+                        AudioCommand::SetPlaybackRate(node_id, rate) => {
+                            // REAL-TIME SAFE: Only updates the sampler's read rate
+                            if let Some(rate) = graph.set_playback_rate(node_id, rate) {
+                                playback_rate = f64::from(rate);
+                                let _ = channels
+                                    .event_tx
+                                    .push(AudioEvent::PlaybackRateChanged { node_id, rate });
+                            } else {
+                                let _ = channels.event_tx.push(AudioEvent::Error(format!(
+                                    "Cannot set playback rate of node {node_id}: not a sampler"
+                                )));
+                            }
+                        }
+                        AudioCommand::SetBypass { node_id, bypassed } => {
+                            // REAL-TIME SAFE: Only flips a flag on the node
+                            if graph.set_bypass(node_id, bypassed) {
+                                let _ = channels
+                                    .event_tx
+                                    .push(AudioEvent::NodeBypassChanged { node_id, bypassed });
+                            } else {
+                                let _ = channels.event_tx.push(AudioEvent::Error(format!(
+                                    "Cannot bypass node {node_id}: not found"
+                                )));
+                            }
+                        }
+                        AudioCommand::SetSolo { node_id, solo } => {
+                            // REAL-TIME SAFE: Only flips a flag on the node
+                            if graph.set_solo(node_id, solo) {
+                                let _ = channels
+                                    .event_tx
+                                    .push(AudioEvent::NodeSoloChanged { node_id, solo });
+                            } else {
+                                let _ = channels.event_tx.push(AudioEvent::Error(format!(
+                                    "Cannot solo node {node_id}: not found"
+                                )));
+                            }
+                        }
+                        AudioCommand::SetGraphBypass(bypassed) => {
+                            // REAL-TIME SAFE: Only flips a flag - the graph stays intact.
+                            // Playheads stood still while bypassed, so move them back
+                            // in step with the transport before processing resumes.
+                            if graph_bypassed && !bypassed {
+                                graph.seek(frame_position);
+                            }
+                            graph_bypassed = bypassed;
+                            let _ = channels
+                                .event_tx
+                                .push(AudioEvent::GraphBypassChanged { bypassed });
+                        }
+                        AudioCommand::SetInputMonitor(enabled) => {
+                            // REAL-TIME SAFE: Only flips a flag
+                            if enabled && live_input.is_none() {
+                                let _ = channels.event_tx.push(AudioEvent::Error(
+                                    "No live input - check AudioConfig::input_channels".to_string(),
+                                ));
+                            }
+                            input_monitor = enabled;
+                            let _ = channels
+                                .event_tx
+                                .push(AudioEvent::InputMonitorChanged { enabled });
+                        }
+                        AudioCommand::SetMetering { node_id, enabled } => {
+                            // REAL-TIME SAFE: Only flips a flag on the node
+                            let found = if enabled {
+                                graph.enable_metering(node_id)
+                            } else {
+                                graph.disable_metering(node_id)
+                            };
+                            if !found {
+                                let _ = channels.event_tx.push(AudioEvent::Error(format!(
+                                    "Cannot meter node {node_id}: not found"
+                                )));
+                            }
+                        }
+                        AudioCommand::SetProfiling(enabled) => {
+                            // REAL-TIME SAFE: Only flips a flag (and clears fixed-size histories)
+                            graph.set_profiling(enabled);
+                        }
+                        AudioCommand::SetConfig {
+                            sample_rate,
+                            block_size,
+                        } => {
+                            // REAL-TIME SAFE: Only records the request - the stream
+                            // thread reinitializes the graph. Later commands wait
+                            // for the new stream.
+                            restart = Some((sample_rate, block_size));
+                            break;
                         }
                         AudioCommand::AddNode => {
                             // REAL-TIME SAFETY: Only modify graph when audio is stopped
@@ -179,6 +849,80 @@ impl AudioEngine {
                                 let _ = channels.event_tx.push(AudioEvent::NodeRemoved { node_id });
                             }
                         }
+                        AudioCommand::ReplaceNode(old_node_id) => {
+                            // REAL-TIME SAFETY: Same rules as RemoveNode + AddNode -
+                            // only modify the graph when audio is stopped
+                            if is_running {
+                                let _ = channels.plugin_rx.try_recv(); // Drain the plugin
+                                let _ = channels.event_tx.push(AudioEvent::Error(
+                                    "Cannot replace nodes while playing. Stop audio first."
+                                        .to_string(),
+                                ));
+                            } else {
+                                if graph.remove_node(old_node_id).is_some() {
+                                    let _ = channels.event_tx.push(AudioEvent::NodeRemoved {
+                                        node_id: old_node_id,
+                                    });
+                                }
+                                let added = channels.plugin_rx.try_recv().ok().and_then(|plugin| {
+                                    graph
+                                        .add_node(plugin, crate::graph::PluginSource::Unknown)
+                                        .ok()
+                                });
+                                let _ = channels.event_tx.push(added.map_or_else(
+                                    || {
+                                        AudioEvent::Error(format!(
+                                            "Failed to add node replacing {old_node_id}"
+                                        ))
+                                    },
+                                    |node_id| AudioEvent::NodeAdded { node_id },
+                                ));
+                            }
+                        }
+                        AudioCommand::ReloadNode(node_id) => {
+                            // REAL-TIME SAFETY: Same rules as ReplaceNode - swapping
+                            // saves/loads plugin state and reallocates node buffers
+                            if is_running {
+                                let _ = channels.plugin_rx.try_recv(); // Drain the plugin
+                                let _ = channels.event_tx.push(AudioEvent::Error(
+                                    "Cannot reload nodes while playing. Stop audio first."
+                                        .to_string(),
+                                ));
+                            } else {
+                                let result = channels
+                                    .plugin_rx
+                                    .try_recv()
+                                    .map_err(|_| "no plugin queued".to_string())
+                                    .and_then(|plugin| graph.swap_plugin(node_id, plugin));
+                                let event = match result {
+                                    Ok(_old) => AudioEvent::NodeReloaded { node_id },
+                                    Err(e) => AudioEvent::Error(format!(
+                                        "Cannot reload node {node_id}: {e}"
+                                    )),
+                                };
+                                let _ = channels.event_tx.push(event);
+                            }
+                        }
+                        AudioCommand::MoveNode { node_id, before } => {
+                            // REAL-TIME SAFETY: Only modify graph when audio is stopped
+                            //
+                            // AudioGraph::move_node() rewires connections and calls
+                            // update_processing_order, which allocates
+                            if is_running {
+                                let _ = channels.event_tx.push(AudioEvent::Error(
+                                    "Cannot move nodes while playing. Stop audio first."
+                                        .to_string(),
+                                ));
+                            } else {
+                                let event = match graph.move_node(node_id, before) {
+                                    Ok(()) => AudioEvent::NodeMoved { node_id, before },
+                                    Err(e) => AudioEvent::Error(format!(
+                                        "Cannot move node {node_id}: {e}"
+                                    )),
+                                };
+                                let _ = channels.event_tx.push(event);
+                            }
+                        }
                         AudioCommand::Connect { from, to } => {
                             // REAL-TIME SAFETY: Only modify graph when audio is stopped
                             //
@@ -190,8 +934,10 @@ impl AudioEngine {
                                     "Cannot modify connections while playing. Stop audio first."
                                         .to_string(),
                                 ));
-                            } else {
-                                let _ = graph.connect(from, to);
+                            } else if let Err(e) = graph.connect_checked(from, to) {
+                                let _ = channels
+                                    .event_tx
+                                    .push(AudioEvent::Error(format!("Cannot connect: {e}")));
                             }
                         }
                         AudioCommand::Disconnect { from, to } => {
@@ -208,11 +954,95 @@ impl AudioEngine {
                                 graph.disconnect(from, to);
                             }
                         }
+                        AudioCommand::SetConnectionGain { from, to, gain } => {
+                            // REAL-TIME SAFE: Only updates the connections in place
+                            if let Err(e) = graph.set_connection_gain(from, to, gain) {
+                                let _ = channels.event_tx.push(AudioEvent::Error(format!(
+                                    "Cannot set connection gain: {e}"
+                                )));
+                            }
+                        }
+                    }
+                }
+
+                // Hand everything back to the stream thread to reopen the stream
+                // with the new settings. A recording can't span two sample rates.
+                if let Some((sample_rate, block_size)) = restart {
+                    data.fill(0.0);
+                    recorder.stop();
+                    if let Some(mut state) = handoff.take() {
+                        state.resume = Resume {
+                            transport,
+                            frame_position,
+                            playback_rate,
+                            loop_region,
+                            graph_bypassed,
+                            input_monitor,
+                        };
+                        // REAL-TIME SAFE: The channel is bounded (preallocated) and
+                        // the state is moved, not freed. Should the stream thread
+                        // not take it, keep running with the current settings.
+                        if let Err(
+                            mpsc::TrySendError::Full(StreamControl::Restart { state, .. })
+                            | mpsc::TrySendError::Disconnected(StreamControl::Restart {
+                                state, ..
+                            }),
+                        ) = control_tx.try_send(StreamControl::Restart {
+                            sample_rate,
+                            block_size,
+                            state,
+                        }) {
+                            handoff = Some(state);
+                        }
                     }
+                    return;
                 }
 
-                if is_running {
-                    // De-interleave input (silence for now - no audio input yet)
+                // Forward what the recorder's writer thread has to say
+                while let Some(event) = recorder.pop_report() {
+                    let _ = channels.event_tx.push(event);
+                }
+
+                // Forward spectra only while there's room, so their bins are
+                // never dropped (freed) on the audio thread
+                while channels.event_tx.slots() > 0
+                    && let Some(event) = spectrum.pop_report()
+                {
+                    let _ = channels.event_tx.push(event);
+                }
+
+                // A late callback, or a buffer too big to fill, means the device
+                // may have played a gap
+                if is_running && (late || data.len() / num_channels > max_frames) {
+                    xrun_count += 1;
+                    let _ = channels
+                        .event_tx
+                        .push(AudioEvent::Xrun { count: xrun_count });
+                }
+
+                // Graph edits can change the compensated latency
+                if graph.latency() != reported_latency {
+                    reported_latency = graph.latency();
+                    let _ = channels.event_tx.push(AudioEvent::LatencyChanged {
+                        frames: reported_latency,
+                    });
+                }
+
+                // Mirror parameters moved in plugin editors - polled even while
+                // stopped, so knobs turned between takes still show up
+                graph.poll_parameter_edits(|node_id, param_id, value| {
+                    let _ = channels.event_tx.push(AudioEvent::ParameterChanged {
+                        node_id,
+                        param_id,
+                        value,
+                    });
+                });
+
+                // Stopped, but the output is still fading out: keep playing
+                // (and advancing) until it's silent
+                let fading_out = !is_running && !soft_mute.is_silent();
+
+                if is_running || fading_out || scrub_frames_remaining > 0 {
                     let frames_per_buffer = (data.len() / num_channels).min(max_frames);
 
                     // REAL-TIME SAFE: Only use pre-allocated buffer space
@@ -224,11 +1054,28 @@ impl AudioEngine {
                         ch_buf[..frames_per_buffer].fill(0.0);
                     }
 
-                    // Process the audio graph
+                    // De-interleave live input while it's monitored. Otherwise
+                    // drain it, so stale input never piles up in the ring.
+                    if let Some(input) = live_input.as_mut() {
+                        if input_monitor && (is_running || fading_out) {
+                            input.read_into(&mut channel_buffers_in, frames_per_buffer);
+                        } else {
+                            input.discard();
+                        }
+                    }
+
+                    // Process the audio graph, or pass the input through untouched
                     // REAL-TIME SAFE: SmallVec uses stack storage for <=8 channels (no heap allocation)
                     // Covers stereo (2ch), 5.1 (6ch), and 7.1 (8ch) without allocating
                     // Scope ensures mutable references are dropped before re-interleaving
-                    {
+                    if graph_bypassed {
+                        for (output, input) in
+                            channel_buffers_out.iter_mut().zip(&channel_buffers_in)
+                        {
+                            output[..frames_per_buffer]
+                                .copy_from_slice(&input[..frames_per_buffer]);
+                        }
+                    } else {
                         let input_refs: SmallVec<[&[f32]; 8]> = channel_buffers_in
                             .iter()
                             .map(|v| &v[..frames_per_buffer])
@@ -237,42 +1084,97 @@ impl AudioEngine {
                             .iter_mut()
                             .map(|v| &mut v[..frames_per_buffer])
                             .collect();
-                        graph.process(&input_refs, &mut output_refs);
+                        let process_start = Instant::now();
+                        graph.process_with_events(
+                            &input_refs,
+                            &mut output_refs,
+                            &no_events,
+                            &transport,
+                        );
+                        let block_period =
+                            Duration::from_secs_f64(frames_per_buffer as f64 * seconds_per_frame);
+                        if let Some(fraction) =
+                            dsp_load.record(process_start.elapsed(), block_period)
+                            && is_running
+                        {
+                            let _ = channels.event_tx.push(AudioEvent::DspLoad { fraction });
+                            for (node_id, fraction) in graph.node_cpus() {
+                                let _ = channels
+                                    .event_tx
+                                    .push(AudioEvent::NodeCpu { node_id, fraction });
+                            }
+                        }
                     } // output_refs dropped here, allowing channel_buffers_out to be accessed again
 
-                    // Re-interleave output (only the frames we processed)
-                    for (frame_idx, frame) in data
-                        .chunks_exact_mut(num_channels)
-                        .enumerate()
-                        .take(frames_per_buffer)
-                    {
-                        for (ch_idx, sample) in frame.iter_mut().enumerate() {
-                            if let Some(ch_buf) = channel_buffers_out.get(ch_idx) {
-                                *sample = *ch_buf.get(frame_idx).unwrap_or(&0.0);
-                            } else {
-                                *sample = 0.0;
+                    // Report metered nodes - dropped if the queue is full, like waveform data.
+                    // A bypassed graph has nothing new to report.
+                    for (node_id, reading) in graph.meters().filter(|_| !graph_bypassed) {
+                        let _ = channels.event_tx.push(AudioEvent::NodeMeter {
+                            node_id,
+                            peak: reading.peak,
+                            rms: reading.rms,
+                        });
+                    }
+
+                    // Fade in after Start and out after Stop. Scrubs while stopped
+                    // are heard as-is.
+                    if is_running || fading_out {
+                        soft_mute.apply(&mut channel_buffers_out, frames_per_buffer);
+                    }
+
+                    // Feed the spectrum analyzer, if one is running
+                    match spectrum.config().map(|config| config.node_id) {
+                        Some(None) => spectrum.push_block(&channel_buffers_out, frames_per_buffer),
+                        Some(Some(node_id)) => {
+                            if let Some(output) = graph.node_output(node_id) {
+                                spectrum.push_block(output, frames_per_buffer);
                             }
                         }
+                        None => {}
+                    }
+
+                    // Light the clip indicator for master channels over full scale.
+                    // Dropped if the queue is full, like meter readings.
+                    for (channel, buffer) in channel_buffers_out.iter().enumerate() {
+                        let count = clipped_samples(&buffer[..frames_per_buffer]);
+                        if count > 0 {
+                            let _ = channels.event_tx.push(AudioEvent::Clip { channel, count });
+                        }
                     }
 
+                    // Re-interleave output (only the frames we processed)
+                    vvdaw_core::buffer::interleave(
+                        &channel_buffers_out,
+                        &mut data[..frames_per_buffer * num_channels],
+                    );
+
                     // Fill any remaining frames with silence if cpal gave us more than we can handle
                     if frames_per_buffer < data.len() / num_channels {
                         let remaining_start = frames_per_buffer * num_channels;
                         data[remaining_start..].fill(0.0);
                     }
 
-                    // Send waveform samples to UI for visualization
-                    // Compute peak values for left and right channels from interleaved data
-                    if !data.is_empty() && num_channels >= 2 {
-                        // For stereo: data is [L, R, L, R, ...]
-                        // Compute peaks for each channel separately
-                        let mut left_peak = 0.0_f32;
-                        let mut right_peak = 0.0_f32;
+                    // Capture what was just played (including scrubs)
+                    recorder.push_block(&data[..frames_per_buffer * num_channels]);
+                    scope.push_block(
+                        &data[..frames_per_buffer * num_channels],
+                        num_channels,
+                        frame_position,
+                    );
+                    scope.publish(&mut channels.scope_tx);
 
-                        for frame in data.chunks_exact(num_channels) {
-                            left_peak = left_peak.max(frame[0].abs());
-                            right_peak = right_peak.max(frame[1].abs());
-                        }
+                    // Scrubbing while stopped: don't advance the transport or stream
+                    // waveform data, just count down the remaining scrub window
+                    if !is_running && !fading_out {
+                        scrub_frames_remaining =
+                            scrub_frames_remaining.saturating_sub(frames_per_buffer);
+                        return;
+                    }
+
+                    // Send waveform samples to UI for visualization
+                    if !data.is_empty() {
+                        let (left_peak, right_peak) =
+                            waveform_peaks(&data[..frames_per_buffer * num_channels], num_channels);
 
                         // Send waveform sample event with position for synchronization
                         // Drop if queue is full - they're informational and will be replaced
@@ -283,11 +1185,44 @@ impl AudioEngine {
                         });
                     }
 
-                    // Increment frame position for next buffer
-                    frame_position = frame_position.wrapping_add(frames_per_buffer as u64);
+                    // Phase correlation needs a pair of channels (the front pair
+                    // beyond stereo)
+                    if num_channels >= 2 {
+                        // Phase correlation of the master output, for goniometer-style meters
+                        let value = if graph_bypassed {
+                            stereo_correlation(
+                                &channel_buffers_out[0][..frames_per_buffer],
+                                &channel_buffers_out[1][..frames_per_buffer],
+                            )
+                        } else {
+                            graph.output_correlation()
+                        };
+                        let _ = channels
+                            .event_tx
+                            .push(AudioEvent::StereoCorrelation { value });
+                    }
+
+                    // Increment frame position for next buffer, at the samplers' rate
+                    let previous_position = frame_position;
+                    frame_position = advance_position(
+                        frame_position,
+                        &mut position_fraction,
+                        frames_per_buffer,
+                        playback_rate,
+                        loop_region,
+                    );
+                    if frame_position == previous_position + frames_per_buffer as u64 {
+                        transport.advance(frames_per_buffer);
+                    } else {
+                        // Wrapped, or playing at another speed
+                        transport.locate(frame_position);
+                    }
                 } else {
                     // Silence when not running
                     data.fill(0.0);
+                    if let Some(input) = live_input.as_mut() {
+                        input.discard();
+                    }
                 }
             },
             move |err| {
@@ -296,27 +1231,265 @@ impl AudioEngine {
             None,
         )?;
 
-        // Start the stream
         stream.play()?;
-        tracing::info!("Audio stream started");
-
-        // Store the stream (keeps it alive)
-        self.stream = Some(stream);
-
-        Ok(())
+        Ok((stream, input_stream))
     }
 
-    /// Stop the audio engine
+    /// Stop the audio engine, waiting up to [`STOP_TIMEOUT`]
+    ///
+    /// Send [`AudioCommand::Shutdown`] and wait for its `Stopped` ack first so
+    /// the callback is idle when the stream is torn down.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream thread panicked or didn't finish in time.
     pub fn stop(&mut self) -> Result<()> {
+        self.stop_with_timeout(STOP_TIMEOUT)
+    }
+
+    /// Stop the audio engine, waiting at most `timeout` for the stream to close
+    ///
+    /// On timeout the stream thread is detached (it may still finish later)
+    /// so the caller is never blocked indefinitely.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream thread panicked or didn't finish in time.
+    pub fn stop_with_timeout(&mut self, timeout: Duration) -> Result<()> {
+        let Some(thread) = self.stream_thread.take() else {
+            return Ok(());
+        };
         tracing::info!("Audio engine stopping");
 
-        if let Some(stream) = self.stream.take() {
-            stream.pause()?;
-            drop(stream);
-            tracing::info!("Audio stream stopped");
+        // The thread may already be gone if startup failed late
+        let _ = thread.stop_tx.send(StreamControl::Stop);
+
+        let deadline = Instant::now() + timeout;
+        while !thread.handle.is_finished() {
+            if Instant::now() >= deadline {
+                anyhow::bail!("Audio stream did not stop within {timeout:?} - abandoning it");
+            }
+            std::thread::sleep(Duration::from_millis(5));
         }
 
-        Ok(())
+        thread
+            .handle
+            .join()
+            .map_err(|_| anyhow::anyhow!("Audio stream thread panicked"))
+    }
+}
+
+/// Pause and drop an output stream and its input stream, if any
+///
+/// Dropping the output stream drops its callback and whatever the callback
+/// still owns.
+fn close_streams(streams: Option<(Stream, Option<Stream>)>) {
+    let Some((stream, input_stream)) = streams else {
+        return;
+    };
+    if let Err(e) = stream.pause() {
+        tracing::warn!("Failed to pause audio stream: {e}");
+    }
+    drop(input_stream);
+    drop(stream);
+}
+
+/// Peak levels of the first two channels of an interleaved block
+///
+/// The waveform display is stereo: mono output shows on both sides, and
+/// channels beyond the front pair are left out.
+fn waveform_peaks(interleaved: &[f32], channels: usize) -> (f32, f32) {
+    let mut left_peak = 0.0_f32;
+    let mut right_peak = 0.0_f32;
+
+    for frame in interleaved.chunks_exact(channels) {
+        left_peak = left_peak.max(frame[0].abs());
+        right_peak = right_peak.max(frame.get(1).unwrap_or(&frame[0]).abs());
+    }
+
+    (left_peak, right_peak)
+}
+
+/// Number of samples beyond full scale (absolute value over 1.0)
+///
+/// REAL-TIME SAFE: One comparison per sample.
+fn clipped_samples(samples: &[f32]) -> u32 {
+    samples
+        .iter()
+        .fold(0, |count, sample| count + u32::from(sample.abs() > 1.0))
+}
+
+/// Timeline position after playing `frames` output frames at varispeed `rate`
+///
+/// Mirrors the sampler's playhead: `fraction` carries the sub-frame remainder
+/// between buffers (so slow rates still creep along), the loop region wraps in
+/// either direction, and reverse playback stops at frame 0.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)] // Positions are far below 2^52 frames and clamped to >= 0
+fn advance_position(
+    position: u64,
+    fraction: &mut f64,
+    frames: Frames,
+    rate: f64,
+    loop_region: Option<(u64, u64)>,
+) -> u64 {
+    let previous = position as f64 + *fraction;
+    let mut exact = (frames as f64).mul_add(rate, previous);
+
+    if let Some((start, end)) = loop_region {
+        let (start, end) = (start as f64, end as f64);
+        if rate > 0.0 && previous < end && exact >= end {
+            exact = start + (exact - end) % (end - start);
+        } else if rate < 0.0 && previous >= start && exact < start {
+            let back = (start - exact) % (end - start);
+            exact = if back > 0.0 { end - back } else { start };
+        }
+    }
+
+    let exact = exact.max(0.0);
+    let whole = exact.floor();
+    *fraction = exact - whole;
+    whole as u64
+}
+
+/// Buffer size limits of a device config range (`None` if unknown)
+fn block_size_range(range: &cpal::SupportedStreamConfigRange) -> Option<RangeInclusive<Frames>> {
+    match *range.buffer_size() {
+        cpal::SupportedBufferSize::Range { min, max } => Some(min as Frames..=max as Frames),
+        cpal::SupportedBufferSize::Unknown => None,
+    }
+}
+
+/// Expand device config ranges into concrete configs based on `base`
+fn configs_from_ranges(
+    base: &AudioConfig,
+    ranges: &[cpal::SupportedStreamConfigRange],
+) -> Vec<AudioConfig> {
+    let mut configs: Vec<AudioConfig> = Vec::new();
+
+    for range in ranges {
+        let (min_rate, max_rate) = (range.min_sample_rate().0, range.max_sample_rate().0);
+        let block_size_range = block_size_range(range);
+        let block_size = block_size_range.as_ref().map_or(base.block_size, |sizes| {
+            base.block_size.clamp(*sizes.start(), *sizes.end())
+        });
+
+        let mut rates: Vec<SampleRate> = STANDARD_SAMPLE_RATES
+            .into_iter()
+            .filter(|rate| (min_rate..=max_rate).contains(rate))
+            .chain([min_rate, max_rate])
+            .collect();
+        rates.sort_unstable();
+        rates.dedup();
+
+        for sample_rate in rates {
+            let config = AudioConfig {
+                sample_rate,
+                block_size,
+                output_channels: usize::from(range.channels()),
+                block_size_range: block_size_range.clone(),
+                ..base.clone()
+            };
+            // The same rate often shows up once per sample format
+            let duplicate = configs.iter().any(|existing| {
+                existing.sample_rate == config.sample_rate
+                    && existing.output_channels == config.output_channels
+                    && existing.block_size_range == config.block_size_range
+            });
+            if !duplicate {
+                configs.push(config);
+            }
+        }
+    }
+
+    configs
+}
+
+/// Check `config` against the device's config ranges
+///
+/// Returns why it can't run, phrased to follow the device name.
+fn check_supported(
+    config: &AudioConfig,
+    ranges: &[cpal::SupportedStreamConfigRange],
+) -> Result<(), String> {
+    let matching: Vec<_> = ranges
+        .iter()
+        .filter(|range| usize::from(range.channels()) == config.output_channels)
+        .filter(|range| {
+            (range.min_sample_rate().0..=range.max_sample_rate().0).contains(&config.sample_rate)
+        })
+        .collect();
+
+    if matching.is_empty() {
+        return Err(format!(
+            "does not support {}Hz with {} output channels",
+            config.sample_rate, config.output_channels
+        ));
+    }
+
+    // Devices that don't report buffer limits get the benefit of the doubt
+    let block_size_ok = matching.iter().any(|range| {
+        block_size_range(range).is_none_or(|sizes| sizes.contains(&config.block_size))
+    });
+    if !block_size_ok {
+        let limits: Vec<String> = matching
+            .iter()
+            .filter_map(|range| block_size_range(range))
+            .map(|sizes| format!("{}-{}", sizes.start(), sizes.end()))
+            .collect();
+        return Err(format!(
+            "does not support a buffer size of {} frames at {}Hz (supported: {})",
+            config.block_size,
+            config.sample_rate,
+            limits.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// Check `config` against the config ranges `device` reports
+///
+/// Returns the reason it can't run, naming the device. A device that can't
+/// be queried gets the benefit of the doubt.
+fn check_device(
+    device: &cpal::Device,
+    device_name: &str,
+    config: &AudioConfig,
+) -> Result<(), String> {
+    match device.supported_output_configs() {
+        Ok(ranges) => check_supported(config, &ranges.collect::<Vec<_>>())
+            .map_err(|reason| format!("Output device '{device_name}' {reason}")),
+        Err(e) => {
+            tracing::warn!("Could not query supported configs ({e}), trying the requested one");
+            Ok(())
+        }
+    }
+}
+
+/// The device called `name` among `devices`, warning when there isn't one
+fn find_device<I>(devices: Result<I, cpal::DevicesError>, name: &str) -> Option<cpal::Device>
+where
+    I: Iterator<Item = cpal::Device>,
+{
+    match devices {
+        Ok(mut devices) => {
+            let found = devices.find(|device| device.name().is_ok_and(|found| found == name));
+            if found.is_none() {
+                tracing::warn!("Device '{}' not found, using the default", name);
+            }
+            found
+        }
+        Err(e) => {
+            tracing::warn!(
+                "Failed to enumerate devices ({e}), using the default instead of '{}'",
+                name
+            );
+            None
+        }
     }
 }
 
@@ -342,6 +1515,159 @@ mod tests {
         host.default_output_device().is_none()
     }
 
+    /// Device config range with the given rates and buffer sizes
+    fn device_range(
+        channels: u16,
+        rates: (u32, u32),
+        buffer: Option<(u32, u32)>,
+        format: cpal::SampleFormat,
+    ) -> cpal::SupportedStreamConfigRange {
+        cpal::SupportedStreamConfigRange::new(
+            channels,
+            cpal::SampleRate(rates.0),
+            cpal::SampleRate(rates.1),
+            buffer.map_or(cpal::SupportedBufferSize::Unknown, |(min, max)| {
+                cpal::SupportedBufferSize::Range { min, max }
+            }),
+            format,
+        )
+    }
+
+    #[test]
+    fn test_waveform_peaks_by_channel_count() {
+        // Mono shows on both sides
+        assert_eq!(waveform_peaks(&[0.5, -0.75], 1), (0.75, 0.75));
+        // Stereo
+        assert_eq!(waveform_peaks(&[0.5, -0.25, 0.1, 0.2], 2), (0.5, 0.25));
+        // Quad: only the front pair is drawn
+        assert_eq!(waveform_peaks(&[0.1, 0.2, 0.9, 0.9], 4), (0.1, 0.2));
+    }
+
+    #[test]
+    fn test_clipped_samples_counts_overs() {
+        assert_eq!(clipped_samples(&[0.5, -1.0, 1.0]), 0);
+        assert_eq!(clipped_samples(&[1.01, -1.5, 0.9, 2.0]), 3);
+        assert_eq!(clipped_samples(&[]), 0);
+    }
+
+    #[test]
+    fn test_advance_position_follows_playback_rate() {
+        let mut fraction = 0.0;
+
+        // Normal speed, with and without a loop
+        assert_eq!(advance_position(100, &mut fraction, 64, 1.0, None), 164);
+        assert_eq!(
+            advance_position(100, &mut fraction, 64, 1.0, Some((0, 150))),
+            14
+        );
+
+        // Slow rates accumulate the remainder across buffers
+        assert_eq!(advance_position(0, &mut fraction, 3, 0.5, None), 1);
+        assert_eq!(advance_position(1, &mut fraction, 3, 0.5, None), 3);
+        assert!(fraction.abs() < 1e-9);
+
+        // Hold, reverse, and reverse through a loop start
+        assert_eq!(advance_position(50, &mut fraction, 64, 0.0, None), 50);
+        assert_eq!(advance_position(50, &mut fraction, 64, -1.0, None), 0);
+        assert_eq!(
+            advance_position(110, &mut fraction, 20, -1.0, Some((100, 150))),
+            140
+        );
+    }
+
+    #[test]
+    fn test_configs_from_ranges() {
+        let ranges = [
+            device_range(2, (44100, 96000), Some((64, 4096)), cpal::SampleFormat::F32),
+            // Same range in another sample format - no duplicates
+            device_range(2, (44100, 96000), Some((64, 4096)), cpal::SampleFormat::I16),
+            device_range(1, (22050, 22050), None, cpal::SampleFormat::F32),
+        ];
+        let base = AudioConfig {
+            block_size: 8192,
+            ..AudioConfig::default()
+        };
+
+        let configs = configs_from_ranges(&base, &ranges);
+        let stereo_rates: Vec<u32> = configs
+            .iter()
+            .filter(|config| config.output_channels == 2)
+            .map(|config| config.sample_rate)
+            .collect();
+        assert_eq!(stereo_rates, vec![44100, 48000, 88200, 96000]);
+
+        let stereo = &configs[0];
+        assert_eq!(stereo.block_size_range, Some(64..=4096));
+        assert_eq!(stereo.block_size, 4096); // Clamped into the device's range
+
+        let mono: Vec<_> = configs
+            .iter()
+            .filter(|config| config.output_channels == 1)
+            .collect();
+        assert_eq!(mono.len(), 1);
+        assert_eq!(mono[0].sample_rate, 22050);
+        assert_eq!(mono[0].block_size_range, None);
+        assert_eq!(mono[0].block_size, 8192);
+    }
+
+    #[test]
+    fn test_check_supported_rejects_invalid_combos() {
+        let ranges = [device_range(
+            2,
+            (44100, 48000),
+            Some((64, 1024)),
+            cpal::SampleFormat::F32,
+        )];
+
+        assert!(check_supported(&AudioConfig::default(), &ranges).is_ok());
+
+        let high_rate = AudioConfig {
+            sample_rate: 96000,
+            ..AudioConfig::default()
+        };
+        let reason = check_supported(&high_rate, &ranges).unwrap_err();
+        assert!(reason.contains("96000Hz"));
+
+        let big_buffer = AudioConfig {
+            block_size: 2048,
+            ..AudioConfig::default()
+        };
+        let reason = check_supported(&big_buffer, &ranges).unwrap_err();
+        assert!(reason.contains("64-1024"));
+
+        let surround = AudioConfig {
+            output_channels: 6,
+            ..AudioConfig::default()
+        };
+        assert!(check_supported(&surround, &ranges).is_err());
+
+        // Unknown buffer limits are not held against the config
+        let unknown = [device_range(
+            2,
+            (48000, 48000),
+            None,
+            cpal::SampleFormat::F32,
+        )];
+        assert!(check_supported(&big_buffer, &unknown).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_device_falls_back_to_default() {
+        if should_skip_audio_test() {
+            eprintln!("Skipping test: No audio device available (CI environment)");
+            return;
+        }
+
+        let host = cpal::default_host();
+        let device =
+            AudioEngine::select_output_device(&host, Some("no such device (vvdaw test)")).unwrap();
+        assert_eq!(
+            device.name().ok(),
+            host.default_output_device()
+                .and_then(|device| device.name().ok())
+        );
+    }
+
     #[test]
     fn test_engine_start_stop() {
         if should_skip_audio_test() {
@@ -423,6 +1749,50 @@ mod tests {
         engine.stop().unwrap();
     }
 
+    #[test]
+    fn test_stop_without_start() {
+        let mut engine = AudioEngine::new(AudioConfig::default());
+        assert!(engine.stop().is_ok());
+    }
+
+    #[test]
+    fn test_shutdown_is_acknowledged() {
+        if should_skip_audio_test() {
+            eprintln!("Skipping test: No audio device available (CI environment)");
+            return;
+        }
+
+        let mut engine = AudioEngine::new(AudioConfig::default());
+        let (mut ui_channels, audio_channels) = create_channels(256);
+
+        if let Err(e) = engine.start(audio_channels) {
+            eprintln!("Skipping test: Audio device unavailable - {e}");
+            return;
+        }
+
+        ui_channels.command_tx.push(AudioCommand::Start).unwrap();
+        ui_channels.command_tx.push(AudioCommand::Shutdown).unwrap();
+        std::thread::sleep(Duration::from_millis(200));
+
+        let mut received_stopped = false;
+        while let Ok(event) = ui_channels.event_rx.pop() {
+            if matches!(event, AudioEvent::Stopped) {
+                received_stopped = true;
+            }
+        }
+        assert!(
+            received_stopped,
+            "Shutdown should be acknowledged with Stopped"
+        );
+
+        // Commands after Shutdown are ignored
+        ui_channels.command_tx.push(AudioCommand::Start).unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(ui_channels.event_rx.pop().is_err());
+
+        assert!(engine.stop_with_timeout(Duration::from_secs(5)).is_ok());
+    }
+
     #[test]
     fn test_multiple_start_stop_cycles() {
         if should_skip_audio_test() {
@@ -0,0 +1,137 @@
+//! Anti-click fades for starting and stopping playback.
+//!
+//! Cutting the output mid-waveform clicks. [`SoftMute`] ramps the master
+//! output over [`SOFT_MUTE_SECONDS`] instead: down to silence on Stop, back
+//! up on Start. The ramp always continues from the current gain, so a Stop
+//! followed straight away by a Start just turns the fade around.
+
+use vvdaw_core::{Frames, Sample, SampleRate};
+
+/// Length of a full fade in or out
+pub const SOFT_MUTE_SECONDS: f64 = 0.005;
+
+/// Linear gain ramp between silence and unity, applied to the master output
+#[derive(Debug, Clone)]
+pub struct SoftMute {
+    /// Gain applied to the current frame
+    gain: f32,
+    /// Gain being ramped toward (0.0 or 1.0)
+    target: f32,
+    /// Gain change per frame
+    step: f32,
+}
+
+impl SoftMute {
+    /// A silent soft mute whose fades last [`SOFT_MUTE_SECONDS`] at `sample_rate`
+    pub fn new(sample_rate: SampleRate) -> Self {
+        let fade_frames = (f64::from(sample_rate) * SOFT_MUTE_SECONDS).max(1.0);
+        Self {
+            gain: 0.0,
+            target: 0.0,
+            step: (1.0 / fade_frames) as f32,
+        }
+    }
+
+    /// Ramp up to unity gain
+    pub const fn fade_in(&mut self) {
+        self.target = 1.0;
+    }
+
+    /// Ramp down to silence
+    pub const fn fade_out(&mut self) {
+        self.target = 0.0;
+    }
+
+    /// Whether the output is fully muted (faded out, not fading)
+    pub fn is_silent(&self) -> bool {
+        self.gain == 0.0 && self.target == 0.0
+    }
+
+    /// Current gain (0.0 = muted, 1.0 = unity)
+    pub const fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Apply the ramp to the first `frames` of each channel buffer
+    ///
+    /// Every channel gets the same gain per frame.
+    ///
+    /// REAL-TIME SAFE: Arithmetic only.
+    pub fn apply(&mut self, buffers: &mut [Vec<Sample>], frames: Frames) {
+        if self.gain == self.target {
+            if self.gain == 0.0 {
+                for buffer in buffers.iter_mut() {
+                    buffer[..frames].fill(0.0);
+                }
+            }
+            return;
+        }
+
+        for frame in 0..frames {
+            self.gain = if self.gain < self.target {
+                (self.gain + self.step).min(self.target)
+            } else {
+                (self.gain - self.step).max(self.target)
+            };
+            for buffer in buffers.iter_mut() {
+                buffer[frame] *= self.gain;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Frames in a full fade at 48 kHz
+    const FADE_FRAMES: usize = 240;
+
+    #[test]
+    fn test_fade_in_ramps_to_unity() {
+        let mut mute = SoftMute::new(48000);
+        let mut buffers = vec![vec![1.0; 512]; 2];
+
+        mute.apply(&mut buffers, 512);
+        assert!(buffers[0].iter().all(|&sample| sample == 0.0));
+
+        mute.fade_in();
+        let mut buffers = vec![vec![1.0; 512]; 2];
+        mute.apply(&mut buffers, 512);
+        assert!(buffers[0][0] > 0.0 && buffers[0][0] < 0.01);
+        assert!(buffers[1][FADE_FRAMES / 2] < 0.51);
+        assert_eq!(buffers[0][FADE_FRAMES], 1.0);
+        assert_eq!(mute.gain(), 1.0);
+        assert!(!mute.is_silent());
+    }
+
+    #[test]
+    fn test_fade_out_reaches_silence() {
+        let mut mute = SoftMute::new(48000);
+        mute.fade_in();
+        mute.apply(&mut [vec![1.0; 512]], 512);
+
+        mute.fade_out();
+        let mut buffers = vec![vec![1.0; 512]];
+        mute.apply(&mut buffers, 512);
+        assert!(buffers[0][0] > 0.99);
+        assert_eq!(buffers[0][FADE_FRAMES], 0.0);
+        assert!(mute.is_silent());
+    }
+
+    #[test]
+    fn test_stop_then_start_turns_the_fade_around() {
+        let mut mute = SoftMute::new(48000);
+        mute.fade_in();
+        mute.apply(&mut [vec![1.0; 512]], 512);
+
+        // Stop and Start within one block: no stuck gain
+        mute.fade_out();
+        mute.apply(&mut [vec![1.0; 16]], 16);
+        mute.fade_in();
+        let mut buffers = vec![vec![1.0; 512]];
+        mute.apply(&mut buffers, 512);
+        assert!(buffers[0][0] < 1.0);
+        assert_eq!(buffers[0][511], 1.0);
+    }
+}
@@ -0,0 +1,217 @@
+//! Live audio input from a capture device.
+//!
+//! cpal has no duplex streams, and the input device may not be the output
+//! device at all, so live input runs on its own stream. [`InputCapture`] lives
+//! in that stream's callback and copies each captured block into a lock-free
+//! ring buffer; [`LiveInput`] lives in the output callback and drains the ring
+//! into the graph's `system_input`, one output block at a time.
+//!
+//! The two devices never tick in perfect lockstep. When input runs short the
+//! missing frames are silence, and when it piles up (more than
+//! [`MAX_BUFFERED_BLOCKS`] blocks behind) the oldest frames are skipped, so
+//! monitoring latency stays bounded.
+
+use rtrb::{Consumer, Producer, RingBuffer};
+use vvdaw_core::{Frames, Sample};
+
+/// Output blocks of input buffered between the two streams
+const RING_BLOCKS: usize = 8;
+
+/// Buffered input, in output blocks, beyond which the oldest frames are skipped
+pub const MAX_BUFFERED_BLOCKS: usize = 2;
+
+/// Input-stream side of live input
+pub struct InputCapture {
+    samples: Producer<Sample>,
+    channels: usize,
+    /// Frames that didn't fit in the ring
+    dropped_frames: u64,
+}
+
+/// Output-stream side of live input
+pub struct LiveInput {
+    samples: Consumer<Sample>,
+    channels: usize,
+    /// Frames kept buffered at most before skipping ahead
+    max_buffered_frames: Frames,
+    /// Frames the output needed but the input hadn't delivered
+    missing_frames: u64,
+}
+
+/// Create both ends of a live input ring for `channels` captured channels
+///
+/// `block_size` is the output block size - the ring holds a few of them.
+pub fn live_input(channels: usize, block_size: Frames) -> (InputCapture, LiveInput) {
+    let channels = channels.max(1);
+    let (producer, consumer) = RingBuffer::new(block_size * channels * RING_BLOCKS);
+    (
+        InputCapture {
+            samples: producer,
+            channels,
+            dropped_frames: 0,
+        },
+        LiveInput {
+            samples: consumer,
+            channels,
+            max_buffered_frames: block_size * MAX_BUFFERED_BLOCKS,
+            missing_frames: 0,
+        },
+    )
+}
+
+impl InputCapture {
+    /// Queue one block of interleaved captured input
+    ///
+    /// Frames that don't fit are dropped (the output side skips ahead anyway
+    /// once it falls this far behind).
+    ///
+    /// REAL-TIME SAFE: Writes into the preallocated ring only.
+    pub fn push_block(&mut self, interleaved: &[Sample]) {
+        let frames = (self.samples.slots() / self.channels).min(interleaved.len() / self.channels);
+        let samples = frames * self.channels;
+        if let Ok(chunk) = self.samples.write_chunk_uninit(samples) {
+            chunk.fill_from_iter(interleaved[..samples].iter().copied());
+        }
+        self.dropped_frames += (interleaved.len() / self.channels - frames) as u64;
+    }
+
+    /// Captured frames dropped because the ring was full
+    pub const fn dropped_frames(&self) -> u64 {
+        self.dropped_frames
+    }
+}
+
+impl LiveInput {
+    /// De-interleave the next `frames` of input into `buffers`
+    ///
+    /// Input channel `i` fills `buffers[i]`; mono input fills every buffer.
+    /// Input channels without a buffer are discarded, and buffers without an
+    /// input channel are left untouched. Frames the input hasn't delivered yet
+    /// are silence.
+    ///
+    /// REAL-TIME SAFE: Reads from the preallocated ring only.
+    pub fn read_into(&mut self, buffers: &mut [Vec<Sample>], frames: Frames) {
+        self.skip_stale(frames);
+
+        let available = (self.samples.slots() / self.channels).min(frames);
+        if let Ok(chunk) = self.samples.read_chunk(available * self.channels) {
+            let (first, second) = chunk.as_slices();
+            let interleaved = first.iter().chain(second).copied();
+            for (index, sample) in interleaved.enumerate() {
+                let (frame, channel) = (index / self.channels, index % self.channels);
+                if self.channels == 1 {
+                    for buffer in buffers.iter_mut() {
+                        buffer[frame] = sample;
+                    }
+                } else if let Some(buffer) = buffers.get_mut(channel) {
+                    buffer[frame] = sample;
+                }
+            }
+            chunk.commit_all();
+        }
+
+        let fed = if self.channels == 1 {
+            buffers.len()
+        } else {
+            self.channels.min(buffers.len())
+        };
+        for buffer in &mut buffers[..fed] {
+            buffer[available..frames].fill(0.0);
+        }
+        self.missing_frames += (frames - available) as u64;
+    }
+
+    /// Throw away everything captured so far
+    ///
+    /// Call this for blocks where the input isn't heard, so it doesn't pile up.
+    ///
+    /// REAL-TIME SAFE: Only moves the ring's read position.
+    pub fn discard(&mut self) {
+        let buffered = self.samples.slots();
+        if let Ok(chunk) = self.samples.read_chunk(buffered) {
+            chunk.commit_all();
+        }
+    }
+
+    /// Output frames that had no input to go with them
+    pub const fn missing_frames(&self) -> u64 {
+        self.missing_frames
+    }
+
+    /// Skip the oldest frames if more than `max_buffered_frames` would remain
+    /// after reading `frames`
+    fn skip_stale(&mut self, frames: Frames) {
+        let buffered = self.samples.slots() / self.channels;
+        let excess = buffered.saturating_sub(frames + self.max_buffered_frames);
+        if excess > 0
+            && let Ok(chunk) = self.samples.read_chunk(excess * self.channels)
+        {
+            chunk.commit_all();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::ramp;
+
+    #[test]
+    fn test_input_reaches_buffers() {
+        let (mut capture, mut input) = live_input(2, 4);
+        capture.push_block(&ramp(4));
+
+        let mut buffers = vec![vec![9.0; 4]; 2];
+        input.read_into(&mut buffers, 4);
+        assert_eq!(buffers[0], vec![0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(buffers[1], vec![0.0, -1.0, -2.0, -3.0]);
+        assert_eq!(input.missing_frames(), 0);
+    }
+
+    #[test]
+    fn test_short_input_is_padded_with_silence() {
+        let (mut capture, mut input) = live_input(2, 4);
+        capture.push_block(&ramp(3));
+
+        let mut buffers = vec![vec![9.0; 4]; 2];
+        input.read_into(&mut buffers, 4);
+        assert_eq!(buffers[0], vec![0.0, 1.0, 2.0, 0.0]);
+        assert_eq!(input.missing_frames(), 1);
+    }
+
+    #[test]
+    fn test_mono_input_feeds_every_channel() {
+        let (mut capture, mut input) = live_input(1, 2);
+        capture.push_block(&[0.25, 0.5]);
+
+        let mut buffers = vec![vec![0.0; 2]; 2];
+        input.read_into(&mut buffers, 2);
+        assert_eq!(buffers[0], vec![0.25, 0.5]);
+        assert_eq!(buffers[1], vec![0.25, 0.5]);
+    }
+
+    #[test]
+    fn test_backlog_is_skipped() {
+        let (mut capture, mut input) = live_input(2, 4);
+        // Far more than the output keeps buffered
+        capture.push_block(&ramp(4 * RING_BLOCKS));
+
+        let mut buffers = vec![vec![0.0; 4]; 2];
+        input.read_into(&mut buffers, 4);
+
+        // Only MAX_BUFFERED_BLOCKS blocks are left behind after this read
+        let skipped = 4 * RING_BLOCKS - 4 * (MAX_BUFFERED_BLOCKS + 1);
+        assert_eq!(buffers[0][0], skipped as Sample);
+
+        input.discard();
+        input.read_into(&mut buffers, 4);
+        assert_eq!(buffers[0], vec![0.0; 4]);
+    }
+
+    #[test]
+    fn test_full_ring_drops_frames() {
+        let (mut capture, _input) = live_input(2, 4);
+        capture.push_block(&ramp(4 * RING_BLOCKS + 3));
+        assert_eq!(capture.dropped_frames(), 3);
+    }
+}
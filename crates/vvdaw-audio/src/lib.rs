@@ -3,15 +3,33 @@
 //! This crate provides the audio graph, audio thread management,
 //! and integration with cpal for audio I/O.
 
+pub mod automation;
 pub mod builtin;
 pub mod engine;
+pub mod fade;
 pub mod graph;
+pub mod input;
+pub mod load;
+pub mod midi_file;
+pub mod oversample;
+pub mod realtime;
+pub mod recorder;
+pub mod render;
+pub mod resample;
+pub mod scope;
 pub mod session;
+pub mod spectrum;
+pub mod strip;
+pub mod wav_progress;
+
+#[cfg(test)]
+mod test_util;
 
 pub use engine::AudioEngine;
 pub use graph::AudioGraph;
 pub use session::Session;
 
+use std::ops::RangeInclusive;
 use vvdaw_core::{Frames, SampleRate};
 
 /// Audio configuration
@@ -19,8 +37,42 @@ use vvdaw_core::{Frames, SampleRate};
 pub struct AudioConfig {
     pub sample_rate: SampleRate,
     pub block_size: Frames,
+    /// Live input channels to capture (0 disables live input)
+    ///
+    /// Captured audio feeds the graph's `system_input` while
+    /// `AudioCommand::SetInputMonitor` is on.
     pub input_channels: usize,
     pub output_channels: usize,
+    /// Smallest to largest buffer size the device accepts
+    ///
+    /// Filled in by `AudioEngine::supported_configs` (`None` when the device
+    /// doesn't say). Ignored when starting the engine.
+    pub block_size_range: Option<RangeInclusive<Frames>>,
+    /// Output device to open, by name (see `AudioEngine::list_devices`)
+    ///
+    /// `None` uses the host's default device, as does a name that isn't found
+    /// (with a warning).
+    pub device_name: Option<String>,
+    /// Input device for live input, by name
+    ///
+    /// May differ from the output device. `None` (or a name that isn't found)
+    /// uses the host's default input device.
+    pub input_device_name: Option<String>,
+    /// Request real-time scheduling for the audio callback thread
+    ///
+    /// Falls back to normal priority (reported via `AudioEvent::RealtimePriority`)
+    /// if the OS refuses, e.g. when the user lacks `rtprio` permissions on Linux.
+    pub realtime_priority: bool,
+    /// CPU core to pin the audio callback thread to
+    ///
+    /// `None` lets the OS move it between cores. Only supported on Linux and
+    /// Windows; if pinning fails (reported via `AudioEvent::CpuAffinity`) the
+    /// thread keeps running unpinned.
+    pub cpu_affinity: Option<usize>,
+    /// Let plugins that support it process in 64-bit (double precision)
+    ///
+    /// The graph itself stays `f32`; see `Plugin::set_double_precision`.
+    pub double_precision: bool,
 }
 
 impl Default for AudioConfig {
@@ -30,6 +82,12 @@ impl Default for AudioConfig {
             block_size: 256,
             input_channels: 2,
             output_channels: 2,
+            block_size_range: None,
+            device_name: None,
+            input_device_name: None,
+            realtime_priority: false,
+            cpu_affinity: None,
+            double_precision: false,
         }
     }
 }
@@ -43,5 +101,9 @@ mod tests {
         let config = AudioConfig::default();
         assert_eq!(config.sample_rate, 48000);
         assert_eq!(config.block_size, 256);
+        assert!(config.device_name.is_none());
+        assert!(!config.realtime_priority);
+        assert!(config.cpu_affinity.is_none());
+        assert!(!config.double_precision);
     }
 }
@@ -0,0 +1,244 @@
+//! Audio callback health: xrun detection and DSP load.
+//!
+//! cpal doesn't report underruns on every backend, so [`CallbackWatchdog`]
+//! infers them from timing: callbacks arrive one buffer apart, and one that
+//! arrives much later means the device ran dry in between. [`DspLoad`] tracks
+//! how much of each buffer's duration the graph spends processing - as it
+//! approaches 1.0 the callback is about to miss its deadline. [`CpuHistory`]
+//! breaks that down per graph node.
+
+use std::time::{Duration, Instant};
+
+/// How late a callback may be, relative to one buffer period, before it
+/// counts as an xrun
+///
+/// Backends deliver callbacks with some jitter, so only a clearly missed
+/// period counts.
+const XRUN_LATENESS: f64 = 1.75;
+
+/// Weight of each new measurement in the rolling DSP load
+const LOAD_SMOOTHING: f64 = 0.2;
+
+/// Seconds of audio between DSP load reports
+const LOAD_REPORT_SECONDS: f64 = 0.25;
+
+/// Blocks remembered by each [`CpuHistory`]
+pub const CPU_HISTORY_BLOCKS: usize = 32;
+
+/// Detects callbacks that arrive too late to have kept the device fed
+#[derive(Debug, Default)]
+pub struct CallbackWatchdog {
+    /// When the previous callback started
+    last_callback: Option<Instant>,
+    /// Audio duration of the previous callback's buffer
+    last_period: Duration,
+}
+
+impl CallbackWatchdog {
+    pub const fn new() -> Self {
+        Self {
+            last_callback: None,
+            last_period: Duration::ZERO,
+        }
+    }
+
+    /// Note a callback starting at `now` for a buffer lasting `period`
+    ///
+    /// Returns whether it came too late after the previous one (an xrun).
+    ///
+    /// REAL-TIME SAFE: Arithmetic only.
+    pub fn callback(&mut self, now: Instant, period: Duration) -> bool {
+        let late = self.last_callback.is_some_and(|last| {
+            now.saturating_duration_since(last) > self.last_period.mul_f64(XRUN_LATENESS)
+        });
+        self.last_callback = Some(now);
+        self.last_period = period;
+        late
+    }
+
+    /// Forget the previous callback, e.g. after a deliberate pause
+    pub const fn reset(&mut self) {
+        self.last_callback = None;
+    }
+}
+
+/// Rolling estimate of processing time over buffer duration
+#[derive(Debug, Default)]
+pub struct DspLoad {
+    /// Smoothed load (1.0 = processing takes the whole buffer)
+    fraction: f64,
+    /// Whether `fraction` holds a measurement yet
+    primed: bool,
+    /// Audio time since the last report
+    since_report: Duration,
+}
+
+impl DspLoad {
+    pub const fn new() -> Self {
+        Self {
+            fraction: 0.0,
+            primed: false,
+            since_report: Duration::ZERO,
+        }
+    }
+
+    /// Record one callback that took `elapsed` to process a buffer of `period`
+    ///
+    /// Returns the rolling load when a report is due (a few times a second).
+    ///
+    /// REAL-TIME SAFE: Arithmetic only.
+    pub fn record(&mut self, elapsed: Duration, period: Duration) -> Option<f32> {
+        if period.is_zero() {
+            return None;
+        }
+
+        let load = elapsed.as_secs_f64() / period.as_secs_f64();
+        self.fraction = if self.primed {
+            (load - self.fraction).mul_add(LOAD_SMOOTHING, self.fraction)
+        } else {
+            load
+        };
+        self.primed = true;
+
+        self.since_report += period;
+        if self.since_report.as_secs_f64() >= LOAD_REPORT_SECONDS {
+            self.since_report = Duration::ZERO;
+            Some(self.fraction as f32)
+        } else {
+            None
+        }
+    }
+}
+
+/// Ring of recent processing times, each as a fraction of the block budget
+///
+/// Averaging the last few blocks smooths out one-off spikes while still
+/// following a plugin that gets busier.
+#[derive(Debug, Clone, Copy)]
+pub struct CpuHistory {
+    /// Recent fractions, oldest overwritten first
+    samples: [f32; CPU_HISTORY_BLOCKS],
+    /// Slot the next measurement goes into
+    next: usize,
+    /// How many slots hold a measurement
+    filled: usize,
+}
+
+impl Default for CpuHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CpuHistory {
+    pub const fn new() -> Self {
+        Self {
+            samples: [0.0; CPU_HISTORY_BLOCKS],
+            next: 0,
+            filled: 0,
+        }
+    }
+
+    /// Record one block that took `elapsed` out of a budget of `period`
+    ///
+    /// REAL-TIME SAFE: Arithmetic only.
+    pub fn record(&mut self, elapsed: Duration, period: Duration) {
+        if period.is_zero() {
+            return;
+        }
+        self.samples[self.next] = (elapsed.as_secs_f64() / period.as_secs_f64()) as f32;
+        self.next = (self.next + 1) % CPU_HISTORY_BLOCKS;
+        self.filled = (self.filled + 1).min(CPU_HISTORY_BLOCKS);
+    }
+
+    /// Average fraction of the block budget over the remembered blocks
+    ///
+    /// 0.0 before anything has been recorded.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // At most CPU_HISTORY_BLOCKS
+    pub fn average(&self) -> f32 {
+        if self.filled == 0 {
+            return 0.0;
+        }
+        self.samples[..self.filled].iter().sum::<f32>() / self.filled as f32
+    }
+
+    /// Forget all measurements
+    pub const fn clear(&mut self) {
+        self.next = 0;
+        self.filled = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERIOD: Duration = Duration::from_millis(5);
+
+    #[test]
+    fn test_on_time_callbacks_are_not_xruns() {
+        let mut watchdog = CallbackWatchdog::new();
+        let start = Instant::now();
+
+        assert!(!watchdog.callback(start, PERIOD));
+        assert!(!watchdog.callback(start + PERIOD, PERIOD));
+        // A little jitter is fine
+        assert!(!watchdog.callback(start + PERIOD * 5 / 2, PERIOD));
+    }
+
+    #[test]
+    fn test_late_callback_is_an_xrun() {
+        let mut watchdog = CallbackWatchdog::new();
+        let start = Instant::now();
+
+        watchdog.callback(start, PERIOD);
+        assert!(watchdog.callback(start + PERIOD * 3, PERIOD));
+
+        // Nothing to compare against after a reset
+        watchdog.reset();
+        assert!(!watchdog.callback(start + PERIOD * 10, PERIOD));
+    }
+
+    #[test]
+    fn test_load_is_reported_periodically() {
+        let mut load = DspLoad::new();
+        let callbacks_per_report = (LOAD_REPORT_SECONDS / PERIOD.as_secs_f64()).round() as usize;
+
+        let reports: Vec<f32> = (0..callbacks_per_report * 2)
+            .filter_map(|_| load.record(PERIOD / 2, PERIOD))
+            .collect();
+        assert_eq!(reports.len(), 2);
+        assert!((reports[0] - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_load_follows_heavier_processing() {
+        let mut load = DspLoad::new();
+        let mut last = None;
+        load.record(PERIOD / 10, PERIOD);
+        for _ in 0..200 {
+            last = load.record(PERIOD * 9 / 10, PERIOD).or(last);
+        }
+        assert!((last.unwrap() - 0.9).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_cpu_history_averages_recent_blocks() {
+        let mut history = CpuHistory::new();
+        assert_eq!(history.average(), 0.0);
+
+        history.record(PERIOD / 4, PERIOD);
+        history.record(PERIOD * 3 / 4, PERIOD);
+        assert!((history.average() - 0.5).abs() < 1e-6);
+
+        // Older blocks drop out once the ring wraps
+        for _ in 0..CPU_HISTORY_BLOCKS {
+            history.record(PERIOD / 10, PERIOD);
+        }
+        assert!((history.average() - 0.1).abs() < 1e-6);
+
+        history.clear();
+        assert_eq!(history.average(), 0.0);
+    }
+}
@@ -0,0 +1,328 @@
+//! Standard MIDI file playback for offline rendering.
+//!
+//! [`MidiSequence`] converts the notes of a `.mid` file into sample-timed
+//! [`Event::NoteOn`]/[`Event::NoteOff`] events, so instrument plugins can be
+//! rendered headlessly. Each block's events are handed out with their
+//! `sample_offset` relative to the start of that block.
+//!
+//! Tempo handling is basic: the first tempo event in the file applies to the
+//! whole sequence (120 BPM if there is none). Tempo changes are ignored.
+
+use midly::{Format, MetaMessage, MidiMessage, Smf, Timing, TrackEventKind};
+use std::path::Path;
+use vvdaw_core::{Frames, SampleRate};
+use vvdaw_plugin::{Event, EventBuffer};
+
+/// Tempo assumed when a file has no tempo event (120 BPM)
+const DEFAULT_MICROS_PER_BEAT: u64 = 500_000;
+
+/// Note events of a MIDI file, timed in frames at a fixed sample rate
+#[derive(Debug, Clone)]
+pub struct MidiSequence {
+    /// Events sorted by absolute frame (file order within a frame)
+    events: Vec<(u64, Event)>,
+}
+
+impl MidiSequence {
+    /// Load a standard MIDI file, timing its notes at `sample_rate`
+    pub fn load(path: impl AsRef<Path>, sample_rate: SampleRate) -> Result<Self, MidiFileError> {
+        let bytes = std::fs::read(path.as_ref()).map_err(|e| MidiFileError::Io(e.to_string()))?;
+        Self::parse(&bytes, sample_rate)
+    }
+
+    /// Parse standard MIDI file data, timing its notes at `sample_rate`
+    ///
+    /// All tracks are merged. Tracks of a sequential (format 2) file are
+    /// played one after another.
+    pub fn parse(bytes: &[u8], sample_rate: SampleRate) -> Result<Self, MidiFileError> {
+        if sample_rate == 0 {
+            return Err(MidiFileError::InvalidData(
+                "Sample rate must be greater than 0".to_string(),
+            ));
+        }
+
+        let smf = Smf::parse(bytes).map_err(|e| MidiFileError::Parse(e.to_string()))?;
+
+        let micros_per_beat = smf
+            .tracks
+            .iter()
+            .flatten()
+            .find_map(|event| match event.kind {
+                TrackEventKind::Meta(MetaMessage::Tempo(tempo)) => Some(u64::from(tempo.as_int())),
+                _ => None,
+            })
+            .unwrap_or(DEFAULT_MICROS_PER_BEAT);
+
+        let to_frame = |tick: u64| -> Result<u64, MidiFileError> {
+            match smf.header.timing {
+                Timing::Metrical(ticks_per_beat) => {
+                    let ticks_per_beat = u64::from(ticks_per_beat.as_int());
+                    if ticks_per_beat == 0 {
+                        return Err(MidiFileError::InvalidData(
+                            "File has 0 ticks per beat".to_string(),
+                        ));
+                    }
+                    // Integer math keeps long files exact: frames = ticks * seconds/tick * rate
+                    let numerator =
+                        u128::from(tick) * u128::from(micros_per_beat) * u128::from(sample_rate);
+                    let denominator = u128::from(ticks_per_beat) * 1_000_000;
+                    Ok(((numerator + denominator / 2) / denominator) as u64)
+                }
+                Timing::Timecode(fps, subframes) => {
+                    let ticks_per_second = f64::from(fps.as_f32()) * f64::from(subframes);
+                    if ticks_per_second <= 0.0 {
+                        return Err(MidiFileError::InvalidData(
+                            "File has 0 ticks per second".to_string(),
+                        ));
+                    }
+                    Ok((tick as f64 * f64::from(sample_rate) / ticks_per_second).round() as u64)
+                }
+            }
+        };
+
+        let mut events = Vec::new();
+        let mut track_start = 0_u64;
+        for track in &smf.tracks {
+            let mut tick = track_start;
+            for event in track {
+                tick += u64::from(event.delta.as_int());
+
+                let TrackEventKind::Midi { channel, message } = event.kind else {
+                    continue;
+                };
+                let channel = channel.as_int();
+                let note_event = match message {
+                    MidiMessage::NoteOn { key, vel } if vel.as_int() > 0 => Event::NoteOn {
+                        channel,
+                        note: key.as_int(),
+                        velocity: f32::from(vel.as_int()) / 127.0,
+                        sample_offset: 0,
+                    },
+                    // Note-on with velocity 0 is a note-off by convention
+                    MidiMessage::NoteOn { key, .. } | MidiMessage::NoteOff { key, .. } => {
+                        Event::NoteOff {
+                            channel,
+                            note: key.as_int(),
+                            sample_offset: 0,
+                        }
+                    }
+                    _ => continue,
+                };
+                events.push((to_frame(tick)?, note_event));
+            }
+
+            if smf.header.format == Format::Sequential {
+                track_start = tick;
+            }
+        }
+
+        // Stable, so simultaneous events keep their file order
+        events.sort_by_key(|&(frame, _)| frame);
+
+        tracing::debug!("Parsed {} note events from MIDI file", events.len());
+
+        Ok(Self { events })
+    }
+
+    /// Number of note events in the sequence
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Whether the sequence has no note events
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Frame just after the last event (0 for an empty sequence)
+    pub fn end_frame(&self) -> u64 {
+        self.events.last().map_or(0, |&(frame, _)| frame + 1)
+    }
+
+    /// Replace `buffer`'s contents with the events of one block
+    ///
+    /// Covers frames `start_frame..start_frame + frames`, with each event's
+    /// `sample_offset` relative to `start_frame`.
+    pub fn fill_block(&self, start_frame: u64, frames: Frames, buffer: &mut EventBuffer) {
+        buffer.clear();
+
+        let end_frame = start_frame + frames as u64;
+        let first = self
+            .events
+            .partition_point(|&(frame, _)| frame < start_frame);
+
+        for (frame, event) in self.events[first..]
+            .iter()
+            .take_while(|&&(frame, _)| frame < end_frame)
+        {
+            let mut event = event.clone();
+            match &mut event {
+                Event::NoteOn { sample_offset, .. }
+                | Event::NoteOff { sample_offset, .. }
+                | Event::ParamChange { sample_offset, .. } => {
+                    *sample_offset = (frame - start_frame) as u32;
+                }
+            }
+            buffer.events.push(event);
+        }
+    }
+}
+
+/// Errors that can occur while reading a MIDI file
+#[derive(Debug, thiserror::Error)]
+pub enum MidiFileError {
+    /// I/O error reading the file
+    #[error("I/O error: {0}")]
+    Io(String),
+
+    /// The data is not a valid standard MIDI file
+    #[error("Failed to parse MIDI file: {0}")]
+    Parse(String),
+
+    /// The file parsed but can't be timed
+    #[error("Invalid MIDI data: {0}")]
+    InvalidData(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use midly::num::{u4, u7, u15, u24, u28};
+    use midly::{Header, TrackEvent};
+
+    fn note(delta: u32, key: u8, vel: u8) -> TrackEvent<'static> {
+        TrackEvent {
+            delta: u28::new(delta),
+            kind: TrackEventKind::Midi {
+                channel: u4::new(0),
+                message: MidiMessage::NoteOn {
+                    key: u7::new(key),
+                    vel: u7::new(vel),
+                },
+            },
+        }
+    }
+
+    fn tempo(micros_per_beat: u32) -> TrackEvent<'static> {
+        TrackEvent {
+            delta: u28::new(0),
+            kind: TrackEventKind::Meta(MetaMessage::Tempo(u24::new(micros_per_beat))),
+        }
+    }
+
+    fn midi_bytes(format: Format, tracks: Vec<Vec<TrackEvent<'static>>>) -> Vec<u8> {
+        let mut smf = Smf::new(Header::new(format, Timing::Metrical(u15::new(480))));
+        smf.tracks = tracks;
+        let mut bytes = Vec::new();
+        smf.write_std(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn offsets(buffer: &EventBuffer) -> Vec<(u8, bool, u32)> {
+        buffer
+            .events
+            .iter()
+            .map(|event| match *event {
+                Event::NoteOn {
+                    note,
+                    sample_offset,
+                    ..
+                } => (note, true, sample_offset),
+                Event::NoteOff {
+                    note,
+                    sample_offset,
+                    ..
+                } => (note, false, sample_offset),
+                Event::ParamChange { .. } => panic!("unexpected parameter change"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_notes_timed_by_tempo() {
+        // 480 ticks per beat at 120 BPM: one beat is 24000 frames at 48kHz
+        let bytes = midi_bytes(
+            Format::SingleTrack,
+            vec![vec![tempo(500_000), note(0, 60, 100), note(480, 60, 0)]],
+        );
+        let sequence = MidiSequence::parse(&bytes, 48000).unwrap();
+        assert_eq!(sequence.len(), 2);
+        assert_eq!(sequence.end_frame(), 24001);
+
+        let mut buffer = EventBuffer::new();
+        sequence.fill_block(0, 512, &mut buffer);
+        assert_eq!(offsets(&buffer), vec![(60, true, 0)]);
+
+        // The note-off (velocity 0) lands mid-block with a relative offset
+        sequence.fill_block(23_900, 512, &mut buffer);
+        assert_eq!(offsets(&buffer), vec![(60, false, 100)]);
+    }
+
+    #[test]
+    fn test_missing_tempo_defaults_to_120_bpm() {
+        let bytes = midi_bytes(
+            Format::SingleTrack,
+            vec![vec![note(240, 64, 90), note(240, 64, 0)]],
+        );
+        let sequence = MidiSequence::parse(&bytes, 44100).unwrap();
+
+        let mut buffer = EventBuffer::new();
+        sequence.fill_block(11_000, 100, &mut buffer);
+        assert_eq!(offsets(&buffer), vec![(64, true, 25)]);
+    }
+
+    #[test]
+    fn test_parallel_tracks_merge() {
+        let bytes = midi_bytes(
+            Format::Parallel,
+            vec![
+                vec![tempo(250_000), note(480, 60, 100)],
+                vec![note(240, 67, 100), note(240, 67, 0)],
+            ],
+        );
+        let sequence = MidiSequence::parse(&bytes, 48000).unwrap();
+
+        // Beat 1 is frame 12000 at 240 BPM; both tracks have an event there
+        let mut buffer = EventBuffer::new();
+        sequence.fill_block(0, 12_001, &mut buffer);
+        assert_eq!(
+            offsets(&buffer),
+            vec![(67, true, 6000), (60, true, 12_000), (67, false, 12_000)]
+        );
+    }
+
+    #[test]
+    fn test_sequential_tracks_follow_each_other() {
+        let bytes = midi_bytes(
+            Format::Sequential,
+            vec![vec![note(480, 60, 100)], vec![note(480, 62, 100)]],
+        );
+        let sequence = MidiSequence::parse(&bytes, 48000).unwrap();
+        assert_eq!(sequence.end_frame(), 48001);
+    }
+
+    #[test]
+    fn test_fill_block_clears_previous_events() {
+        let bytes = midi_bytes(Format::SingleTrack, vec![vec![note(0, 60, 100)]]);
+        let sequence = MidiSequence::parse(&bytes, 48000).unwrap();
+
+        let mut buffer = EventBuffer::new();
+        sequence.fill_block(0, 256, &mut buffer);
+        assert_eq!(buffer.events.len(), 1);
+        sequence.fill_block(256, 256, &mut buffer);
+        assert!(buffer.events.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_data_is_rejected() {
+        assert!(matches!(
+            MidiSequence::parse(b"not a midi file", 48000),
+            Err(MidiFileError::Parse(_))
+        ));
+        let bytes = midi_bytes(Format::SingleTrack, vec![vec![note(0, 60, 100)]]);
+        assert!(matches!(
+            MidiSequence::parse(&bytes, 0),
+            Err(MidiFileError::InvalidData(_))
+        ));
+    }
+}
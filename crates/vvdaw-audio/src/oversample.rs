@@ -0,0 +1,221 @@
+//! Integer-factor oversampling for offline rendering.
+//!
+//! Nonlinear processors (distortion, saturation) create harmonics above the
+//! Nyquist frequency, which fold back down as aliasing. Running them at a
+//! multiple of the file's rate leaves room for those harmonics, and filtering
+//! them out before coming back down keeps them from folding.
+//!
+//! [`Oversampler`] converts whole interleaved buffers up and back down with a
+//! windowed-sinc low-pass applied polyphase, so only the samples that are
+//! kept get computed. The filter is linear-phase; its delay is trimmed on the
+//! way down, so the output lines up with the input.
+
+use std::f64::consts::PI;
+use vvdaw_core::{Frames, Sample};
+
+/// Filter taps per polyphase branch
+///
+/// Also the conversion latency (in frames at the original rate) that
+/// [`Oversampler::downsample`] trims.
+const TAPS_PER_PHASE: usize = 64;
+
+/// Cutoff as a share of the original rate's Nyquist frequency
+///
+/// Just under 1.0, so the transition band ends before the aliases start.
+const CUTOFF: f64 = 0.9;
+
+/// Converts interleaved audio to `factor` times its rate and back
+#[derive(Debug, Clone)]
+pub struct Oversampler {
+    factor: usize,
+    /// Low-pass prototype at the oversampled rate (unity DC gain)
+    taps: Vec<f64>,
+}
+
+impl Oversampler {
+    /// Oversampler for an integer `factor` (a factor of 1 passes audio through)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is 0.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // Tap indices are tiny
+    pub fn new(factor: usize) -> Self {
+        assert!(factor > 0, "Oversampling factor must be at least 1");
+
+        // Odd length, so the delay is a whole number of original frames
+        let len = TAPS_PER_PHASE * factor + 1;
+        let center = (len / 2) as f64;
+        let cutoff = CUTOFF * 0.5 / factor as f64;
+
+        let mut taps: Vec<f64> = (0..len)
+            .map(|i| {
+                let t = i as f64 - center;
+                let sinc = if t == 0.0 {
+                    2.0 * cutoff
+                } else {
+                    (2.0 * PI * cutoff * t).sin() / (PI * t)
+                };
+                // Blackman window
+                let phase = 2.0 * PI * i as f64 / (len - 1) as f64;
+                let window =
+                    0.08f64.mul_add((2.0 * phase).cos(), 0.5f64.mul_add(-phase.cos(), 0.42));
+                sinc * window
+            })
+            .collect();
+
+        let sum: f64 = taps.iter().sum();
+        for tap in &mut taps {
+            *tap /= sum;
+        }
+
+        Self { factor, taps }
+    }
+
+    /// The oversampling factor
+    #[must_use]
+    pub const fn factor(&self) -> usize {
+        self.factor
+    }
+
+    /// Frames (at the original rate) the up and down filters delay the audio by
+    ///
+    /// [`Oversampler::upsample`] adds this many frames and
+    /// [`Oversampler::downsample`] removes them again.
+    #[must_use]
+    pub const fn latency(&self) -> Frames {
+        if self.factor == 1 { 0 } else { TAPS_PER_PHASE }
+    }
+
+    /// Convert interleaved `input` to `factor` times its rate
+    ///
+    /// The input is followed by [`Oversampler::latency`] frames of silence,
+    /// so the filter rings out completely: the output holds
+    /// `(frames + latency) * factor` frames. A trailing partial frame is
+    /// ignored.
+    ///
+    /// NOT real-time safe: allocates the output.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub fn upsample(&self, input: &[Sample], channels: usize) -> Vec<Sample> {
+        if self.factor == 1 || channels == 0 {
+            return input.to_vec();
+        }
+
+        let frames = input.len() / channels;
+        let out_frames = (frames + self.latency()) * self.factor;
+        let mut output = vec![0.0; out_frames * channels];
+        // Zero stuffing leaves 1/factor of the energy - make it up in the gain
+        let gain = self.factor as f64;
+
+        for (out_frame, out) in output.chunks_exact_mut(channels).enumerate() {
+            // Branch `phase` of the filter sees only the original frames
+            let (frame, phase) = (out_frame / self.factor, out_frame % self.factor);
+            for (channel, sample) in out.iter_mut().enumerate() {
+                let acc: f64 = self
+                    .taps
+                    .iter()
+                    .skip(phase)
+                    .step_by(self.factor)
+                    .zip((0..=frame).rev())
+                    .filter(|&(_, source)| source < frames)
+                    .map(|(tap, source)| tap * f64::from(input[source * channels + channel]))
+                    .sum();
+                *sample = (acc * gain) as Sample;
+            }
+        }
+        output
+    }
+
+    /// Filter interleaved oversampled `input` and bring it back to the original rate
+    ///
+    /// Removes everything above the original Nyquist frequency, then keeps
+    /// every `factor`th frame. The filters' delay is trimmed, so after
+    /// [`Oversampler::upsample`] the output has the original length and lines
+    /// up with the original audio.
+    ///
+    /// NOT real-time safe: allocates the output.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn downsample(&self, input: &[Sample], channels: usize) -> Vec<Sample> {
+        if self.factor == 1 || channels == 0 {
+            return input.to_vec();
+        }
+
+        let frames = input.len() / channels;
+        let out_frames = (frames / self.factor).saturating_sub(self.latency());
+        let mut output = vec![0.0; out_frames * channels];
+
+        for (out_frame, out) in output.chunks_exact_mut(channels).enumerate() {
+            let center = (out_frame + self.latency()) * self.factor;
+            for (channel, sample) in out.iter_mut().enumerate() {
+                let acc: f64 = self
+                    .taps
+                    .iter()
+                    .zip((0..=center).rev())
+                    .map(|(tap, source)| tap * f64::from(input[source * channels + channel]))
+                    .sum();
+                *sample = acc as Sample;
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Interleaved stereo sine at `frequency` cycles per frame, right channel inverted
+    fn sine(frequency: f64, frames: usize) -> Vec<Sample> {
+        (0..frames)
+            .flat_map(|frame| {
+                let value = (2.0 * PI * frequency * frame as f64).sin() as Sample;
+                [value, -value]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_round_trip_keeps_audio_aligned() {
+        // 1 kHz at 48 kHz
+        let input = sine(1000.0 / 48000.0, 2000);
+        for factor in [2, 4, 8] {
+            let oversampler = Oversampler::new(factor);
+            let up = oversampler.upsample(&input, 2);
+            assert_eq!(up.len(), (2000 + oversampler.latency()) * factor * 2);
+
+            let down = oversampler.downsample(&up, 2);
+            assert_eq!(down.len(), input.len());
+            // Away from the edges, where the filter sees the whole window
+            let error = down[400..3600]
+                .iter()
+                .zip(&input[400..3600])
+                .map(|(a, b)| (a - b).abs())
+                .fold(0.0, f32::max);
+            assert!(error < 1e-3, "factor {factor}: error {error}");
+        }
+    }
+
+    #[test]
+    fn test_downsample_removes_content_above_nyquist() {
+        // 30 kHz at 96 kHz would alias to 18 kHz at 48 kHz
+        let oversampler = Oversampler::new(2);
+        let input = sine(30000.0 / 96000.0, 8000);
+        let down = oversampler.downsample(&input, 2);
+
+        let peak = down[400..]
+            .iter()
+            .fold(0.0, |peak: f32, s| peak.max(s.abs()));
+        assert!(peak < 1e-3, "aliased peak {peak}");
+    }
+
+    #[test]
+    fn test_factor_one_passes_through() {
+        let oversampler = Oversampler::new(1);
+        let input = sine(0.01, 100);
+        assert_eq!(oversampler.latency(), 0);
+        assert_eq!(oversampler.upsample(&input, 2), input);
+        assert_eq!(oversampler.downsample(&input, 2), input);
+    }
+}
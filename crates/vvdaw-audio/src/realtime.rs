@@ -0,0 +1,355 @@
+//! Real-time scheduling for the audio callback thread.
+//!
+//! The audio callback runs on a thread owned by cpal at default OS priority,
+//! which risks dropouts when the system is under load. This module requests
+//! real-time scheduling for the *current* thread ([`promote_current_thread`]):
+//!
+//! - **Linux**: `SCHED_FIFO` via `pthread_setschedparam` (needs `CAP_SYS_NICE`
+//!   or an `rtprio` limit, e.g. membership of the `audio` group)
+//! - **macOS**: `THREAD_TIME_CONSTRAINT_POLICY` sized to the audio period
+//! - **Windows**: the MMCSS "Pro Audio" task via `AvSetMmThreadCharacteristicsW`
+//! - **Other platforms**: not supported yet (returns an error)
+//!
+//! Elevation is best-effort - callers should fall back to normal priority
+//! when it fails.
+//!
+//! [`pin_current_thread`] can also pin the thread to a single CPU core
+//! (Linux and Windows; macOS only takes affinity hints, so it isn't
+//! supported there), keeping it off cores busy with other work.
+//!
+//! # Permissions
+//!
+//! On Linux, unprivileged users are refused `SCHED_FIFO` unless their
+//! `RLIMIT_RTPRIO` allows it. Most distributions grant that to the `audio`
+//! group; otherwise add a line such as `@audio - rtprio 95` to
+//! `/etc/security/limits.conf` (or a file in `limits.d`), add the user to
+//! the group and log in again. `ulimit -r` shows the current limit. macOS
+//! grants time-constraint scheduling to any process, and Windows lets any
+//! process join an MMCSS task (while the Multimedia Class Scheduler service
+//! runs).
+
+/// Priority requested for `SCHED_FIFO` (clamped to the range the OS allows)
+///
+/// High enough to preempt normal threads, but below the kernel's own
+/// real-time threads (IRQ handlers typically run at 50-99).
+#[cfg(target_os = "linux")]
+const FIFO_PRIORITY: libc::c_int = 40;
+
+/// MMCSS task the audio thread joins, as configured under
+/// `HKLM\SOFTWARE\Microsoft\Windows NT\CurrentVersion\Multimedia\SystemProfile\Tasks`
+#[cfg(target_os = "windows")]
+const MMCSS_TASK: &str = "Pro Audio";
+
+/// Request real-time scheduling for the calling thread
+///
+/// `sample_rate` and `block_size` describe the audio period, which is used
+/// by platforms that schedule real-time threads by deadline (macOS).
+///
+/// Returns a description of the scheduling the thread got, for logging.
+///
+/// NOT real-time safe: makes a system call and allocates.
+/// Call it once when the audio thread starts, not on every callback.
+///
+/// # Errors
+///
+/// Returns an error describing why elevation was refused (e.g. missing
+/// permissions) or that the platform isn't supported.
+#[cfg(target_os = "linux")]
+#[allow(unsafe_code)]
+pub fn promote_current_thread(_sample_rate: u32, _block_size: usize) -> Result<String, String> {
+    // SAFETY: sched_get_priority_min/max only read a constant table in the kernel.
+    let (min, max) = unsafe {
+        (
+            libc::sched_get_priority_min(libc::SCHED_FIFO),
+            libc::sched_get_priority_max(libc::SCHED_FIFO),
+        )
+    };
+    if min < 0 || max < 0 {
+        return Err(format!(
+            "SCHED_FIFO unavailable: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+
+    let param = libc::sched_param {
+        sched_priority: FIFO_PRIORITY.clamp(min, max),
+    };
+
+    // SAFETY: pthread_self() always returns a valid handle for the calling thread,
+    // and `param` is a valid, initialized sched_param that outlives the call.
+    let result = unsafe {
+        libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &raw const param)
+    };
+    if result != 0 {
+        return Err(format!(
+            "pthread_setschedparam(SCHED_FIFO) failed: {}",
+            std::io::Error::from_raw_os_error(result)
+        ));
+    }
+
+    // Read back what the kernel actually applied
+    let mut policy = 0;
+    let mut applied = libc::sched_param { sched_priority: 0 };
+    // SAFETY: pthread_self() is valid, and both out-pointers are valid and writable.
+    let result = unsafe {
+        libc::pthread_getschedparam(libc::pthread_self(), &raw mut policy, &raw mut applied)
+    };
+    if result != 0 || policy != libc::SCHED_FIFO {
+        return Ok(format!("SCHED_FIFO priority {}", param.sched_priority));
+    }
+    Ok(format!(
+        "SCHED_FIFO priority {} (allowed {min}-{max})",
+        applied.sched_priority
+    ))
+}
+
+/// Request real-time scheduling for the calling thread
+///
+/// `sample_rate` and `block_size` describe the audio period, which is used
+/// by platforms that schedule real-time threads by deadline (macOS).
+///
+/// Returns a description of the scheduling the thread got, for logging.
+///
+/// NOT real-time safe: makes a system call and allocates.
+/// Call it once when the audio thread starts, not on every callback.
+///
+/// # Errors
+///
+/// Returns an error describing why elevation was refused (e.g. missing
+/// permissions) or that the platform isn't supported.
+#[cfg(target_os = "macos")]
+#[allow(unsafe_code)]
+pub fn promote_current_thread(sample_rate: u32, block_size: usize) -> Result<String, String> {
+    if sample_rate == 0 || block_size == 0 {
+        return Err("Invalid audio period (sample rate or block size is 0)".to_string());
+    }
+
+    let mut timebase = libc::mach_timebase_info { numer: 0, denom: 0 };
+    // SAFETY: `timebase` is a valid, writable mach_timebase_info struct.
+    if unsafe { libc::mach_timebase_info(&raw mut timebase) } != libc::KERN_SUCCESS
+        || timebase.numer == 0
+    {
+        return Err("mach_timebase_info failed".to_string());
+    }
+
+    // Convert the audio period from nanoseconds to mach absolute time units
+    let period_ns = block_size as f64 / f64::from(sample_rate) * 1_000_000_000.0;
+    let period = (period_ns * f64::from(timebase.denom) / f64::from(timebase.numer)) as u32;
+
+    // Same shape CoreAudio uses for its own IO threads: we may use up to half
+    // the period for computation and must finish within the full period.
+    let mut policy = libc::thread_time_constraint_policy {
+        period,
+        computation: period / 2,
+        constraint: period,
+        preemptible: 1,
+    };
+
+    // SAFETY: pthread_mach_thread_np(pthread_self()) is the calling thread's mach port,
+    // and `policy` is a valid struct whose size matches THREAD_TIME_CONSTRAINT_POLICY_COUNT.
+    let result = unsafe {
+        libc::thread_policy_set(
+            libc::pthread_mach_thread_np(libc::pthread_self()),
+            libc::THREAD_TIME_CONSTRAINT_POLICY as libc::thread_policy_flavor_t,
+            (&raw mut policy).cast(),
+            libc::THREAD_TIME_CONSTRAINT_POLICY_COUNT,
+        )
+    };
+    if result != libc::KERN_SUCCESS {
+        return Err(format!(
+            "thread_policy_set(THREAD_TIME_CONSTRAINT_POLICY) failed: kern_return_t {result}"
+        ));
+    }
+
+    Ok(format!(
+        "time-constraint policy, {:.0} us period",
+        period_ns / 1000.0
+    ))
+}
+
+/// Request real-time scheduling for the calling thread
+///
+/// `sample_rate` and `block_size` describe the audio period, which is used
+/// by platforms that schedule real-time threads by deadline (macOS).
+///
+/// Returns a description of the scheduling the thread got, for logging.
+///
+/// The MMCSS registration lasts until the thread exits, so the task handle
+/// isn't kept to revert it.
+///
+/// NOT real-time safe: makes a system call and allocates.
+/// Call it once when the audio thread starts, not on every callback.
+///
+/// # Errors
+///
+/// Returns an error describing why elevation was refused (e.g. the
+/// Multimedia Class Scheduler service isn't running).
+#[cfg(target_os = "windows")]
+#[allow(unsafe_code)]
+pub fn promote_current_thread(_sample_rate: u32, _block_size: usize) -> Result<String, String> {
+    use windows_sys::Win32::System::Threading::AvSetMmThreadCharacteristicsW;
+
+    let task: Vec<u16> = MMCSS_TASK
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut task_index = 0;
+    // SAFETY: `task` is a NUL-terminated UTF-16 string and `task_index` a valid,
+    // writable u32; both outlive the call.
+    let handle = unsafe { AvSetMmThreadCharacteristicsW(task.as_ptr(), &raw mut task_index) };
+    if handle.is_null() {
+        return Err(format!(
+            "AvSetMmThreadCharacteristicsW(\"{MMCSS_TASK}\") failed: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+
+    Ok(format!("MMCSS \"{MMCSS_TASK}\" task {task_index}"))
+}
+
+/// Request real-time scheduling for the calling thread
+///
+/// Not yet supported on this platform - always returns an error so callers
+/// fall back to normal priority.
+///
+/// # Errors
+///
+/// Always returns an error on this platform.
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn promote_current_thread(_sample_rate: u32, _block_size: usize) -> Result<String, String> {
+    Err("Real-time scheduling is not supported on this platform".to_string())
+}
+
+/// Pin the calling thread to CPU core `cpu`
+///
+/// Returns a description of the affinity the thread got, for logging.
+///
+/// NOT real-time safe: makes a system call and allocates.
+/// Call it once when the audio thread starts, not on every callback.
+///
+/// # Errors
+///
+/// Returns an error if the core doesn't exist or isn't available to the
+/// process (e.g. outside its cpuset).
+#[cfg(target_os = "linux")]
+#[allow(unsafe_code)]
+pub fn pin_current_thread(cpu: usize) -> Result<String, String> {
+    if cpu >= libc::CPU_SETSIZE as usize {
+        return Err(format!("CPU {cpu} is out of range"));
+    }
+
+    // SAFETY: cpu_set_t is a plain bitmask, for which all zeroes is the empty set.
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    // SAFETY: `cpu` was checked against CPU_SETSIZE, the capacity of `set`.
+    unsafe { libc::CPU_SET(cpu, &mut set) };
+
+    // SAFETY: pthread_self() always returns a valid handle for the calling thread,
+    // and `set` is a valid cpu_set_t of the size passed.
+    let result = unsafe {
+        libc::pthread_setaffinity_np(
+            libc::pthread_self(),
+            std::mem::size_of::<libc::cpu_set_t>(),
+            &raw const set,
+        )
+    };
+    if result != 0 {
+        return Err(format!(
+            "pthread_setaffinity_np(CPU {cpu}) failed: {}",
+            std::io::Error::from_raw_os_error(result)
+        ));
+    }
+
+    Ok(format!("CPU {cpu}"))
+}
+
+/// Pin the calling thread to CPU core `cpu`
+///
+/// Returns a description of the affinity the thread got, for logging.
+///
+/// NOT real-time safe: makes a system call and allocates.
+/// Call it once when the audio thread starts, not on every callback.
+///
+/// # Errors
+///
+/// Returns an error if the core doesn't exist or isn't available to the
+/// process.
+#[cfg(target_os = "windows")]
+#[allow(unsafe_code)]
+pub fn pin_current_thread(cpu: usize) -> Result<String, String> {
+    use windows_sys::Win32::System::Threading::{GetCurrentThread, SetThreadAffinityMask};
+
+    // Masks only cover the cores of one processor group
+    if cpu >= usize::BITS as usize {
+        return Err(format!("CPU {cpu} is out of range"));
+    }
+
+    // SAFETY: GetCurrentThread returns a pseudo handle that is always valid for
+    // the calling thread, and needs no closing.
+    let previous = unsafe { SetThreadAffinityMask(GetCurrentThread(), 1 << cpu) };
+    if previous == 0 {
+        return Err(format!(
+            "SetThreadAffinityMask(CPU {cpu}) failed: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+
+    Ok(format!("CPU {cpu}"))
+}
+
+/// Pin the calling thread to CPU core `cpu`
+///
+/// Not supported on this platform - always returns an error so callers
+/// leave the thread wherever the OS schedules it.
+///
+/// # Errors
+///
+/// Always returns an error on this platform.
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+pub fn pin_current_thread(cpu: usize) -> Result<String, String> {
+    Err(format!(
+        "Pinning to CPU {cpu} is not supported on this platform"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_promote_reports_result_without_panicking() {
+        // Run on a throwaway thread so a successful elevation doesn't leak
+        // into the test harness. Whether it succeeds depends on the
+        // environment's permissions (CI usually refuses), so only check
+        // that we get a well-formed result either way.
+        let result = std::thread::spawn(|| promote_current_thread(48000, 256))
+            .join()
+            .expect("Elevation attempt must not panic");
+
+        match result {
+            Ok(granted) => {
+                eprintln!("Real-time scheduling granted: {granted}");
+                assert!(!granted.is_empty(), "Success should say what was granted");
+            }
+            Err(e) => {
+                eprintln!("Real-time scheduling refused: {e}");
+                assert!(!e.is_empty(), "Failure should explain why");
+            }
+        }
+    }
+
+    #[test]
+    fn test_pin_refuses_missing_core() {
+        // On a throwaway thread, like elevation above. CPU 0 may still be
+        // outside a restricted cpuset, so only a missing core must fail.
+        let (first, missing) =
+            std::thread::spawn(|| (pin_current_thread(0), pin_current_thread(usize::MAX)))
+                .join()
+                .expect("Pinning attempt must not panic");
+
+        match first {
+            Ok(pinned) => eprintln!("Pinned to {pinned}"),
+            Err(e) => eprintln!("Pinning to CPU 0 refused: {e}"),
+        }
+        assert!(missing.is_err());
+    }
+}
@@ -0,0 +1,475 @@
+//! Recording the engine output to a WAV file.
+//!
+//! The audio thread can't touch the disk, so recording is split in two:
+//! [`Recorder`] lives in the audio callback and copies each finished output
+//! block into a lock-free ring buffer, and a writer thread drains that buffer
+//! into a `hound::WavWriter`. Start/stop requests travel on a second ring,
+//! and each stop carries the sample count that ends its take, so a take
+//! never picks up samples of the next one. The writer reports back
+//! ([`AudioEvent::RecordingStopped`], [`AudioEvent::Error`]) on a third, which
+//! the callback forwards to the UI.
+//!
+//! Takes are written as 32-bit float WAV at the engine's sample rate and
+//! channel count. If the writer falls behind (or the disk fills up) the audio
+//! thread carries on: blocks that don't fit are dropped and reported when the
+//! take stops.
+
+use anyhow::{Context, Result};
+use hound::{SampleFormat, WavSpec, WavWriter};
+use rtrb::{Consumer, Producer, RingBuffer};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use vvdaw_comms::AudioEvent;
+use vvdaw_core::SampleRate;
+
+/// Seconds of output buffered between the audio thread and the writer
+pub const RECORD_BUFFER_SECONDS: usize = 2;
+
+/// Start/stop requests buffered for the writer
+const CONTROL_CAPACITY: usize = 16;
+
+/// Reports buffered for the audio thread to forward
+const REPORT_CAPACITY: usize = 16;
+
+/// How long the writer thread sleeps between drains
+const WRITER_IDLE: Duration = Duration::from_millis(10);
+
+/// Requests from the audio thread to the writer thread
+enum Control {
+    /// Start writing a new file (any previous take was stopped first)
+    Start {
+        path: PathBuf,
+        sample_rate: SampleRate,
+        channels: u16,
+    },
+    /// Write out the rest of the take and finish it
+    Stop {
+        /// Total samples pushed by the audio thread when the take ended
+        end_sample: u64,
+        /// Frames the audio thread couldn't buffer during the take
+        dropped_frames: u64,
+    },
+}
+
+/// Audio-thread side of the recorder
+///
+/// # Real-Time Safety
+///
+/// Every method only pushes to or pops from preallocated ring buffers. The
+/// path passed to [`Recorder::start`] is moved to the writer thread, so it is
+/// freed there rather than on the audio thread.
+pub struct Recorder {
+    control: Producer<Control>,
+    samples: Producer<f32>,
+    reports: Consumer<AudioEvent>,
+    /// Whether a take is in progress
+    recording: bool,
+    /// Channels of the current take
+    channels: usize,
+    /// Frames dropped from the current take because the buffer was full
+    dropped_frames: u64,
+    /// Samples pushed to the buffer over all takes
+    samples_pushed: u64,
+}
+
+impl Recorder {
+    /// Spawn the writer thread, buffering up to `buffer_samples` samples
+    ///
+    /// The thread finishes any take in progress and exits once the
+    /// `Recorder` is dropped; join the returned handle to wait for that.
+    pub fn spawn(buffer_samples: usize) -> Result<(Self, JoinHandle<()>)> {
+        let (control_tx, control_rx) = RingBuffer::new(CONTROL_CAPACITY);
+        let (samples_tx, samples_rx) = RingBuffer::new(buffer_samples.max(1));
+        let (reports_tx, reports_rx) = RingBuffer::new(REPORT_CAPACITY);
+
+        let writer = Writer {
+            control: control_rx,
+            samples: samples_rx,
+            reports: reports_tx,
+            take: TakeState::Idle,
+            samples_consumed: 0,
+        };
+        let handle = std::thread::Builder::new()
+            .name("vvdaw-recorder".to_string())
+            .spawn(move || writer.run())
+            .context("Failed to spawn recorder thread")?;
+
+        let recorder = Self {
+            control: control_tx,
+            samples: samples_tx,
+            reports: reports_rx,
+            recording: false,
+            channels: 0,
+            dropped_frames: 0,
+            samples_pushed: 0,
+        };
+        Ok((recorder, handle))
+    }
+
+    /// Start recording to `path`, finishing any take in progress first
+    pub fn start(&mut self, path: PathBuf, sample_rate: SampleRate, channels: usize) {
+        self.stop();
+        if self.recording {
+            // The previous take couldn't be stopped (request queue full)
+            return;
+        }
+
+        let start = Control::Start {
+            path,
+            sample_rate,
+            channels: u16::try_from(channels).unwrap_or(u16::MAX),
+        };
+        if self.control.push(start).is_ok() {
+            self.recording = true;
+            self.channels = channels.max(1);
+            self.dropped_frames = 0;
+        }
+    }
+
+    /// Finish the current take (does nothing if not recording)
+    pub fn stop(&mut self) {
+        if !self.recording {
+            return;
+        }
+        let stop = Control::Stop {
+            end_sample: self.samples_pushed,
+            dropped_frames: self.dropped_frames,
+        };
+        if self.control.push(stop).is_ok() {
+            self.recording = false;
+        }
+    }
+
+    /// Whether a take is in progress
+    pub const fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Queue one block of interleaved output for the current take
+    ///
+    /// A block that doesn't fit in the buffer is dropped whole, so takes
+    /// never contain torn frames.
+    pub fn push_block(&mut self, interleaved: &[f32]) {
+        if !self.recording || interleaved.is_empty() {
+            return;
+        }
+        match self.samples.write_chunk_uninit(interleaved.len()) {
+            Ok(chunk) => {
+                chunk.fill_from_iter(interleaved.iter().copied());
+                self.samples_pushed += interleaved.len() as u64;
+            }
+            Err(_) => {
+                self.dropped_frames += (interleaved.len() / self.channels) as u64;
+            }
+        }
+    }
+
+    /// Next report from the writer thread, to forward to the UI
+    pub fn pop_report(&mut self) -> Option<AudioEvent> {
+        self.reports.pop().ok()
+    }
+}
+
+/// File being written
+struct Take {
+    path: PathBuf,
+    wav: WavWriter<BufWriter<File>>,
+    channels: u16,
+    samples_written: u64,
+}
+
+impl Take {
+    fn frames_written(&self) -> u64 {
+        self.samples_written / u64::from(self.channels.max(1))
+    }
+}
+
+/// Writer thread's view of the current take
+enum TakeState {
+    /// Not recording
+    Idle,
+    /// Writing to a file
+    Recording(Take),
+    /// The take couldn't be written - its samples are discarded until it stops
+    Failed { frames_written: u64 },
+}
+
+/// Writer-thread side of the recorder
+struct Writer {
+    control: Consumer<Control>,
+    samples: Consumer<f32>,
+    reports: Producer<AudioEvent>,
+    take: TakeState,
+    /// Samples read from the buffer over all takes
+    samples_consumed: u64,
+}
+
+impl Writer {
+    /// Drain requests and samples until the audio side is dropped
+    fn run(mut self) {
+        loop {
+            // Checked first so everything queued before the drop is handled
+            let closed = self.control.is_abandoned();
+            // Counted before reading requests, so the Start of every sample
+            // counted here has already been queued
+            let mut available = self.samples.slots();
+
+            while let Ok(control) = self.control.pop() {
+                match control {
+                    Control::Start {
+                        path,
+                        sample_rate,
+                        channels,
+                    } => self.begin(path, sample_rate, channels),
+                    Control::Stop {
+                        end_sample,
+                        dropped_frames,
+                    } => {
+                        let rest = end_sample.saturating_sub(self.samples_consumed) as usize;
+                        self.consume(rest);
+                        available = available.saturating_sub(rest);
+                        self.finish(dropped_frames);
+                    }
+                }
+            }
+            self.consume(available);
+
+            if closed {
+                self.finish(0);
+                return;
+            }
+            std::thread::sleep(WRITER_IDLE);
+        }
+    }
+
+    /// Open the file for a new take
+    fn begin(&mut self, path: PathBuf, sample_rate: SampleRate, channels: u16) {
+        let spec = WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: 32,
+            sample_format: SampleFormat::Float,
+        };
+
+        self.take = match WavWriter::create(&path, spec) {
+            Ok(wav) => {
+                tracing::info!("Recording to {}", path.display());
+                TakeState::Recording(Take {
+                    path,
+                    wav,
+                    channels,
+                    samples_written: 0,
+                })
+            }
+            Err(e) => {
+                self.report(AudioEvent::Error(format!(
+                    "Failed to start recording to {}: {e}",
+                    path.display()
+                )));
+                TakeState::Failed { frames_written: 0 }
+            }
+        };
+    }
+
+    /// Read `count` buffered samples into the current take
+    ///
+    /// Samples are discarded if the take failed (or there is none).
+    fn consume(&mut self, count: usize) {
+        if count == 0 {
+            return;
+        }
+        let Ok(chunk) = self.samples.read_chunk(count) else {
+            return;
+        };
+        self.samples_consumed += count as u64;
+
+        let mut failure = None;
+        if let TakeState::Recording(take) = &mut self.take {
+            let (first, second) = chunk.as_slices();
+            let written = first
+                .iter()
+                .chain(second)
+                .try_for_each(|&sample| take.wav.write_sample(sample));
+            match written {
+                Ok(()) => take.samples_written += count as u64,
+                Err(e) => {
+                    failure = Some((
+                        format!("Recording to {} failed: {e}", take.path.display()),
+                        take.frames_written(),
+                    ));
+                }
+            }
+        }
+        chunk.commit_all();
+
+        // E.g. disk full - keep the take's length, drop the rest of its samples
+        if let Some((message, frames_written)) = failure {
+            self.take = TakeState::Failed { frames_written };
+            self.report(AudioEvent::Error(message));
+        }
+    }
+
+    /// Close the current take and report how much was written
+    fn finish(&mut self, dropped_frames: u64) {
+        let frames_written = match std::mem::replace(&mut self.take, TakeState::Idle) {
+            TakeState::Idle => return,
+            TakeState::Failed { frames_written } => frames_written,
+            TakeState::Recording(take) => {
+                let frames_written = take.frames_written();
+                let path = take.path.clone();
+                match take.wav.finalize() {
+                    Ok(()) => {
+                        tracing::info!("Recorded {frames_written} frames to {}", path.display());
+                    }
+                    Err(e) => self.report(AudioEvent::Error(format!(
+                        "Failed to finish recording {}: {e}",
+                        path.display()
+                    ))),
+                }
+                frames_written
+            }
+        };
+
+        if dropped_frames > 0 {
+            self.report(AudioEvent::Error(format!(
+                "Recording dropped {dropped_frames} frames - the disk couldn't keep up"
+            )));
+        }
+        self.report(AudioEvent::RecordingStopped { frames_written });
+    }
+
+    /// Queue a report for the audio thread, logging it as well
+    fn report(&mut self, event: AudioEvent) {
+        if let AudioEvent::Error(message) = &event {
+            tracing::error!("{message}");
+        }
+        if self.reports.push(event).is_err() {
+            tracing::warn!("Recorder report queue full - dropping report");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    /// Forward reports until `RecordingStopped` arrives, returning them all
+    fn wait_for_stop(recorder: &mut Recorder) -> Vec<AudioEvent> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut reports = Vec::new();
+        while Instant::now() < deadline {
+            while let Some(event) = recorder.pop_report() {
+                let stopped = matches!(event, AudioEvent::RecordingStopped { .. });
+                reports.push(event);
+                if stopped {
+                    return reports;
+                }
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        panic!("recording did not stop, got {reports:?}");
+    }
+
+    #[test]
+    fn test_records_blocks_to_wav() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("take.wav");
+        let (mut recorder, writer) = Recorder::spawn(4096).unwrap();
+
+        recorder.start(path.clone(), 48000, 2);
+        assert!(recorder.is_recording());
+        for block in 0..10 {
+            let samples: Vec<f32> = (0..128).map(|i| (block * 128 + i) as f32).collect();
+            recorder.push_block(&samples);
+        }
+        recorder.stop();
+        assert!(!recorder.is_recording());
+
+        let reports = wait_for_stop(&mut recorder);
+        assert!(matches!(
+            reports.as_slice(),
+            [AudioEvent::RecordingStopped {
+                frames_written: 640
+            }]
+        ));
+
+        let mut reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().channels, 2);
+        assert_eq!(reader.spec().sample_rate, 48000);
+        let samples: Vec<f32> = reader.samples::<f32>().map(Result::unwrap).collect();
+        assert_eq!(samples.len(), 1280);
+        assert!(samples.iter().enumerate().all(|(i, &s)| s == i as f32));
+
+        drop(recorder);
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn test_overrun_drops_whole_blocks_and_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("take.wav");
+        let (mut recorder, writer) = Recorder::spawn(256).unwrap();
+
+        recorder.start(path.clone(), 48000, 2);
+        // Larger than the whole buffer, so it can never fit
+        recorder.push_block(&[0.5; 512]);
+        recorder.push_block(&[0.5; 128]);
+        recorder.stop();
+
+        let reports = wait_for_stop(&mut recorder);
+        assert!(matches!(
+            reports.as_slice(),
+            [
+                AudioEvent::Error(_),
+                AudioEvent::RecordingStopped { frames_written: 64 }
+            ]
+        ));
+        assert_eq!(hound::WavReader::open(&path).unwrap().len(), 128);
+
+        drop(recorder);
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn test_unwritable_path_reports_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing").join("take.wav");
+        let (mut recorder, writer) = Recorder::spawn(1024).unwrap();
+
+        recorder.start(path, 48000, 2);
+        recorder.push_block(&[0.5; 128]);
+        recorder.stop();
+
+        let reports = wait_for_stop(&mut recorder);
+        assert!(matches!(
+            reports.as_slice(),
+            [
+                AudioEvent::Error(_),
+                AudioEvent::RecordingStopped { frames_written: 0 }
+            ]
+        ));
+
+        drop(recorder);
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn test_dropping_recorder_finishes_take() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("take.wav");
+        let (mut recorder, writer) = Recorder::spawn(1024).unwrap();
+
+        recorder.start(path.clone(), 44100, 1);
+        recorder.push_block(&[0.25; 100]);
+        drop(recorder);
+        writer.join().unwrap();
+
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().channels, 1);
+        assert_eq!(reader.len(), 100);
+    }
+}
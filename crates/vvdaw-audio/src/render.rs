@@ -0,0 +1,393 @@
+//! Offline rendering: interleaved samples in, processed interleaved samples out.
+//!
+//! These run a plugin or a whole [`AudioGraph`] over a buffer block by block,
+//! the way `vvdaw-process` renders WAV files, so the same loop serves tests
+//! and tools that want a plugin's output without an audio device. The plugin
+//! or graph must already be initialized for a block size at least as large as
+//! the one rendered with.
+//!
+//! ```
+//! use vvdaw_audio::{builtin, render};
+//!
+//! let mut gain = builtin::create_builtin("gain").unwrap();
+//! gain.initialize(48000, 64).unwrap();
+//! gain.set_parameter(builtin::gain::PARAM_GAIN, 0.5).unwrap();
+//!
+//! let input = vec![1.0; 2 * 256];
+//! let output = render::render_through_plugin(gain.as_mut(), &input, 2, 64).unwrap();
+//! assert_eq!(output.len(), input.len());
+//! ```
+
+use crate::automation::AutomationLane;
+use crate::graph::AudioGraph;
+use crate::midi_file::MidiSequence;
+use vvdaw_core::{Frames, Sample, SampleRate, Transport, buffer, format};
+use vvdaw_plugin::{AudioBuffer, EventBuffer, ParameterInfo, Plugin, PluginError};
+
+/// Sample rate of the transport seen by [`render_through_plugin`]
+pub const DEFAULT_RENDER_SAMPLE_RATE: SampleRate = 48000;
+
+/// Everything about an offline render besides the audio itself
+#[derive(Debug, Clone, Copy)]
+pub struct RenderOptions<'a> {
+    /// Transport and automation sample rate (graph renders use the graph's own)
+    pub sample_rate: SampleRate,
+    /// Frames processed per call
+    pub block_size: Frames,
+    /// Notes sent with block-relative offsets
+    pub midi: Option<&'a MidiSequence>,
+    /// Parameters set at the start of every block (plugin renders only)
+    pub automation: &'a [(ParameterInfo, &'a AutomationLane)],
+    /// Frames of silence fed in after the input so the output can ring out
+    pub tail: Frames,
+    /// Blend with the input (0.0 = dry, 1.0 = processed), compensating latency
+    ///
+    /// `None` returns the processed output as-is, without moving it earlier
+    /// by the latency.
+    pub mix: Option<f32>,
+}
+
+impl RenderOptions<'_> {
+    /// Plain render: no MIDI, automation, tail or dry/wet blend
+    pub const fn new(sample_rate: SampleRate, block_size: Frames) -> Self {
+        Self {
+            sample_rate,
+            block_size,
+            midi: None,
+            automation: &[],
+            tail: 0,
+            mix: None,
+        }
+    }
+}
+
+/// Dry/wet blend applied when writing processed blocks to the output
+#[derive(Debug, Clone, Copy)]
+struct DryWet {
+    /// 0.0 = dry input only, 1.0 = processed output only
+    mix: f32,
+    /// Processing latency in frames - the wet signal is moved this much earlier
+    latency: usize,
+}
+
+impl DryWet {
+    /// Processed output only, as-is
+    const WET: Self = Self {
+        mix: 1.0,
+        latency: 0,
+    };
+
+    /// `mix`, if given, compensating for `latency`
+    fn new(mix: Option<f32>, latency: usize) -> Self {
+        mix.map_or(Self::WET, |mix| {
+            tracing::info!("Dry/wet mix: {mix:.2} (compensating {latency} frames of latency)");
+            Self { mix, latency }
+        })
+    }
+}
+
+/// Render interleaved `input_samples` through a plugin
+///
+/// The plugin sees a playing transport at [`DEFAULT_RENDER_SAMPLE_RATE`];
+/// use [`render_plugin`] for MIDI, automation, a tail or another rate.
+///
+/// # Errors
+///
+/// Returns error if the plugin fails to process a block
+pub fn render_through_plugin(
+    plugin: &mut dyn Plugin,
+    input_samples: &[Sample],
+    channel_count: usize,
+    block_size: Frames,
+) -> Result<Vec<Sample>, PluginError> {
+    render_plugin(
+        plugin,
+        input_samples,
+        channel_count,
+        &RenderOptions::new(DEFAULT_RENDER_SAMPLE_RATE, block_size),
+    )
+}
+
+/// Render interleaved `input_samples` through a graph's system input and output
+///
+/// Use [`render_graph`] for MIDI, a tail or a dry/wet blend.
+pub fn render_through_graph(
+    graph: &mut AudioGraph,
+    input_samples: &[Sample],
+    channel_count: usize,
+    block_size: Frames,
+) -> Vec<Sample> {
+    let options = RenderOptions::new(graph.sample_rate(), block_size);
+    render_graph(graph, input_samples, channel_count, &options)
+}
+
+/// Render interleaved `input_samples` through a plugin with full [`RenderOptions`]
+///
+/// The plugin sees a playing transport at the default tempo, starting at
+/// frame 0. The output holds the input's frames plus the tail.
+///
+/// # Errors
+///
+/// Returns error if an automated parameter can't be set or the plugin fails
+/// to process a block
+pub fn render_plugin(
+    plugin: &mut dyn Plugin,
+    input_samples: &[Sample],
+    channel_count: usize,
+    options: &RenderOptions<'_>,
+) -> Result<Vec<Sample>, PluginError> {
+    let dry_wet = DryWet::new(options.mix, plugin.latency());
+    render_blocks(
+        input_samples,
+        channel_count,
+        options.sample_rate,
+        options,
+        dry_wet,
+        |audio, events, transport, block_start| {
+            for (param, lane) in options.automation {
+                let value = param.constrain(lane.value_at(block_start, options.sample_rate));
+                plugin.set_parameter(param.id, value).map_err(|e| {
+                    PluginError::InvalidParameter(format!(
+                        "Failed to automate parameter '{}': {e}",
+                        param.name
+                    ))
+                })?;
+            }
+
+            plugin.set_transport(transport);
+            plugin.process(audio, events)
+        },
+    )
+}
+
+/// Render interleaved `input_samples` through a graph with full [`RenderOptions`]
+///
+/// The graph's own sample rate drives the transport, and `automation` is
+/// ignored (set node parameters on the graph instead). The output holds the
+/// input's frames plus the tail.
+pub fn render_graph(
+    graph: &mut AudioGraph,
+    input_samples: &[Sample],
+    channel_count: usize,
+    options: &RenderOptions<'_>,
+) -> Vec<Sample> {
+    let dry_wet = DryWet::new(options.mix, graph.latency());
+    let result = render_blocks(
+        input_samples,
+        channel_count,
+        graph.sample_rate(),
+        options,
+        dry_wet,
+        |audio, events, transport, _| {
+            graph.process_with_events(audio.inputs, audio.outputs, events, transport);
+            Ok(())
+        },
+    );
+    // Graph processing can't fail
+    result.unwrap_or_default()
+}
+
+/// The shared block loop: deinterleave, process, blend and interleave
+///
+/// `process` gets each block's audio, its MIDI events, the transport and the
+/// block's first frame.
+fn render_blocks(
+    input_samples: &[Sample],
+    channel_count: usize,
+    sample_rate: SampleRate,
+    options: &RenderOptions<'_>,
+    dry_wet: DryWet,
+    mut process: impl FnMut(&mut AudioBuffer, &EventBuffer, &Transport, u64) -> Result<(), PluginError>,
+) -> Result<Vec<Sample>, PluginError> {
+    let block_size = options.block_size;
+    if channel_count == 0 || block_size == 0 {
+        return Ok(Vec::new());
+    }
+
+    // The input followed by `tail` frames of silence
+    let frame_count = input_samples.len() / channel_count + options.tail;
+    let mut output_samples = vec![0.0; frame_count * channel_count];
+
+    let mut input_buffers: Vec<Vec<Sample>> = vec![vec![0.0; block_size]; channel_count];
+    let mut output_buffers: Vec<Vec<Sample>> = vec![vec![0.0; block_size]; channel_count];
+
+    let mut event_buffer = EventBuffer::new();
+    let mut transport = Transport::new(sample_rate);
+    transport.is_playing = true;
+
+    // Latency-compensated output runs on past the input to flush the plugin
+    let total_frames = frame_count + dry_wet.latency;
+
+    let mut frames_processed = 0;
+    for block in buffer::blocks(total_frames, block_size) {
+        let current_block_size = block.len();
+
+        // Deinterleave input block (silence past the end of the input)
+        let block_input = input_samples
+            .get(block.start * channel_count..)
+            .unwrap_or_default();
+        let block_input = &block_input[..block_input.len().min(current_block_size * channel_count)];
+        let frames_read = buffer::deinterleave(block_input, channel_count, &mut input_buffers);
+        for buf in &mut input_buffers {
+            buf[frames_read..current_block_size].fill(0.0);
+        }
+
+        let input_refs: Vec<&[Sample]> = input_buffers
+            .iter()
+            .map(|buf| &buf[..current_block_size])
+            .collect();
+        let mut output_refs: Vec<&mut [Sample]> = output_buffers
+            .iter_mut()
+            .map(|buf| &mut buf[..current_block_size])
+            .collect();
+
+        if let Some(midi) = options.midi {
+            midi.fill_block(
+                frames_processed as u64,
+                current_block_size,
+                &mut event_buffer,
+            );
+        }
+
+        let mut audio = AudioBuffer {
+            inputs: &input_refs,
+            outputs: &mut output_refs,
+            frames: current_block_size,
+        };
+        process(
+            &mut audio,
+            &event_buffer,
+            &transport,
+            frames_processed as u64,
+        )?;
+        transport.advance(current_block_size);
+
+        write_output_block(
+            &output_buffers,
+            input_samples,
+            &mut output_samples,
+            frames_processed,
+            current_block_size,
+            dry_wet,
+        );
+
+        frames_processed += current_block_size;
+
+        if frames_processed % (block_size * 100) == 0 {
+            tracing::debug!(
+                "Processed {} / {}",
+                timecode(frames_processed, sample_rate),
+                timecode(total_frames, sample_rate)
+            );
+        }
+    }
+
+    tracing::info!(
+        "Processed {frames_processed} frames total ({})",
+        timecode(frames_processed, sample_rate)
+    );
+    Ok(output_samples)
+}
+
+/// `frames` at `sample_rate` as `mm:ss.mmm`, for progress logging
+fn timecode(frames: Frames, sample_rate: SampleRate) -> String {
+    format::seconds_to_timecode_string(format::samples_to_seconds(frames as u64, sample_rate))
+}
+
+/// Interleave a processed block into `output_samples`, blended with the dry input
+///
+/// The block starts at `block_start` frames into the processed stream. Frames
+/// that fall before the start or after the end of `output_samples` (once moved
+/// earlier by the latency) are dropped, and the dry signal is silent past the
+/// end of the input.
+fn write_output_block(
+    output_buffers: &[Vec<Sample>],
+    input_samples: &[Sample],
+    output_samples: &mut [Sample],
+    block_start: usize,
+    frames: usize,
+    dry_wet: DryWet,
+) {
+    let channel_count = output_buffers.len();
+    for frame in 0..frames {
+        let Some(output_frame) = (block_start + frame).checked_sub(dry_wet.latency) else {
+            continue;
+        };
+        let sample_offset = output_frame * channel_count;
+        for (ch, buf) in output_buffers.iter().enumerate() {
+            let Some(output) = output_samples.get_mut(sample_offset + ch) else {
+                continue;
+            };
+            let dry = input_samples
+                .get(sample_offset + ch)
+                .copied()
+                .unwrap_or(0.0);
+            *output = buf[frame].mul_add(dry_wet.mix, dry * (1.0 - dry_wet.mix));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builtin::{self, gain};
+    use crate::graph::PluginSource;
+    use crate::test_util::ramp;
+
+    /// Graph with a single gain node between system input and output
+    fn gain_graph(gain: f32) -> AudioGraph {
+        let mut graph = AudioGraph::with_config(48000, 64);
+        let node = graph
+            .add_node(
+                builtin::create_builtin("gain").unwrap(),
+                PluginSource::Builtin {
+                    name: "gain".to_string(),
+                },
+            )
+            .unwrap();
+        graph
+            .set_node_parameter(node, gain::PARAM_GAIN, gain)
+            .unwrap();
+        graph
+    }
+
+    #[test]
+    fn test_graph_render_covers_every_block() {
+        let mut graph = gain_graph(1.0);
+        // Not a whole number of blocks
+        let input = ramp(150);
+
+        let output = render_through_graph(&mut graph, &input, 2, 64);
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn test_plugin_render_with_tail() {
+        let mut plugin = builtin::create_builtin("gain").unwrap();
+        plugin.initialize(48000, 32).unwrap();
+        plugin.set_parameter(gain::PARAM_GAIN, 0.5).unwrap();
+
+        let options = RenderOptions {
+            tail: 10,
+            ..RenderOptions::new(48000, 32)
+        };
+        let output = render_plugin(plugin.as_mut(), &ramp(100), 2, &options).unwrap();
+        assert_eq!(output.len(), 2 * 110);
+        assert_eq!(output[2 * 99], 49.5);
+        assert!(output[2 * 100..].iter().all(|&sample| sample == 0.0));
+    }
+
+    #[test]
+    fn test_mix_blends_dry_input() {
+        // Silent wet signal, so a half mix leaves half the input
+        let mut graph = gain_graph(0.0);
+        let input = vec![1.0; 2 * 100];
+        let options = RenderOptions {
+            mix: Some(0.5),
+            ..RenderOptions::new(48000, 64)
+        };
+
+        let output = render_graph(&mut graph, &input, 2, &options);
+        assert!(output.iter().all(|&sample| sample == 0.5));
+    }
+}
@@ -0,0 +1,126 @@
+//! Sample-rate conversion for imported audio.
+//!
+//! `SamplerProcessor` plays its samples one frame per output frame, so audio
+//! recorded at a different rate than the engine's would play at the wrong
+//! speed and pitch (a 44.1 kHz file on a 48 kHz engine plays ~8.8% fast).
+//! Loaders convert to the engine rate (reported by
+//! `AudioEvent::EngineInitialized`) before building the sampler.
+//!
+//! Linear interpolation is used for now - cheap and good enough for
+//! 44.1 kHz ↔ 48 kHz, though it softens the top octave slightly.
+
+use vvdaw_core::SampleRate;
+
+/// Resample interleaved stereo audio using linear interpolation
+///
+/// The output covers the same duration as the input, so pitch and speed are
+/// unchanged when played back at `target_rate`. Returns the input unchanged
+/// if the rates match or either rate is 0.
+///
+/// NOT real-time safe: allocates the output. Call it on a loader thread.
+pub fn resample_stereo(
+    stereo_samples: &[f32],
+    source_rate: SampleRate,
+    target_rate: SampleRate,
+) -> Vec<f32> {
+    if source_rate == 0 || target_rate == 0 {
+        tracing::error!("Cannot resample from {source_rate}Hz to {target_rate}Hz");
+        return stereo_samples.to_vec();
+    }
+
+    if source_rate == target_rate || stereo_samples.is_empty() {
+        return stereo_samples.to_vec();
+    }
+
+    let frame_count = stereo_samples.len() / 2;
+    let output_frame_count =
+        (frame_count as u64 * u64::from(target_rate)).div_ceil(u64::from(source_rate)) as usize;
+
+    tracing::info!(
+        "Resampling {frame_count} frames from {source_rate}Hz to {target_rate}Hz ({output_frame_count} frames)"
+    );
+
+    // Source frames advanced per output frame
+    let step = f64::from(source_rate) / f64::from(target_rate);
+    let last = frame_count - 1;
+
+    let mut resampled = Vec::with_capacity(output_frame_count * 2);
+    for frame in 0..output_frame_count {
+        let position = frame as f64 * step;
+        let index = (position as usize).min(last);
+        let next = (index + 1).min(last);
+        let frac = (position - index as f64).min(1.0) as f32;
+
+        for channel in 0..2 {
+            let a = stereo_samples[index * 2 + channel];
+            let b = stereo_samples[next * 2 + channel];
+            resampled.push((b - a).mul_add(frac, a));
+        }
+    }
+
+    resampled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resample_empty_input() {
+        assert!(resample_stereo(&[], 44100, 48000).is_empty());
+    }
+
+    #[test]
+    fn test_resample_zero_rate_returns_input() {
+        let input = vec![0.1, -0.1, 0.2, -0.2];
+        assert_eq!(resample_stereo(&input, 0, 48000), input);
+        assert_eq!(resample_stereo(&input, 44100, 0), input);
+    }
+
+    #[test]
+    fn test_resample_same_rate_is_identity() {
+        let input = vec![0.5, -0.5, 0.3, -0.3];
+        assert_eq!(resample_stereo(&input, 48000, 48000), input);
+    }
+
+    #[test]
+    fn test_resample_preserves_duration() {
+        // One second at 44.1kHz is one second at 48kHz
+        let input = vec![0.0; 44100 * 2];
+        assert_eq!(resample_stereo(&input, 44100, 48000).len(), 48000 * 2);
+        assert_eq!(
+            resample_stereo(&vec![0.0; 48000 * 2], 48000, 44100).len(),
+            44100 * 2
+        );
+    }
+
+    #[test]
+    fn test_resample_interpolates_between_frames() {
+        // Doubling the rate inserts midpoints, keeping channels separate
+        let input = vec![0.0, 1.0, 1.0, 0.0];
+        let result = resample_stereo(&input, 24000, 48000);
+        assert_eq!(result, vec![0.0, 1.0, 0.5, 0.5, 1.0, 0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn test_resample_preserves_pitch() {
+        // A 1kHz tone should still cross zero ~2000 times per second afterwards
+        let tone: Vec<f32> = (0..44100)
+            .flat_map(|i| {
+                let s = (std::f32::consts::TAU * 1000.0 * i as f32 / 44100.0).sin();
+                [s, s]
+            })
+            .collect();
+        let result = resample_stereo(&tone, 44100, 48000);
+
+        let crossings = result
+            .chunks_exact(2)
+            .map(|frame| frame[0])
+            .collect::<Vec<_>>()
+            .windows(2)
+            .filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0))
+            .count();
+        assert!((1990..=2010).contains(&crossings), "{crossings} crossings");
+    }
+}
@@ -0,0 +1,168 @@
+//! Oscilloscope tap on the engine output.
+//!
+//! [`ScopeTap`] keeps every `n`th output frame in a fixed ring and copies it
+//! out as a [`ScopeWindow`] for the UI's triple buffer. Everything is stored
+//! inline, so feeding and publishing never allocate on the audio thread.
+
+use vvdaw_comms::{SCOPE_WINDOW_FRAMES, ScopeSender, ScopeWindow};
+use vvdaw_core::Sample;
+
+/// Decimating ring of the most recent output frames
+pub struct ScopeTap {
+    /// Left channel ring
+    left: [Sample; SCOPE_WINDOW_FRAMES],
+    /// Right channel ring
+    right: [Sample; SCOPE_WINDOW_FRAMES],
+    /// Ring index of the oldest point (the next one overwritten)
+    next: usize,
+    /// Output frames per point
+    decimation: usize,
+    /// Frames still to skip before the next point is kept
+    skip: usize,
+    /// Frame position of the newest point
+    newest_position: u64,
+}
+
+impl ScopeTap {
+    /// Tap keeping every `decimation`th frame (at least every frame)
+    pub const fn new(decimation: usize) -> Self {
+        Self {
+            left: [0.0; SCOPE_WINDOW_FRAMES],
+            right: [0.0; SCOPE_WINDOW_FRAMES],
+            next: 0,
+            decimation: if decimation == 0 { 1 } else { decimation },
+            skip: 0,
+            newest_position: 0,
+        }
+    }
+
+    /// Change how many frames each point covers (values below 1 mean 1)
+    ///
+    /// REAL-TIME SAFE: Only updates counters.
+    pub fn set_decimation(&mut self, decimation: usize) {
+        self.decimation = decimation.max(1);
+        self.skip = 0;
+    }
+
+    /// Output frames per point
+    pub const fn decimation(&self) -> usize {
+        self.decimation
+    }
+
+    /// Feed an interleaved output block that starts at frame `position`
+    ///
+    /// Mono output is shown on both channels.
+    ///
+    /// REAL-TIME SAFE: Writes into the fixed ring only.
+    pub fn push_block(&mut self, data: &[Sample], channels: usize, position: u64) {
+        if channels == 0 {
+            return;
+        }
+
+        for (index, frame) in data.chunks_exact(channels).enumerate() {
+            if self.skip > 0 {
+                self.skip -= 1;
+                continue;
+            }
+            self.skip = self.decimation - 1;
+
+            self.left[self.next] = frame[0];
+            self.right[self.next] = frame.get(1).copied().unwrap_or(frame[0]);
+            self.next = (self.next + 1) % SCOPE_WINDOW_FRAMES;
+            self.newest_position = position + index as u64;
+        }
+    }
+
+    /// The ring in time order, oldest point first
+    pub fn window(&self) -> ScopeWindow {
+        let span = (SCOPE_WINDOW_FRAMES - 1) * self.decimation;
+        let mut window = ScopeWindow {
+            position: self.newest_position.saturating_sub(span as u64),
+            decimation: self.decimation,
+            ..ScopeWindow::default()
+        };
+
+        for (ring, unrolled) in [
+            (&self.left, &mut window.left),
+            (&self.right, &mut window.right),
+        ] {
+            let (newer, older) = ring.split_at(self.next);
+            unrolled[..older.len()].copy_from_slice(older);
+            unrolled[older.len()..].copy_from_slice(newer);
+        }
+
+        window
+    }
+
+    /// Hand the current window to the UI
+    ///
+    /// REAL-TIME SAFE: The triple buffer swaps preallocated slots.
+    pub fn publish(&self, scope_tx: &mut ScopeSender) {
+        scope_tx.write(self.window());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::ramp;
+
+    #[test]
+    fn test_window_is_oldest_first() {
+        let mut tap = ScopeTap::new(1);
+        tap.push_block(&ramp(SCOPE_WINDOW_FRAMES + 10), 2, 0);
+
+        let window = tap.window();
+        assert_eq!(window.position, 10);
+        assert_eq!(window.left[0], 10.0);
+        assert_eq!(window.right[0], -10.0);
+        assert_eq!(
+            window.left[SCOPE_WINDOW_FRAMES - 1],
+            (SCOPE_WINDOW_FRAMES + 9) as Sample
+        );
+    }
+
+    #[test]
+    fn test_decimation_across_blocks() {
+        let mut tap = ScopeTap::new(3);
+        let data = ramp(4000);
+
+        // Uneven block sizes must not disturb the every-3rd-frame spacing
+        tap.push_block(&data[..200], 2, 0);
+        tap.push_block(&data[200..], 2, 100);
+
+        let window = tap.window();
+        assert_eq!(window.decimation, 3);
+        let newest = window.left[SCOPE_WINDOW_FRAMES - 1];
+        assert_eq!(newest, 3999.0);
+        for pair in window.left.windows(2) {
+            assert_eq!(pair[1] - pair[0], 3.0);
+        }
+        assert_eq!(window.position, 3999 - 3 * (SCOPE_WINDOW_FRAMES as u64 - 1));
+        assert_eq!(window.left[0], window.position as Sample);
+    }
+
+    #[test]
+    fn test_mono_fills_both_channels() {
+        let mut tap = ScopeTap::new(0);
+        assert_eq!(tap.decimation(), 1);
+        tap.push_block(&[0.25, 0.5], 1, 0);
+
+        let window = tap.window();
+        assert_eq!(window.left[SCOPE_WINDOW_FRAMES - 1], 0.5);
+        assert_eq!(window.right[SCOPE_WINDOW_FRAMES - 1], 0.5);
+    }
+
+    #[test]
+    fn test_publish_reaches_reader() {
+        let (mut scope_tx, mut scope_rx) =
+            vvdaw_comms::triple_buffer::triple_buffer(&ScopeWindow::default());
+        let mut tap = ScopeTap::new(2);
+        tap.push_block(&ramp(8), 2, 500);
+        tap.publish(&mut scope_tx);
+
+        let window = scope_rx.read();
+        assert_eq!(window.decimation, 2);
+        assert_eq!(window.left[SCOPE_WINDOW_FRAMES - 1], 6.0);
+    }
+}
@@ -3,10 +3,13 @@
 //! Uses RON (Rust Object Notation) for human-readable, version-control-friendly
 //! serialization of the audio graph, plugin configurations, and parameters.
 
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+use crate::AudioConfig;
 use crate::graph::{AudioGraph, PluginSource};
 use vvdaw_plugin::Plugin;
 
@@ -21,9 +24,9 @@ pub enum PluginSpec {
         /// Name of the built-in processor (e.g., "gain", "pan", "mixer")
         name: String,
 
-        /// Parameter values (parameter ID -> normalized value 0.0-1.0)
+        /// Parameter values (parameter ID -> value from `Plugin::get_parameter`)
         #[serde(default)]
-        parameters: HashMap<u32, f64>,
+        parameters: HashMap<u32, f32>,
     },
 
     /// VST3 plugin loaded from a bundle path
@@ -43,20 +46,71 @@ pub enum PluginSpec {
         /// This should be an absolute path. Relative paths may not work correctly.
         path: PathBuf,
 
-        /// Parameter values (parameter ID -> normalized value 0.0-1.0)
+        /// Parameter values (parameter ID -> value from `Plugin::get_parameter`)
         #[serde(default)]
-        parameters: HashMap<u32, f64>,
+        parameters: HashMap<u32, f32>,
+    },
+
+    /// CLAP plugin loaded from a `.clap` file (a bundle on macOS)
+    ///
+    /// The same security caveats as [`PluginSpec::Vst3`] apply.
+    Clap {
+        /// Absolute path to the `.clap` file or bundle
+        path: PathBuf,
+
+        /// Parameter values (parameter ID -> value from `Plugin::get_parameter`)
+        #[serde(default)]
+        parameters: HashMap<u32, f32>,
     },
-    // Future plugin types:
-    // Clap { path: PathBuf, parameters: HashMap<u32, f64> },
 }
 
 impl PluginSpec {
+    /// Spec for a plugin loaded from `source` with the given parameter values
+    ///
+    /// Returns `None` for [`PluginSource::Unknown`], which can't be reloaded.
+    #[must_use]
+    pub fn from_source(source: &PluginSource, parameters: HashMap<u32, f32>) -> Option<Self> {
+        match source {
+            PluginSource::Builtin { name } => Some(Self::Builtin {
+                name: name.clone(),
+                parameters,
+            }),
+            PluginSource::Vst3 { path } => Some(Self::Vst3 {
+                path: path.clone(),
+                parameters,
+            }),
+            PluginSource::Clap { path } => Some(Self::Clap {
+                path: path.clone(),
+                parameters,
+            }),
+            PluginSource::Unknown => None,
+        }
+    }
+
+    /// Where the plugin comes from, for the graph node it is loaded into
+    #[must_use]
+    pub fn source(&self) -> PluginSource {
+        match self {
+            Self::Builtin { name, .. } => PluginSource::Builtin { name: name.clone() },
+            Self::Vst3 { path, .. } => PluginSource::Vst3 { path: path.clone() },
+            Self::Clap { path, .. } => PluginSource::Clap { path: path.clone() },
+        }
+    }
+
+    /// Plugin identifier for error messages (`builtin:<name>` or the file path)
+    #[must_use]
+    pub fn display_name(&self) -> String {
+        match self {
+            Self::Builtin { name, .. } => format!("builtin:{name}"),
+            Self::Vst3 { path, .. } | Self::Clap { path, .. } => path.display().to_string(),
+        }
+    }
+
     /// Validate the plugin specification
     ///
     /// Checks for:
     /// - Built-ins: Name is non-empty
-    /// - VST3: Absolute paths, expected file extensions, no directory traversal
+    /// - VST3/CLAP: Absolute paths, expected file extensions, no directory traversal
     ///
     /// # Errors
     ///
@@ -71,44 +125,48 @@ impl PluginSpec {
                 }
                 Ok(())
             }
-            Self::Vst3 { path, .. } => {
-                // Check if path is absolute
-                if !path.is_absolute() {
-                    return Err(SessionError::InvalidPath(format!(
-                        "VST3 path must be absolute, got: {}",
-                        path.display()
-                    )));
-                }
+            Self::Vst3 { path, .. } => Self::validate_path("VST3", "vst3", path),
+            Self::Clap { path, .. } => Self::validate_path("CLAP", "clap", path),
+        }
+    }
 
-                // Check for directory traversal
-                if path.components().any(|c| {
-                    matches!(
-                        c,
-                        std::path::Component::ParentDir | std::path::Component::CurDir
-                    )
-                }) {
-                    return Err(SessionError::InvalidPath(format!(
-                        "VST3 path contains invalid components (.. or .): {}",
-                        path.display()
-                    )));
-                }
+    /// Validate a plugin file path for the given format
+    fn validate_path(format: &str, extension: &str, path: &Path) -> Result<(), SessionError> {
+        // Check if path is absolute
+        if !path.is_absolute() {
+            return Err(SessionError::InvalidPath(format!(
+                "{format} path must be absolute, got: {}",
+                path.display()
+            )));
+        }
 
-                // Check file extension
-                if let Some(ext) = path.extension() {
-                    if ext != "vst3" {
-                        tracing::warn!(
-                            "VST3 path has unexpected extension '{}': {}",
-                            ext.to_string_lossy(),
-                            path.display()
-                        );
-                    }
-                } else {
-                    tracing::warn!("VST3 path has no extension: {}", path.display());
-                }
+        // Check for directory traversal
+        if path.components().any(|c| {
+            matches!(
+                c,
+                std::path::Component::ParentDir | std::path::Component::CurDir
+            )
+        }) {
+            return Err(SessionError::InvalidPath(format!(
+                "{format} path contains invalid components (.. or .): {}",
+                path.display()
+            )));
+        }
 
-                Ok(())
+        // Check file extension
+        if let Some(ext) = path.extension() {
+            if ext != extension {
+                tracing::warn!(
+                    "{format} path has unexpected extension '{}': {}",
+                    ext.to_string_lossy(),
+                    path.display()
+                );
             }
+        } else {
+            tracing::warn!("{format} path has no extension: {}", path.display());
         }
+
+        Ok(())
     }
 }
 
@@ -126,16 +184,42 @@ pub struct SessionNode {
 
     /// Number of output channels
     pub outputs: usize,
+
+    /// Opaque plugin state from `Plugin::save_state`, base64-encoded
+    ///
+    /// Restored before the parameter values, so it covers state that isn't
+    /// exposed as parameters. `None` for plugins without extra state.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
 }
 
 /// Connection between two nodes
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct SessionConnection {
     /// Source node ID
     pub from: usize,
 
     /// Destination node ID
     pub to: usize,
+
+    /// Source output channel and destination input channel for a single-channel
+    /// connection, or `None` for a whole-bus connection (see `AudioGraph::connect`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channels: Option<(usize, usize)>,
+
+    /// Level the source is mixed in at (see `AudioGraph::set_connection_gain`)
+    #[serde(default = "unity_gain", skip_serializing_if = "is_unity_gain")]
+    pub gain: f32,
+}
+
+/// Connection gain for sessions saved before connections had one
+const fn unity_gain() -> f32 {
+    1.0
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // serde passes a reference
+fn is_unity_gain(gain: &f32) -> bool {
+    *gain == 1.0
 }
 
 /// The complete audio graph structure
@@ -148,6 +232,16 @@ pub struct SessionGraph {
     pub connections: Vec<SessionConnection>,
 }
 
+/// A named position on the timeline (a cue point)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionMarker {
+    /// Position in frames from the start
+    pub position: u64,
+
+    /// Name shown for the marker
+    pub label: String,
+}
+
 /// Top-level session structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Session {
@@ -165,6 +259,10 @@ pub struct Session {
 
     /// The audio graph
     pub graph: SessionGraph,
+
+    /// Markers on the timeline, in position order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub markers: Vec<SessionMarker>,
 }
 
 impl Session {
@@ -180,6 +278,7 @@ impl Session {
                 nodes: Vec::new(),
                 connections: Vec::new(),
             },
+            markers: Vec::new(),
         }
     }
 
@@ -228,49 +327,80 @@ impl Session {
     /// # Errors
     ///
     /// Returns error if any node has an unknown plugin source (cannot be serialized)
+    /// or a plugin fails to save its state
     pub fn from_graph(graph: &AudioGraph, name: impl Into<String>) -> Result<Self, SessionError> {
         let mut nodes = Vec::new();
         let mut connections = Vec::new();
 
-        // Convert nodes
-        for node in graph.nodes() {
+        // Convert nodes in ID order, so the file is stable between saves
+        let mut graph_nodes: Vec<_> = graph.nodes().collect();
+        graph_nodes.sort_unstable_by_key(|node| node.id());
+
+        for node in graph_nodes {
             // Extract parameter values
             let mut parameters = HashMap::new();
             for param in node.plugin().parameters() {
                 if let Ok(value) = node.plugin().get_parameter(param.id) {
-                    parameters.insert(param.id, f64::from(value));
+                    parameters.insert(param.id, value);
                 }
             }
 
             // Convert plugin source to spec
-            let plugin_spec = match node.source() {
-                PluginSource::Builtin { name } => PluginSpec::Builtin {
-                    name: name.clone(),
-                    parameters,
-                },
-                PluginSource::Vst3 { path } => PluginSpec::Vst3 {
-                    path: path.clone(),
-                    parameters,
-                },
-                PluginSource::Unknown => {
-                    return Err(SessionError::UnknownPluginSource { node_id: node.id() });
-                }
-            };
+            let plugin_spec = PluginSpec::from_source(node.source(), parameters)
+                .ok_or_else(|| SessionError::UnknownPluginSource { node_id: node.id() })?;
+
+            let state = node
+                .plugin()
+                .save_state()
+                .map_err(|e| SessionError::StateFailed {
+                    node_id: node.id(),
+                    reason: e.to_string(),
+                })?
+                .map(|bytes| BASE64.encode(bytes));
 
             nodes.push(SessionNode {
                 id: node.id(),
                 plugin: plugin_spec,
                 inputs: node.inputs(),
                 outputs: node.outputs(),
+                state,
             });
         }
 
-        // Convert connections
-        for conn in graph.connections() {
-            connections.push(SessionConnection {
-                from: conn.from,
-                to: conn.to,
-            });
+        // Convert connections, collapsing whole-bus wiring back into one entry
+        let mut graph_connections = graph.connections();
+        graph_connections.sort_unstable_by_key(|c| (c.from, c.to, c.from_ch, c.to_ch));
+
+        for pair in graph_connections.chunk_by(|a, b| (a.from, a.to) == (b.from, b.to)) {
+            let (from, to) = (pair[0].from, pair[0].to);
+            let bus_channels = graph
+                .node(from)
+                .zip(graph.node(to))
+                .map_or(0, |(source, dest)| source.outputs().min(dest.inputs()));
+
+            // Connections between two nodes share a gain
+            let gain = pair[0].gain;
+            let is_whole_bus = pair.len() == bus_channels
+                && pair
+                    .iter()
+                    .enumerate()
+                    .all(|(ch, conn)| conn.from_ch == ch && conn.to_ch == ch);
+
+            if is_whole_bus {
+                connections.push(SessionConnection {
+                    from,
+                    to,
+                    channels: None,
+                    gain,
+                });
+            } else {
+                connections.extend(pair.iter().map(|conn| SessionConnection {
+                    from,
+                    to,
+                    channels: Some((conn.from_ch, conn.to_ch)),
+                    gain: conn.gain,
+                }));
+            }
         }
 
         Ok(Self {
@@ -279,31 +409,78 @@ impl Session {
             sample_rate: graph.sample_rate(),
             block_size: graph.block_size(),
             graph: SessionGraph { nodes, connections },
+            markers: Vec::new(),
         })
     }
 
-    /// Reconstruct an audio graph from this session
+    /// Check that this session was saved at the engine's sample rate
+    ///
+    /// Plugins restored at another rate would play back pitched and mistimed,
+    /// so callers that want to refuse such sessions (rather than letting
+    /// [`Session::to_graph`] convert them with a warning) check first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SessionError::SampleRateMismatch`] if the rates differ
+    pub fn verify_compatible(&self, config: &AudioConfig) -> Result<(), SessionError> {
+        if self.sample_rate == config.sample_rate {
+            Ok(())
+        } else {
+            Err(SessionError::SampleRateMismatch {
+                session: self.sample_rate,
+                engine: config.sample_rate,
+            })
+        }
+    }
+
+    /// Reconstruct an audio graph from this session, for an engine running with `config`
     ///
     /// The `plugin_loader` callback is responsible for instantiating plugins
     /// based on their `PluginSpec`. This avoids circular dependencies between crates.
     ///
+    /// Plugins are initialized at the engine's sample rate and block size, not
+    /// the ones stored in the session - a mismatch is logged as a warning. Use
+    /// [`Session::verify_compatible`] to reject it instead.
+    ///
+    /// Every node is recreated under its session ID, so connections (and anything
+    /// else holding node IDs) stay valid, and nodes added later get IDs above the
+    /// highest restored one. Every node exists before the first connection is made.
+    ///
+    /// Saved plugin state is restored before the parameter values. Parameters
+    /// the loaded plugin no longer exposes are skipped with a warning.
+    ///
     /// # Errors
     ///
     /// Returns error if plugin instantiation fails or graph construction fails
-    pub fn to_graph<F>(&self, mut plugin_loader: F) -> Result<AudioGraph, SessionError>
+    pub fn to_graph<F>(
+        &self,
+        config: &AudioConfig,
+        mut plugin_loader: F,
+    ) -> Result<AudioGraph, SessionError>
     where
         F: FnMut(&PluginSpec) -> Result<Box<dyn Plugin>, String>,
     {
-        let mut graph = AudioGraph::with_config(self.sample_rate, self.block_size);
-        let mut node_id_map = HashMap::new(); // Session ID -> Graph ID
+        if self.sample_rate != config.sample_rate {
+            tracing::warn!(
+                "Session '{}' was saved at {}Hz but the engine runs at {}Hz; \
+                 plugins are initialized at the engine rate",
+                self.name,
+                self.sample_rate,
+                config.sample_rate
+            );
+        }
+        let mut graph = AudioGraph::with_config(config.sample_rate, config.block_size);
 
-        // Create all nodes
         for session_node in &self.graph.nodes {
+            if graph.node(session_node.id).is_some() {
+                return Err(SessionError::InvalidData(format!(
+                    "Duplicate node ID {}",
+                    session_node.id
+                )));
+            }
+
             // Get plugin identifier for error messages
-            let plugin_path = match &session_node.plugin {
-                PluginSpec::Builtin { name, .. } => format!("builtin:{name}"),
-                PluginSpec::Vst3 { path, .. } => path.display().to_string(),
-            };
+            let plugin_path = session_node.plugin.display_name();
 
             // Load the plugin
             let plugin = plugin_loader(&session_node.plugin).map_err(|e| {
@@ -313,32 +490,55 @@ impl Session {
                 }
             })?;
 
-            // Determine plugin source for the graph node
-            let source = match &session_node.plugin {
-                PluginSpec::Builtin { name, .. } => PluginSource::Builtin { name: name.clone() },
-                PluginSpec::Vst3 { path, .. } => PluginSource::Vst3 { path: path.clone() },
-            };
+            // Add node to graph under its saved ID
+            let node_id = graph
+                .add_node_with_id(session_node.id, plugin, session_node.plugin.source())
+                .map_err(|e| SessionError::PluginLoadFailed {
+                    plugin_path: plugin_path.clone(),
+                    reason: e.to_string(),
+                })?;
 
-            // Add node to graph
-            let graph_id =
+            // Restore opaque plugin state
+            if let Some(state) = &session_node.state {
+                let state_error = |reason: String| SessionError::StateFailed {
+                    node_id: session_node.id,
+                    reason,
+                };
+                let bytes = BASE64
+                    .decode(state)
+                    .map_err(|e| state_error(format!("Invalid base64: {e}")))?;
                 graph
-                    .add_node(plugin, source)
-                    .map_err(|e| SessionError::PluginLoadFailed {
-                        plugin_path: plugin_path.clone(),
-                        reason: e.to_string(),
-                    })?;
-
-            node_id_map.insert(session_node.id, graph_id);
+                    .load_node_state(node_id, &bytes)
+                    .map_err(|e| state_error(e.to_string()))?;
+            }
 
             // Restore parameters
             let parameters = match &session_node.plugin {
-                PluginSpec::Builtin { parameters, .. } | PluginSpec::Vst3 { parameters, .. } => {
-                    parameters
-                }
+                PluginSpec::Builtin { parameters, .. }
+                | PluginSpec::Vst3 { parameters, .. }
+                | PluginSpec::Clap { parameters, .. } => parameters,
             };
             for (&param_id, &value) in parameters {
+                // A newer plugin version may have dropped the parameter (one
+                // it no longer lists but still reads, like the gain
+                // builtin's linear gain, is kept for older sessions)
+                let exists = graph.node(node_id).is_some_and(|node| {
+                    node.plugin()
+                        .parameters()
+                        .iter()
+                        .any(|param| param.id == param_id)
+                        || node.plugin().get_parameter(param_id).is_ok()
+                });
+                if !exists {
+                    tracing::warn!(
+                        "Skipping parameter {param_id} on node {}: not exposed by {plugin_path}",
+                        session_node.id
+                    );
+                    continue;
+                }
+
                 graph
-                    .set_node_parameter(graph_id, param_id, value as f32)
+                    .set_node_parameter(node_id, param_id, value)
                     .map_err(|e| SessionError::ParameterFailed {
                         node_id: session_node.id,
                         param_id,
@@ -347,29 +547,22 @@ impl Session {
             }
         }
 
-        // Create all connections
+        // Create all connections (all nodes exist by now)
         for session_conn in &self.graph.connections {
-            let from =
-                node_id_map
-                    .get(&session_conn.from)
-                    .ok_or(SessionError::InvalidConnection {
-                        from: session_conn.from,
-                        to: session_conn.to,
-                    })?;
-
-            let to = node_id_map
-                .get(&session_conn.to)
-                .ok_or(SessionError::InvalidConnection {
-                    from: session_conn.from,
-                    to: session_conn.to,
-                })?;
+            let (from, to) = (session_conn.from, session_conn.to);
+            if graph.node(from).is_none() || graph.node(to).is_none() {
+                return Err(SessionError::InvalidConnection { from, to });
+            }
 
-            graph
-                .connect(*from, *to)
-                .map_err(|_e| SessionError::InvalidConnection {
-                    from: session_conn.from,
-                    to: session_conn.to,
-                })?;
+            let result = match session_conn.channels {
+                None => graph.connect(from, to),
+                Some((from_ch, to_ch)) => graph.connect_channels(from, from_ch, to, to_ch),
+            }
+            .and_then(|()| graph.set_connection_gain(from, to, session_conn.gain));
+            result.map_err(|_e| SessionError::InvalidConnection {
+                from: session_conn.from,
+                to: session_conn.to,
+            })?;
         }
 
         Ok(graph)
@@ -391,6 +584,10 @@ pub enum SessionError {
     #[error("Deserialization failed: {0}")]
     DeserializationFailed(String),
 
+    /// Session was saved at a different sample rate than the engine runs at
+    #[error("Session sample rate {session}Hz does not match the engine's {engine}Hz")]
+    SampleRateMismatch { session: u32, engine: u32 },
+
     /// Session format version is not supported
     #[error("Unsupported session version: {0}")]
     UnsupportedVersion(u32),
@@ -419,6 +616,10 @@ pub enum SessionError {
         reason: String,
     },
 
+    /// Plugin state could not be saved or restored
+    #[error("Failed to save or restore the state of node {node_id}: {reason}")]
+    StateFailed { node_id: usize, reason: String },
+
     /// Graph contains nodes with unknown plugin sources (can't be serialized)
     #[error("Node {node_id} has unknown plugin source and cannot be serialized")]
     UnknownPluginSource { node_id: usize },
@@ -456,6 +657,7 @@ mod tests {
             },
             inputs: 2,
             outputs: 2,
+            state: None,
         });
 
         session.graph.nodes.push(SessionNode {
@@ -466,12 +668,15 @@ mod tests {
             },
             inputs: 2,
             outputs: 2,
+            state: None,
         });
 
-        session
-            .graph
-            .connections
-            .push(SessionConnection { from: 0, to: 1 });
+        session.graph.connections.push(SessionConnection {
+            from: 0,
+            to: 1,
+            channels: None,
+            gain: 1.0,
+        });
 
         // Serialize to RON
         let ron_string = ron::ser::to_string_pretty(&session, ron::ser::PrettyConfig::default())
@@ -488,6 +693,37 @@ mod tests {
         assert_eq!(deserialized.graph.connections.len(), 1);
     }
 
+    #[test]
+    fn test_markers_round_trip() {
+        let mut session = Session::new("Review", 48000, 512);
+        let ron_string = ron::ser::to_string_pretty(&session, ron::ser::PrettyConfig::default())
+            .expect("Serialization should succeed");
+        assert!(
+            !ron_string.contains("markers"),
+            "Sessions without markers don't mention them"
+        );
+
+        session.markers = vec![
+            SessionMarker {
+                position: 48000,
+                label: "Intro".to_string(),
+            },
+            SessionMarker {
+                position: 1_440_000,
+                label: "Solo".to_string(),
+            },
+        ];
+
+        let file = tempfile::NamedTempFile::new().expect("Failed to create temp file");
+        session.save(file.path()).unwrap();
+        let loaded = Session::load(file.path()).unwrap();
+        assert_eq!(loaded.markers, session.markers);
+
+        // Files written before markers existed still load
+        let old: Session = ron::from_str(&ron_string).expect("Deserialization should succeed");
+        assert!(old.markers.is_empty());
+    }
+
     #[test]
     fn test_path_validation_absolute() {
         let spec = PluginSpec::Vst3 {
@@ -536,6 +772,21 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_clap_path_validation() {
+        let spec = PluginSpec::Clap {
+            path: PathBuf::from("/usr/lib/clap/Test.clap"),
+            parameters: HashMap::new(),
+        };
+        assert!(spec.validate().is_ok());
+
+        let spec = PluginSpec::Clap {
+            path: PathBuf::from("../Test.clap"),
+            parameters: HashMap::new(),
+        };
+        assert!(matches!(spec.validate(), Err(SessionError::InvalidPath(_))));
+    }
+
     #[test]
     fn test_session_load_validates_paths() {
         use std::io::Write;
@@ -583,6 +834,385 @@ mod tests {
         // The behavior is tested indirectly through the session demo.
     }
 
+    /// Engine config matching the sessions built in these tests
+    fn config() -> AudioConfig {
+        AudioConfig {
+            sample_rate: 48000,
+            block_size: 512,
+            ..AudioConfig::default()
+        }
+    }
+
+    /// Load built-in processors only (for graph round-trip tests)
+    fn load_builtin(spec: &PluginSpec) -> Result<Box<dyn Plugin>, String> {
+        match spec {
+            PluginSpec::Builtin { name, .. } => {
+                crate::builtin::create_builtin(name).ok_or_else(|| format!("Unknown: {name}"))
+            }
+            PluginSpec::Vst3 { .. } | PluginSpec::Clap { .. } => {
+                Err("Plugin files not available in tests".to_string())
+            }
+        }
+    }
+
+    #[test]
+    fn test_channel_connections_round_trip() {
+        let mut graph = AudioGraph::with_config(48000, 512);
+        let source = PluginSource::Builtin {
+            name: "gain".to_string(),
+        };
+        let a = graph
+            .add_node(
+                crate::builtin::create_builtin("gain").unwrap(),
+                source.clone(),
+            )
+            .unwrap();
+        let b = graph
+            .add_node(
+                crate::builtin::create_builtin("gain").unwrap(),
+                source.clone(),
+            )
+            .unwrap();
+        let c = graph
+            .add_node(crate::builtin::create_builtin("gain").unwrap(), source)
+            .unwrap();
+
+        graph.connect(a, b).unwrap();
+        graph.connect_channels(b, 0, c, 1).unwrap();
+        graph.set_connection_gain(b, c, 0.25).unwrap();
+
+        let session = Session::from_graph(&graph, "Routing").unwrap();
+
+        // The whole-bus connection is stored once, the channel connection explicitly
+        assert_eq!(session.graph.connections.len(), 2);
+        assert!(session.graph.connections.contains(&SessionConnection {
+            from: a,
+            to: b,
+            channels: None,
+            gain: 1.0,
+        }));
+        assert!(session.graph.connections.contains(&SessionConnection {
+            from: b,
+            to: c,
+            channels: Some((0, 1)),
+            gain: 0.25,
+        }));
+
+        let restored = session.to_graph(&config(), load_builtin).unwrap();
+        assert_eq!(restored.connections(), graph.connections());
+        let gains: Vec<f32> = restored.connections().iter().map(|c| c.gain).collect();
+        assert_eq!(gains, vec![1.0, 1.0, 0.25]);
+    }
+
+    #[test]
+    fn test_diamond_round_trip() {
+        // A -> B -> D and A -> C -> D
+        let mut graph = AudioGraph::with_config(48000, 512);
+        let mut ids = Vec::new();
+        for _ in 0..4 {
+            let source = PluginSource::Builtin {
+                name: "gain".to_string(),
+            };
+            ids.push(
+                graph
+                    .add_node(crate::builtin::create_builtin("gain").unwrap(), source)
+                    .unwrap(),
+            );
+        }
+        let (a, b, c, d) = (ids[0], ids[1], ids[2], ids[3]);
+        graph.connect(a, b).unwrap();
+        graph.connect(a, c).unwrap();
+        graph.connect(b, d).unwrap();
+        graph.connect(c, d).unwrap();
+
+        let file = tempfile::NamedTempFile::new().expect("Failed to create temp file");
+        Session::from_graph(&graph, "Diamond")
+            .unwrap()
+            .save(file.path())
+            .unwrap();
+
+        // Reverse the node order in the loaded session - IDs must still line up
+        let mut session = Session::load(file.path()).unwrap();
+        assert_eq!(session.graph.nodes.len(), 4);
+        assert_eq!(session.graph.connections.len(), 4);
+        session.graph.nodes.reverse();
+
+        let restored = session.to_graph(&config(), load_builtin).unwrap();
+        assert_eq!(restored.connections(), graph.connections());
+    }
+
+    #[test]
+    fn test_node_ids_survive_round_trip() {
+        let mut graph = AudioGraph::with_config(48000, 512);
+        for _ in 0..6 {
+            let source = PluginSource::Builtin {
+                name: "gain".to_string(),
+            };
+            graph
+                .add_node(crate::builtin::create_builtin("gain").unwrap(), source)
+                .unwrap();
+        }
+        // Leave gaps: IDs {0, 2, 5}
+        for id in [1, 3, 4] {
+            graph.remove_node(id);
+        }
+        graph.connect(0, 2).unwrap();
+        graph.connect(2, 5).unwrap();
+
+        let file = tempfile::NamedTempFile::new().expect("Failed to create temp file");
+        Session::from_graph(&graph, "Gaps")
+            .unwrap()
+            .save(file.path())
+            .unwrap();
+        let mut restored = Session::load(file.path())
+            .unwrap()
+            .to_graph(&config(), load_builtin)
+            .unwrap();
+
+        let mut ids: Vec<_> = restored.nodes().map(crate::graph::AudioNode::id).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, vec![0, 2, 5]);
+        assert_eq!(restored.connections(), graph.connections());
+
+        // New nodes continue after the highest restored ID
+        let source = PluginSource::Builtin {
+            name: "gain".to_string(),
+        };
+        let next = restored
+            .add_node(crate::builtin::create_builtin("gain").unwrap(), source)
+            .unwrap();
+        assert_eq!(next, 6);
+    }
+
+    #[test]
+    fn test_duplicate_node_ids_are_rejected() {
+        let mut session = Session::new("Duplicates", 48000, 512);
+        for _ in 0..2 {
+            session.graph.nodes.push(SessionNode {
+                id: 3,
+                plugin: PluginSpec::Builtin {
+                    name: "gain".to_string(),
+                    parameters: HashMap::new(),
+                },
+                inputs: 2,
+                outputs: 2,
+                state: None,
+            });
+        }
+
+        assert!(matches!(
+            session.to_graph(&config(), load_builtin),
+            Err(SessionError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_sample_rate_mismatch() {
+        let mut session = Session::new("CD rate", 44100, 256);
+        session.graph.nodes.push(SessionNode {
+            id: 0,
+            plugin: PluginSpec::Builtin {
+                name: "gain".to_string(),
+                parameters: HashMap::new(),
+            },
+            inputs: 2,
+            outputs: 2,
+            state: None,
+        });
+
+        assert!(matches!(
+            session.verify_compatible(&config()),
+            Err(SessionError::SampleRateMismatch {
+                session: 44100,
+                engine: 48000
+            })
+        ));
+        session.sample_rate = 48000;
+        assert!(session.verify_compatible(&config()).is_ok());
+
+        // Without the check, the graph follows the engine rather than the file
+        session.sample_rate = 44100;
+        let graph = session.to_graph(&config(), load_builtin).unwrap();
+        assert_eq!(graph.sample_rate(), 48000);
+        assert_eq!(graph.block_size(), 512);
+    }
+
+    #[test]
+    fn test_parameters_round_trip() {
+        let mut graph = AudioGraph::with_config(48000, 512);
+        let node = graph
+            .add_node(
+                crate::builtin::create_builtin("gain").unwrap(),
+                PluginSource::Builtin {
+                    name: "gain".to_string(),
+                },
+            )
+            .unwrap();
+        graph.set_node_parameter(node, 0, 0.3).unwrap();
+
+        // Saved as the listed (dB) gain parameter
+        let session = Session::from_graph(&graph, "Params").unwrap();
+        let PluginSpec::Builtin { parameters, .. } = &session.graph.nodes[0].plugin else {
+            panic!("Expected a built-in spec");
+        };
+        let db = parameters[&crate::builtin::gain::PARAM_GAIN_DB];
+        assert!((db - vvdaw_core::linear_to_db(0.3)).abs() < 1e-4);
+
+        let restored = session.to_graph(&config(), load_builtin).unwrap();
+        let value = restored
+            .node(node)
+            .unwrap()
+            .plugin()
+            .get_parameter(0)
+            .unwrap();
+        assert!((value - 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_unknown_parameter_is_skipped() {
+        let mut session = Session::new("Stale", 48000, 512);
+        session.graph.nodes.push(SessionNode {
+            id: 0,
+            plugin: PluginSpec::Builtin {
+                name: "gain".to_string(),
+                parameters: HashMap::from([(0, 0.3), (99, 0.5)]),
+            },
+            inputs: 2,
+            outputs: 2,
+            state: None,
+        });
+
+        // Parameter 99 doesn't exist - the rest of the session still loads
+        let graph = session.to_graph(&config(), load_builtin).unwrap();
+        let value = graph.node(0).unwrap().plugin().get_parameter(0).unwrap();
+        assert!((value - 0.3).abs() < 1e-6);
+    }
+
+    /// Gain processor that also carries an opaque state blob
+    struct StatefulPlugin {
+        inner: Box<dyn Plugin>,
+        state: Vec<u8>,
+    }
+
+    impl Plugin for StatefulPlugin {
+        fn info(&self) -> &vvdaw_plugin::PluginInfo {
+            self.inner.info()
+        }
+
+        fn initialize(
+            &mut self,
+            sample_rate: vvdaw_core::SampleRate,
+            max_block_size: vvdaw_core::Frames,
+        ) -> Result<(), vvdaw_plugin::PluginError> {
+            self.inner.initialize(sample_rate, max_block_size)
+        }
+
+        fn process(
+            &mut self,
+            audio: &mut vvdaw_plugin::AudioBuffer,
+            events: &vvdaw_plugin::EventBuffer,
+        ) -> Result<(), vvdaw_plugin::PluginError> {
+            self.inner.process(audio, events)
+        }
+
+        fn set_parameter(&mut self, id: u32, value: f32) -> Result<(), vvdaw_plugin::PluginError> {
+            self.inner.set_parameter(id, value)
+        }
+
+        fn get_parameter(&self, id: u32) -> Result<f32, vvdaw_plugin::PluginError> {
+            self.inner.get_parameter(id)
+        }
+
+        fn parameters(&self) -> Vec<vvdaw_plugin::ParameterInfo> {
+            self.inner.parameters()
+        }
+
+        fn input_channels(&self) -> usize {
+            self.inner.input_channels()
+        }
+
+        fn output_channels(&self) -> usize {
+            self.inner.output_channels()
+        }
+
+        fn deactivate(&mut self) {}
+
+        fn save_state(&self) -> Result<Option<Vec<u8>>, vvdaw_plugin::PluginError> {
+            Ok(Some(self.state.clone()))
+        }
+
+        fn load_state(&mut self, state: &[u8]) -> Result<(), vvdaw_plugin::PluginError> {
+            self.state = state.to_vec();
+            Ok(())
+        }
+    }
+
+    fn load_stateful(spec: &PluginSpec) -> Result<Box<dyn Plugin>, String> {
+        Ok(Box::new(StatefulPlugin {
+            inner: load_builtin(spec)?,
+            state: Vec::new(),
+        }))
+    }
+
+    #[test]
+    fn test_plugin_state_round_trip() {
+        let mut graph = AudioGraph::with_config(48000, 512);
+        let source = PluginSource::Builtin {
+            name: "gain".to_string(),
+        };
+        let stateful = load_stateful(&PluginSpec::Builtin {
+            name: "gain".to_string(),
+            parameters: HashMap::new(),
+        })
+        .unwrap();
+        let node = graph.add_node(stateful, source.clone()).unwrap();
+        graph.load_node_state(node, &[0, 1, 2, 255]).unwrap();
+        let plain = graph
+            .add_node(crate::builtin::create_builtin("gain").unwrap(), source)
+            .unwrap();
+
+        let file = tempfile::NamedTempFile::new().expect("Failed to create temp file");
+        Session::from_graph(&graph, "State")
+            .unwrap()
+            .save(file.path())
+            .unwrap();
+
+        // Plugins without extra state don't store any
+        let session = Session::load(file.path()).unwrap();
+        assert_eq!(session.graph.nodes[0].state.as_deref(), Some("AAEC/w=="));
+        assert!(session.graph.nodes[plain].state.is_none());
+
+        let restored = session.to_graph(&config(), load_stateful).unwrap();
+        let state = restored.node(node).unwrap().plugin().save_state().unwrap();
+        assert_eq!(state, Some(vec![0, 1, 2, 255]));
+    }
+
+    #[test]
+    fn test_invalid_state_is_rejected() {
+        let mut session = Session::new("Corrupt", 48000, 512);
+        session.graph.nodes.push(SessionNode {
+            id: 0,
+            plugin: PluginSpec::Builtin {
+                name: "gain".to_string(),
+                parameters: HashMap::new(),
+            },
+            inputs: 2,
+            outputs: 2,
+            state: Some("not base64!".to_string()),
+        });
+        assert!(matches!(
+            session.to_graph(&config(), load_stateful),
+            Err(SessionError::StateFailed { node_id: 0, .. })
+        ));
+
+        // Plugins without state support refuse a blob rather than dropping it
+        session.graph.nodes[0].state = Some("AAEC/w==".to_string());
+        assert!(matches!(
+            session.to_graph(&config(), load_builtin),
+            Err(SessionError::StateFailed { node_id: 0, .. })
+        ));
+    }
+
     #[test]
     fn test_error_message_specificity() {
         // Test that specific error variants provide useful information
@@ -0,0 +1,441 @@
+//! Spectrum analysis of the master output or a single node.
+//!
+//! An FFT has no place in the audio callback, so analysis is split the same
+//! way as recording: [`SpectrumTap`] lives in the callback and copies a mono
+//! mix of each block into a lock-free ring buffer, and an analyzer thread
+//! keeps the latest `fft_size` samples. Every `sample_rate /
+//! updates_per_second` samples it windows them (Hann), runs an FFT and
+//! queues an [`AudioEvent::Spectrum`] on a second ring, which the callback
+//! forwards to the UI.
+//!
+//! Samples that don't fit in the ring (the analyzer fell behind) are
+//! dropped - the next spectrum is simply taken over a slightly torn window.
+
+use anyhow::{Context, Result};
+use rtrb::{Consumer, Producer, RingBuffer};
+use std::f64::consts::TAU;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use vvdaw_comms::{AudioEvent, DEFAULT_SPECTRUM_RATE, SpectrumConfig};
+use vvdaw_core::{Frames, Sample, SampleRate};
+
+/// Smallest FFT the analyzer runs
+pub const MIN_FFT_SIZE: usize = 64;
+
+/// Largest FFT the analyzer runs
+pub const MAX_FFT_SIZE: usize = 16384;
+
+/// Update rate range (spectra per second)
+const MIN_UPDATES_PER_SECOND: f32 = 1.0;
+const MAX_UPDATES_PER_SECOND: f32 = 120.0;
+
+/// Samples buffered between the audio thread and the analyzer
+const SAMPLE_CAPACITY: usize = MAX_FFT_SIZE * 4;
+
+/// Start/stop requests buffered for the analyzer
+const CONTROL_CAPACITY: usize = 16;
+
+/// Spectra buffered for the audio thread to forward
+const REPORT_CAPACITY: usize = 4;
+
+/// How long the analyzer thread sleeps between drains
+const ANALYZER_IDLE: Duration = Duration::from_millis(5);
+
+/// Requests from the audio thread to the analyzer thread
+enum Control {
+    /// Analyze `fft_size` samples every `hop` samples
+    Start { fft_size: usize, hop: Frames },
+    /// Stop analyzing and discard samples
+    Stop,
+}
+
+/// FFT size and hop (samples between spectra) for a config, within the
+/// analyzer's limits
+fn analysis_params(config: &SpectrumConfig, sample_rate: SampleRate) -> (usize, Frames) {
+    let fft_size = config
+        .fft_size
+        .clamp(MIN_FFT_SIZE, MAX_FFT_SIZE)
+        .next_power_of_two();
+    let rate = if config.updates_per_second.is_finite() {
+        config
+            .updates_per_second
+            .clamp(MIN_UPDATES_PER_SECOND, MAX_UPDATES_PER_SECOND)
+    } else {
+        DEFAULT_SPECTRUM_RATE
+    };
+    let hop = (f64::from(sample_rate) / f64::from(rate)).round() as Frames;
+    (fft_size, hop.max(1))
+}
+
+/// Audio-thread side of the spectrum analyzer
+///
+/// # Real-Time Safety
+///
+/// Every method only pushes to or pops from preallocated ring buffers.
+/// Spectra should only be popped when they can be passed on, so their bins
+/// are never freed on the audio thread.
+pub struct SpectrumTap {
+    control: Producer<Control>,
+    samples: Producer<Sample>,
+    reports: Consumer<AudioEvent>,
+    /// Analysis in progress, if any
+    config: Option<SpectrumConfig>,
+}
+
+impl SpectrumTap {
+    /// Spawn the analyzer thread
+    ///
+    /// The thread exits once the `SpectrumTap` is dropped; join the returned
+    /// handle to wait for that.
+    pub fn spawn() -> Result<(Self, JoinHandle<()>)> {
+        let (control_tx, control_rx) = RingBuffer::new(CONTROL_CAPACITY);
+        let (samples_tx, samples_rx) = RingBuffer::new(SAMPLE_CAPACITY);
+        let (reports_tx, reports_rx) = RingBuffer::new(REPORT_CAPACITY);
+
+        let analyzer = Analyzer {
+            control: control_rx,
+            samples: samples_rx,
+            reports: reports_tx,
+            analysis: None,
+        };
+        let handle = std::thread::Builder::new()
+            .name("vvdaw-spectrum".to_string())
+            .spawn(move || analyzer.run())
+            .context("Failed to spawn spectrum analyzer thread")?;
+
+        let tap = Self {
+            control: control_tx,
+            samples: samples_tx,
+            reports: reports_rx,
+            config: None,
+        };
+        Ok((tap, handle))
+    }
+
+    /// Start analyzing with `config` (replacing any analysis), or stop with `None`
+    pub fn configure(&mut self, config: Option<SpectrumConfig>, sample_rate: SampleRate) {
+        let control = config.as_ref().map_or(Control::Stop, |config| {
+            let (fft_size, hop) = analysis_params(config, sample_rate);
+            Control::Start { fft_size, hop }
+        });
+        // Keep the current analysis if the request queue is full
+        if self.control.push(control).is_ok() {
+            self.config = config;
+        }
+    }
+
+    /// Analysis in progress, if any
+    pub const fn config(&self) -> Option<SpectrumConfig> {
+        self.config
+    }
+
+    /// Queue the first `frames` of a block of channel buffers, mixed to mono
+    ///
+    /// Does nothing while not analyzing.
+    #[allow(clippy::cast_precision_loss)] // Channel counts are tiny
+    pub fn push_block(&mut self, channels: &[Vec<Sample>], frames: Frames) {
+        if self.config.is_none() || channels.is_empty() {
+            return;
+        }
+        let frames = channels
+            .iter()
+            .map(Vec::len)
+            .min()
+            .unwrap_or(0)
+            .min(frames)
+            .min(self.samples.slots());
+        if let Ok(chunk) = self.samples.write_chunk_uninit(frames) {
+            let scale = 1.0 / channels.len() as Sample;
+            chunk.fill_from_iter((0..frames).map(|frame| {
+                channels
+                    .iter()
+                    .map(|channel| channel[frame])
+                    .sum::<Sample>()
+                    * scale
+            }));
+        }
+    }
+
+    /// Next spectrum from the analyzer thread, to forward to the UI
+    pub fn pop_report(&mut self) -> Option<AudioEvent> {
+        self.reports.pop().ok()
+    }
+}
+
+/// Windowed magnitude spectrum via an in-place radix-2 FFT
+struct Fft {
+    /// Hann window, `fft_size` points
+    window: Vec<f32>,
+    /// Scale turning a bin's magnitude into the amplitude of a sine
+    scale: f32,
+    /// `exp(-i * TAU * k / fft_size)` for the first half of the circle
+    twiddles: Vec<(f32, f32)>,
+    /// Working buffers
+    re: Vec<f32>,
+    im: Vec<f32>,
+}
+
+impl Fft {
+    /// Set up for a power-of-two `size`
+    #[allow(clippy::cast_precision_loss)] // FFT sizes are far below 2^24
+    fn new(size: usize) -> Self {
+        let window: Vec<f32> = (0..size)
+            .map(|i| (0.5 * (1.0 - (TAU * i as f64 / size as f64).cos())) as f32)
+            .collect();
+        let window_sum: f32 = window.iter().sum();
+        let twiddles = (0..size / 2)
+            .map(|k| {
+                let (sin, cos) = (-TAU * k as f64 / size as f64).sin_cos();
+                (cos as f32, sin as f32)
+            })
+            .collect();
+        Self {
+            window,
+            scale: 2.0 / window_sum,
+            twiddles,
+            re: vec![0.0; size],
+            im: vec![0.0; size],
+        }
+    }
+
+    /// Magnitudes of the first half of the bins for `samples` (oldest first)
+    fn magnitudes(&mut self, samples: impl Iterator<Item = Sample>) -> Vec<Sample> {
+        for ((re, im), (sample, window)) in self
+            .re
+            .iter_mut()
+            .zip(&mut self.im)
+            .zip(samples.zip(&self.window))
+        {
+            *re = sample * window;
+            *im = 0.0;
+        }
+        self.transform();
+
+        let bins = self.re.len() / 2;
+        self.re[..bins]
+            .iter()
+            .zip(&self.im[..bins])
+            .map(|(re, im)| re.hypot(*im) * self.scale)
+            .collect()
+    }
+
+    /// Iterative Cooley-Tukey FFT of `re` + `i * im`, in place
+    fn transform(&mut self) {
+        let n = self.re.len();
+
+        // Bit-reversal permutation
+        let mut j = 0;
+        for i in 1..n {
+            let mut bit = n >> 1;
+            while j & bit != 0 {
+                j ^= bit;
+                bit >>= 1;
+            }
+            j |= bit;
+            if i < j {
+                self.re.swap(i, j);
+                self.im.swap(i, j);
+            }
+        }
+
+        // Butterflies, doubling the transform length each pass
+        let mut len = 2;
+        while len <= n {
+            let half = len / 2;
+            let stride = n / len;
+            for start in (0..n).step_by(len) {
+                for k in 0..half {
+                    let (w_re, w_im) = self.twiddles[k * stride];
+                    let (a, b) = (start + k, start + k + half);
+                    let t_re = self.re[b].mul_add(w_re, -(self.im[b] * w_im));
+                    let t_im = self.re[b].mul_add(w_im, self.im[b] * w_re);
+                    self.re[b] = self.re[a] - t_re;
+                    self.im[b] = self.im[a] - t_im;
+                    self.re[a] += t_re;
+                    self.im[a] += t_im;
+                }
+            }
+            len <<= 1;
+        }
+    }
+}
+
+/// Running analysis on the analyzer thread
+struct Analysis {
+    fft: Fft,
+    /// The latest `fft_size` samples, circular
+    history: Vec<Sample>,
+    /// Where the next sample goes in `history` (and so the oldest sample)
+    write: usize,
+    /// Samples between spectra
+    hop: Frames,
+    /// Samples since the last spectrum
+    since_update: Frames,
+}
+
+impl Analysis {
+    fn new(fft_size: usize, hop: Frames) -> Self {
+        Self {
+            fft: Fft::new(fft_size),
+            history: vec![0.0; fft_size],
+            write: 0,
+            hop,
+            since_update: 0,
+        }
+    }
+
+    /// Add samples, returning a spectrum if one is due
+    ///
+    /// Only one spectrum is taken however many hops the samples cover, so a
+    /// stalled analyzer catches up instead of sending a burst.
+    fn push(&mut self, samples: impl Iterator<Item = Sample>) -> Option<Vec<Sample>> {
+        for sample in samples {
+            self.history[self.write] = sample;
+            self.write = (self.write + 1) % self.history.len();
+            self.since_update += 1;
+        }
+        if self.since_update < self.hop {
+            return None;
+        }
+        self.since_update %= self.hop;
+
+        let (newest, oldest) = self.history.split_at(self.write);
+        Some(self.fft.magnitudes(oldest.iter().chain(newest).copied()))
+    }
+}
+
+/// Analyzer-thread side of the spectrum analyzer
+struct Analyzer {
+    control: Consumer<Control>,
+    samples: Consumer<Sample>,
+    reports: Producer<AudioEvent>,
+    analysis: Option<Analysis>,
+}
+
+impl Analyzer {
+    /// Drain requests and samples until the audio side is dropped
+    fn run(mut self) {
+        loop {
+            let closed = self.control.is_abandoned();
+
+            while let Ok(control) = self.control.pop() {
+                self.analysis = match control {
+                    Control::Start { fft_size, hop } => Some(Analysis::new(fft_size, hop)),
+                    Control::Stop => None,
+                };
+            }
+
+            let available = self.samples.slots();
+            if let Ok(chunk) = self.samples.read_chunk(available) {
+                let spectrum = self.analysis.as_mut().and_then(|analysis| {
+                    let (first, second) = chunk.as_slices();
+                    analysis.push(first.iter().chain(second).copied())
+                });
+                chunk.commit_all();
+
+                // Dropped if the audio thread hasn't forwarded the last ones yet
+                if let Some(bins) = spectrum {
+                    let _ = self.reports.push(AudioEvent::Spectrum { bins });
+                }
+            }
+
+            if closed {
+                return;
+            }
+            std::thread::sleep(ANALYZER_IDLE);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    /// Sine with a whole number of cycles in `fft_size` samples, i.e. centered on `bin`
+    #[allow(clippy::cast_precision_loss)] // Test sizes are tiny
+    fn sine(bin: usize, fft_size: usize, frames: usize) -> Vec<Sample> {
+        (0..frames)
+            .map(|i| (TAU * (bin * i) as f64 / fft_size as f64).sin() as Sample)
+            .collect()
+    }
+
+    /// Index of the largest bin
+    fn peak_bin(bins: &[Sample]) -> usize {
+        bins.iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map_or(0, |(bin, _)| bin)
+    }
+
+    #[test]
+    fn test_fft_finds_a_sine() {
+        let mut fft = Fft::new(64);
+        let bins = fft.magnitudes(sine(8, 64, 64).into_iter());
+
+        assert_eq!(bins.len(), 32);
+        assert_eq!(peak_bin(&bins), 8);
+        // A full-scale sine centered on a bin reads 1.0
+        assert!((bins[8] - 1.0).abs() < 1e-4);
+        // Hann leaks only into the neighboring bins
+        assert!(bins[20].abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_analysis_params_are_clamped() {
+        let config = SpectrumConfig {
+            node_id: None,
+            fft_size: 1000,
+            updates_per_second: 1000.0,
+        };
+        assert_eq!(analysis_params(&config, 48000), (1024, 400));
+
+        let config = SpectrumConfig {
+            fft_size: 0,
+            updates_per_second: f32::NAN,
+            ..config
+        };
+        assert_eq!(analysis_params(&config, 48000), (MIN_FFT_SIZE, 1600));
+    }
+
+    #[test]
+    fn test_spectra_are_sent_at_the_update_rate() {
+        let mut analysis = Analysis::new(256, 100);
+        assert!(analysis.push(std::iter::repeat_n(0.0, 99)).is_none());
+        assert!(analysis.push(std::iter::once(0.0)).is_some());
+        // Several hops at once still give one spectrum
+        assert!(analysis.push(std::iter::repeat_n(0.0, 350)).is_some());
+        assert!(analysis.push(std::iter::repeat_n(0.0, 49)).is_none());
+    }
+
+    #[test]
+    fn test_tap_streams_spectra() {
+        let (mut tap, analyzer) = SpectrumTap::spawn().unwrap();
+        tap.configure(
+            Some(SpectrumConfig {
+                node_id: None,
+                fft_size: 256,
+                updates_per_second: 100.0,
+            }),
+            48000,
+        );
+
+        let block = sine(16, 256, 480);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let bins = loop {
+            tap.push_block(&[block.clone(), block.clone()], 480);
+            if let Some(AudioEvent::Spectrum { bins }) = tap.pop_report() {
+                break bins;
+            }
+            assert!(Instant::now() < deadline, "no spectrum arrived");
+            std::thread::sleep(Duration::from_millis(5));
+        };
+        assert_eq!(bins.len(), 128);
+        assert_eq!(peak_bin(&bins), 16);
+
+        tap.configure(None, 48000);
+        assert!(tap.config().is_none());
+        drop(tap);
+        analyzer.join().unwrap();
+    }
+}
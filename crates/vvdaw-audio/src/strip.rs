@@ -0,0 +1,288 @@
+//! Channel strips: the standard gain -> pan chain, metered at its output.
+//!
+//! A strip is nothing but ordinary nodes and connections in an
+//! [`AudioGraph`]; [`ChannelStrip`] remembers their IDs so callers can set
+//! gain and pan (or read the meter) without tracking them by hand. Effects
+//! appended to a strip go after the pan, and the meter moves along to stay
+//! at the end of the chain.
+//!
+//! ```
+//! use vvdaw_audio::AudioGraph;
+//! use vvdaw_audio::strip::ChannelStrip;
+//!
+//! let mut graph = AudioGraph::with_config(48000, 512);
+//! let strip = ChannelStrip::build(&mut graph).unwrap();
+//! strip.set_gain(&mut graph, 0.5).unwrap();
+//! strip.set_pan(&mut graph, -0.25).unwrap();
+//! ```
+
+use crate::builtin::{self, gain, pan};
+use crate::graph::{AudioGraph, AudioNode, MeterReading, PluginSource};
+use vvdaw_plugin::{Plugin, PluginError};
+
+/// Handle to a gain -> pan chain (plus any appended effects) in a graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelStrip {
+    /// Gain node (the strip's input)
+    gain: usize,
+    /// Pan node
+    pan: usize,
+    /// Every node in signal order, gain first
+    chain: Vec<usize>,
+}
+
+impl ChannelStrip {
+    /// Add a gain and a pan node wired in series, metering the pan output
+    ///
+    /// Nothing is connected to the strip's input or from its output - wire
+    /// those with [`ChannelStrip::input`] and [`ChannelStrip::output`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a node can't be added or connected
+    pub fn build(graph: &mut AudioGraph) -> Result<Self, String> {
+        let gain = add_builtin(graph, "gain")?;
+        let pan = match add_builtin(graph, "pan") {
+            Ok(pan) => pan,
+            Err(e) => {
+                graph.remove_node(gain);
+                return Err(e);
+            }
+        };
+
+        let strip = Self {
+            gain,
+            pan,
+            chain: vec![gain, pan],
+        };
+        if let Err(e) = graph.connect(gain, pan) {
+            strip.remove(graph);
+            return Err(e);
+        }
+        graph.enable_metering(pan);
+
+        Ok(strip)
+    }
+
+    /// Node to connect sources to
+    pub fn input(&self) -> usize {
+        self.gain
+    }
+
+    /// Node at the end of the chain, to connect onward
+    pub fn output(&self) -> usize {
+        self.chain.last().copied().unwrap_or(self.pan)
+    }
+
+    /// The strip's gain node
+    pub const fn gain_node(&self) -> usize {
+        self.gain
+    }
+
+    /// The strip's pan node
+    pub const fn pan_node(&self) -> usize {
+        self.pan
+    }
+
+    /// All of the strip's nodes in signal order
+    pub fn nodes(&self) -> &[usize] {
+        &self.chain
+    }
+
+    /// Set the strip's linear gain (0.0 to +12 dB, see [`gain::GainProcessor`])
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the gain node is no longer in the graph
+    pub fn set_gain(&self, graph: &mut AudioGraph, gain: f32) -> Result<(), PluginError> {
+        graph.set_node_parameter(self.gain, gain::PARAM_GAIN, gain)
+    }
+
+    /// Set the strip's gain in dB (-60 = silence to +12)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the gain node is no longer in the graph
+    pub fn set_gain_db(&self, graph: &mut AudioGraph, db: f32) -> Result<(), PluginError> {
+        graph.set_node_parameter(self.gain, gain::PARAM_GAIN_DB, db)
+    }
+
+    /// Set the strip's pan (-1.0 = left, 1.0 = right, see [`pan::PanProcessor`])
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the pan node is no longer in the graph
+    pub fn set_pan(&self, graph: &mut AudioGraph, pan: f32) -> Result<(), PluginError> {
+        graph.set_node_parameter(self.pan, pan::PARAM_PAN, pan)
+    }
+
+    /// Level at the end of the strip from the last processed block
+    pub fn meter(&self, graph: &AudioGraph) -> Option<MeterReading> {
+        graph.node(self.output()).and_then(AudioNode::meter)
+    }
+
+    /// Add an effect at the end of the strip
+    ///
+    /// Whatever the strip's output fed now comes from the new node, and the
+    /// meter moves to it. Returns the new node's ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the plugin can't be added or wired in; the strip is
+    /// left as it was
+    pub fn append(
+        &mut self,
+        graph: &mut AudioGraph,
+        plugin: Box<dyn Plugin>,
+        source: PluginSource,
+    ) -> Result<usize, String> {
+        let previous = self.output();
+        let node = graph.add_node(plugin, source).map_err(|e| e.to_string())?;
+        if let Err(e) = graph.connect(previous, node) {
+            graph.remove_node(node);
+            return Err(e);
+        }
+
+        // Move the strip's downstream connections onto the new node
+        let downstream: Vec<_> = graph
+            .connections()
+            .into_iter()
+            .filter(|conn| conn.from == previous && conn.to != node)
+            .collect();
+        for conn in &downstream {
+            graph.disconnect_channels(previous, conn.from_ch, conn.to, conn.to_ch);
+            if let Err(e) = graph.connect_channels(node, conn.from_ch, conn.to, conn.to_ch) {
+                // Put the old wiring back
+                graph.remove_node(node);
+                for conn in &downstream {
+                    let _ = graph.connect_channels(previous, conn.from_ch, conn.to, conn.to_ch);
+                }
+                return Err(e);
+            }
+        }
+
+        graph.disable_metering(previous);
+        graph.enable_metering(node);
+        self.chain.push(node);
+        Ok(node)
+    }
+
+    /// Remove all of the strip's nodes from the graph
+    pub fn remove(self, graph: &mut AudioGraph) {
+        for node in self.chain {
+            graph.remove_node(node);
+        }
+    }
+}
+
+/// Add a built-in processor as a node
+fn add_builtin(graph: &mut AudioGraph, name: &str) -> Result<usize, String> {
+    let plugin =
+        builtin::create_builtin(name).ok_or_else(|| format!("Unknown built-in: {name}"))?;
+    graph
+        .add_node(
+            plugin,
+            PluginSource::Builtin {
+                name: name.to_string(),
+            },
+        )
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builtin_source(name: &str) -> PluginSource {
+        PluginSource::Builtin {
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_strip_is_wired_in_series() {
+        let mut graph = AudioGraph::with_config(48000, 64);
+        let strip = ChannelStrip::build(&mut graph).unwrap();
+
+        assert_eq!(strip.nodes(), &[strip.gain_node(), strip.pan_node()]);
+        assert_eq!(strip.input(), strip.gain_node());
+        assert_eq!(strip.output(), strip.pan_node());
+        assert!(
+            graph
+                .connections()
+                .iter()
+                .all(|conn| conn.from == strip.gain_node() && conn.to == strip.pan_node())
+        );
+
+        strip.set_gain(&mut graph, 0.5).unwrap();
+        strip.set_pan(&mut graph, -1.0).unwrap();
+        let gain = graph.node(strip.gain_node()).unwrap().plugin();
+        assert_eq!(gain.get_parameter(gain::PARAM_GAIN).unwrap(), 0.5);
+        let pan = graph.node(strip.pan_node()).unwrap().plugin();
+        assert_eq!(pan.get_parameter(pan::PARAM_PAN).unwrap(), -1.0);
+    }
+
+    #[test]
+    fn test_strip_processes_and_meters() {
+        let mut graph = AudioGraph::with_config(48000, 64);
+        let strip = ChannelStrip::build(&mut graph).unwrap();
+        strip.set_gain(&mut graph, 0.5).unwrap();
+
+        // Let the gain smoothing settle, then read the meter
+        let input = [1.0; 64];
+        let mut left = vec![0.0; 64];
+        let mut right = vec![0.0; 64];
+        for _ in 0..100 {
+            graph.process(&[&input, &input], &mut [&mut left, &mut right]);
+        }
+
+        // Half gain, then -3 dB for the centered pan
+        let expected = 0.5 * std::f32::consts::FRAC_1_SQRT_2;
+        assert!((strip.meter(&graph).unwrap().peak - expected).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_append_moves_output_and_meter() {
+        let mut graph = AudioGraph::with_config(48000, 64);
+        let mut strip = ChannelStrip::build(&mut graph).unwrap();
+        let pan = strip.pan_node();
+
+        // Something downstream of the strip
+        let downstream = graph
+            .add_node(
+                builtin::create_builtin("gain").unwrap(),
+                builtin_source("gain"),
+            )
+            .unwrap();
+        graph.connect(pan, downstream).unwrap();
+
+        let delay = strip
+            .append(
+                &mut graph,
+                builtin::create_builtin("delay").unwrap(),
+                builtin_source("delay"),
+            )
+            .unwrap();
+
+        assert_eq!(strip.output(), delay);
+        assert_eq!(strip.nodes().len(), 3);
+        let connections = graph.connections();
+        assert!(connections.iter().any(|c| c.from == pan && c.to == delay));
+        assert!(
+            connections
+                .iter()
+                .any(|c| c.from == delay && c.to == downstream)
+        );
+        assert!(
+            !connections
+                .iter()
+                .any(|c| c.from == pan && c.to == downstream)
+        );
+
+        let meters: Vec<_> = graph.meters().map(|(node, _)| node).collect();
+        assert_eq!(meters, vec![delay]);
+
+        strip.remove(&mut graph);
+        assert_eq!(graph.nodes().count(), 1);
+    }
+}
@@ -0,0 +1,10 @@
+//! Helpers shared by this crate's unit tests.
+
+use vvdaw_core::Sample;
+
+/// Interleaved stereo ramp: left = frame index, right = -frame index
+pub fn ramp(frames: usize) -> Vec<Sample> {
+    (0..frames)
+        .flat_map(|frame| [frame as Sample, -(frame as Sample)])
+        .collect()
+}
@@ -0,0 +1,196 @@
+//! Reading WAV samples with progress reports.
+//!
+//! Loading a large WAV file into memory takes seconds, which the UIs spend
+//! off the main thread. [`read_samples`] reads a file's samples while
+//! reporting how far it has got, so they can draw a progress bar instead of
+//! a bare "Loading...". A file whose header doesn't give a usable length (a
+//! stream written before its length was known) reports
+//! [`LoadProgress::Indeterminate`] instead.
+//!
+//! [`wav_open_error`] turns hound's open errors into the message the UIs and
+//! tools show.
+
+use hound::{SampleFormat, WavReader};
+use std::io::Read;
+
+/// Samples read between progress reports
+const REPORT_INTERVAL: usize = 1 << 16;
+
+/// How far a file load has got
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoadProgress {
+    /// Share of the samples read, from 0.0 to 1.0
+    Fraction(f32),
+    /// The file's length is unknown, so only activity can be shown
+    Indeterminate,
+}
+
+/// Samples a WAV header promises, or `None` if it doesn't give a usable length
+///
+/// Streamed WAVs are written before their length is known, leaving the data
+/// chunk size at 0 or at a placeholder larger than the whole file.
+pub fn expected_samples(header_samples: u32, bits_per_sample: u16, file_len: u64) -> Option<usize> {
+    let bytes = u64::from(header_samples) * u64::from(bits_per_sample.div_ceil(8));
+    if header_samples == 0 || bytes > file_len {
+        return None;
+    }
+    usize::try_from(header_samples).ok()
+}
+
+/// Describe why hound couldn't open a WAV file, for showing to the user
+///
+/// hound only reads 32-bit float and rejects 64-bit float in the header
+/// with a bare format error, which is turned into a hint to convert the file.
+pub fn wav_open_error(error: hound::Error) -> String {
+    match error {
+        hound::Error::FormatError("bits per sample is not 32") => {
+            "Unsupported float WAV: only 32-bit float is supported (convert 64-bit files first)"
+                .to_string()
+        }
+        e => format!("Failed to open WAV file: {e}"),
+    }
+}
+
+/// Read every sample of `reader` as an `f32`, calling `on_progress` as it goes
+///
+/// Integer samples are scaled to [-1.0, 1.0] by their bit depth. `file_len`
+/// is the file's size in bytes, to tell a real length in the header from a
+/// placeholder (see [`expected_samples`]). Reports start at
+/// `Fraction(0.0)` (or `Indeterminate`) and end at `Fraction(1.0)`.
+///
+/// # Errors
+///
+/// Returns the reader's error if a sample can't be read.
+#[allow(clippy::cast_precision_loss)] // Samples and counts only need f32 precision
+pub fn read_samples<R: Read>(
+    reader: &mut WavReader<R>,
+    file_len: u64,
+    on_progress: &mut dyn FnMut(LoadProgress),
+) -> Result<Vec<f32>, hound::Error> {
+    let spec = reader.spec();
+    let total = expected_samples(reader.len(), spec.bits_per_sample, file_len);
+    on_progress(total.map_or(LoadProgress::Indeterminate, |_| LoadProgress::Fraction(0.0)));
+
+    let mut report = |read: usize| {
+        if let Some(total) = total
+            && read.is_multiple_of(REPORT_INTERVAL)
+        {
+            on_progress(LoadProgress::Fraction(
+                (read as f32 / total as f32).min(1.0),
+            ));
+        }
+    };
+
+    let mut samples = Vec::with_capacity(total.unwrap_or(0));
+    match spec.sample_format {
+        SampleFormat::Float => {
+            for sample in reader.samples::<f32>() {
+                samples.push(sample?);
+                report(samples.len());
+            }
+        }
+        SampleFormat::Int => {
+            // 8-bit WAV is unsigned, but hound shifts it to signed (-128..127),
+            // so it scales like any other depth
+            let scale = 2.0_f32.powi(i32::from(spec.bits_per_sample) - 1).recip();
+            for sample in reader.samples::<i32>() {
+                samples.push(sample? as f32 * scale);
+                report(samples.len());
+            }
+        }
+    }
+
+    if total.is_some() {
+        on_progress(LoadProgress::Fraction(1.0));
+    }
+    Ok(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hound::{WavSpec, WavWriter};
+
+    #[test]
+    fn test_expected_samples_rejects_placeholder_lengths() {
+        // 1000 16-bit samples need 2000 bytes
+        assert_eq!(expected_samples(1000, 16, 2044), Some(1000));
+        assert_eq!(expected_samples(1000, 16, 1500), None);
+        assert_eq!(expected_samples(0, 16, 2044), None);
+        assert_eq!(expected_samples(u32::MAX, 24, 2044), None);
+    }
+
+    #[test]
+    fn test_64_bit_float_wav_gets_a_conversion_hint() {
+        // hound can't write 64-bit float, so build the file by hand: mono
+        // IEEE float (format 3) at 64 bits, with a single sample
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&36u32.to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&3u16.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&48000u32.to_le_bytes());
+        wav.extend_from_slice(&(48000u32 * 8).to_le_bytes());
+        wav.extend_from_slice(&8u16.to_le_bytes());
+        wav.extend_from_slice(&64u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&8u32.to_le_bytes());
+        wav.extend_from_slice(&0.5f64.to_le_bytes());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("double.wav");
+        std::fs::write(&path, wav).unwrap();
+
+        let Err(e) = WavReader::open(&path) else {
+            panic!("hound now reads 64-bit float WAVs");
+        };
+        assert!(
+            wav_open_error(e).starts_with("Unsupported float WAV"),
+            "hound's error for 64-bit float changed"
+        );
+
+        let Err(missing) = WavReader::open(dir.path().join("missing.wav")) else {
+            panic!("Opened a file that doesn't exist");
+        };
+        assert!(wav_open_error(missing).starts_with("Failed to open WAV file"));
+    }
+
+    #[test]
+    fn test_read_samples_reports_progress_to_completion() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ramp.wav");
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: 48000,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let mut writer = WavWriter::create(&path, spec).unwrap();
+        for i in 0..(REPORT_INTERVAL * 2 + 100) {
+            writer.write_sample((i % 2) as i16 * 16384).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let file_len = std::fs::metadata(&path).unwrap().len();
+        let mut reader = WavReader::open(&path).unwrap();
+        let mut reports = Vec::new();
+        let samples = read_samples(&mut reader, file_len, &mut |progress| {
+            reports.push(progress);
+        })
+        .unwrap();
+
+        assert_eq!(samples.len(), REPORT_INTERVAL * 2 + 100);
+        assert_eq!(&samples[..2], [0.0, 0.5]);
+
+        // Start, one report per interval, then done
+        assert_eq!(reports.len(), 4);
+        assert_eq!(reports[0], LoadProgress::Fraction(0.0));
+        assert_eq!(reports[3], LoadProgress::Fraction(1.0));
+        let LoadProgress::Fraction(halfway) = reports[1] else {
+            panic!("Known length should report fractions");
+        };
+        assert!((0.45..0.55).contains(&halfway), "{halfway}");
+    }
+}
@@ -0,0 +1,202 @@
+//! CLAP library entry point.
+//!
+//! Every `.clap` library exports a `clap_entry` struct. It must be initialized
+//! before any factory is queried, and deinitialized before the library is
+//! unloaded - [`ClapEntry`] ties both to its own lifetime.
+
+use clap_sys::entry::clap_plugin_entry;
+use clap_sys::factory::plugin_factory::{CLAP_PLUGIN_FACTORY_ID, clap_plugin_factory};
+use clap_sys::plugin::clap_plugin_descriptor;
+use clap_sys::version::clap_version_is_compatible;
+use libloading::{Library, Symbol};
+use std::ffi::{CStr, CString, c_char};
+use std::path::Path;
+use vvdaw_plugin::{PluginError, PluginInfo};
+
+/// An initialized `clap_entry` and the library that exports it
+///
+/// Field order matters: `deinit()` runs in `Drop` before the library unloads.
+pub struct ClapEntry {
+    entry: *const clap_plugin_entry,
+    factory: *const clap_plugin_factory,
+    _library: Library,
+}
+
+impl ClapEntry {
+    /// Load the library and initialize its entry point
+    ///
+    /// `plugin_path` is passed to `clap_entry.init()` - the bundle path on macOS,
+    /// otherwise the library itself.
+    #[allow(unsafe_code)] // Required for FFI
+    pub fn open(library_path: &Path, plugin_path: &Path) -> Result<Self, PluginError> {
+        tracing::debug!("Loading CLAP library: {}", library_path.display());
+
+        let library = unsafe {
+            Library::new(library_path).map_err(|e| {
+                PluginError::FormatError(format!("Failed to load CLAP library: {e}"))
+            })?
+        };
+
+        // `clap_entry` is a data symbol, so the symbol's address is the struct
+        let entry: *const clap_plugin_entry = unsafe {
+            let symbol: Symbol<*const clap_plugin_entry> =
+                library.get(b"clap_entry").map_err(|e| {
+                    PluginError::FormatError(format!("clap_entry symbol not found: {e}"))
+                })?;
+            *symbol
+        };
+
+        if entry.is_null() {
+            return Err(PluginError::FormatError(
+                "clap_entry symbol is null".to_string(),
+            ));
+        }
+
+        unsafe {
+            let version = (*entry).clap_version;
+            if !clap_version_is_compatible(version) {
+                return Err(PluginError::FormatError(format!(
+                    "Incompatible CLAP version {}.{}.{}",
+                    version.major, version.minor, version.revision
+                )));
+            }
+
+            let path = CString::new(plugin_path.to_string_lossy().as_bytes()).map_err(|_| {
+                PluginError::FormatError("Plugin path contains a NUL byte".to_string())
+            })?;
+            let init = (*entry)
+                .init
+                .ok_or_else(|| PluginError::FormatError("clap_entry has no init()".to_string()))?;
+            if !init(path.as_ptr()) {
+                return Err(PluginError::FormatError(
+                    "clap_entry.init() failed".to_string(),
+                ));
+            }
+        }
+
+        // From here on Drop calls deinit()
+        let mut opened = Self {
+            entry,
+            factory: std::ptr::null(),
+            _library: library,
+        };
+
+        opened.factory = unsafe {
+            let get_factory = (*entry).get_factory.ok_or_else(|| {
+                PluginError::FormatError("clap_entry has no get_factory()".to_string())
+            })?;
+            get_factory(CLAP_PLUGIN_FACTORY_ID.as_ptr()).cast::<clap_plugin_factory>()
+        };
+
+        if opened.factory.is_null() {
+            return Err(PluginError::FormatError(
+                "Library does not provide a plugin factory".to_string(),
+            ));
+        }
+
+        Ok(opened)
+    }
+
+    /// The plugin factory (never null)
+    pub fn factory(&self) -> *const clap_plugin_factory {
+        self.factory
+    }
+
+    /// Descriptors of every plugin the factory can create
+    #[allow(unsafe_code)] // Required for FFI
+    pub fn descriptors(&self) -> Vec<*const clap_plugin_descriptor> {
+        unsafe {
+            let (Some(get_count), Some(get_descriptor)) = (
+                (*self.factory).get_plugin_count,
+                (*self.factory).get_plugin_descriptor,
+            ) else {
+                return Vec::new();
+            };
+
+            (0..get_count(self.factory))
+                .map(|index| get_descriptor(self.factory, index))
+                .filter(|descriptor| !descriptor.is_null())
+                .collect()
+        }
+    }
+}
+
+// SAFETY: The entry and factory are plain C function tables that the CLAP
+// spec requires to be thread-safe, and the Library is already Send.
+#[allow(unsafe_code)]
+unsafe impl Send for ClapEntry {}
+
+impl Drop for ClapEntry {
+    #[allow(unsafe_code)] // Required for FFI
+    fn drop(&mut self) {
+        unsafe {
+            if let Some(deinit) = (*self.entry).deinit {
+                deinit();
+            }
+        }
+    }
+}
+
+/// Build `PluginInfo` from a plugin descriptor
+///
+/// # Safety
+///
+/// `descriptor` must point to a valid descriptor owned by a live factory.
+#[allow(unsafe_code)] // Required for FFI
+pub unsafe fn plugin_info(descriptor: *const clap_plugin_descriptor) -> PluginInfo {
+    unsafe {
+        let descriptor = &*descriptor;
+        PluginInfo {
+            name: c_string(descriptor.name),
+            vendor: c_string(descriptor.vendor),
+            version: c_string(descriptor.version),
+            unique_id: c_string(descriptor.id),
+            category: None,
+        }
+    }
+}
+
+/// Convert a (possibly null) C string to an owned `String`
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a NUL-terminated string.
+#[allow(unsafe_code)] // Required for FFI
+pub unsafe fn c_string(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    unsafe { CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Convert a fixed-size, NUL-padded C string buffer (e.g. a parameter name)
+pub fn c_string_buffer(buffer: &[c_char]) -> String {
+    let bytes: Vec<u8> = buffer
+        .iter()
+        .take_while(|&&c| c != 0)
+        .map(|&c| c as u8)
+        .collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_c_string_buffer_stops_at_nul() {
+        let mut buffer = [0 as c_char; 8];
+        for (slot, &byte) in buffer.iter_mut().zip(b"Gain") {
+            *slot = byte as c_char;
+        }
+        assert_eq!(c_string_buffer(&buffer), "Gain");
+    }
+
+    #[test]
+    #[allow(unsafe_code)]
+    fn test_c_string_null() {
+        assert_eq!(unsafe { c_string(std::ptr::null()) }, "");
+    }
+}
@@ -0,0 +1,149 @@
+//! CLAP event lists for parameter changes.
+//!
+//! `clap_input_events` is a C vtable over a host-owned list. [`ParamEventList`]
+//! keeps a pre-allocated list of `CLAP_EVENT_PARAM_VALUE` events that is
+//! refilled before each `process()` call, avoiding allocations in the audio
+//! thread.
+
+use clap_sys::events::{
+    CLAP_CORE_EVENT_SPACE_ID, CLAP_EVENT_PARAM_VALUE, clap_event_header, clap_event_param_value,
+    clap_input_events, clap_output_events,
+};
+use std::ffi::c_void;
+
+/// Initial capacity - enough for typical automation without reallocating
+const INITIAL_CAPACITY: usize = 128;
+
+/// Parameter value events to send to a plugin
+pub struct ParamEventList {
+    events: Vec<clap_event_param_value>,
+}
+
+impl ParamEventList {
+    pub fn new() -> Self {
+        Self {
+            events: Vec::with_capacity(INITIAL_CAPACITY),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Add a parameter change at the given sample offset
+    pub fn push(&mut self, param_id: u32, time: u32, value: f64) {
+        self.events.push(clap_event_param_value {
+            header: clap_event_header {
+                size: std::mem::size_of::<clap_event_param_value>() as u32,
+                time,
+                space_id: CLAP_CORE_EVENT_SPACE_ID,
+                type_: CLAP_EVENT_PARAM_VALUE,
+                flags: 0,
+            },
+            param_id,
+            cookie: std::ptr::null_mut(),
+            // -1 = applies to all notes, ports, channels and keys
+            note_id: -1,
+            port_index: -1,
+            channel: -1,
+            key: -1,
+            value,
+        });
+    }
+
+    /// CLAP requires input events ordered by time
+    ///
+    /// REAL-TIME SAFE: Unstable sort doesn't allocate.
+    pub fn sort(&mut self) {
+        self.events.sort_unstable_by_key(|event| event.header.time);
+    }
+
+    /// C view of this list, valid while `self` is neither moved nor modified
+    pub fn as_input_events(&self) -> clap_input_events {
+        clap_input_events {
+            ctx: std::ptr::from_ref(self).cast_mut().cast::<c_void>(),
+            size: Some(input_events_size),
+            get: Some(input_events_get),
+        }
+    }
+}
+
+// SAFETY: ParamEventList is only used from the thread that owns its ClapPlugin.
+// The `cookie` pointers are always null.
+#[allow(unsafe_code)]
+#[allow(clippy::non_send_fields_in_send_ty)] // Events only hold null cookies
+unsafe impl Send for ParamEventList {}
+
+/// Output events sink that drops everything the plugin sends
+///
+/// Parameter changes made by the plugin itself are not reported yet.
+pub fn discard_output_events() -> clap_output_events {
+    clap_output_events {
+        ctx: std::ptr::null_mut(),
+        try_push: Some(output_events_try_push),
+    }
+}
+
+#[allow(unsafe_code)] // Required for FFI callbacks
+unsafe extern "C" fn input_events_size(list: *const clap_input_events) -> u32 {
+    unsafe {
+        let events = &*(*list).ctx.cast::<ParamEventList>();
+        events.events.len() as u32
+    }
+}
+
+#[allow(unsafe_code)] // Required for FFI callbacks
+unsafe extern "C" fn input_events_get(
+    list: *const clap_input_events,
+    index: u32,
+) -> *const clap_event_header {
+    unsafe {
+        let events = &*(*list).ctx.cast::<ParamEventList>();
+        events
+            .events
+            .get(index as usize)
+            .map_or(std::ptr::null(), |event| &raw const event.header)
+    }
+}
+
+#[allow(unsafe_code)]
+unsafe extern "C" fn output_events_try_push(
+    _list: *const clap_output_events,
+    _event: *const clap_event_header,
+) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(unsafe_code)]
+    fn test_input_events_are_sorted_and_readable() {
+        let mut list = ParamEventList::new();
+        list.push(1, 32, 0.25);
+        list.push(0, 0, 0.75);
+        list.sort();
+
+        let raw = list.as_input_events();
+        unsafe {
+            assert_eq!(raw.size.unwrap()(&raw const raw), 2);
+
+            let header = raw.get.unwrap()(&raw const raw, 0);
+            assert_eq!((*header).time, 0);
+            assert_eq!((*header).type_, CLAP_EVENT_PARAM_VALUE);
+            // The header is the first field of an 8-byte aligned event
+            #[allow(clippy::cast_ptr_alignment)]
+            let event = &*header.cast::<clap_event_param_value>();
+            assert_eq!(event.param_id, 0);
+            assert_eq!(event.value, 0.75);
+
+            assert!(raw.get.unwrap()(&raw const raw, 2).is_null());
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! Host-side `clap_host` implementation.
+//!
+//! Plugins receive a `clap_host` when created and may call back into it.
+//! We don't offer any host extensions yet, and treat restart/process/callback
+//! requests as no-ops (the plugin keeps running with its current setup).
+
+use clap_sys::host::clap_host;
+use clap_sys::version::CLAP_VERSION;
+use std::ffi::{c_char, c_void};
+
+/// A `clap_host` with a stable address for the plugin to hold on to
+///
+/// Heap-allocated and freed on drop, so it must outlive the plugin.
+pub struct ClapHost {
+    raw: *mut clap_host,
+}
+
+impl ClapHost {
+    pub fn new() -> Self {
+        Self {
+            raw: Box::into_raw(Box::new(clap_host {
+                clap_version: CLAP_VERSION,
+                host_data: std::ptr::null_mut(),
+                name: c"vvdaw".as_ptr(),
+                vendor: c"vvdaw".as_ptr(),
+                url: c"https://github.com/navicore/vvdaw".as_ptr(),
+                version: c"0.1.0".as_ptr(),
+                get_extension: Some(get_extension),
+                request_restart: Some(request_restart),
+                request_process: Some(request_process),
+                request_callback: Some(request_callback),
+            })),
+        }
+    }
+
+    /// Pointer to pass to `clap_plugin_factory.create_plugin()`
+    ///
+    /// Valid for as long as this `ClapHost` lives.
+    pub fn as_ptr(&self) -> *const clap_host {
+        self.raw
+    }
+}
+
+impl Drop for ClapHost {
+    #[allow(unsafe_code)] // Reclaims the Box leaked in new()
+    fn drop(&mut self) {
+        drop(unsafe { Box::from_raw(self.raw) });
+    }
+}
+
+// SAFETY: The `clap_host` is never mutated after construction, and only points
+// to static strings and stateless callbacks.
+#[allow(unsafe_code)]
+unsafe impl Send for ClapHost {}
+
+/// No host extensions are supported yet
+#[allow(unsafe_code)]
+unsafe extern "C" fn get_extension(
+    _host: *const clap_host,
+    _extension_id: *const c_char,
+) -> *const c_void {
+    std::ptr::null()
+}
+
+#[allow(unsafe_code)]
+unsafe extern "C" fn request_restart(_host: *const clap_host) {
+    tracing::debug!("CLAP plugin requested a restart (ignored)");
+}
+
+#[allow(unsafe_code)]
+unsafe extern "C" fn request_process(_host: *const clap_host) {
+    // We process continuously, so there's nothing to wake up
+}
+
+#[allow(unsafe_code)]
+unsafe extern "C" fn request_callback(_host: *const clap_host) {
+    tracing::debug!("CLAP plugin requested a main-thread callback (ignored)");
+}
@@ -1,22 +1,65 @@
 //! CLAP plugin host implementation.
 //!
-//! This crate will implement CLAP plugin hosting functionality.
-//! Currently a stub - to be implemented after VST3 is working.
+//! This crate implements CLAP plugin hosting, wrapping CLAP plugins to
+//! implement our common Plugin trait.
+//!
+//! ## CLAP API
+//!
+//! The raw C API comes from the `clap-sys` crate (MIT/Apache-2.0).
+//!
+//! ## Architecture
+//!
+//! CLAP is a plain C ABI. The loading flow is:
+//! 1. Load the `.clap` dynamic library (a bundle directory on macOS)
+//! 2. Read the exported `clap_entry` and call `init()`
+//! 3. Query the entry for the plugin factory and its descriptors
+//! 4. Create a plugin instance and call `init()`
+//! 5. Activate it and start processing
+//!
+//! Only audio ports are handled for now - note ports (instruments) are a
+//! follow-up.
+
+use std::path::Path;
+use vvdaw_plugin::{PluginError, PluginInfo};
 
-use vvdaw_plugin::PluginInfo;
+mod entry;
+mod events;
+mod host;
+mod loader;
+mod wrapper;
 
-/// CLAP plugin wrapper (stub)
-#[allow(dead_code)]
-pub struct ClapPlugin {
-    info: PluginInfo,
+pub use loader::ClapLoader;
+pub use wrapper::ClapPlugin;
+
+/// Scan a directory for CLAP plugins
+///
+/// Convenience function that delegates to `ClapLoader::scan`.
+pub fn scan_plugins<P: AsRef<Path>>(path: P) -> Result<Vec<PluginInfo>, PluginError> {
+    ClapLoader::scan(path)
 }
 
-// TODO: Implement Plugin trait for ClapPlugin when ready
+/// Load a CLAP plugin from a path
+///
+/// Convenience function that delegates to `ClapLoader::load`.
+pub fn load_plugin<P: AsRef<Path>>(path: P) -> Result<ClapPlugin, PluginError> {
+    ClapLoader::load(path)
+}
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_plugins() {
+        // Test that scan_plugins delegates to loader
+        let result = scan_plugins("/nonexistent");
+        assert!(result.is_ok());
+        assert!(result.unwrap().is_empty());
+    }
+
     #[test]
-    fn test_placeholder() {
-        // Placeholder test - to be replaced when CLAP implementation begins
+    fn test_load_missing_plugin() {
+        let result = load_plugin("/nonexistent.clap");
+        assert!(result.is_err());
     }
 }
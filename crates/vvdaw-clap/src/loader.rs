@@ -0,0 +1,476 @@
+//! CLAP plugin loading functionality.
+//!
+//! This module handles loading CLAP plugins from `.clap` files (bundle
+//! directories on macOS), querying the plugin factory, and creating plugin
+//! instances.
+
+use crate::entry::{ClapEntry, plugin_info};
+use crate::host::ClapHost;
+use crate::wrapper::ClapPlugin;
+use clap_sys::ext::audio_ports::{
+    CLAP_AUDIO_PORT_IS_MAIN, CLAP_EXT_AUDIO_PORTS, clap_audio_port_info, clap_plugin_audio_ports,
+};
+use clap_sys::ext::params::{CLAP_EXT_PARAMS, clap_plugin_params};
+use clap_sys::plugin::clap_plugin;
+use std::path::{Path, PathBuf};
+use vvdaw_core::ChannelCount;
+use vvdaw_plugin::{PluginError, PluginInfo};
+
+/// CLAP plugin loader
+///
+/// Handles loading CLAP plugins from filesystem paths.
+/// CLAP plugins are distributed as:
+/// - macOS: `<name>.clap` bundle containing `Contents/MacOS/<name>`
+/// - Windows/Linux: `<name>.clap` dynamic library
+pub struct ClapLoader;
+
+impl ClapLoader {
+    /// Load a CLAP plugin from a path
+    ///
+    /// Instantiates the first plugin the library's factory describes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PluginError::FormatError` if:
+    /// - The file/bundle doesn't exist
+    /// - The library can't be loaded or has no plugin factory
+    /// - The factory has no plugins, or the plugin fails to initialize
+    #[allow(unsafe_code)] // Required for FFI
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<ClapPlugin, PluginError> {
+        let path = path.as_ref();
+        tracing::info!("Loading CLAP plugin from: {}", path.display());
+
+        // Security: Validate path to prevent directory traversal attacks
+        Self::validate_plugin_path(path)?;
+
+        let library_path = Self::get_library_path(path);
+        if !library_path.exists() {
+            return Err(PluginError::FormatError(format!(
+                "CLAP library not found at: {}",
+                library_path.display()
+            )));
+        }
+
+        let entry = ClapEntry::open(&library_path, path)?;
+
+        let descriptor = entry.descriptors().first().copied().ok_or_else(|| {
+            PluginError::FormatError(format!("No plugins found in {}", path.display()))
+        })?;
+        let info = unsafe { plugin_info(descriptor) };
+        tracing::debug!("Creating CLAP plugin '{}' ({})", info.name, info.unique_id);
+
+        let host = ClapHost::new();
+        let plugin = unsafe {
+            let factory = entry.factory();
+            let create_plugin = (*factory).create_plugin.ok_or_else(|| {
+                PluginError::FormatError("Plugin factory has no create_plugin()".to_string())
+            })?;
+            create_plugin(factory, host.as_ptr(), (*descriptor).id)
+        };
+
+        if plugin.is_null() {
+            return Err(PluginError::FormatError(format!(
+                "Failed to create plugin '{}'",
+                info.name
+            )));
+        }
+
+        // From here on the plugin must be destroyed on failure
+        let initialized = unsafe { (*plugin).init.is_some_and(|init| init(plugin)) };
+        if !initialized {
+            unsafe { Self::destroy(plugin) };
+            return Err(PluginError::FormatError(format!(
+                "Failed to initialize plugin '{}'",
+                info.name
+            )));
+        }
+
+        let params = unsafe { Self::get_extension::<clap_plugin_params>(plugin, CLAP_EXT_PARAMS) };
+        let (input_channels, output_channels) = unsafe { Self::main_audio_ports(plugin) };
+        tracing::debug!(
+            "CLAP plugin '{}' has {} input and {} output channels",
+            info.name,
+            input_channels,
+            output_channels
+        );
+
+        Ok(ClapPlugin::new(
+            info,
+            entry,
+            host,
+            plugin,
+            params,
+            input_channels,
+            output_channels,
+        ))
+    }
+
+    /// Scan a directory for CLAP plugins
+    ///
+    /// Searches for `.clap` files (or bundles) and returns information about
+    /// every plugin their factories describe, without instantiating them.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PluginError::FormatError` if the directory can't be read.
+    pub fn scan<P: AsRef<Path>>(path: P) -> Result<Vec<PluginInfo>, PluginError> {
+        let path = path.as_ref();
+        tracing::info!("Scanning for CLAP plugins in: {}", path.display());
+
+        if !path.exists() {
+            tracing::debug!("Scan path does not exist: {}", path.display());
+            return Ok(Vec::new());
+        }
+
+        if !path.is_dir() {
+            return Err(PluginError::FormatError(format!(
+                "Scan path is not a directory: {}",
+                path.display()
+            )));
+        }
+
+        let mut plugins = Vec::new();
+        Self::walk_directory(path, &mut plugins)?;
+        tracing::info!("Found {} CLAP plugins in {}", plugins.len(), path.display());
+
+        Ok(plugins)
+    }
+
+    /// Scan all standard CLAP plugin directories on the system
+    ///
+    /// Returns information about all discovered CLAP plugins.
+    /// Skips directories that don't exist and continues on errors.
+    pub fn scan_system() -> Vec<PluginInfo> {
+        let mut all_plugins = Vec::new();
+
+        for search_path in Self::get_clap_search_paths() {
+            tracing::debug!("Scanning CLAP search path: {}", search_path.display());
+            match Self::scan(&search_path) {
+                Ok(mut plugins) => all_plugins.append(&mut plugins),
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to scan {}: {} (continuing)",
+                        search_path.display(),
+                        e
+                    );
+                }
+            }
+        }
+
+        tracing::info!("Total CLAP plugins found: {}", all_plugins.len());
+        all_plugins
+    }
+
+    /// Get the standard CLAP search paths for the current platform
+    ///
+    /// Directories listed in `CLAP_PATH` come first, as the CLAP spec requires.
+    fn get_clap_search_paths() -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+
+        if let Some(clap_path) = std::env::var_os("CLAP_PATH") {
+            paths.extend(std::env::split_paths(&clap_path));
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            // User plugins
+            if let Some(home) = std::env::var_os("HOME") {
+                let mut user_path = PathBuf::from(home);
+                user_path.push("Library/Audio/Plug-Ins/CLAP");
+                paths.push(user_path);
+            }
+
+            // System-wide plugins
+            paths.push(PathBuf::from("/Library/Audio/Plug-Ins/CLAP"));
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            // Standard CLAP path
+            if let Some(common) = std::env::var_os("COMMONPROGRAMFILES") {
+                let mut common_path = PathBuf::from(common);
+                common_path.push("CLAP");
+                paths.push(common_path);
+            }
+
+            // User plugins
+            if let Some(appdata) = std::env::var_os("LOCALAPPDATA") {
+                let mut user_path = PathBuf::from(appdata);
+                user_path.push("Programs\\Common\\CLAP");
+                paths.push(user_path);
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            // User plugins
+            if let Some(home) = std::env::var_os("HOME") {
+                let mut user_path = PathBuf::from(home);
+                user_path.push(".clap");
+                paths.push(user_path);
+            }
+
+            // System-wide plugins
+            paths.push(PathBuf::from("/usr/lib/clap"));
+        }
+
+        paths
+    }
+
+    /// Recursively walk a directory to find CLAP plugins
+    ///
+    /// Unlike VST3 scanning this runs in-process: reading descriptors doesn't
+    /// instantiate any plugin.
+    fn walk_directory(path: &Path, plugins: &mut Vec<PluginInfo>) -> Result<(), PluginError> {
+        let entries = std::fs::read_dir(path).map_err(|e| {
+            PluginError::FormatError(format!(
+                "Failed to read directory {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        for entry in entries {
+            let entry = match entry {
+                Ok(e) => e,
+                Err(e) => {
+                    tracing::warn!("Failed to read directory entry: {}", e);
+                    continue;
+                }
+            };
+
+            let entry_path = entry.path();
+
+            if entry_path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("clap"))
+            {
+                // Found a .clap library (or bundle) - don't recurse into it
+                match Self::load_plugin_info(&entry_path) {
+                    Ok(mut found) => {
+                        tracing::debug!(
+                            "Found {} plugin(s) at {}",
+                            found.len(),
+                            entry_path.display()
+                        );
+                        plugins.append(&mut found);
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to scan plugin from {}: {} (skipping)",
+                            entry_path.display(),
+                            e
+                        );
+                    }
+                }
+                continue;
+            }
+
+            if entry_path.is_dir()
+                && let Err(e) = Self::walk_directory(&entry_path, plugins)
+            {
+                tracing::warn!(
+                    "Failed to scan subdirectory {}: {}",
+                    entry_path.display(),
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read the descriptors of every plugin in a `.clap` library
+    #[allow(unsafe_code)] // Required for FFI
+    fn load_plugin_info(path: &Path) -> Result<Vec<PluginInfo>, PluginError> {
+        let entry = ClapEntry::open(&Self::get_library_path(path), path)?;
+
+        Ok(entry
+            .descriptors()
+            .into_iter()
+            .map(|descriptor| unsafe { plugin_info(descriptor) })
+            .collect())
+    }
+
+    /// Query a plugin extension, returning null if it isn't supported
+    ///
+    /// # Safety
+    ///
+    /// `plugin` must be a valid, initialized plugin and `T` the struct for `id`.
+    #[allow(unsafe_code)] // Required for FFI
+    unsafe fn get_extension<T>(plugin: *const clap_plugin, id: &std::ffi::CStr) -> *const T {
+        unsafe {
+            (*plugin)
+                .get_extension
+                .map_or(std::ptr::null(), |get_extension| {
+                    get_extension(plugin, id.as_ptr()).cast::<T>()
+                })
+        }
+    }
+
+    /// Channel counts of the main input and output audio ports
+    ///
+    /// Uses the port flagged as main, falling back to the first port.
+    /// Plugins without the audio-ports extension have no audio I/O.
+    ///
+    /// # Safety
+    ///
+    /// `plugin` must be a valid, initialized plugin.
+    #[allow(unsafe_code)] // Required for FFI
+    unsafe fn main_audio_ports(plugin: *const clap_plugin) -> (ChannelCount, ChannelCount) {
+        unsafe {
+            let ports =
+                Self::get_extension::<clap_plugin_audio_ports>(plugin, CLAP_EXT_AUDIO_PORTS);
+            if ports.is_null() {
+                tracing::warn!("CLAP plugin has no audio-ports extension - no audio I/O");
+                return (0, 0);
+            }
+
+            let (Some(count), Some(get)) = ((*ports).count, (*ports).get) else {
+                return (0, 0);
+            };
+
+            let channels = |is_input: bool| -> ChannelCount {
+                let mut main = None;
+                for index in 0..count(plugin, is_input) {
+                    let mut info: clap_audio_port_info = std::mem::zeroed();
+                    if !get(plugin, index, is_input, &raw mut info) {
+                        continue;
+                    }
+                    if info.flags & CLAP_AUDIO_PORT_IS_MAIN != 0 {
+                        return info.channel_count as ChannelCount;
+                    }
+                    main.get_or_insert(info.channel_count as ChannelCount);
+                }
+                main.unwrap_or(0)
+            };
+
+            (channels(true), channels(false))
+        }
+    }
+
+    /// Destroy a plugin instance that never made it into a `ClapPlugin`
+    ///
+    /// # Safety
+    ///
+    /// `plugin` must be valid and not used afterwards.
+    #[allow(unsafe_code)] // Required for FFI
+    unsafe fn destroy(plugin: *const clap_plugin) {
+        unsafe {
+            if let Some(destroy) = (*plugin).destroy {
+                destroy(plugin);
+            }
+        }
+    }
+
+    /// Get the dynamic library inside a `.clap` path
+    ///
+    /// On macOS `.clap` plugins are bundles: `Contents/MacOS/<name>`.
+    #[cfg(target_os = "macos")]
+    fn get_library_path(path: &Path) -> PathBuf {
+        match path.file_stem() {
+            Some(name) if path.is_dir() => path.join("Contents").join("MacOS").join(name),
+            _ => path.to_path_buf(),
+        }
+    }
+
+    /// Get the dynamic library inside a `.clap` path
+    ///
+    /// On Windows and Linux the `.clap` file is the library itself.
+    #[cfg(not(target_os = "macos"))]
+    fn get_library_path(path: &Path) -> PathBuf {
+        path.to_path_buf()
+    }
+
+    /// Validate plugin path for security
+    ///
+    /// Prevents directory traversal attacks by rejecting `..` components.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PluginError::FormatError` if path validation fails
+    fn validate_plugin_path(path: &Path) -> Result<(), PluginError> {
+        if path
+            .components()
+            .any(|component| component == std::path::Component::ParentDir)
+        {
+            return Err(PluginError::FormatError(
+                "Plugin path cannot contain '..' components (directory traversal)".to_string(),
+            ));
+        }
+
+        if !path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("clap"))
+        {
+            tracing::warn!(
+                "Plugin path has unusual extension (expected .clap): {}",
+                path.display()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_nonexistent_directory() {
+        // Scanning a nonexistent directory should return empty list, not error
+        let result = ClapLoader::scan("/nonexistent/path/to/plugins");
+        assert!(result.is_ok());
+        assert!(result.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_scan_file_not_directory() {
+        // Scanning a file (not a directory) should return an error
+        let result = ClapLoader::scan("/etc/hosts");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_scan_skips_invalid_libraries() {
+        let dir = std::env::temp_dir().join(format!("vvdaw-clap-scan-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("broken.clap"), b"not a library").unwrap();
+
+        let result = ClapLoader::scan(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(result.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_scan_system() {
+        // Informational - passes regardless of how many plugins are installed
+        let plugins = ClapLoader::scan_system();
+
+        eprintln!("Found {} CLAP plugins on system", plugins.len());
+        for plugin in &plugins {
+            eprintln!(
+                "  - {} by {} (v{})",
+                plugin.name, plugin.vendor, plugin.version
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_linux_search_paths() {
+        let paths = ClapLoader::get_clap_search_paths();
+        assert!(paths.contains(&PathBuf::from("/usr/lib/clap")));
+        if let Some(home) = std::env::var_os("HOME") {
+            assert!(paths.contains(&PathBuf::from(home).join(".clap")));
+        }
+    }
+
+    #[test]
+    fn test_path_validation_rejects_parent_dir() {
+        let result = ClapLoader::load("/usr/lib/clap/../../etc/passwd.clap");
+        assert!(result.is_err());
+    }
+}
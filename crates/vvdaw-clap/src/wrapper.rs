@@ -0,0 +1,481 @@
+//! CLAP plugin wrapper that implements the Plugin trait.
+//!
+//! This module wraps a `clap_plugin` instance and its extensions and
+//! implements our format-agnostic Plugin trait.
+
+use crate::entry::{ClapEntry, c_string_buffer};
+use crate::events::{ParamEventList, discard_output_events};
+use crate::host::ClapHost;
+use clap_sys::audio_buffer::clap_audio_buffer;
+use clap_sys::ext::latency::{CLAP_EXT_LATENCY, clap_plugin_latency};
+use clap_sys::ext::params::{CLAP_PARAM_IS_STEPPED, clap_param_info, clap_plugin_params};
+use clap_sys::ext::tail::{CLAP_EXT_TAIL, clap_plugin_tail};
+use clap_sys::plugin::clap_plugin;
+use clap_sys::process::{CLAP_PROCESS_ERROR, clap_process};
+use std::collections::HashMap;
+use vvdaw_core::{ChannelCount, Frames, SampleRate};
+use vvdaw_plugin::{
+    AudioBuffer, Event, EventBuffer, INFINITE_TAIL, ParameterInfo, Plugin, PluginError, PluginInfo,
+};
+
+/// CLAP plugin wrapper
+///
+/// Wraps a `clap_plugin` instance and implements our common Plugin trait.
+pub struct ClapPlugin {
+    info: PluginInfo,
+
+    // Plugin instance and its params extension (null if unsupported)
+    plugin: *const clap_plugin,
+    params: *const clap_plugin_params,
+
+    // Must outlive the plugin: dropped after `Drop::drop` destroys it,
+    // and the entry (which unloads the library) last
+    _host: ClapHost,
+    _entry: ClapEntry,
+
+    // Audio configuration
+    sample_rate: SampleRate,
+    block_size: Frames,
+    input_channels: ChannelCount,
+    output_channels: ChannelCount,
+
+    // Pre-allocated buffers for CLAP process calls
+    // These avoid allocations in the audio hot path
+    input_channel_ptrs: Vec<*mut f32>,
+    output_channel_ptrs: Vec<*mut f32>,
+
+    // Activation state - CLAP separates activate() from start_processing()
+    is_active: bool,
+    is_processing: bool,
+
+    // Processing latency reported by the plugin when it was last activated
+    latency: Frames,
+
+    // Tail length reported by the plugin when it was last activated
+    tail: Frames,
+
+    // Running sample counter passed to the plugin as `steady_time`
+    steady_time: i64,
+
+    // Parameters changed since the last process() call (ID -> plain value)
+    dirty_parameters: HashMap<u32, f64>,
+
+    // Reusable event list, populated from dirty_parameters and the EventBuffer
+    param_events: ParamEventList,
+}
+
+impl ClapPlugin {
+    /// Create a new CLAP plugin wrapper
+    ///
+    /// Called by the loader once the plugin has been created and `init()`ed.
+    pub fn new(
+        info: PluginInfo,
+        entry: ClapEntry,
+        host: ClapHost,
+        plugin: *const clap_plugin,
+        params: *const clap_plugin_params,
+        input_channels: ChannelCount,
+        output_channels: ChannelCount,
+    ) -> Self {
+        Self {
+            info,
+            plugin,
+            params,
+            _host: host,
+            _entry: entry,
+            sample_rate: 48000,
+            block_size: 512,
+            input_channels,
+            output_channels,
+            input_channel_ptrs: Vec::with_capacity(input_channels),
+            output_channel_ptrs: Vec::with_capacity(output_channels),
+            is_active: false,
+            is_processing: false,
+            latency: 0,
+            tail: 0,
+            steady_time: 0,
+            dirty_parameters: HashMap::new(),
+            param_events: ParamEventList::new(),
+        }
+    }
+
+    /// Apply parameter changes immediately via `clap_plugin_params.flush()`
+    ///
+    /// Used while the plugin isn't processing, so `get_parameter` reflects the
+    /// change without waiting for the next `process()` call.
+    #[allow(unsafe_code)] // Required for FFI calls
+    fn flush_parameters(&mut self) {
+        if self.params.is_null() || self.dirty_parameters.is_empty() {
+            return;
+        }
+
+        self.param_events.clear();
+        for (&param_id, &value) in &self.dirty_parameters {
+            self.param_events.push(param_id, 0, value);
+        }
+
+        unsafe {
+            if let Some(flush) = (*self.params).flush {
+                let in_events = self.param_events.as_input_events();
+                let out_events = discard_output_events();
+                flush(self.plugin, &raw const in_events, &raw const out_events);
+                self.dirty_parameters.clear();
+            }
+        }
+    }
+}
+
+// SAFETY: CLAP plugins are driven from the audio thread once active.
+// Our architecture ensures a plugin is only ever used from one thread at a
+// time (plugins live on the audio thread once added to the graph).
+#[allow(unsafe_code)]
+unsafe impl Send for ClapPlugin {}
+
+impl Plugin for ClapPlugin {
+    fn info(&self) -> &PluginInfo {
+        &self.info
+    }
+
+    #[allow(unsafe_code)] // Required for FFI calls
+    fn initialize(
+        &mut self,
+        sample_rate: SampleRate,
+        max_block_size: Frames,
+    ) -> Result<(), PluginError> {
+        tracing::info!(
+            "Initializing CLAP plugin '{}' at {} Hz, block size {}",
+            self.info.name,
+            sample_rate,
+            max_block_size
+        );
+
+        // CLAP only accepts a new configuration while deactivated
+        self.deactivate();
+
+        self.sample_rate = sample_rate;
+        self.block_size = max_block_size;
+
+        // Resize channel pointer vectors to match configuration
+        // This happens once during initialization, not in the audio hot path
+        self.input_channel_ptrs.clear();
+        self.input_channel_ptrs
+            .resize(self.input_channels, std::ptr::null_mut());
+        self.output_channel_ptrs.clear();
+        self.output_channel_ptrs
+            .resize(self.output_channels, std::ptr::null_mut());
+
+        unsafe {
+            let activate = (*self.plugin).activate.ok_or_else(|| {
+                PluginError::InitializationFailed("Plugin has no activate()".to_string())
+            })?;
+            if !activate(
+                self.plugin,
+                f64::from(sample_rate),
+                1,
+                max_block_size as u32,
+            ) {
+                return Err(PluginError::InitializationFailed(format!(
+                    "CLAP plugin '{}' failed to activate",
+                    self.info.name
+                )));
+            }
+
+            // Latency may depend on the configuration, so query it once active
+            let latency = (*self.plugin)
+                .get_extension
+                .map_or(std::ptr::null(), |get| {
+                    get(self.plugin, CLAP_EXT_LATENCY.as_ptr()).cast::<clap_plugin_latency>()
+                });
+            self.latency = if latency.is_null() {
+                0
+            } else {
+                (*latency).get.map_or(0, |get| get(self.plugin) as Frames)
+            };
+            tracing::debug!("Plugin latency: {} samples", self.latency);
+
+            // CLAP reports an endless tail as i32::MAX
+            let tail = (*self.plugin)
+                .get_extension
+                .map_or(std::ptr::null(), |get| {
+                    get(self.plugin, CLAP_EXT_TAIL.as_ptr()).cast::<clap_plugin_tail>()
+                });
+            self.tail = if tail.is_null() {
+                0
+            } else {
+                match (*tail).get.map_or(0, |get| get(self.plugin)) {
+                    tail if tail >= i32::MAX as u32 => INFINITE_TAIL,
+                    tail => tail as Frames,
+                }
+            };
+            tracing::debug!("Plugin tail: {} samples", self.tail);
+        }
+
+        self.is_active = true;
+        self.steady_time = 0;
+
+        tracing::info!("CLAP plugin '{}' initialized successfully", self.info.name);
+        Ok(())
+    }
+
+    #[allow(unsafe_code)] // Required for FFI calls
+    fn process(
+        &mut self,
+        audio: &mut AudioBuffer,
+        events: &EventBuffer,
+    ) -> Result<(), PluginError> {
+        if !self.is_active {
+            return Err(PluginError::ProcessingFailed(
+                "CLAP plugin not initialized".to_string(),
+            ));
+        }
+
+        unsafe {
+            // Step 1: start_processing() must be called from the audio thread
+            if !self.is_processing {
+                let started = (*self.plugin)
+                    .start_processing
+                    .is_none_or(|start| start(self.plugin));
+                if !started {
+                    return Err(PluginError::ProcessingFailed(
+                        "CLAP plugin failed to start processing".to_string(),
+                    ));
+                }
+                self.is_processing = true;
+            }
+
+            // Step 2: Update channel pointers (only for actual available channels)
+            let actual_input_channels = audio.inputs.len().min(self.input_channels);
+            let actual_output_channels = audio.outputs.len().min(self.output_channels);
+
+            for (ptr, input_slice) in self.input_channel_ptrs.iter_mut().zip(audio.inputs.iter()) {
+                // Cast away const - CLAP may process in place
+                *ptr = input_slice.as_ptr().cast_mut();
+            }
+            for (ptr, output_slice) in self
+                .output_channel_ptrs
+                .iter_mut()
+                .zip(audio.outputs.iter_mut())
+            {
+                *ptr = output_slice.as_mut_ptr();
+            }
+
+            let input_buffer = clap_audio_buffer {
+                data32: self.input_channel_ptrs.as_mut_ptr(),
+                data64: std::ptr::null_mut(),
+                channel_count: actual_input_channels as u32,
+                latency: 0,
+                constant_mask: 0,
+            };
+            let mut output_buffer = clap_audio_buffer {
+                data32: self.output_channel_ptrs.as_mut_ptr(),
+                data64: std::ptr::null_mut(),
+                channel_count: actual_output_channels as u32,
+                latency: 0,
+                constant_mask: 0,
+            };
+
+            // Step 3: Populate parameter events (pending changes first, at offset 0)
+            self.param_events.clear();
+            for (&param_id, &value) in &self.dirty_parameters {
+                self.param_events.push(param_id, 0, value);
+            }
+            for event in &events.events {
+                if let Event::ParamChange {
+                    id,
+                    value,
+                    sample_offset,
+                } = *event
+                {
+                    self.param_events.push(id, sample_offset, f64::from(value));
+                }
+            }
+            if !self.param_events.is_empty() {
+                self.param_events.sort();
+            }
+
+            let in_events = self.param_events.as_input_events();
+            let out_events = discard_output_events();
+
+            // Step 4: Call clap_plugin.process()
+            let process = clap_process {
+                steady_time: self.steady_time,
+                frames_count: audio.frames as u32,
+                transport: std::ptr::null(),
+                audio_inputs: &raw const input_buffer,
+                audio_outputs: &raw mut output_buffer,
+                audio_inputs_count: u32::from(self.input_channels > 0),
+                audio_outputs_count: u32::from(self.output_channels > 0),
+                in_events: &raw const in_events,
+                out_events: &raw const out_events,
+            };
+
+            let process_fn = (*self.plugin).process.ok_or_else(|| {
+                PluginError::ProcessingFailed("Plugin has no process()".to_string())
+            })?;
+            let status = process_fn(self.plugin, &raw const process);
+
+            self.dirty_parameters.clear();
+            self.steady_time += audio.frames as i64;
+
+            if status == CLAP_PROCESS_ERROR {
+                return Err(PluginError::ProcessingFailed(format!(
+                    "CLAP plugin '{}' reported a processing error",
+                    self.info.name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn set_parameter(&mut self, id: u32, value: f32) -> Result<(), PluginError> {
+        tracing::trace!("Setting parameter {} to {}", id, value);
+
+        if self.params.is_null() {
+            return Err(PluginError::InvalidParameter(format!(
+                "Cannot set parameter {id}: plugin has no parameters"
+            )));
+        }
+
+        // Sent with the next process() call, or flushed right away if not processing
+        self.dirty_parameters.insert(id, f64::from(value));
+        if !self.is_processing {
+            self.flush_parameters();
+        }
+
+        Ok(())
+    }
+
+    #[allow(unsafe_code)] // Required for FFI calls
+    fn get_parameter(&self, id: u32) -> Result<f32, PluginError> {
+        // A change the plugin hasn't seen yet is the current value
+        if let Some(&value) = self.dirty_parameters.get(&id) {
+            return Ok(value as f32);
+        }
+
+        let get_value = (!self.params.is_null())
+            .then(|| unsafe { (*self.params).get_value })
+            .flatten()
+            .ok_or_else(|| {
+                PluginError::InvalidParameter(format!(
+                    "Cannot read parameter {id}: plugin has no parameters"
+                ))
+            })?;
+
+        let mut value = 0.0;
+        if unsafe { get_value(self.plugin, id, &raw mut value) } {
+            Ok(value as f32)
+        } else {
+            Err(PluginError::InvalidParameter(format!(
+                "Unknown parameter ID: {id}"
+            )))
+        }
+    }
+
+    #[allow(unsafe_code)] // Required for FFI calls
+    fn parameters(&self) -> Vec<ParameterInfo> {
+        if self.params.is_null() {
+            tracing::debug!("No params extension - plugin has no parameters");
+            return Vec::new();
+        }
+
+        unsafe {
+            let (Some(count), Some(get_info)) = ((*self.params).count, (*self.params).get_info)
+            else {
+                return Vec::new();
+            };
+
+            let param_count = count(self.plugin);
+            let mut parameters = Vec::with_capacity(param_count as usize);
+
+            for index in 0..param_count {
+                let mut info: clap_param_info = std::mem::zeroed();
+                if !get_info(self.plugin, index, &raw mut info) {
+                    tracing::warn!("Failed to get parameter info for index {}", index);
+                    continue;
+                }
+
+                // CLAP parameters use plain values in [min, max]; stepped
+                // ones take every integer in that range
+                let step_count = if info.flags & CLAP_PARAM_IS_STEPPED == 0 {
+                    0
+                } else {
+                    (info.max_value - info.min_value).round().max(0.0) as u32
+                };
+                parameters.push(ParameterInfo {
+                    id: info.id,
+                    name: c_string_buffer(&info.name),
+                    min_value: info.min_value as f32,
+                    max_value: info.max_value as f32,
+                    default_value: info.default_value as f32,
+                    step_count,
+                });
+            }
+
+            parameters
+        }
+    }
+
+    fn input_channels(&self) -> ChannelCount {
+        self.input_channels
+    }
+
+    fn output_channels(&self) -> ChannelCount {
+        self.output_channels
+    }
+
+    fn latency(&self) -> Frames {
+        self.latency
+    }
+
+    fn tail_samples(&self) -> Frames {
+        self.tail
+    }
+
+    #[allow(unsafe_code)] // Required for FFI calls
+    fn deactivate(&mut self) {
+        // Only deactivate if currently active (avoid double-deactivation)
+        if !self.is_active {
+            return;
+        }
+
+        tracing::info!("Deactivating CLAP plugin '{}'", self.info.name);
+
+        unsafe {
+            if self.is_processing
+                && let Some(stop_processing) = (*self.plugin).stop_processing
+            {
+                stop_processing(self.plugin);
+            }
+
+            if let Some(deactivate) = (*self.plugin).deactivate {
+                deactivate(self.plugin);
+            }
+        }
+
+        self.is_processing = false;
+        self.is_active = false;
+
+        // Changes queued for a process() call that never came
+        self.flush_parameters();
+
+        tracing::debug!("CLAP plugin '{}' deactivated", self.info.name);
+    }
+}
+
+impl Drop for ClapPlugin {
+    #[allow(unsafe_code)] // Required for FFI cleanup
+    fn drop(&mut self) {
+        self.deactivate();
+
+        unsafe {
+            if let Some(destroy) = (*self.plugin).destroy {
+                tracing::debug!("Destroying CLAP plugin instance");
+                destroy(self.plugin);
+            }
+        }
+
+        // The host and entry fields drop after this, unloading the library
+        tracing::debug!("CLAP plugin '{}' dropped", self.info.name);
+    }
+}
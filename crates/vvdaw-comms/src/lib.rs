@@ -7,6 +7,7 @@ pub use rtrb;
 pub use triple_buffer;
 
 use crossbeam_channel::{Receiver, Sender};
+use std::path::PathBuf;
 use vvdaw_core::Sample;
 
 /// Commands that can be sent from UI thread to audio thread
@@ -19,13 +20,186 @@ pub enum AudioCommand {
     Start,
     /// Stop audio processing
     Stop,
+    /// Stop audio processing for good, before the engine is stopped
+    ///
+    /// Answered with [`AudioEvent::Stopped`]. The audio callback then outputs
+    /// silence and ignores further commands, so the UI can wait for the ack
+    /// and tear down the engine without racing the callback.
+    Shutdown,
     /// Set a parameter value (`node_id`, `param_id`, value)
     SetParameter(usize, u32, f32),
+    /// Apply the next [`ParameterBatch`] queued on `param_batch_tx`
+    ///
+    /// Use [`UiChannels::send_parameter_batch`] rather than pushing this directly,
+    /// so the batch is always queued before the command that consumes it.
+    SetParametersBulk,
+    /// Scrub playback around a position (tape-machine style)
+    ///
+    /// Plays a short window starting at `frame` at the given rate, even while
+    /// stopped. Send repeatedly (e.g. as the camera moves) for continuous scrubbing.
+    Scrub {
+        /// Position to scrub from (in frames)
+        frame: u64,
+        /// Playback rate (1.0 = normal speed, negative = reverse)
+        rate: f32,
+    },
+    /// Jump playback to a frame position
+    ///
+    /// Answered with [`AudioEvent::PositionChanged`] carrying the position
+    /// actually landed on (clamped to the loaded material).
+    Seek(u64),
+    /// Set the loop region for playback
+    ///
+    /// Playback wraps from `end` back to `start` (frames, end exclusive).
+    /// An empty region (`start >= end`) disables looping.
+    SetLoop {
+        /// First frame of the loop
+        start: u64,
+        /// Frame at which playback wraps back to `start`
+        end: u64,
+        /// Whether looping is enabled
+        enabled: bool,
+    },
+    /// Set the varispeed rate of a sampler node (node id, rate)
+    ///
+    /// Pitch follows speed like a turntable: 1.0 = normal, 0.0 = hold,
+    /// negative = reverse. Clamped to the node's range and answered with
+    /// [`AudioEvent::PlaybackRateChanged`]. Safe while playing.
+    SetPlaybackRate(usize, f32),
+    /// Set the transport tempo in beats per minute
+    ///
+    /// Passed to plugins with the rest of the transport state (see
+    /// `vvdaw_core::Transport`) so tempo-synced effects follow it. Clamped to
+    /// 1-999 BPM; safe while playing.
+    SetTempo(f32),
+    /// Bypass a node (pass its input straight through) or re-enable it
+    ///
+    /// Answered with [`AudioEvent::NodeBypassChanged`]. Safe while playing.
+    SetBypass {
+        /// Node to bypass
+        node_id: usize,
+        /// Whether the node is bypassed
+        bypassed: bool,
+    },
+    /// Solo a node, or clear its solo
+    ///
+    /// While any node is soloed only soloed output nodes are heard; solos add
+    /// up, and clearing them all restores normal routing. Answered with
+    /// [`AudioEvent::NodeSoloChanged`]. Safe while playing.
+    SetSolo {
+        /// Node to solo
+        node_id: usize,
+        /// Whether the node is soloed
+        solo: bool,
+    },
+    /// Skip the whole graph and monitor the raw input, or process it again
+    ///
+    /// While bypassed, `system_input` is copied straight to `system_output`,
+    /// for comparing processed and unprocessed audio. Unlike [`SetBypass`](Self::SetBypass)
+    /// this covers every node at once, and the graph is left intact so
+    /// switching back is instant. Answered with [`AudioEvent::GraphBypassChanged`].
+    SetGraphBypass(bool),
+    /// Feed live input into the graph, or stop
+    ///
+    /// While enabled (and playing), audio captured from the input device fills
+    /// `system_input`, so it runs through the graph like any other source -
+    /// e.g. a guitar into an amp-sim plugin. Off by default, to avoid feedback
+    /// from a live mic. Answered with [`AudioEvent::InputMonitorChanged`], plus
+    /// [`AudioEvent::Error`] if the engine has no live input. Safe while playing.
+    SetInputMonitor(bool),
+    /// Start or stop metering a node's output
+    ///
+    /// While enabled, the node's peak and RMS are reported with
+    /// [`AudioEvent::NodeMeter`] after every processed block. Safe while playing.
+    SetMetering {
+        /// Node to meter
+        node_id: usize,
+        /// Whether metering is enabled
+        enabled: bool,
+    },
+    /// Start or stop measuring how long each node takes to process
+    ///
+    /// While enabled, each node's share of the block budget is reported with
+    /// [`AudioEvent::NodeCpu`] alongside [`AudioEvent::DspLoad`]. Off by
+    /// default, since the timing itself costs a little. Safe while playing.
+    SetProfiling(bool),
+    /// Set how many output frames each oscilloscope point covers
+    ///
+    /// Every `n`th frame of the output goes into the [`ScopeWindow`] on
+    /// `scope_rx`, so the window spans `SCOPE_WINDOW_FRAMES * n` frames.
+    /// Values below 1 are treated as 1. Safe while playing.
+    SetScopeDecimation(usize),
+    /// Start (`Some`) or stop (`None`) the spectrum analyzer
+    ///
+    /// The audio thread only copies samples for an analyzer thread, which
+    /// runs the FFT and sends [`AudioEvent::Spectrum`] at the configured
+    /// rate. Replaces any analysis in progress. Safe while playing.
+    SetSpectrum(Option<SpectrumConfig>),
+    /// Start recording the engine output to a WAV file
+    ///
+    /// Output blocks are queued lock-free for a writer thread, which writes
+    /// 32-bit float WAV at the engine's sample rate. Replaces any recording in
+    /// progress. The path is moved on to the writer thread, so it is never
+    /// freed on the audio thread. Failures are reported with
+    /// [`AudioEvent::Error`].
+    StartRecording(PathBuf),
+    /// Stop recording and finish the file
+    ///
+    /// Answered with [`AudioEvent::RecordingStopped`] once the file is complete.
+    StopRecording,
+    /// Switch the engine to a new sample rate and block size
+    ///
+    /// The output stream is closed, every plugin is reinitialized and the
+    /// stream is reopened with the new settings - trading latency for
+    /// stability without restarting the app. Playback resumes if it was
+    /// running, but a recording in progress is finished. Answered with a fresh
+    /// [`AudioEvent::EngineInitialized`]; settings the device can't run, and
+    /// plugins that fail to reinitialize, are reported with
+    /// [`AudioEvent::Error`]. Commands queued behind this one run once the
+    /// stream is back.
+    SetConfig {
+        /// New sample rate in Hz
+        sample_rate: u32,
+        /// New block (buffer) size in frames
+        block_size: usize,
+    },
     /// Add a node to the graph
     AddNode,
     /// Remove a node from the graph
     RemoveNode(usize),
+    /// Replace a node with the plugin queued on `plugin_tx`
+    ///
+    /// Removes the node and adds the new plugin in one command, so a full
+    /// queue can't drop one half and orphan a node. Answered with
+    /// [`AudioEvent::NodeRemoved`] (if the node existed) then
+    /// [`AudioEvent::NodeAdded`], or [`AudioEvent::Error`] if nothing was added.
+    ///
+    /// Use [`UiChannels::send_node`] rather than pushing this directly.
+    ReplaceNode(usize),
+    /// Swap the plugin queued on `plugin_tx` into an existing node
+    ///
+    /// For hot-reloading a rebuilt plugin: load a fresh instance from the
+    /// node's path off the audio thread, then send it with this command. The
+    /// node keeps its ID and connections, and the old plugin's state is carried
+    /// over. Answered with [`AudioEvent::NodeReloaded`], or [`AudioEvent::Error`].
+    ///
+    /// Use [`UiChannels::send_reload`] rather than pushing this directly.
+    ReloadNode(usize),
+    /// Move a node to a new position in its serial chain
+    ///
+    /// Splices the node in just before `before`, or at the end of its chain
+    /// when `None`, rewiring its old and new neighbours in one step.
+    /// Answered with [`AudioEvent::NodeMoved`].
+    MoveNode {
+        /// Node to move
+        node_id: usize,
+        /// Node to place it in front of (`None` = end of the chain)
+        before: Option<usize>,
+    },
     /// Connect two nodes
+    ///
+    /// Answered with [`AudioEvent::Error`] if the connection would create a
+    /// cycle (or either node doesn't exist).
     Connect {
         /// Source node ID
         from: usize,
@@ -39,6 +213,81 @@ pub enum AudioCommand {
         /// Destination node ID
         to: usize,
     },
+    /// Set the level a connection mixes its source in at
+    ///
+    /// Applies to every channel connection from `from` to `to` (1.0 = unity,
+    /// 0.0 = muted). Answered with [`AudioEvent::Error`] if the nodes aren't
+    /// connected. Safe while playing.
+    SetConnectionGain {
+        /// Source node ID
+        from: usize,
+        /// Destination node ID
+        to: usize,
+        /// Linear gain
+        gain: f32,
+    },
+}
+
+/// Maximum number of parameter values carried by a single [`ParameterBatch`]
+pub const MAX_BATCH_PARAMETERS: usize = 64;
+
+/// A preloaded set of parameter values for one node
+///
+/// Lets a whole snapshot be recalled with a single [`AudioCommand::SetParametersBulk`]
+/// instead of flooding the command ring with one `SetParameter` per value.
+///
+/// Values are stored inline, so a batch never owns heap memory: it travels through
+/// the bounded `param_batch_tx` channel (whose slots are preallocated) and can be
+/// dropped on the audio thread without deallocating.
+#[derive(Debug, Clone, Copy)]
+pub struct ParameterBatch {
+    node_id: usize,
+    len: usize,
+    values: [(u32, f32); MAX_BATCH_PARAMETERS],
+}
+
+impl ParameterBatch {
+    /// Create an empty batch targeting the given node
+    pub const fn new(node_id: usize) -> Self {
+        Self {
+            node_id,
+            len: 0,
+            values: [(0, 0.0); MAX_BATCH_PARAMETERS],
+        }
+    }
+
+    /// Add a parameter value to the batch
+    ///
+    /// Returns `false` (and leaves the batch unchanged) if the batch already
+    /// holds [`MAX_BATCH_PARAMETERS`] values.
+    pub fn push(&mut self, param_id: u32, value: f32) -> bool {
+        if self.len == MAX_BATCH_PARAMETERS {
+            return false;
+        }
+        self.values[self.len] = (param_id, value);
+        self.len += 1;
+        true
+    }
+
+    /// The node these values apply to
+    pub const fn node_id(&self) -> usize {
+        self.node_id
+    }
+
+    /// Number of parameter values in the batch
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the batch holds no values
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterate over the `(param_id, value)` pairs in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = (u32, f32)> + '_ {
+        self.values[..self.len].iter().copied()
+    }
 }
 
 /// Events sent from audio thread back to UI thread
@@ -48,16 +297,47 @@ pub enum AudioEvent {
     Started,
     /// Audio processing stopped
     Stopped,
-    /// Audio engine initialized with actual device sample rate
+    /// Audio engine initialized and its output device opened
     ///
-    /// Sent once when the audio engine successfully starts and configures
-    /// the audio device. Reports the actual sample rate the engine is using,
-    /// which may differ from the requested rate if the device doesn't support it.
+    /// Sent when the audio engine successfully starts and configures the
+    /// audio device, and again each time [`AudioCommand::SetConfig`] reopens
+    /// it. Reports the sample rate the engine is using - the
+    /// requested one, since a rate the device doesn't support is rejected with
+    /// [`AudioEvent::Error`] instead.
     ///
     /// UI should use this rate for resampling imported audio files.
     EngineInitialized {
         /// Actual sample rate the audio engine is running at (e.g., 44100, 48000)
         sample_rate: u32,
+        /// Number of `system_output` channels (1 = mono, 2 = stereo, 4 = quad, ...)
+        output_channels: usize,
+        /// Name of the output device actually opened
+        ///
+        /// The default device when the requested one wasn't found.
+        device_name: String,
+    },
+    /// Result of requesting real-time scheduling for the audio thread
+    ///
+    /// Sent from the first audio callback (of each stream, see
+    /// [`AudioCommand::SetConfig`]) when `AudioConfig::realtime_priority`
+    /// is enabled. If not granted, audio keeps running at normal priority.
+    RealtimePriority {
+        /// Whether the OS granted real-time scheduling
+        granted: bool,
+        /// The scheduling achieved (e.g. `SCHED_FIFO priority 40`), or why
+        /// the OS refused
+        detail: String,
+    },
+    /// Result of pinning the audio thread to a CPU core
+    ///
+    /// Sent from the first audio callback (of each stream) when
+    /// `AudioConfig::cpu_affinity` is set. If not pinned, the OS keeps
+    /// scheduling the thread on any core.
+    CpuAffinity {
+        /// Whether the thread was pinned
+        pinned: bool,
+        /// The core pinned to (e.g. `CPU 3`), or why pinning failed
+        detail: String,
     },
     /// Error occurred
     Error(String),
@@ -84,6 +364,123 @@ pub enum AudioEvent {
         /// The ID of the removed node
         node_id: usize,
     },
+    /// Node's plugin was swapped by a `ReloadNode` command
+    NodeReloaded {
+        /// The reloaded node
+        node_id: usize,
+    },
+    /// Node was moved by a `MoveNode` command
+    NodeMoved {
+        /// The moved node
+        node_id: usize,
+        /// Node it now precedes (`None` = end of the chain)
+        before: Option<usize>,
+    },
+    /// Node bypass state changed by a `SetBypass` command
+    NodeBypassChanged {
+        /// The node whose bypass state changed
+        node_id: usize,
+        /// Whether the node is now bypassed
+        bypassed: bool,
+    },
+    /// A parameter was moved in a plugin's own editor
+    ///
+    /// The plugin already uses the new value; this keeps the host's view in sync.
+    ParameterChanged {
+        /// The node whose plugin changed the parameter
+        node_id: usize,
+        /// Parameter ID
+        param_id: u32,
+        /// New value (in the units of `Plugin::get_parameter`)
+        value: f32,
+    },
+    /// Node solo state changed by a `SetSolo` command
+    NodeSoloChanged {
+        /// The node whose solo state changed
+        node_id: usize,
+        /// Whether the node is now soloed
+        solo: bool,
+    },
+    /// Varispeed rate changed by a `SetPlaybackRate` command
+    PlaybackRateChanged {
+        /// The sampler node whose rate changed
+        node_id: usize,
+        /// Rate actually applied, after clamping
+        rate: f32,
+    },
+    /// Whole-graph bypass changed by a `SetGraphBypass` command
+    GraphBypassChanged {
+        /// Whether the graph is now bypassed (raw input on the output)
+        bypassed: bool,
+    },
+    /// Live input monitoring changed by a `SetInputMonitor` command
+    InputMonitorChanged {
+        /// Whether live input now feeds the graph
+        enabled: bool,
+    },
+    /// Output level of a metered node (see [`AudioCommand::SetMetering`])
+    ///
+    /// Covers the last processed block, across all of the node's output channels.
+    NodeMeter {
+        /// The metered node
+        node_id: usize,
+        /// Largest absolute sample value
+        peak: Sample,
+        /// Root mean square level
+        rms: Sample,
+    },
+    /// Total graph latency changed (after nodes or connections changed)
+    ///
+    /// Reports the latency of the slowest path, which the graph compensates
+    /// for on all other paths. The UI can use it to display output delay.
+    LatencyChanged {
+        /// Latency from input to output (in frames)
+        frames: usize,
+    },
+    /// The audio callback missed its deadline while playing (an xrun)
+    ///
+    /// Detected from callback timing, so the device may have played a gap.
+    /// Sent for each xrun, carrying the total since the engine started.
+    Xrun {
+        /// Xruns detected so far
+        count: u64,
+    },
+    /// Rolling estimate of how busy the graph keeps the audio callback
+    ///
+    /// Graph processing time over buffer duration, sent a few times a second
+    /// while playing. Audio glitches as this approaches 1.0, so warn well before.
+    DspLoad {
+        /// Share of each buffer's duration spent processing
+        fraction: f32,
+    },
+    /// Share of the block budget one node's plugin takes, while profiling
+    /// (see [`AudioCommand::SetProfiling`])
+    ///
+    /// Averaged over recent blocks and sent for every node a few times a
+    /// second while playing. The shares add up to roughly the
+    /// [`AudioEvent::DspLoad`], so the largest is the plugin to blame for
+    /// glitches.
+    NodeCpu {
+        /// The profiled node
+        node_id: usize,
+        /// Share of each block's duration spent in this node
+        fraction: f32,
+    },
+    /// Master output went beyond full scale (±1.0) in the last block
+    ///
+    /// Sent per channel, only for blocks where that channel clipped, so the
+    /// UI can light a clip indicator. Summed graphs overshoot easily.
+    Clip {
+        /// Output channel that clipped
+        channel: usize,
+        /// Samples in the block whose absolute value exceeded 1.0
+        count: u32,
+    },
+    /// Playback position was moved by a `Seek` command
+    PositionChanged {
+        /// New position (in frames)
+        position: u64,
+    },
     /// Waveform sample data for visualization
     ///
     /// Sent from audio thread with peak values for the current audio buffer.
@@ -96,6 +493,92 @@ pub enum AudioEvent {
         /// Right channel peak value for this buffer
         right_peak: Sample,
     },
+    /// Phase correlation of the left and right master output over one buffer
+    ///
+    /// Sent alongside [`AudioEvent::WaveformSample`] while playing stereo output.
+    StereoCorrelation {
+        /// -1.0 = fully out of phase, 0.0 = decorrelated (or silent), +1.0 = mono
+        value: Sample,
+    },
+    /// Magnitude spectrum of the analyzed signal (see [`AudioCommand::SetSpectrum`])
+    ///
+    /// Computed off the audio thread from the latest `fft_size` samples with
+    /// a Hann window.
+    Spectrum {
+        /// Linear magnitude per bin (1.0 = full-scale sine), from DC up to
+        /// just below Nyquist in steps of `sample_rate / fft_size`
+        bins: Vec<Sample>,
+    },
+    /// A recording started with [`AudioCommand::StartRecording`] was finished
+    ///
+    /// Also sent when a recording failed (after the [`AudioEvent::Error`]),
+    /// with the frames written before the failure.
+    RecordingStopped {
+        /// Frames written to the file
+        frames_written: u64,
+    },
+}
+
+/// Number of points in a [`ScopeWindow`]
+pub const SCOPE_WINDOW_FRAMES: usize = 1024;
+
+/// Oscilloscope decimation until [`AudioCommand::SetScopeDecimation`] changes it
+pub const DEFAULT_SCOPE_DECIMATION: usize = 4;
+
+/// Spectrum analyzer FFT size until a [`SpectrumConfig`] says otherwise
+pub const DEFAULT_SPECTRUM_FFT_SIZE: usize = 1024;
+
+/// Spectrum updates per second until a [`SpectrumConfig`] says otherwise
+pub const DEFAULT_SPECTRUM_RATE: f32 = 30.0;
+
+/// What the spectrum analyzer listens to, and how often it reports
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpectrumConfig {
+    /// Node whose output is analyzed (`None` = the master output)
+    pub node_id: Option<usize>,
+    /// FFT length in samples, rounded up to a power of two - the spectrum
+    /// has half as many bins
+    pub fft_size: usize,
+    /// How many spectra to send per second of audio
+    pub updates_per_second: f32,
+}
+
+impl Default for SpectrumConfig {
+    fn default() -> Self {
+        Self {
+            node_id: None,
+            fft_size: DEFAULT_SPECTRUM_FFT_SIZE,
+            updates_per_second: DEFAULT_SPECTRUM_RATE,
+        }
+    }
+}
+
+/// The most recent stretch of the master output, for an oscilloscope display
+///
+/// The audio thread republishes this on the `scope_tx` triple buffer after
+/// every block, so the UI always reads the latest window without the audio
+/// thread allocating (the samples are stored inline) or waiting.
+#[derive(Debug, Clone, Copy)]
+pub struct ScopeWindow {
+    /// Frame position of the first (oldest) point
+    pub position: u64,
+    /// Output frames per point (see [`AudioCommand::SetScopeDecimation`])
+    pub decimation: usize,
+    /// Left channel, oldest point first
+    pub left: [Sample; SCOPE_WINDOW_FRAMES],
+    /// Right channel, oldest point first (a copy of the left for mono output)
+    pub right: [Sample; SCOPE_WINDOW_FRAMES],
+}
+
+impl Default for ScopeWindow {
+    fn default() -> Self {
+        Self {
+            position: 0,
+            decimation: DEFAULT_SCOPE_DECIMATION,
+            left: [0.0; SCOPE_WINDOW_FRAMES],
+            right: [0.0; SCOPE_WINDOW_FRAMES],
+        }
+    }
 }
 
 /// Type alias for command channel (UI -> Audio)
@@ -108,6 +591,11 @@ pub type EventSender = rtrb::Producer<AudioEvent>;
 /// Event receiver (UI thread)
 pub type EventReceiver = rtrb::Consumer<AudioEvent>;
 
+/// Oscilloscope window publisher (audio thread)
+pub type ScopeSender = triple_buffer::Input<ScopeWindow>;
+/// Oscilloscope window reader (UI thread)
+pub type ScopeReceiver = triple_buffer::Output<ScopeWindow>;
+
 /// Type alias for plugin instance (sent from UI to audio thread)
 pub type PluginInstance = Box<dyn vvdaw_plugin::Plugin>;
 
@@ -116,17 +604,24 @@ pub fn create_channels(capacity: usize) -> (UiChannels, AudioChannels) {
     let (cmd_tx, cmd_rx) = rtrb::RingBuffer::new(capacity);
     let (evt_tx, evt_rx) = rtrb::RingBuffer::new(capacity);
     let (plugin_tx, plugin_rx) = crossbeam_channel::unbounded();
+    // Bounded so the slots are allocated up front - send/recv never allocate
+    let (param_batch_tx, param_batch_rx) = crossbeam_channel::bounded(capacity);
+    let (scope_tx, scope_rx) = triple_buffer::triple_buffer(&ScopeWindow::default());
 
     let ui_channels = UiChannels {
         command_tx: cmd_tx,
         event_rx: evt_rx,
         plugin_tx,
+        param_batch_tx,
+        scope_rx,
     };
 
     let audio_channels = AudioChannels {
         command_rx: cmd_rx,
         event_tx: evt_tx,
         plugin_rx,
+        param_batch_rx,
+        scope_tx,
     };
 
     (ui_channels, audio_channels)
@@ -140,6 +635,120 @@ pub struct UiChannels {
     pub event_rx: rtrb::Consumer<AudioEvent>,
     /// Plugin sender (UI -> Audio) - separate channel for non-Clone types
     pub plugin_tx: Sender<PluginInstance>,
+    /// Parameter batch sender (UI -> Audio) - consumed by `SetParametersBulk`
+    pub param_batch_tx: Sender<ParameterBatch>,
+    /// Latest oscilloscope window (Audio -> UI) - `read()` never blocks
+    pub scope_rx: ScopeReceiver,
+}
+
+impl UiChannels {
+    /// Queue a parameter batch and the `SetParametersBulk` command that applies it
+    ///
+    /// The batch is sent before the command so the audio thread always finds it.
+    /// Returns `false` if either queue is full, in which case nothing is sent.
+    pub fn send_parameter_batch(&mut self, batch: &ParameterBatch) -> bool {
+        // Check for command space first so a queued batch is never left without
+        // its command (it would otherwise be applied by the *next* bulk command)
+        if self.command_tx.slots() == 0 || self.param_batch_tx.try_send(*batch).is_err() {
+            return false;
+        }
+        // Cannot fail: we are the only producer and a slot was free above
+        let _ = self.command_tx.push(AudioCommand::SetParametersBulk);
+        true
+    }
+
+    /// Queue a plugin to add, optionally replacing an existing node
+    ///
+    /// See [`send_node`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the plugin if nothing could be sent.
+    pub fn send_node(
+        &mut self,
+        plugin: PluginInstance,
+        replacing: Option<usize>,
+    ) -> Result<(), PluginInstance> {
+        send_node(&mut self.command_tx, &self.plugin_tx, plugin, replacing)
+    }
+
+    /// Queue a freshly loaded plugin to swap into an existing node
+    ///
+    /// See [`send_reload`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the plugin if nothing could be sent.
+    pub fn send_reload(
+        &mut self,
+        plugin: PluginInstance,
+        node_id: usize,
+    ) -> Result<(), PluginInstance> {
+        send_reload(&mut self.command_tx, &self.plugin_tx, plugin, node_id)
+    }
+}
+
+/// Queue a plugin and the command that adds it to the graph
+///
+/// Sends `AddNode`, or `ReplaceNode` when `replacing` is set. The command slot
+/// is checked before the plugin is queued, so a plugin is never stranded
+/// without its command (the *next* `AddNode` would otherwise pick it up).
+///
+/// # Errors
+///
+/// Returns the plugin if the command queue is full or the audio thread is
+/// gone, in which case nothing is sent and the caller can retry later.
+pub fn send_node(
+    command_tx: &mut CommandSender,
+    plugin_tx: &Sender<PluginInstance>,
+    plugin: PluginInstance,
+    replacing: Option<usize>,
+) -> Result<(), PluginInstance> {
+    send_plugin_with(
+        command_tx,
+        plugin_tx,
+        plugin,
+        replacing.map_or(AudioCommand::AddNode, AudioCommand::ReplaceNode),
+    )
+}
+
+/// Queue a plugin and the `ReloadNode` command that swaps it into `node_id`
+///
+/// Like [`send_node`], the plugin is never stranded without its command.
+///
+/// # Errors
+///
+/// Returns the plugin if the command queue is full or the audio thread is
+/// gone, in which case nothing is sent and the caller can retry later.
+pub fn send_reload(
+    command_tx: &mut CommandSender,
+    plugin_tx: &Sender<PluginInstance>,
+    plugin: PluginInstance,
+    node_id: usize,
+) -> Result<(), PluginInstance> {
+    send_plugin_with(
+        command_tx,
+        plugin_tx,
+        plugin,
+        AudioCommand::ReloadNode(node_id),
+    )
+}
+
+/// Queue a plugin, then the command that consumes it
+fn send_plugin_with(
+    command_tx: &mut CommandSender,
+    plugin_tx: &Sender<PluginInstance>,
+    plugin: PluginInstance,
+    command: AudioCommand,
+) -> Result<(), PluginInstance> {
+    if command_tx.slots() == 0 {
+        return Err(plugin);
+    }
+    plugin_tx.send(plugin).map_err(|e| e.0)?;
+
+    // Cannot fail: we are the only producer and a slot was free above
+    let _ = command_tx.push(command);
+    Ok(())
 }
 
 /// Channels for the audio thread (receives commands, sends events)
@@ -150,15 +759,164 @@ pub struct AudioChannels {
     pub event_tx: rtrb::Producer<AudioEvent>,
     /// Plugin receiver (UI -> Audio) - `try_recv` is non-blocking
     pub plugin_rx: Receiver<PluginInstance>,
+    /// Parameter batch receiver (UI -> Audio) - `try_recv` is non-blocking
+    pub param_batch_rx: Receiver<ParameterBatch>,
+    /// Oscilloscope window publisher (Audio -> UI) - `write()` never blocks
+    pub scope_tx: ScopeSender,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use vvdaw_core::{ChannelCount, Frames, SampleRate};
+    use vvdaw_plugin::{AudioBuffer, EventBuffer, ParameterInfo, Plugin, PluginError, PluginInfo};
+
+    /// Plugin that does nothing, for exercising the plugin channel
+    struct NullPlugin {
+        info: PluginInfo,
+    }
+
+    impl NullPlugin {
+        fn new() -> Self {
+            Self {
+                info: PluginInfo {
+                    name: "Null".to_string(),
+                    vendor: "vvdaw".to_string(),
+                    version: "1.0.0".to_string(),
+                    unique_id: "vvdaw.test.null".to_string(),
+                    category: None,
+                },
+            }
+        }
+    }
+
+    impl Plugin for NullPlugin {
+        fn info(&self) -> &PluginInfo {
+            &self.info
+        }
+
+        fn initialize(
+            &mut self,
+            _sample_rate: SampleRate,
+            _block: Frames,
+        ) -> Result<(), PluginError> {
+            Ok(())
+        }
+
+        fn process(
+            &mut self,
+            _audio: &mut AudioBuffer,
+            _events: &EventBuffer,
+        ) -> Result<(), PluginError> {
+            Ok(())
+        }
+
+        fn set_parameter(&mut self, _id: u32, _value: f32) -> Result<(), PluginError> {
+            Ok(())
+        }
+
+        fn get_parameter(&self, _id: u32) -> Result<f32, PluginError> {
+            Ok(0.0)
+        }
+
+        fn parameters(&self) -> Vec<ParameterInfo> {
+            Vec::new()
+        }
+
+        fn input_channels(&self) -> ChannelCount {
+            0
+        }
+
+        fn output_channels(&self) -> ChannelCount {
+            0
+        }
+
+        fn deactivate(&mut self) {}
+    }
 
     #[test]
     fn test_channel_creation() {
         let (mut ui, _audio) = create_channels(256);
         assert!(ui.command_tx.push(AudioCommand::Start).is_ok());
     }
+
+    #[test]
+    fn test_parameter_batch_capacity() {
+        let mut batch = ParameterBatch::new(3);
+        assert!(batch.is_empty());
+
+        for id in 0..MAX_BATCH_PARAMETERS as u32 {
+            assert!(batch.push(id, 0.5));
+        }
+        assert_eq!(batch.len(), MAX_BATCH_PARAMETERS);
+        assert!(!batch.push(999, 1.0), "Full batch should reject values");
+        assert_eq!(batch.node_id(), 3);
+        assert_eq!(
+            batch.iter().last(),
+            Some((MAX_BATCH_PARAMETERS as u32 - 1, 0.5))
+        );
+    }
+
+    #[test]
+    fn test_send_parameter_batch_queues_batch_then_command() {
+        let (mut ui, mut audio) = create_channels(4);
+
+        let mut batch = ParameterBatch::new(0);
+        batch.push(1, 0.25);
+        assert!(ui.send_parameter_batch(&batch));
+
+        assert!(matches!(
+            audio.command_rx.pop(),
+            Ok(AudioCommand::SetParametersBulk)
+        ));
+        let received = audio.param_batch_rx.try_recv().unwrap();
+        assert_eq!(received.iter().collect::<Vec<_>>(), vec![(1, 0.25)]);
+    }
+
+    #[test]
+    fn test_send_node_replacing() {
+        let (mut ui, mut audio) = create_channels(4);
+
+        assert!(ui.send_node(Box::new(NullPlugin::new()), Some(3)).is_ok());
+        assert!(matches!(
+            audio.command_rx.pop(),
+            Ok(AudioCommand::ReplaceNode(3))
+        ));
+        assert!(audio.plugin_rx.try_recv().is_ok());
+
+        assert!(ui.send_node(Box::new(NullPlugin::new()), None).is_ok());
+        assert!(matches!(audio.command_rx.pop(), Ok(AudioCommand::AddNode)));
+    }
+
+    #[test]
+    fn test_send_reload() {
+        let (mut ui, mut audio) = create_channels(4);
+
+        assert!(ui.send_reload(Box::new(NullPlugin::new()), 5).is_ok());
+        assert!(matches!(
+            audio.command_rx.pop(),
+            Ok(AudioCommand::ReloadNode(5))
+        ));
+        assert!(audio.plugin_rx.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_send_node_full_command_queue() {
+        let (mut ui, audio) = create_channels(1);
+        ui.command_tx.push(AudioCommand::Start).unwrap();
+
+        // The plugin comes back rather than being stranded without its command
+        assert!(ui.send_node(Box::new(NullPlugin::new()), None).is_err());
+        assert!(audio.plugin_rx.is_empty());
+    }
+
+    #[test]
+    fn test_send_parameter_batch_full_command_queue() {
+        let (mut ui, audio) = create_channels(1);
+        ui.command_tx.push(AudioCommand::Start).unwrap();
+
+        assert!(!ui.send_parameter_batch(&ParameterBatch::new(0)));
+        // The batch must not be stranded without its command
+        assert!(audio.param_batch_rx.is_empty());
+    }
 }
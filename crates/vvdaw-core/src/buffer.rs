@@ -0,0 +1,158 @@
+//! Conversions between interleaved and per-channel sample buffers.
+//!
+//! Audio devices and files interleave channels (`L R L R ...`) while plugins
+//! and the graph process one buffer per channel. These helpers convert
+//! between the two into caller-owned buffers, so the audio thread can reuse
+//! the same preallocated storage every block.
+
+use crate::{ChannelCount, Frames, Sample};
+use std::ops::Range;
+
+/// Split interleaved samples into one buffer per channel
+///
+/// Converts as many whole frames as both `interleaved` and the shortest
+/// buffer in `out` hold, and returns that frame count. A trailing partial
+/// frame is ignored. Channels without a buffer in `out` are dropped, and
+/// buffers past `channels` are filled with silence.
+///
+/// REAL-TIME SAFE: Never allocates.
+pub fn deinterleave(
+    interleaved: &[Sample],
+    channels: ChannelCount,
+    out: &mut [Vec<Sample>],
+) -> Frames {
+    if channels == 0 {
+        return 0;
+    }
+    let frames = out
+        .iter()
+        .map(Vec::len)
+        .min()
+        .unwrap_or(0)
+        .min(interleaved.len() / channels);
+
+    for (channel, buffer) in out.iter_mut().enumerate() {
+        if channel < channels {
+            for (sample, frame) in buffer[..frames]
+                .iter_mut()
+                .zip(interleaved.chunks_exact(channels))
+            {
+                *sample = frame[channel];
+            }
+        } else {
+            buffer[..frames].fill(0.0);
+        }
+    }
+    frames
+}
+
+/// Interleave one buffer per channel into `out`
+///
+/// `out` holds `channels.len()` samples per frame. Converts as many whole
+/// frames as both `out` and the shortest channel buffer hold, and returns
+/// that frame count. Samples in `out` past the last whole frame are left
+/// untouched.
+///
+/// REAL-TIME SAFE: Never allocates.
+pub fn interleave(channels: &[Vec<Sample>], out: &mut [Sample]) -> Frames {
+    if channels.is_empty() {
+        return 0;
+    }
+    let frames = channels
+        .iter()
+        .map(Vec::len)
+        .min()
+        .unwrap_or(0)
+        .min(out.len() / channels.len());
+
+    for (index, frame) in out
+        .chunks_exact_mut(channels.len())
+        .take(frames)
+        .enumerate()
+    {
+        for (sample, buffer) in frame.iter_mut().zip(channels) {
+            *sample = buffer[index];
+        }
+    }
+    frames
+}
+
+/// Split `total` frames into consecutive blocks of at most `block_size`
+///
+/// Yields each block's frame range; only the last block can be short. A zero
+/// block size yields nothing.
+pub fn blocks(total: Frames, block_size: Frames) -> impl Iterator<Item = Range<Frames>> {
+    let total = if block_size == 0 { 0 } else { total };
+    (0..total)
+        .step_by(block_size.max(1))
+        .map(move |start| start..(start + block_size).min(total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deinterleave_stereo() {
+        let interleaved = [1.0, -1.0, 2.0, -2.0, 3.0, -3.0];
+        let mut out = vec![vec![0.0; 4]; 2];
+
+        assert_eq!(deinterleave(&interleaved, 2, &mut out), 3);
+        assert_eq!(out[0], [1.0, 2.0, 3.0, 0.0]);
+        assert_eq!(out[1], [-1.0, -2.0, -3.0, 0.0]);
+    }
+
+    #[test]
+    fn test_deinterleave_limits() {
+        // A trailing partial frame and frames past the buffers are ignored
+        let interleaved = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+        let mut out = vec![vec![9.0; 2]; 3];
+        assert_eq!(deinterleave(&interleaved, 2, &mut out), 2);
+        assert_eq!(out[0], [1.0, 3.0]);
+        assert_eq!(out[1], [2.0, 4.0]);
+        assert_eq!(out[2], [0.0, 0.0], "Extra buffers are silent");
+
+        // Channels without a buffer are dropped
+        let mut out = vec![vec![0.0; 4]];
+        assert_eq!(deinterleave(&interleaved, 3, &mut out), 2);
+        assert_eq!(out[0], [1.0, 4.0, 0.0, 0.0]);
+
+        assert_eq!(deinterleave(&interleaved, 0, &mut out), 0);
+    }
+
+    #[test]
+    fn test_interleave_round_trip() {
+        let interleaved: Vec<Sample> = (0..12).map(|i| i as Sample).collect();
+        let mut planar = vec![vec![0.0; 4]; 3];
+        assert_eq!(deinterleave(&interleaved, 3, &mut planar), 4);
+
+        let mut out = vec![0.0; 12];
+        assert_eq!(interleave(&planar, &mut out), 4);
+        assert_eq!(out, interleaved);
+    }
+
+    #[test]
+    fn test_interleave_limits() {
+        let planar = vec![vec![1.0, 2.0, 3.0], vec![-1.0, -2.0]];
+
+        // Shortest channel wins; the rest of `out` is untouched
+        let mut out = vec![9.0; 7];
+        assert_eq!(interleave(&planar, &mut out), 2);
+        assert_eq!(out, [1.0, -1.0, 2.0, -2.0, 9.0, 9.0, 9.0]);
+
+        // `out` shorter than the channels
+        let mut out = vec![9.0; 3];
+        assert_eq!(interleave(&planar, &mut out), 1);
+        assert_eq!(out, [1.0, -1.0, 9.0]);
+
+        assert_eq!(interleave(&[], &mut out), 0);
+    }
+
+    #[test]
+    fn test_blocks() {
+        assert_eq!(blocks(10, 4).collect::<Vec<_>>(), [0..4, 4..8, 8..10]);
+        assert_eq!(blocks(8, 4).collect::<Vec<_>>(), [0..4, 4..8]);
+        assert_eq!(blocks(0, 4).count(), 0);
+        assert_eq!(blocks(10, 0).count(), 0);
+    }
+}
@@ -0,0 +1,154 @@
+//! Position formatting: frames to clock time and to bars and beats.
+//!
+//! The UI and the offline tools show playback positions the same way, so the
+//! conversions live here instead of being re-derived next to each display.
+
+use crate::SampleRate;
+use std::fmt;
+
+/// Subdivisions of a beat in [`BarsBeats::ticks`]
+pub const TICKS_PER_BEAT: u32 = 960;
+
+/// Convert a frame count to seconds
+///
+/// A zero sample rate gives 0.0 rather than dividing by zero.
+#[must_use]
+#[allow(clippy::cast_precision_loss)] // Positions are far below 2^52 frames
+pub fn samples_to_seconds(samples: u64, sample_rate: SampleRate) -> f64 {
+    if sample_rate == 0 {
+        return 0.0;
+    }
+    samples as f64 / f64::from(sample_rate)
+}
+
+/// Format seconds as `mm:ss.mmm`
+///
+/// Minutes keep counting past an hour (`75:00.000`). Negative and non-finite
+/// times show as zero.
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Non-negative, finite
+pub fn seconds_to_timecode_string(seconds: f64) -> String {
+    let seconds = if seconds.is_finite() {
+        seconds.max(0.0)
+    } else {
+        0.0
+    };
+
+    // Round once, so 59.9996 shows as 01:00.000 rather than 00:60.000
+    let millis = (seconds * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}.{:03}",
+        millis / 60_000,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+/// Musical position: 1-based bar and beat, plus ticks into the beat
+///
+/// Beats are in the time signature's denominator (eighth notes in 6/8).
+/// Displays as `bar.beat.ticks`, e.g. `3.2.480`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BarsBeats {
+    /// Bar number, starting at 1
+    pub bar: u64,
+    /// Beat within the bar, starting at 1
+    pub beat: u32,
+    /// Position within the beat (0..[`TICKS_PER_BEAT`])
+    pub ticks: u32,
+}
+
+impl fmt::Display for BarsBeats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{:03}", self.bar, self.beat, self.ticks)
+    }
+}
+
+/// Convert a frame count to bars and beats at a fixed tempo
+///
+/// `tempo` is in quarter notes per minute and `time_signature` is
+/// `(numerator, denominator)`, as in [`crate::Transport`]. A zero sample
+/// rate, a non-positive tempo or an empty time signature gives `1.1.000`.
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Non-negative, finite
+pub fn samples_to_bars_beats(
+    samples: u64,
+    sample_rate: SampleRate,
+    tempo: f64,
+    time_signature: (u32, u32),
+) -> BarsBeats {
+    let (numerator, denominator) = time_signature;
+    let start = BarsBeats {
+        bar: 1,
+        beat: 1,
+        ticks: 0,
+    };
+    if numerator == 0 || denominator == 0 || !tempo.is_finite() || tempo <= 0.0 {
+        return start;
+    }
+
+    // Quarter notes elapsed, in whole ticks of the signature's beat
+    let quarter_notes = samples_to_seconds(samples, sample_rate) * tempo / 60.0;
+    let beats = quarter_notes * f64::from(denominator) / 4.0;
+    let total_ticks = (beats * f64::from(TICKS_PER_BEAT)).round() as u64;
+
+    let total_beats = total_ticks / u64::from(TICKS_PER_BEAT);
+    BarsBeats {
+        bar: total_beats / u64::from(numerator) + 1,
+        beat: (total_beats % u64::from(numerator)) as u32 + 1,
+        ticks: (total_ticks % u64::from(TICKS_PER_BEAT)) as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_samples_to_seconds() {
+        assert_eq!(samples_to_seconds(96000, 48000), 2.0);
+        assert_eq!(samples_to_seconds(22050, 44100), 0.5);
+        assert_eq!(samples_to_seconds(1000, 0), 0.0);
+    }
+
+    #[test]
+    fn test_timecode_string() {
+        assert_eq!(seconds_to_timecode_string(0.0), "00:00.000");
+        assert_eq!(seconds_to_timecode_string(83.25), "01:23.250");
+        assert_eq!(seconds_to_timecode_string(4500.0), "75:00.000");
+        assert_eq!(seconds_to_timecode_string(59.9996), "01:00.000");
+        assert_eq!(seconds_to_timecode_string(-1.0), "00:00.000");
+        assert_eq!(seconds_to_timecode_string(f64::NAN), "00:00.000");
+    }
+
+    #[test]
+    fn test_bars_beats_in_four_four() {
+        // One beat is half a second at 120 BPM
+        let at = |seconds: u64| samples_to_bars_beats(seconds * 24000, 48000, 120.0, (4, 4));
+        assert_eq!(at(0).to_string(), "1.1.000");
+        assert_eq!(at(1).to_string(), "1.2.000");
+        assert_eq!(at(4).to_string(), "2.1.000");
+        assert_eq!(at(7).to_string(), "2.4.000");
+
+        // Half a beat in
+        let position = samples_to_bars_beats(12000, 48000, 120.0, (4, 4));
+        assert_eq!(position.ticks, TICKS_PER_BEAT / 2);
+    }
+
+    #[test]
+    fn test_bars_beats_in_six_eight() {
+        // Eighth-note beats: a 6/8 bar is three quarter notes (1.5 s at 120 BPM)
+        let position = samples_to_bars_beats(72000, 48000, 120.0, (6, 8));
+        assert_eq!(position.to_string(), "2.1.000");
+        let position = samples_to_bars_beats(12000, 48000, 120.0, (6, 8));
+        assert_eq!(position.to_string(), "1.2.000");
+    }
+
+    #[test]
+    fn test_bars_beats_degenerate_input() {
+        let start = samples_to_bars_beats(0, 48000, 120.0, (4, 4));
+        assert_eq!(samples_to_bars_beats(48000, 0, 120.0, (4, 4)), start);
+        assert_eq!(samples_to_bars_beats(48000, 48000, 0.0, (4, 4)), start);
+        assert_eq!(samples_to_bars_beats(48000, 48000, 120.0, (0, 4)), start);
+    }
+}
@@ -0,0 +1,56 @@
+//! Decibel and linear amplitude conversions.
+//!
+//! Faders and meters talk in decibels (relative to full scale), while
+//! processing multiplies by linear gain. Silence is `-inf` dB, and the
+//! conversions map it to and from exactly 0.0.
+
+/// Convert decibels to linear amplitude (0 dB = 1.0)
+///
+/// `-inf` dB gives exactly 0.0.
+pub fn db_to_linear(db: f32) -> f32 {
+    if db == f32::NEG_INFINITY {
+        0.0
+    } else {
+        10.0_f32.powf(db / 20.0)
+    }
+}
+
+/// Convert linear amplitude to decibels (1.0 = 0 dB)
+///
+/// The sign is ignored, and silence (0.0) gives `-inf` dB.
+pub fn linear_to_db(linear: f32) -> f32 {
+    let amplitude = linear.abs();
+    if amplitude == 0.0 {
+        f32::NEG_INFINITY
+    } else {
+        20.0 * amplitude.log10()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_db_to_linear() {
+        assert_eq!(db_to_linear(0.0), 1.0);
+        assert!((db_to_linear(-6.0) - 0.501).abs() < 1e-3);
+        assert!((db_to_linear(12.0) - 3.981).abs() < 1e-3);
+        assert_eq!(db_to_linear(f32::NEG_INFINITY), 0.0);
+    }
+
+    #[test]
+    fn test_linear_to_db() {
+        assert_eq!(linear_to_db(1.0), 0.0);
+        assert!((linear_to_db(0.5) + 6.021).abs() < 1e-3);
+        assert!((linear_to_db(-2.0) - 6.021).abs() < 1e-3);
+        assert_eq!(linear_to_db(0.0), f32::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for db in [-60.0, -18.5, -0.1, 0.0, 3.0, 12.0] {
+            assert!((linear_to_db(db_to_linear(db)) - db).abs() < 1e-4);
+        }
+    }
+}
@@ -2,6 +2,15 @@
 //!
 //! This crate provides fundamental building blocks that all other vvdaw crates depend on.
 
+pub mod buffer;
+pub mod format;
+pub mod level;
+pub mod paths;
+pub mod transport;
+
+pub use level::{db_to_linear, linear_to_db};
+pub use transport::Transport;
+
 /// Sample rate in Hz
 pub type SampleRate = u32;
 
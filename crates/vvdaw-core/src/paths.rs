@@ -0,0 +1,110 @@
+//! Per-user directories for vvdaw's files.
+//!
+//! The UI config and the plugin scan cache both live in a `vvdaw`
+//! subdirectory of the platform's standard location, resolved here so the
+//! two can't disagree about where that is.
+
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// Kind of per-user directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserDir {
+    /// Settings the user would want to keep
+    Config,
+    /// Data that can be rebuilt at any time
+    Cache,
+}
+
+/// vvdaw's directory of the given kind for the current platform
+///
+/// - macOS: `~/Library/Application Support/vvdaw` or `~/Library/Caches/vvdaw`
+/// - Windows: `%APPDATA%\vvdaw` or `%LOCALAPPDATA%\vvdaw`
+/// - Linux: `$XDG_CONFIG_HOME/vvdaw` (default `~/.config`) or
+///   `$XDG_CACHE_HOME/vvdaw` (default `~/.cache`)
+///
+/// A relative XDG variable is ignored, as the spec requires. Returns `None`
+/// if the home directory can't be determined.
+pub fn user_dir(kind: UserDir) -> Option<PathBuf> {
+    resolve(kind, |var| std::env::var_os(var))
+}
+
+/// [`user_dir`] with the environment supplied by `var`
+fn resolve(kind: UserDir, var: impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    let home = || var("HOME").map(PathBuf::from);
+
+    let base = if cfg!(target_os = "macos") {
+        home().map(|home| match kind {
+            UserDir::Config => home.join("Library/Application Support"),
+            UserDir::Cache => home.join("Library/Caches"),
+        })
+    } else if cfg!(target_os = "windows") {
+        match kind {
+            UserDir::Config => var("APPDATA"),
+            UserDir::Cache => var("LOCALAPPDATA"),
+        }
+        .map(PathBuf::from)
+    } else {
+        let (xdg_var, fallback) = match kind {
+            UserDir::Config => ("XDG_CONFIG_HOME", ".config"),
+            UserDir::Cache => ("XDG_CACHE_HOME", ".cache"),
+        };
+        var(xdg_var)
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| home().map(|home| home.join(fallback)))
+    };
+
+    base.map(|dir| dir.join("vvdaw"))
+}
+
+#[cfg(all(test, not(any(target_os = "macos", target_os = "windows"))))]
+mod tests {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<OsString> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| OsString::from(value))
+        }
+    }
+
+    #[test]
+    fn test_xdg_dirs_are_used_when_absolute() {
+        let vars = [
+            ("HOME", "/home/user"),
+            ("XDG_CONFIG_HOME", "/xdg/config"),
+            ("XDG_CACHE_HOME", "/xdg/cache"),
+        ];
+
+        assert_eq!(
+            resolve(UserDir::Config, env(&vars)),
+            Some(PathBuf::from("/xdg/config/vvdaw"))
+        );
+        assert_eq!(
+            resolve(UserDir::Cache, env(&vars)),
+            Some(PathBuf::from("/xdg/cache/vvdaw"))
+        );
+    }
+
+    #[test]
+    fn test_home_fallback_when_xdg_missing_or_relative() {
+        let vars = [("HOME", "/home/user"), ("XDG_CONFIG_HOME", "relative")];
+
+        assert_eq!(
+            resolve(UserDir::Config, env(&vars)),
+            Some(PathBuf::from("/home/user/.config/vvdaw"))
+        );
+        assert_eq!(
+            resolve(UserDir::Cache, env(&vars)),
+            Some(PathBuf::from("/home/user/.cache/vvdaw"))
+        );
+    }
+
+    #[test]
+    fn test_no_home_gives_none() {
+        assert_eq!(resolve(UserDir::Config, env(&[])), None);
+        assert_eq!(resolve(UserDir::Cache, env(&[])), None);
+    }
+}
@@ -0,0 +1,173 @@
+//! Musical transport state shared with plugins.
+//!
+//! The engine owns one [`Transport`], advances it after every processed block
+//! and hands it to each node, so tempo-synced plugins (delays, LFOs,
+//! arpeggiators) can follow the host's tempo and position.
+
+use crate::{Frames, SampleRate};
+
+/// Default tempo in beats per minute
+pub const DEFAULT_TEMPO_BPM: f64 = 120.0;
+
+/// Slowest tempo accepted by [`Transport::set_tempo`]
+pub const MIN_TEMPO_BPM: f64 = 1.0;
+
+/// Fastest tempo accepted by [`Transport::set_tempo`]
+pub const MAX_TEMPO_BPM: f64 = 999.0;
+
+/// Tempo, meter and running position of the host's playback
+///
+/// Musical positions are in quarter notes (PPQ), as VST3's `ProcessContext`
+/// and most plugin formats expect. The PPQ position is accumulated block by
+/// block, so tempo changes bend the musical timeline rather than jumping it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transport {
+    /// Sample rate used to convert frames to musical time
+    pub sample_rate: SampleRate,
+
+    /// Tempo in beats (quarter notes) per minute
+    pub tempo_bpm: f64,
+
+    /// Time signature as `(numerator, denominator)`, e.g. `(6, 8)`
+    pub time_signature: (u32, u32),
+
+    /// Whether playback is running
+    pub is_playing: bool,
+
+    /// Playback position in frames since the start of the project
+    pub project_time_samples: u64,
+
+    /// Playback position in quarter notes since the start of the project
+    pub ppq_position: f64,
+}
+
+impl Transport {
+    /// A stopped transport at the start of the project, in 4/4 at 120 BPM
+    #[must_use]
+    pub const fn new(sample_rate: SampleRate) -> Self {
+        Self {
+            sample_rate,
+            tempo_bpm: DEFAULT_TEMPO_BPM,
+            time_signature: (4, 4),
+            is_playing: false,
+            project_time_samples: 0,
+            ppq_position: 0.0,
+        }
+    }
+
+    /// Set the tempo, clamped to [`MIN_TEMPO_BPM`]..=[`MAX_TEMPO_BPM`]
+    ///
+    /// Non-finite values are ignored. The position is kept, so the music
+    /// continues from the current beat at the new tempo.
+    pub const fn set_tempo(&mut self, bpm: f64) {
+        if bpm.is_finite() {
+            self.tempo_bpm = bpm.clamp(MIN_TEMPO_BPM, MAX_TEMPO_BPM);
+        }
+    }
+
+    /// Quarter notes per frame at the current tempo
+    fn ppq_per_frame(&self) -> f64 {
+        if self.sample_rate == 0 {
+            return 0.0;
+        }
+        self.tempo_bpm / 60.0 / f64::from(self.sample_rate)
+    }
+
+    /// Move the position forward by one processed block
+    ///
+    /// Does nothing while stopped.
+    #[allow(clippy::cast_precision_loss)] // Block sizes are far below 2^52
+    pub fn advance(&mut self, frames: Frames) {
+        if !self.is_playing {
+            return;
+        }
+        self.project_time_samples += frames as u64;
+        self.ppq_position += frames as f64 * self.ppq_per_frame();
+    }
+
+    /// Jump to `frame`, deriving the musical position from the current tempo
+    #[allow(clippy::cast_precision_loss)] // Positions are far below 2^52 frames
+    pub fn locate(&mut self, frame: u64) {
+        self.project_time_samples = frame;
+        self.ppq_position = frame as f64 * self.ppq_per_frame();
+    }
+
+    /// Length of one bar in quarter notes (4.0 in 4/4, 3.0 in 6/8)
+    #[must_use]
+    pub fn quarter_notes_per_bar(&self) -> f64 {
+        let (numerator, denominator) = self.time_signature;
+        if denominator == 0 {
+            return 0.0;
+        }
+        f64::from(numerator) * 4.0 / f64::from(denominator)
+    }
+
+    /// Position of the start of the current bar in quarter notes
+    #[must_use]
+    pub fn bar_position_ppq(&self) -> f64 {
+        let bar = self.quarter_notes_per_bar();
+        if bar <= 0.0 {
+            return 0.0;
+        }
+        (self.ppq_position / bar).floor() * bar
+    }
+}
+
+impl Default for Transport {
+    fn default() -> Self {
+        Self::new(crate::sample_rates::SR_48000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advance_only_while_playing() {
+        let mut transport = Transport::new(48000);
+        transport.advance(512);
+        assert_eq!(transport.project_time_samples, 0);
+
+        transport.is_playing = true;
+        transport.advance(24000);
+        assert_eq!(transport.project_time_samples, 24000);
+        // Half a second at 120 BPM is one beat
+        assert!((transport.ppq_position - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_tempo_change_keeps_position() {
+        let mut transport = Transport::new(48000);
+        transport.is_playing = true;
+        transport.advance(48000);
+        transport.set_tempo(60.0);
+        transport.advance(48000);
+        // Two beats at 120 BPM, then one at 60 BPM
+        assert!((transport.ppq_position - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_set_tempo_clamps_and_ignores_nan() {
+        let mut transport = Transport::default();
+        transport.set_tempo(0.0);
+        assert_eq!(transport.tempo_bpm, MIN_TEMPO_BPM);
+        transport.set_tempo(f64::NAN);
+        assert_eq!(transport.tempo_bpm, MIN_TEMPO_BPM);
+        transport.set_tempo(5000.0);
+        assert_eq!(transport.tempo_bpm, MAX_TEMPO_BPM);
+    }
+
+    #[test]
+    fn test_bar_position() {
+        let mut transport = Transport::new(48000);
+        transport.locate(48000 * 3);
+        // 3 seconds at 120 BPM = 6 beats, inside bar 2 of 4/4
+        assert!((transport.ppq_position - 6.0).abs() < 1e-9);
+        assert!((transport.bar_position_ppq() - 4.0).abs() < 1e-9);
+
+        transport.time_signature = (6, 8);
+        assert!((transport.quarter_notes_per_bar() - 3.0).abs() < 1e-9);
+        assert!((transport.bar_position_ppq() - 6.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,358 @@
+//! VST3 ABI definitions used by the fixture.
+//!
+//! Hand-written rather than generated from the SDK headers, so the fixture
+//! builds anywhere. Layouts follow the SDK on 64-bit platforms, and IIDs use
+//! the byte order of the SDK's non-COM-compatible platforms (macOS and
+//! Linux), as vvdaw's host does.
+
+use std::ffi::{c_char, c_void};
+
+/// COM result type (tresult in VST3)
+pub type TResult = i32;
+
+/// `kResultOk` / `kResultTrue`
+pub const K_RESULT_OK: TResult = 0;
+/// `kResultFalse`
+pub const K_RESULT_FALSE: TResult = 1;
+/// `kNoInterface`
+pub const K_NO_INTERFACE: TResult = -1;
+/// `kInvalidArgument`
+pub const K_INVALID_ARGUMENT: TResult = 2;
+/// `kNotImplemented`
+pub const K_NOT_IMPLEMENTED: TResult = 3;
+
+/// A 16-byte interface or class ID (TUID)
+pub type Tuid = [u8; 16];
+
+/// Build a TUID from the four 32-bit words of an SDK `DECLARE_CLASS_IID`
+pub const fn uid(l1: u32, l2: u32, l3: u32, l4: u32) -> Tuid {
+    let [a0, a1, a2, a3] = l1.to_be_bytes();
+    let [b0, b1, b2, b3] = l2.to_be_bytes();
+    let [c0, c1, c2, c3] = l3.to_be_bytes();
+    let [d0, d1, d2, d3] = l4.to_be_bytes();
+    [
+        a0, a1, a2, a3, b0, b1, b2, b3, c0, c1, c2, c3, d0, d1, d2, d3,
+    ]
+}
+
+/// `FUnknown` IID
+pub const FUNKNOWN_IID: Tuid = uid(0x0000_0000, 0x0000_0000, 0xC000_0000, 0x0000_0046);
+/// `IPluginBase` IID
+pub const IPLUGIN_BASE_IID: Tuid = uid(0x2288_8DDB, 0x156E_45AE, 0x8358_B348, 0x0819_0625);
+/// `IPluginFactory` IID
+pub const IPLUGIN_FACTORY_IID: Tuid = uid(0x7A4D_811C, 0x5211_4A1F, 0xAED9_D2EE, 0x0B43_BF9F);
+/// `IPluginFactory2` IID
+pub const IPLUGIN_FACTORY2_IID: Tuid = uid(0x0007_B650, 0xF24B_4C0B, 0xA464_EDB9, 0xF00B_2ABB);
+/// `IComponent` IID
+pub const ICOMPONENT_IID: Tuid = uid(0xE831_FF31, 0xF2D5_4301, 0x928E_BBEE, 0x2569_7802);
+/// `IAudioProcessor` IID
+pub const IAUDIO_PROCESSOR_IID: Tuid = uid(0x4204_3F99, 0xB7DA_453C, 0xA569_E79D, 0x9AAE_C33D);
+/// `IEditController` IID
+pub const IEDIT_CONTROLLER_IID: Tuid = uid(0xDCD7_BBE3, 0x7742_448D, 0xA874_AACC, 0x979C_759E);
+
+/// `MediaTypes::kAudio`
+pub const MEDIA_AUDIO: i32 = 0;
+/// `BusDirections::kInput`
+pub const DIRECTION_INPUT: i32 = 0;
+/// `BusDirections::kOutput`
+pub const DIRECTION_OUTPUT: i32 = 1;
+/// `BusTypes::kMain`
+pub const BUS_MAIN: i32 = 0;
+/// `BusTypes::kAux`
+pub const BUS_AUX: i32 = 1;
+/// `BusInfo::kDefaultActive`
+pub const BUS_DEFAULT_ACTIVE: u32 = 1;
+
+/// `SpeakerArr::kStereo` (left and right)
+pub const SPEAKERS_STEREO: u64 = 0b11;
+
+/// `SymbolicSampleSizes::kSample32`
+pub const SAMPLE_32: i32 = 0;
+/// `SymbolicSampleSizes::kSample64`
+pub const SAMPLE_64: i32 = 1;
+
+/// `ParameterInfo::kCanAutomate`
+pub const PARAM_CAN_AUTOMATE: i32 = 1;
+/// `ParameterInfo::kIsBypass`
+pub const PARAM_IS_BYPASS: i32 = 1 << 16;
+
+/// `PClassInfo::kManyInstances`
+pub const MANY_INSTANCES: i32 = 0x7FFF_FFFF;
+
+/// Length of a VST3 `String128` in UTF-16 code units (including the NUL)
+pub const STRING128_LEN: usize = 128;
+
+/// VST3 `PFactoryInfo` structure
+#[repr(C)]
+pub struct PFactoryInfo {
+    pub vendor: [c_char; 64],
+    pub url: [c_char; 256],
+    pub email: [c_char; 128],
+    pub flags: i32,
+}
+
+/// VST3 `PClassInfo` structure
+#[repr(C)]
+pub struct PClassInfo {
+    pub cid: Tuid,
+    pub cardinality: i32,
+    pub category: [c_char; 32],
+    pub name: [c_char; 64],
+}
+
+/// VST3 `PClassInfo2` structure
+#[repr(C)]
+pub struct PClassInfo2 {
+    pub cid: Tuid,
+    pub cardinality: i32,
+    pub category: [c_char; 32],
+    pub name: [c_char; 64],
+    pub class_flags: u32,
+    pub sub_categories: [c_char; 128],
+    pub vendor: [c_char; 64],
+    pub version: [c_char; 64],
+    pub sdk_version: [c_char; 64],
+}
+
+/// VST3 `BusInfo` structure
+#[repr(C)]
+pub struct BusInfo {
+    pub media_type: i32,
+    pub direction: i32,
+    pub channel_count: i32,
+    pub name: [u16; STRING128_LEN],
+    pub bus_type: i32,
+    pub flags: u32,
+}
+
+/// VST3 `ParameterInfo` structure
+#[repr(C)]
+pub struct ParameterInfo {
+    pub id: u32,
+    pub title: [u16; STRING128_LEN],
+    pub short_title: [u16; STRING128_LEN],
+    pub units: [u16; STRING128_LEN],
+    pub step_count: i32,
+    pub default_normalized_value: f64,
+    pub unit_id: i32,
+    pub flags: i32,
+}
+
+/// VST3 `ProcessSetup` structure
+#[repr(C)]
+pub struct ProcessSetup {
+    pub process_mode: i32,
+    pub symbolic_sample_size: i32,
+    pub max_samples_per_block: i32,
+    pub sample_rate: f64,
+}
+
+/// VST3 `AudioBusBuffers` structure
+///
+/// The channel buffers are a union in the SDK; which member is valid depends
+/// on the `ProcessData` sample size.
+#[repr(C)]
+pub struct AudioBusBuffers {
+    pub num_channels: i32,
+    pub silence_flags: u64,
+    pub channel_buffers: *mut *mut c_void,
+}
+
+/// VST3 `ProcessData` structure
+#[repr(C)]
+pub struct ProcessData {
+    pub process_mode: i32,
+    pub symbolic_sample_size: i32,
+    pub num_samples: i32,
+    pub num_inputs: i32,
+    pub num_outputs: i32,
+    pub inputs: *mut AudioBusBuffers,
+    pub outputs: *mut AudioBusBuffers,
+    pub input_param_changes: *mut c_void,
+    pub output_param_changes: *mut c_void,
+    pub input_events: *mut c_void,
+    pub output_events: *mut c_void,
+    pub process_context: *mut c_void,
+}
+
+/// Copy `text` into a fixed-size C string field, truncating if needed
+pub fn copy_c_string(field: &mut [c_char], text: &str) {
+    field.fill(0);
+    let len = text.len().min(field.len() - 1);
+    for (dst, &src) in field.iter_mut().zip(&text.as_bytes()[..len]) {
+        *dst = src as c_char;
+    }
+}
+
+/// Copy `text` into a fixed-size UTF-16 field (such as a `String128`), truncating if needed
+pub fn copy_utf16(field: &mut [u16], text: &str) {
+    field.fill(0);
+    let len = field.len() - 1;
+    for (dst, src) in field[..len].iter_mut().zip(text.encode_utf16()) {
+        *dst = src;
+    }
+}
+
+/// Read a NUL-terminated UTF-16 string written by the host
+///
+/// # Safety
+///
+/// `text` must be null or point to a NUL-terminated UTF-16 string.
+pub unsafe fn read_utf16(text: *const u16) -> Option<String> {
+    if text.is_null() {
+        return None;
+    }
+
+    let mut units = Vec::new();
+    for index in 0.. {
+        let unit = unsafe { *text.add(index) };
+        if unit == 0 {
+            break;
+        }
+        units.push(unit);
+    }
+    String::from_utf16(&units).ok()
+}
+
+/// Call `IBStream::read(buffer, numBytes, numBytesRead)`
+///
+/// Returns the number of bytes read, or `None` if the call failed.
+///
+/// # Safety
+///
+/// `stream` must be a valid `IBStream` pointer.
+pub unsafe fn stream_read(stream: *mut c_void, buffer: &mut [u8]) -> Option<usize> {
+    type ReadFn = unsafe extern "C" fn(*mut c_void, *mut c_void, i32, *mut i32) -> TResult;
+
+    unsafe {
+        // read is at vtable[3] (after queryInterface, addRef, release)
+        let vtable = *stream.cast::<*const *const c_void>();
+        let read: ReadFn = std::mem::transmute(*vtable.add(3));
+
+        let mut bytes_read = 0;
+        let result = read(
+            stream,
+            buffer.as_mut_ptr().cast(),
+            buffer.len() as i32,
+            &raw mut bytes_read,
+        );
+        (result == K_RESULT_OK).then_some(bytes_read.max(0) as usize)
+    }
+}
+
+/// Call `IBStream::write(buffer, numBytes, numBytesWritten)`
+///
+/// Returns whether every byte was written.
+///
+/// # Safety
+///
+/// `stream` must be a valid `IBStream` pointer.
+pub unsafe fn stream_write(stream: *mut c_void, buffer: &[u8]) -> bool {
+    type WriteFn = unsafe extern "C" fn(*mut c_void, *const c_void, i32, *mut i32) -> TResult;
+
+    unsafe {
+        // write is at vtable[4] (after read)
+        let vtable = *stream.cast::<*const *const c_void>();
+        let write: WriteFn = std::mem::transmute(*vtable.add(4));
+
+        let mut bytes_written = 0;
+        let result = write(
+            stream,
+            buffer.as_ptr().cast(),
+            buffer.len() as i32,
+            &raw mut bytes_written,
+        );
+        result == K_RESULT_OK && bytes_written == buffer.len() as i32
+    }
+}
+
+/// Visit the last value of every parameter in an `IParameterChanges`
+///
+/// The fixture applies changes per block rather than sample-accurately, so
+/// only the final point of each queue matters.
+///
+/// # Safety
+///
+/// `changes` must be null or a valid `IParameterChanges` pointer.
+pub unsafe fn for_each_last_change(changes: *mut c_void, mut f: impl FnMut(u32, f64)) {
+    type GetParameterCountFn = unsafe extern "C" fn(*mut c_void) -> i32;
+    type GetParameterDataFn = unsafe extern "C" fn(*mut c_void, i32) -> *mut c_void;
+    type GetParameterIdFn = unsafe extern "C" fn(*mut c_void) -> u32;
+    type GetPointCountFn = unsafe extern "C" fn(*mut c_void) -> i32;
+    type GetPointFn = unsafe extern "C" fn(*mut c_void, i32, *mut i32, *mut f64) -> TResult;
+
+    if changes.is_null() {
+        return;
+    }
+
+    unsafe {
+        // IParameterChanges: getParameterCount at vtable[3], getParameterData at vtable[4]
+        let vtable = *changes.cast::<*const *const c_void>();
+        let get_parameter_count: GetParameterCountFn = std::mem::transmute(*vtable.add(3));
+        let get_parameter_data: GetParameterDataFn = std::mem::transmute(*vtable.add(4));
+
+        for index in 0..get_parameter_count(changes) {
+            let queue = get_parameter_data(changes, index);
+            if queue.is_null() {
+                continue;
+            }
+
+            // IParamValueQueue: getParameterId, getPointCount, getPoint at vtable[3..=5]
+            let vtable = *queue.cast::<*const *const c_void>();
+            let get_parameter_id: GetParameterIdFn = std::mem::transmute(*vtable.add(3));
+            let get_point_count: GetPointCountFn = std::mem::transmute(*vtable.add(4));
+            let get_point: GetPointFn = std::mem::transmute(*vtable.add(5));
+
+            let points = get_point_count(queue);
+            if points <= 0 {
+                continue;
+            }
+
+            let mut sample_offset = 0;
+            let mut value = 0.0;
+            if get_point(queue, points - 1, &raw mut sample_offset, &raw mut value) == K_RESULT_OK {
+                f(get_parameter_id(queue), value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uid_matches_sdk_byte_order() {
+        // FUID E831FF31-F2D54301-928EBBEE-25697802, as the host spells it out
+        assert_eq!(
+            ICOMPONENT_IID,
+            [
+                0xE8, 0x31, 0xFF, 0x31, 0xF2, 0xD5, 0x43, 0x01, 0x92, 0x8E, 0xBB, 0xEE, 0x25, 0x69,
+                0x78, 0x02,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_layouts_match_sdk() {
+        // sizeof() of the SDK structs on 64-bit platforms
+        assert_eq!(std::mem::size_of::<AudioBusBuffers>(), 24);
+        assert_eq!(std::mem::size_of::<ProcessData>(), 80);
+        assert_eq!(std::mem::size_of::<ProcessSetup>(), 24);
+        assert_eq!(std::mem::size_of::<BusInfo>(), 276);
+        assert_eq!(std::mem::size_of::<ParameterInfo>(), 792);
+        assert_eq!(std::mem::size_of::<PClassInfo2>(), 440);
+    }
+
+    #[test]
+    fn test_strings_are_truncated_and_terminated() {
+        let mut field = [1 as c_char; 4];
+        copy_c_string(&mut field, "vvdaw");
+        assert_eq!(field, [b'v' as c_char, b'v' as c_char, b'd' as c_char, 0]);
+
+        let mut field = [1_u16; 4];
+        copy_utf16(&mut field, "On");
+        assert_eq!(field, [u16::from(b'O'), u16::from(b'n'), 0, 0]);
+        assert_eq!(unsafe { read_utf16(field.as_ptr()) }.as_deref(), Some("On"));
+    }
+}
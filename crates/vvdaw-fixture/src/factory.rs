@@ -0,0 +1,242 @@
+//! `IPluginFactory2` implementation.
+//!
+//! Exports a single audio effect class. The factory is a static object:
+//! reference counting is a no-op, and it stays valid for as long as the
+//! library is loaded.
+
+use crate::com::{
+    FUNKNOWN_IID, IPLUGIN_FACTORY_IID, IPLUGIN_FACTORY2_IID, K_INVALID_ARGUMENT, K_NO_INTERFACE,
+    K_RESULT_OK, MANY_INSTANCES, PClassInfo, PClassInfo2, PFactoryInfo, TResult, Tuid,
+    copy_c_string, uid,
+};
+use crate::plugin::Fixture;
+use std::ffi::c_void;
+
+/// Class ID of the fixture ("vvdaw" "Fixt" "ure" 1)
+pub const CLASS_ID: Tuid = uid(0x7676_6461, 0x7746_6978, 0x7475_7265, 0x0000_0001);
+
+/// Category of audio processor classes (`kVstAudioEffectClass`)
+const AUDIO_MODULE_CATEGORY: &str = "Audio Module Class";
+
+/// Subcategory string reported through `PClassInfo2`
+const SUB_CATEGORIES: &str = "Fx";
+
+/// Version reported through `PClassInfo2`
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// SDK version reported through `PClassInfo2`
+const SDK_VERSION: &str = "VST 3.7.0";
+
+/// The plugin factory object
+#[repr(C)]
+struct Factory {
+    /// COM vtable pointer (must be first field)
+    vtable: &'static FactoryVTable,
+}
+
+/// `IPluginFactory2` vtable structure
+#[repr(C)]
+struct FactoryVTable {
+    // FUnknown methods
+    query_interface:
+        unsafe extern "C" fn(this: *mut c_void, iid: *const Tuid, obj: *mut *mut c_void) -> TResult,
+    add_ref: unsafe extern "C" fn(this: *mut c_void) -> u32,
+    release: unsafe extern "C" fn(this: *mut c_void) -> u32,
+
+    // IPluginFactory methods
+    get_factory_info: unsafe extern "C" fn(this: *mut c_void, info: *mut PFactoryInfo) -> TResult,
+    count_classes: unsafe extern "C" fn(this: *mut c_void) -> i32,
+    get_class_info:
+        unsafe extern "C" fn(this: *mut c_void, index: i32, info: *mut PClassInfo) -> TResult,
+    create_instance: unsafe extern "C" fn(
+        this: *mut c_void,
+        cid: *const Tuid,
+        iid: *const Tuid,
+        obj: *mut *mut c_void,
+    ) -> TResult,
+
+    // IPluginFactory2 methods
+    get_class_info2:
+        unsafe extern "C" fn(this: *mut c_void, index: i32, info: *mut PClassInfo2) -> TResult,
+}
+
+/// Static vtable instance
+static VTABLE: FactoryVTable = FactoryVTable {
+    query_interface,
+    add_ref,
+    release,
+    get_factory_info,
+    count_classes,
+    get_class_info,
+    create_instance,
+    get_class_info2,
+};
+
+/// The one factory instance
+static FACTORY: Factory = Factory { vtable: &VTABLE };
+
+/// Pointer to the factory, as returned by `GetPluginFactory`
+pub fn factory_ptr() -> *mut c_void {
+    (&raw const FACTORY).cast_mut().cast()
+}
+
+// COM vtable implementations
+
+unsafe extern "C" fn query_interface(
+    this: *mut c_void,
+    iid: *const Tuid,
+    obj: *mut *mut c_void,
+) -> TResult {
+    if iid.is_null() || obj.is_null() {
+        return K_INVALID_ARGUMENT;
+    }
+
+    unsafe {
+        let iid = *iid;
+        if iid == FUNKNOWN_IID || iid == IPLUGIN_FACTORY_IID || iid == IPLUGIN_FACTORY2_IID {
+            *obj = this;
+            K_RESULT_OK
+        } else {
+            *obj = std::ptr::null_mut();
+            K_NO_INTERFACE
+        }
+    }
+}
+
+unsafe extern "C" fn add_ref(_this: *mut c_void) -> u32 {
+    1
+}
+
+unsafe extern "C" fn release(_this: *mut c_void) -> u32 {
+    1
+}
+
+unsafe extern "C" fn get_factory_info(_this: *mut c_void, info: *mut PFactoryInfo) -> TResult {
+    if info.is_null() {
+        return K_INVALID_ARGUMENT;
+    }
+
+    let info = unsafe { &mut *info };
+    copy_c_string(&mut info.vendor, crate::VENDOR);
+    copy_c_string(&mut info.url, env!("CARGO_PKG_REPOSITORY"));
+    copy_c_string(&mut info.email, "");
+    info.flags = 0;
+    K_RESULT_OK
+}
+
+unsafe extern "C" fn count_classes(_this: *mut c_void) -> i32 {
+    1
+}
+
+unsafe extern "C" fn get_class_info(
+    _this: *mut c_void,
+    index: i32,
+    info: *mut PClassInfo,
+) -> TResult {
+    if index != 0 || info.is_null() {
+        return K_INVALID_ARGUMENT;
+    }
+
+    let info = unsafe { &mut *info };
+    info.cid = CLASS_ID;
+    info.cardinality = MANY_INSTANCES;
+    copy_c_string(&mut info.category, AUDIO_MODULE_CATEGORY);
+    copy_c_string(&mut info.name, crate::NAME);
+    K_RESULT_OK
+}
+
+unsafe extern "C" fn get_class_info2(
+    _this: *mut c_void,
+    index: i32,
+    info: *mut PClassInfo2,
+) -> TResult {
+    if index != 0 || info.is_null() {
+        return K_INVALID_ARGUMENT;
+    }
+
+    let info = unsafe { &mut *info };
+    info.cid = CLASS_ID;
+    info.cardinality = MANY_INSTANCES;
+    copy_c_string(&mut info.category, AUDIO_MODULE_CATEGORY);
+    copy_c_string(&mut info.name, crate::NAME);
+    info.class_flags = 0;
+    copy_c_string(&mut info.sub_categories, SUB_CATEGORIES);
+    copy_c_string(&mut info.vendor, crate::VENDOR);
+    copy_c_string(&mut info.version, VERSION);
+    copy_c_string(&mut info.sdk_version, SDK_VERSION);
+    K_RESULT_OK
+}
+
+unsafe extern "C" fn create_instance(
+    _this: *mut c_void,
+    cid: *const Tuid,
+    iid: *const Tuid,
+    obj: *mut *mut c_void,
+) -> TResult {
+    if cid.is_null() || iid.is_null() || obj.is_null() {
+        return K_INVALID_ARGUMENT;
+    }
+
+    unsafe {
+        *obj = std::ptr::null_mut();
+        if *cid != CLASS_ID {
+            return K_NO_INTERFACE;
+        }
+
+        // The new object starts with one reference, which the query below
+        // hands to the caller on success (or frees on failure)
+        let fixture = Fixture::create();
+        let result = Fixture::query(fixture, &*iid, obj);
+        Fixture::release_ref(fixture);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::com::ICOMPONENT_IID;
+
+    #[test]
+    fn test_factory_describes_one_effect_class() {
+        let this = factory_ptr();
+        unsafe {
+            assert_eq!(count_classes(this), 1);
+
+            let mut info: PClassInfo2 = std::mem::zeroed();
+            assert_eq!(get_class_info2(this, 0, &raw mut info), K_RESULT_OK);
+            assert_eq!(info.cid, CLASS_ID);
+            let name: Vec<u8> = info
+                .name
+                .iter()
+                .take_while(|&&c| c != 0)
+                .map(|&c| c as u8)
+                .collect();
+            assert_eq!(name, crate::NAME.as_bytes());
+
+            assert_eq!(get_class_info2(this, 1, &raw mut info), K_INVALID_ARGUMENT);
+        }
+    }
+
+    #[test]
+    fn test_create_instance_checks_class_id() {
+        let this = factory_ptr();
+        let mut obj = std::ptr::null_mut();
+        unsafe {
+            let unknown = [0; 16];
+            assert_eq!(
+                create_instance(this, &raw const unknown, &ICOMPONENT_IID, &raw mut obj),
+                K_NO_INTERFACE
+            );
+            assert!(obj.is_null());
+
+            assert_eq!(
+                create_instance(this, &CLASS_ID, &ICOMPONENT_IID, &raw mut obj),
+                K_RESULT_OK
+            );
+            assert!(!obj.is_null());
+            // The IComponent pointer is the object itself
+            Fixture::release_ref(obj.cast());
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! Deterministic VST3 test plugin for vvdaw's host tests.
+//!
+//! `vvdaw-vst3`'s integration tests build this crate and bundle it as
+//! `vvdaw-fixture.vst3` (see its `test_fixtures` module), so they always have
+//! a plugin with known behaviour to load. The contract they assert against is
+//! in this crate's README.
+//!
+//! The plugin is a single object implementing `IComponent`,
+//! `IAudioProcessor` and `IEditController`, written against hand-written
+//! vtables instead of the SDK so it builds without it. It is not meant to be
+//! installed or used in a session.
+
+// VST3 entry points have fixed C names
+#![allow(non_snake_case)]
+
+mod com;
+mod factory;
+mod plugin;
+
+use std::ffi::c_void;
+
+/// Class name reported by the factory
+pub const NAME: &str = "vvdaw Fixture";
+
+/// Vendor reported by the factory
+pub const VENDOR: &str = "vvdaw";
+
+/// Processing latency reported by `IAudioProcessor::getLatencySamples`
+pub const LATENCY_SAMPLES: usize = 64;
+
+/// Tail reported by `IAudioProcessor::getTailSamples`
+pub const TAIL_SAMPLES: u32 = 4800;
+
+/// VST3 entry point: the plugin factory
+///
+/// The factory is a static singleton, so the reference the host releases
+/// never frees it.
+#[unsafe(no_mangle)]
+pub extern "C" fn GetPluginFactory() -> *mut c_void {
+    factory::factory_ptr()
+}
+
+/// Linux module entry point (nothing to set up)
+#[cfg(target_os = "linux")]
+#[unsafe(no_mangle)]
+pub extern "C" fn ModuleEntry(_shared_library_handle: *mut c_void) -> bool {
+    true
+}
+
+/// Linux module exit point
+#[cfg(target_os = "linux")]
+#[unsafe(no_mangle)]
+pub extern "C" fn ModuleExit() -> bool {
+    true
+}
+
+/// macOS bundle entry point (nothing to set up)
+#[cfg(target_os = "macos")]
+#[unsafe(no_mangle)]
+pub extern "C" fn bundleEntry(_bundle: *mut c_void) -> bool {
+    true
+}
+
+/// macOS bundle exit point
+#[cfg(target_os = "macos")]
+#[unsafe(no_mangle)]
+pub extern "C" fn bundleExit() -> bool {
+    true
+}
+
+/// Windows DLL entry point (nothing to set up)
+#[cfg(target_os = "windows")]
+#[unsafe(no_mangle)]
+pub extern "C" fn InitDll() -> bool {
+    true
+}
+
+/// Windows DLL exit point
+#[cfg(target_os = "windows")]
+#[unsafe(no_mangle)]
+pub extern "C" fn ExitDll() -> bool {
+    true
+}
@@ -0,0 +1,1131 @@
+//! The fixture plugin: `IComponent`, `IAudioProcessor` and `IEditController`
+//! on one object.
+//!
+//! Like the SDK's single-component effects, the object has one vtable
+//! pointer per interface; each method finds the object from its interface
+//! pointer by subtracting that pointer's offset. The controller and the
+//! processor keep separate parameter values, as in a split plugin, so edits
+//! only reach processing through the host's parameter changes (or state).
+//!
+//! Processing delays the main input by [`LATENCY_SAMPLES`] and scales it by
+//! `1 - Mix + Mix × Gain`, or passes it through (still delayed) when
+//! bypassed. The sidechain is accepted but not used.
+
+use crate::com::{
+    AudioBusBuffers, BUS_AUX, BUS_DEFAULT_ACTIVE, BUS_MAIN, BusInfo, DIRECTION_INPUT,
+    DIRECTION_OUTPUT, FUNKNOWN_IID, IAUDIO_PROCESSOR_IID, ICOMPONENT_IID, IEDIT_CONTROLLER_IID,
+    IPLUGIN_BASE_IID, K_INVALID_ARGUMENT, K_NO_INTERFACE, K_NOT_IMPLEMENTED, K_RESULT_FALSE,
+    K_RESULT_OK, MEDIA_AUDIO, PARAM_CAN_AUTOMATE, PARAM_IS_BYPASS, ParameterInfo, ProcessData,
+    ProcessSetup, SAMPLE_32, SAMPLE_64, SPEAKERS_STEREO, STRING128_LEN, TResult, Tuid, copy_utf16,
+    for_each_last_change, read_utf16, stream_read, stream_write,
+};
+use crate::{LATENCY_SAMPLES, TAIL_SAMPLES};
+use std::ffi::{c_char, c_void};
+use std::mem::offset_of;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+/// Channels of every bus
+const CHANNELS: usize = 2;
+
+/// Gain parameter ID (linear gain, 0.0-1.0)
+const GAIN: u32 = 0;
+/// Mix parameter ID (wet share, 0.0-1.0)
+const MIX: u32 = 1;
+/// Bypass parameter ID (stepped: off or on)
+const BYPASS: u32 = 2;
+
+/// A parameter the controller declares
+struct Parameter {
+    title: &'static str,
+    units: &'static str,
+    step_count: i32,
+    default: f64,
+    flags: i32,
+}
+
+/// Declared parameters, indexed by ID
+const PARAMETERS: [Parameter; 3] = [
+    Parameter {
+        title: "Gain",
+        units: "",
+        step_count: 0,
+        default: 0.5,
+        flags: PARAM_CAN_AUTOMATE,
+    },
+    Parameter {
+        title: "Mix",
+        units: "%",
+        step_count: 0,
+        default: 1.0,
+        flags: PARAM_CAN_AUTOMATE,
+    },
+    Parameter {
+        title: "Bypass",
+        units: "",
+        step_count: 1,
+        default: 0.0,
+        flags: PARAM_CAN_AUTOMATE | PARAM_IS_BYPASS,
+    },
+];
+
+/// An audio bus the component declares (all stereo)
+struct Bus {
+    name: &'static str,
+    kind: i32,
+    flags: u32,
+}
+
+/// Input buses: the main input and a sidechain
+const INPUT_BUSES: [Bus; 2] = [
+    Bus {
+        name: "Main In",
+        kind: BUS_MAIN,
+        flags: BUS_DEFAULT_ACTIVE,
+    },
+    Bus {
+        name: "Sidechain",
+        kind: BUS_AUX,
+        flags: 0,
+    },
+];
+
+/// Output buses: the main output
+const OUTPUT_BUSES: [Bus; 1] = [Bus {
+    name: "Main Out",
+    kind: BUS_MAIN,
+    flags: BUS_DEFAULT_ACTIVE,
+}];
+
+/// Audio buses in one direction (`None` for an invalid direction)
+fn buses(direction: i32) -> Option<&'static [Bus]> {
+    match direction {
+        DIRECTION_INPUT => Some(&INPUT_BUSES),
+        DIRECTION_OUTPUT => Some(&OUTPUT_BUSES),
+        _ => None,
+    }
+}
+
+/// Bit of a bus in the active bus mask
+fn bus_bit(direction: i32, index: i32) -> u32 {
+    1 << (direction * 8 + index)
+}
+
+/// Start of the component state written by `IComponent::getState`
+const STATE_MAGIC: &[u8; 4] = b"VVFX";
+
+/// Length of the component state: the magic, then each parameter as a little-endian f64
+const STATE_LEN: usize = STATE_MAGIC.len() + PARAMETERS.len() * 8;
+
+/// Normalized parameter values, shared between threads
+struct ParameterValues([AtomicU64; PARAMETERS.len()]);
+
+impl ParameterValues {
+    /// Every parameter at its default
+    fn defaults() -> Self {
+        Self(std::array::from_fn(|id| {
+            AtomicU64::new(PARAMETERS[id].default.to_bits())
+        }))
+    }
+
+    /// Value of parameter `id`, if it exists
+    fn get(&self, id: u32) -> Option<f64> {
+        let value = self.0.get(id as usize)?;
+        Some(f64::from_bits(value.load(Ordering::Relaxed)))
+    }
+
+    /// Set parameter `id`, clamped to 0.0-1.0
+    ///
+    /// Returns `false` if there is no such parameter.
+    fn set(&self, id: u32, value: f64) -> bool {
+        let Some(slot) = self.0.get(id as usize) else {
+            return false;
+        };
+        slot.store(value.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+        true
+    }
+
+    /// Linear factor applied to the delayed input
+    fn output_gain(&self) -> f64 {
+        let gain = self.get(GAIN).unwrap_or_default();
+        let mix = self.get(MIX).unwrap_or_default();
+        if self.get(BYPASS).unwrap_or_default() >= 0.5 {
+            1.0
+        } else {
+            mix.mul_add(gain, 1.0 - mix)
+        }
+    }
+
+    /// Encode the values as component state
+    fn to_state(&self) -> [u8; STATE_LEN] {
+        let mut state = [0; STATE_LEN];
+        state[..STATE_MAGIC.len()].copy_from_slice(STATE_MAGIC);
+        for (id, chunk) in state[STATE_MAGIC.len()..].chunks_exact_mut(8).enumerate() {
+            let value = self.get(id as u32).unwrap_or_default();
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+        state
+    }
+
+    /// Restore the values from component state
+    ///
+    /// Returns `false` (leaving the values alone) if `state` isn't fixture state.
+    fn load_state(&self, state: &[u8]) -> bool {
+        let Some(values) = state.strip_prefix(STATE_MAGIC) else {
+            return false;
+        };
+        if values.len() != PARAMETERS.len() * 8 {
+            return false;
+        }
+
+        for (id, chunk) in values.chunks_exact(8).enumerate() {
+            let bytes = chunk.try_into().unwrap_or_default();
+            self.set(id as u32, f64::from_le_bytes(bytes));
+        }
+        true
+    }
+}
+
+/// Text shown for a normalized parameter value
+fn display(id: u32, value: f64) -> Option<String> {
+    match id {
+        GAIN => Some(format!("{value:.2}")),
+        MIX => Some(format!("{:.0}", value * 100.0)),
+        BYPASS => Some(if value >= 0.5 { "On" } else { "Off" }.to_string()),
+        _ => None,
+    }
+}
+
+/// Parse text (as produced by [`display`]) into a normalized value
+fn parse(id: u32, text: &str) -> Option<f64> {
+    let text = text.trim();
+    let value = match id {
+        GAIN => text.parse::<f64>().ok()?,
+        MIX => text.parse::<f64>().ok()? / 100.0,
+        BYPASS if text.eq_ignore_ascii_case("on") => 1.0,
+        BYPASS if text.eq_ignore_ascii_case("off") => 0.0,
+        _ => return None,
+    };
+    value.is_finite().then(|| value.clamp(0.0, 1.0))
+}
+
+/// Plain (display unit) value of a normalized value
+fn to_plain(id: u32, value: f64) -> f64 {
+    match id {
+        MIX => value * 100.0,
+        BYPASS => value.round(),
+        _ => value,
+    }
+}
+
+/// Normalized value of a plain (display unit) value
+fn to_normalized(id: u32, plain: f64) -> f64 {
+    match id {
+        MIX => plain / 100.0,
+        BYPASS => plain.round(),
+        _ => plain,
+    }
+}
+
+/// Delay line holding the last [`LATENCY_SAMPLES`] frames of the main input
+struct DelayLine {
+    frames: [[f64; CHANNELS]; LATENCY_SAMPLES],
+    position: usize,
+}
+
+impl DelayLine {
+    /// A delay line full of silence
+    const fn new() -> Self {
+        Self {
+            frames: [[0.0; CHANNELS]; LATENCY_SAMPLES],
+            position: 0,
+        }
+    }
+
+    /// Push one input frame, returning the frame pushed `LATENCY_SAMPLES` ago
+    fn push(&mut self, frame: [f64; CHANNELS]) -> [f64; CHANNELS] {
+        let delayed = std::mem::replace(&mut self.frames[self.position], frame);
+        self.position = (self.position + 1) % LATENCY_SAMPLES;
+        delayed
+    }
+}
+
+/// A sample type the processor handles (32- or 64-bit float)
+trait Sample: Copy {
+    fn to_f64(self) -> f64;
+    fn from_f64(value: f64) -> Self;
+}
+
+impl Sample for f32 {
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as Self
+    }
+}
+
+impl Sample for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+/// Run the delay and gain over one block
+///
+/// Each input sample is read before the output sample at the same position
+/// is written, so in-place buffers work.
+///
+/// # Safety
+///
+/// `input` (if not null) and `output` must point to [`CHANNELS`] channel
+/// pointers of at least `frames` samples of type `S`.
+unsafe fn process_block<S: Sample>(
+    delay: &mut DelayLine,
+    input: *mut *mut c_void,
+    output: *mut *mut c_void,
+    frames: usize,
+    gain: f64,
+) {
+    unsafe {
+        let input = input.cast::<*mut S>();
+        let output = output.cast::<*mut S>();
+        for frame in 0..frames {
+            let dry = std::array::from_fn(|channel| {
+                if input.is_null() {
+                    0.0
+                } else {
+                    (*(*input.add(channel)).add(frame)).to_f64()
+                }
+            });
+            let delayed = delay.push(dry);
+            for (channel, sample) in delayed.into_iter().enumerate() {
+                *(*output.add(channel)).add(frame) = S::from_f64(sample * gain);
+            }
+        }
+    }
+}
+
+/// The plugin object
+#[repr(C)]
+pub struct Fixture {
+    /// `IComponent` (and `FUnknown`/`IPluginBase`) vtable pointer (must be first field)
+    component: &'static ComponentVTable,
+    /// `IAudioProcessor` vtable pointer
+    processor: &'static ProcessorVTable,
+    /// `IEditController` vtable pointer
+    controller: &'static EditControllerVTable,
+
+    /// Reference count for COM lifetime management
+    ref_count: AtomicU32,
+
+    /// Parameter values as the edit controller sees them
+    controller_values: ParameterValues,
+    /// Parameter values processing uses
+    processor_values: ParameterValues,
+
+    /// Active audio buses, one bit per bus (see [`bus_bit`])
+    active_buses: AtomicU32,
+
+    /// Processing state
+    ///
+    /// Only `setActive` (before processing starts) and `process` lock it, so
+    /// the audio thread never waits.
+    delay: Mutex<DelayLine>,
+}
+
+/// Offset of the `IComponent` interface pointer in [`Fixture`]
+const COMPONENT: usize = offset_of!(Fixture, component);
+/// Offset of the `IAudioProcessor` interface pointer in [`Fixture`]
+const PROCESSOR: usize = offset_of!(Fixture, processor);
+/// Offset of the `IEditController` interface pointer in [`Fixture`]
+const CONTROLLER: usize = offset_of!(Fixture, controller);
+
+impl Fixture {
+    /// Allocate a fixture with one reference, returned as its `IComponent` pointer
+    pub fn create() -> *mut Self {
+        let fixture = Self {
+            component: &COMPONENT_VTABLE,
+            processor: &PROCESSOR_VTABLE,
+            controller: &EDIT_CONTROLLER_VTABLE,
+            ref_count: AtomicU32::new(1),
+            controller_values: ParameterValues::defaults(),
+            processor_values: ParameterValues::defaults(),
+            active_buses: AtomicU32::new(
+                bus_bit(DIRECTION_INPUT, 0) | bus_bit(DIRECTION_OUTPUT, 0),
+            ),
+            delay: Mutex::new(DelayLine::new()),
+        };
+        Box::into_raw(Box::new(fixture))
+    }
+
+    /// `FUnknown::queryInterface` for every interface of the object
+    ///
+    /// # Safety
+    ///
+    /// `fixture` must be a live fixture and `obj` writable.
+    pub unsafe fn query(fixture: *mut Self, iid: &Tuid, obj: *mut *mut c_void) -> TResult {
+        let offset = match *iid {
+            FUNKNOWN_IID | IPLUGIN_BASE_IID | ICOMPONENT_IID => COMPONENT,
+            IAUDIO_PROCESSOR_IID => PROCESSOR,
+            IEDIT_CONTROLLER_IID => CONTROLLER,
+            _ => {
+                unsafe { *obj = std::ptr::null_mut() };
+                return K_NO_INTERFACE;
+            }
+        };
+
+        unsafe {
+            (*fixture).ref_count.fetch_add(1, Ordering::Relaxed);
+            *obj = fixture.byte_add(offset).cast();
+        }
+        K_RESULT_OK
+    }
+
+    /// `FUnknown::release`, freeing the object with the last reference
+    ///
+    /// # Safety
+    ///
+    /// `fixture` must be a live fixture the caller holds a reference to.
+    pub unsafe fn release_ref(fixture: *mut Self) -> u32 {
+        let previous = unsafe { (*fixture).ref_count.fetch_sub(1, Ordering::Release) };
+        if previous == 1 {
+            std::sync::atomic::fence(Ordering::Acquire);
+            drop(unsafe { Box::from_raw(fixture) });
+            0
+        } else {
+            previous - 1
+        }
+    }
+
+    /// Whether the host's bus buffers match the active buses
+    ///
+    /// Every active bus must come with its stereo channels. This is what
+    /// notices a host laying out `AudioBusBuffers` differently from the SDK.
+    ///
+    /// # Safety
+    ///
+    /// `buffers` must point to `count` bus buffers (or be null if `count` is 0).
+    unsafe fn buses_match(
+        &self,
+        direction: i32,
+        buffers: *const AudioBusBuffers,
+        count: i32,
+    ) -> bool {
+        let declared = buses(direction).map_or(0, <[Bus]>::len);
+        if count < 0 || count as usize > declared || (count > 0 && buffers.is_null()) {
+            return false;
+        }
+
+        let active = self.active_buses.load(Ordering::Relaxed);
+        (0..count).all(|index| {
+            let bus = unsafe { &*buffers.add(index as usize) };
+            if active & bus_bit(direction, index) == 0 {
+                bus.num_channels == 0 || bus.num_channels == CHANNELS as i32
+            } else {
+                bus.num_channels == CHANNELS as i32 && !bus.channel_buffers.is_null()
+            }
+        })
+    }
+}
+
+/// The object an interface pointer at `OFFSET` belongs to
+fn object<const OFFSET: usize>(this: *mut c_void) -> *mut Fixture {
+    this.wrapping_byte_sub(OFFSET).cast()
+}
+
+/// The fixture behind an interface pointer at `OFFSET`
+///
+/// # Safety
+///
+/// `this` must be the interface pointer at `OFFSET` of a live fixture.
+unsafe fn fixture<'a, const OFFSET: usize>(this: *mut c_void) -> &'a Fixture {
+    unsafe { &*object::<OFFSET>(this) }
+}
+
+/// `IComponent` vtable structure
+#[repr(C)]
+struct ComponentVTable {
+    // FUnknown methods
+    query_interface:
+        unsafe extern "C" fn(this: *mut c_void, iid: *const Tuid, obj: *mut *mut c_void) -> TResult,
+    add_ref: unsafe extern "C" fn(this: *mut c_void) -> u32,
+    release: unsafe extern "C" fn(this: *mut c_void) -> u32,
+
+    // IPluginBase methods
+    initialize: unsafe extern "C" fn(this: *mut c_void, context: *mut c_void) -> TResult,
+    terminate: unsafe extern "C" fn(this: *mut c_void) -> TResult,
+
+    // IComponent methods
+    get_controller_class_id:
+        unsafe extern "C" fn(this: *mut c_void, class_id: *mut Tuid) -> TResult,
+    set_io_mode: unsafe extern "C" fn(this: *mut c_void, mode: i32) -> TResult,
+    get_bus_count: unsafe extern "C" fn(this: *mut c_void, media_type: i32, direction: i32) -> i32,
+    get_bus_info: unsafe extern "C" fn(
+        this: *mut c_void,
+        media_type: i32,
+        direction: i32,
+        index: i32,
+        info: *mut BusInfo,
+    ) -> TResult,
+    get_routing_info:
+        unsafe extern "C" fn(this: *mut c_void, input: *mut c_void, output: *mut c_void) -> TResult,
+    activate_bus: unsafe extern "C" fn(
+        this: *mut c_void,
+        media_type: i32,
+        direction: i32,
+        index: i32,
+        state: u8,
+    ) -> TResult,
+    set_active: unsafe extern "C" fn(this: *mut c_void, state: u8) -> TResult,
+    set_state: unsafe extern "C" fn(this: *mut c_void, stream: *mut c_void) -> TResult,
+    get_state: unsafe extern "C" fn(this: *mut c_void, stream: *mut c_void) -> TResult,
+}
+
+/// `IAudioProcessor` vtable structure
+#[repr(C)]
+struct ProcessorVTable {
+    // FUnknown methods
+    query_interface:
+        unsafe extern "C" fn(this: *mut c_void, iid: *const Tuid, obj: *mut *mut c_void) -> TResult,
+    add_ref: unsafe extern "C" fn(this: *mut c_void) -> u32,
+    release: unsafe extern "C" fn(this: *mut c_void) -> u32,
+
+    // IAudioProcessor methods
+    set_bus_arrangements: unsafe extern "C" fn(
+        this: *mut c_void,
+        inputs: *const u64,
+        num_inputs: i32,
+        outputs: *const u64,
+        num_outputs: i32,
+    ) -> TResult,
+    get_bus_arrangement: unsafe extern "C" fn(
+        this: *mut c_void,
+        direction: i32,
+        index: i32,
+        arrangement: *mut u64,
+    ) -> TResult,
+    can_process_sample_size: unsafe extern "C" fn(this: *mut c_void, size: i32) -> TResult,
+    get_latency_samples: unsafe extern "C" fn(this: *mut c_void) -> u32,
+    setup_processing:
+        unsafe extern "C" fn(this: *mut c_void, setup: *const ProcessSetup) -> TResult,
+    set_processing: unsafe extern "C" fn(this: *mut c_void, state: u8) -> TResult,
+    process: unsafe extern "C" fn(this: *mut c_void, data: *mut ProcessData) -> TResult,
+    get_tail_samples: unsafe extern "C" fn(this: *mut c_void) -> u32,
+}
+
+/// `IEditController` vtable structure
+#[repr(C)]
+struct EditControllerVTable {
+    // FUnknown methods
+    query_interface:
+        unsafe extern "C" fn(this: *mut c_void, iid: *const Tuid, obj: *mut *mut c_void) -> TResult,
+    add_ref: unsafe extern "C" fn(this: *mut c_void) -> u32,
+    release: unsafe extern "C" fn(this: *mut c_void) -> u32,
+
+    // IPluginBase methods
+    initialize: unsafe extern "C" fn(this: *mut c_void, context: *mut c_void) -> TResult,
+    terminate: unsafe extern "C" fn(this: *mut c_void) -> TResult,
+
+    // IEditController methods
+    set_component_state: unsafe extern "C" fn(this: *mut c_void, stream: *mut c_void) -> TResult,
+    set_state: unsafe extern "C" fn(this: *mut c_void, stream: *mut c_void) -> TResult,
+    get_state: unsafe extern "C" fn(this: *mut c_void, stream: *mut c_void) -> TResult,
+    get_parameter_count: unsafe extern "C" fn(this: *mut c_void) -> i32,
+    get_parameter_info:
+        unsafe extern "C" fn(this: *mut c_void, index: i32, info: *mut ParameterInfo) -> TResult,
+    get_param_string_by_value:
+        unsafe extern "C" fn(this: *mut c_void, id: u32, value: f64, string: *mut u16) -> TResult,
+    get_param_value_by_string: unsafe extern "C" fn(
+        this: *mut c_void,
+        id: u32,
+        string: *const u16,
+        value: *mut f64,
+    ) -> TResult,
+    normalized_param_to_plain: unsafe extern "C" fn(this: *mut c_void, id: u32, value: f64) -> f64,
+    plain_param_to_normalized: unsafe extern "C" fn(this: *mut c_void, id: u32, plain: f64) -> f64,
+    get_param_normalized: unsafe extern "C" fn(this: *mut c_void, id: u32) -> f64,
+    set_param_normalized: unsafe extern "C" fn(this: *mut c_void, id: u32, value: f64) -> TResult,
+    set_component_handler: unsafe extern "C" fn(this: *mut c_void, handler: *mut c_void) -> TResult,
+    create_view: unsafe extern "C" fn(this: *mut c_void, name: *const c_char) -> *mut c_void,
+}
+
+/// Static vtable instances
+static COMPONENT_VTABLE: ComponentVTable = ComponentVTable {
+    query_interface: query_interface::<COMPONENT>,
+    add_ref: add_ref::<COMPONENT>,
+    release: release::<COMPONENT>,
+    initialize,
+    terminate,
+    get_controller_class_id,
+    set_io_mode,
+    get_bus_count,
+    get_bus_info,
+    get_routing_info,
+    activate_bus,
+    set_active,
+    set_state: component_set_state,
+    get_state: component_get_state,
+};
+
+static PROCESSOR_VTABLE: ProcessorVTable = ProcessorVTable {
+    query_interface: query_interface::<PROCESSOR>,
+    add_ref: add_ref::<PROCESSOR>,
+    release: release::<PROCESSOR>,
+    set_bus_arrangements,
+    get_bus_arrangement,
+    can_process_sample_size,
+    get_latency_samples,
+    setup_processing,
+    set_processing,
+    process,
+    get_tail_samples,
+};
+
+static EDIT_CONTROLLER_VTABLE: EditControllerVTable = EditControllerVTable {
+    query_interface: query_interface::<CONTROLLER>,
+    add_ref: add_ref::<CONTROLLER>,
+    release: release::<CONTROLLER>,
+    initialize,
+    terminate,
+    set_component_state,
+    set_state: controller_set_state,
+    get_state: controller_get_state,
+    get_parameter_count,
+    get_parameter_info,
+    get_param_string_by_value,
+    get_param_value_by_string,
+    normalized_param_to_plain,
+    plain_param_to_normalized,
+    get_param_normalized,
+    set_param_normalized,
+    set_component_handler,
+    create_view,
+};
+
+// FUnknown implementation (one instance per interface offset)
+
+unsafe extern "C" fn query_interface<const OFFSET: usize>(
+    this: *mut c_void,
+    iid: *const Tuid,
+    obj: *mut *mut c_void,
+) -> TResult {
+    if this.is_null() || iid.is_null() || obj.is_null() {
+        return K_INVALID_ARGUMENT;
+    }
+
+    unsafe { Fixture::query(object::<OFFSET>(this), &*iid, obj) }
+}
+
+unsafe extern "C" fn add_ref<const OFFSET: usize>(this: *mut c_void) -> u32 {
+    let fixture = unsafe { fixture::<OFFSET>(this) };
+    fixture.ref_count.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+unsafe extern "C" fn release<const OFFSET: usize>(this: *mut c_void) -> u32 {
+    unsafe { Fixture::release_ref(object::<OFFSET>(this)) }
+}
+
+// IPluginBase implementation (shared by the component and the controller)
+
+/// Nothing to set up, so calling it once per interface (as hosts do for
+/// single-component plugins) is fine
+unsafe extern "C" fn initialize(_this: *mut c_void, _context: *mut c_void) -> TResult {
+    K_RESULT_OK
+}
+
+unsafe extern "C" fn terminate(_this: *mut c_void) -> TResult {
+    K_RESULT_OK
+}
+
+// IComponent implementation
+
+/// There is no separate controller class
+unsafe extern "C" fn get_controller_class_id(_this: *mut c_void, _class_id: *mut Tuid) -> TResult {
+    K_NOT_IMPLEMENTED
+}
+
+unsafe extern "C" fn set_io_mode(_this: *mut c_void, _mode: i32) -> TResult {
+    K_NOT_IMPLEMENTED
+}
+
+unsafe extern "C" fn get_bus_count(_this: *mut c_void, media_type: i32, direction: i32) -> i32 {
+    if media_type != MEDIA_AUDIO {
+        return 0;
+    }
+    buses(direction).map_or(0, |buses| buses.len() as i32)
+}
+
+unsafe extern "C" fn get_bus_info(
+    _this: *mut c_void,
+    media_type: i32,
+    direction: i32,
+    index: i32,
+    info: *mut BusInfo,
+) -> TResult {
+    let bus = (media_type == MEDIA_AUDIO)
+        .then(|| buses(direction)?.get(usize::try_from(index).ok()?))
+        .flatten();
+    let (Some(bus), false) = (bus, info.is_null()) else {
+        return K_INVALID_ARGUMENT;
+    };
+
+    let info = unsafe { &mut *info };
+    info.media_type = media_type;
+    info.direction = direction;
+    info.channel_count = CHANNELS as i32;
+    copy_utf16(&mut info.name, bus.name);
+    info.bus_type = bus.kind;
+    info.flags = bus.flags;
+    K_RESULT_OK
+}
+
+unsafe extern "C" fn get_routing_info(
+    _this: *mut c_void,
+    _input: *mut c_void,
+    _output: *mut c_void,
+) -> TResult {
+    K_NOT_IMPLEMENTED
+}
+
+unsafe extern "C" fn activate_bus(
+    this: *mut c_void,
+    media_type: i32,
+    direction: i32,
+    index: i32,
+    state: u8,
+) -> TResult {
+    let count = buses(direction).map_or(0, <[Bus]>::len);
+    if media_type != MEDIA_AUDIO || usize::try_from(index).map_or(true, |index| index >= count) {
+        return K_INVALID_ARGUMENT;
+    }
+
+    let fixture = unsafe { fixture::<COMPONENT>(this) };
+    let bit = bus_bit(direction, index);
+    if state == 0 {
+        fixture.active_buses.fetch_and(!bit, Ordering::Relaxed);
+    } else {
+        fixture.active_buses.fetch_or(bit, Ordering::Relaxed);
+    }
+    K_RESULT_OK
+}
+
+unsafe extern "C" fn set_active(this: *mut c_void, state: u8) -> TResult {
+    if state != 0 {
+        // Start from silence, so the first LATENCY_SAMPLES of output are silent
+        let fixture = unsafe { fixture::<COMPONENT>(this) };
+        let mut delay = fixture
+            .delay
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        *delay = DelayLine::new();
+    }
+    K_RESULT_OK
+}
+
+unsafe extern "C" fn component_set_state(this: *mut c_void, stream: *mut c_void) -> TResult {
+    let fixture = unsafe { fixture::<COMPONENT>(this) };
+    unsafe { load_state(&fixture.processor_values, stream) }
+}
+
+unsafe extern "C" fn component_get_state(this: *mut c_void, stream: *mut c_void) -> TResult {
+    if stream.is_null() {
+        return K_INVALID_ARGUMENT;
+    }
+
+    let fixture = unsafe { fixture::<COMPONENT>(this) };
+    if unsafe { stream_write(stream, &fixture.processor_values.to_state()) } {
+        K_RESULT_OK
+    } else {
+        K_RESULT_FALSE
+    }
+}
+
+/// Read component state from `stream` into `values`
+///
+/// # Safety
+///
+/// `stream` must be null or a valid `IBStream` pointer.
+unsafe fn load_state(values: &ParameterValues, stream: *mut c_void) -> TResult {
+    if stream.is_null() {
+        return K_INVALID_ARGUMENT;
+    }
+
+    let mut state = [0; STATE_LEN];
+    let read = unsafe { stream_read(stream, &mut state) };
+    if read == Some(STATE_LEN) && values.load_state(&state) {
+        K_RESULT_OK
+    } else {
+        K_RESULT_FALSE
+    }
+}
+
+// IAudioProcessor implementation
+
+/// Only stereo on every bus is supported
+unsafe extern "C" fn set_bus_arrangements(
+    _this: *mut c_void,
+    inputs: *const u64,
+    num_inputs: i32,
+    outputs: *const u64,
+    num_outputs: i32,
+) -> TResult {
+    let all_stereo = |arrangements: *const u64, count: i32, declared: usize| {
+        count as usize == declared
+            && (0..declared).all(|index| {
+                !arrangements.is_null() && unsafe { *arrangements.add(index) } == SPEAKERS_STEREO
+            })
+    };
+
+    if all_stereo(inputs, num_inputs, INPUT_BUSES.len())
+        && all_stereo(outputs, num_outputs, OUTPUT_BUSES.len())
+    {
+        K_RESULT_OK
+    } else {
+        K_RESULT_FALSE
+    }
+}
+
+unsafe extern "C" fn get_bus_arrangement(
+    _this: *mut c_void,
+    direction: i32,
+    index: i32,
+    arrangement: *mut u64,
+) -> TResult {
+    let count = buses(direction).map_or(0, <[Bus]>::len);
+    if arrangement.is_null() || usize::try_from(index).map_or(true, |index| index >= count) {
+        return K_INVALID_ARGUMENT;
+    }
+
+    unsafe { *arrangement = SPEAKERS_STEREO };
+    K_RESULT_OK
+}
+
+unsafe extern "C" fn can_process_sample_size(_this: *mut c_void, size: i32) -> TResult {
+    if size == SAMPLE_32 || size == SAMPLE_64 {
+        K_RESULT_OK
+    } else {
+        K_RESULT_FALSE
+    }
+}
+
+unsafe extern "C" fn get_latency_samples(_this: *mut c_void) -> u32 {
+    LATENCY_SAMPLES as u32
+}
+
+unsafe extern "C" fn setup_processing(_this: *mut c_void, setup: *const ProcessSetup) -> TResult {
+    if setup.is_null() {
+        return K_INVALID_ARGUMENT;
+    }
+
+    let size = unsafe { (*setup).symbolic_sample_size };
+    if size == SAMPLE_32 || size == SAMPLE_64 {
+        K_RESULT_OK
+    } else {
+        K_INVALID_ARGUMENT
+    }
+}
+
+unsafe extern "C" fn set_processing(_this: *mut c_void, _state: u8) -> TResult {
+    K_RESULT_OK
+}
+
+unsafe extern "C" fn process(this: *mut c_void, data: *mut ProcessData) -> TResult {
+    if data.is_null() {
+        return K_INVALID_ARGUMENT;
+    }
+
+    let fixture = unsafe { fixture::<PROCESSOR>(this) };
+    let data = unsafe { &mut *data };
+
+    // Parameter changes arrive even in blocks without audio
+    unsafe {
+        for_each_last_change(data.input_param_changes, |id, value| {
+            fixture.processor_values.set(id, value);
+        });
+    }
+
+    let Ok(frames) = usize::try_from(data.num_samples) else {
+        return K_INVALID_ARGUMENT;
+    };
+    if frames == 0 || data.num_outputs == 0 {
+        return K_RESULT_OK;
+    }
+
+    let buses_match = unsafe {
+        fixture.buses_match(DIRECTION_INPUT, data.inputs, data.num_inputs)
+            && fixture.buses_match(DIRECTION_OUTPUT, data.outputs, data.num_outputs)
+    };
+    if !buses_match {
+        return K_INVALID_ARGUMENT;
+    }
+
+    let output = unsafe { &mut *data.outputs };
+    if output.num_channels == 0 {
+        return K_RESULT_OK;
+    }
+    output.silence_flags = 0;
+
+    // An inactive main input reads as silence
+    let input = if data.num_inputs > 0 && unsafe { (*data.inputs).num_channels } > 0 {
+        unsafe { (*data.inputs).channel_buffers }
+    } else {
+        std::ptr::null_mut()
+    };
+
+    let gain = fixture.processor_values.output_gain();
+    let mut delay = fixture
+        .delay
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    match data.symbolic_sample_size {
+        SAMPLE_32 => unsafe {
+            process_block::<f32>(&mut delay, input, output.channel_buffers, frames, gain);
+        },
+        SAMPLE_64 => unsafe {
+            process_block::<f64>(&mut delay, input, output.channel_buffers, frames, gain);
+        },
+        _ => return K_INVALID_ARGUMENT,
+    }
+    K_RESULT_OK
+}
+
+unsafe extern "C" fn get_tail_samples(_this: *mut c_void) -> u32 {
+    TAIL_SAMPLES
+}
+
+// IEditController implementation
+
+unsafe extern "C" fn set_component_state(this: *mut c_void, stream: *mut c_void) -> TResult {
+    let fixture = unsafe { fixture::<CONTROLLER>(this) };
+    unsafe { load_state(&fixture.controller_values, stream) }
+}
+
+/// The controller has no state of its own
+unsafe extern "C" fn controller_set_state(_this: *mut c_void, _stream: *mut c_void) -> TResult {
+    K_RESULT_OK
+}
+
+unsafe extern "C" fn controller_get_state(_this: *mut c_void, _stream: *mut c_void) -> TResult {
+    K_RESULT_OK
+}
+
+unsafe extern "C" fn get_parameter_count(_this: *mut c_void) -> i32 {
+    PARAMETERS.len() as i32
+}
+
+unsafe extern "C" fn get_parameter_info(
+    _this: *mut c_void,
+    index: i32,
+    info: *mut ParameterInfo,
+) -> TResult {
+    let parameter = usize::try_from(index)
+        .ok()
+        .and_then(|index| PARAMETERS.get(index));
+    let (Some(parameter), false) = (parameter, info.is_null()) else {
+        return K_INVALID_ARGUMENT;
+    };
+
+    let info = unsafe { &mut *info };
+    // Parameter IDs are their indices
+    info.id = index as u32;
+    copy_utf16(&mut info.title, parameter.title);
+    copy_utf16(&mut info.short_title, parameter.title);
+    copy_utf16(&mut info.units, parameter.units);
+    info.step_count = parameter.step_count;
+    info.default_normalized_value = parameter.default;
+    info.unit_id = 0;
+    info.flags = parameter.flags;
+    K_RESULT_OK
+}
+
+unsafe extern "C" fn get_param_string_by_value(
+    _this: *mut c_void,
+    id: u32,
+    value: f64,
+    string: *mut u16,
+) -> TResult {
+    let (Some(text), false) = (display(id, value), string.is_null()) else {
+        return K_INVALID_ARGUMENT;
+    };
+
+    let string = unsafe { std::slice::from_raw_parts_mut(string, STRING128_LEN) };
+    copy_utf16(string, &text);
+    K_RESULT_OK
+}
+
+unsafe extern "C" fn get_param_value_by_string(
+    _this: *mut c_void,
+    id: u32,
+    string: *const u16,
+    value: *mut f64,
+) -> TResult {
+    let text = unsafe { read_utf16(string) };
+    let (Some(parsed), false) = (text.and_then(|text| parse(id, &text)), value.is_null()) else {
+        return K_INVALID_ARGUMENT;
+    };
+
+    unsafe { *value = parsed };
+    K_RESULT_OK
+}
+
+unsafe extern "C" fn normalized_param_to_plain(_this: *mut c_void, id: u32, value: f64) -> f64 {
+    to_plain(id, value)
+}
+
+unsafe extern "C" fn plain_param_to_normalized(_this: *mut c_void, id: u32, plain: f64) -> f64 {
+    to_normalized(id, plain)
+}
+
+/// Unknown IDs read as 0.0, as the SDK's controllers do
+unsafe extern "C" fn get_param_normalized(this: *mut c_void, id: u32) -> f64 {
+    let fixture = unsafe { fixture::<CONTROLLER>(this) };
+    fixture.controller_values.get(id).unwrap_or_default()
+}
+
+unsafe extern "C" fn set_param_normalized(this: *mut c_void, id: u32, value: f64) -> TResult {
+    let fixture = unsafe { fixture::<CONTROLLER>(this) };
+    if fixture.controller_values.set(id, value) {
+        K_RESULT_OK
+    } else {
+        K_INVALID_ARGUMENT
+    }
+}
+
+/// The fixture never edits its own parameters, so it doesn't keep the handler
+unsafe extern "C" fn set_component_handler(_this: *mut c_void, _handler: *mut c_void) -> TResult {
+    K_RESULT_OK
+}
+
+/// No editor
+unsafe extern "C" fn create_view(_this: *mut c_void, _name: *const c_char) -> *mut c_void {
+    std::ptr::null_mut()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Query `iid` on a new fixture, releasing the creation reference
+    fn interface(iid: &Tuid) -> *mut c_void {
+        let fixture = Fixture::create();
+        let mut obj = std::ptr::null_mut();
+        unsafe {
+            assert_eq!(Fixture::query(fixture, iid, &raw mut obj), K_RESULT_OK);
+            Fixture::release_ref(fixture);
+        }
+        obj
+    }
+
+    #[test]
+    fn test_parameter_text_round_trips() {
+        for (id, value) in [(GAIN, 0.5), (MIX, 0.25), (BYPASS, 1.0), (BYPASS, 0.0)] {
+            let text = display(id, value).unwrap();
+            assert_eq!(parse(id, &text), Some(value), "'{text}' for parameter {id}");
+        }
+        assert_eq!(parse(GAIN, "loud"), None);
+        assert_eq!(parse(3, "0.5"), None);
+    }
+
+    #[test]
+    fn test_state_round_trips() {
+        let values = ParameterValues::defaults();
+        values.set(GAIN, 0.25);
+        let state = values.to_state();
+
+        let restored = ParameterValues::defaults();
+        assert!(restored.load_state(&state));
+        assert_eq!(restored.get(GAIN), Some(0.25));
+        assert_eq!(restored.get(MIX), Some(1.0));
+        assert!(!restored.load_state(b"nope"));
+    }
+
+    #[test]
+    fn test_output_gain() {
+        let values = ParameterValues::defaults();
+        assert_eq!(values.output_gain(), 0.5);
+        values.set(MIX, 0.5);
+        assert_eq!(values.output_gain(), 0.75);
+        values.set(BYPASS, 1.0);
+        assert_eq!(values.output_gain(), 1.0);
+    }
+
+    #[test]
+    fn test_interfaces_share_one_object() {
+        let component = interface(&ICOMPONENT_IID);
+        let mut processor = std::ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                query_interface::<COMPONENT>(component, &IAUDIO_PROCESSOR_IID, &raw mut processor),
+                K_RESULT_OK
+            );
+            assert_eq!(
+                std::ptr::from_ref(fixture::<PROCESSOR>(processor)),
+                std::ptr::from_ref(fixture::<COMPONENT>(component))
+            );
+            assert_eq!(release::<PROCESSOR>(processor), 1);
+            assert_eq!(release::<COMPONENT>(component), 0);
+        }
+    }
+
+    #[test]
+    fn test_process_delays_and_scales_main_input() {
+        let processor = interface(&IAUDIO_PROCESSOR_IID);
+        let frames = LATENCY_SAMPLES + 4;
+
+        let mut input: Vec<Vec<f32>> = vec![(1..=frames).map(|i| i as f32).collect(); CHANNELS];
+        let mut output = vec![vec![f32::NAN; frames]; CHANNELS];
+        let mut sidechain = vec![vec![0.0_f32; frames]; CHANNELS];
+        let mut input_ptrs: Vec<*mut f32> = input.iter_mut().map(Vec::as_mut_ptr).collect();
+        let mut output_ptrs: Vec<*mut f32> = output.iter_mut().map(Vec::as_mut_ptr).collect();
+        let mut sidechain_ptrs: Vec<*mut f32> = sidechain.iter_mut().map(Vec::as_mut_ptr).collect();
+
+        let bus = |ptrs: &mut Vec<*mut f32>| AudioBusBuffers {
+            num_channels: CHANNELS as i32,
+            silence_flags: 0,
+            channel_buffers: ptrs.as_mut_ptr().cast(),
+        };
+        let mut inputs = [bus(&mut input_ptrs), bus(&mut sidechain_ptrs)];
+        let mut outputs = [bus(&mut output_ptrs)];
+        let mut data = ProcessData {
+            process_mode: 0,
+            symbolic_sample_size: SAMPLE_32,
+            num_samples: frames as i32,
+            num_inputs: 2,
+            num_outputs: 1,
+            inputs: inputs.as_mut_ptr(),
+            outputs: outputs.as_mut_ptr(),
+            input_param_changes: std::ptr::null_mut(),
+            output_param_changes: std::ptr::null_mut(),
+            input_events: std::ptr::null_mut(),
+            output_events: std::ptr::null_mut(),
+            process_context: std::ptr::null_mut(),
+        };
+
+        unsafe {
+            // The sidechain isn't active yet, but arrives with channels
+            assert_eq!(process(processor, &raw mut data), K_RESULT_OK);
+
+            // Once active, a sidechain without channels is a layout mismatch
+            let component = object::<PROCESSOR>(processor).cast::<c_void>();
+            assert_eq!(
+                activate_bus(component, MEDIA_AUDIO, DIRECTION_INPUT, 1, 1),
+                K_RESULT_OK
+            );
+            (*data.inputs.add(1)).num_channels = 0;
+            assert_eq!(process(processor, &raw mut data), K_INVALID_ARGUMENT);
+
+            release::<PROCESSOR>(processor);
+        }
+
+        for channel in &output {
+            assert!(channel[..LATENCY_SAMPLES].iter().all(|&s| s == 0.0));
+            assert_eq!(&channel[LATENCY_SAMPLES..], &[0.5, 1.0, 1.5, 2.0]);
+        }
+    }
+}
@@ -4,7 +4,11 @@
 //! (VST3, CLAP, etc.) must implement. This allows the audio engine to work
 //! with plugins in a format-agnostic way.
 
-use vvdaw_core::{ChannelCount, Frames, Sample, SampleRate};
+use std::path::Path;
+use vvdaw_core::{ChannelCount, Frames, Sample, SampleRate, Transport};
+
+/// [`Plugin::tail_samples`] of a plugin whose output never dies away
+pub const INFINITE_TAIL: Frames = Frames::MAX;
 
 /// Audio buffer for processing
 pub struct AudioBuffer<'a> {
@@ -14,7 +18,7 @@ pub struct AudioBuffer<'a> {
 }
 
 /// MIDI/parameter events
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Event {
     /// Note on event
     NoteOn {
@@ -37,6 +41,18 @@ pub enum Event {
     },
 }
 
+impl Event {
+    /// Position of the event within its block (frames)
+    #[must_use]
+    pub const fn sample_offset(&self) -> u32 {
+        match *self {
+            Self::NoteOn { sample_offset, .. }
+            | Self::NoteOff { sample_offset, .. }
+            | Self::ParamChange { sample_offset, .. } => sample_offset,
+        }
+    }
+}
+
 /// Buffer of events for a processing block
 pub struct EventBuffer {
     pub events: Vec<Event>,
@@ -48,9 +64,30 @@ impl EventBuffer {
         Self { events: Vec::new() }
     }
 
+    /// An empty buffer with room for `capacity` events
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            events: Vec::with_capacity(capacity),
+        }
+    }
+
     pub fn clear(&mut self) {
         self.events.clear();
     }
+
+    /// Add an event if the buffer has room for it
+    ///
+    /// Returns `false` (dropping the event) once the buffer is at capacity.
+    ///
+    /// REAL-TIME SAFE: never allocates.
+    pub fn try_push(&mut self, event: Event) -> bool {
+        if self.events.len() == self.events.capacity() {
+            return false;
+        }
+        self.events.push(event);
+        true
+    }
 }
 
 impl Default for EventBuffer {
@@ -67,6 +104,33 @@ pub struct ParameterInfo {
     pub min_value: f32,
     pub max_value: f32,
     pub default_value: f32,
+    /// Number of steps between `min_value` and `max_value` for discrete
+    /// parameters (3 steps = 4 positions), or 0 for continuous ones
+    pub step_count: u32,
+}
+
+impl ParameterInfo {
+    /// Whether the parameter only takes discrete positions (switches, modes)
+    #[must_use]
+    pub const fn is_discrete(&self) -> bool {
+        self.step_count > 0
+    }
+
+    /// Clamp `value` to the parameter's range, snapping discrete parameters
+    /// to the nearest step
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // Step counts are far below 2^24
+    pub fn constrain(&self, value: f32) -> f32 {
+        let clamped = value.clamp(self.min_value, self.max_value);
+        let range = self.max_value - self.min_value;
+        if !self.is_discrete() || range <= 0.0 {
+            return clamped;
+        }
+
+        let steps = self.step_count as f32;
+        let step = ((clamped - self.min_value) / range * steps).round();
+        (step / steps).mul_add(range, self.min_value)
+    }
 }
 
 /// Plugin metadata
@@ -76,6 +140,10 @@ pub struct PluginInfo {
     pub vendor: String,
     pub version: String,
     pub unique_id: String,
+    /// Format-specific category, e.g. VST3 subcategories like `Fx|Reverb`
+    /// or `Instrument|Synth` (`None` if the plugin doesn't report one)
+    #[serde(default)]
+    pub category: Option<String>,
 }
 
 /// Main plugin trait that all plugin formats implement
@@ -111,6 +179,174 @@ pub trait Plugin: Send {
 
     /// Deactivate and cleanup
     fn deactivate(&mut self);
+
+    /// Processing latency in frames
+    ///
+    /// Plugins that delay their output (lookahead, linear-phase filters, etc.)
+    /// report the delay here so the host can compensate. The default is 0.
+    fn latency(&self) -> Frames {
+        0
+    }
+
+    /// Length of the plugin's tail in frames
+    ///
+    /// How long the plugin keeps producing output after its input goes silent
+    /// (reverb decay, delay repeats, etc.), so offline renders know how far to
+    /// run past the end of the input. [`INFINITE_TAIL`] means the output never
+    /// dies away on its own. The default is 0.
+    fn tail_samples(&self) -> Frames {
+        0
+    }
+
+    /// Scrub playback-style plugins (samplers, players) like a tape machine
+    ///
+    /// Jump to `frame` in the plugin's material and play the next `window` frames
+    /// at `rate` (1.0 = normal speed, negative = reverse). Effects and other
+    /// plugins without a playhead ignore this (the default does nothing).
+    ///
+    /// Called from the audio thread - implementations must be real-time safe.
+    fn scrub(&mut self, _frame: u64, _rate: f32, _window: Frames) {}
+
+    /// Move the playhead of playback-style plugins to `frame`
+    ///
+    /// Returns the frame actually landed on (implementations clamp to their
+    /// material), or `None` for plugins without a playhead (the default).
+    ///
+    /// Called from the audio thread - implementations must be real-time safe.
+    fn seek(&mut self, _frame: u64) -> Option<u64> {
+        None
+    }
+
+    /// Set the loop region of playback-style plugins
+    ///
+    /// When enabled, playback wraps from `end` back to `start` (frames, end
+    /// exclusive). Returns the effective region after clamping to the plugin's
+    /// material, or `None` if looping ended up disabled or isn't supported
+    /// (the default).
+    ///
+    /// Called from the audio thread - implementations must be real-time safe.
+    fn set_loop(&mut self, _start: u64, _end: u64, _enabled: bool) -> Option<(u64, u64)> {
+        None
+    }
+
+    /// Set the varispeed rate of playback-style plugins
+    ///
+    /// Material is read at `rate` frames per output frame, so pitch follows
+    /// speed like a turntable (1.0 = normal, 0.0 = hold, negative = reverse).
+    /// Returns the rate actually applied after clamping, or `None` for plugins
+    /// without a playhead (the default).
+    ///
+    /// Called from the audio thread - implementations must be real-time safe.
+    fn set_playback_rate(&mut self, _rate: f32) -> Option<f32> {
+        None
+    }
+
+    /// Load a preset file in the plugin format's own format (e.g. `.vstpreset`)
+    ///
+    /// Call after [`Plugin::initialize`]. The default reports that presets
+    /// aren't supported.
+    fn load_preset(&mut self, path: &Path) -> Result<(), PluginError> {
+        Err(PluginError::FormatError(format!(
+            "{} does not support preset files ({})",
+            self.info().name,
+            path.display()
+        )))
+    }
+
+    /// Format a parameter value the way the plugin displays it (e.g. "-6.0 dB")
+    ///
+    /// `value` is in the same units as [`Plugin::get_parameter`]. The default
+    /// reports that the plugin has no display strings.
+    fn parameter_display(&self, id: u32, _value: f32) -> Result<String, PluginError> {
+        Err(PluginError::InvalidParameter(format!(
+            "{} has no display string for parameter {id}",
+            self.info().name
+        )))
+    }
+
+    /// Parse display text (the inverse of [`Plugin::parameter_display`]) into a value
+    ///
+    /// The default reports that the plugin can't parse display strings.
+    fn parameter_from_string(&self, id: u32, text: &str) -> Result<f32, PluginError> {
+        Err(PluginError::InvalidParameter(format!(
+            "{} can't parse '{text}' for parameter {id}",
+            self.info().name
+        )))
+    }
+
+    /// Capture the plugin's complete internal state as an opaque blob
+    ///
+    /// Sessions store the blob alongside parameter values, so state that isn't
+    /// exposed as parameters (loaded samples, wavetables, ...) survives a
+    /// reload. Call after [`Plugin::initialize`]. The default returns `None`:
+    /// the plugin's parameters describe all of its state.
+    fn save_state(&self) -> Result<Option<Vec<u8>>, PluginError> {
+        Ok(None)
+    }
+
+    /// Restore a blob captured by [`Plugin::save_state`]
+    ///
+    /// Call after [`Plugin::initialize`]. The default reports that state
+    /// blobs aren't supported.
+    fn load_state(&mut self, _state: &[u8]) -> Result<(), PluginError> {
+        Err(PluginError::FormatError(format!(
+            "{} does not support saved state",
+            self.info().name
+        )))
+    }
+
+    /// Allow (or forbid) 64-bit processing inside the plugin
+    ///
+    /// Takes effect at the next [`Plugin::initialize`], where plugins that
+    /// support double precision switch to it. Audio still crosses the
+    /// [`AudioBuffer`] as `f32` - plugins convert at the boundary. The default
+    /// ignores this and always processes in 32-bit.
+    fn set_double_precision(&mut self, _allowed: bool) {}
+
+    /// Whether the plugin negotiated 64-bit processing at its last initialize
+    fn double_precision(&self) -> bool {
+        false
+    }
+
+    /// Receive the host's tempo and musical position for the next block
+    ///
+    /// Called before every [`Plugin::process`] call. Tempo-synced plugins
+    /// keep what they need; the default ignores it.
+    ///
+    /// Called from the audio thread - implementations must be real-time safe.
+    fn set_transport(&mut self, _transport: &Transport) {}
+
+    /// Take the next parameter change made in the plugin's own editor
+    ///
+    /// Returns `(parameter ID, value)` with the value in the units of
+    /// [`Plugin::get_parameter`], or `None` once no edits are pending. The
+    /// plugin applies the edit itself - the host polls so its view of the
+    /// parameter stays in sync. The default never has edits.
+    ///
+    /// Called from the audio thread - implementations must be real-time safe.
+    fn poll_parameter_edit(&mut self) -> Option<(u32, f32)> {
+        None
+    }
+
+    /// Whether the plugin emits events of its own (an arpeggiator's notes, a
+    /// sequencer, parameter automation from an LFO)
+    ///
+    /// The graph routes an emitting plugin's events to the nodes it feeds in
+    /// place of the host's events. Checked when the graph's connections
+    /// change, after [`Plugin::initialize`]. The default is `false`.
+    fn emits_events(&self) -> bool {
+        false
+    }
+
+    /// Append the events emitted by the last [`Plugin::process`] call to `events`
+    ///
+    /// Only called on plugins that report [`Plugin::emits_events`]. Events
+    /// are in block order, with sample offsets relative to the block start.
+    /// The default emits nothing.
+    ///
+    /// Called from the audio thread - implementations must be real-time safe
+    /// (use [`EventBuffer::try_push`] rather than growing `events`).
+    fn take_output_events(&mut self, _events: &mut EventBuffer) {}
 }
 
 /// Plugin-related errors
@@ -143,5 +379,53 @@ mod tests {
             sample_offset: 0,
         });
         assert_eq!(buffer.events.len(), 1);
+        assert_eq!(buffer.events[0].sample_offset(), 0);
+    }
+
+    #[test]
+    fn test_try_push_stops_at_capacity() {
+        let mut buffer = EventBuffer::with_capacity(2);
+        let note_off = |sample_offset| Event::NoteOff {
+            channel: 0,
+            note: 60,
+            sample_offset,
+        };
+        assert!(buffer.try_push(note_off(0)));
+        assert!(buffer.try_push(note_off(10)));
+        assert!(!buffer.try_push(note_off(20)));
+        assert_eq!(buffer.events.len(), 2);
+        assert_eq!(buffer.events[1].sample_offset(), 10);
+    }
+
+    fn param(min_value: f32, max_value: f32, step_count: u32) -> ParameterInfo {
+        ParameterInfo {
+            id: 0,
+            name: "Test".to_string(),
+            min_value,
+            max_value,
+            default_value: min_value,
+            step_count,
+        }
+    }
+
+    #[test]
+    fn test_constrain_snaps_discrete_parameters() {
+        // 3 steps = 4 positions: 0, 1/3, 2/3, 1
+        let switch = param(0.0, 1.0, 3);
+        assert!(switch.is_discrete());
+        assert!((switch.constrain(0.4) - 1.0 / 3.0).abs() < 1e-6);
+        assert!((switch.constrain(0.9) - 1.0).abs() < 1e-6);
+        assert!(switch.constrain(-2.0).abs() < 1e-6);
+
+        let octave = param(-2.0, 2.0, 4);
+        assert!((octave.constrain(0.6) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_constrain_clamps_continuous_parameters() {
+        let gain = param(0.0, 2.0, 0);
+        assert!(!gain.is_discrete());
+        assert!((gain.constrain(0.37) - 0.37).abs() < 1e-6);
+        assert!((gain.constrain(5.0) - 2.0).abs() < 1e-6);
     }
 }
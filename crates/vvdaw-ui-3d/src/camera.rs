@@ -1,19 +1,50 @@
 //! Camera system with flight controls
 //!
 //! Implements Descent-style 6DOF camera movement for navigating the 3D highway.
+//! How fast it flies and turns is set by [`CameraSettings`]: `-` and `=`
+//! change the move speed on the fly, and View > Camera Settings opens a panel
+//! with the rest. The app saves the settings with the rest of the UI config.
 
 use bevy::camera::{Exposure, PhysicalCameraParameters};
 use bevy::input::mouse::MouseMotion;
 use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
 use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::playback::{PlaybackState, PlaybackStatus};
+use crate::waveform::{WaveformData, WaveformMeshConfig};
+
+/// Maximum scrub rate (in either direction) sent to the audio engine
+///
+/// Keeps fast flybys audible instead of turning into noise.
+const MAX_SCRUB_RATE: f32 = 8.0;
+
+/// Factor each `-`/`=` press changes the move speed by
+pub const MOVE_SPEED_STEP: f32 = 1.25;
+
+/// Range of move speeds (units per second)
+pub const MOVE_SPEED_RANGE: std::ops::RangeInclusive<f32> = 2.0..=500.0;
 
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(InputManagerPlugin::<CameraAction>::default())
+            .init_resource::<CameraSettings>()
+            .init_resource::<CameraSettingsPanel>()
             .add_systems(Startup, setup_camera)
-            .add_systems(Update, (camera_movement, camera_look).chain());
+            .add_systems(
+                Update,
+                (
+                    camera_speed_keys,
+                    camera_movement,
+                    camera_look,
+                    camera_scrub,
+                )
+                    .chain(),
+            )
+            .add_systems(EguiPrimaryContextPass, camera_settings_panel_system);
     }
 }
 
@@ -28,32 +59,64 @@ pub enum CameraAction {
     Down,
     SpeedBoost,
     Look,
+    Faster,
+    Slower,
 }
 
-/// Component marking the flight camera
-#[derive(Component)]
-pub struct FlightCamera {
+/// How the flight camera moves and turns
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraSettings {
     /// Movement speed (units per second)
-    pub speed: f32,
+    pub move_speed: f32,
+    /// Speed multiplier while Shift is held
+    pub boost_multiplier: f32,
     /// Rotation speed (radians per pixel)
     pub look_sensitivity: f32,
-    /// Current pitch angle (radians)
-    pub pitch: f32,
-    /// Current yaw angle (radians)
-    pub yaw: f32,
+    /// Moving the mouse up looks down
+    pub invert_y: bool,
 }
 
-impl Default for FlightCamera {
+impl Default for CameraSettings {
     fn default() -> Self {
         Self {
-            speed: 20.0,
+            move_speed: 20.0,
+            boost_multiplier: 3.0,
             look_sensitivity: 0.003,
-            pitch: 0.0,
-            yaw: 0.0,
+            invert_y: false,
         }
     }
 }
 
+impl CameraSettings {
+    /// Multiply the move speed by `factor`, staying within [`MOVE_SPEED_RANGE`]
+    pub fn scale_move_speed(&mut self, factor: f32) {
+        self.move_speed =
+            (self.move_speed * factor).clamp(*MOVE_SPEED_RANGE.start(), *MOVE_SPEED_RANGE.end());
+    }
+
+    /// Pitch change (radians) for a vertical mouse movement of `delta_y` pixels
+    pub fn pitch_delta(&self, delta_y: f32) -> f32 {
+        let delta = -delta_y * self.look_sensitivity;
+        if self.invert_y { -delta } else { delta }
+    }
+}
+
+/// Resource tracking whether the camera settings panel is shown
+#[derive(Resource, Debug, Default)]
+pub struct CameraSettingsPanel {
+    pub open: bool,
+}
+
+/// Component marking the flight camera
+#[derive(Component, Default)]
+pub struct FlightCamera {
+    /// Current pitch angle (radians)
+    pub pitch: f32,
+    /// Current yaw angle (radians)
+    pub yaw: f32,
+}
+
 /// Setup the camera at the starting position
 fn setup_camera(mut commands: Commands) {
     // Create input map for camera controls
@@ -65,6 +128,8 @@ fn setup_camera(mut commands: Commands) {
         (CameraAction::Up, KeyCode::KeyQ),
         (CameraAction::Down, KeyCode::KeyE),
         (CameraAction::SpeedBoost, KeyCode::ShiftLeft),
+        (CameraAction::Faster, KeyCode::Equal),
+        (CameraAction::Slower, KeyCode::Minus),
     ])
     .with(CameraAction::Look, MouseButton::Right);
 
@@ -94,13 +159,32 @@ fn setup_camera(mut commands: Commands) {
     ));
 }
 
+/// Change the move speed with `-` and `=`
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn camera_speed_keys(
+    query: Query<&ActionState<CameraAction>, With<FlightCamera>>,
+    mut settings: ResMut<CameraSettings>,
+) {
+    for action_state in &query {
+        if action_state.just_pressed(&CameraAction::Faster) {
+            settings.scale_move_speed(MOVE_SPEED_STEP);
+            tracing::info!("Camera speed {:.1}", settings.move_speed);
+        }
+        if action_state.just_pressed(&CameraAction::Slower) {
+            settings.scale_move_speed(MOVE_SPEED_STEP.recip());
+            tracing::info!("Camera speed {:.1}", settings.move_speed);
+        }
+    }
+}
+
 /// Handle camera movement (WASD + QE for up/down)
 #[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
 fn camera_movement(
     time: Res<Time>,
-    mut query: Query<(&mut Transform, &FlightCamera, &ActionState<CameraAction>)>,
+    settings: Res<CameraSettings>,
+    mut query: Query<(&mut Transform, &ActionState<CameraAction>), With<FlightCamera>>,
 ) {
-    for (mut transform, camera, action_state) in &mut query {
+    for (mut transform, action_state) in &mut query {
         let mut velocity = Vec3::ZERO;
         let forward = *transform.forward();
         let right = *transform.right();
@@ -132,14 +216,16 @@ fn camera_movement(
 
         // Speed boost with Shift
         let speed_multiplier = if action_state.pressed(&CameraAction::SpeedBoost) {
-            3.0
+            settings.boost_multiplier
         } else {
             1.0
         };
 
         // Apply movement
-        let delta =
-            velocity.normalize_or_zero() * camera.speed * speed_multiplier * time.delta_secs();
+        let delta = velocity.normalize_or_zero()
+            * settings.move_speed
+            * speed_multiplier
+            * time.delta_secs();
         transform.translation += delta;
     }
 }
@@ -148,6 +234,7 @@ fn camera_movement(
 #[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
 fn camera_look(
     mut mouse_motion: MessageReader<MouseMotion>,
+    settings: Res<CameraSettings>,
     mut query: Query<(
         &mut Transform,
         &mut FlightCamera,
@@ -162,8 +249,8 @@ fn camera_look(
             }
 
             // Update yaw and pitch
-            camera.yaw -= motion.delta.x * camera.look_sensitivity;
-            camera.pitch -= motion.delta.y * camera.look_sensitivity;
+            camera.yaw -= motion.delta.x * settings.look_sensitivity;
+            camera.pitch += settings.pitch_delta(motion.delta.y);
 
             // Clamp pitch to avoid gimbal lock
             camera.pitch = camera.pitch.clamp(-1.5, 1.5);
@@ -178,3 +265,127 @@ fn camera_look(
         }
     }
 }
+
+/// Camera settings panel system
+fn camera_settings_panel_system(
+    mut contexts: EguiContexts,
+    mut panel: ResMut<CameraSettingsPanel>,
+    mut settings: ResMut<CameraSettings>,
+) -> Result {
+    if !panel.open {
+        return Ok(());
+    }
+
+    // Edit a copy so the settings only register a change when one is made
+    let mut edited = settings.clone();
+    egui::Window::new("Camera")
+        .open(&mut panel.open)
+        .resizable(false)
+        .show(contexts.ctx_mut()?, |ui| {
+            ui.add(
+                egui::Slider::new(&mut edited.move_speed, MOVE_SPEED_RANGE)
+                    .logarithmic(true)
+                    .text("Move speed  [-/=]"),
+            );
+            ui.add(
+                egui::Slider::new(&mut edited.boost_multiplier, 1.0..=10.0).text("Boost (Shift)"),
+            );
+            ui.add(
+                egui::Slider::new(&mut edited.look_sensitivity, 0.0005..=0.01)
+                    .logarithmic(true)
+                    .text("Look sensitivity"),
+            );
+            ui.checkbox(&mut edited.invert_y, "Invert Y");
+
+            ui.separator();
+            if ui.button("Reset to Defaults").clicked() {
+                edited = CameraSettings::default();
+            }
+        });
+
+    if edited != *settings {
+        *settings = edited;
+    }
+    Ok(())
+}
+
+/// Scrub audio as the camera moves along the highway, like dragging tape past a head
+///
+/// The waveform walls are laid out relative to the playback position at z = 0,
+/// with time increasing down the road (negative Z). The camera's Z therefore maps
+/// to a time in the track, and its speed along the road to a scrub rate.
+///
+/// Only active while playback is stopped or paused - during playback the audio
+/// drives the highway, not the other way round.
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn camera_scrub(
+    time: Res<Time>,
+    playback: Res<PlaybackState>,
+    waveform: Res<WaveformData>,
+    mut audio_command_tx: Option<ResMut<crate::AudioCommandChannel>>,
+    query: Query<&Transform, With<FlightCamera>>,
+    mut last_z: Local<Option<f32>>,
+) {
+    let Ok(transform) = query.single() else {
+        return;
+    };
+    let z = transform.translation.z;
+    let previous_z = last_z.replace(z);
+
+    if playback.status == PlaybackStatus::Playing || !waveform.is_loaded() {
+        return;
+    }
+
+    let (Some(previous_z), Some(tx)) = (previous_z, &mut audio_command_tx) else {
+        return;
+    };
+
+    let delta_secs = time.delta_secs();
+    if z == previous_z || delta_secs <= 0.0 {
+        return; // Camera not moving along the road - let the scrub window run out
+    }
+
+    // Track time under the camera, and how fast it's sweeping past
+    let config = WaveformMeshConfig::default();
+    let track_time = config.z_to_time(z, playback.current_position);
+    let previous_time = config.z_to_time(previous_z, playback.current_position);
+    let rate = ((track_time - previous_time) / delta_secs).clamp(-MAX_SCRUB_RATE, MAX_SCRUB_RATE);
+    let track_time = track_time.clamp(0.0, playback.total_duration);
+
+    let frame = (track_time * waveform.sample_rate as f32) as u64;
+
+    // Drop the scrub if the queue is full - the next frame sends a fresh one
+    let _ = tx.0.push(vvdaw_comms::AudioCommand::Scrub { frame, rate });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_speed_steps_stay_in_range() {
+        let mut settings = CameraSettings::default();
+        settings.scale_move_speed(MOVE_SPEED_STEP);
+        assert_eq!(settings.move_speed, 25.0);
+
+        for _ in 0..100 {
+            settings.scale_move_speed(MOVE_SPEED_STEP);
+        }
+        assert_eq!(settings.move_speed, *MOVE_SPEED_RANGE.end());
+
+        for _ in 0..100 {
+            settings.scale_move_speed(MOVE_SPEED_STEP.recip());
+        }
+        assert_eq!(settings.move_speed, *MOVE_SPEED_RANGE.start());
+    }
+
+    #[test]
+    fn test_invert_y_flips_pitch() {
+        let mut settings = CameraSettings::default();
+        // Moving the mouse up (negative delta) looks up
+        assert!(settings.pitch_delta(-10.0) > 0.0);
+
+        settings.invert_y = true;
+        assert!(settings.pitch_delta(-10.0) < 0.0);
+    }
+}
@@ -1,11 +1,21 @@
 //! WAV file loading system
 //!
 //! Handles loading WAV files from disk and converting them to waveform data.
+//! Files over [`STREAMING_THRESHOLD_BYTES`] are too large to hold in memory
+//! and are played by a `StreamingSampler` instead, as in the 2D UI. They get
+//! no waveform walls (only the live peaks from the engine), so the controls
+//! that need the whole waveform - seeking by click or key, markers and loop
+//! regions - aren't available for them.
 
 use bevy::prelude::*;
 use hound::WavReader;
+use std::collections::VecDeque;
 use std::path::{Path, PathBuf};
 use tracing::{error, info, warn};
+use vvdaw_audio::builtin::streaming_sampler::{STREAMING_THRESHOLD_BYTES, StreamingSampler};
+use vvdaw_audio::wav_progress::{self, LoadProgress};
+use vvdaw_core::buffer;
+use vvdaw_core::format::samples_to_seconds;
 
 use crate::menu::FileSelected;
 use crate::playback::PlaybackState;
@@ -30,13 +40,19 @@ impl Plugin for FileLoadingPlugin {
 /// Resource for tracking file loading tasks
 #[derive(Resource, Default)]
 struct FileLoadTask {
-    pending: Option<std::thread::JoinHandle<Result<LoadedAudio, String>>>,
+    pending: Option<std::thread::JoinHandle<Result<LoadedFile, String>>>,
+    /// Progress reports from the pending load
+    progress_rx: Option<crossbeam_channel::Receiver<LoadProgress>>,
+    /// Files waiting for the current load to finish, in request order
+    queued: VecDeque<PathBuf>,
 }
 
 /// Resource for tracking file loading state and errors
 #[derive(Resource, Default, Clone)]
 pub struct FileLoadingState {
     pub is_loading: bool,
+    /// How far the current load has got, while loading
+    pub progress: Option<LoadProgress>,
     pub error: Option<String>,
 }
 
@@ -44,21 +60,26 @@ pub struct FileLoadingState {
 #[derive(Resource, Default)]
 pub struct CurrentSamplerNode {
     pub node_id: Option<usize>,
+    /// A sampler has been sent to the engine and its `NodeAdded` is still due
+    pub awaiting_node: bool,
 }
 
 impl FileLoadingState {
     pub fn start_loading(&mut self) {
         self.is_loading = true;
+        self.progress = Some(LoadProgress::Fraction(0.0));
         self.error = None;
     }
 
     pub fn complete_successfully(&mut self) {
         self.is_loading = false;
+        self.progress = None;
         self.error = None;
     }
 
     pub fn fail_with_error(&mut self, error: String) {
         self.is_loading = false;
+        self.progress = None;
         self.error = Some(error);
     }
 
@@ -75,7 +96,20 @@ struct LoadedAudio {
     path: PathBuf,
 }
 
+/// A file ready to play: loaded into memory, or streamed from disk
+enum LoadedFile {
+    Samples(LoadedAudio),
+    Streamed {
+        // Boxed: it holds a whole chunk of audio inline
+        sampler: Box<StreamingSampler>,
+        path: PathBuf,
+    },
+}
+
 /// System that starts loading a file when selected
+///
+/// Files selected while a load is in progress (e.g. several dropped at once)
+/// queue up and load one after another, in order.
 #[allow(clippy::needless_pass_by_value)] // Bevy MessageReader requires by-value
 fn start_file_load_system(
     mut file_events: MessageReader<FileSelected>,
@@ -84,32 +118,42 @@ fn start_file_load_system(
     engine_info: Res<crate::AudioEngineInfo>,
 ) {
     for event in file_events.read() {
-        // Check if a load is already in progress
         if load_task.pending.is_some() {
-            info!("File load already in progress, ignoring new request");
-            continue;
+            info!("File load in progress, queueing {}", event.0.display());
         }
+        load_task.queued.push_back(event.0.clone());
+    }
 
-        let path = event.0.clone();
-        info!("Loading WAV file: {}", path.display());
+    if load_task.pending.is_some() {
+        return;
+    }
+    let Some(path) = load_task.queued.pop_front() else {
+        return;
+    };
+    info!("Loading WAV file: {}", path.display());
 
-        // Update loading state
-        loading_state.start_loading();
+    // Update loading state
+    loading_state.start_loading();
 
-        // Capture engine sample rate to pass to background thread
-        // Wait for engine initialization before loading files
-        let Some(target_sample_rate) = engine_info.sample_rate else {
-            warn!("Audio engine not yet initialized, deferring file load");
-            loading_state.fail_with_error(
-                "Audio engine not ready. Please wait a moment and try again.".to_string(),
-            );
-            continue;
-        };
+    // Capture engine sample rate to pass to background thread
+    // Wait for engine initialization before loading files
+    let Some(target_sample_rate) = engine_info.sample_rate else {
+        warn!("Audio engine not yet initialized, deferring file load");
+        loading_state.fail_with_error(
+            "Audio engine not ready. Please wait a moment and try again.".to_string(),
+        );
+        return;
+    };
 
-        // Spawn background thread to load file
-        let task = std::thread::spawn(move || load_wav_file(&path, target_sample_rate));
-        load_task.pending = Some(task);
-    }
+    // Spawn background thread to load file
+    let (progress_tx, progress_rx) = crossbeam_channel::unbounded();
+    let task = std::thread::spawn(move || {
+        load_file(&path, target_sample_rate, &mut |progress: LoadProgress| {
+            let _ = progress_tx.send(progress);
+        })
+    });
+    load_task.pending = Some(task);
+    load_task.progress_rx = Some(progress_rx);
 }
 
 /// System that polls for completed file loads
@@ -121,42 +165,76 @@ fn poll_file_load_system(
     mut playback_state: ResMut<PlaybackState>,
     mut loading_state: ResMut<FileLoadingState>,
     mut current_sampler: ResMut<CurrentSamplerNode>,
+    plugin_load: Res<crate::plugin_loading::PluginLoadState>,
+    mut recent_files: ResMut<crate::recent_files::RecentFiles>,
     mut audio_command_tx: Option<ResMut<crate::AudioCommandChannel>>,
     audio_plugin_tx: Option<Res<crate::AudioPluginChannel>>,
 ) {
+    if loading_state.is_loading
+        && let Some(progress) = load_task
+            .progress_rx
+            .as_ref()
+            .and_then(|rx| rx.try_iter().last())
+    {
+        loading_state.progress = Some(progress);
+    }
+
+    // Hold a finished load while a plugin's NodeAdded is due, so the two
+    // nodes can't be mistaken for each other, and while the previous
+    // sampler's is, so this one replaces the new node rather than the old one
+    if plugin_load.is_awaiting_node() || current_sampler.awaiting_node {
+        return;
+    }
+
     if let Some(task) = load_task.pending.take() {
         if task.is_finished() {
             match task.join() {
-                Ok(Ok(audio)) => {
-                    info!(
-                        "Successfully loaded {} frames at {}Hz",
-                        audio.samples.len() / 2,
-                        audio.sample_rate
-                    );
+                Ok(Ok(file)) => {
+                    waveform_data.clear_streaming();
 
-                    // Clone samples for audio engine (will be moved into sampler)
-                    let samples_for_engine = audio.samples.clone();
-                    let sample_rate = audio.sample_rate;
+                    let (sampler, path): (vvdaw_comms::PluginInstance, PathBuf) = match file {
+                        LoadedFile::Samples(audio) => {
+                            info!(
+                                "Successfully loaded {} frames at {}Hz",
+                                audio.samples.len() / 2,
+                                audio.sample_rate
+                            );
 
-                    // Update waveform data (for visualization)
-                    waveform_data.clear_streaming();
-                    waveform_data.samples = audio.samples;
-                    waveform_data.sample_rate = audio.sample_rate;
-                    waveform_data.needs_mesh_update = true;
+                            // Clone samples for audio engine (will be moved into sampler)
+                            let sampler = vvdaw_audio::builtin::sampler::SamplerProcessor::new(
+                                audio.samples.clone(),
+                                audio.sample_rate,
+                            );
+
+                            // Update waveform data (for visualization)
+                            waveform_data.set_samples(audio.samples, audio.sample_rate);
+                            playback_state.sample_rate = audio.sample_rate;
+                            playback_state.total_duration = samples_to_seconds(
+                                waveform_data.frame_count() as u64,
+                                audio.sample_rate,
+                            ) as f32;
+                            (Box::new(sampler), audio.path)
+                        }
+                        LoadedFile::Streamed { sampler, path } => {
+                            // No samples in memory: clear the walls of the previous file
+                            waveform_data.set_samples(Vec::new(), sampler.sample_rate());
+                            playback_state.sample_rate = sampler.sample_rate();
+                            playback_state.total_duration =
+                                samples_to_seconds(sampler.frame_count(), sampler.sample_rate())
+                                    as f32;
+                            (sampler, path)
+                        }
+                    };
 
                     // Update playback state
                     playback_state.loaded_file = Some(
-                        audio
-                            .path
-                            .file_name()
+                        path.file_name()
                             .and_then(|n| n.to_str())
                             .unwrap_or("unknown")
                             .to_string(),
                     );
-                    playback_state.sample_rate = audio.sample_rate;
-                    playback_state.total_duration =
-                        waveform_data.frame_count() as f32 / audio.sample_rate as f32;
                     playback_state.current_position = 0.0;
+                    recent_files.add(&path);
 
                     // Send sampler to audio engine
                     if let (Some(cmd_tx), Some(plugin_tx)) =
@@ -178,44 +256,28 @@ fn poll_file_load_system(
                             playback_state.status = crate::playback::PlaybackStatus::Stopped;
                         }
 
-                        // Step 2: Remove old sampler node if it exists
-                        if let Some(old_node_id) = current_sampler.node_id {
-                            info!("→ Sending RemoveNode({old_node_id}) command");
-                            if let Err(e) = cmd_tx
-                                .0
-                                .push(vvdaw_comms::AudioCommand::RemoveNode(old_node_id))
-                            {
-                                error!("✗ Failed to send RemoveNode command: {e:?}");
-                            }
-                            current_sampler.node_id = None;
-                        } else {
-                            info!("No existing sampler node to remove");
-                        }
-
-                        // Step 3: Create and send new sampler
-                        info!("→ Creating new sampler processor");
-                        let sampler = vvdaw_audio::builtin::sampler::SamplerProcessor::new(
-                            samples_for_engine,
-                            sample_rate,
+                        // Step 2: Send the new sampler, replacing the old sampler node (if any) in
+                        // the same command so a full queue can't orphan either one.
+                        // current_sampler is updated from the NodeAdded/NodeRemoved events.
+                        info!(
+                            "→ Sending sampler (replacing {:?})",
+                            current_sampler.node_id
                         );
-
-                        // Send plugin instance to audio thread
-                        info!("→ Sending plugin instance via crossbeam_channel");
-                        if let Err(e) = plugin_tx.0.send(Box::new(sampler)) {
-                            error!("✗ Failed to send sampler to audio engine: {e}");
-                            loading_state.fail_with_error(format!("Audio engine error: {e}"));
-                            return;
-                        }
-
-                        // Send AddNode command (audio thread will create the node)
-                        info!("→ Sending AddNode command");
-                        if let Err(e) = cmd_tx.0.push(vvdaw_comms::AudioCommand::AddNode) {
-                            error!("✗ Failed to send AddNode command: {e:?}");
+                        if vvdaw_comms::send_node(
+                            &mut cmd_tx.0,
+                            &plugin_tx.0,
+                            sampler,
+                            current_sampler.node_id,
+                        )
+                        .is_err()
+                        {
+                            error!("✗ Failed to send sampler: command queue full");
                             loading_state.fail_with_error(
                                 "Failed to add sampler to audio graph".to_string(),
                             );
                             return;
                         }
+                        current_sampler.awaiting_node = true;
 
                         info!("✓ All commands sent to audio engine");
                     } else {
@@ -244,82 +306,45 @@ fn poll_file_load_system(
     }
 }
 
-/// Resample stereo audio from one sample rate to another using linear interpolation
-fn resample_stereo(stereo_samples: &[f32], source_rate: u32, target_rate: u32) -> Vec<f32> {
-    use dasp::{Signal, interpolate::linear::Linear, signal};
-
-    // Early validation: handle empty input
-    if stereo_samples.is_empty() {
-        debug!("Resample called with empty input, returning empty Vec");
-        return Vec::new();
-    }
-
-    // Early validation: prevent division by zero
-    if source_rate == 0 {
-        error!("Source sample rate is 0, cannot resample");
-        return stereo_samples.to_vec();
-    }
-
-    if target_rate == 0 {
-        error!("Target sample rate is 0, cannot resample");
-        return stereo_samples.to_vec();
+/// Open a WAV file for playback, streaming it if it's too large to load
+///
+/// Streamed files aren't resampled (see `StreamingSampler`), so a rate
+/// different from the engine's is only warned about.
+fn load_file(
+    path: &Path,
+    target_sample_rate: u32,
+    on_progress: &mut dyn FnMut(LoadProgress),
+) -> Result<LoadedFile, String> {
+    let canonical_path = validate_wav_path(path)?;
+    let file_size = std::fs::metadata(&canonical_path)
+        .map_err(|e| format!("Failed to read file metadata: {e}"))?
+        .len();
+    if file_size <= STREAMING_THRESHOLD_BYTES {
+        return load_wav_file(&canonical_path, target_sample_rate, on_progress)
+            .map(LoadedFile::Samples);
     }
 
     info!(
-        "Resampling from {}Hz to {}Hz ({:.1}% speed change)",
-        source_rate,
-        target_rate,
-        ((f64::from(target_rate) / f64::from(source_rate)) - 1.0) * 100.0
+        "Streaming {:.1}MB file from disk",
+        file_size as f64 / (1024.0 * 1024.0)
     );
-
-    // Convert interleaved stereo to stereo frames [[L, R], [L, R], ...]
-    let frame_count = stereo_samples.len() / 2;
-    let stereo_frames: Vec<[f32; 2]> = stereo_samples
-        .chunks_exact(2)
-        .map(|chunk| [chunk[0], chunk[1]])
-        .collect();
-
-    // Create signal from stereo frames
-    let stereo_signal = signal::from_iter(stereo_frames.iter().copied());
-
-    // Calculate number of output frames
-    let source_rate_f64 = f64::from(source_rate);
-    let target_rate_f64 = f64::from(target_rate);
-    let output_frame_count =
-        ((frame_count as f64) * (target_rate_f64 / source_rate_f64)).ceil() as usize;
-
-    // Resample stereo signal using linear interpolation
-    let resampled_frames: Vec<[f32; 2]> = stereo_signal
-        .from_hz_to_hz(
-            Linear::new([0.0, 0.0], [0.0, 0.0]), // Stereo interpolator state
-            source_rate_f64,
-            target_rate_f64,
-        )
-        .take(output_frame_count)
-        .collect();
-
-    // Convert stereo frames back to interleaved samples
-    let mut resampled = Vec::with_capacity(output_frame_count * 2);
-    for frame in resampled_frames {
-        resampled.push(frame[0]); // Left
-        resampled.push(frame[1]); // Right
+    let sampler = StreamingSampler::open(&canonical_path).map_err(|e| e.to_string())?;
+    if sampler.sample_rate() != target_sample_rate {
+        warn!(
+            "Streamed file is {}Hz but the engine runs at {target_sample_rate}Hz - it will play at the wrong speed",
+            sampler.sample_rate()
+        );
     }
-
-    info!(
-        "✓ Resampled: {} → {} frames",
-        frame_count, output_frame_count
-    );
-
-    resampled
+    Ok(LoadedFile::Streamed {
+        sampler: Box::new(sampler),
+        path: canonical_path,
+    })
 }
 
-/// Load a WAV file and return audio data
-fn load_wav_file(path: &Path, target_sample_rate: u32) -> Result<LoadedAudio, String> {
+/// Check that `path` is an existing `.wav` file, returning its canonical path
+fn validate_wav_path(path: &Path) -> Result<PathBuf, String> {
     use std::fs;
 
-    // Validate file size (500MB limit)
-    const MAX_FILE_SIZE: u64 = 500 * 1024 * 1024;
-
     // Validate and sanitize path using canonicalization
     let path_obj = Path::new(path);
 
@@ -348,18 +373,30 @@ fn load_wav_file(path: &Path, target_sample_rate: u32) -> Result<LoadedAudio, St
     } else {
         return Err("File must have .wav extension".to_string());
     }
-    let metadata =
-        fs::metadata(&canonical_path).map_err(|e| format!("Failed to read file metadata: {e}"))?;
 
-    if metadata.len() > MAX_FILE_SIZE {
+    Ok(canonical_path)
+}
+
+/// Load a WAV file into memory and return audio data
+///
+/// Reports progress through `on_progress` while reading samples.
+fn load_wav_file(
+    path: &Path,
+    target_sample_rate: u32,
+    on_progress: &mut dyn FnMut(LoadProgress),
+) -> Result<LoadedAudio, String> {
+    let canonical_path = validate_wav_path(path)?;
+    let metadata = std::fs::metadata(&canonical_path)
+        .map_err(|e| format!("Failed to read file metadata: {e}"))?;
+
+    if metadata.len() > STREAMING_THRESHOLD_BYTES {
         return Err(format!(
-            "File too large: {:.1}MB (max 500MB)",
+            "File too large: {:.1}MB (max 500MB). Large files should be streamed with StreamingSampler, not loaded entirely into memory.",
             metadata.len() as f64 / (1024.0 * 1024.0)
         ));
     }
 
-    let mut reader =
-        WavReader::open(&canonical_path).map_err(|e| format!("Failed to open WAV file: {e}"))?;
+    let mut reader = WavReader::open(&canonical_path).map_err(wav_progress::wav_open_error)?;
 
     let spec = reader.spec();
     let sample_rate = spec.sample_rate;
@@ -373,26 +410,9 @@ fn load_wav_file(path: &Path, target_sample_rate: u32) -> Result<LoadedAudio, St
         ));
     }
 
-    // Read all samples and convert to f32
-    let raw_samples: Vec<f32> = match spec.sample_format {
-        hound::SampleFormat::Float => reader
-            .samples::<f32>()
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| format!("Failed to read samples: {e}"))?,
-        hound::SampleFormat::Int => {
-            // For 32-bit audio, we need to avoid bit shift overflow
-            let max_value = if spec.bits_per_sample == 32 {
-                2_147_483_648.0_f32 // 2^31
-            } else {
-                (1_i32 << (spec.bits_per_sample - 1)) as f32
-            };
-            reader
-                .samples::<i32>()
-                .map(|s| s.map(|v| v as f32 / max_value))
-                .collect::<Result<Vec<_>, _>>()
-                .map_err(|e| format!("Failed to read samples: {e}"))?
-        }
-    };
+    // Read all samples and convert to f32 [-1.0, 1.0]
+    let raw_samples = wav_progress::read_samples(&mut reader, metadata.len(), on_progress)
+        .map_err(|e| format!("Failed to read samples: {e}"))?;
 
     // Convert to interleaved stereo
     let stereo_samples = match channels {
@@ -412,11 +432,11 @@ fn load_wav_file(path: &Path, target_sample_rate: u32) -> Result<LoadedAudio, St
         _ => {
             // More than 2 channels: take first 2
             warn!("WAV file has {} channels, using only first 2", channels);
-            let mut stereo = Vec::with_capacity((raw_samples.len() / channels) * 2);
-            for chunk in raw_samples.chunks(channels) {
-                stereo.push(chunk[0]); // Left
-                stereo.push(chunk[1]); // Right
-            }
+            let frames = raw_samples.len() / channels;
+            let mut planar = vec![vec![0.0; frames]; 2];
+            buffer::deinterleave(&raw_samples, channels, &mut planar);
+            let mut stereo = vec![0.0; frames * 2];
+            buffer::interleave(&planar, &mut stereo);
             stereo
         }
     };
@@ -425,7 +445,11 @@ fn load_wav_file(path: &Path, target_sample_rate: u32) -> Result<LoadedAudio, St
     let (final_samples, final_sample_rate) = if sample_rate == target_sample_rate {
         (stereo_samples, sample_rate)
     } else {
-        let resampled = resample_stereo(&stereo_samples, sample_rate, target_sample_rate);
+        let resampled = vvdaw_audio::resample::resample_stereo(
+            &stereo_samples,
+            sample_rate,
+            target_sample_rate,
+        );
         (resampled, target_sample_rate)
     };
 
@@ -443,7 +467,7 @@ mod tests {
 
     #[test]
     fn test_load_wav_nonexistent_file() {
-        let result = load_wav_file(Path::new("/nonexistent/file.wav"), 48000);
+        let result = load_wav_file(Path::new("/nonexistent/file.wav"), 48000, &mut |_| {});
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("File not found"));
     }
@@ -455,7 +479,7 @@ mod tests {
         let test_file = temp_dir.join("test_invalid.txt");
         fs::write(&test_file, b"not a wav file").unwrap();
 
-        let result = load_wav_file(&test_file, 48000);
+        let result = load_wav_file(&test_file, 48000, &mut |_| {});
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Invalid file extension"));
 
@@ -466,7 +490,7 @@ mod tests {
     #[test]
     fn test_load_wav_directory_not_file() {
         let temp_dir = std::env::temp_dir();
-        let result = load_wav_file(&temp_dir, 48000);
+        let result = load_wav_file(&temp_dir, 48000, &mut |_| {});
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Path is not a file"));
     }
@@ -475,7 +499,7 @@ mod tests {
     fn test_load_wav_path_traversal_prevention() {
         // Attempting to load a file with path traversal should fail safely
         // The canonicalize step will resolve ../ and prevent traversal
-        let result = load_wav_file(Path::new("../../etc/passwd"), 48000);
+        let result = load_wav_file(Path::new("../../etc/passwd"), 48000, &mut |_| {});
         // Should fail because file doesn't exist or isn't a .wav
         assert!(result.is_err());
     }
@@ -486,7 +510,7 @@ mod tests {
         let test_file = temp_dir.join("test_no_extension");
         fs::write(&test_file, b"data").unwrap();
 
-        let result = load_wav_file(&test_file, 48000);
+        let result = load_wav_file(&test_file, 48000, &mut |_| {});
         assert!(result.is_err());
         assert!(
             result
@@ -498,6 +522,78 @@ mod tests {
         let _ = fs::remove_file(test_file);
     }
 
+    #[test]
+    fn test_load_wav_8bit_unsigned() {
+        let test_file = std::env::temp_dir().join("test_load_8bit.wav");
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 48000,
+            bits_per_sample: 8,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&test_file, spec).unwrap();
+        // Stored on disk as unsigned 0, 128 and 192
+        for sample in [-128_i8, 0, 64] {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let loaded = load_wav_file(&test_file, 48000, &mut |_| {}).unwrap();
+        assert_eq!(loaded.samples, vec![-1.0, -1.0, 0.0, 0.0, 0.5, 0.5]);
+
+        let _ = fs::remove_file(test_file);
+    }
+
+    #[test]
+    fn test_small_files_load_into_memory() {
+        let test_file = std::env::temp_dir().join("test_load_small_file.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 48000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&test_file, spec).unwrap();
+        for _ in 0..8 {
+            writer.write_sample(0_i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let Ok(LoadedFile::Samples(audio)) = load_file(&test_file, 48000, &mut |_| {}) else {
+            panic!("A small file should load into memory");
+        };
+        assert_eq!(audio.samples.len(), 8);
+
+        let _ = fs::remove_file(test_file);
+    }
+
+    #[test]
+    fn test_load_wav_64bit_float_rejected() {
+        // Hand-built header: hound can't write 64-bit float files
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&44_u32.to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16_u32.to_le_bytes());
+        bytes.extend_from_slice(&3_u16.to_le_bytes()); // WAVE_FORMAT_IEEE_FLOAT
+        bytes.extend_from_slice(&1_u16.to_le_bytes()); // mono
+        bytes.extend_from_slice(&48000_u32.to_le_bytes());
+        bytes.extend_from_slice(&(48000_u32 * 8).to_le_bytes());
+        bytes.extend_from_slice(&8_u16.to_le_bytes()); // block align
+        bytes.extend_from_slice(&64_u16.to_le_bytes()); // bits per sample
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&8_u32.to_le_bytes());
+        bytes.extend_from_slice(&0.5_f64.to_le_bytes());
+
+        let test_file = std::env::temp_dir().join("test_load_64bit_float.wav");
+        fs::write(&test_file, bytes).unwrap();
+
+        let error = load_wav_file(&test_file, 48000, &mut |_| {}).unwrap_err();
+        assert!(error.contains("only 32-bit float"), "{error}");
+
+        let _ = fs::remove_file(test_file);
+    }
+
     #[test]
     fn test_file_loading_state_lifecycle() {
         let mut state = FileLoadingState::default();
@@ -529,61 +625,4 @@ mod tests {
         state.clear_error();
         assert!(state.error.is_none());
     }
-
-    #[test]
-    fn test_resample_stereo_empty_input() {
-        let result = resample_stereo(&[], 44100, 48000);
-        assert!(result.is_empty());
-    }
-
-    #[test]
-    fn test_resample_stereo_zero_source_rate() {
-        // Should return input unchanged when source rate is 0 (defensive behavior)
-        let input = vec![0.1, -0.1, 0.2, -0.2];
-        let result = resample_stereo(&input, 0, 48000);
-        assert_eq!(result, input);
-    }
-
-    #[test]
-    fn test_resample_stereo_zero_target_rate() {
-        // Should return input unchanged when target rate is 0 (defensive behavior)
-        let input = vec![0.1, -0.1, 0.2, -0.2];
-        let result = resample_stereo(&input, 44100, 0);
-        assert_eq!(result, input);
-    }
-
-    #[test]
-    fn test_resample_stereo_valid_upsampling() {
-        // Test upsampling from 44.1kHz to 48kHz (small sample)
-        let input = vec![0.5, -0.5, 0.3, -0.3]; // 2 stereo frames
-        let result = resample_stereo(&input, 44100, 48000);
-
-        // Should have more frames after upsampling (44.1 -> 48 is ~8.8% increase)
-        // 2 frames * (48000/44100) ≈ 2.18 frames → ceil to 3 frames = 6 samples
-        assert!(!result.is_empty());
-        assert!(result.len() >= input.len());
-    }
-
-    #[test]
-    fn test_resample_stereo_valid_downsampling() {
-        // Test downsampling from 48kHz to 44.1kHz (small sample)
-        let input = vec![0.5, -0.5, 0.3, -0.3, 0.1, -0.1]; // 3 stereo frames
-        let result = resample_stereo(&input, 48000, 44100);
-
-        // Should have fewer frames after downsampling (48 -> 44.1 is ~8.2% decrease)
-        // 3 frames * (44100/48000) ≈ 2.75 frames → ceil to 3 frames = 6 samples
-        assert!(!result.is_empty());
-        assert!(result.len() <= input.len() + 2); // Allow small rounding difference
-    }
-
-    #[test]
-    fn test_resample_stereo_same_rate() {
-        // When source and target rates are the same, should be close to input
-        let input = vec![0.5, -0.5, 0.3, -0.3];
-        let result = resample_stereo(&input, 48000, 48000);
-
-        // Should have similar length (may differ slightly due to interpolation)
-        assert!(!result.is_empty());
-        assert!((result.len() as i32 - input.len() as i32).abs() <= 2);
-    }
 }
@@ -4,12 +4,16 @@
 //! - The road surface represents the timeline
 //! - Left/right walls represent stereo waveforms (guardrails)
 
-use crate::waveform::{WaveformData, WaveformMeshConfig, generate_channel_meshes};
+use crate::waveform::{
+    VERTICAL_GAIN_STEP, WaveformData, WaveformMeshConfig, WaveformStyle, generate_channel_meshes,
+};
 use bevy::asset::RenderAssetUsages;
 use bevy::light::NotShadowCaster;
 use bevy::mesh::PrimitiveTopology;
 use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
 use vvdaw_comms::{AudioEvent, EventReceiver};
+use vvdaw_core::format::samples_to_seconds;
 
 /// Resource wrapping the audio event receiver channel
 pub struct AudioEventChannel(pub EventReceiver);
@@ -45,13 +49,34 @@ pub struct HighwayPlugin;
 
 impl Plugin for HighwayPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup_highway)
+        app.add_plugins(InputManagerPlugin::<WaveformAction>::default())
+            .add_systems(Startup, (setup_highway, setup_waveform_input))
             .add_systems(Update, process_audio_events)
-            .add_systems(Update, update_waveform_meshes)
+            .add_systems(
+                Update,
+                (
+                    waveform_style_input,
+                    apply_waveform_style,
+                    update_waveform_meshes,
+                )
+                    .chain(),
+            )
             .add_systems(Update, update_playback_position);
     }
 }
 
+/// Actions for adjusting how the waveform walls are drawn
+#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
+pub enum WaveformAction {
+    GainUp,
+    GainDown,
+    ToggleColorRamp,
+}
+
+/// Marker component for the waveform style controller
+#[derive(Component)]
+struct WaveformController;
+
 /// Marker component for left channel base wall
 #[derive(Component)]
 struct LeftWallBase;
@@ -85,8 +110,8 @@ type WallQuery<'w, 's> = Query<
     ),
 >;
 
-/// Highway visual configuration
-const ROAD_WIDTH: f32 = 20.0;
+/// Highway visual configuration (`ROAD_WIDTH` is the road's half-width)
+pub const ROAD_WIDTH: f32 = 20.0;
 pub const ROAD_LENGTH: f32 = 500.0;
 /// Offset between road edge and wall position (creates gap for visual separation)
 const WALL_OFFSET: f32 = 0.25;
@@ -96,6 +121,8 @@ const ASPHALT_COLOR: Color = Color::srgb(0.25, 0.25, 0.27);
 const CONCRETE_BASE_COLOR: Color = Color::srgb(0.35, 0.35, 0.37);
 const WAVEFORM_TEAL_COLOR: Color = Color::srgb(0.2, 0.8, 0.7);
 const WAVEFORM_AMBER_COLOR: Color = Color::srgb(1.0, 0.6, 0.2);
+const WAVEFORM_TEAL_GLOW: LinearRgba = LinearRgba::rgb(0.0, 0.5, 0.4);
+const WAVEFORM_AMBER_GLOW: LinearRgba = LinearRgba::rgb(0.6, 0.3, 0.0);
 
 /// Setup the highway geometry (road + placeholder walls)
 fn setup_highway(
@@ -154,8 +181,8 @@ fn setup_highway(
         MeshMaterial3d(materials.add(StandardMaterial {
             base_color: WAVEFORM_TEAL_COLOR,
             metallic: 0.0,
-            perceptual_roughness: 0.3, // Smoother than base wall
-            emissive: bevy::color::LinearRgba::rgb(0.0, 0.5, 0.4), // Stronger teal glow for visibility
+            perceptual_roughness: 0.3,    // Smoother than base wall
+            emissive: WAVEFORM_TEAL_GLOW, // Stronger teal glow for visibility
             ..default()
         })),
         Transform::from_xyz(wall_position_left, 0.0, 0.0),
@@ -184,8 +211,8 @@ fn setup_highway(
         MeshMaterial3d(materials.add(StandardMaterial {
             base_color: WAVEFORM_AMBER_COLOR,
             metallic: 0.0,
-            perceptual_roughness: 0.3, // Smoother than base wall
-            emissive: bevy::color::LinearRgba::rgb(0.6, 0.3, 0.0), // Stronger amber glow for visibility
+            perceptual_roughness: 0.3,     // Smoother than base wall
+            emissive: WAVEFORM_AMBER_GLOW, // Stronger amber glow for visibility
             ..default()
         })),
         Transform::from_xyz(wall_position_right, 0.0, 0.0),
@@ -194,6 +221,77 @@ fn setup_highway(
     ));
 }
 
+/// Setup the waveform style keys
+fn setup_waveform_input(mut commands: Commands) {
+    let input_map = InputMap::new([
+        (WaveformAction::GainUp, KeyCode::BracketRight),
+        (WaveformAction::GainDown, KeyCode::BracketLeft),
+        (WaveformAction::ToggleColorRamp, KeyCode::KeyC),
+    ]);
+
+    commands.spawn((WaveformController, input_map));
+}
+
+/// System to adjust the waveform style from the keyboard
+///
+/// `]`/`[` raise and lower the vertical gain, `C` toggles coloring by level.
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn waveform_style_input(
+    query: Query<&ActionState<WaveformAction>, With<WaveformController>>,
+    mut style: ResMut<WaveformStyle>,
+) {
+    let Ok(action_state) = query.single() else {
+        return;
+    };
+
+    if action_state.just_pressed(&WaveformAction::GainUp) {
+        style.scale_gain(VERTICAL_GAIN_STEP);
+    }
+    if action_state.just_pressed(&WaveformAction::GainDown) {
+        style.scale_gain(VERTICAL_GAIN_STEP.recip());
+    }
+    if action_state.just_pressed(&WaveformAction::ToggleColorRamp) {
+        style.toggle_color_ramp();
+    }
+}
+
+/// Redraw the waveform walls when [`WaveformStyle`] changes
+///
+/// With a color ramp the waveform materials go white and unlit, so the
+/// per-vertex colors show as-is; without one they get their channel colors
+/// and glow back.
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn apply_waveform_style(
+    style: Res<WaveformStyle>,
+    mut waveform: ResMut<WaveformData>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    query: Query<
+        (&MeshMaterial3d<StandardMaterial>, Has<LeftWaveform>),
+        Or<(With<LeftWaveform>, With<RightWaveform>)>,
+    >,
+) {
+    if !style.is_changed() {
+        return;
+    }
+    waveform.needs_mesh_update = true;
+
+    for (material, is_left) in &query {
+        let Some(material) = materials.get_mut(&material.0) else {
+            continue;
+        };
+        let (color, glow) = if is_left {
+            (WAVEFORM_TEAL_COLOR, WAVEFORM_TEAL_GLOW)
+        } else {
+            (WAVEFORM_AMBER_COLOR, WAVEFORM_AMBER_GLOW)
+        };
+
+        let ramp = style.color_ramp.is_some();
+        material.base_color = if ramp { Color::WHITE } else { color };
+        material.emissive = if ramp { LinearRgba::BLACK } else { glow };
+        material.unlit = ramp;
+    }
+}
+
 /// Update waveform wall meshes dynamically as playback advances
 ///
 /// Creates a scrolling waveform window that follows the playback position.
@@ -201,6 +299,7 @@ fn setup_highway(
 #[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
 fn update_waveform_meshes(
     mut waveform: ResMut<WaveformData>,
+    style: Res<WaveformStyle>,
     playback: Res<crate::playback::PlaybackState>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut commands: Commands,
@@ -210,11 +309,9 @@ fn update_waveform_meshes(
     // or if force update is requested (e.g., new file loaded)
     const UPDATE_THRESHOLD: f32 = 0.1; // Update every 0.1 seconds of playback
 
-    // Only update if waveform is loaded
-    if !waveform.is_loaded() {
-        if waveform.needs_mesh_update {
-            waveform.needs_mesh_update = false;
-        }
+    // Only update if waveform is loaded, or to clear the walls when a file
+    // without one (a streamed file) replaces it
+    if !waveform.is_loaded() && !waveform.needs_mesh_update {
         return;
     }
 
@@ -233,6 +330,7 @@ fn update_waveform_meshes(
         amplitude_scale: 20.0,
         base_height: 15.0,
         window_duration: 15.0, // Show 15 seconds before and after cursor
+        style: *style,
         ..Default::default()
     };
 
@@ -296,6 +394,7 @@ fn process_audio_events(
     event_channel: Option<ResMut<AudioEventChannel>>,
     mut waveform: ResMut<WaveformData>,
     mut current_sampler: ResMut<crate::file_loading::CurrentSamplerNode>,
+    mut plugin_load: ResMut<crate::plugin_loading::PluginLoadState>,
     mut engine_info: ResMut<crate::AudioEngineInfo>,
 ) {
     // Early return if audio event channel is not available (e.g., in basic examples)
@@ -314,29 +413,129 @@ fn process_audio_events(
                 // Update waveform data with new streaming sample
                 waveform.push_streaming_peak(position, left_peak, right_peak);
             }
+            AudioEvent::PositionChanged { position } => {
+                // Seek landed - jump the highway there without waiting for playback
+                tracing::debug!("Playback position moved to frame {position}");
+                waveform.current_position = position;
+                waveform.needs_mesh_update = true;
+            }
             AudioEvent::Started => {
                 tracing::info!("Audio playback started");
             }
             AudioEvent::Stopped => {
                 tracing::info!("Audio playback stopped");
             }
-            AudioEvent::EngineInitialized { sample_rate } => {
-                tracing::info!("✓ Audio engine initialized at {}Hz", sample_rate);
+            AudioEvent::EngineInitialized {
+                sample_rate,
+                output_channels,
+                device_name,
+            } => {
+                tracing::info!(
+                    "✓ Audio engine initialized at {}Hz, {} channels on {}",
+                    sample_rate,
+                    output_channels,
+                    device_name
+                );
                 engine_info.sample_rate = Some(sample_rate);
+                engine_info.output_channels = Some(output_channels);
+                engine_info.device_name = Some(device_name);
             }
             AudioEvent::NodeAdded { node_id } => {
-                tracing::info!("✓ Sampler node added with ID: {node_id}");
-                current_sampler.node_id = Some(node_id);
+                // Only one add is in flight at a time - a plugin's or the sampler's
+                if !plugin_load.claim_node(node_id) {
+                    tracing::info!("✓ Sampler node added with ID: {node_id}");
+                    current_sampler.node_id = Some(node_id);
+                    current_sampler.awaiting_node = false;
+                }
             }
             AudioEvent::NodeRemoved { node_id } => {
-                tracing::info!("✓ Sampler node removed: {node_id}");
+                tracing::info!("✓ Node removed: {node_id}");
+                if current_sampler.node_id == Some(node_id) {
+                    current_sampler.node_id = None;
+                }
+                plugin_load.remove_node(node_id);
+            }
+            AudioEvent::NodeBypassChanged { node_id, bypassed } => {
+                tracing::info!("Node {node_id} bypassed: {bypassed}");
+            }
+            AudioEvent::NodeSoloChanged { node_id, solo } => {
+                tracing::info!("Node {node_id} soloed: {solo}");
+            }
+            AudioEvent::ParameterChanged {
+                node_id,
+                param_id,
+                value,
+            } => {
+                tracing::debug!("Node {node_id} parameter {param_id} edited: {value}");
+                plugin_load.update_parameter(node_id, param_id, value);
+            }
+            AudioEvent::PlaybackRateChanged { node_id, rate } => {
+                tracing::info!("Node {node_id} playback rate: {rate}x");
+            }
+            AudioEvent::GraphBypassChanged { bypassed } => {
+                tracing::info!("Graph bypassed: {bypassed}");
+            }
+            AudioEvent::InputMonitorChanged { enabled } => {
+                tracing::info!("Input monitoring: {enabled}");
+            }
+            AudioEvent::NodeReloaded { node_id } => {
+                tracing::info!("✓ Node reloaded: {node_id}");
+            }
+            AudioEvent::NodeMoved { node_id, before } => {
+                tracing::info!("Node {node_id} moved before {before:?}");
+            }
+            AudioEvent::LatencyChanged { frames } => {
+                tracing::info!("Graph latency: {frames} frames");
+            }
+            AudioEvent::Xrun { count } => {
+                tracing::warn!("Audio xrun detected ({count} so far)");
+            }
+            AudioEvent::DspLoad { fraction } => {
+                tracing::trace!("DSP load: {:.0}%", fraction * 100.0);
+            }
+            AudioEvent::NodeCpu { node_id, fraction } => {
+                tracing::trace!("Node {node_id} CPU: {:.0}%", fraction * 100.0);
+            }
+            AudioEvent::Clip { channel, count } => {
+                tracing::info!("Master channel {channel} clipped ({count} samples)");
+            }
+            AudioEvent::RealtimePriority { granted, detail } => {
+                if granted {
+                    tracing::info!("✓ Audio thread running with real-time priority ({detail})");
+                } else {
+                    tracing::warn!(
+                        "Real-time priority not permitted ({detail}) - audio thread running at normal priority"
+                    );
+                }
+            }
+            AudioEvent::CpuAffinity { pinned, detail } => {
+                if pinned {
+                    tracing::info!("✓ Audio thread pinned to {detail}");
+                } else {
+                    tracing::warn!("Could not pin audio thread ({detail}) - running unpinned");
+                }
             }
             AudioEvent::Error(msg) => {
                 tracing::error!("Audio error: {}", msg);
+                // A rejected add is answered with an error instead of NodeAdded
+                plugin_load.fail_pending(&msg);
+                current_sampler.awaiting_node = false;
             }
             AudioEvent::PeakLevel { .. } => {
                 // Ignore peak levels for now
             }
+            AudioEvent::NodeMeter { .. } => {
+                // Ignore node meters for now
+            }
+            AudioEvent::StereoCorrelation { .. } => {
+                // Ignore phase correlation for now
+            }
+            AudioEvent::Spectrum { bins } => {
+                tracing::trace!("Spectrum with {} bins", bins.len());
+            }
+            AudioEvent::RecordingStopped { frames_written } => {
+                tracing::info!("Recording stopped after {frames_written} frames");
+            }
         }
     }
 }
@@ -349,11 +548,9 @@ fn update_playback_position(
     waveform: Res<WaveformData>,
     mut playback: ResMut<crate::playback::PlaybackState>,
 ) {
-    // Only update if we have valid sample rate (avoid division by zero)
+    // Only update if we have valid sample rate (a zero rate would read as 0 s)
     if waveform.sample_rate > 0 {
-        // Convert frame position to seconds
-        // Position is in frames, sample_rate is frames per second
-        let position_seconds = waveform.current_position as f32 / waveform.sample_rate as f32;
-        playback.current_position = position_seconds;
+        playback.current_position =
+            samples_to_seconds(waveform.current_position, waveform.sample_rate) as f32;
     }
 }
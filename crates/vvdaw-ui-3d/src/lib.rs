@@ -11,9 +11,16 @@ use vvdaw_comms::UiChannels;
 pub mod camera;
 pub mod file_loading;
 pub mod highway;
+pub mod loop_region;
+pub mod markers;
 pub mod menu;
+pub mod parameter_panel;
 pub mod playback;
+pub mod plugin_browser;
+pub mod plugin_loading;
+pub mod recent_files;
 pub mod scene;
+pub mod scope;
 pub mod waveform;
 
 /// Resource wrapping the command sender (UI -> Audio)
@@ -66,6 +73,15 @@ pub struct AudioEngineInfo {
     /// `None` until the `EngineInitialized` event is received.
     /// File loading should wait for this to be `Some` before proceeding.
     pub sample_rate: Option<u32>,
+    /// Number of output channels the engine drives
+    ///
+    /// `None` until the `EngineInitialized` event is received. The highway
+    /// only draws the first two.
+    pub output_channels: Option<usize>,
+    /// Name of the output device the engine opened
+    ///
+    /// `None` until the `EngineInitialized` event is received.
+    pub device_name: Option<String>,
 }
 
 /// Plugin that sets up the 3D highway UI
@@ -77,92 +93,105 @@ impl Plugin for Highway3dPlugin {
             // Initialize resources
             .init_resource::<AudioEngineInfo>()
             .init_resource::<waveform::WaveformData>()
+            .init_resource::<waveform::WaveformStyle>()
             // Add our custom plugins
             .add_plugins(scene::ScenePlugin)
             .add_plugins(camera::CameraPlugin)
             .add_plugins(highway::HighwayPlugin)
             .add_plugins(menu::MenuPlugin)
             .add_plugins(playback::PlaybackPlugin)
+            .add_plugins(markers::MarkersPlugin)
+            .add_plugins(loop_region::LoopRegionPlugin)
             .add_plugins(file_loading::FileLoadingPlugin)
-            // Add cleanup system for graceful shutdown
+            .add_plugins(recent_files::RecentFilesPlugin)
+            .add_plugins(plugin_loading::PluginLoadingPlugin)
+            .add_plugins(plugin_browser::PluginBrowserPlugin)
+            .add_plugins(parameter_panel::ParameterPanelPlugin)
+            .add_plugins(scope::ScopePlugin)
+            // Shut the audio thread down cleanly on exit
             .add_systems(Last, cleanup_on_exit);
     }
 }
 
-/// System to handle graceful shutdown when `AppExit` is triggered
-///
-/// ## TEMPORARY WORKAROUND - NOT PRODUCTION QUALITY
-///
-/// This function uses `std::process::exit(0)` to force immediate termination,
-/// which is **problematic** because:
-/// - Bypasses all destructors and Drop implementations
-/// - Audio devices may not be properly released
-/// - File handles and resources are not cleaned up gracefully
-/// - Masks the underlying deadlock/hang issue
+/// How long to wait for the audio thread to acknowledge `Shutdown` on exit
 ///
-/// ### Why This Exists
-/// Without forced exit, the application hangs on shutdown when:
-/// 1. Audio thread is in a blocking state
-/// 2. egui has locks that prevent clean shutdown
-/// 3. Bevy's shutdown sequence deadlocks with audio callback
-///
-/// ### TODO: Proper Fix Required
-/// This is a temporary workaround. The proper solution requires:
-/// 1. **Investigate root cause** - Why does shutdown hang without forced exit?
-/// 2. **Add timeouts** - Audio thread joins should timeout, not block forever
-/// 3. **Interruptible audio** - Audio callback must respect shutdown signals
-/// 4. **Proper coordination** - Bevy/egui/audio should coordinate shutdown order
-/// 5. **Test shutdown** - Add automated tests that verify clean shutdown
-///
-/// See: <https://github.com/navicore/vvdaw/pull/26/> (reviewer rated this 8/10 severity)
+/// The callback runs every few milliseconds, so this only expires if the
+/// engine isn't running at all.
+const SHUTDOWN_ACK_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// System to shut the audio thread down cleanly when `AppExit` is triggered
 ///
-/// Until fixed, this allows users to exit the application without killing the process,
-/// relying on the OS to clean up resources.
+/// Sends `AudioCommand::Shutdown` and waits (up to [`SHUTDOWN_ACK_TIMEOUT`])
+/// for the `Stopped` acknowledgement, so the audio callback is idle before
+/// Bevy exits. The engine itself is stopped when it's dropped after
+/// `App::run()` returns - with its own bounded timeout - which drops the
+/// graph and lets plugins deactivate and release their COM interfaces.
 fn cleanup_on_exit(
     mut exit_events: MessageReader<AppExit>,
     mut audio_command_tx: Option<ResMut<AudioCommandChannel>>,
+    mut audio_event_rx: Option<ResMut<highway::AudioEventChannel>>,
 ) {
-    // Check if we're exiting
-    if exit_events.read().next().is_some() {
-        tracing::info!("App exit detected - forcing immediate shutdown");
+    if exit_events.read().next().is_none() {
+        return;
+    }
 
-        // Try to stop audio gracefully first
-        if let Some(tx) = &mut audio_command_tx {
-            let _ = tx.0.push(vvdaw_comms::AudioCommand::Stop);
-        }
+    let (Some(command_tx), Some(event_rx)) = (&mut audio_command_tx, &mut audio_event_rx) else {
+        return;
+    };
 
-        // Give audio thread a tiny moment to process stop command
-        // TODO: This should be a proper wait with timeout, not a sleep
-        std::thread::sleep(std::time::Duration::from_millis(10));
+    tracing::info!("App exit detected - shutting down audio");
 
-        tracing::warn!(
-            "Using std::process::exit() - see cleanup_on_exit documentation for why this is temporary"
-        );
+    // Drop stale events so an earlier Stopped isn't mistaken for the ack
+    while event_rx.0.pop().is_ok() {}
 
-        // TEMPORARY WORKAROUND: Force immediate process termination
-        // The OS will clean up all resources (audio threads, file handles, etc.)
-        // This prevents hanging but is not the correct long-term solution
-        std::process::exit(0);
+    if let Err(e) = command_tx.0.push(vvdaw_comms::AudioCommand::Shutdown) {
+        tracing::warn!("Failed to send Shutdown command: {e:?}");
+        return;
+    }
+
+    let deadline = std::time::Instant::now() + SHUTDOWN_ACK_TIMEOUT;
+    while std::time::Instant::now() < deadline {
+        while let Ok(event) = event_rx.0.pop() {
+            if matches!(event, vvdaw_comms::AudioEvent::Stopped) {
+                tracing::info!("Audio thread acknowledged shutdown");
+                return;
+            }
+        }
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+
+    tracing::warn!("Audio thread did not acknowledge shutdown within {SHUTDOWN_ACK_TIMEOUT:?}");
+}
+
+/// The 3D UI's primary window, before any saved geometry is applied
+pub fn default_window() -> Window {
+    Window {
+        title: "vvdaw - 3D Highway UI".to_string(),
+        resolution: (1920, 1080).into(),
+        ..default()
     }
 }
 
 /// Create a Bevy app configured for the 3D highway UI
 pub fn create_app(ui_channels: UiChannels) -> App {
+    create_app_with_window(ui_channels, default_window())
+}
+
+/// Like [`create_app`], opening `primary_window` (e.g. with the size and
+/// position it had last time)
+pub fn create_app_with_window(ui_channels: UiChannels, primary_window: Window) -> App {
     let mut app = App::new();
 
     // Extract channels for resources
     let command_tx = ui_channels.command_tx;
     let event_rx = ui_channels.event_rx;
     let plugin_tx = ui_channels.plugin_tx;
+    let scope_rx = ui_channels.scope_rx;
 
     app.add_plugins(
         DefaultPlugins
             .set(WindowPlugin {
-                primary_window: Some(Window {
-                    title: "vvdaw - 3D Highway UI".to_string(),
-                    resolution: (1920, 1080).into(),
-                    ..default()
-                }),
+                primary_window: Some(primary_window),
                 ..default()
             })
             .disable::<bevy::log::LogPlugin>(), // Disable Bevy's LogPlugin - tracing is initialized in main.rs
@@ -171,7 +200,8 @@ pub fn create_app(ui_channels: UiChannels) -> App {
     // Insert audio communication channels as resources
     .insert_resource(AudioCommandChannel(command_tx))
     .insert_resource(AudioPluginChannel(plugin_tx))
-    .insert_resource(highway::AudioEventChannel(event_rx));
+    .insert_resource(highway::AudioEventChannel(event_rx))
+    .insert_resource(scope::ScopeChannel(scope_rx));
 
     app
 }
@@ -0,0 +1,300 @@
+//! Loop region selection
+//!
+//! `I` and `O` set the loop's in and out points at the playhead, and `L`
+//! clears them. Once both are set the region is sent to the engine as
+//! [`AudioCommand::SetLoop`](vvdaw_comms::AudioCommand::SetLoop) and drawn as
+//! a highlighted stretch of road; clearing it turns looping off again.
+//!
+//! Like markers, the region belongs to the loaded file and is cleared when
+//! another one loads.
+
+use bevy::light::NotShadowCaster;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use tracing::info;
+use vvdaw_core::format::samples_to_seconds;
+
+use crate::highway::ROAD_WIDTH;
+use crate::playback::PlaybackState;
+use crate::waveform::{WaveformData, WaveformMeshConfig};
+
+/// Plugin that manages the loop region
+pub struct LoopRegionPlugin;
+
+impl Plugin for LoopRegionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(InputManagerPlugin::<LoopAction>::default())
+            .init_resource::<LoopRegion>()
+            .add_systems(Startup, setup_loop_region)
+            .add_systems(
+                Update,
+                (
+                    clear_loop_on_load,
+                    loop_input_system,
+                    send_loop_region,
+                    position_loop_highlight,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Actions for setting the loop region
+#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
+pub enum LoopAction {
+    SetIn,
+    SetOut,
+    Clear,
+}
+
+/// Marker component for the loop input controller
+#[derive(Component)]
+struct LoopController;
+
+/// Marker component for the highlighted stretch of road
+#[derive(Component)]
+struct LoopHighlight;
+
+/// Height of the highlight above the road, so it doesn't z-fight the asphalt
+const HIGHLIGHT_HEIGHT: f32 = 0.02;
+
+const LOOP_HIGHLIGHT_COLOR: Color = Color::srgba(0.2, 0.6, 1.0, 0.35);
+
+/// Loop in and out points, in frames
+///
+/// Either point can be set on its own; the region is only active once both
+/// are. The in-point is always kept below the out-point.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LoopRegion {
+    in_point: Option<u64>,
+    out_point: Option<u64>,
+}
+
+impl LoopRegion {
+    /// The loop's in-point, if set
+    pub const fn in_point(&self) -> Option<u64> {
+        self.in_point
+    }
+
+    /// The loop's out-point, if set
+    pub const fn out_point(&self) -> Option<u64> {
+        self.out_point
+    }
+
+    /// Set the in-point, clamped below the out-point
+    pub fn set_in(&mut self, frame: u64) {
+        self.in_point = Some(match self.out_point {
+            Some(out_point) => frame.min(out_point.saturating_sub(1)),
+            None => frame,
+        });
+    }
+
+    /// Set the out-point, clamped above the in-point
+    pub fn set_out(&mut self, frame: u64) {
+        self.out_point = Some(match self.in_point {
+            Some(in_point) => frame.max(in_point + 1),
+            None => frame,
+        });
+    }
+
+    /// Clear both points, which turns looping off
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// The active region as `(start, end)` frames, end exclusive
+    ///
+    /// `None` until both points are set.
+    pub fn region(&self) -> Option<(u64, u64)> {
+        self.in_point
+            .zip(self.out_point)
+            .filter(|(start, end)| start < end)
+    }
+
+    /// Whether `frame` lies inside the active region
+    pub fn contains(&self, frame: u64) -> bool {
+        self.region()
+            .is_some_and(|(start, end)| (start..end).contains(&frame))
+    }
+}
+
+/// Setup loop input controls and the (hidden) highlight
+fn setup_loop_region(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let input_map = InputMap::new([
+        (LoopAction::SetIn, KeyCode::KeyI),
+        (LoopAction::SetOut, KeyCode::KeyO),
+        (LoopAction::Clear, KeyCode::KeyL),
+    ]);
+    commands.spawn((LoopController, input_map));
+
+    // One world unit long; stretched along Z to the region's length
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::new(Vec3::Y, Vec2::new(ROAD_WIDTH, 0.5)))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: LOOP_HIGHLIGHT_COLOR,
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        })),
+        Transform::from_xyz(0.0, HIGHLIGHT_HEIGHT, 0.0),
+        Visibility::Hidden,
+        NotShadowCaster,
+        LoopHighlight,
+    ));
+}
+
+/// Drop the loop region when a different file is loaded
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn clear_loop_on_load(
+    playback: Res<PlaybackState>,
+    mut loop_region: ResMut<LoopRegion>,
+    mut loaded_file: Local<Option<String>>,
+) {
+    if !playback.is_changed() || playback.loaded_file == *loaded_file {
+        return;
+    }
+    loaded_file.clone_from(&playback.loaded_file);
+
+    if *loop_region != LoopRegion::default() {
+        info!("New file loaded - clearing loop region");
+        loop_region.clear();
+    }
+}
+
+/// System to set and clear the loop points from the keyboard
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn loop_input_system(
+    query: Query<&ActionState<LoopAction>, With<LoopController>>,
+    mut loop_region: ResMut<LoopRegion>,
+    waveform: Res<WaveformData>,
+) {
+    let Ok(action_state) = query.single() else {
+        return;
+    };
+    if !waveform.is_loaded() {
+        return;
+    }
+    let playhead = waveform.current_position;
+
+    // I: Loop in-point at the playhead
+    if action_state.just_pressed(&LoopAction::SetIn) {
+        loop_region.set_in(playhead);
+        info!("Loop in-point at frame {:?}", loop_region.in_point());
+    }
+
+    // O: Loop out-point at the playhead
+    if action_state.just_pressed(&LoopAction::SetOut) {
+        loop_region.set_out(playhead);
+        info!("Loop out-point at frame {:?}", loop_region.out_point());
+    }
+
+    // L: Clear the region
+    if action_state.just_pressed(&LoopAction::Clear) {
+        loop_region.clear();
+        info!("Loop region cleared");
+    }
+}
+
+/// Send the loop region to the engine whenever it changes
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn send_loop_region(
+    loop_region: Res<LoopRegion>,
+    mut audio_command_tx: Option<ResMut<crate::AudioCommandChannel>>,
+) {
+    if !loop_region.is_changed() || loop_region.is_added() {
+        return;
+    }
+    let Some(tx) = &mut audio_command_tx else {
+        return;
+    };
+
+    let command = match loop_region.region() {
+        Some((start, end)) => vvdaw_comms::AudioCommand::SetLoop {
+            start,
+            end,
+            enabled: true,
+        },
+        None => vvdaw_comms::AudioCommand::SetLoop {
+            start: 0,
+            end: 0,
+            enabled: false,
+        },
+    };
+    if let Err(e) = tx.0.push(command) {
+        tracing::error!("Failed to send SetLoop command to audio engine: {e:?}");
+    }
+}
+
+/// Stretch the highlight over the loop region, or hide it without one
+///
+/// Uses the same mapping as the waveform walls: the playhead at z = 0, with
+/// later times further down the road.
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn position_loop_highlight(
+    loop_region: Res<LoopRegion>,
+    playback: Res<PlaybackState>,
+    waveform: Res<WaveformData>,
+    mut query: Query<(&mut Transform, &mut Visibility), With<LoopHighlight>>,
+) {
+    let Ok((mut transform, mut visibility)) = query.single_mut() else {
+        return;
+    };
+    let Some((start, end)) = loop_region.region() else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    let config = WaveformMeshConfig::default();
+    let z = |frame: u64| {
+        let time = samples_to_seconds(frame, waveform.sample_rate) as f32;
+        config.time_to_z(time, playback.current_position)
+    };
+    let (z_start, z_end) = (z(start), z(end));
+
+    *visibility = Visibility::Visible;
+    transform.translation.z = (z_start + z_end) / 2.0;
+    transform.scale.z = z_start - z_end;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_needs_both_points() {
+        let mut loop_region = LoopRegion::default();
+        loop_region.set_in(100);
+        assert_eq!(loop_region.region(), None);
+
+        loop_region.set_out(500);
+        assert_eq!(loop_region.region(), Some((100, 500)));
+        assert!(loop_region.contains(100));
+        assert!(!loop_region.contains(500));
+
+        loop_region.clear();
+        assert_eq!(loop_region.region(), None);
+    }
+
+    #[test]
+    fn test_in_point_stays_below_out_point() {
+        let mut loop_region = LoopRegion::default();
+        loop_region.set_out(500);
+        loop_region.set_in(800);
+        assert_eq!(loop_region.region(), Some((499, 500)));
+
+        // An out-point at or before the in-point lands just after it
+        loop_region.set_in(200);
+        loop_region.set_out(200);
+        assert_eq!(loop_region.region(), Some((200, 201)));
+
+        // An out-point at frame 0 leaves no room for an in-point before it
+        let mut loop_region = LoopRegion::default();
+        loop_region.set_out(0);
+        loop_region.set_in(10);
+        assert_eq!(loop_region.region(), None);
+    }
+}
@@ -0,0 +1,370 @@
+//! Markers (cue points) on the timeline
+//!
+//! Named frame positions the playhead can jump between: `M` drops a marker at
+//! the playhead, `Delete` removes the one nearest it, and Tab / Shift-Tab jump
+//! to the next and previous marker. Each marker stands as a pair of posts at
+//! the road edges, scrolling with the waveform walls.
+//!
+//! Markers belong to the loaded file and are cleared when another one loads.
+//! Sessions store them as [`SessionMarker`]s - see [`Markers::from_session`].
+
+use bevy::light::NotShadowCaster;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use tracing::info;
+use vvdaw_audio::session::{Session, SessionMarker};
+use vvdaw_core::format::samples_to_seconds;
+
+use crate::highway::ROAD_WIDTH;
+use crate::playback::{PlaybackCommand, PlaybackState};
+use crate::waveform::{WaveformData, WaveformMeshConfig};
+
+/// Plugin that manages timeline markers
+pub struct MarkersPlugin;
+
+impl Plugin for MarkersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(InputManagerPlugin::<MarkerAction>::default())
+            .init_resource::<Markers>()
+            .add_systems(Startup, setup_markers)
+            .add_systems(
+                Update,
+                (
+                    clear_markers_on_load,
+                    marker_input_system,
+                    sync_marker_posts,
+                    position_marker_posts,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Actions for placing and jumping between markers
+#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
+pub enum MarkerAction {
+    Add,
+    Delete,
+    Next,
+    /// Modifier: [`MarkerAction::Next`] jumps to the previous marker instead
+    Reverse,
+}
+
+/// Marker component for the marker input controller
+#[derive(Component)]
+struct MarkerController;
+
+/// One of the two posts standing for a marker
+#[derive(Component)]
+struct MarkerPost {
+    /// The marker's position in frames
+    position: u64,
+}
+
+/// Mesh and material shared by every marker post
+#[derive(Resource)]
+struct MarkerPostAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+/// How close (in seconds) the playhead has to be for Delete to remove a marker
+const DELETE_RADIUS_SECS: f64 = 0.5;
+
+/// Marker post dimensions - taller than the walls, so they show over them
+const POST_WIDTH: f32 = 0.4;
+const POST_HEIGHT: f32 = 30.0;
+
+const MARKER_COLOR: Color = Color::srgb(0.9, 0.3, 0.9);
+const MARKER_GLOW: LinearRgba = LinearRgba::rgb(0.6, 0.1, 0.6);
+
+/// Markers on the timeline, kept in position order
+#[derive(Resource, Debug, Default)]
+pub struct Markers {
+    markers: Vec<SessionMarker>,
+    /// Markers added so far, for numbering new ones
+    added: usize,
+}
+
+impl Markers {
+    /// Markers from a session, in position order
+    pub fn from_session(session: &Session) -> Self {
+        let mut markers = Self::default();
+        for marker in &session.markers {
+            markers.insert(marker.clone());
+        }
+        markers.added = markers.markers.len();
+        markers
+    }
+
+    /// Store these markers in `session`, replacing any it has
+    pub fn store_in(&self, session: &mut Session) {
+        session.markers.clone_from(&self.markers);
+    }
+
+    /// All markers, in position order
+    pub fn as_slice(&self) -> &[SessionMarker] {
+        &self.markers
+    }
+
+    /// Whether there are no markers
+    pub fn is_empty(&self) -> bool {
+        self.markers.is_empty()
+    }
+
+    /// Remove every marker
+    pub fn clear(&mut self) {
+        self.markers.clear();
+        self.added = 0;
+    }
+
+    /// Add a marker, after any others at the same position
+    pub fn insert(&mut self, marker: SessionMarker) {
+        let index = self
+            .markers
+            .partition_point(|existing| existing.position <= marker.position);
+        self.markers.insert(index, marker);
+    }
+
+    /// Add a marker at `position` with the next free number ("Marker 3"),
+    /// returning its label
+    pub fn add_numbered(&mut self, position: u64) -> String {
+        self.added += 1;
+        let label = format!("Marker {}", self.added);
+        self.insert(SessionMarker {
+            position,
+            label: label.clone(),
+        });
+        label
+    }
+
+    /// Remove the marker nearest `position`, if one is within `radius` frames
+    pub fn remove_nearest(&mut self, position: u64, radius: u64) -> Option<SessionMarker> {
+        let (index, _) = self
+            .markers
+            .iter()
+            .enumerate()
+            .map(|(index, marker)| (index, marker.position.abs_diff(position)))
+            .filter(|&(_, distance)| distance <= radius)
+            .min_by_key(|&(_, distance)| distance)?;
+        Some(self.markers.remove(index))
+    }
+
+    /// The first marker after `position`
+    pub fn next_after(&self, position: u64) -> Option<&SessionMarker> {
+        self.markers
+            .iter()
+            .find(|marker| marker.position > position)
+    }
+
+    /// The last marker before `position`
+    pub fn previous_before(&self, position: u64) -> Option<&SessionMarker> {
+        self.markers
+            .iter()
+            .rev()
+            .find(|marker| marker.position < position)
+    }
+}
+
+/// Setup marker input controls and the shared post assets
+fn setup_markers(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let input_map = InputMap::new([
+        (MarkerAction::Add, KeyCode::KeyM),
+        (MarkerAction::Delete, KeyCode::Delete),
+        (MarkerAction::Delete, KeyCode::Backspace),
+        (MarkerAction::Next, KeyCode::Tab),
+        (MarkerAction::Reverse, KeyCode::ShiftLeft),
+        (MarkerAction::Reverse, KeyCode::ShiftRight),
+    ]);
+    commands.spawn((MarkerController, input_map));
+
+    commands.insert_resource(MarkerPostAssets {
+        mesh: meshes.add(Cuboid::new(POST_WIDTH, POST_HEIGHT, POST_WIDTH)),
+        material: materials.add(StandardMaterial {
+            base_color: MARKER_COLOR,
+            emissive: MARKER_GLOW,
+            ..default()
+        }),
+    });
+}
+
+/// Drop the markers when a different file is loaded
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn clear_markers_on_load(
+    playback: Res<PlaybackState>,
+    mut markers: ResMut<Markers>,
+    mut loaded_file: Local<Option<String>>,
+) {
+    if !playback.is_changed() || playback.loaded_file == *loaded_file {
+        return;
+    }
+    loaded_file.clone_from(&playback.loaded_file);
+
+    if !markers.is_empty() {
+        info!("New file loaded - clearing markers");
+        markers.clear();
+    }
+}
+
+/// System to add, delete and jump between markers from the keyboard
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn marker_input_system(
+    query: Query<&ActionState<MarkerAction>, With<MarkerController>>,
+    mut markers: ResMut<Markers>,
+    waveform: Res<WaveformData>,
+    mut playback_commands: MessageWriter<PlaybackCommand>,
+) {
+    let Ok(action_state) = query.single() else {
+        return;
+    };
+    if !waveform.is_loaded() {
+        return;
+    }
+    let playhead = waveform.current_position;
+
+    // M: Add a marker at the playhead
+    if action_state.just_pressed(&MarkerAction::Add) {
+        let label = markers.add_numbered(playhead);
+        info!("Added marker '{label}' at frame {playhead}");
+    }
+
+    // Delete/Backspace: Remove the marker nearest the playhead
+    if action_state.just_pressed(&MarkerAction::Delete) {
+        let radius = (DELETE_RADIUS_SECS * f64::from(waveform.sample_rate)) as u64;
+        if let Some(marker) = markers.remove_nearest(playhead, radius) {
+            info!(
+                "Removed marker '{}' at frame {}",
+                marker.label, marker.position
+            );
+        }
+    }
+
+    // Tab / Shift-Tab: Jump to the next / previous marker
+    if action_state.just_pressed(&MarkerAction::Next) {
+        let target = if action_state.pressed(&MarkerAction::Reverse) {
+            markers.previous_before(playhead)
+        } else {
+            markers.next_after(playhead)
+        };
+        if let Some(marker) = target {
+            info!("Jumping to marker '{}'", marker.label);
+            playback_commands.write(PlaybackCommand::SeekFrame(marker.position));
+        }
+    }
+}
+
+/// Respawn the marker posts when the markers change
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn sync_marker_posts(
+    mut commands: Commands,
+    markers: Res<Markers>,
+    assets: Res<MarkerPostAssets>,
+    posts: Query<Entity, With<MarkerPost>>,
+) {
+    if !markers.is_changed() {
+        return;
+    }
+
+    for entity in &posts {
+        commands.entity(entity).despawn();
+    }
+
+    // One post at each road edge, just inside the walls
+    let edge = ROAD_WIDTH - 1.0;
+    for marker in markers.as_slice() {
+        for x in [-edge, edge] {
+            commands.spawn((
+                Mesh3d(assets.mesh.clone()),
+                MeshMaterial3d(assets.material.clone()),
+                Transform::from_xyz(x, POST_HEIGHT / 2.0, 0.0),
+                NotShadowCaster,
+                MarkerPost {
+                    position: marker.position,
+                },
+            ));
+        }
+    }
+}
+
+/// Keep the marker posts where their positions are on the highway
+///
+/// Uses the same mapping as the waveform walls: the playhead at z = 0, with
+/// later times further down the road.
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn position_marker_posts(
+    playback: Res<PlaybackState>,
+    waveform: Res<WaveformData>,
+    mut posts: Query<(&MarkerPost, &mut Transform)>,
+) {
+    let config = WaveformMeshConfig::default();
+    for (post, mut transform) in &mut posts {
+        let time = samples_to_seconds(post.position, waveform.sample_rate) as f32;
+        transform.translation.z = config.time_to_z(time, playback.current_position);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marker(position: u64, label: &str) -> SessionMarker {
+        SessionMarker {
+            position,
+            label: label.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_markers_stay_in_position_order() {
+        let mut markers = Markers::default();
+        assert_eq!(markers.add_numbered(300), "Marker 1");
+        assert_eq!(markers.add_numbered(100), "Marker 2");
+        markers.insert(marker(200, "Verse"));
+
+        let positions: Vec<u64> = markers.as_slice().iter().map(|m| m.position).collect();
+        assert_eq!(positions, [100, 200, 300]);
+    }
+
+    #[test]
+    fn test_next_and_previous_skip_the_marker_under_the_playhead() {
+        let mut markers = Markers::default();
+        markers.insert(marker(100, "A"));
+        markers.insert(marker(200, "B"));
+
+        assert_eq!(markers.next_after(0).unwrap().label, "A");
+        assert_eq!(markers.next_after(100).unwrap().label, "B");
+        assert!(markers.next_after(200).is_none());
+
+        assert_eq!(markers.previous_before(200).unwrap().label, "A");
+        assert!(markers.previous_before(100).is_none());
+    }
+
+    #[test]
+    fn test_remove_nearest_within_radius() {
+        let mut markers = Markers::default();
+        markers.insert(marker(100, "A"));
+        markers.insert(marker(200, "B"));
+
+        assert!(markers.remove_nearest(500, 50).is_none());
+        assert_eq!(markers.remove_nearest(160, 50).unwrap().label, "B");
+        assert_eq!(markers.as_slice().len(), 1);
+    }
+
+    #[test]
+    fn test_session_round_trip() {
+        let mut session = Session::new("Review", 48000, 512);
+        session.markers = vec![marker(48000, "Solo"), marker(100, "Intro")];
+
+        let mut markers = Markers::from_session(&session);
+        assert_eq!(markers.as_slice()[0].label, "Intro");
+        assert_eq!(markers.add_numbered(0), "Marker 3");
+
+        markers.store_in(&mut session);
+        assert_eq!(session.markers.len(), 3);
+        assert_eq!(session.markers[0].label, "Marker 3");
+    }
+}
@@ -1,14 +1,25 @@
 //! Menu bar system for 3D UI
 //!
-//! Provides File menu for loading WAV files and controlling playback.
+//! Provides File menu for loading WAV files (including recent ones) and
+//! controlling playback, and loads WAV files and plugins dropped onto the
+//! window.
 
 use bevy::prelude::*;
+use bevy::window::FileDragAndDrop;
 use bevy_egui::{EguiContexts, EguiPlugin, EguiPrimaryContextPass, egui};
 use rfd::FileDialog;
-use std::path::PathBuf;
-use tracing::info;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+use vvdaw_audio::wav_progress::LoadProgress;
+use vvdaw_core::format::seconds_to_timecode_string;
 
+use crate::camera::CameraSettingsPanel;
+use crate::loop_region::LoopRegion;
 use crate::playback::{PlaybackCommand, PlaybackState};
+use crate::plugin_browser::PluginBrowser;
+use crate::plugin_loading::{LoadPlugin, PluginLoadState};
+use crate::recent_files::RecentFiles;
+use crate::waveform::{VERTICAL_GAIN_STEP, WaveformStyle};
 
 /// Plugin that adds menu bar to the 3D UI
 pub struct MenuPlugin;
@@ -19,7 +30,7 @@ impl Plugin for MenuPlugin {
             .init_resource::<FileDialogState>()
             .add_systems(EguiPrimaryContextPass, menu_bar_system)
             .add_systems(EguiPrimaryContextPass, hud_overlay_system)
-            .add_systems(Update, file_dialog_poll_system);
+            .add_systems(Update, (file_dialog_poll_system, file_drop_system));
     }
 }
 
@@ -27,6 +38,29 @@ impl Plugin for MenuPlugin {
 #[derive(Resource, Default)]
 struct FileDialogState {
     pending_task: Option<std::thread::JoinHandle<Option<PathBuf>>>,
+    /// Dialog picking a plugin to load
+    pending_plugin_task: Option<std::thread::JoinHandle<Option<PathBuf>>>,
+}
+
+impl FileDialogState {
+    /// Open a dialog for picking a VST3 plugin, unless one is already open
+    ///
+    /// VST3 plugins are bundles: macOS dialogs show them as files, other
+    /// platforms as the directories they are.
+    fn open_plugin_dialog(&mut self) {
+        if self.pending_plugin_task.is_some() {
+            return;
+        }
+        let task = std::thread::spawn(|| {
+            let dialog = FileDialog::new().set_title("Load VST3 Plugin");
+            if cfg!(target_os = "macos") {
+                dialog.add_filter("VST3 Plugin", &["vst3"]).pick_file()
+            } else {
+                dialog.pick_folder()
+            }
+        });
+        self.pending_plugin_task = Some(task);
+    }
 }
 
 /// Message sent when a file is selected
@@ -35,11 +69,19 @@ pub struct FileSelected(pub PathBuf);
 impl Message for FileSelected {}
 
 /// Menu bar system
+#[allow(clippy::too_many_arguments)] // Bevy system parameters
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
 fn menu_bar_system(
     mut contexts: EguiContexts,
     mut file_dialog: ResMut<FileDialogState>,
     mut app_exit: MessageWriter<AppExit>,
     mut playback_commands: MessageWriter<PlaybackCommand>,
+    mut waveform_style: ResMut<WaveformStyle>,
+    mut loop_region: ResMut<LoopRegion>,
+    mut plugin_browser: ResMut<PluginBrowser>,
+    mut camera_settings_panel: ResMut<CameraSettingsPanel>,
+    recent_files: Res<RecentFiles>,
+    mut file_selected: MessageWriter<FileSelected>,
 ) -> Result {
     egui::TopBottomPanel::top("menu_bar").show(contexts.ctx_mut()?, |ui| {
         ui.horizontal(|ui| {
@@ -59,6 +101,20 @@ fn menu_bar_system(
                     ui.close();
                 }
 
+                ui.menu_button("Recent Files", |ui| {
+                    recent_files_menu(ui, &recent_files, &mut file_selected);
+                });
+
+                if ui.button("Load VST3 Plugin...").clicked() {
+                    file_dialog.open_plugin_dialog();
+                    ui.close();
+                }
+
+                if ui.button("Browse Plugins...").clicked() {
+                    plugin_browser.open = true;
+                    ui.close();
+                }
+
                 ui.separator();
 
                 if ui.button("Exit").clicked() {
@@ -77,13 +133,53 @@ fn menu_bar_system(
                     playback_commands.write(PlaybackCommand::Stop);
                     ui.close();
                 }
+
+                if ui.button("Go to Start  [Home]").clicked() {
+                    playback_commands.write(PlaybackCommand::SeekFrame(0));
+                    ui.close();
+                }
+
+                ui.separator();
+
+                let has_loop = loop_region.region().is_some();
+                if ui
+                    .add_enabled(has_loop, egui::Button::new("Clear Loop  [L]"))
+                    .clicked()
+                {
+                    loop_region.clear();
+                    ui.close();
+                }
             });
 
             ui.menu_button("View", |ui| {
-                if ui.button("Toggle Camera Mode  [Tab]").clicked() {
+                if ui.button("Toggle Camera Mode").clicked() {
                     // TODO: Toggle camera mode
                     ui.close();
                 }
+
+                if ui.button("Camera Settings...").clicked() {
+                    camera_settings_panel.open = true;
+                    ui.close();
+                }
+
+                ui.separator();
+
+                if ui.button("Taller Waveform  []]").clicked() {
+                    waveform_style.scale_gain(VERTICAL_GAIN_STEP);
+                }
+
+                if ui.button("Shorter Waveform  [[]").clicked() {
+                    waveform_style.scale_gain(VERTICAL_GAIN_STEP.recip());
+                }
+
+                let color_by_level = waveform_style.color_ramp.is_some();
+                if ui
+                    .selectable_label(color_by_level, "Color By Level  [C]")
+                    .clicked()
+                {
+                    waveform_style.toggle_color_ramp();
+                    ui.close();
+                }
             });
 
             ui.label("|");
@@ -97,12 +193,42 @@ fn menu_bar_system(
     Ok(())
 }
 
+/// Recent files submenu, newest first
+///
+/// Files that have gone missing since they were loaded are greyed out.
+fn recent_files_menu(
+    ui: &mut egui::Ui,
+    recent_files: &RecentFiles,
+    file_selected: &mut MessageWriter<FileSelected>,
+) {
+    if recent_files.files.is_empty() {
+        ui.label("No recent files");
+        return;
+    }
+
+    for path in recent_files.newest_first() {
+        let name = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        let response = ui
+            .add_enabled(path.is_file(), egui::Button::new(name))
+            .on_hover_text(path.display().to_string())
+            .on_disabled_hover_text(format!("File not found: {}", path.display()));
+        if response.clicked() {
+            file_selected.write(FileSelected(path.to_path_buf()));
+            ui.close();
+        }
+    }
+}
+
 /// HUD overlay system
 #[allow(clippy::needless_pass_by_value)]
 fn hud_overlay_system(
     mut contexts: EguiContexts,
     playback_state: Res<PlaybackState>,
     mut loading_state: ResMut<crate::file_loading::FileLoadingState>,
+    mut plugin_load: ResMut<PluginLoadState>,
 ) -> Result {
     egui::Window::new("Status")
         .title_bar(false)
@@ -111,9 +237,20 @@ fn hud_overlay_system(
         .anchor(egui::Align2::LEFT_BOTTOM, [10.0, -10.0])
         .show(contexts.ctx_mut()?, |ui| {
             ui.vertical(|ui| {
-                // Loading status
+                // Loading status - a spinner if the file's length is unknown
                 if loading_state.is_loading {
-                    ui.colored_label(egui::Color32::YELLOW, "⏳ Loading...");
+                    if let Some(LoadProgress::Fraction(fraction)) = loading_state.progress {
+                        ui.add(
+                            egui::ProgressBar::new(fraction)
+                                .desired_width(200.0)
+                                .show_percentage(),
+                        );
+                    } else {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.colored_label(egui::Color32::YELLOW, "Loading...");
+                        });
+                    }
                 }
 
                 // Error display with dismiss button
@@ -126,6 +263,25 @@ fn hud_overlay_system(
                     });
                 }
 
+                // Plugin loading status
+                if plugin_load.is_loading() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.colored_label(egui::Color32::YELLOW, "Loading plugin...");
+                    });
+                }
+                if let Some(error) = plugin_load.error.clone() {
+                    ui.horizontal(|ui| {
+                        ui.colored_label(egui::Color32::RED, format!("❌ Plugin: {error}"));
+                        if ui.small_button("✖").clicked() {
+                            plugin_load.clear_error();
+                        }
+                    });
+                }
+                for plugin in &plugin_load.loaded {
+                    ui.label(format!("Plugin: {} (node {})", plugin.name, plugin.node_id));
+                }
+
                 // Playback status
                 let status_text = match playback_state.status {
                     crate::playback::PlaybackStatus::Stopped => "⏹ Stopped",
@@ -135,13 +291,10 @@ fn hud_overlay_system(
                 ui.label(status_text);
 
                 // Time display
-                let current_min = (playback_state.current_position / 60.0) as u32;
-                let current_sec = (playback_state.current_position % 60.0) as u32;
-                let total_min = (playback_state.total_duration / 60.0) as u32;
-                let total_sec = (playback_state.total_duration % 60.0) as u32;
-
                 ui.label(format!(
-                    "Time: {current_min:02}:{current_sec:02} / {total_min:02}:{total_sec:02}"
+                    "Time: {} / {}",
+                    seconds_to_timecode_string(f64::from(playback_state.current_position)),
+                    seconds_to_timecode_string(f64::from(playback_state.total_duration))
                 ));
 
                 // Loaded file
@@ -159,11 +312,78 @@ fn hud_overlay_system(
     Ok(())
 }
 
+/// What a file dropped onto the window loads as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DroppedFileKind {
+    Wav,
+    Vst3Plugin,
+}
+
+impl DroppedFileKind {
+    /// Classify a dropped path by its extension (case-insensitive)
+    fn of(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "wav" => Some(Self::Wav),
+            "vst3" => Some(Self::Vst3Plugin),
+            _ => None,
+        }
+    }
+}
+
+/// System to load files dropped onto the window
+///
+/// WAV files load as if picked in the file dialog and VST3 bundles as
+/// plugins. Files dropped together queue up in the order they arrive.
+#[allow(clippy::needless_pass_by_value)] // Bevy MessageReader requires by-value
+fn file_drop_system(
+    mut drops: MessageReader<FileDragAndDrop>,
+    mut file_selected: MessageWriter<FileSelected>,
+    mut load_plugin: MessageWriter<LoadPlugin>,
+    mut loading_state: ResMut<crate::file_loading::FileLoadingState>,
+) {
+    for event in drops.read() {
+        let FileDragAndDrop::DroppedFile { path_buf, .. } = event else {
+            continue;
+        };
+
+        match DroppedFileKind::of(path_buf) {
+            Some(DroppedFileKind::Wav) => {
+                info!("WAV file dropped: {}", path_buf.display());
+                file_selected.write(FileSelected(path_buf.clone()));
+            }
+            Some(DroppedFileKind::Vst3Plugin) => {
+                info!("Plugin dropped: {}", path_buf.display());
+                load_plugin.write(LoadPlugin(path_buf.clone()));
+            }
+            None => {
+                warn!("Ignoring dropped file: {}", path_buf.display());
+                loading_state.fail_with_error(format!(
+                    "Can't load {} - drop a .wav file or a .vst3 plugin",
+                    path_buf.display()
+                ));
+            }
+        }
+    }
+}
+
 /// System to poll file dialog results
 fn file_dialog_poll_system(
     mut file_dialog: ResMut<FileDialogState>,
     mut file_selected: MessageWriter<FileSelected>,
+    mut load_plugin: MessageWriter<LoadPlugin>,
 ) {
+    if let Some(task) = file_dialog.pending_plugin_task.take() {
+        if task.is_finished() {
+            if let Ok(Some(path)) = task.join() {
+                info!("Plugin selected: {}", path.display());
+                load_plugin.write(LoadPlugin(path));
+            }
+        } else {
+            file_dialog.pending_plugin_task = Some(task);
+        }
+    }
+
     if let Some(task) = file_dialog.pending_task.take() {
         if task.is_finished() {
             if let Ok(Some(path)) = task.join() {
@@ -176,3 +396,22 @@ fn file_dialog_poll_system(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dropped_files_are_classified_by_extension() {
+        assert_eq!(
+            DroppedFileKind::of(Path::new("/music/Take 1.WAV")),
+            Some(DroppedFileKind::Wav)
+        );
+        assert_eq!(
+            DroppedFileKind::of(Path::new("/plugins/Reverb.vst3")),
+            Some(DroppedFileKind::Vst3Plugin)
+        );
+        assert_eq!(DroppedFileKind::of(Path::new("/music/take.mp3")), None);
+        assert_eq!(DroppedFileKind::of(Path::new("/music/wav")), None);
+    }
+}
@@ -0,0 +1,214 @@
+//! Parameter control panel
+//!
+//! Shows a slider per parameter of a loaded plugin, generated from the
+//! parameter list read when it loaded (see [`crate::plugin_loading`]).
+//! Moving a slider sends `AudioCommand::SetParameter`; discrete parameters
+//! snap to their steps and show the plugin's text for the current step, and
+//! double-clicking a slider resets it to the parameter's default.
+//!
+//! The panel appears once a plugin is in the graph; with several, a picker
+//! selects which one to edit.
+
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::plugin_loading::{LoadedPlugin, PluginLoadState, PluginParameter};
+
+/// Plugin that adds the parameter control panel
+pub struct ParameterPanelPlugin;
+
+impl Plugin for ParameterPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ParameterPanel>()
+            .add_systems(EguiPrimaryContextPass, parameter_panel_system);
+    }
+}
+
+/// Resource tracking which plugin the panel edits
+#[derive(Resource, Debug, Default)]
+pub struct ParameterPanel {
+    /// Node ID of the plugin picked in the panel
+    pub selected: Option<usize>,
+}
+
+impl ParameterPanel {
+    /// The node to edit: the one picked if it's still loaded, otherwise the
+    /// plugin added last
+    pub fn selected_in(&self, plugins: &[LoadedPlugin]) -> Option<usize> {
+        self.selected
+            .filter(|&node_id| plugins.iter().any(|plugin| plugin.node_id == node_id))
+            .or_else(|| plugins.last().map(|plugin| plugin.node_id))
+    }
+}
+
+/// Draw a slider for `parameter`, returning whether its value changed
+fn parameter_slider(ui: &mut egui::Ui, parameter: &mut PluginParameter) -> bool {
+    let response = ui
+        .horizontal(|ui| {
+            let info = &parameter.info;
+            let mut slider =
+                egui::Slider::new(&mut parameter.value, info.min_value..=info.max_value)
+                    .text(info.name.as_str());
+            if info.is_discrete() {
+                let step = (info.max_value - info.min_value) / info.step_count as f32;
+                slider = slider.step_by(f64::from(step));
+            }
+
+            let response = ui.add(slider);
+            if let Some(text) = parameter.display() {
+                ui.weak(text);
+            }
+            response
+        })
+        .inner;
+
+    if response.double_clicked() {
+        parameter.value = parameter.info.default_value;
+        return true;
+    }
+    response.changed()
+}
+
+/// Snap a moved slider's value to its parameter's range and steps, and send
+/// it to the plugin on `node_id` if the audio engine is running
+fn commit_parameter(
+    parameter: &mut PluginParameter,
+    node_id: usize,
+    tx: Option<&mut vvdaw_comms::CommandSender>,
+) {
+    parameter.value = parameter.info.constrain(parameter.value);
+
+    let Some(tx) = tx else {
+        return;
+    };
+    let command =
+        vvdaw_comms::AudioCommand::SetParameter(node_id, parameter.info.id, parameter.value);
+    if let Err(e) = tx.push(command) {
+        tracing::error!("Failed to send SetParameter command: {e:?}");
+    }
+}
+
+/// Parameter panel system
+fn parameter_panel_system(
+    mut contexts: EguiContexts,
+    mut panel: ResMut<ParameterPanel>,
+    mut plugin_load: ResMut<PluginLoadState>,
+    mut audio_command_tx: Option<ResMut<crate::AudioCommandChannel>>,
+) -> Result {
+    let Some(mut selected) = panel.selected_in(&plugin_load.loaded) else {
+        return Ok(());
+    };
+
+    egui::Window::new("Parameters")
+        .default_width(320.0)
+        .show(contexts.ctx_mut()?, |ui| {
+            // Plugin picker
+            let selected_name = plugin_load
+                .loaded
+                .iter()
+                .find(|plugin| plugin.node_id == selected)
+                .map(|plugin| plugin.name.clone())
+                .unwrap_or_default();
+            egui::ComboBox::from_label("Plugin")
+                .selected_text(selected_name)
+                .show_ui(ui, |ui| {
+                    for plugin in &plugin_load.loaded {
+                        ui.selectable_value(
+                            &mut selected,
+                            plugin.node_id,
+                            format!("{} (node {})", plugin.name, plugin.node_id),
+                        );
+                    }
+                });
+            ui.separator();
+
+            let Some(plugin) = plugin_load.plugin_mut(selected) else {
+                return;
+            };
+            if plugin.parameters.is_empty() {
+                ui.label("No parameters.");
+                return;
+            }
+
+            egui::ScrollArea::vertical()
+                .max_height(400.0)
+                .show(ui, |ui| {
+                    for parameter in &mut plugin.parameters {
+                        if parameter_slider(ui, parameter) {
+                            commit_parameter(
+                                parameter,
+                                plugin.node_id,
+                                audio_command_tx.as_deref_mut().map(|tx| &mut tx.0),
+                            );
+                        }
+                    }
+                });
+        });
+
+    if panel.selected != Some(selected) {
+        panel.selected = Some(selected);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vvdaw_plugin::ParameterInfo;
+
+    fn loaded(node_id: usize) -> LoadedPlugin {
+        LoadedPlugin {
+            node_id,
+            name: format!("Plugin {node_id}"),
+            parameters: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_selection_falls_back_to_the_newest_plugin() {
+        let mut panel = ParameterPanel::default();
+        assert_eq!(panel.selected_in(&[]), None);
+
+        let plugins = [loaded(2), loaded(5)];
+        assert_eq!(panel.selected_in(&plugins), Some(5));
+
+        panel.selected = Some(2);
+        assert_eq!(panel.selected_in(&plugins), Some(2));
+
+        // The picked plugin was removed
+        panel.selected = Some(9);
+        assert_eq!(panel.selected_in(&plugins), Some(5));
+    }
+
+    #[test]
+    fn test_moved_slider_sends_snapped_value() {
+        let (mut ui, mut audio) = vvdaw_comms::create_channels(16);
+        let mut parameter = PluginParameter {
+            info: ParameterInfo {
+                id: 4,
+                name: "Mode".to_string(),
+                min_value: 0.0,
+                max_value: 1.0,
+                default_value: 0.0,
+                step_count: 2,
+            },
+            value: 0.6,
+            step_labels: Vec::new(),
+        };
+
+        commit_parameter(&mut parameter, 3, Some(&mut ui.command_tx));
+        assert_eq!(parameter.value, 0.5);
+        match audio.command_rx.pop() {
+            Ok(vvdaw_comms::AudioCommand::SetParameter(node_id, param_id, value)) => {
+                assert_eq!((node_id, param_id, value), (3, 4, 0.5));
+            }
+            other => panic!("Expected SetParameter, got {other:?}"),
+        }
+
+        // Without an engine the value is still snapped, but nothing is sent
+        parameter.value = 0.9;
+        commit_parameter(&mut parameter, 3, None);
+        assert_eq!(parameter.value, 1.0);
+        assert!(audio.command_rx.pop().is_err());
+    }
+}
@@ -1,9 +1,16 @@
 //! Playback state and control systems
 
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::EguiContexts;
 use leafwing_input_manager::prelude::*;
 use tracing::info;
 
+use crate::camera::FlightCamera;
+use crate::loop_region::LoopRegion;
+use crate::waveform::{WaveformData, WaveformMeshConfig};
+use vvdaw_core::format::samples_to_seconds;
+
 /// Plugin that manages playback state
 pub struct PlaybackPlugin;
 
@@ -11,9 +18,17 @@ impl Plugin for PlaybackPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(InputManagerPlugin::<PlaybackAction>::default())
             .init_resource::<PlaybackState>()
+            .init_resource::<NudgeConfig>()
             .add_message::<PlaybackCommand>()
             .add_systems(Startup, setup_playback_input)
-            .add_systems(Update, keyboard_input_system)
+            .add_systems(
+                Update,
+                (
+                    keyboard_input_system,
+                    nudge_input_system,
+                    highway_click_seek,
+                ),
+            )
             .add_systems(Update, handle_playback_commands);
     }
 }
@@ -24,8 +39,43 @@ pub enum PlaybackAction {
     Toggle,
     Stop,
     Exit,
+    NudgeBack,
+    NudgeForward,
+    /// Modifier: nudge by [`NudgeConfig::fine_frames`]
+    Fine,
+    /// Modifier: nudge by [`NudgeConfig::coarse_frames`]
+    Coarse,
+    JumpToStart,
+    JumpToEnd,
+}
+
+/// How far the arrow keys move the playhead per step, in frames
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NudgeConfig {
+    /// Step with no modifier held
+    pub frames: u64,
+    /// Step with Shift held
+    pub fine_frames: u64,
+    /// Step with Ctrl held
+    pub coarse_frames: u64,
+}
+
+impl Default for NudgeConfig {
+    fn default() -> Self {
+        Self {
+            frames: 4800,         // A tenth of a second at 48 kHz
+            fine_frames: 1,       // Frame-accurate
+            coarse_frames: 48000, // A second at 48 kHz
+        }
+    }
 }
 
+/// How long a nudge key is held before it starts repeating (seconds)
+const NUDGE_REPEAT_DELAY: f32 = 0.25;
+
+/// Steps per second while a nudge key repeats
+const NUDGE_REPEAT_RATE: f32 = 30.0;
+
 /// Marker component for playback controller
 #[derive(Component)]
 struct PlaybackController;
@@ -65,8 +115,9 @@ pub enum PlaybackCommand {
     Play,
     Pause,
     Stop,
-    Toggle,    // Play if stopped/paused, pause if playing
-    Seek(f32), // Jump to position in seconds
+    Toggle,         // Play if stopped/paused, pause if playing
+    Seek(f32),      // Jump to position in seconds
+    SeekFrame(u64), // Jump to an exact frame
 }
 
 impl Message for PlaybackCommand {}
@@ -78,6 +129,14 @@ fn setup_playback_input(mut commands: Commands) {
         (PlaybackAction::Toggle, KeyCode::Space),
         (PlaybackAction::Stop, KeyCode::KeyX),
         (PlaybackAction::Exit, KeyCode::Escape),
+        (PlaybackAction::NudgeBack, KeyCode::ArrowLeft),
+        (PlaybackAction::NudgeForward, KeyCode::ArrowRight),
+        (PlaybackAction::Fine, KeyCode::ShiftLeft),
+        (PlaybackAction::Fine, KeyCode::ShiftRight),
+        (PlaybackAction::Coarse, KeyCode::ControlLeft),
+        (PlaybackAction::Coarse, KeyCode::ControlRight),
+        (PlaybackAction::JumpToStart, KeyCode::Home),
+        (PlaybackAction::JumpToEnd, KeyCode::End),
     ]);
 
     commands.spawn((PlaybackController, input_map));
@@ -111,13 +170,165 @@ fn keyboard_input_system(
     }
 }
 
+/// Key-repeat state for a held nudge key
+#[derive(Default)]
+struct NudgeRepeat {
+    /// Direction of the key being held (-1, 0 or 1)
+    direction: i64,
+    /// Seconds the key has been held
+    held: f32,
+    /// Steps already taken since the key went down
+    taken: u64,
+}
+
+impl NudgeRepeat {
+    /// Steps to move this frame, signed, for a key held in `direction`
+    ///
+    /// Pressing a key steps once; holding it steps again after
+    /// [`NUDGE_REPEAT_DELAY`], then [`NUDGE_REPEAT_RATE`] times a second, so
+    /// a held key scrubs steadily whatever the frame rate.
+    fn steps(&mut self, direction: i64, delta_secs: f32) -> i64 {
+        if direction != self.direction {
+            // Key pressed, released or reversed
+            *self = Self {
+                direction,
+                held: 0.0,
+                taken: 1,
+            };
+            return direction;
+        }
+        if direction == 0 {
+            return 0;
+        }
+
+        self.held += delta_secs;
+        let due = ((self.held - NUDGE_REPEAT_DELAY).max(0.0) * NUDGE_REPEAT_RATE) as u64 + 1;
+        let steps = due.saturating_sub(self.taken);
+        self.taken = due;
+        direction * steps as i64
+    }
+}
+
+/// System to nudge the playhead with the arrow keys, and jump with Home/End
+///
+/// Steps are counted in frames from the last known position, so fine nudges
+/// land on exact frames. Shift nudges by [`NudgeConfig::fine_frames`], Ctrl by
+/// [`NudgeConfig::coarse_frames`].
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn nudge_input_system(
+    time: Res<Time>,
+    query: Query<&ActionState<PlaybackAction>, With<PlaybackController>>,
+    nudge: Res<NudgeConfig>,
+    waveform: Res<WaveformData>,
+    mut playback_commands: MessageWriter<PlaybackCommand>,
+    mut repeat: Local<NudgeRepeat>,
+) {
+    let Ok(action_state) = query.single() else {
+        return;
+    };
+    if !waveform.is_loaded() {
+        return;
+    }
+    let end = waveform.frame_count() as u64;
+
+    if action_state.just_pressed(&PlaybackAction::JumpToStart) {
+        playback_commands.write(PlaybackCommand::SeekFrame(0));
+        return;
+    }
+    if action_state.just_pressed(&PlaybackAction::JumpToEnd) {
+        playback_commands.write(PlaybackCommand::SeekFrame(end));
+        return;
+    }
+
+    let direction = i64::from(action_state.pressed(&PlaybackAction::NudgeForward))
+        - i64::from(action_state.pressed(&PlaybackAction::NudgeBack));
+    let steps = repeat.steps(direction, time.delta_secs());
+    if steps == 0 {
+        return;
+    }
+
+    let step = if action_state.pressed(&PlaybackAction::Fine) {
+        nudge.fine_frames
+    } else if action_state.pressed(&PlaybackAction::Coarse) {
+        nudge.coarse_frames
+    } else {
+        nudge.frames
+    };
+    let distance = step.saturating_mul(steps.unsigned_abs());
+    let frame = if steps > 0 {
+        waveform.current_position.saturating_add(distance).min(end)
+    } else {
+        waveform.current_position.saturating_sub(distance)
+    };
+
+    playback_commands.write(PlaybackCommand::SeekFrame(frame));
+}
+
+/// Jump playback to the point on the highway under the cursor when it's clicked
+///
+/// Casts a ray from the cursor onto the road plane (y = 0) and converts the hit's
+/// Z coordinate to a track time, the same mapping the waveform walls use.
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn highway_click_seek(
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<FlightCamera>>,
+    state: Res<PlaybackState>,
+    waveform: Res<WaveformData>,
+    mut contexts: EguiContexts,
+    mut playback_commands: MessageWriter<PlaybackCommand>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) || !waveform.is_loaded() {
+        return;
+    }
+
+    // Clicks on the menu bar or status window belong to egui
+    if contexts
+        .ctx_mut()
+        .is_ok_and(|ctx| ctx.is_pointer_over_area())
+    {
+        return;
+    }
+
+    let (Ok(window), Ok((camera, camera_transform))) = (windows.single(), cameras.single()) else {
+        return;
+    };
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+    let Ok(ray) = camera.viewport_to_world(camera_transform, cursor) else {
+        return;
+    };
+    let Some(distance) = ray.intersect_plane(Vec3::ZERO, InfinitePlane3d::new(Vec3::Y)) else {
+        return; // Looking away from the road
+    };
+
+    let z = ray.get_point(distance).z;
+    let track_time = WaveformMeshConfig::default()
+        .z_to_time(z, state.current_position)
+        .clamp(0.0, state.total_duration);
+
+    playback_commands.write(PlaybackCommand::Seek(track_time));
+}
+
 /// System to handle playback commands
 fn handle_playback_commands(
     mut commands: MessageReader<PlaybackCommand>,
     mut state: ResMut<PlaybackState>,
+    mut waveform: ResMut<WaveformData>,
+    loop_region: Res<LoopRegion>,
     mut audio_command_tx: Option<ResMut<crate::AudioCommandChannel>>,
 ) {
     for command in commands.read() {
+        let starts_playback = match command {
+            PlaybackCommand::Play => true,
+            PlaybackCommand::Toggle => state.status != PlaybackStatus::Playing,
+            _ => false,
+        };
+        if starts_playback {
+            enter_loop_region(&loop_region, &mut waveform, audio_command_tx.as_deref_mut());
+        }
+
         match command {
             PlaybackCommand::Play => {
                 info!("Play command");
@@ -146,11 +357,14 @@ fn handle_playback_commands(
                 state.status = PlaybackStatus::Stopped;
                 state.current_position = 0.0;
 
-                // Send Stop command to audio engine
-                if let Some(tx) = &mut audio_command_tx
-                    && let Err(e) = tx.0.push(vvdaw_comms::AudioCommand::Stop)
-                {
-                    tracing::error!("Failed to send Stop command to audio engine: {e:?}");
+                // Send Stop command to audio engine, then rewind to the start
+                if let Some(tx) = &mut audio_command_tx {
+                    if let Err(e) = tx.0.push(vvdaw_comms::AudioCommand::Stop) {
+                        tracing::error!("Failed to send Stop command to audio engine: {e:?}");
+                    }
+                    if let Err(e) = tx.0.push(vvdaw_comms::AudioCommand::Seek(0)) {
+                        tracing::error!("Failed to send Seek command to audio engine: {e:?}");
+                    }
                 }
             }
             PlaybackCommand::Toggle => {
@@ -182,8 +396,80 @@ fn handle_playback_commands(
             PlaybackCommand::Seek(position) => {
                 info!("Seek to {position}s");
                 state.current_position = position.clamp(0.0, state.total_duration);
-                // TODO: Implement seeking in sampler
+
+                // The engine confirms with PositionChanged once the sampler has moved
+                let frame = (state.current_position * state.sample_rate as f32) as u64;
+                if let Some(tx) = &mut audio_command_tx
+                    && let Err(e) = tx.0.push(vvdaw_comms::AudioCommand::Seek(frame))
+                {
+                    tracing::error!("Failed to send Seek command to audio engine: {e:?}");
+                }
+            }
+            PlaybackCommand::SeekFrame(frame) => {
+                let frame = (*frame).min(waveform.frame_count() as u64);
+                tracing::debug!("Seek to frame {frame}");
+
+                // Move the highway now, so repeated nudges build on each other
+                // instead of on a position the engine hasn't confirmed yet
+                waveform.current_position = frame;
+                waveform.needs_mesh_update = true;
+                state.current_position = samples_to_seconds(frame, waveform.sample_rate) as f32;
+
+                if let Some(tx) = &mut audio_command_tx
+                    && let Err(e) = tx.0.push(vvdaw_comms::AudioCommand::Seek(frame))
+                {
+                    tracing::error!("Failed to send Seek command to audio engine: {e:?}");
+                }
             }
         }
     }
 }
+
+/// Move the playhead to the loop's start if it's outside the loop region
+///
+/// Called as playback starts, so pressing play with a region set plays the
+/// loop rather than running up to it from wherever the playhead was.
+fn enter_loop_region(
+    loop_region: &LoopRegion,
+    waveform: &mut WaveformData,
+    audio_command_tx: Option<&mut crate::AudioCommandChannel>,
+) {
+    let Some((start, _)) = loop_region.region() else {
+        return;
+    };
+    if loop_region.contains(waveform.current_position) {
+        return;
+    }
+
+    info!("Starting playback at loop in-point (frame {start})");
+    waveform.current_position = start;
+    waveform.needs_mesh_update = true;
+    if let Some(tx) = audio_command_tx
+        && let Err(e) = tx.0.push(vvdaw_comms::AudioCommand::Seek(start))
+    {
+        tracing::error!("Failed to send Seek command to audio engine: {e:?}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nudge_steps_once_then_repeats_while_held() {
+        let mut repeat = NudgeRepeat::default();
+        assert_eq!(repeat.steps(0, 0.016), 0);
+
+        // Press: one step, then nothing until the repeat delay passes
+        assert_eq!(repeat.steps(1, 0.016), 1);
+        assert_eq!(repeat.steps(1, 0.125), 0);
+        assert_eq!(repeat.steps(1, 0.25), 3); // 0.125 s into repeating at 30/s
+
+        // A long frame catches up rather than dropping steps
+        assert_eq!(repeat.steps(1, 0.5), 15);
+
+        // Reversing starts over in the other direction
+        assert_eq!(repeat.steps(-1, 0.016), -1);
+        assert_eq!(repeat.steps(0, 0.016), 0);
+    }
+}
@@ -0,0 +1,220 @@
+//! Plugin browser panel
+//!
+//! Lists the VST3 plugins installed on the system by name, vendor and
+//! category, with a search box; clicking Load sends the plugin down the
+//! background loading path in [`crate::plugin_loading`].
+//!
+//! The system scan runs a subprocess per bundle, so it runs as a task on
+//! Bevy's `AsyncComputeTaskPool` the first time the panel opens. Its report
+//! is kept for the rest of the session (and `Vst3Loader::scan_system` caches
+//! bundles on disk), so reopening the panel doesn't scan again; Rescan does.
+
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use futures_lite::future;
+use tracing::info;
+use vvdaw_plugin::PluginInfo;
+use vvdaw_vst3::{ScanReport, Vst3Loader};
+
+use crate::plugin_loading::LoadPlugin;
+
+/// Plugin that adds the plugin browser panel
+pub struct PluginBrowserPlugin;
+
+impl Plugin for PluginBrowserPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PluginBrowser>()
+            .add_systems(Update, plugin_scan_system)
+            .add_systems(EguiPrimaryContextPass, plugin_browser_system);
+    }
+}
+
+/// Resource holding the browser panel's state and the last scan
+#[derive(Resource, Default)]
+pub struct PluginBrowser {
+    /// Whether the panel is shown
+    pub open: bool,
+    /// Search box contents
+    pub search: String,
+    /// Scan in progress
+    scan: Option<Task<ScanReport>>,
+    /// Result of the last finished scan
+    report: Option<ScanReport>,
+}
+
+impl PluginBrowser {
+    /// Whether a scan is in progress
+    pub fn is_scanning(&self) -> bool {
+        self.scan.is_some()
+    }
+
+    /// Result of the last finished scan, if any
+    pub fn report(&self) -> Option<&ScanReport> {
+        self.report.as_ref()
+    }
+
+    /// Start scanning the system for plugins, unless a scan is already running
+    pub fn start_scan(&mut self) {
+        if self.scan.is_some() {
+            return;
+        }
+        info!("Scanning for VST3 plugins");
+        self.scan = Some(AsyncComputeTaskPool::get().spawn(async { Vst3Loader::scan_system() }));
+    }
+}
+
+/// Whether `plugin` matches every word of `search` (case-insensitive)
+///
+/// Words can match the name, vendor or category; an empty search matches
+/// every plugin.
+pub fn matches_search(plugin: &PluginInfo, search: &str) -> bool {
+    let haystack = format!(
+        "{} {} {}",
+        plugin.name,
+        plugin.vendor,
+        plugin.category.as_deref().unwrap_or_default()
+    )
+    .to_lowercase();
+    search
+        .split_whitespace()
+        .all(|word| haystack.contains(&word.to_lowercase()))
+}
+
+/// System that scans when the panel first opens and collects the report
+fn plugin_scan_system(mut browser: ResMut<PluginBrowser>) {
+    if browser.open && browser.report.is_none() {
+        browser.start_scan();
+    }
+
+    let Some(task) = &mut browser.scan else {
+        return;
+    };
+    if let Some(report) = future::block_on(future::poll_once(task)) {
+        info!(
+            "Plugin scan found {} plugins ({} failed)",
+            report.plugins.len(),
+            report.errors.len()
+        );
+        browser.scan = None;
+        browser.report = Some(report);
+    }
+}
+
+/// Plugin browser panel system
+fn plugin_browser_system(
+    mut contexts: EguiContexts,
+    mut browser: ResMut<PluginBrowser>,
+    mut load_plugin: MessageWriter<LoadPlugin>,
+) -> Result {
+    if !browser.open {
+        return Ok(());
+    }
+
+    let mut open = true;
+    let mut rescan = false;
+    egui::Window::new("Plugins")
+        .open(&mut open)
+        .default_width(480.0)
+        .show(contexts.ctx_mut()?, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Search:");
+                ui.text_edit_singleline(&mut browser.search);
+                rescan = ui
+                    .add_enabled(!browser.is_scanning(), egui::Button::new("Rescan"))
+                    .clicked();
+            });
+            ui.separator();
+
+            if browser.is_scanning() {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Scanning for plugins...");
+                });
+            }
+
+            let Some(report) = browser.report() else {
+                return;
+            };
+            let plugins: Vec<&PluginInfo> = report
+                .plugins
+                .iter()
+                .filter(|plugin| matches_search(plugin, &browser.search))
+                .collect();
+
+            if plugins.is_empty() {
+                ui.label("No plugins found.");
+            } else {
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("plugin_list").striped(true).show(ui, |ui| {
+                            ui.strong("Name");
+                            ui.strong("Vendor");
+                            ui.strong("Category");
+                            ui.end_row();
+
+                            for plugin in plugins {
+                                ui.label(plugin.name.as_str());
+                                ui.label(plugin.vendor.as_str());
+                                ui.label(plugin.category.as_deref().unwrap_or("-"));
+                                let bundle = report.bundle_of(plugin);
+                                if ui
+                                    .add_enabled(bundle.is_some(), egui::Button::new("Load"))
+                                    .clicked()
+                                    && let Some(bundle) = bundle
+                                {
+                                    load_plugin.write(LoadPlugin(bundle.to_path_buf()));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    });
+            }
+
+            // Bundles that failed, so a broken install doesn't just vanish
+            if !report.errors.is_empty() {
+                ui.separator();
+                egui::CollapsingHeader::new(format!("⚠ {} failed to scan", report.errors.len()))
+                    .show(ui, |ui| {
+                        for error in &report.errors {
+                            ui.colored_label(egui::Color32::RED, error.to_string());
+                        }
+                    });
+            }
+        });
+
+    browser.open = open;
+    if rescan {
+        browser.start_scan();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plugin(name: &str, vendor: &str, category: Option<&str>) -> PluginInfo {
+        PluginInfo {
+            name: name.to_string(),
+            vendor: vendor.to_string(),
+            version: "1.0".to_string(),
+            unique_id: name.to_string(),
+            category: category.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_search_matches_every_word_anywhere() {
+        let reverb = plugin("Room Reverb", "Acme", Some("Fx|Reverb"));
+        assert!(matches_search(&reverb, ""));
+        assert!(matches_search(&reverb, "reverb"));
+        assert!(matches_search(&reverb, "ACME fx"));
+        assert!(!matches_search(&reverb, "acme synth"));
+
+        let synth = plugin("Lead", "Acme", None);
+        assert!(matches_search(&synth, "lead"));
+        assert!(!matches_search(&synth, "fx"));
+    }
+}
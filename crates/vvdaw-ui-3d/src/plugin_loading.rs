@@ -0,0 +1,378 @@
+//! Background plugin loading
+//!
+//! Spawning a VST3 plugin starts a host subprocess and waits for it to come
+//! up, which can take a second - far too long to block a frame. Like WAV
+//! loads in the 2D UI (`PendingWavLoads`), each load runs as a task on Bevy's
+//! `AsyncComputeTaskPool`, and a system polls the tasks and sends finished
+//! plugins to the engine over `plugin_tx` with an `AddNode` command.
+//!
+//! [`PluginLoadState`] holds what the HUD shows: a spinner while a load is in
+//! flight, and the last error. It also keeps the plugins in the graph with
+//! their parameters: once a plugin is on the audio thread the UI can't ask it
+//! anything, so the parameter list and values (and the display strings of
+//! discrete parameters' steps) are read while loading.
+
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use futures_lite::future;
+use std::path::PathBuf;
+use tracing::{error, info};
+use vvdaw_comms::PluginInstance;
+use vvdaw_plugin::{ParameterInfo, Plugin as _};
+use vvdaw_vst3::MultiProcessPlugin;
+
+use crate::file_loading::CurrentSamplerNode;
+use crate::playback::{PlaybackState, PlaybackStatus};
+
+/// Plugin that handles loading audio plugins into the graph
+pub struct PluginLoadingPlugin;
+
+impl Plugin for PluginLoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PluginLoadState>()
+            .add_message::<LoadPlugin>()
+            .add_systems(
+                Update,
+                (start_plugin_load_system, poll_plugin_load_system).chain(),
+            );
+    }
+}
+
+/// Message requesting a VST3 plugin be loaded into the graph
+pub struct LoadPlugin(pub PathBuf);
+
+impl Message for LoadPlugin {}
+
+/// Most steps a discrete parameter can have for its display strings to be
+/// read while loading
+const MAX_LABELLED_STEPS: u32 = 128;
+
+/// A parameter of a loaded plugin, with the value the UI last saw
+#[derive(Debug, Clone)]
+pub struct PluginParameter {
+    pub info: ParameterInfo,
+    pub value: f32,
+    /// The plugin's display string for each step, lowest first
+    ///
+    /// Empty for continuous parameters and plugins without display strings.
+    pub step_labels: Vec<String>,
+}
+
+impl PluginParameter {
+    /// Read the current value of `info` from `plugin`, with its step labels
+    fn read(plugin: &dyn vvdaw_plugin::Plugin, info: ParameterInfo) -> Self {
+        Self {
+            value: plugin.get_parameter(info.id).unwrap_or(info.default_value),
+            step_labels: step_labels(plugin, &info),
+            info,
+        }
+    }
+
+    /// The plugin's display string for the current value, if it's known
+    pub fn display(&self) -> Option<&str> {
+        let last = self.step_labels.len().checked_sub(1)?;
+        let range = self.info.max_value - self.info.min_value;
+        let step = if range > 0.0 {
+            ((self.value - self.info.min_value) / range * last as f32).round()
+        } else {
+            0.0
+        };
+        self.step_labels
+            .get((step.max(0.0) as usize).min(last))
+            .map(String::as_str)
+    }
+}
+
+/// Display strings for each step of a discrete parameter
+///
+/// Continuous parameters get none: their text depends on the exact value, and
+/// the plugin can't be asked once it's on the audio thread.
+fn step_labels(plugin: &dyn vvdaw_plugin::Plugin, info: &ParameterInfo) -> Vec<String> {
+    if !info.is_discrete() || info.step_count > MAX_LABELLED_STEPS {
+        return Vec::new();
+    }
+
+    let range = info.max_value - info.min_value;
+    let steps = info.step_count as f32;
+    (0..=info.step_count)
+        .map(|step| {
+            let value = (step as f32 / steps).mul_add(range, info.min_value);
+            plugin.parameter_display(info.id, value)
+        })
+        .collect::<Result<_, _>>()
+        .unwrap_or_default()
+}
+
+/// A plugin in the graph
+#[derive(Debug, Clone)]
+pub struct LoadedPlugin {
+    /// The plugin's node in the audio graph
+    pub node_id: usize,
+    pub name: String,
+    /// Parameters reported when the plugin loaded
+    pub parameters: Vec<PluginParameter>,
+}
+
+/// A plugin sent to the engine, before it has a node ID
+struct PendingPlugin {
+    name: String,
+    parameters: Vec<PluginParameter>,
+}
+
+/// Result of async plugin loading: the plugin and what the UI needs to know
+/// about it, or an error message
+type PluginLoadResult = Result<(PluginInstance, PendingPlugin), String>;
+
+/// Resource tracking plugin loads: background tasks, plugins added, and errors
+#[derive(Resource, Default)]
+pub struct PluginLoadState {
+    /// Active background loading tasks, in request order
+    tasks: Vec<Task<PluginLoadResult>>,
+    /// Plugin sent to the engine whose `NodeAdded` is expected next
+    awaiting_node: Option<PendingPlugin>,
+    /// Plugins added to the graph, in the order they were added
+    pub loaded: Vec<LoadedPlugin>,
+    /// Error from the last failed load, until dismissed
+    pub error: Option<String>,
+}
+
+impl PluginLoadState {
+    /// Whether a plugin is being spawned or added to the graph
+    pub fn is_loading(&self) -> bool {
+        !self.tasks.is_empty() || self.awaiting_node.is_some()
+    }
+
+    /// Whether a plugin has been sent to the engine and not yet added
+    pub fn is_awaiting_node(&self) -> bool {
+        self.awaiting_node.is_some()
+    }
+
+    pub fn clear_error(&mut self) {
+        self.error = None;
+    }
+
+    /// Claim a `NodeAdded` for the plugin sent last
+    ///
+    /// Returns `false` if no plugin was waiting, in which case the node is
+    /// someone else's (the sampler).
+    pub fn claim_node(&mut self, node_id: usize) -> bool {
+        let Some(PendingPlugin { name, parameters }) = self.awaiting_node.take() else {
+            return false;
+        };
+        info!("✓ Plugin '{name}' added as node {node_id}");
+        self.loaded.push(LoadedPlugin {
+            node_id,
+            name,
+            parameters,
+        });
+        true
+    }
+
+    /// The engine answered the plugin sent last with an error instead of `NodeAdded`
+    pub fn fail_pending(&mut self, message: &str) {
+        if let Some(pending) = self.awaiting_node.take() {
+            self.error = Some(format!("Failed to add {}: {message}", pending.name));
+        }
+    }
+
+    /// Forget a plugin node the engine removed
+    pub fn remove_node(&mut self, node_id: usize) {
+        self.loaded.retain(|plugin| plugin.node_id != node_id);
+    }
+
+    /// The loaded plugin at `node_id`
+    pub fn plugin_mut(&mut self, node_id: usize) -> Option<&mut LoadedPlugin> {
+        self.loaded
+            .iter_mut()
+            .find(|plugin| plugin.node_id == node_id)
+    }
+
+    /// Record a parameter value the plugin reported (e.g. edited in its own editor)
+    pub fn update_parameter(&mut self, node_id: usize, param_id: u32, value: f32) {
+        if let Some(parameter) = self.plugin_mut(node_id).and_then(|plugin| {
+            plugin
+                .parameters
+                .iter_mut()
+                .find(|parameter| parameter.info.id == param_id)
+        }) {
+            parameter.value = value;
+        }
+    }
+}
+
+/// System that starts a background load for each requested plugin
+#[allow(clippy::needless_pass_by_value)] // Bevy MessageReader requires by-value
+fn start_plugin_load_system(
+    mut load_requests: MessageReader<LoadPlugin>,
+    mut load_state: ResMut<PluginLoadState>,
+) {
+    for LoadPlugin(path) in load_requests.read() {
+        info!("Loading plugin: {}", path.display());
+        load_state.error = None;
+
+        let path = path.clone();
+        let task = AsyncComputeTaskPool::get().spawn(async move {
+            MultiProcessPlugin::spawn(&path)
+                .map(|plugin| {
+                    let parameters = plugin
+                        .parameters()
+                        .into_iter()
+                        .map(|info| PluginParameter::read(&plugin, info))
+                        .collect();
+                    let pending = PendingPlugin {
+                        name: plugin.info().name.clone(),
+                        parameters,
+                    };
+                    (Box::new(plugin) as PluginInstance, pending)
+                })
+                .map_err(|e| format!("{}: {e}", path.display()))
+        });
+        load_state.tasks.push(task);
+    }
+}
+
+/// System that sends finished plugins to the audio engine
+///
+/// Plugins are sent one at a time, and not while a sampler is on its way:
+/// `NodeAdded` only carries the node ID, so the UI tells nodes apart by
+/// having at most one add in flight.
+#[allow(clippy::needless_pass_by_value)] // Bevy SystemParam requires Option<Res<T>>, not Option<&Res<T>>
+fn poll_plugin_load_system(
+    mut load_state: ResMut<PluginLoadState>,
+    mut playback_state: ResMut<PlaybackState>,
+    current_sampler: Res<CurrentSamplerNode>,
+    mut audio_command_tx: Option<ResMut<crate::AudioCommandChannel>>,
+    audio_plugin_tx: Option<Res<crate::AudioPluginChannel>>,
+) {
+    while !load_state.is_awaiting_node() && !current_sampler.awaiting_node {
+        // Finish loads in request order; unfinished tasks are polled again next frame
+        let Some(task) = load_state.tasks.first_mut() else {
+            return;
+        };
+        let Some(result) = future::block_on(future::poll_once(task)) else {
+            return;
+        };
+        drop(load_state.tasks.remove(0));
+
+        let (plugin, pending) = match result {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                error!("Plugin load failed: {e}");
+                load_state.error = Some(e);
+                continue;
+            }
+        };
+
+        let name = pending.name.clone();
+        let (Some(cmd_tx), Some(plugin_tx)) = (&mut audio_command_tx, &audio_plugin_tx) else {
+            tracing::warn!("Audio engine not available - dropping plugin '{name}'");
+            continue;
+        };
+
+        // Nodes can only be added while the engine is stopped
+        if playback_state.status != PlaybackStatus::Stopped {
+            info!("→ Stopping playback to add plugin '{name}'");
+            if let Err(e) = cmd_tx.0.push(vvdaw_comms::AudioCommand::Stop) {
+                error!("✗ Failed to send Stop command: {e:?}");
+            }
+            playback_state.status = PlaybackStatus::Stopped;
+        }
+
+        info!("→ Sending plugin '{name}' to audio engine");
+        if vvdaw_comms::send_node(&mut cmd_tx.0, &plugin_tx.0, plugin, None).is_err() {
+            error!("✗ Failed to send plugin: command queue full");
+            load_state.error = Some(format!("Failed to add {name}: command queue full"));
+        } else {
+            load_state.awaiting_node = Some(pending);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(name: &str) -> Option<PendingPlugin> {
+        Some(PendingPlugin {
+            name: name.to_string(),
+            parameters: vec![PluginParameter {
+                info: ParameterInfo {
+                    id: 7,
+                    name: "Mix".to_string(),
+                    min_value: 0.0,
+                    max_value: 1.0,
+                    default_value: 0.5,
+                    step_count: 0,
+                },
+                value: 0.5,
+                step_labels: Vec::new(),
+            }],
+        })
+    }
+
+    #[test]
+    fn test_node_added_is_claimed_only_while_awaiting() {
+        let mut load_state = PluginLoadState::default();
+        assert!(
+            !load_state.claim_node(0),
+            "Nothing sent - the node is the sampler's"
+        );
+
+        load_state.awaiting_node = pending("Reverb");
+        assert!(load_state.is_loading());
+        assert!(load_state.claim_node(3));
+        assert_eq!(load_state.loaded.len(), 1);
+        assert_eq!(load_state.loaded[0].node_id, 3);
+        assert_eq!(load_state.loaded[0].name, "Reverb");
+        assert!(!load_state.is_loading());
+
+        load_state.update_parameter(3, 7, 0.9);
+        load_state.update_parameter(3, 8, 0.1);
+        assert_eq!(load_state.loaded[0].parameters[0].value, 0.9);
+
+        load_state.remove_node(3);
+        assert!(load_state.loaded.is_empty());
+    }
+
+    #[test]
+    fn test_display_follows_the_value_across_steps() {
+        let mut parameter = PluginParameter {
+            info: ParameterInfo {
+                id: 2,
+                name: "Shape".to_string(),
+                min_value: 0.0,
+                max_value: 1.0,
+                default_value: 0.0,
+                step_count: 2,
+            },
+            value: 0.0,
+            step_labels: vec!["Sine".to_string(), "Saw".to_string(), "Square".to_string()],
+        };
+        assert_eq!(parameter.display(), Some("Sine"));
+
+        parameter.value = 0.5;
+        assert_eq!(parameter.display(), Some("Saw"));
+
+        // Values between steps show the nearest one
+        parameter.value = 0.9;
+        assert_eq!(parameter.display(), Some("Square"));
+
+        parameter.step_labels.clear();
+        assert_eq!(parameter.display(), None);
+    }
+
+    #[test]
+    fn test_engine_error_fails_the_pending_plugin() {
+        let mut load_state = PluginLoadState::default();
+        load_state.fail_pending("unrelated");
+        assert!(load_state.error.is_none());
+
+        load_state.awaiting_node = pending("Delay");
+        load_state.fail_pending("Cannot add nodes while playing");
+        assert!(!load_state.is_awaiting_node());
+        assert_eq!(
+            load_state.error.as_deref(),
+            Some("Failed to add Delay: Cannot add nodes while playing")
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! Recently loaded WAV files
+//!
+//! [`RecentFiles`] records each file that loads successfully, so the app can
+//! save the list on exit and hand it back on the next start; File > Recent
+//! Files lists them for loading again. The file loaded last in the previous
+//! session is reopened as soon as the audio engine reports its sample rate
+//! (loads before then are refused, since the samples are resampled to the
+//! engine's rate).
+
+use bevy::prelude::*;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::menu::FileSelected;
+
+/// Files kept in the recent files list
+pub const MAX_RECENT_FILES: usize = 10;
+
+/// Plugin that tracks recently loaded files and reopens the last one
+pub struct RecentFilesPlugin;
+
+impl Plugin for RecentFilesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RecentFiles>()
+            .add_systems(Update, reopen_last_file_system);
+    }
+}
+
+/// Resource holding the files loaded in this and earlier sessions
+#[derive(Resource, Debug, Default, Clone)]
+pub struct RecentFiles {
+    /// Files loaded, oldest first
+    pub files: Vec<PathBuf>,
+    /// File to load once the audio engine is ready
+    pub reopen: Option<PathBuf>,
+}
+
+impl RecentFiles {
+    /// Files restored from an earlier session, reopening `last_file` on start
+    ///
+    /// Only the newest [`MAX_RECENT_FILES`] are kept.
+    pub fn restored(mut files: Vec<PathBuf>, last_file: Option<PathBuf>) -> Self {
+        let excess = files.len().saturating_sub(MAX_RECENT_FILES);
+        Self {
+            files: files.split_off(excess),
+            reopen: last_file,
+        }
+    }
+
+    /// Record a loaded file, moving it to the end if it was already listed
+    ///
+    /// The oldest file drops off once there are [`MAX_RECENT_FILES`].
+    pub fn add(&mut self, path: &Path) {
+        self.files.retain(|file| file != path);
+        self.files.push(path.to_path_buf());
+        if self.files.len() > MAX_RECENT_FILES {
+            self.files.remove(0);
+        }
+    }
+
+    /// The files, newest first
+    pub fn newest_first(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().rev().map(PathBuf::as_path)
+    }
+
+    /// The file loaded last (or still to be reopened)
+    pub fn last_file(&self) -> Option<&Path> {
+        self.reopen
+            .as_deref()
+            .or_else(|| self.files.last().map(PathBuf::as_path))
+    }
+}
+
+/// System that loads the restored file once the engine's sample rate is known
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn reopen_last_file_system(
+    mut recent_files: ResMut<RecentFiles>,
+    engine_info: Res<crate::AudioEngineInfo>,
+    mut file_selected: MessageWriter<FileSelected>,
+) {
+    if recent_files.reopen.is_none() || engine_info.sample_rate.is_none() {
+        return;
+    }
+    let Some(path) = recent_files.reopen.take() else {
+        return;
+    };
+
+    if path.is_file() {
+        info!("Reopening {}", path.display());
+        file_selected.write(FileSelected(path));
+    } else {
+        warn!(
+            "Last loaded file {} is gone - not reopening it",
+            path.display()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reloaded_file_moves_to_the_end() {
+        let mut recent_files = RecentFiles::restored(
+            vec![PathBuf::from("/a.wav"), PathBuf::from("/b.wav")],
+            Some(PathBuf::from("/a.wav")),
+        );
+        assert_eq!(recent_files.last_file(), Some(Path::new("/a.wav")));
+
+        recent_files.reopen = None;
+        assert_eq!(recent_files.last_file(), Some(Path::new("/b.wav")));
+
+        recent_files.add(Path::new("/a.wav"));
+        recent_files.add(Path::new("/c.wav"));
+        assert_eq!(
+            recent_files.files,
+            [
+                PathBuf::from("/b.wav"),
+                PathBuf::from("/a.wav"),
+                PathBuf::from("/c.wav")
+            ]
+        );
+        assert_eq!(
+            recent_files.newest_first().next(),
+            Some(Path::new("/c.wav"))
+        );
+    }
+
+    #[test]
+    fn test_list_is_capped() {
+        let file = |i: usize| PathBuf::from(format!("/{i}.wav"));
+        let mut recent_files =
+            RecentFiles::restored((0..MAX_RECENT_FILES + 2).map(file).collect(), None);
+        assert_eq!(recent_files.files.len(), MAX_RECENT_FILES);
+        assert_eq!(recent_files.files[0], file(2));
+
+        recent_files.add(&file(99));
+        assert_eq!(recent_files.files.len(), MAX_RECENT_FILES);
+        assert_eq!(recent_files.files[0], file(3));
+        assert_eq!(recent_files.last_file(), Some(file(99).as_path()));
+    }
+}
@@ -0,0 +1,133 @@
+//! Oscilloscope hanging over the highway
+//!
+//! Draws the engine's latest [`ScopeWindow`](vvdaw_comms::ScopeWindow) as two
+//! line traces - left channel above right - spanning the road ahead of the
+//! starting camera. The audio thread publishes a new window every buffer; the
+//! traces are rebuilt whenever one has arrived since the last frame.
+
+use bevy::asset::RenderAssetUsages;
+use bevy::light::NotShadowCaster;
+use bevy::mesh::PrimitiveTopology;
+use bevy::prelude::*;
+use vvdaw_comms::{SCOPE_WINDOW_FRAMES, ScopeReceiver};
+
+/// Resource wrapping the oscilloscope triple buffer (Audio -> UI)
+pub struct ScopeChannel(pub ScopeReceiver);
+
+// SAFETY: Manual Send/Sync implementation required for triple_buffer::Output
+//
+// `triple_buffer::Output` is built to live on a different thread than its
+// `Input`, but reading swaps buffers through `&mut self`, so it is not Sync.
+// Bevy's `Resource` system only hands `&mut` access to one system at a time,
+// so the reader is never used concurrently - the same reasoning as
+// `AudioEventChannel` in highway.rs.
+#[allow(unsafe_code)]
+unsafe impl Send for ScopeChannel {}
+#[allow(unsafe_code)]
+unsafe impl Sync for ScopeChannel {}
+
+impl Resource for ScopeChannel {}
+
+pub struct ScopePlugin;
+
+impl Plugin for ScopePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_scope)
+            .add_systems(Update, update_scope_traces);
+    }
+}
+
+/// Width of the traces across the road
+const SCOPE_WIDTH: f32 = 40.0;
+/// Height of a full-scale (±1.0) sample above/below a trace's center line
+const SCOPE_AMPLITUDE: f32 = 2.5;
+/// Center line heights of the left and right traces
+const LEFT_TRACE_HEIGHT: f32 = 14.0;
+const RIGHT_TRACE_HEIGHT: f32 = 8.0;
+/// Distance down the road from the origin
+const SCOPE_DISTANCE: f32 = 40.0;
+
+const SCOPE_TEAL_COLOR: Color = Color::srgb(0.2, 0.8, 0.7);
+const SCOPE_AMBER_COLOR: Color = Color::srgb(1.0, 0.6, 0.2);
+
+/// Line trace for one channel of the scope
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum ScopeTrace {
+    Left,
+    Right,
+}
+
+/// Trace vertices for one channel of a scope window, left to right
+#[allow(clippy::cast_precision_loss)] // Point indices are far below 2^24
+fn trace_positions(samples: &[f32; SCOPE_WINDOW_FRAMES]) -> Vec<[f32; 3]> {
+    samples
+        .iter()
+        .enumerate()
+        .map(|(index, sample)| {
+            let x = (index as f32 / (SCOPE_WINDOW_FRAMES - 1) as f32 - 0.5) * SCOPE_WIDTH;
+            [x, sample.clamp(-1.0, 1.0) * SCOPE_AMPLITUDE, 0.0]
+        })
+        .collect()
+}
+
+/// Spawn the left and right traces as flat lines
+fn setup_scope(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (trace, color, height) in [
+        (ScopeTrace::Left, SCOPE_TEAL_COLOR, LEFT_TRACE_HEIGHT),
+        (ScopeTrace::Right, SCOPE_AMBER_COLOR, RIGHT_TRACE_HEIGHT),
+    ] {
+        commands.spawn((
+            // Kept in the main world too, so it can be rewritten every frame
+            Mesh3d(
+                meshes.add(
+                    Mesh::new(
+                        PrimitiveTopology::LineStrip,
+                        RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+                    )
+                    .with_inserted_attribute(
+                        Mesh::ATTRIBUTE_POSITION,
+                        trace_positions(&[0.0; SCOPE_WINDOW_FRAMES]),
+                    ),
+                ),
+            ),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: color,
+                unlit: true, // Lines have no normals to light
+                ..default()
+            })),
+            Transform::from_xyz(0.0, height, -SCOPE_DISTANCE),
+            NotShadowCaster,
+            trace,
+        ));
+    }
+}
+
+/// Rebuild the traces from the newest scope window
+#[allow(clippy::needless_pass_by_value)] // Bevy system parameters must be passed by value
+fn update_scope_traces(
+    scope: Option<ResMut<ScopeChannel>>,
+    traces: Query<(&Mesh3d, &ScopeTrace)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let Some(mut scope) = scope else {
+        return;
+    };
+    if !scope.0.updated() {
+        return;
+    }
+
+    let window = scope.0.read();
+    for (mesh, trace) in &traces {
+        let samples = match trace {
+            ScopeTrace::Left => &window.left,
+            ScopeTrace::Right => &window.right,
+        };
+        if let Some(mesh) = meshes.get_mut(&mesh.0) {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, trace_positions(samples));
+        }
+    }
+}
@@ -5,7 +5,8 @@
 use bevy::asset::RenderAssetUsages;
 use bevy::mesh::{Indices, PrimitiveTopology};
 use bevy::prelude::*;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, PoisonError};
 
 /// Resource holding loaded audio waveform data
 ///
@@ -35,6 +36,10 @@ pub struct WaveformData {
     /// Last position (in seconds) where mesh was generated
     /// Used to throttle mesh updates - only regenerate when position changes significantly
     pub last_mesh_position: f32,
+
+    /// Min/max peak bins already computed by [`WaveformData::min_max_bins`], by bin count
+    /// Cleared by [`WaveformData::set_samples`]
+    peak_bins: Mutex<HashMap<usize, Vec<(f32, f32)>>>,
 }
 
 impl WaveformData {
@@ -48,9 +53,43 @@ impl WaveformData {
             max_streaming_samples: 9000, // ~100 seconds at 90 samples/sec
             needs_mesh_update: true,     // Always request mesh update for new data
             last_mesh_position: 0.0,
+            peak_bins: Mutex::default(),
         }
     }
 
+    /// Replace the loaded audio, invalidating cached peak bins
+    pub fn set_samples(&mut self, samples: Vec<f32>, sample_rate: u32) {
+        self.samples = samples;
+        self.sample_rate = sample_rate;
+        self.needs_mesh_update = true;
+        self.peak_bins
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Per-bin `(min, max)` sample values across the whole file, for drawing
+    /// the waveform `bin_count` points wide
+    ///
+    /// Both channels are folded into each bin. Bins are computed once per
+    /// `bin_count` and cached, so switching between zoom levels is cheap.
+    /// With more bins than frames, each bin holds a single frame (sample-level
+    /// zoom). Returns an empty list if no audio is loaded or `bin_count` is 0.
+    pub fn min_max_bins(&self, bin_count: usize) -> Vec<(f32, f32)> {
+        if bin_count == 0 || !self.is_loaded() {
+            return Vec::new();
+        }
+
+        let mut cache = self
+            .peak_bins
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        cache
+            .entry(bin_count)
+            .or_insert_with(|| compute_min_max_bins(&self.samples, bin_count))
+            .clone()
+    }
+
     /// Append a new peak sample from the audio thread
     ///
     /// Maintains a ring buffer of the most recent peak samples
@@ -104,6 +143,107 @@ impl WaveformData {
     }
 }
 
+/// Split interleaved stereo `samples` into `bin_count` equal spans of frames
+/// and find the lowest and highest sample in each
+fn compute_min_max_bins(samples: &[f32], bin_count: usize) -> Vec<(f32, f32)> {
+    let frames: Vec<&[f32]> = samples.chunks(2).collect();
+    if frames.is_empty() {
+        return Vec::new();
+    }
+
+    (0..bin_count)
+        .map(|bin| {
+            // Every bin covers at least one frame, repeating frames when zoomed in
+            let start = (bin * frames.len() / bin_count).min(frames.len() - 1);
+            let end = ((bin + 1) * frames.len() / bin_count).max(start + 1);
+
+            frames[start..end]
+                .iter()
+                .flat_map(|frame| frame.iter())
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &sample| {
+                    (min.min(sample), max.max(sample))
+                })
+        })
+        .collect()
+}
+
+/// Lowest vertical gain [`WaveformStyle`] allows
+pub const MIN_VERTICAL_GAIN: f32 = 0.25;
+/// Highest vertical gain [`WaveformStyle`] allows
+pub const MAX_VERTICAL_GAIN: f32 = 32.0;
+/// How much one step up or down changes the vertical gain (6 dB)
+pub const VERTICAL_GAIN_STEP: f32 = 2.0;
+
+/// Colors for the waveform relief keyed to displayed amplitude
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorRamp {
+    /// Color at silence
+    pub quiet: Color,
+    /// Color at full scale
+    pub loud: Color,
+}
+
+impl ColorRamp {
+    /// Green for quiet passages through to red at full scale
+    pub const LEVEL: Self = Self {
+        quiet: Color::srgb(0.1, 0.9, 0.2),
+        loud: Color::srgb(1.0, 0.1, 0.1),
+    };
+
+    /// Color for a displayed amplitude (0.0 silent to 1.0 full scale)
+    ///
+    /// Blends in linear space, since that's what vertex colors are in.
+    /// Amplitudes outside 0.0..=1.0 are clamped.
+    pub fn color_at(&self, amplitude: f32) -> LinearRgba {
+        LinearRgba::from(self.quiet).mix(&LinearRgba::from(self.loud), amplitude.clamp(0.0, 1.0))
+    }
+}
+
+/// How the waveform relief is drawn, adjustable at runtime
+///
+/// Purely visual: the audio is never touched. Changing this resource (from a
+/// system, the View menu or the `[`/`]`/`C` keys) redraws the walls.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct WaveformStyle {
+    /// Gain applied to displayed peaks, so quiet recordings don't lie flat
+    ///
+    /// Boosted peaks stop at full scale.
+    pub vertical_gain: f32,
+    /// Color the relief by amplitude instead of each channel's own color
+    pub color_ramp: Option<ColorRamp>,
+}
+
+impl Default for WaveformStyle {
+    fn default() -> Self {
+        Self {
+            vertical_gain: 1.0,
+            color_ramp: None,
+        }
+    }
+}
+
+impl WaveformStyle {
+    /// Multiply the vertical gain by `factor`, within
+    /// [`MIN_VERTICAL_GAIN`]..=[`MAX_VERTICAL_GAIN`]
+    pub fn scale_gain(&mut self, factor: f32) {
+        self.vertical_gain =
+            (self.vertical_gain * factor).clamp(MIN_VERTICAL_GAIN, MAX_VERTICAL_GAIN);
+    }
+
+    /// Switch between the [`ColorRamp::LEVEL`] ramp and per-channel colors
+    pub fn toggle_color_ramp(&mut self) {
+        self.color_ramp = match self.color_ramp {
+            Some(_) => None,
+            None => Some(ColorRamp::LEVEL),
+        };
+    }
+
+    /// Amplitude as drawn: `sample` with the vertical gain applied, held to full scale
+    pub fn displayed(&self, sample: f32) -> f32 {
+        (sample * self.vertical_gain).clamp(-1.0, 1.0)
+    }
+}
+
 /// Configuration for waveform mesh generation
 pub struct WaveformMeshConfig {
     /// How many samples to skip between vertices (LOD)
@@ -123,6 +263,8 @@ pub struct WaveformMeshConfig {
     pub base_height: f32,
     /// Time window to render (seconds before and after current position)
     pub window_duration: f32,
+    /// Vertical gain and coloring of the waveform relief
+    pub style: WaveformStyle,
 }
 
 impl Default for WaveformMeshConfig {
@@ -136,10 +278,26 @@ impl Default for WaveformMeshConfig {
             time_scale: 50.0,         // 50 units per second
             base_height: 10.0,        // Center line at 10 units above road
             window_duration: 15.0,    // Render 15 seconds ahead and behind (30 total)
+            style: WaveformStyle::default(),
         }
     }
 }
 
+impl WaveformMeshConfig {
+    /// Highway z of track time `time` (seconds), with the playhead at
+    /// `current_position`
+    ///
+    /// The playhead is at z = 0, with later times further down the road.
+    pub fn time_to_z(&self, time: f32, current_position: f32) -> f32 {
+        -(time - current_position) * self.time_scale
+    }
+
+    /// Track time (seconds) at highway `z` - the inverse of [`Self::time_to_z`]
+    pub fn z_to_time(&self, z: f32, current_position: f32) -> f32 {
+        current_position - z / self.time_scale
+    }
+}
+
 /// Create an empty mesh with required PBR attributes
 fn create_empty_mesh() -> Mesh {
     let mut mesh = Mesh::new(
@@ -360,8 +518,8 @@ fn generate_base_wall_mesh(
     // Calculate z positions for start and end of window
     let start_abs_time = start_sample as f32 * time_per_sample;
     let end_abs_time = end_sample.saturating_sub(1) as f32 * time_per_sample;
-    let z_start = -(start_abs_time - current_position_seconds) * config.time_scale;
-    let z_end = -(end_abs_time - current_position_seconds) * config.time_scale;
+    let z_start = config.time_to_z(start_abs_time, current_position_seconds);
+    let z_end = config.time_to_z(end_abs_time, current_position_seconds);
 
     // --- STEP 1: Generate base wall panel (simple rectangle) ---
 
@@ -445,6 +603,9 @@ fn generate_base_wall_mesh(
 }
 
 /// Generate waveform relief mesh (extrudes toward road center)
+///
+/// With a color ramp in `config.style`, each vertex also gets a color for its
+/// displayed amplitude.
 fn generate_waveform_mesh(
     samples: &[f32],
     sample_rate: u32,
@@ -456,6 +617,7 @@ fn generate_waveform_mesh(
     let mut normals = Vec::new();
     let mut tangents = Vec::new();
     let mut uvs = Vec::new();
+    let mut colors = Vec::new();
     let mut indices = Vec::new();
 
     if samples.is_empty() {
@@ -484,12 +646,16 @@ fn generate_waveform_mesh(
     // Generate waveform relief on outer face
     let mut prev_z = None;
     let mut prev_y_wave = None;
+    let mut prev_color = None;
 
     for sample_idx in (start_sample..end_sample).step_by(sample_stride) {
-        let sample = samples[sample_idx];
+        let sample = config.style.displayed(samples[sample_idx]);
+        let color = config
+            .style
+            .color_ramp
+            .map(|ramp| ramp.color_at(sample.abs()).to_f32_array());
         let absolute_time = sample_idx as f32 * time_per_sample;
-        let relative_time = absolute_time - current_position_seconds;
-        let z = -relative_time * config.time_scale;
+        let z = config.time_to_z(absolute_time, current_position_seconds);
 
         // Waveform height oscillates around base_height
         let y_wave = sample.mul_add(config.amplitude_scale, config.base_height);
@@ -546,10 +712,15 @@ fn generate_waveform_mesh(
                 wave_start_idx + 2,
                 wave_start_idx + 3,
             ]);
+
+            if let (Some(prev_color), Some(color)) = (prev_color, color) {
+                colors.extend_from_slice(&[prev_color, prev_color, color, color]);
+            }
         }
 
         prev_z = Some(z);
         prev_y_wave = Some(y_wave);
+        prev_color = color;
     }
 
     // Build waveform mesh
@@ -562,7 +733,82 @@ fn generate_waveform_mesh(
     mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
     mesh.insert_attribute(Mesh::ATTRIBUTE_TANGENT, tangents);
     mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    if config.style.color_ramp.is_some() {
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    }
     mesh.insert_indices(Indices::U32(indices));
 
     mesh
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_max_bins_fold_frames_and_channels() {
+        // Four stereo frames, two bins of two frames each
+        let waveform = WaveformData::new(vec![0.1, -0.2, 0.5, 0.0, -0.7, 0.3, 0.2, 0.9], 48000);
+        assert_eq!(waveform.min_max_bins(2), vec![(-0.2, 0.5), (-0.7, 0.9)]);
+        assert_eq!(waveform.min_max_bins(1), vec![(-0.7, 0.9)]);
+        assert!(waveform.min_max_bins(0).is_empty());
+    }
+
+    #[test]
+    fn test_min_max_bins_at_sample_level() {
+        // More bins than frames: every bin still holds a frame
+        let waveform = WaveformData::new(vec![0.1, 0.2, -0.3, -0.4], 48000);
+        assert_eq!(
+            waveform.min_max_bins(4),
+            vec![(0.1, 0.2), (0.1, 0.2), (-0.4, -0.3), (-0.4, -0.3)]
+        );
+    }
+
+    #[test]
+    fn test_set_samples_invalidates_bins() {
+        let mut waveform = WaveformData::new(vec![0.5, 0.5], 48000);
+        assert_eq!(waveform.min_max_bins(1), vec![(0.5, 0.5)]);
+
+        waveform.set_samples(vec![-1.0, 1.0], 44100);
+        assert_eq!(waveform.min_max_bins(1), vec![(-1.0, 1.0)]);
+        assert_eq!(waveform.sample_rate, 44100);
+        assert!(waveform.needs_mesh_update);
+    }
+
+    #[test]
+    fn test_time_and_z_round_trip() {
+        let config = WaveformMeshConfig::default();
+        // Later times are further down the road (-z), the playhead at 0
+        assert_eq!(config.time_to_z(3.0, 3.0), 0.0);
+        assert!(config.time_to_z(4.0, 3.0) < 0.0);
+
+        let z = config.time_to_z(7.5, 2.0);
+        assert!((config.z_to_time(z, 2.0) - 7.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_style_gain_holds_at_full_scale() {
+        let mut style = WaveformStyle::default();
+        assert_eq!(style.displayed(0.1), 0.1);
+
+        style.scale_gain(4.0);
+        assert_eq!(style.displayed(0.1), 0.4);
+        assert_eq!(style.displayed(-0.5), -1.0);
+
+        style.scale_gain(1000.0);
+        assert_eq!(style.vertical_gain, MAX_VERTICAL_GAIN);
+        style.scale_gain(0.0);
+        assert_eq!(style.vertical_gain, MIN_VERTICAL_GAIN);
+    }
+
+    #[test]
+    fn test_color_ramp_keys_on_amplitude() {
+        let ramp = ColorRamp::LEVEL;
+        assert_eq!(ramp.color_at(0.0), LinearRgba::from(ramp.quiet));
+        assert_eq!(ramp.color_at(1.0), LinearRgba::from(ramp.loud));
+        assert_eq!(ramp.color_at(2.0), ramp.color_at(1.0));
+
+        let middle = ramp.color_at(0.5);
+        assert!(middle.red > ramp.color_at(0.0).red && middle.red < ramp.color_at(1.0).red);
+    }
+}
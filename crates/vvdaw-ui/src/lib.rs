@@ -8,12 +8,16 @@ use vvdaw_comms::{AudioCommand, AudioEvent, UiChannels};
 
 mod ui;
 
-pub use ui::{AudioState, PlaybackState};
+pub use ui::{AudioState, FilePathState, PlaybackState};
 
 /// Main UI plugin for Bevy
 pub struct VvdawUiPlugin {
     /// Audio communication channels (wrapped for thread safety)
     pub audio_channels: Arc<Mutex<UiChannels>>,
+    /// Files loaded in earlier sessions, in the order they were first loaded
+    pub loaded_files: Vec<String>,
+    /// File to reopen on start
+    pub last_file: Option<String>,
 }
 
 impl VvdawUiPlugin {
@@ -21,8 +25,18 @@ impl VvdawUiPlugin {
     pub fn new(channels: UiChannels) -> Self {
         Self {
             audio_channels: Arc::new(Mutex::new(channels)),
+            loaded_files: Vec::new(),
+            last_file: None,
         }
     }
+
+    /// Restore the file history of an earlier session, reopening `last_file`
+    #[must_use]
+    pub fn with_history(mut self, loaded_files: Vec<String>, last_file: Option<String>) -> Self {
+        self.loaded_files = loaded_files;
+        self.last_file = last_file;
+        self
+    }
 }
 
 impl Plugin for VvdawUiPlugin {
@@ -31,6 +45,13 @@ impl Plugin for VvdawUiPlugin {
         app.insert_resource(AudioChannelResource {
             channels: self.audio_channels.clone(),
         });
+        // Keep the newest files if the history is over the limit
+        let skip = self.loaded_files.len().saturating_sub(ui::MAX_RECENT_FILES);
+        app.insert_resource(ui::FilePathState {
+            loaded_files: self.loaded_files[skip..].to_vec(),
+            reopen: self.last_file.clone(),
+            ..default()
+        });
 
         // Add UI systems
         app.add_systems(Startup, ui::setup_ui).add_systems(
@@ -39,8 +60,15 @@ impl Plugin for VvdawUiPlugin {
                 ui::handle_button_interactions,
                 ui::update_file_path_text,
                 ui::poll_audio_events,
+                ui::update_clip_indicator,
+                ui::handle_file_drops,
                 ui::poll_file_dialog,
                 ui::poll_wav_load_tasks,
+                ui::update_load_progress,
+                ui::reopen_last_file,
+                ui::toggle_recent_files_menu,
+                ui::update_recent_files_menu,
+                ui::handle_recent_file_clicks,
             ),
         );
     }
@@ -71,6 +99,21 @@ impl AudioChannelResource {
             .map_err(|_| "Plugin channel disconnected".to_string())
     }
 
+    /// Send a plugin and the command that adds it (see [`vvdaw_comms::send_node`])
+    ///
+    /// When `replacing` is set, the old node is removed in the same command, so a
+    /// full queue can't leave it orphaned.
+    pub fn send_node(
+        &self,
+        plugin: vvdaw_comms::PluginInstance,
+        replacing: Option<usize>,
+    ) -> Result<(), String> {
+        let mut channels = self.channels.lock().map_err(|e| e.to_string())?;
+        channels
+            .send_node(plugin, replacing)
+            .map_err(|_| "Command channel full".to_string())
+    }
+
     /// Poll for events from the audio thread
     pub fn poll_events(&self) -> Vec<AudioEvent> {
         let Ok(mut channels) = self.channels.lock() else {
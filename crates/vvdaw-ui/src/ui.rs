@@ -2,8 +2,12 @@
 
 use bevy::prelude::*;
 use bevy::tasks::{AsyncComputeTaskPool, Task};
+use bevy::window::FileDragAndDrop;
 use crossbeam_channel::{Receiver, Sender};
 use futures_lite::future;
+use vvdaw_audio::builtin::sampler::SamplerProcessor;
+use vvdaw_audio::builtin::streaming_sampler::{STREAMING_THRESHOLD_BYTES, StreamingSampler};
+use vvdaw_audio::wav_progress::{self, LoadProgress};
 use vvdaw_comms::{AudioCommand, AudioEvent};
 
 use crate::AudioChannelResource;
@@ -25,8 +29,21 @@ pub struct AudioState {
     ///
     /// This tracks the sampler added by the UI to prevent the race condition
     /// where rapid next/prev clicking adds multiple samplers to the graph.
-    /// Before adding a new sampler, we remove the previous one.
+    /// New samplers replace the previous one with a single `ReplaceNode` command.
+    /// Only updated from engine events, so it can't drift from the graph.
     pub current_sampler_node: Option<usize>,
+    /// A sampler was sent and its `NodeAdded` (or `Error`) hasn't arrived yet
+    ///
+    /// Further loads wait, so they replace the new node rather than the old one.
+    pub awaiting_node: bool,
+    /// Sample rate the audio engine runs at (None until `EngineInitialized`)
+    ///
+    /// Loaded WAV files are resampled to this rate so they play at the right pitch.
+    pub engine_sample_rate: Option<u32>,
+    /// Latest `(peak, rms)` of each metered node, for channel-strip meters
+    pub node_meters: std::collections::HashMap<usize, (f32, f32)>,
+    /// Seconds the clip indicator stays lit after the master output clipped
+    pub clip_hold: f32,
 }
 
 /// File path state resource
@@ -34,6 +51,8 @@ pub struct AudioState {
 pub struct FilePathState {
     pub current_path: String,
     pub loaded_files: Vec<String>,
+    /// File from the last session, loaded once the engine's sample rate is known
+    pub reopen: Option<String>,
 }
 
 impl Default for FilePathState {
@@ -41,10 +60,23 @@ impl Default for FilePathState {
         Self {
             current_path: "No file selected - click Browse to load a WAV file".to_string(),
             loaded_files: Vec::new(),
+            reopen: None,
         }
     }
 }
 
+impl FilePathState {
+    /// The file loaded last (or still to be reopened), if any
+    pub fn last_file(&self) -> Option<&str> {
+        self.reopen.as_deref().or_else(|| {
+            self.loaded_files
+                .iter()
+                .find(|file| **file == self.current_path)
+                .map(String::as_str)
+        })
+    }
+}
+
 /// Channel for receiving file paths selected from the file dialog
 #[derive(Resource)]
 pub struct FileDialogChannel {
@@ -60,17 +92,28 @@ impl Default for FileDialogChannel {
 }
 
 /// Result of async WAV file loading
-type WavLoadResult = Result<(Vec<f32>, u32, String), String>;
+type WavLoadResult = Result<(vvdaw_comms::PluginInstance, String), String>;
 
 /// Resource tracking pending async WAV file loads
 ///
-/// WAV files are loaded asynchronously to prevent UI freezing on large files (up to 500MB).
-/// Each task runs on Bevy's `AsyncComputeTaskPool` and returns the loaded samples when complete.
+/// WAV files are loaded asynchronously to prevent UI freezing on large files.
+/// Each task runs on Bevy's `AsyncComputeTaskPool` and returns a ready-to-send
+/// sampler when complete - a `SamplerProcessor` holding the samples, or a
+/// `StreamingSampler` for files too large to load into memory.
 #[derive(Resource, Default)]
 pub struct PendingWavLoads {
-    /// Active background loading tasks
-    /// Each task returns `(samples, sample_rate, file_path)` or error message
-    pub tasks: Vec<Task<WavLoadResult>>,
+    /// Active background loads, in the order they were started
+    pub tasks: Vec<PendingWavLoad>,
+}
+
+/// A WAV file being loaded in the background
+pub struct PendingWavLoad {
+    /// Returns `(sampler, file_path)` or error message
+    pub task: Task<WavLoadResult>,
+    /// Progress reports from the task
+    progress_rx: Receiver<LoadProgress>,
+    /// Latest progress report
+    pub progress: LoadProgress,
 }
 
 /// Marker component for the play button
@@ -93,6 +136,18 @@ pub struct PrevFileButton;
 #[derive(Component)]
 pub struct BrowseButton;
 
+/// Marker component for the button that opens the recent files list
+#[derive(Component)]
+pub struct RecentFilesButton;
+
+/// Marker component for the recent files list
+#[derive(Component)]
+pub struct RecentFilesMenu;
+
+/// An entry of the recent files list, holding the file's path
+#[derive(Component)]
+pub struct RecentFileEntry(pub String);
+
 /// Marker component for the file path text
 #[derive(Component)]
 pub struct FilePathText;
@@ -101,9 +156,38 @@ pub struct FilePathText;
 #[derive(Component)]
 pub struct StatusText;
 
+/// Marker component for the master clip indicator
+#[derive(Component)]
+pub struct ClipIndicator;
+
+/// Marker component for the WAV loading progress bar
+#[derive(Component)]
+pub struct LoadProgressBar;
+
+/// Marker component for the filled part of the progress bar
+#[derive(Component)]
+pub struct LoadProgressFill;
+
 const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
 const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
 const PRESSED_BUTTON: Color = Color::srgb(0.35, 0.75, 0.35);
+const CLIP_OFF: Color = Color::srgb(0.25, 0.05, 0.05);
+const CLIP_ON: Color = Color::srgb(1.0, 0.1, 0.1);
+const PROGRESS_TRACK: Color = Color::srgb(0.15, 0.15, 0.15);
+const PROGRESS_FILL: Color = Color::srgb(0.35, 0.75, 0.35);
+const MISSING_FILE_TEXT: Color = Color::srgb(0.45, 0.45, 0.45);
+
+/// Files kept in the loaded files history (and the recent files list)
+pub const MAX_RECENT_FILES: usize = 10;
+
+/// Share of the progress bar the sweeping block covers when a load's length is unknown
+const INDETERMINATE_WIDTH: f32 = 0.25;
+
+/// Sweeps of the indeterminate block per second
+const INDETERMINATE_SPEED: f32 = 0.8;
+
+/// How long the clip indicator stays lit after the last clipped block
+const CLIP_HOLD_SECONDS: f32 = 1.0;
 
 /// Type alias for button interaction query
 type ButtonInteractionQuery<'w, 's> = Query<
@@ -126,7 +210,6 @@ type ButtonInteractionQuery<'w, 's> = Query<
 pub fn setup_ui(mut commands: Commands) {
     // Insert resources
     commands.insert_resource(AudioState::default());
-    commands.insert_resource(FilePathState::default());
     commands.insert_resource(FileDialogChannel::default());
     commands.insert_resource(PendingWavLoads::default());
 
@@ -284,7 +367,48 @@ pub fn setup_ui(mut commands: Commands) {
                                         TextColor(Color::srgb(0.9, 0.9, 0.9)),
                                     ));
                                 });
+
+                            // Recent files button
+                            parent
+                                .spawn((
+                                    Button,
+                                    Node {
+                                        width: Val::Px(150.0),
+                                        height: Val::Px(50.0),
+                                        margin: UiRect::all(Val::Px(10.0)),
+                                        border: UiRect::all(Val::Px(2.0)),
+                                        justify_content: JustifyContent::Center,
+                                        align_items: AlignItems::Center,
+                                        ..default()
+                                    },
+                                    BorderColor::all(Color::BLACK),
+                                    BackgroundColor(NORMAL_BUTTON),
+                                    RecentFilesButton,
+                                ))
+                                .with_children(|parent| {
+                                    parent.spawn((
+                                        Text::new("Recent"),
+                                        TextFont {
+                                            font_size: 20.0,
+                                            ..default()
+                                        },
+                                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                                    ));
+                                });
                         });
+
+                    // Recent files list - hidden until the Recent button is
+                    // pressed, filled in by update_recent_files_menu
+                    parent.spawn((
+                        Node {
+                            display: Display::None,
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Stretch,
+                            min_width: Val::Px(400.0),
+                            ..default()
+                        },
+                        RecentFilesMenu,
+                    ));
                 });
 
             // Playback controls section
@@ -367,6 +491,60 @@ pub fn setup_ui(mut commands: Commands) {
                 },
                 StatusText,
             ));
+
+            // Loading progress - shown while a WAV file loads
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(400.0),
+                        height: Val::Px(8.0),
+                        margin: UiRect::bottom(Val::Px(20.0)),
+                        overflow: Overflow::clip(),
+                        ..default()
+                    },
+                    BackgroundColor(PROGRESS_TRACK),
+                    Visibility::Hidden,
+                    LoadProgressBar,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Node {
+                            width: Val::Percent(0.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(PROGRESS_FILL),
+                        LoadProgressFill,
+                    ));
+                });
+
+            // Clip indicator - lights up when the master output clips
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        Node {
+                            width: Val::Px(16.0),
+                            height: Val::Px(16.0),
+                            margin: UiRect::right(Val::Px(8.0)),
+                            ..default()
+                        },
+                        BackgroundColor(CLIP_OFF),
+                        ClipIndicator,
+                    ));
+                    parent.spawn((
+                        Text::new("Clip"),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                    ));
+                });
         });
 
     tracing::info!("UI setup complete");
@@ -495,6 +673,170 @@ pub fn update_file_path_text(
     }
 }
 
+/// Show or hide the recent files list when the Recent button is pressed
+#[allow(clippy::needless_pass_by_value)]
+pub fn toggle_recent_files_menu(
+    button_query: Query<&Interaction, (Changed<Interaction>, With<RecentFilesButton>)>,
+    mut menu_query: Query<&mut Node, With<RecentFilesMenu>>,
+) {
+    if !button_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        return;
+    }
+    for mut node in &mut menu_query {
+        node.display = if node.display == Display::None {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
+
+/// Rebuild the recent files list when the file history changes
+///
+/// Files are listed newest first. Files that have gone missing since they
+/// were loaded are greyed out.
+#[allow(clippy::needless_pass_by_value)]
+pub fn update_recent_files_menu(
+    mut commands: Commands,
+    file_state: Res<FilePathState>,
+    menu_query: Query<(Entity, Option<&Children>), With<RecentFilesMenu>>,
+) {
+    if !file_state.is_changed() {
+        return;
+    }
+
+    for (menu, children) in &menu_query {
+        for child in children.into_iter().flatten() {
+            commands.entity(*child).despawn();
+        }
+
+        commands.entity(menu).with_children(|parent| {
+            if file_state.loaded_files.is_empty() {
+                parent.spawn((
+                    Text::new("No recent files"),
+                    TextFont {
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(MISSING_FILE_TEXT),
+                ));
+            }
+
+            for path in file_state.loaded_files.iter().rev() {
+                let text_color = if std::path::Path::new(path).is_file() {
+                    Color::srgb(0.9, 0.9, 0.9)
+                } else {
+                    MISSING_FILE_TEXT
+                };
+                parent
+                    .spawn((
+                        Button,
+                        Node {
+                            padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                            margin: UiRect::vertical(Val::Px(1.0)),
+                            ..default()
+                        },
+                        BackgroundColor(NORMAL_BUTTON),
+                        RecentFileEntry(path.clone()),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            Text::new(path.as_str()),
+                            TextFont {
+                                font_size: 16.0,
+                                ..default()
+                            },
+                            TextColor(text_color),
+                        ));
+                    });
+            }
+        });
+    }
+}
+
+/// Load a file picked from the recent files list and close the list
+///
+/// The file goes through the same path as one picked in the file dialog.
+#[allow(clippy::needless_pass_by_value)]
+pub fn handle_recent_file_clicks(
+    entry_query: Query<(&Interaction, &RecentFileEntry), Changed<Interaction>>,
+    mut menu_query: Query<&mut Node, With<RecentFilesMenu>>,
+    file_dialog_channel: Res<FileDialogChannel>,
+    mut audio_state: ResMut<AudioState>,
+) {
+    for (interaction, RecentFileEntry(path)) in &entry_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        if std::path::Path::new(path).is_file() {
+            let _ = file_dialog_channel.sender.send(path.clone());
+        } else {
+            audio_state.status_message = format!("File not found: {path}");
+        }
+        for mut node in &mut menu_query {
+            node.display = Display::None;
+        }
+    }
+}
+
+/// Light the clip indicator while the clip hold runs down
+#[allow(clippy::needless_pass_by_value)]
+pub fn update_clip_indicator(
+    time: Res<Time>,
+    mut audio_state: ResMut<AudioState>,
+    mut query: Query<&mut BackgroundColor, With<ClipIndicator>>,
+) {
+    let lit = audio_state.clip_hold > 0.0;
+    if lit {
+        audio_state.clip_hold = (audio_state.clip_hold - time.delta_secs()).max(0.0);
+    }
+    for mut color in &mut query {
+        color.0 = if lit { CLIP_ON } else { CLIP_OFF };
+    }
+}
+
+/// Show the progress of the oldest pending WAV load
+///
+/// A load of unknown length shows a block sweeping across the bar instead.
+pub fn update_load_progress(
+    time: Res<Time>,
+    mut pending_loads: ResMut<PendingWavLoads>,
+    mut bar_query: Query<&mut Visibility, With<LoadProgressBar>>,
+    mut fill_query: Query<&mut Node, With<LoadProgressFill>>,
+) {
+    for load in &mut pending_loads.tasks {
+        if let Some(progress) = load.progress_rx.try_iter().last() {
+            load.progress = progress;
+        }
+    }
+
+    let progress = pending_loads.tasks.first().map(|load| load.progress);
+    for mut visibility in &mut bar_query {
+        *visibility = if progress.is_some() {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+
+    let (left, width) = match progress {
+        Some(LoadProgress::Fraction(fraction)) => (0.0, fraction),
+        Some(LoadProgress::Indeterminate) => (
+            (time.elapsed_secs() * INDETERMINATE_SPEED).fract() * (1.0 - INDETERMINATE_WIDTH),
+            INDETERMINATE_WIDTH,
+        ),
+        None => return,
+    };
+    for mut node in &mut fill_query {
+        node.left = Val::Percent(left * 100.0);
+        node.width = Val::Percent(width * 100.0);
+    }
+}
+
 /// Poll for events from the audio thread and update UI state
 #[allow(clippy::needless_pass_by_value)]
 pub fn poll_audio_events(
@@ -516,13 +858,40 @@ pub fn poll_audio_events(
                 audio_state.playback = PlaybackState::Stopped;
                 audio_state.status_message = "Status: Stopped".to_string();
             }
-            AudioEvent::EngineInitialized { sample_rate } => {
-                tracing::info!("✓ Audio engine initialized at {}Hz", sample_rate);
-                // 2D UI doesn't need to track sample rate currently, but we log it
+            AudioEvent::EngineInitialized {
+                sample_rate,
+                output_channels,
+                device_name,
+            } => {
+                tracing::info!(
+                    "✓ Audio engine initialized at {}Hz, {} channels on {}",
+                    sample_rate,
+                    output_channels,
+                    device_name
+                );
+                audio_state.engine_sample_rate = Some(sample_rate);
+            }
+            AudioEvent::RealtimePriority { granted, detail } => {
+                if granted {
+                    tracing::info!("✓ Audio thread running with real-time priority ({detail})");
+                } else {
+                    tracing::warn!(
+                        "Real-time priority not permitted ({detail}) - audio thread running at normal priority"
+                    );
+                }
+            }
+            AudioEvent::CpuAffinity { pinned, detail } => {
+                if pinned {
+                    tracing::info!("✓ Audio thread pinned to {detail}");
+                } else {
+                    tracing::warn!("Could not pin audio thread ({detail}) - running unpinned");
+                }
             }
             AudioEvent::Error(msg) => {
                 tracing::error!("Audio error: {msg}");
                 audio_state.status_message = format!("Error: {msg}");
+                // A rejected add/replace is answered with an error instead of NodeAdded
+                audio_state.awaiting_node = false;
             }
             AudioEvent::PeakLevel { channel, level } => {
                 // Just log for now, we're not rendering meters yet
@@ -534,13 +903,76 @@ pub fn poll_audio_events(
                 tracing::debug!("Node added to graph with ID: {node_id}");
                 // Track this as the current sampler (assuming UI only adds samplers)
                 audio_state.current_sampler_node = Some(node_id);
+                audio_state.awaiting_node = false;
             }
             AudioEvent::NodeRemoved { node_id } => {
                 tracing::debug!("Node removed from graph: {node_id}");
+                if audio_state.current_sampler_node == Some(node_id) {
+                    audio_state.current_sampler_node = None;
+                }
+            }
+            AudioEvent::NodeBypassChanged { node_id, bypassed } => {
+                tracing::debug!("Node {node_id} bypassed: {bypassed}");
+            }
+            AudioEvent::NodeSoloChanged { node_id, solo } => {
+                tracing::debug!("Node {node_id} soloed: {solo}");
+            }
+            AudioEvent::ParameterChanged {
+                node_id,
+                param_id,
+                value,
+            } => {
+                tracing::debug!("Node {node_id} parameter {param_id} edited: {value}");
+            }
+            AudioEvent::PlaybackRateChanged { node_id, rate } => {
+                tracing::debug!("Node {node_id} playback rate: {rate}x");
+            }
+            AudioEvent::GraphBypassChanged { bypassed } => {
+                tracing::debug!("Graph bypassed: {bypassed}");
+            }
+            AudioEvent::InputMonitorChanged { enabled } => {
+                tracing::debug!("Input monitoring: {enabled}");
+            }
+            AudioEvent::NodeReloaded { node_id } => {
+                tracing::debug!("Node reloaded: {node_id}");
+            }
+            AudioEvent::NodeMoved { node_id, before } => {
+                tracing::debug!("Node {node_id} moved before {before:?}");
+            }
+            AudioEvent::NodeMeter { node_id, peak, rms } => {
+                audio_state.node_meters.insert(node_id, (peak, rms));
+            }
+            AudioEvent::LatencyChanged { frames } => {
+                tracing::debug!("Graph latency changed to {frames} frames");
+            }
+            AudioEvent::Xrun { count } => {
+                tracing::warn!("Audio xrun detected ({count} so far)");
+            }
+            AudioEvent::DspLoad { fraction } => {
+                tracing::trace!("DSP load: {:.0}%", fraction * 100.0);
+            }
+            AudioEvent::NodeCpu { node_id, fraction } => {
+                tracing::trace!("Node {node_id} CPU: {:.0}%", fraction * 100.0);
+            }
+            AudioEvent::Clip { channel, count } => {
+                tracing::debug!("Master channel {channel} clipped ({count} samples)");
+                audio_state.clip_hold = CLIP_HOLD_SECONDS;
+            }
+            AudioEvent::PositionChanged { position } => {
+                tracing::debug!("Playback position moved to frame {position}");
             }
             AudioEvent::WaveformSample { .. } => {
                 // Waveform samples are handled by 3D visualization, ignore in 2D UI
             }
+            AudioEvent::StereoCorrelation { value } => {
+                tracing::trace!("Stereo correlation: {value:+.2}");
+            }
+            AudioEvent::Spectrum { bins } => {
+                tracing::trace!("Spectrum with {} bins", bins.len());
+            }
+            AudioEvent::RecordingStopped { frames_written } => {
+                tracing::info!("Recording stopped after {frames_written} frames");
+            }
         }
     }
 
@@ -552,6 +984,65 @@ pub fn poll_audio_events(
     }
 }
 
+/// Load the file from the last session once the engine's sample rate is known
+///
+/// Waiting for the rate means the file is resampled like any other load,
+/// rather than playing at the wrong pitch.
+#[allow(clippy::needless_pass_by_value)]
+pub fn reopen_last_file(
+    mut file_state: ResMut<FilePathState>,
+    audio_state: Res<AudioState>,
+    file_dialog_channel: Res<FileDialogChannel>,
+) {
+    if file_state.reopen.is_none() || audio_state.engine_sample_rate.is_none() {
+        return;
+    }
+    let Some(path) = file_state.reopen.take() else {
+        return;
+    };
+
+    if std::path::Path::new(&path).is_file() {
+        tracing::info!("Reopening {path}");
+        let _ = file_dialog_channel.sender.send(path);
+    } else {
+        tracing::warn!("Last loaded file {path} is gone - not reopening it");
+    }
+}
+
+/// Route files dropped onto the window
+///
+/// WAV files load as if picked in the file dialog, in the order they were
+/// dropped. This UI only hosts samplers, so anything else is refused.
+#[allow(clippy::needless_pass_by_value)]
+pub fn handle_file_drops(
+    mut drops: MessageReader<FileDragAndDrop>,
+    file_dialog_channel: Res<FileDialogChannel>,
+    mut audio_state: ResMut<AudioState>,
+) {
+    for event in drops.read() {
+        let FileDragAndDrop::DroppedFile { path_buf, .. } = event else {
+            continue;
+        };
+
+        let is_wav = path_buf
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("wav"));
+        if is_wav {
+            tracing::info!("WAV file dropped: {}", path_buf.display());
+            let _ = file_dialog_channel
+                .sender
+                .send(path_buf.display().to_string());
+        } else {
+            tracing::warn!("Ignoring dropped file: {}", path_buf.display());
+            audio_state.status_message = format!(
+                "Can't load {} - only WAV files can be dropped here",
+                path_buf.display()
+            );
+        }
+    }
+}
+
 /// Poll for file paths selected from the file dialog
 #[allow(clippy::needless_pass_by_value)]
 pub fn poll_file_dialog(
@@ -568,6 +1059,9 @@ pub fn poll_file_dialog(
         // Add to loaded files history if not already there
         if !file_state.loaded_files.contains(&path_string) {
             file_state.loaded_files.push(path_string.clone());
+            if file_state.loaded_files.len() > MAX_RECENT_FILES {
+                file_state.loaded_files.remove(0);
+            }
         }
 
         // Load asynchronously in background thread
@@ -577,85 +1071,65 @@ pub fn poll_file_dialog(
 
 /// Poll for completed async WAV loading tasks and send processors to audio thread
 ///
-/// This system checks all pending async tasks and processes completed ones.
-/// Completed tasks are removed from the pending list.
+/// Tasks finish in the order they were started, so several files loaded at
+/// once (e.g. dropped together) are sent in that order. Completed tasks are
+/// removed from the pending list.
 #[allow(clippy::needless_pass_by_value)]
 pub fn poll_wav_load_tasks(
     mut pending_loads: ResMut<PendingWavLoads>,
     audio_channels: Res<AudioChannelResource>,
     mut audio_state: ResMut<AudioState>,
 ) {
-    // Check each pending task for completion (non-blocking)
-    let mut completed_indices = Vec::new();
-
-    for (idx, task) in pending_loads.tasks.iter_mut().enumerate() {
-        // Non-blocking poll - returns Some if task is ready
-        if let Some(result) = future::block_on(future::poll_once(task)) {
-            completed_indices.push(idx);
-
-            match result {
-                Ok((samples, sample_rate, path)) => {
-                    tracing::info!(
-                        "WAV load task completed: {} frames at {}Hz",
-                        samples.len() / 2,
-                        sample_rate
-                    );
-
-                    // Create sampler processor with loaded audio
-                    let processor = Box::new(vvdaw_audio::builtin::sampler::SamplerProcessor::new(
-                        samples,
-                        sample_rate,
-                    ));
-
-                    // Remove old sampler if one exists to prevent multiple samplers
-                    // This prevents the race condition where rapid next/prev clicking
-                    // adds multiple samplers to the graph.
-                    if let Some(old_node_id) = audio_state.current_sampler_node {
-                        tracing::debug!("Removing old sampler node {old_node_id}");
-                        if let Err(e) =
-                            audio_channels.send_command(AudioCommand::RemoveNode(old_node_id))
-                        {
-                            tracing::warn!("Failed to remove old sampler: {e}");
-                            // Continue anyway - the new sampler will still be added
-                        }
-                        audio_state.current_sampler_node = None;
-                    }
-
-                    // Send sampler to audio thread
-                    if let Err(e) = audio_channels.send_plugin(processor) {
-                        tracing::error!("Failed to send sampler to audio thread: {e}");
-                        audio_state.status_message = format!("Error: {e}");
-                        continue;
-                    }
+    // Check pending tasks for completion in order (non-blocking)
+    let mut completed = 0;
+
+    for load in &mut pending_loads.tasks {
+        // Wait for the previous sampler's NodeAdded so this one replaces it
+        // (unfinished tasks stay pending and are polled again next frame)
+        if audio_state.awaiting_node {
+            break;
+        }
 
-                    // Send AddNode command
-                    // The audio thread will send back a NodeAdded event with the ID,
-                    // which we'll handle in poll_audio_events to track the new sampler
-                    if let Err(e) = audio_channels.send_command(AudioCommand::AddNode) {
-                        tracing::error!("Failed to send AddNode command: {e}");
-                        audio_state.status_message = format!("Error: {e}");
-                    } else {
-                        audio_state.status_message = format!(
-                            "Loaded: {}",
-                            std::path::Path::new(&path)
-                                .file_name()
-                                .and_then(|n| n.to_str())
-                                .unwrap_or("file")
-                        );
-                    }
-                }
-                Err(e) => {
-                    tracing::error!("WAV load task failed: {e}");
-                    audio_state.status_message = format!("Load error: {e}");
+        // Non-blocking poll - returns Some if task is ready; later tasks
+        // wait for this one
+        let Some(result) = future::block_on(future::poll_once(&mut load.task)) else {
+            break;
+        };
+        completed += 1;
+
+        match result {
+            Ok((processor, path)) => {
+                tracing::info!("WAV load task completed: {path}");
+
+                // Replace the old sampler (if any) in a single command, so rapid
+                // next/prev clicking can't leave multiple samplers in the graph.
+                // The audio thread answers with NodeAdded, which we handle in
+                // poll_audio_events to track the new sampler.
+                if let Err(e) =
+                    audio_channels.send_node(processor, audio_state.current_sampler_node)
+                {
+                    tracing::error!("Failed to send sampler to audio thread: {e}");
+                    audio_state.status_message = format!("Error: {e}");
+                } else {
+                    audio_state.awaiting_node = true;
+                    audio_state.status_message = format!(
+                        "Loaded: {}",
+                        std::path::Path::new(&path)
+                            .file_name()
+                            .and_then(|n| n.to_str())
+                            .unwrap_or("file")
+                    );
                 }
             }
+            Err(e) => {
+                tracing::error!("WAV load task failed: {e}");
+                audio_state.status_message = format!("Load error: {e}");
+            }
         }
     }
 
-    // Remove completed tasks (iterate in reverse to maintain indices)
-    for idx in completed_indices.into_iter().rev() {
-        drop(pending_loads.tasks.swap_remove(idx));
-    }
+    // Remove completed tasks
+    drop(pending_loads.tasks.drain(..completed));
 }
 
 /// Load a WAV file asynchronously and queue it for sending to the audio thread
@@ -676,19 +1150,54 @@ fn load_and_send_wav(
             .unwrap_or("file")
     );
 
+    if audio_state.engine_sample_rate.is_none() {
+        tracing::warn!("Engine sample rate not known yet - loading without resampling");
+    }
+
     // Spawn async task on compute thread pool
+    let (progress_tx, progress_rx) = crossbeam_channel::unbounded();
     let path_owned = path.to_string();
+    let target_rate = audio_state.engine_sample_rate;
     let task_pool = AsyncComputeTaskPool::get();
     let task = task_pool.spawn(async move {
+        // Files too large to load into memory are streamed from disk instead
+        let file_size = std::fs::metadata(&path_owned).map_or(0, |metadata| metadata.len());
+        if file_size > STREAMING_THRESHOLD_BYTES {
+            tracing::info!(
+                "Streaming {:.1}MB file from disk",
+                file_size as f64 / (1024.0 * 1024.0)
+            );
+            return StreamingSampler::open(&path_owned)
+                .map(|sampler| (Box::new(sampler) as vvdaw_comms::PluginInstance, path_owned))
+                .map_err(|e| {
+                    tracing::error!("Async load failed: {e}");
+                    e.to_string()
+                });
+        }
+
         // Load WAV file in background thread
-        match load_wav_file(&path_owned) {
+        let mut report = |progress: LoadProgress| {
+            let _ = progress_tx.send(progress);
+        };
+        match load_wav_file(&path_owned, &mut report) {
             Ok((samples, sample_rate)) => {
                 tracing::info!(
                     "Async load complete: {} frames at {}Hz",
                     samples.len() / 2,
                     sample_rate
                 );
-                Ok((samples, sample_rate, path_owned))
+
+                // Resample here, off the UI thread, so the sampler plays at the right pitch
+                let (samples, sample_rate) = match target_rate {
+                    Some(target) if target != sample_rate => (
+                        vvdaw_audio::resample::resample_stereo(&samples, sample_rate, target),
+                        target,
+                    ),
+                    _ => (samples, sample_rate),
+                };
+
+                let sampler = SamplerProcessor::new(samples, sample_rate);
+                Ok((Box::new(sampler) as vvdaw_comms::PluginInstance, path_owned))
             }
             Err(e) => {
                 tracing::error!("Async load failed: {e}");
@@ -697,7 +1206,11 @@ fn load_and_send_wav(
         }
     });
 
-    pending_loads.tasks.push(task);
+    pending_loads.tasks.push(PendingWavLoad {
+        task,
+        progress_rx,
+        progress: LoadProgress::Fraction(0.0),
+    });
 }
 
 /// Load a WAV file and convert to interleaved stereo f32 samples
@@ -706,31 +1219,38 @@ fn load_and_send_wav(
 ///
 /// # Safety Limits
 ///
-/// - Maximum file size: 500MB (prevents UI freezing on multi-GB files)
-/// - Bit depth: 1-31 bits (prevents integer overflow)
+/// - Maximum file size: 500MB (larger files are streamed by `load_and_send_wav`)
+/// - Integer bit depth: 1-31 bits (prevents integer overflow), including unsigned 8-bit
+/// - Float: 32-bit only (64-bit float is rejected with a clear error)
 /// - Validates file exists and is readable
-fn load_wav_file(path: &str) -> Result<(Vec<f32>, u32), String> {
-    // Validate file size before loading (500MB limit)
-    const MAX_FILE_SIZE: u64 = 500 * 1024 * 1024; // 500MB
+///
+/// Reports progress through `on_progress` while reading samples.
+fn load_wav_file(
+    path: &str,
+    on_progress: &mut dyn FnMut(LoadProgress),
+) -> Result<(Vec<f32>, u32), String> {
+    // Validate file size before loading (larger files must be streamed)
+    const MAX_FILE_SIZE: u64 = STREAMING_THRESHOLD_BYTES;
     let metadata =
         std::fs::metadata(path).map_err(|e| format!("Failed to read file metadata: {e}"))?;
 
     if metadata.len() > MAX_FILE_SIZE {
         return Err(format!(
-            "File too large: {:.1}MB (max 500MB). Large files should be streamed, not loaded entirely into memory.",
+            "File too large: {:.1}MB (max 500MB). Large files should be streamed with StreamingSampler, not loaded entirely into memory.",
             metadata.len() as f64 / (1024.0 * 1024.0)
         ));
     }
 
-    let mut reader =
-        hound::WavReader::open(path).map_err(|e| format!("Failed to open WAV file: {e}"))?;
+    let mut reader = hound::WavReader::open(path).map_err(wav_progress::wav_open_error)?;
 
     let spec = reader.spec();
     let sample_rate = spec.sample_rate;
     let channels = spec.channels as usize;
 
-    // Validate bit depth to prevent integer overflow
-    if spec.bits_per_sample == 0 || spec.bits_per_sample > 31 {
+    // Validate integer bit depth to prevent overflow (float is always 32-bit)
+    if spec.sample_format == hound::SampleFormat::Int
+        && (spec.bits_per_sample == 0 || spec.bits_per_sample > 31)
+    {
         return Err(format!(
             "Unsupported bit depth: {} bits (supported: 1-31)",
             spec.bits_per_sample
@@ -744,23 +1264,9 @@ fn load_wav_file(path: &str) -> Result<(Vec<f32>, u32), String> {
         spec.bits_per_sample
     );
 
-    // Read all samples and convert to f32
-    let raw_samples: Vec<f32> = match spec.sample_format {
-        hound::SampleFormat::Float => reader
-            .samples::<f32>()
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| format!("Failed to read samples: {e}"))?,
-        hound::SampleFormat::Int => {
-            // Convert integer samples to f32 [-1.0, 1.0]
-            // Safe: bits_per_sample is validated to be 1-31 above
-            let max_value = (1_i32 << (spec.bits_per_sample - 1)) as f32;
-            reader
-                .samples::<i32>()
-                .map(|s| s.map(|v| v as f32 / max_value))
-                .collect::<Result<Vec<_>, _>>()
-                .map_err(|e| format!("Failed to read samples: {e}"))?
-        }
-    };
+    // Read all samples and convert to f32 [-1.0, 1.0]
+    let raw_samples = wav_progress::read_samples(&mut reader, metadata.len(), on_progress)
+        .map_err(|e| format!("Failed to read samples: {e}"))?;
 
     // Convert to interleaved stereo
     let stereo_samples = match channels {
@@ -1,9 +1,10 @@
 //! Example: Scan for installed VST3 plugins
 //!
 //! This example discovers all VST3 plugins installed on the system
-//! and displays their information.
+//! and displays their information. Directories given as arguments are
+//! scanned before the standard ones.
 //!
-//! Run with: `cargo run --example scan_plugins --release`
+//! Run with: `cargo run --example scan_plugins --release [-- /opt/plugins ...]`
 
 fn main() {
     tracing_subscriber::fmt()
@@ -11,8 +12,15 @@ fn main() {
         .init();
     println!("VST3 Plugin Scanner");
     println!("===================\n");
-    println!("Scanning system VST3 directories...\n");
-    let plugins = vvdaw_vst3::Vst3Loader::scan_system();
+    let extra_paths = std::env::args_os().skip(1).map(Into::into).collect();
+    vvdaw_vst3::Vst3Loader::set_extra_search_paths(extra_paths);
+    println!("Scanning VST3 directories:");
+    for path in vvdaw_vst3::Vst3Loader::search_paths() {
+        println!("  - {}", path.display());
+    }
+    println!();
+    let report = vvdaw_vst3::Vst3Loader::scan_system();
+    let plugins = report.plugins;
     if plugins.is_empty() {
         println!("No VST3 plugins found.");
         println!("\nStandard VST3 locations:");
@@ -57,4 +65,12 @@ fn main() {
 
         println!("Total: {} plugins", plugins.len());
     }
+
+    if !report.errors.is_empty() {
+        println!("\nFailed to scan {} bundle(s):", report.errors.len());
+        for error in &report.errors {
+            println!("  - {}", error.path.display());
+            println!("    {}", error.message);
+        }
+    }
 }
@@ -199,12 +199,18 @@ fn handle_control_message(
             sample_rate,
             max_block_size,
         } => {
-            plugin
+            let mut plugin = plugin
                 .lock()
-                .map_err(|e| format!("Plugin lock poisoned: {e}"))?
+                .map_err(|e| format!("Plugin lock poisoned: {e}"))?;
+            plugin
                 .initialize(*sample_rate, *max_block_size)
                 .map_err(|e| format!("Failed to initialize plugin: {e}"))?;
-            Ok(Some(ResponseMessage::Initialized))
+            Ok(Some(ResponseMessage::Initialized {
+                latency: plugin.latency(),
+                tail: plugin.tail_samples(),
+                input_channels: plugin.input_channels(),
+                output_channels: plugin.output_channels(),
+            }))
         }
 
         ControlMessage::Activate => {
@@ -247,6 +253,24 @@ fn handle_control_message(
             Ok(Some(ResponseMessage::ParameterValue { id: *id, value }))
         }
 
+        ControlMessage::GetParameterDisplay { id, value } => {
+            let text = plugin
+                .lock()
+                .map_err(|e| format!("Plugin lock poisoned: {e}"))?
+                .parameter_display(*id, *value)
+                .map_err(|e| format!("Failed to format parameter: {e}"))?;
+            Ok(Some(ResponseMessage::ParameterDisplay { id: *id, text }))
+        }
+
+        ControlMessage::ParseParameterValue { id, text } => {
+            let value = plugin
+                .lock()
+                .map_err(|e| format!("Plugin lock poisoned: {e}"))?
+                .parameter_from_string(*id, text)
+                .map_err(|e| format!("Failed to parse parameter: {e}"))?;
+            Ok(Some(ResponseMessage::ParameterValue { id: *id, value }))
+        }
+
         ControlMessage::GetParameters => {
             let params = plugin
                 .lock()
@@ -257,6 +281,33 @@ fn handle_control_message(
             let parameters: Vec<_> = params.into_iter().map(Into::into).collect();
             Ok(Some(ResponseMessage::Parameters { parameters }))
         }
+
+        ControlMessage::LoadPreset { path } => {
+            plugin
+                .lock()
+                .map_err(|e| format!("Plugin lock poisoned: {e}"))?
+                .load_preset(path)
+                .map_err(|e| format!("Failed to load preset: {e}"))?;
+            Ok(Some(ResponseMessage::PresetLoaded))
+        }
+
+        ControlMessage::SaveState => {
+            let data = plugin
+                .lock()
+                .map_err(|e| format!("Plugin lock poisoned: {e}"))?
+                .save_state()
+                .map_err(|e| format!("Failed to save state: {e}"))?;
+            Ok(Some(ResponseMessage::State { data }))
+        }
+
+        ControlMessage::LoadState { data } => {
+            plugin
+                .lock()
+                .map_err(|e| format!("Plugin lock poisoned: {e}"))?
+                .load_state(data)
+                .map_err(|e| format!("Failed to load state: {e}"))?;
+            Ok(Some(ResponseMessage::StateLoaded))
+        }
     }
 }
 
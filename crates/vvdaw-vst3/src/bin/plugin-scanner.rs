@@ -8,8 +8,12 @@
 //! - Each plugin gets its own Objective-C runtime (no class conflicts)
 //! - Multiple plugins can be scanned in parallel
 //!
-//! Usage: plugin-scanner <path-to-plugin.vst3>
-//! Output: JSON-encoded `PluginInfo` or error message
+//! Usage: plugin-scanner [--parameters] <path-to-plugin.vst3>
+//! Output: JSON-encoded `PluginInfo` for each audio processor class in the bundle
+//! (plus its parameter list with `--parameters`) or error message
+//!
+//! `--parameters` fully instantiates the plugin to enumerate its parameters,
+//! so the host can cache them without loading the plugin itself.
 
 use std::env;
 use std::process;
@@ -18,19 +22,29 @@ use std::process;
 #[serde(tag = "status")]
 enum ScanResult {
     #[serde(rename = "success")]
-    Success { plugin: vvdaw_plugin::PluginInfo },
+    Success { classes: Vec<ScannedClass> },
 
     #[serde(rename = "error")]
     Error { message: String },
 }
 
+/// One audio processor class of the bundle
+#[derive(serde::Serialize)]
+struct ScannedClass {
+    /// Factory class index (for `ClassSelector::Index`)
+    index: i32,
+    plugin: vvdaw_plugin::PluginInfo,
+    parameters: Vec<vvdaw_vst3::SerializableParameterInfo>,
+}
+
 fn main() {
     // Get plugin path from command line argument
     let args: Vec<String> = env::args().collect();
+    let with_parameters = args.len() == 3 && args[1] == "--parameters";
 
-    if args.len() != 2 {
+    if args.len() != 2 && !with_parameters {
         let error = ScanResult::Error {
-            message: "Usage: plugin-scanner <path-to-plugin.vst3>".to_string(),
+            message: "Usage: plugin-scanner [--parameters] <path-to-plugin.vst3>".to_string(),
         };
         let json = serde_json::to_string(&error)
             .unwrap_or_else(|_| r#"{"status":"error","message":"Usage error"}"#.to_string());
@@ -38,12 +52,30 @@ fn main() {
         process::exit(1);
     }
 
-    let plugin_path = &args[1];
+    let plugin_path = &args[args.len() - 1];
+
+    // Try to load plugin and extract info for every class
+    let scan = load_plugin_info(plugin_path).and_then(|infos| {
+        infos
+            .into_iter()
+            .map(|(index, info)| {
+                let parameters = if with_parameters {
+                    vvdaw_vst3::Vst3Loader::load_plugin_parameters(plugin_path, index)?
+                } else {
+                    Vec::new()
+                };
+                Ok(ScannedClass {
+                    index,
+                    plugin: info,
+                    parameters: parameters.into_iter().map(Into::into).collect(),
+                })
+            })
+            .collect::<Result<Vec<_>, vvdaw_plugin::PluginError>>()
+    });
 
-    // Try to load plugin and extract info
-    match load_plugin_info(plugin_path) {
-        Ok(info) => {
-            let result = ScanResult::Success { plugin: info };
+    match scan {
+        Ok(classes) => {
+            let result = ScanResult::Success { classes };
             let json = serde_json::to_string(&result).unwrap_or_else(|_| {
                 r#"{"status":"error","message":"Serialization error"}"#.to_string()
             });
@@ -64,7 +96,9 @@ fn main() {
 }
 
 /// Load plugin info using the existing loader infrastructure
-fn load_plugin_info(path: &str) -> Result<vvdaw_plugin::PluginInfo, vvdaw_plugin::PluginError> {
+fn load_plugin_info(
+    path: &str,
+) -> Result<Vec<(i32, vvdaw_plugin::PluginInfo)>, vvdaw_plugin::PluginError> {
     // Use the existing load_plugin_info_internal method
     // This loads the library, queries factory, extracts info, then unloads
     vvdaw_vst3::Vst3Loader::load_plugin_info_internal(path)
@@ -16,9 +16,12 @@
 //! - vtable[4] = countClasses
 //! - vtable[5] = getClassInfo
 //! - vtable[6] = createInstance
+//!
+//! `IPluginFactory2` (queried via `queryInterface`) adds:
+//! - vtable[7] = getClassInfo2
 
 use crate::ffi;
-use std::ffi::c_void;
+use std::ffi::{c_char, c_void};
 use vvdaw_plugin::PluginError;
 
 /// COM result type (tresult in VST3)
@@ -55,6 +58,51 @@ pub const ICONNECTION_POINT_IID: [u8; 16] = [
     0x70, 0xA4, 0x15, 0x6F, 0x6E, 0x6E, 0x40, 0x26, 0x98, 0x91, 0x48, 0xBF, 0xAA, 0x60, 0xD8, 0xD1,
 ];
 
+/// `IPluginFactory2` interface ID (IID)
+/// FUID: 0007B650-F24B-4C0B-A464-EDB9F00B2ABB
+/// From VST3 SDK: `DECLARE_CLASS_IID` (`IPluginFactory2`, 0x0007B650, 0xF24B4C0B, 0xA464EDB9, 0xF00B2ABB)
+pub const IPLUGIN_FACTORY2_IID: [u8; 16] = [
+    0x00, 0x07, 0xB6, 0x50, 0xF2, 0x4B, 0x4C, 0x0B, 0xA4, 0x64, 0xED, 0xB9, 0xF0, 0x0B, 0x2A, 0xBB,
+];
+
+/// VST3 `PFactoryInfo` structure
+///
+/// Basic information about the factory (vendor, URL, contact).
+#[repr(C)]
+struct PFactoryInfo {
+    vendor: [c_char; 64],
+    url: [c_char; 256],
+    email: [c_char; 128],
+    flags: i32,
+}
+
+/// VST3 `PClassInfo2` structure
+///
+/// Extended class information returned by `IPluginFactory2::getClassInfo2`.
+#[repr(C)]
+struct PClassInfo2 {
+    cid: [i8; 16],
+    cardinality: i32,
+    category: [c_char; 32],
+    name: [c_char; 64],
+    class_flags: u32,
+    sub_categories: [c_char; 128],
+    vendor: [c_char; 64],
+    version: [c_char; 64],
+    sdk_version: [c_char; 64],
+}
+
+/// Function pointer type for `IPluginFactory::getFactoryInfo`
+///
+/// Fills a `PFactoryInfo` structure with information about the factory.
+type GetFactoryInfoFn = unsafe extern "C" fn(this: *mut c_void, info: *mut PFactoryInfo) -> TResult;
+
+/// Function pointer type for `IPluginFactory2::getClassInfo2`
+///
+/// Fills a `PClassInfo2` structure with extended information about a class.
+type GetClassInfo2Fn =
+    unsafe extern "C" fn(this: *mut c_void, index: i32, info: *mut PClassInfo2) -> TResult;
+
 /// Function pointer type for `IPluginFactory::countClasses`
 ///
 /// Returns the number of classes exported by this factory.
@@ -106,6 +154,30 @@ type ComponentGetBusCountFn = unsafe extern "C" fn(
     bus_direction: i32, // 0=input, 1=output
 ) -> i32;
 
+/// Function pointer type for `IComponent::getBusInfo`
+///
+/// Fills a `BusInfo` structure for the bus at the given index.
+type ComponentGetBusInfoFn = unsafe extern "C" fn(
+    this: *mut c_void,
+    media_type: i32,    // 0=audio, 1=event
+    bus_direction: i32, // 0=input, 1=output
+    bus_index: i32,
+    info: *mut BusInfo,
+) -> TResult;
+
+/// VST3 `BusInfo` structure
+///
+/// Describes a single audio or event bus.
+#[repr(C)]
+pub struct BusInfo {
+    pub media_type: i32,    // 0=audio, 1=event
+    pub direction: i32,     // 0=input, 1=output
+    pub channel_count: i32, // Number of channels (audio buses)
+    pub name: [u16; 128],   // Bus name (UTF-16)
+    pub bus_type: i32,      // 0=main, 1=aux (e.g. sidechain)
+    pub flags: u32,         // BusFlags (1 = default active)
+}
+
 /// Function pointer type for `IComponent::activateBus`
 ///
 /// Activates or deactivates a specific bus.
@@ -122,6 +194,27 @@ type ComponentActivateBusFn = unsafe extern "C" fn(
 /// Activates or deactivates the component.
 type ComponentSetActiveFn = unsafe extern "C" fn(this: *mut c_void, state: u8) -> TResult;
 
+/// Function pointer type for `IAudioProcessor::getBusArrangement`
+///
+/// Fills the speaker arrangement (a bitmask of speakers) of an audio bus.
+type ProcessorGetBusArrangementFn = unsafe extern "C" fn(
+    this: *mut c_void,
+    bus_direction: i32, // 0=input, 1=output
+    bus_index: i32,
+    arrangement: *mut u64,
+) -> TResult;
+
+/// Function pointer type for `IAudioProcessor::canProcessSampleSize`
+///
+/// Returns `kResultTrue` if the plugin can process the given symbolic sample size.
+type ProcessorCanProcessSampleSizeFn =
+    unsafe extern "C" fn(this: *mut c_void, symbolic_sample_size: i32) -> TResult;
+
+/// Function pointer type for `IAudioProcessor::getLatencySamples`
+///
+/// Returns the processing latency in samples.
+type ProcessorGetLatencySamplesFn = unsafe extern "C" fn(this: *mut c_void) -> u32;
+
 /// Function pointer type for `IAudioProcessor::setupProcessing`
 ///
 /// Sets up the audio processing parameters.
@@ -149,10 +242,21 @@ pub struct ProcessSetup {
 /// Contains pointers to audio channel buffers for a single bus.
 #[repr(C)]
 pub struct AudioBusBuffers {
-    pub num_channels: i32,                 // Number of channels in this bus
-    pub silence_flags: u64,                // Bitfield indicating silent channels
-    pub channel_buffers_32: *mut *mut f32, // Array of pointers to 32-bit channel buffers
-    pub channel_buffers_64: *mut *mut f64, // Array of pointers to 64-bit channel buffers
+    pub num_channels: i32,               // Number of channels in this bus
+    pub silence_flags: u64,              // Bitfield indicating silent channels
+    pub channel_buffers: ChannelBuffers, // Channel buffers of the processing sample size
+}
+
+/// The channel buffer array of an `AudioBusBuffers`
+///
+/// A union in the SDK: which member is valid depends on the `ProcessData`
+/// sample size. Plugins index the bus array with the SDK's struct size, so
+/// this must stay a single pointer wide.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union ChannelBuffers {
+    pub samples_32: *mut *mut f32, // Array of pointers to 32-bit channel buffers
+    pub samples_64: *mut *mut f64, // Array of pointers to 64-bit channel buffers
 }
 
 /// VST3 `ProcessData` structure
@@ -160,18 +264,88 @@ pub struct AudioBusBuffers {
 /// Contains all data for a single process call.
 #[repr(C)]
 pub struct ProcessData {
-    pub process_mode: i32,                 // 0=realtime, 1=prefetch, 2=offline
-    pub symbolic_sample_size: i32,         // 0=32bit, 1=64bit
-    pub num_samples: i32,                  // Number of samples in this block
-    pub num_inputs: i32,                   // Number of input buses
-    pub num_outputs: i32,                  // Number of output buses
-    pub inputs: *mut AudioBusBuffers,      // Array of input bus buffers
-    pub outputs: *mut AudioBusBuffers,     // Array of output bus buffers
-    pub input_param_changes: *mut c_void,  // IParameterChanges (null for now)
-    pub output_param_changes: *mut c_void, // IParameterChanges (null for now)
-    pub input_events: *mut c_void,         // IEventList (null for now)
-    pub output_events: *mut c_void,        // IEventList (null for now)
-    pub process_context: *mut c_void,      // ProcessContext (null for now)
+    pub process_mode: i32,                    // 0=realtime, 1=prefetch, 2=offline
+    pub symbolic_sample_size: i32,            // 0=32bit, 1=64bit
+    pub num_samples: i32,                     // Number of samples in this block
+    pub num_inputs: i32,                      // Number of input buses
+    pub num_outputs: i32,                     // Number of output buses
+    pub inputs: *mut AudioBusBuffers,         // Array of input bus buffers
+    pub outputs: *mut AudioBusBuffers,        // Array of output bus buffers
+    pub input_param_changes: *mut c_void,     // IParameterChanges (null for now)
+    pub output_param_changes: *mut c_void,    // IParameterChanges (null for now)
+    pub input_events: *mut c_void,            // IEventList (null if no events)
+    pub output_events: *mut c_void,           // IEventList (null for now)
+    pub process_context: *mut ProcessContext, // Transport state (null if unknown)
+}
+
+/// VST3 `ProcessContext` structure
+///
+/// Transport state (tempo, meter, position) for a single process call.
+/// Only fields flagged as valid in `state` may be read by the plugin.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessContext {
+    pub state: u32,                        // ProcessContext::StatesAndFlags bitfield
+    pub sample_rate: f64,                  // Current sample rate (always valid)
+    pub project_time_samples: i64,         // Project time in samples (always valid)
+    pub system_time: i64,                  // System time in nanoseconds
+    pub continous_time_samples: i64,       // Sample count since processing started
+    pub project_time_music: f64,           // Musical position in quarter notes
+    pub bar_position_music: f64,           // Last bar start in quarter notes
+    pub cycle_start_music: f64,            // Cycle (loop) start in quarter notes
+    pub cycle_end_music: f64,              // Cycle (loop) end in quarter notes
+    pub tempo: f64,                        // Tempo in BPM
+    pub time_sig_numerator: i32,           // Time signature numerator
+    pub time_sig_denominator: i32,         // Time signature denominator
+    pub chord_key_note: u8,                // Chord: key note (0 = C)
+    pub chord_root_note: u8,               // Chord: root note (0 = C)
+    pub chord_mask: i16,                   // Chord: bitmask of chord notes
+    pub smpte_offset_subframes: i32,       // SMPTE offset in 1/80ths of a frame
+    pub frame_rate_frames_per_second: u32, // SMPTE frame rate
+    pub frame_rate_flags: u32,             // SMPTE frame rate flags (pull-down, drop)
+    pub samples_to_next_clock: i32,        // MIDI clock resolution (24 ppq)
+}
+
+impl ProcessContext {
+    /// Describe the host transport at the start of a block
+    #[allow(clippy::cast_possible_wrap)] // Positions and meters are far below i64/i32::MAX
+    pub fn from_transport(transport: &vvdaw_core::Transport) -> Self {
+        use process_context_state::{
+            BAR_POSITION_VALID, PLAYING, PROJECT_TIME_MUSIC_VALID, TEMPO_VALID, TIME_SIG_VALID,
+        };
+
+        let mut state =
+            PROJECT_TIME_MUSIC_VALID | TEMPO_VALID | BAR_POSITION_VALID | TIME_SIG_VALID;
+        if transport.is_playing {
+            state |= PLAYING;
+        }
+
+        Self {
+            state,
+            sample_rate: f64::from(transport.sample_rate),
+            project_time_samples: transport.project_time_samples as i64,
+            project_time_music: transport.ppq_position,
+            bar_position_music: transport.bar_position_ppq(),
+            tempo: transport.tempo_bpm,
+            time_sig_numerator: transport.time_signature.0 as i32,
+            time_sig_denominator: transport.time_signature.1 as i32,
+            ..Self::default()
+        }
+    }
+}
+
+/// `ProcessContext::state` flags (from `ivstprocesscontext.h`)
+pub mod process_context_state {
+    /// Transport is running
+    pub const PLAYING: u32 = 1 << 1;
+    /// `project_time_music` is valid
+    pub const PROJECT_TIME_MUSIC_VALID: u32 = 1 << 9;
+    /// `tempo` is valid
+    pub const TEMPO_VALID: u32 = 1 << 10;
+    /// `bar_position_music` is valid
+    pub const BAR_POSITION_VALID: u32 = 1 << 11;
+    /// `time_sig_numerator` and `time_sig_denominator` are valid
+    pub const TIME_SIG_VALID: u32 = 1 << 13;
 }
 
 /// Function pointer type for `IAudioProcessor::process`
@@ -180,6 +354,14 @@ pub struct ProcessData {
 type ProcessorProcessFn =
     unsafe extern "C" fn(this: *mut c_void, data: *mut ProcessData) -> TResult;
 
+/// Function pointer type for `IAudioProcessor::getTailSamples`
+///
+/// Returns how many samples of output the plugin produces after its input stops.
+type ProcessorGetTailSamplesFn = unsafe extern "C" fn(this: *mut c_void) -> u32;
+
+/// `kInfiniteTail`: the plugin never stops producing output on its own
+pub const K_INFINITE_TAIL: u32 = u32::MAX;
+
 /// Type alias for the `GetPluginFactory` function signature
 ///
 /// The VST3 entry point that returns the plugin factory.
@@ -269,6 +451,86 @@ impl PluginFactory {
         }
     }
 
+    /// Get information about the factory itself (vendor, URL, contact email)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the call fails.
+    #[allow(unsafe_code)]
+    pub fn get_factory_info(&self) -> Result<FactoryInfo, PluginError> {
+        unsafe {
+            let mut factory_info: PFactoryInfo = std::mem::zeroed();
+
+            // Get the vtable pointer
+            let vtable_ptr = *(self.ptr.cast::<*const *const c_void>());
+
+            // getFactoryInfo is at vtable[3]
+            let get_factory_info_ptr = *vtable_ptr.add(3);
+            let get_factory_info_fn: GetFactoryInfoFn = std::mem::transmute(get_factory_info_ptr);
+
+            let result = get_factory_info_fn(self.ptr.cast::<c_void>(), &raw mut factory_info);
+
+            if result != K_RESULT_OK {
+                return Err(PluginError::FormatError(format!(
+                    "getFactoryInfo failed with result: {result}"
+                )));
+            }
+
+            Ok(FactoryInfo {
+                vendor: fixed_c_string(&factory_info.vendor),
+                url: fixed_c_string(&factory_info.url),
+                email: fixed_c_string(&factory_info.email),
+                flags: factory_info.flags,
+            })
+        }
+    }
+
+    /// Get extended information about a class by index
+    ///
+    /// Only available when the factory implements `IPluginFactory2` (or later).
+    /// Callers should fall back to [`Self::get_class_info`] on error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the factory doesn't implement `IPluginFactory2`,
+    /// the index is out of bounds, or the call fails.
+    #[allow(unsafe_code)]
+    pub fn get_class_info2(&self, index: i32) -> Result<ClassInfo2, PluginError> {
+        unsafe {
+            // queryInterface adds a reference which we must release
+            let factory2 = query_interface(self.ptr.cast::<c_void>(), &IPLUGIN_FACTORY2_IID)?;
+
+            let mut class_info: PClassInfo2 = std::mem::zeroed();
+
+            // Get the vtable pointer
+            let vtable_ptr = *(factory2.cast::<*const *const c_void>());
+
+            // getClassInfo2 is at vtable[7] (after the IPluginFactory methods)
+            let get_class_info2_ptr = *vtable_ptr.add(7);
+            let get_class_info2_fn: GetClassInfo2Fn = std::mem::transmute(get_class_info2_ptr);
+
+            let result = get_class_info2_fn(factory2, index, &raw mut class_info);
+            release_interface(factory2);
+
+            if result != K_RESULT_OK {
+                return Err(PluginError::FormatError(format!(
+                    "getClassInfo2 failed with result: {result}"
+                )));
+            }
+
+            Ok(ClassInfo2 {
+                class_id: std::array::from_fn(|i| class_info.cid[i] as u8),
+                category: fixed_c_string(&class_info.category),
+                name: fixed_c_string(&class_info.name),
+                class_flags: class_info.class_flags,
+                sub_categories: fixed_c_string(&class_info.sub_categories),
+                vendor: fixed_c_string(&class_info.vendor),
+                version: fixed_c_string(&class_info.version),
+                sdk_version: fixed_c_string(&class_info.sdk_version),
+            })
+        }
+    }
+
     /// Create an instance of a plugin class
     ///
     /// # Safety
@@ -383,11 +645,52 @@ pub struct ClassInfo {
     pub class_id: [u8; 16], // FUID (128-bit UUID)
     #[allow(dead_code)] // Will be used for plugin validation
     pub cardinality: i32,
-    #[allow(dead_code)] // Will be used for plugin filtering
     pub category: String,
     pub name: String,
 }
 
+/// Information about a plugin factory
+#[derive(Debug, Clone)]
+pub struct FactoryInfo {
+    pub vendor: String,
+    #[allow(dead_code)] // Will be shown in the plugin browser
+    pub url: String,
+    #[allow(dead_code)] // Will be shown in the plugin browser
+    pub email: String,
+    #[allow(dead_code)] // Will be used to honor kClassesDiscardable etc.
+    pub flags: i32,
+}
+
+/// Extended information about a plugin class (`PClassInfo2`)
+#[derive(Debug, Clone)]
+pub struct ClassInfo2 {
+    #[allow(dead_code)] // Same as ClassInfo::class_id
+    pub class_id: [u8; 16],
+    #[allow(dead_code)] // Same as ClassInfo::category
+    pub category: String,
+    #[allow(dead_code)] // Same as ClassInfo::name
+    pub name: String,
+    #[allow(dead_code)] // Will be used for distributable checks
+    pub class_flags: u32,
+    pub sub_categories: String,
+    pub vendor: String,
+    pub version: String,
+    #[allow(dead_code)] // Will be shown in the plugin browser
+    pub sdk_version: String,
+}
+
+/// Convert a fixed-size, NUL-padded C string field to a `String`
+///
+/// Tolerates fields that fill the whole buffer without a terminator.
+fn fixed_c_string(chars: &[c_char]) -> String {
+    let bytes: Vec<u8> = chars
+        .iter()
+        .take_while(|&&c| c != 0)
+        .map(|&c| c as u8)
+        .collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
 /// Helper functions for calling VST3 COM methods
 ///
 /// These functions provide safe(r) wrappers around raw vtable calls.
@@ -507,10 +810,52 @@ pub unsafe fn component_get_controller_class_id(
     }
 }
 
+/// Function pointer type for `IComponent::setState`
+///
+/// Restores the component state (e.g. from a preset).
+type ComponentSetStateFn = unsafe extern "C" fn(this: *mut c_void, state: *mut c_void) -> TResult;
+
+/// Call `IComponent::setState(state)`
+///
+/// Restores the complete component state from the provided stream.
+///
+/// # Safety
+///
+/// The `component` pointer must be valid and point to a valid, initialized
+/// `IComponent` interface. The `state_stream` pointer must be a valid `IBStream` interface.
+#[allow(unsafe_code)]
+pub unsafe fn component_set_state(
+    component: *mut c_void,
+    state_stream: *mut c_void,
+) -> Result<(), PluginError> {
+    unsafe {
+        // Get the vtable pointer
+        let vtable_ptr = *(component.cast::<*const *const c_void>());
+
+        // IComponent vtable layout:
+        // [0-2] FUnknown: queryInterface, addRef, release
+        // [3-4] IPluginBase: initialize, terminate
+        // [5-11] IComponent: getControllerClassId, setIoMode, getBusCount, getBusInfo,
+        //                    getRoutingInfo, activateBus, setActive
+        // [12] IComponent: setState
+        let set_state_ptr = *vtable_ptr.add(12);
+        let set_state_fn: ComponentSetStateFn = std::mem::transmute(set_state_ptr);
+
+        let result = set_state_fn(component, state_stream);
+
+        if result != K_RESULT_OK {
+            return Err(PluginError::FormatError(format!(
+                "IComponent::setState failed with result: {result}"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
 /// Function pointer type for `IComponent::getState`
 ///
 /// Gets the current component state.
-#[allow(dead_code)] // Will be used for preset/session loading
 type ComponentGetStateFn = unsafe extern "C" fn(this: *mut c_void, state: *mut c_void) -> TResult;
 
 /// Call `IComponent::getState(state)`
@@ -522,7 +867,6 @@ type ComponentGetStateFn = unsafe extern "C" fn(this: *mut c_void, state: *mut c
 /// The `component` pointer must be valid and point to a valid `IComponent` interface.
 /// The `state_stream` pointer must be a valid `IBStream` interface.
 #[allow(unsafe_code)]
-#[allow(dead_code)] // Will be used for preset/session loading
 pub unsafe fn component_get_state(
     component: *mut c_void,
     state_stream: *mut c_void,
@@ -634,6 +978,45 @@ pub unsafe fn component_get_bus_count(
     }
 }
 
+/// Call `IComponent::getBusInfo(type, dir, index, info)`
+///
+/// # Safety
+///
+/// The component pointer must be valid and point to a valid `IComponent` interface.
+#[allow(unsafe_code)]
+pub unsafe fn component_get_bus_info(
+    component: *mut c_void,
+    media_type: i32,
+    bus_direction: i32,
+    bus_index: i32,
+) -> Result<BusInfo, PluginError> {
+    unsafe {
+        // Get the vtable pointer
+        let vtable_ptr = *(component.cast::<*const *const c_void>());
+
+        // getBusInfo is at vtable[8]
+        let get_bus_info_ptr = *vtable_ptr.add(8);
+        let get_bus_info_fn: ComponentGetBusInfoFn = std::mem::transmute(get_bus_info_ptr);
+
+        let mut info: BusInfo = std::mem::zeroed();
+        let result = get_bus_info_fn(
+            component,
+            media_type,
+            bus_direction,
+            bus_index,
+            &raw mut info,
+        );
+
+        if result != K_RESULT_OK {
+            return Err(PluginError::FormatError(format!(
+                "IComponent::getBusInfo failed with result: {result}"
+            )));
+        }
+
+        Ok(info)
+    }
+}
+
 /// Call `IComponent::activateBus(type, dir, index, state)`
 ///
 /// # Safety
@@ -712,6 +1095,99 @@ pub unsafe fn component_set_active(component: *mut c_void, state: bool) -> Resul
     }
 }
 
+/// `ProcessSetup::symbolic_sample_size` for 32-bit float processing
+pub const SAMPLE_32: i32 = 0;
+
+/// `ProcessSetup::symbolic_sample_size` for 64-bit float processing
+pub const SAMPLE_64: i32 = 1;
+
+/// Call `IAudioProcessor::getBusArrangement(dir, index, arr)`
+///
+/// Returns the bus's `SpeakerArrangement`: one bit per speaker, so its
+/// channel count is the number of bits set.
+///
+/// # Safety
+///
+/// The processor pointer must be valid and point to a valid `IAudioProcessor` interface.
+#[allow(unsafe_code)]
+pub unsafe fn processor_get_bus_arrangement(
+    processor: *mut c_void,
+    bus_direction: i32,
+    bus_index: i32,
+) -> Result<u64, PluginError> {
+    unsafe {
+        // Get the vtable pointer
+        let vtable_ptr = *(processor.cast::<*const *const c_void>());
+
+        // getBusArrangement is at vtable[4]
+        // (after queryInterface, addRef, release, setBusArrangements)
+        let get_arrangement_ptr = *vtable_ptr.add(4);
+        let get_arrangement_fn: ProcessorGetBusArrangementFn =
+            std::mem::transmute(get_arrangement_ptr);
+
+        let mut arrangement = 0;
+        let result = get_arrangement_fn(processor, bus_direction, bus_index, &raw mut arrangement);
+
+        if result != K_RESULT_OK {
+            return Err(PluginError::FormatError(format!(
+                "IAudioProcessor::getBusArrangement failed with result: {result}"
+            )));
+        }
+
+        Ok(arrangement)
+    }
+}
+
+/// Call `IAudioProcessor::canProcessSampleSize(symbolicSampleSize)`
+///
+/// Returns whether the plugin supports [`SAMPLE_32`] or [`SAMPLE_64`] processing.
+///
+/// # Safety
+///
+/// The processor pointer must be valid and point to a valid `IAudioProcessor` interface.
+#[allow(unsafe_code)]
+pub unsafe fn processor_can_process_sample_size(
+    processor: *mut c_void,
+    symbolic_sample_size: i32,
+) -> bool {
+    unsafe {
+        // Get the vtable pointer
+        let vtable_ptr = *(processor.cast::<*const *const c_void>());
+
+        // canProcessSampleSize is at vtable[5]
+        // (after queryInterface, addRef, release, setBusArrangements, getBusArrangement)
+        let can_process_ptr = *vtable_ptr.add(5);
+        let can_process_fn: ProcessorCanProcessSampleSizeFn = std::mem::transmute(can_process_ptr);
+
+        // kResultTrue (0) = supported, kResultFalse (1) = not supported
+        can_process_fn(processor, symbolic_sample_size) == K_RESULT_OK
+    }
+}
+
+/// Call `IAudioProcessor::getLatencySamples()`
+///
+/// Only meaningful after `setupProcessing` and `setActive(true)`, since the
+/// latency may depend on the sample rate.
+///
+/// # Safety
+///
+/// The processor pointer must be valid and point to a valid `IAudioProcessor` interface.
+#[allow(unsafe_code)]
+pub unsafe fn processor_get_latency_samples(processor: *mut c_void) -> u32 {
+    unsafe {
+        // Get the vtable pointer
+        let vtable_ptr = *(processor.cast::<*const *const c_void>());
+
+        // getLatencySamples is at vtable[6]
+        // (after queryInterface, addRef, release, setBusArrangements, getBusArrangement,
+        //  canProcessSampleSize)
+        let get_latency_ptr = *vtable_ptr.add(6);
+        let get_latency_fn: ProcessorGetLatencySamplesFn = std::mem::transmute(get_latency_ptr);
+
+        get_latency_fn(processor)
+    }
+}
+
 /// Call `IAudioProcessor::setupProcessing(setup)`
 ///
 /// # Safety
@@ -809,6 +1285,28 @@ pub unsafe fn processor_process(
     }
 }
 
+/// Call `IAudioProcessor::getTailSamples()`
+///
+/// Like the latency, only meaningful once the processor is set up and active.
+/// Returns [`K_INFINITE_TAIL`] for plugins whose tail never ends.
+///
+/// # Safety
+///
+/// The processor pointer must be valid and point to a valid `IAudioProcessor` interface.
+#[allow(unsafe_code)]
+pub unsafe fn processor_get_tail_samples(processor: *mut c_void) -> u32 {
+    unsafe {
+        // Get the vtable pointer
+        let vtable_ptr = *(processor.cast::<*const *const c_void>());
+
+        // getTailSamples is at vtable[10] (after process)
+        let get_tail_ptr = *vtable_ptr.add(10);
+        let get_tail_fn: ProcessorGetTailSamplesFn = std::mem::transmute(get_tail_ptr);
+
+        get_tail_fn(processor)
+    }
+}
+
 /// Function pointer type for `IEditController::getParameterCount`
 ///
 /// Returns the number of parameters exposed by the controller.
@@ -838,7 +1336,6 @@ type EditControllerGetParameterInfoFn =
 /// Function pointer type for `IEditController::getParamStringByValue`
 ///
 /// Converts a normalized value to a string representation.
-#[allow(dead_code)] // Will be used for parameter display
 type EditControllerGetParamStringByValueFn = unsafe extern "C" fn(
     this: *mut c_void,
     id: u32,
@@ -849,7 +1346,6 @@ type EditControllerGetParamStringByValueFn = unsafe extern "C" fn(
 /// Function pointer type for `IEditController::getParamValueByString`
 ///
 /// Converts a string to a normalized value.
-#[allow(dead_code)] // Will be used for parameter parsing
 type EditControllerGetParamValueByStringFn = unsafe extern "C" fn(
     this: *mut c_void,
     id: u32,
@@ -866,10 +1362,15 @@ type EditControllerSetComponentHandlerFn =
 /// Function pointer type for `IEditController::setComponentState`
 ///
 /// Sets the component state for the edit controller.
-#[allow(dead_code)] // Will be used for preset/session loading
 type EditControllerSetComponentStateFn =
     unsafe extern "C" fn(this: *mut c_void, state: *mut c_void) -> TResult;
 
+/// Function pointer type for `IEditController::setState`
+///
+/// Restores the edit controller's own state (e.g. from a preset).
+type EditControllerSetStateFn =
+    unsafe extern "C" fn(this: *mut c_void, state: *mut c_void) -> TResult;
+
 /// Call `IEditController::setComponentHandler(handler)`
 ///
 /// Sets the component handler for communication between the edit controller and host.
@@ -938,7 +1439,6 @@ pub unsafe fn edit_controller_set_component_handler(
 /// The `edit_controller` pointer must be valid and point to a valid `IEditController` interface.
 /// The `state_stream` pointer must be valid `IBStream` interface or null.
 #[allow(unsafe_code)]
-#[allow(dead_code)] // Will be used for preset/session loading
 pub unsafe fn edit_controller_set_component_state(
     edit_controller: *mut c_void,
     state_stream: *mut c_void,
@@ -974,6 +1474,43 @@ pub unsafe fn edit_controller_set_component_state(
     }
 }
 
+/// Call `IEditController::setState(state)`
+///
+/// Restores controller-only state, such as the `Cont` chunk of a preset.
+///
+/// # Safety
+///
+/// The `edit_controller` pointer must be valid and point to a valid `IEditController` interface.
+/// The `state_stream` pointer must be a valid `IBStream` interface.
+#[allow(unsafe_code)]
+pub unsafe fn edit_controller_set_state(
+    edit_controller: *mut c_void,
+    state_stream: *mut c_void,
+) -> Result<(), PluginError> {
+    unsafe {
+        // Get the vtable pointer
+        let vtable_ptr = *(edit_controller.cast::<*const *const c_void>());
+
+        // IEditController vtable layout:
+        // [0-2] FUnknown: queryInterface, addRef, release
+        // [3-4] IPluginBase: initialize, terminate
+        // [5] IEditController: setComponentState
+        // [6] IEditController: setState
+        let set_state_ptr = *vtable_ptr.add(6);
+        let set_state_fn: EditControllerSetStateFn = std::mem::transmute(set_state_ptr);
+
+        let result = set_state_fn(edit_controller, state_stream);
+
+        if result != K_RESULT_OK {
+            return Err(PluginError::FormatError(format!(
+                "IEditController::setState failed with result: {result}"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
 /// Call `IEditController::getParameterCount()`
 ///
 /// # Safety
@@ -1089,10 +1626,10 @@ pub unsafe fn edit_controller_get_parameter_info(
 /// Function pointer type for `IEditController::getParamNormalized`
 ///
 /// Gets the current normalized value [0.0, 1.0] for a parameter.
-type EditControllerGetParamNormalizedFn =
-    unsafe extern "C" fn(this: *mut c_void, id: u32, value: *mut f64) -> TResult;
+/// Unlike most VST3 methods this returns the `ParamValue` directly, not a `tresult`.
+type EditControllerGetParamNormalizedFn = unsafe extern "C" fn(this: *mut c_void, id: u32) -> f64;
 
-/// Call `IEditController::getParamNormalized(id, value)`
+/// Call `IEditController::getParamNormalized(id)`
 ///
 /// # Safety
 ///
@@ -1110,8 +1647,6 @@ pub unsafe fn edit_controller_get_param_normalized(
             ));
         }
 
-        let mut value: f64 = 0.0;
-
         // Get the vtable pointer
         let vtable_ptr = *(edit_controller.cast::<*const *const c_void>());
 
@@ -1136,12 +1671,13 @@ pub unsafe fn edit_controller_get_param_normalized(
         let get_param_normalized_fn: EditControllerGetParamNormalizedFn =
             std::mem::transmute(get_param_normalized_ptr);
 
-        // Call getParamNormalized
-        let result = get_param_normalized_fn(edit_controller, id, &raw mut value);
+        // Call getParamNormalized - there is no error code, unknown IDs
+        // typically come back as 0.0
+        let value = get_param_normalized_fn(edit_controller, id);
 
-        if result != K_RESULT_OK {
-            return Err(PluginError::FormatError(format!(
-                "IEditController::getParamNormalized failed with result: {result}"
+        if !value.is_finite() {
+            return Err(PluginError::InvalidParameter(format!(
+                "IEditController::getParamNormalized returned {value} for parameter {id}"
             )));
         }
 
@@ -1216,6 +1752,93 @@ pub unsafe fn edit_controller_set_param_normalized(
     }
 }
 
+/// Length of a VST3 `String128` buffer in UTF-16 code units (including the NUL)
+const STRING128_LEN: usize = 128;
+
+/// Call `IEditController::getParamStringByValue(id, value)`
+///
+/// Returns the plugin's display text for a normalized value (e.g. "-6.0 dB").
+///
+/// # Safety
+///
+/// The `edit_controller` pointer must be valid and point to a valid `IEditController` interface.
+#[allow(unsafe_code)]
+pub unsafe fn edit_controller_get_param_string_by_value(
+    edit_controller: *mut c_void,
+    id: u32,
+    value_normalized: f64,
+) -> Result<String, PluginError> {
+    unsafe {
+        // Get the vtable pointer
+        let vtable_ptr = *(edit_controller.cast::<*const *const c_void>());
+
+        // getParamStringByValue is at vtable[10] (see setComponentHandler for the layout)
+        let get_string_ptr = *vtable_ptr.add(10);
+        let get_string_fn: EditControllerGetParamStringByValueFn =
+            std::mem::transmute(get_string_ptr);
+
+        // String128 output buffer; the plugin writes a NUL-terminated string
+        let mut buffer = [0_u16; STRING128_LEN];
+        let result = get_string_fn(
+            edit_controller,
+            id,
+            value_normalized,
+            buffer.as_mut_ptr().cast::<i16>(),
+        );
+
+        if result != K_RESULT_OK {
+            return Err(PluginError::InvalidParameter(format!(
+                "IEditController::getParamStringByValue failed for parameter {id} with result: {result}"
+            )));
+        }
+
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(STRING128_LEN);
+        Ok(String::from_utf16_lossy(&buffer[..len]))
+    }
+}
+
+/// Call `IEditController::getParamValueByString(id, string)`
+///
+/// Parses display text (as produced by `getParamStringByValue`) into a
+/// normalized value.
+///
+/// # Safety
+///
+/// The `edit_controller` pointer must be valid and point to a valid `IEditController` interface.
+#[allow(unsafe_code)]
+pub unsafe fn edit_controller_get_param_value_by_string(
+    edit_controller: *mut c_void,
+    id: u32,
+    text: &str,
+) -> Result<f64, PluginError> {
+    unsafe {
+        // Get the vtable pointer
+        let vtable_ptr = *(edit_controller.cast::<*const *const c_void>());
+
+        // getParamValueByString is at vtable[11] (see setComponentHandler for the layout)
+        let get_value_ptr = *vtable_ptr.add(11);
+        let get_value_fn: EditControllerGetParamValueByStringFn =
+            std::mem::transmute(get_value_ptr);
+
+        let utf16: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
+        let mut value = 0.0_f64;
+        let result = get_value_fn(
+            edit_controller,
+            id,
+            utf16.as_ptr().cast::<i16>(),
+            &raw mut value,
+        );
+
+        if result != K_RESULT_OK || !value.is_finite() {
+            return Err(PluginError::InvalidParameter(format!(
+                "Parameter {id} does not accept '{text}' (result: {result})"
+            )));
+        }
+
+        Ok(value)
+    }
+}
+
 /// Function pointer type for `IConnectionPoint::connect`
 ///
 /// Establishes a connection between two connection points.
@@ -1269,4 +1892,53 @@ mod tests {
         let factory = unsafe { PluginFactory::from_raw(std::ptr::null_mut()) };
         assert!(factory.is_none());
     }
+
+    #[test]
+    fn test_fixed_c_string() {
+        let mut field = [0 as c_char; 8];
+        for (dst, &src) in field.iter_mut().zip(b"vvdaw") {
+            *dst = src as c_char;
+        }
+        assert_eq!(fixed_c_string(&field), "vvdaw");
+
+        // Fields that fill the buffer have no terminator
+        let full = [b'a' as c_char; 4];
+        assert_eq!(fixed_c_string(&full), "aaaa");
+    }
+
+    #[test]
+    fn test_process_context_from_transport() {
+        // Matches sizeof(Steinberg::Vst::ProcessContext) on 64-bit platforms
+        assert_eq!(std::mem::size_of::<ProcessContext>(), 112);
+
+        let mut transport = vvdaw_core::Transport::new(48000);
+        transport.set_tempo(140.0);
+        transport.time_signature = (3, 4);
+        transport.locate(48000 * 2);
+
+        let context = ProcessContext::from_transport(&transport);
+        assert_eq!(context.state & process_context_state::PLAYING, 0);
+        assert_ne!(context.state & process_context_state::TEMPO_VALID, 0);
+        assert_eq!(context.tempo, 140.0);
+        assert_eq!(context.project_time_samples, 96000);
+        // Two seconds at 140 BPM is 4.67 beats, inside the second bar of 3/4
+        assert!((context.project_time_music - 14.0 / 3.0).abs() < 1e-9);
+        assert_eq!(context.bar_position_music, 3.0);
+        assert_eq!(
+            (context.time_sig_numerator, context.time_sig_denominator),
+            (3, 4)
+        );
+
+        transport.is_playing = true;
+        let context = ProcessContext::from_transport(&transport);
+        assert_ne!(context.state & process_context_state::PLAYING, 0);
+    }
+
+    #[test]
+    fn test_audio_bus_buffers_layout() {
+        // Matches sizeof(Steinberg::Vst::AudioBusBuffers) on 64-bit platforms,
+        // where the channel buffer union follows the silence flags
+        assert_eq!(std::mem::size_of::<AudioBusBuffers>(), 24);
+        assert_eq!(std::mem::offset_of!(AudioBusBuffers, channel_buffers), 16);
+    }
 }
@@ -2,8 +2,15 @@
 //!
 //! This module provides a COM object that implements the `IComponentHandler` interface,
 //! which is the callback interface used by edit controllers to communicate with the host.
+//!
+//! When the plugin's own editor moves a parameter, the controller calls
+//! `performEdit`. The handler queues the edit on a lock-free ring that the
+//! plugin wrapper drains on the audio thread, so the processor gets the new
+//! value and the host hears about it.
 
+use rtrb::{Consumer, Producer, RingBuffer};
 use std::ffi::c_void;
+use std::sync::Mutex;
 use std::sync::atomic::{AtomicU32, Ordering};
 
 /// `IComponentHandler` interface IID
@@ -18,10 +25,27 @@ const FUNKNOWN_IID: [u8; 16] = [
     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46,
 ];
 
+/// Edits that can wait between two audio callbacks before new ones are dropped
+const EDIT_QUEUE_CAPACITY: usize = 1024;
+
+/// A parameter change made in the plugin's own editor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParameterEdit {
+    /// Parameter ID
+    pub id: u32,
+    /// New normalized value (0.0-1.0)
+    pub value: f64,
+}
+
+/// Create the queue carrying editor edits from a [`ComponentHandler`] to the plugin wrapper
+pub fn edit_queue() -> (Producer<ParameterEdit>, Consumer<ParameterEdit>) {
+    RingBuffer::new(EDIT_QUEUE_CAPACITY)
+}
+
 /// Basic `IComponentHandler` implementation
 ///
-/// This is a minimal implementation that allows plugins to function.
-/// Currently it just acknowledges callbacks without actually processing them.
+/// Forwards `performEdit` calls to the edit queue. Edit gestures
+/// (`beginEdit`/`endEdit`) and restart requests are only acknowledged.
 #[repr(C)]
 pub struct ComponentHandler {
     /// COM vtable pointer (must be first field)
@@ -29,6 +53,12 @@ pub struct ComponentHandler {
 
     /// Reference count for COM lifetime management
     ref_count: AtomicU32,
+
+    /// Sending end of the edit queue
+    ///
+    /// Only the editor (UI) thread pushes, so the lock is never contended;
+    /// the audio thread reads the other end without locking.
+    edits: Mutex<Producer<ParameterEdit>>,
 }
 
 /// `IComponentHandler` vtable structure
@@ -65,14 +95,15 @@ static VTABLE: IComponentHandlerVTable = IComponentHandlerVTable {
 };
 
 impl ComponentHandler {
-    /// Create a new component handler
+    /// Create a new component handler that queues editor edits on `edits`
     ///
     /// Caller must use `Box::leak()` to transfer ownership to COM reference counting
     #[allow(unsafe_code)]
-    pub fn new() -> Self {
+    pub fn new(edits: Producer<ParameterEdit>) -> Self {
         Self {
             vtable: &raw const VTABLE,
             ref_count: AtomicU32::new(1),
+            edits: Mutex::new(edits),
         }
     }
 }
@@ -187,12 +218,27 @@ unsafe extern "C" fn perform_edit(this: *mut c_void, id: u32, value_normalized:
         return K_RESULT_OK;
     }
 
-    tracing::info!(
+    tracing::debug!(
         "handler::performEdit - param_id: {}, value: {:.4}",
         id,
         value_normalized
     );
-    // TODO: Send parameter changes to audio processor
+
+    let handler = unsafe { &*(this.cast::<ComponentHandler>()) };
+    let edit = ParameterEdit {
+        id,
+        value: value_normalized,
+    };
+    // A poisoned lock only means another edit panicked mid-push; the ring is intact
+    let queued = handler
+        .edits
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .push(edit)
+        .is_ok();
+    if !queued {
+        tracing::warn!("handler::performEdit - edit queue full, dropping param_id: {id}");
+    }
     K_RESULT_OK
 }
 
@@ -218,3 +264,30 @@ unsafe extern "C" fn restart_component(this: *mut c_void, flags: i32) -> i32 {
     // TODO: Handle restart requests (latency changes, IO changes, etc.)
     K_RESULT_OK
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(unsafe_code)]
+    fn test_perform_edit_is_queued() {
+        let (producer, mut consumer) = edit_queue();
+        let handler = std::ptr::from_mut(Box::leak(Box::new(ComponentHandler::new(producer))))
+            .cast::<c_void>();
+
+        unsafe {
+            assert_eq!(begin_edit(handler, 7), K_RESULT_OK);
+            assert_eq!(perform_edit(handler, 7, 0.25), K_RESULT_OK);
+            assert_eq!(perform_edit(handler, 7, 0.75), K_RESULT_OK);
+            assert_eq!(end_edit(handler, 7), K_RESULT_OK);
+        }
+
+        assert_eq!(consumer.pop(), Ok(ParameterEdit { id: 7, value: 0.25 }));
+        assert_eq!(consumer.pop(), Ok(ParameterEdit { id: 7, value: 0.75 }));
+        assert!(consumer.pop().is_err());
+
+        // Last reference frees the handler
+        assert_eq!(unsafe { release(handler) }, 0);
+    }
+}
@@ -0,0 +1,489 @@
+//! VST3 event list implementation
+//!
+//! Implements the `IEventList` interface for passing note events from the
+//! graph's `EventBuffer` to the audio processor (`ProcessData::inputEvents`),
+//! and for collecting the notes a plugin emits (`ProcessData::outputEvents`).
+//!
+//! Events are stored in a buffer pre-allocated at construction time, so
+//! filling the list from `process()` never allocates. Events beyond the
+//! capacity are dropped.
+
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+const K_RESULT_OK: i32 = 0;
+const K_RESULT_FALSE: i32 = 1;
+
+/// Number of events a list can hold per process call
+pub const EVENT_LIST_CAPACITY: usize = 512;
+
+/// VST3 `Event::type` for `NoteOnEvent`
+const K_NOTE_ON_EVENT: u16 = 0;
+
+/// VST3 `Event::type` for `NoteOffEvent`
+const K_NOTE_OFF_EVENT: u16 = 1;
+
+/// VST3 `Event::flags` bit marking a live (played, not sequenced) event
+const K_IS_LIVE: u16 = 1 << 0;
+
+/// VST3 `NoteOnEvent` structure
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct NoteOnEvent {
+    channel: i16,
+    pitch: i16,
+    tuning: f32,
+    velocity: f32,
+    length: i32,
+    note_id: i32,
+}
+
+/// VST3 `NoteOffEvent` structure
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct NoteOffEvent {
+    channel: i16,
+    pitch: i16,
+    velocity: f32,
+    note_id: i32,
+    tuning: f32,
+}
+
+/// Payload union of the VST3 `Event` structure
+///
+/// Only the note variants are used; `reserved` keeps the union as large as
+/// the SDK's (its biggest member is `NoteExpressionTextEvent`, 24 bytes).
+#[repr(C)]
+#[derive(Clone, Copy)]
+union EventPayload {
+    note_on: NoteOnEvent,
+    note_off: NoteOffEvent,
+    reserved: [u64; 3],
+}
+
+/// VST3 `Event` structure
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Vst3Event {
+    bus_index: i32,
+    sample_offset: i32,
+    ppq_position: f64,
+    flags: u16,
+    event_type: u16,
+    payload: EventPayload,
+}
+
+impl Vst3Event {
+    /// Translate one of our events into a VST3 event
+    ///
+    /// Returns `None` for events that aren't note events (parameter changes
+    /// travel through `IParameterChanges` instead).
+    fn from_event(event: &vvdaw_plugin::Event) -> Option<Self> {
+        // Our velocity is already normalized (0.0-1.0) like VST3's, so it only
+        // needs clamping. Channels (0-15) and pitches (0-127) map directly.
+        let (sample_offset, event_type, payload) = match *event {
+            vvdaw_plugin::Event::NoteOn {
+                channel,
+                note,
+                velocity,
+                sample_offset,
+            } => (
+                sample_offset,
+                K_NOTE_ON_EVENT,
+                EventPayload {
+                    note_on: NoteOnEvent {
+                        channel: i16::from(channel.min(15)),
+                        pitch: i16::from(note.min(127)),
+                        tuning: 0.0,
+                        velocity: velocity.clamp(0.0, 1.0),
+                        length: 0,
+                        note_id: -1,
+                    },
+                },
+            ),
+            vvdaw_plugin::Event::NoteOff {
+                channel,
+                note,
+                sample_offset,
+            } => (
+                sample_offset,
+                K_NOTE_OFF_EVENT,
+                EventPayload {
+                    note_off: NoteOffEvent {
+                        channel: i16::from(channel.min(15)),
+                        pitch: i16::from(note.min(127)),
+                        velocity: 0.0,
+                        note_id: -1,
+                        tuning: 0.0,
+                    },
+                },
+            ),
+            vvdaw_plugin::Event::ParamChange { .. } => return None,
+        };
+
+        Some(Self {
+            bus_index: 0,
+            sample_offset: sample_offset.min(i32::MAX as u32) as i32,
+            ppq_position: 0.0,
+            flags: K_IS_LIVE,
+            event_type,
+            payload,
+        })
+    }
+
+    /// Translate a VST3 event back into one of ours
+    ///
+    /// Returns `None` for event types other than note on/off. The bus index
+    /// is dropped - downstream nodes only have one event input.
+    #[allow(unsafe_code)] // Union reads, selected by event_type
+    fn to_event(self) -> Option<vvdaw_plugin::Event> {
+        let sample_offset = u32::try_from(self.sample_offset).unwrap_or(0);
+        // Out-of-range channels and pitches from the plugin are clamped
+        let channel = |channel: i16| channel.clamp(0, 15) as u8;
+        let note = |pitch: i16| pitch.clamp(0, 127) as u8;
+
+        match self.event_type {
+            K_NOTE_ON_EVENT => {
+                // SAFETY: event_type says the payload is a NoteOnEvent
+                let note_on = unsafe { self.payload.note_on };
+                Some(vvdaw_plugin::Event::NoteOn {
+                    channel: channel(note_on.channel),
+                    note: note(note_on.pitch),
+                    velocity: note_on.velocity.clamp(0.0, 1.0),
+                    sample_offset,
+                })
+            }
+            K_NOTE_OFF_EVENT => {
+                // SAFETY: event_type says the payload is a NoteOffEvent
+                let note_off = unsafe { self.payload.note_off };
+                Some(vvdaw_plugin::Event::NoteOff {
+                    channel: channel(note_off.channel),
+                    note: note(note_off.pitch),
+                    sample_offset,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Implementation of `IEventList`
+#[repr(C)]
+pub struct EventList {
+    /// COM vtable pointer (must be first field)
+    vtable: *const IEventListVTable,
+
+    /// Reference count for COM lifetime management
+    ref_count: AtomicU32,
+
+    /// Events for the current block (capacity fixed at construction)
+    events: Vec<Vst3Event>,
+}
+
+/// COM vtable for `IEventList`
+#[repr(C)]
+struct IEventListVTable {
+    // FUnknown methods
+    query_interface:
+        unsafe extern "C" fn(this: *mut c_void, iid: *const [u8; 16], obj: *mut *mut c_void) -> i32,
+    add_ref: unsafe extern "C" fn(this: *mut c_void) -> u32,
+    release: unsafe extern "C" fn(this: *mut c_void) -> u32,
+
+    // IEventList methods
+    get_event_count: unsafe extern "C" fn(this: *mut c_void) -> i32,
+    get_event: unsafe extern "C" fn(this: *mut c_void, index: i32, event: *mut Vst3Event) -> i32,
+    add_event: unsafe extern "C" fn(this: *mut c_void, event: *mut Vst3Event) -> i32,
+}
+
+static EVENT_LIST_VTABLE: IEventListVTable = IEventListVTable {
+    query_interface,
+    add_ref,
+    release,
+    get_event_count,
+    get_event,
+    add_event,
+};
+
+impl EventList {
+    /// Create a new empty event list
+    pub fn new() -> Self {
+        Self {
+            vtable: &raw const EVENT_LIST_VTABLE,
+            ref_count: AtomicU32::new(1),
+            events: Vec::with_capacity(EVENT_LIST_CAPACITY),
+        }
+    }
+
+    /// Replace the list's contents with the note events from `events`
+    ///
+    /// REAL-TIME SAFE: never allocates. Events past `EVENT_LIST_CAPACITY`
+    /// are dropped.
+    pub fn fill_from(&mut self, events: &vvdaw_plugin::EventBuffer) {
+        self.events.clear();
+        for event in events.events.iter().filter_map(Vst3Event::from_event) {
+            if self.events.len() == EVENT_LIST_CAPACITY {
+                break;
+            }
+            self.events.push(event);
+        }
+    }
+
+    /// Whether the list holds no events
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Remove all events, keeping the capacity
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    /// Append the list's note events to `events`
+    ///
+    /// REAL-TIME SAFE: never allocates. Events that don't fit in the
+    /// buffer's capacity are dropped.
+    pub fn append_to(&self, events: &mut vvdaw_plugin::EventBuffer) {
+        for event in self.events.iter().copied().filter_map(Vst3Event::to_event) {
+            if !events.try_push(event) {
+                break;
+            }
+        }
+    }
+}
+
+impl Default for EventList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// SAFETY: EventList is only used from the audio thread in Vst3Plugin.
+// The vtable is a static reference and safe to share.
+// The ref_count is AtomicU32 which is already Send + Sync.
+#[allow(unsafe_code)]
+unsafe impl Send for EventList {}
+
+// FUnknown implementation
+
+// FUnknown IID: {0x00000000, 0x00000000, 0xC0000000, 0x00000046}
+const FUNKNOWN_IID: [u8; 16] = [
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46,
+];
+
+/// `IEventList` IID
+/// FUID: 3A2C4214-34634FE6-B7BB6F3D-A4CC8CEF
+const IEVENT_LIST_IID: [u8; 16] = [
+    0x3A, 0x2C, 0x42, 0x14, 0x34, 0x63, 0x4F, 0xE6, 0xB7, 0xBB, 0x6F, 0x3D, 0xA4, 0xCC, 0x8C, 0xEF,
+];
+
+#[allow(unsafe_code)]
+unsafe extern "C" fn query_interface(
+    this: *mut c_void,
+    iid: *const [u8; 16],
+    obj: *mut *mut c_void,
+) -> i32 {
+    if iid.is_null() || obj.is_null() {
+        return K_RESULT_FALSE;
+    }
+
+    unsafe {
+        let requested_iid = &*iid;
+
+        if requested_iid == &FUNKNOWN_IID || requested_iid == &IEVENT_LIST_IID {
+            *obj = this;
+            // Add reference for the returned interface
+            let list = &*(this.cast::<EventList>());
+            list.ref_count.fetch_add(1, Ordering::Relaxed);
+            return K_RESULT_OK;
+        }
+
+        // Don't support other interfaces
+        *obj = std::ptr::null_mut();
+        K_RESULT_FALSE
+    }
+}
+
+#[allow(unsafe_code)]
+unsafe extern "C" fn add_ref(this: *mut c_void) -> u32 {
+    unsafe {
+        let list = &*(this.cast::<EventList>());
+        let old_count = list.ref_count.fetch_add(1, Ordering::Relaxed);
+        old_count + 1
+    }
+}
+
+#[allow(unsafe_code)]
+unsafe extern "C" fn release(this: *mut c_void) -> u32 {
+    unsafe {
+        // The list is owned by Vst3Plugin, never boxed - plugins only drop
+        // references they added, so the count never reaches zero here.
+        let list = &*(this.cast::<EventList>());
+        let old_count = list.ref_count.fetch_sub(1, Ordering::Release);
+        old_count.saturating_sub(1)
+    }
+}
+
+// IEventList implementation
+
+#[allow(unsafe_code)]
+unsafe extern "C" fn get_event_count(this: *mut c_void) -> i32 {
+    unsafe {
+        let list = &*(this.cast::<EventList>());
+        // Clamp to i32::MAX to avoid overflow
+        list.events.len().min(i32::MAX as usize) as i32
+    }
+}
+
+#[allow(unsafe_code)]
+unsafe extern "C" fn get_event(this: *mut c_void, index: i32, event: *mut Vst3Event) -> i32 {
+    if event.is_null() {
+        return K_RESULT_FALSE;
+    }
+
+    unsafe {
+        let list = &*(this.cast::<EventList>());
+
+        if index < 0 || index >= list.events.len() as i32 {
+            return K_RESULT_FALSE;
+        }
+
+        *event = list.events[index as usize];
+        K_RESULT_OK
+    }
+}
+
+#[allow(unsafe_code)]
+unsafe extern "C" fn add_event(this: *mut c_void, event: *mut Vst3Event) -> i32 {
+    if event.is_null() {
+        return K_RESULT_FALSE;
+    }
+
+    unsafe {
+        let list = &mut *(this.cast::<EventList>());
+
+        // Refuse rather than allocate on the audio thread
+        if list.events.len() == EVENT_LIST_CAPACITY {
+            return K_RESULT_FALSE;
+        }
+
+        list.events.push(*event);
+        K_RESULT_OK
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vvdaw_plugin::{Event, EventBuffer};
+
+    #[test]
+    fn test_event_layout_matches_sdk() {
+        // sizeof(Steinberg::Vst::Event) on 64-bit platforms
+        assert_eq!(std::mem::size_of::<Vst3Event>(), 48);
+        assert_eq!(std::mem::size_of::<NoteOnEvent>(), 20);
+        assert_eq!(std::mem::size_of::<NoteOffEvent>(), 16);
+    }
+
+    #[test]
+    #[allow(unsafe_code)]
+    fn test_fill_translates_note_events() {
+        let mut buffer = EventBuffer::new();
+        buffer.events.push(Event::NoteOn {
+            channel: 2,
+            note: 60,
+            velocity: 1.5,
+            sample_offset: 10,
+        });
+        buffer.events.push(Event::ParamChange {
+            id: 0,
+            value: 0.5,
+            sample_offset: 0,
+        });
+        buffer.events.push(Event::NoteOff {
+            channel: 2,
+            note: 60,
+            sample_offset: 100,
+        });
+
+        let mut list = EventList::new();
+        list.fill_from(&buffer);
+        assert_eq!(
+            list.events.len(),
+            2,
+            "Parameter changes are not note events"
+        );
+
+        let this = (&raw mut list).cast::<c_void>();
+        unsafe {
+            assert_eq!(get_event_count(this), 2);
+
+            let mut event: Vst3Event = std::mem::zeroed();
+            assert_eq!(get_event(this, 0, &raw mut event), K_RESULT_OK);
+            assert_eq!(event.event_type, K_NOTE_ON_EVENT);
+            assert_eq!(event.sample_offset, 10);
+            assert_eq!(event.payload.note_on.channel, 2);
+            assert_eq!(event.payload.note_on.pitch, 60);
+            assert_eq!(event.payload.note_on.velocity, 1.0, "Velocity is clamped");
+
+            assert_eq!(get_event(this, 1, &raw mut event), K_RESULT_OK);
+            assert_eq!(event.event_type, K_NOTE_OFF_EVENT);
+            assert_eq!(event.sample_offset, 100);
+            assert_eq!(event.payload.note_off.pitch, 60);
+
+            assert_eq!(get_event(this, 2, &raw mut event), K_RESULT_FALSE);
+        }
+    }
+
+    #[test]
+    #[allow(unsafe_code)]
+    fn test_output_events_translate_back() {
+        let mut buffer = EventBuffer::new();
+        buffer.events.push(Event::NoteOn {
+            channel: 1,
+            note: 64,
+            velocity: 0.5,
+            sample_offset: 12,
+        });
+        buffer.events.push(Event::NoteOff {
+            channel: 1,
+            note: 64,
+            sample_offset: 30,
+        });
+        let mut source = EventList::new();
+        source.fill_from(&buffer);
+
+        // The plugin adds events through the COM interface
+        let mut output = EventList::new();
+        let this = (&raw mut output).cast::<c_void>();
+        for event in &mut source.events {
+            assert_eq!(unsafe { add_event(this, event) }, K_RESULT_OK);
+        }
+        let mut unknown = source.events[0];
+        unknown.event_type = 4; // kDataEvent
+        assert_eq!(unsafe { add_event(this, &raw mut unknown) }, K_RESULT_OK);
+
+        let mut emitted = EventBuffer::with_capacity(8);
+        output.append_to(&mut emitted);
+        assert_eq!(emitted.events, buffer.events);
+
+        output.clear();
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_fill_caps_at_capacity() {
+        let mut buffer = EventBuffer::new();
+        for i in 0..(EVENT_LIST_CAPACITY + 10) {
+            buffer.events.push(Event::NoteOn {
+                channel: 0,
+                note: (i % 128) as u8,
+                velocity: 0.8,
+                sample_offset: 0,
+            });
+        }
+
+        let mut list = EventList::new();
+        list.fill_from(&buffer);
+        assert_eq!(list.events.len(), EVENT_LIST_CAPACITY);
+    }
+}
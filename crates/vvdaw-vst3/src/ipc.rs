@@ -58,7 +58,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicU32, Ordering};
-use vvdaw_core::{Frames, SampleRate};
+use vvdaw_core::{ChannelCount, Frames, SampleRate};
 use vvdaw_plugin::PluginInfo;
 
 /// Maximum number of audio channels supported (stereo for MVP)
@@ -93,6 +93,21 @@ pub enum ControlMessage {
 
     /// Get all available parameters
     GetParameters,
+
+    /// Load a `.vstpreset` file (after Init)
+    LoadPreset { path: String },
+
+    /// Get the display text for a parameter value
+    GetParameterDisplay { id: u32, value: f32 },
+
+    /// Parse display text into a parameter value (answered with `ParameterValue`)
+    ParseParameterValue { id: u32, text: String },
+
+    /// Capture the component state (after Init)
+    SaveState,
+
+    /// Restore a component state captured with `SaveState` (after Init)
+    LoadState { data: Vec<u8> },
 }
 
 /// Response messages sent from plugin subprocess to main process
@@ -101,8 +116,17 @@ pub enum ResponseMessage {
     /// Plugin loaded successfully
     Ready { info: PluginInfo },
 
-    /// Plugin initialized successfully
-    Initialized,
+    /// Plugin initialized successfully, reporting its processing latency and
+    /// tail, and its channel counts (0 inputs for an instrument)
+    Initialized {
+        latency: Frames,
+        #[serde(default)]
+        tail: Frames,
+        #[serde(default = "stereo")]
+        input_channels: ChannelCount,
+        #[serde(default = "stereo")]
+        output_channels: ChannelCount,
+    },
 
     /// Plugin activated successfully
     Activated,
@@ -118,10 +142,27 @@ pub enum ResponseMessage {
         parameters: Vec<SerializableParameterInfo>,
     },
 
+    /// Preset loaded successfully
+    PresetLoaded,
+
+    /// Display text response
+    ParameterDisplay { id: u32, text: String },
+
+    /// Component state captured by `SaveState`
+    State { data: Vec<u8> },
+
+    /// State restored successfully
+    StateLoaded,
+
     /// Error occurred
     Error { message: String },
 }
 
+/// Channel count assumed from hosts that don't report one
+const fn stereo() -> ChannelCount {
+    2
+}
+
 /// Serializable version of `ParameterInfo` for IPC
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SerializableParameterInfo {
@@ -130,6 +171,8 @@ pub struct SerializableParameterInfo {
     pub min_value: f32,
     pub max_value: f32,
     pub default_value: f32,
+    #[serde(default)]
+    pub step_count: u32,
 }
 
 impl From<vvdaw_plugin::ParameterInfo> for SerializableParameterInfo {
@@ -140,6 +183,7 @@ impl From<vvdaw_plugin::ParameterInfo> for SerializableParameterInfo {
             min_value: info.min_value,
             max_value: info.max_value,
             default_value: info.default_value,
+            step_count: info.step_count,
         }
     }
 }
@@ -152,6 +196,7 @@ impl From<SerializableParameterInfo> for vvdaw_plugin::ParameterInfo {
             min_value: info.min_value,
             max_value: info.max_value,
             default_value: info.default_value,
+            step_count: info.step_count,
         }
     }
 }
@@ -455,6 +500,35 @@ mod tests {
         assert_eq!(buffer.get_state(), ProcessState::Done);
     }
 
+    #[test]
+    fn test_initialized_channel_counts() {
+        let instrument = ResponseMessage::Initialized {
+            latency: 0,
+            tail: 0,
+            input_channels: 0,
+            output_channels: 2,
+        };
+        let json = serde_json::to_string(&instrument).unwrap();
+        let Ok(ResponseMessage::Initialized { input_channels, .. }) = serde_json::from_str(&json)
+        else {
+            panic!("Initialized did not round-trip: {json}");
+        };
+        assert_eq!(input_channels, 0);
+
+        // Hosts that predate channel reporting are assumed stereo
+        let json = r#"{"Initialized":{"latency":64}}"#;
+        let Ok(ResponseMessage::Initialized {
+            latency,
+            input_channels,
+            output_channels,
+            ..
+        }) = serde_json::from_str(json)
+        else {
+            panic!("Failed to parse {json}");
+        };
+        assert_eq!((latency, input_channels, output_channels), (64, 2, 2));
+    }
+
     #[test]
     fn test_event_conversion() {
         let plugin_event = vvdaw_plugin::Event::NoteOn {
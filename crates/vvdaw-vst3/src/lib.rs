@@ -25,18 +25,30 @@ use vvdaw_plugin::{PluginError, PluginInfo};
 
 mod com;
 mod component_handler;
+mod event_list;
 mod host_application;
 mod ipc;
 mod loader;
 mod multiproc;
 mod parameter_changes;
+mod preset;
+mod scan_cache;
 mod shm;
 mod stream;
+#[cfg(test)]
+mod test_fixtures;
 mod wrapper;
 
-pub use ipc::{ControlMessage, Event, ProcessState, ResponseMessage, SharedAudioBuffer};
-pub use loader::Vst3Loader;
+pub use ipc::{
+    ControlMessage, Event, ProcessState, ResponseMessage, SerializableParameterInfo,
+    SharedAudioBuffer,
+};
+pub use loader::{
+    ClassSelector, DEFAULT_SCAN_TIMEOUT, ScanError, ScanReport, ScannedPlugin, Vst3Loader,
+};
 pub use multiproc::MultiProcessPlugin;
+pub use preset::Vst3Preset;
+pub use scan_cache::{CachedClass, ScanCache};
 pub use shm::SharedMemory;
 pub use wrapper::Vst3Plugin;
 
@@ -3,25 +3,198 @@
 //! This module handles loading VST3 plugins from `.vst3` bundle files,
 //! querying the plugin factory, and creating plugin instances.
 
-use crate::com::{GetPluginFactoryFn, PluginFactory};
+use crate::com::{ClassInfo, GetPluginFactoryFn, PluginFactory};
+use crate::component_handler::{ComponentHandler, ParameterEdit};
+use crate::ipc::SerializableParameterInfo;
+use crate::scan_cache::{CachedClass, ScanCache};
 use crate::wrapper::Vst3Plugin;
 use libloading::{Library, Symbol};
+use rtrb::Producer;
+use std::collections::HashMap;
 use std::ffi::c_void;
+use std::io::Read;
 use std::path::{Path, PathBuf};
-use std::process::Command;
-use vvdaw_plugin::{PluginError, PluginInfo};
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+use vvdaw_plugin::{ParameterInfo, PluginError, PluginInfo};
 
 /// Result type returned by plugin-scanner subprocess
 #[derive(serde::Deserialize)]
 #[serde(tag = "status")]
 enum ScanResult {
     #[serde(rename = "success")]
-    Success { plugin: PluginInfo },
+    Success { classes: Vec<ScannedClass> },
 
     #[serde(rename = "error")]
     Error { message: String },
 }
 
+/// One audio processor class reported by the plugin-scanner subprocess
+#[derive(serde::Deserialize)]
+struct ScannedClass {
+    index: i32,
+    plugin: PluginInfo,
+    /// Only present when the scanner was run with `--parameters`
+    #[serde(default)]
+    parameters: Vec<SerializableParameterInfo>,
+}
+
+/// Category of the factory classes that are audio processors (`kVstAudioEffectClass`)
+///
+/// Bundles also export edit controllers and other helper classes, which can't
+/// be loaded as plugins on their own.
+const AUDIO_MODULE_CATEGORY: &str = "Audio Module Class";
+
+/// Default time a plugin-scanner subprocess gets per bundle
+pub const DEFAULT_SCAN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Current scanner timeout in milliseconds (see [`Vst3Loader::set_scan_timeout`])
+static SCAN_TIMEOUT_MS: AtomicU64 = AtomicU64::new(DEFAULT_SCAN_TIMEOUT.as_millis() as u64);
+
+/// Environment variable listing extra plugin directories, separated like `PATH`
+pub const VST3_PATH_ENV: &str = "VST3_PATH";
+
+/// Directories searched before the standard ones (see [`Vst3Loader::set_extra_search_paths`])
+static EXTRA_SEARCH_PATHS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Which audio processor class of a `.vst3` bundle to load
+///
+/// Bundles may export several effects (e.g. one per module of a suite).
+/// See [`Vst3Loader::load_class`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClassSelector {
+    /// Factory class index, as reported by [`ScannedPlugin::class_index`]
+    Index(i32),
+    /// Class name, as reported by `PluginInfo::name`
+    Name(String),
+}
+
+/// A plugin discovered by a scan, with its parameter list frozen at scan time
+///
+/// Lets the UI list (and pre-populate controls for) a plugin's parameters
+/// without instantiating it. Produced by [`Vst3Loader::scan_with_parameters`].
+#[derive(Debug, Clone)]
+pub struct ScannedPlugin {
+    /// Path to the `.vst3` bundle
+    pub path: PathBuf,
+    /// Factory index of the class within the bundle (for [`ClassSelector::Index`])
+    pub class_index: i32,
+    /// Plugin metadata
+    pub info: PluginInfo,
+    /// Parameters reported when the plugin was scanned
+    pub parameters: Vec<ParameterInfo>,
+}
+
+/// A bundle or directory a scan couldn't read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanError {
+    /// The `.vst3` bundle (or directory) that failed
+    pub path: PathBuf,
+    /// Why, e.g. the scanner's error message or a timeout
+    pub message: String,
+}
+
+impl std::fmt::Display for ScanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+/// Outcome of scanning several directories: what was found and what failed
+///
+/// Returned by [`Vst3Loader::scan_system`] and friends, so a plugin manager
+/// can list the plugins that failed to load alongside the ones that didn't.
+#[derive(Debug, Clone, Default)]
+pub struct ScanReport {
+    /// Plugins found, in scan order
+    pub plugins: Vec<PluginInfo>,
+    /// Bundles and directories that couldn't be scanned
+    pub errors: Vec<ScanError>,
+    /// Bundle each plugin was found in, keyed by `PluginInfo::unique_id`
+    pub bundles: HashMap<String, PathBuf>,
+}
+
+impl ScanReport {
+    /// The `.vst3` bundle `plugin` was found in, for loading it
+    pub fn bundle_of(&self, plugin: &PluginInfo) -> Option<&Path> {
+        self.bundles.get(&plugin.unique_id).map(PathBuf::as_path)
+    }
+}
+
+impl ScannedPlugin {
+    /// Reconcile the cached parameter list with the one a live instance reports
+    ///
+    /// Call this once the plugin has been added to the graph. The live list always
+    /// wins - plugins may legitimately change their parameters between versions,
+    /// or expose different ones once fully initialized.
+    ///
+    /// Returns `true` if the cached list was stale and has been replaced.
+    pub fn reconcile_parameters(&mut self, live: &[ParameterInfo]) -> bool {
+        let matches = self.parameters.len() == live.len()
+            && self.parameters.iter().zip(live).all(|(cached, live)| {
+                cached.id == live.id
+                    && cached.name == live.name
+                    && cached.min_value == live.min_value
+                    && cached.max_value == live.max_value
+                    && cached.default_value == live.default_value
+            });
+
+        if !matches {
+            tracing::info!(
+                "Cached parameters for {} are stale ({} cached, {} live) - using live list",
+                self.info.name,
+                self.parameters.len(),
+                live.len()
+            );
+            self.parameters = live.to_vec();
+        }
+
+        !matches
+    }
+}
+
+/// Scans one `.vst3` bundle for [`Vst3Loader::walk_directory`]
+type BundleScanner<'a> = dyn FnMut(&Path) -> Result<Vec<ScannedPlugin>, PluginError> + 'a;
+
+/// Whether `plugin`'s category starts with `category_prefix`
+fn in_category(plugin: &PluginInfo, category_prefix: &str) -> bool {
+    plugin
+        .category
+        .as_deref()
+        .is_some_and(|category| category.starts_with(category_prefix))
+}
+
+/// Search order: `extra`, then the `PATH`-style list `env`, then `defaults`
+fn merge_search_paths(
+    extra: &[PathBuf],
+    env: Option<&std::ffi::OsStr>,
+    defaults: Vec<PathBuf>,
+) -> Vec<PathBuf> {
+    let from_env = env.map(|env| std::env::split_paths(env).collect::<Vec<_>>());
+    dedupe_paths(
+        extra
+            .iter()
+            .cloned()
+            .chain(from_env.into_iter().flatten())
+            .chain(defaults)
+            .filter(|path| !path.as_os_str().is_empty()),
+    )
+}
+
+/// Drop repeated directories, keeping the first occurrence
+///
+/// Paths that resolve to the same directory (e.g. through a symlink or a
+/// trailing `/.`) count as repeats.
+fn dedupe_paths(paths: impl IntoIterator<Item = PathBuf>) -> Vec<PathBuf> {
+    let mut seen = std::collections::HashSet::new();
+    paths
+        .into_iter()
+        .filter(|path| seen.insert(std::fs::canonicalize(path).unwrap_or_else(|_| path.clone())))
+        .collect()
+}
+
 /// VST3 plugin loader
 ///
 /// Handles loading VST3 plugins from filesystem paths.
@@ -38,15 +211,39 @@ impl Vst3Loader {
     /// - A `.vst3` bundle directory
     /// - A dynamic library file directly
     ///
+    /// Loads the first audio processor class; use [`load_class`](Self::load_class)
+    /// to pick another from a bundle that exports several.
+    ///
     /// # Errors
     ///
     /// Returns `PluginError::FormatError` if:
     /// - The file/bundle doesn't exist
     /// - The binary can't be loaded
     /// - The plugin factory can't be queried
-    #[allow(unsafe_code)] // Required for FFI
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Vst3Plugin, PluginError> {
-        let path = path.as_ref();
+        Self::load_selected(path.as_ref(), None)
+    }
+
+    /// Load a specific audio processor class from a VST3 bundle
+    ///
+    /// # Errors
+    ///
+    /// As for [`load`](Self::load), and `PluginError::FormatError` listing the
+    /// available classes if none matches `class_selector`.
+    #[allow(clippy::needless_pass_by_value)] // Owned, so callers can build it inline
+    pub fn load_class<P: AsRef<Path>>(
+        path: P,
+        class_selector: ClassSelector,
+    ) -> Result<Vst3Plugin, PluginError> {
+        Self::load_selected(path.as_ref(), Some(&class_selector))
+    }
+
+    /// Shared implementation of [`load`](Self::load) and [`load_class`](Self::load_class)
+    #[allow(unsafe_code)] // Required for FFI
+    fn load_selected(
+        path: &Path,
+        class_selector: Option<&ClassSelector>,
+    ) -> Result<Vst3Plugin, PluginError> {
         tracing::info!("Loading VST3 plugin from: {}", path.display());
 
         // Security: Validate path to prevent directory traversal attacks
@@ -92,20 +289,10 @@ impl Vst3Loader {
 
         tracing::debug!("Successfully obtained plugin factory");
 
-        // Step 5: Query the factory for plugin information
-        let class_count = factory.count_classes();
-        tracing::debug!("Plugin factory has {} classes", class_count);
-
-        if class_count == 0 {
-            return Err(PluginError::FormatError(
-                "Plugin has no exported classes".to_string(),
-            ));
-        }
-
-        // For now, just use the first class
-        // TODO: Allow selecting specific class or query by category
-        let class_info = factory.get_class_info(0)?;
-        tracing::info!("Loading plugin class: {}", class_info.name);
+        // Step 5: Find the requested audio processor class
+        let classes = Self::audio_classes(&factory)?;
+        let (class_index, class_info) = Self::select_class(&classes, class_selector)?;
+        tracing::info!("Loading plugin class {class_index}: {}", class_info.name);
 
         // Step 6: Create IComponent instance
         tracing::debug!("Creating IComponent instance...");
@@ -133,35 +320,131 @@ impl Vst3Loader {
         // 1. Query IEditController directly (simple plugins)
         // 2. Create separate edit controller via class ID (commercial plugins)
         tracing::debug!("Obtaining edit controller...");
-        let edit_controller_ptr = unsafe { Self::create_edit_controller(&factory, component_ptr) };
+        // Edits made in the plugin's own editor reach the wrapper through this queue
+        let (edit_producer, edit_consumer) = crate::component_handler::edit_queue();
+        let edit_controller_ptr =
+            unsafe { Self::create_edit_controller(&factory, component_ptr, edit_producer) };
 
         // Step 9: Create the plugin wrapper with COM pointers
-        let info = PluginInfo {
-            name: class_info.name.clone(),
-            vendor: "Unknown".to_string(), // TODO: Get from factory info
-            version: "1.0.0".to_string(),  // TODO: Get from class info
-            unique_id: format!("{:?}", class_info.class_id),
-        };
+        let info = Self::plugin_info_for_class(&factory, *class_index, class_info);
 
         Ok(Vst3Plugin::new_with_library(
             info,
+            class_info.class_id,
             library,
             factory,
             component_ptr,
             processor_ptr,
             edit_controller_ptr,
+            edit_consumer,
         ))
     }
 
+    /// List the factory's audio processor classes with their factory indices
+    ///
+    /// # Errors
+    ///
+    /// Returns `PluginError::FormatError` if the factory has no audio processor class.
+    fn audio_classes(factory: &PluginFactory) -> Result<Vec<(i32, ClassInfo)>, PluginError> {
+        let class_count = factory.count_classes();
+        tracing::debug!("Plugin factory has {} classes", class_count);
+
+        let classes: Vec<_> = (0..class_count)
+            .filter_map(|index| match factory.get_class_info(index) {
+                Ok(info) => Some((index, info)),
+                Err(e) => {
+                    tracing::debug!("Skipping class {index}: {e}");
+                    None
+                }
+            })
+            .filter(|(_, info)| info.category == AUDIO_MODULE_CATEGORY)
+            .collect();
+
+        if classes.is_empty() {
+            return Err(PluginError::FormatError(
+                "Plugin has no audio processor classes".to_string(),
+            ));
+        }
+        Ok(classes)
+    }
+
+    /// Pick the class matching `selector` (the first class for `None`)
+    ///
+    /// # Errors
+    ///
+    /// Returns `PluginError::FormatError` listing the available classes if
+    /// nothing matches.
+    fn select_class<'a>(
+        classes: &'a [(i32, ClassInfo)],
+        selector: Option<&ClassSelector>,
+    ) -> Result<&'a (i32, ClassInfo), PluginError> {
+        let found = match selector {
+            None => classes.first(),
+            Some(ClassSelector::Index(index)) => classes.iter().find(|(i, _)| i == index),
+            Some(ClassSelector::Name(name)) => classes.iter().find(|(_, info)| info.name == *name),
+        };
+
+        found.ok_or_else(|| {
+            let available = classes
+                .iter()
+                .map(|(index, info)| format!("{index}: '{}'", info.name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            PluginError::FormatError(format!(
+                "No audio processor class matches {selector:?} (available: {available})"
+            ))
+        })
+    }
+
+    /// Give an edit controller a component handler that queues its edits on `edits`
+    ///
+    /// Returns `false` (logged as an error) if the controller rejects the handler.
+    #[allow(unsafe_code)]
+    unsafe fn set_component_handler(
+        controller_ptr: *mut c_void,
+        edits: Producer<ParameterEdit>,
+    ) -> bool {
+        tracing::debug!("Creating component handler...");
+        let handler = ComponentHandler::new(edits);
+        // SAFETY: Box::leak is intentional for COM objects.
+        // The plugin takes ownership via COM reference counting (addRef/release).
+        // When the plugin calls release() and ref_count reaches 0, the object
+        // will deallocate itself using Box::from_raw in the release() function.
+        let handler_ptr = std::ptr::from_mut(Box::leak(Box::new(handler))).cast::<c_void>();
+        tracing::debug!(
+            "Created IComponentHandler at {:?} (initial ref_count=1)",
+            handler_ptr
+        );
+
+        // Set the component handler on the edit controller
+        match unsafe {
+            crate::com::edit_controller_set_component_handler(controller_ptr, handler_ptr)
+        } {
+            Ok(()) => {
+                tracing::info!("✓ Component handler set successfully");
+                true
+            }
+            Err(e) => {
+                tracing::error!("Failed to set component handler: {}", e);
+                unsafe { crate::com::release_interface(handler_ptr) };
+                false
+            }
+        }
+    }
+
     /// Create or query for an edit controller
     ///
     /// Tries two approaches:
     /// 1. Query `IEditController` directly on component (simple plugins)
     /// 2. Create separate edit controller via class ID (commercial plugins)
+    ///
+    /// Either way the controller gets a component handler that queues edits
+    /// made in the plugin's editor on `edits`.
     #[allow(unsafe_code)]
     unsafe fn create_edit_controller(
         factory: &PluginFactory,
         component_ptr: *mut c_void,
+        edits: Producer<ParameterEdit>,
     ) -> Option<*mut c_void> {
         unsafe {
             // Approach 1: Try querying IEditController directly on component
@@ -183,6 +466,9 @@ impl Vst3Loader {
                     }
                 }
 
+                // Hear about edits made in the plugin's editor
+                Self::set_component_handler(ptr, edits);
+
                 return Some(ptr);
             }
 
@@ -238,41 +524,9 @@ impl Vst3Loader {
                                         // Create and set component handler (MANDATORY)
                                         // NOTE: State transfer will happen later in Plugin::initialize()
                                         // after the component is fully initialized
-                                        tracing::debug!("Creating component handler...");
-                                        let handler =
-                                            crate::component_handler::ComponentHandler::new();
-                                        // SAFETY: Box::leak is intentional for COM objects.
-                                        // The plugin takes ownership via COM reference counting (addRef/release).
-                                        // When the plugin calls release() and ref_count reaches 0, the object
-                                        // will deallocate itself using Box::from_raw in the release() function.
-                                        let handler_ptr =
-                                            std::ptr::from_mut(Box::leak(Box::new(handler)))
-                                                .cast::<c_void>();
-                                        tracing::debug!(
-                                            "Created IComponentHandler at {:?} (initial ref_count=1)",
-                                            handler_ptr
-                                        );
-
-                                        // Set the component handler on the edit controller
-                                        match crate::com::edit_controller_set_component_handler(
-                                            controller_ptr,
-                                            handler_ptr,
-                                        ) {
-                                            Ok(()) => {
-                                                tracing::info!(
-                                                    "✓ Component handler set successfully"
-                                                );
-                                            }
-                                            Err(e) => {
-                                                tracing::error!(
-                                                    "Failed to set component handler: {}",
-                                                    e
-                                                );
-                                                // Release handler and return
-                                                crate::com::release_interface(handler_ptr);
-                                                // Return controller anyway, but it may not function properly
-                                                return Some(controller_ptr);
-                                            }
+                                        if !Self::set_component_handler(controller_ptr, edits) {
+                                            // Return controller anyway, but it may not function properly
+                                            return Some(controller_ptr);
                                         }
 
                                         // State transfer will happen in Plugin::initialize()
@@ -312,13 +566,49 @@ impl Vst3Loader {
     /// Scan a directory for VST3 plugins
     ///
     /// Searches for `.vst3` bundle directories and returns plugin information
-    /// without fully loading the plugins.
+    /// without fully loading the plugins. A bundle exporting several audio
+    /// processor classes contributes one entry per class.
     ///
     /// # Errors
     ///
     /// Returns `PluginError::FormatError` if the directory can't be read.
     pub fn scan<P: AsRef<Path>>(path: P) -> Result<Vec<PluginInfo>, PluginError> {
-        let path = path.as_ref();
+        Self::scan_internal(
+            path.as_ref(),
+            &mut |bundle: &Path| Self::scan_plugin_subprocess(bundle, false),
+            &mut Vec::new(),
+        )
+        .map(|plugins| plugins.into_iter().map(|p| p.info).collect())
+    }
+
+    /// Scan a directory for VST3 plugins, capturing each plugin's parameter list
+    ///
+    /// Slower than [`scan`](Self::scan): each plugin is fully instantiated (in the
+    /// scanner subprocess) to enumerate its parameters. Cache the result so the
+    /// UI can show parameters without loading the plugin again.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PluginError::FormatError` if the directory can't be read.
+    pub fn scan_with_parameters<P: AsRef<Path>>(
+        path: P,
+    ) -> Result<Vec<ScannedPlugin>, PluginError> {
+        Self::scan_internal(
+            path.as_ref(),
+            &mut |bundle: &Path| Self::scan_plugin_subprocess(bundle, true),
+            &mut Vec::new(),
+        )
+    }
+
+    /// Shared implementation of [`scan`](Self::scan) and [`scan_with_parameters`](Self::scan_with_parameters)
+    ///
+    /// `scan_bundle` is called for every `.vst3` bundle found. Bundles and
+    /// subdirectories that fail are skipped and added to `errors`.
+    fn scan_internal(
+        path: &Path,
+        scan_bundle: &mut BundleScanner<'_>,
+        errors: &mut Vec<ScanError>,
+    ) -> Result<Vec<ScannedPlugin>, PluginError> {
         tracing::info!("Scanning for VST3 plugins in: {}", path.display());
 
         // Check if directory exists
@@ -337,7 +627,7 @@ impl Vst3Loader {
         let mut plugins = Vec::new();
 
         // Walk the directory tree to find .vst3 bundles
-        match Self::walk_directory(path, &mut plugins) {
+        match Self::walk_directory(path, scan_bundle, &mut plugins, errors) {
             Ok(()) => {
                 tracing::info!("Found {} VST3 plugins in {}", plugins.len(), path.display());
                 Ok(plugins)
@@ -349,23 +639,174 @@ impl Vst3Loader {
         }
     }
 
-    /// Scan all standard VST3 plugin directories on the system
+    /// Scan all VST3 plugin directories on the system
+    ///
+    /// Searches [`search_paths`](Self::search_paths): any extra directories
+    /// followed by the platform's standard ones. Returns information about
+    /// all discovered VST3 plugins, plus every bundle that failed to scan and
+    /// why. Skips directories that don't exist and continues on errors.
+    ///
+    /// Results are cached (see [`ScanCache`]): bundles whose binary hasn't
+    /// changed since the last scan aren't scanned again, and the cache is
+    /// updated with what this scan found.
+    pub fn scan_system() -> ScanReport {
+        let Some(cache_path) = ScanCache::default_path() else {
+            tracing::warn!("No cache directory found - scanning without the scan cache");
+            return Self::scan_system_no_cache();
+        };
+        Self::scan_system_with_cache(&cache_path)
+    }
+
+    /// Like [`scan_system`](Self::scan_system), using the cache file at `cache_path`
+    pub fn scan_system_with_cache(cache_path: &Path) -> ScanReport {
+        Self::scan_paths_with_cache(&Self::search_paths(), cache_path)
+    }
+
+    /// Scan the given directories instead of the system search paths
+    ///
+    /// Like [`scan_system`](Self::scan_system) otherwise: missing directories
+    /// are skipped and results are cached. Paths listed twice are scanned once.
+    pub fn scan_paths(paths: &[PathBuf]) -> ScanReport {
+        let paths = dedupe_paths(paths.iter().cloned());
+        let Some(cache_path) = ScanCache::default_path() else {
+            tracing::warn!("No cache directory found - scanning without the scan cache");
+            return Self::scan_search_paths(&paths, &mut |bundle: &Path| {
+                Self::scan_plugin_subprocess(bundle, false)
+            });
+        };
+        Self::scan_paths_with_cache(&paths, &cache_path)
+    }
+
+    /// Scan `paths`, reusing and then updating the cache file at `cache_path`
+    ///
+    /// Cached bundles outside `paths` are kept, so scanning one extra
+    /// directory doesn't evict everything else.
+    fn scan_paths_with_cache(paths: &[PathBuf], cache_path: &Path) -> ScanReport {
+        let previous = ScanCache::load(cache_path);
+        let mut current = previous.clone();
+        for path in paths {
+            current.forget_under(path);
+        }
+
+        let report = Self::scan_search_paths(paths, &mut |bundle: &Path| {
+            Self::scan_bundle_cached(bundle, &previous, &mut current)
+        });
+
+        tracing::debug!(
+            "Saving {} bundles to plugin scan cache {}",
+            current.len(),
+            cache_path.display()
+        );
+        if let Err(e) = current.save(cache_path) {
+            tracing::warn!("{e}");
+        }
+
+        report
+    }
+
+    /// Scan all VST3 plugin directories, running the scanner for every bundle
+    ///
+    /// Neither reads nor updates the scan cache.
+    pub fn scan_system_no_cache() -> ScanReport {
+        Self::scan_search_paths(&Self::search_paths(), &mut |bundle: &Path| {
+            Self::scan_plugin_subprocess(bundle, false)
+        })
+    }
+
+    /// Delete the scan cache, so the next [`scan_system`](Self::scan_system)
+    /// scans every bundle again
+    ///
+    /// # Errors
+    ///
+    /// Returns `PluginError::FormatError` if the cache file exists but can't be removed.
+    pub fn clear_scan_cache() -> Result<(), PluginError> {
+        let Some(cache_path) = ScanCache::default_path() else {
+            return Ok(());
+        };
+
+        match std::fs::remove_file(&cache_path) {
+            Ok(()) => {
+                tracing::info!("Cleared plugin scan cache {}", cache_path.display());
+                Ok(())
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(PluginError::FormatError(format!(
+                "Failed to remove plugin scan cache {}: {e}",
+                cache_path.display()
+            ))),
+        }
+    }
+
+    /// Scan a bundle, reusing `previous` results if its binary is unchanged
+    ///
+    /// Records the result in `current` either way. Failed scans aren't cached,
+    /// so they are retried next time.
+    fn scan_bundle_cached(
+        bundle: &Path,
+        previous: &ScanCache,
+        current: &mut ScanCache,
+    ) -> Result<Vec<ScannedPlugin>, PluginError> {
+        let Some(modified) = Self::bundle_modified(bundle) else {
+            return Self::scan_plugin_subprocess(bundle, false);
+        };
+
+        if let Some(classes) = previous.get(bundle, modified) {
+            tracing::debug!("Using cached scan of {}", bundle.display());
+            current.insert(bundle, modified, classes.to_vec());
+            return Ok(classes
+                .iter()
+                .map(|class| ScannedPlugin {
+                    path: bundle.to_path_buf(),
+                    class_index: class.class_index,
+                    info: class.info.clone(),
+                    parameters: Vec::new(),
+                })
+                .collect());
+        }
+
+        let scanned = Self::scan_plugin_subprocess(bundle, false)?;
+        let classes = scanned
+            .iter()
+            .map(|plugin| CachedClass {
+                class_index: plugin.class_index,
+                info: plugin.info.clone(),
+            })
+            .collect();
+        current.insert(bundle, modified, classes);
+        Ok(scanned)
+    }
+
+    /// Modification time of a bundle's binary (of the bundle itself if the
+    /// binary can't be located)
     ///
-    /// Returns information about all discovered VST3 plugins.
-    /// Skips directories that don't exist and continues on errors.
-    pub fn scan_system() -> Vec<PluginInfo> {
+    /// Installers often update the binary in place without touching the
+    /// bundle directory, so the binary is the better change indicator.
+    fn bundle_modified(bundle: &Path) -> Option<SystemTime> {
+        let binary = Self::get_library_path(bundle).unwrap_or_else(|_| bundle.to_path_buf());
+        std::fs::metadata(binary)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    /// Scan every directory in `paths` with `scan_bundle`
+    fn scan_search_paths(paths: &[PathBuf], scan_bundle: &mut BundleScanner<'_>) -> ScanReport {
         let mut all_plugins = Vec::new();
+        let mut errors = Vec::new();
+        let mut bundles = HashMap::new();
 
-        for search_path in Self::get_vst3_search_paths() {
+        for search_path in paths {
             tracing::debug!("Scanning VST3 search path: {}", search_path.display());
-            match Self::scan(&search_path) {
-                Ok(mut plugins) => {
+            match Self::scan_internal(search_path, scan_bundle, &mut errors) {
+                Ok(plugins) => {
                     tracing::info!(
                         "Found {} plugins in {}",
                         plugins.len(),
                         search_path.display()
                     );
-                    all_plugins.append(&mut plugins);
+                    for plugin in plugins {
+                        bundles.insert(plugin.info.unique_id.clone(), plugin.path);
+                        all_plugins.push(plugin.info);
+                    }
                 }
                 Err(e) => {
                     tracing::warn!(
@@ -373,16 +814,42 @@ impl Vst3Loader {
                         search_path.display(),
                         e
                     );
+                    errors.push(ScanError {
+                        path: search_path.clone(),
+                        message: e.to_string(),
+                    });
                 }
             }
         }
 
-        tracing::info!("Total VST3 plugins found: {}", all_plugins.len());
+        tracing::info!(
+            "Total VST3 plugins found: {} ({} failed)",
+            all_plugins.len(),
+            errors.len()
+        );
 
         // Check for known conflicting plugins
         Self::check_for_conflicts(&all_plugins);
 
-        all_plugins
+        ScanReport {
+            plugins: all_plugins,
+            errors,
+            bundles,
+        }
+    }
+
+    /// Scan the standard VST3 directories for plugins in a category
+    ///
+    /// Keeps plugins whose `PluginInfo::category` starts with `category_prefix`,
+    /// e.g. `"Instrument"` for synths and samplers or `"Fx"` for effects.
+    /// Plugins that don't report a category are left out. Scan errors are
+    /// all kept, since a failed bundle's category is unknown.
+    pub fn scan_system_filtered(category_prefix: &str) -> ScanReport {
+        let mut report = Self::scan_system();
+        report
+            .plugins
+            .retain(|plugin| in_category(plugin, category_prefix));
+        report
     }
 
     /// Check for known conflicting plugins and warn the user
@@ -413,6 +880,34 @@ impl Vst3Loader {
         }
     }
 
+    /// Directories [`scan_system`](Self::scan_system) searches, in order
+    ///
+    /// The extra directories from
+    /// [`set_extra_search_paths`](Self::set_extra_search_paths) come first,
+    /// then those listed in the `VST3_PATH` environment variable, then the
+    /// platform's standard ones. A directory listed twice appears once.
+    pub fn search_paths() -> Vec<PathBuf> {
+        let extra = EXTRA_SEARCH_PATHS
+            .lock()
+            .map(|paths| paths.clone())
+            .unwrap_or_default();
+        merge_search_paths(
+            &extra,
+            std::env::var_os(VST3_PATH_ENV).as_deref(),
+            Self::get_vst3_search_paths(),
+        )
+    }
+
+    /// Set directories to search before the standard ones (e.g. from `--vst3-path`)
+    ///
+    /// Applies process-wide, to scans started afterwards. Replaces any
+    /// previously set directories.
+    pub fn set_extra_search_paths(paths: Vec<PathBuf>) {
+        if let Ok(mut extra) = EXTRA_SEARCH_PATHS.lock() {
+            *extra = paths;
+        }
+    }
+
     /// Get the standard VST3 search paths for the current platform
     fn get_vst3_search_paths() -> Vec<PathBuf> {
         let mut paths = Vec::new();
@@ -466,7 +961,16 @@ impl Vst3Loader {
     /// - Plugin crashes don't kill the main application
     /// - Each plugin gets its own Objective-C runtime (no class conflicts)
     /// - Multiple plugins can be scanned in parallel (future)
-    fn scan_plugin_subprocess(plugin_path: &Path) -> Result<PluginInfo, PluginError> {
+    ///
+    /// With `with_parameters`, the scanner fully instantiates the plugin to
+    /// capture its parameter list as well.
+    ///
+    /// A scanner still running after [`scan_timeout`](Self::scan_timeout) is
+    /// killed and the bundle reported as an error.
+    fn scan_plugin_subprocess(
+        plugin_path: &Path,
+        with_parameters: bool,
+    ) -> Result<Vec<ScannedPlugin>, PluginError> {
         // Determine the scanner binary path
         let exe_name = if cfg!(windows) {
             "plugin-scanner.exe"
@@ -500,12 +1004,19 @@ impl Vst3Loader {
         tracing::debug!("Scanning plugin: {}", plugin_path.display());
 
         // Spawn the scanner subprocess
-        let output = Command::new(&scanner_path)
+        let mut command = Command::new(&scanner_path);
+        if with_parameters {
+            command.arg("--parameters");
+        }
+        let child = command
             .arg(plugin_path.as_os_str())
-            .output()
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
             .map_err(|e| {
                 PluginError::FormatError(format!("Failed to spawn plugin scanner subprocess: {e}"))
             })?;
+        let output = Self::wait_with_timeout(child, Self::scan_timeout())?;
 
         // Parse the JSON output
         let stdout = String::from_utf8_lossy(&output.stdout);
@@ -553,13 +1064,93 @@ impl Vst3Loader {
         })?;
 
         match result {
-            ScanResult::Success { plugin } => Ok(plugin),
+            ScanResult::Success { classes } => Ok(classes
+                .into_iter()
+                .map(|class| ScannedPlugin {
+                    path: plugin_path.to_path_buf(),
+                    class_index: class.index,
+                    info: class.plugin,
+                    parameters: class.parameters.into_iter().map(Into::into).collect(),
+                })
+                .collect()),
             ScanResult::Error { message } => Err(PluginError::FormatError(message)),
         }
     }
 
+    /// How long each plugin-scanner subprocess may run before it is killed
+    pub fn scan_timeout() -> Duration {
+        Duration::from_millis(SCAN_TIMEOUT_MS.load(Ordering::Relaxed))
+    }
+
+    /// Set how long each plugin-scanner subprocess may run (default [`DEFAULT_SCAN_TIMEOUT`])
+    ///
+    /// A plugin that hangs while being scanned is killed after this long and
+    /// reported as a scan error for its bundle; the rest of the scan continues.
+    /// Applies process-wide, to scans started afterwards.
+    pub fn set_scan_timeout(timeout: Duration) {
+        let millis = u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX);
+        SCAN_TIMEOUT_MS.store(millis, Ordering::Relaxed);
+    }
+
+    /// Collect a child's output like `Child::wait_with_output`, killing it after `timeout`
+    ///
+    /// The pipes are drained on their own threads, so a plugin that logs a lot
+    /// can't stall on a full pipe while we wait.
+    fn wait_with_timeout(mut child: Child, timeout: Duration) -> Result<Output, PluginError> {
+        fn drain(pipe: Option<impl Read + Send + 'static>) -> std::thread::JoinHandle<Vec<u8>> {
+            std::thread::spawn(move || {
+                let mut bytes = Vec::new();
+                if let Some(mut pipe) = pipe {
+                    let _ = pipe.read_to_end(&mut bytes);
+                }
+                bytes
+            })
+        }
+
+        let stdout = drain(child.stdout.take());
+        let stderr = drain(child.stderr.take());
+
+        let start = Instant::now();
+        let mut poll_interval = Duration::from_millis(1);
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if start.elapsed() >= timeout => {
+                    // The reader threads finish once the killed child's pipes close
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(PluginError::FormatError(format!(
+                        "Plugin scanner timed out after {:.1}s and was killed",
+                        timeout.as_secs_f64()
+                    )));
+                }
+                Ok(None) => {
+                    std::thread::sleep(poll_interval);
+                    poll_interval = (poll_interval * 2).min(Duration::from_millis(20));
+                }
+                Err(e) => {
+                    let _ = child.kill();
+                    return Err(PluginError::FormatError(format!(
+                        "Failed to wait for plugin scanner: {e}"
+                    )));
+                }
+            }
+        };
+
+        Ok(Output {
+            status,
+            stdout: stdout.join().unwrap_or_default(),
+            stderr: stderr.join().unwrap_or_default(),
+        })
+    }
+
     /// Recursively walk a directory to find VST3 plugins
-    fn walk_directory(path: &Path, plugins: &mut Vec<PluginInfo>) -> Result<(), PluginError> {
+    fn walk_directory(
+        path: &Path,
+        scan_bundle: &mut BundleScanner<'_>,
+        plugins: &mut Vec<ScannedPlugin>,
+        errors: &mut Vec<ScanError>,
+    ) -> Result<(), PluginError> {
         let entries = std::fs::read_dir(path).map_err(|e| {
             PluginError::FormatError(format!(
                 "Failed to read directory {}: {}",
@@ -584,15 +1175,17 @@ impl Vst3Loader {
                 if let Some(ext) = entry_path.extension()
                     && ext.eq_ignore_ascii_case("vst3")
                 {
-                    // Found a VST3 bundle - scan it using subprocess
-                    match Self::scan_plugin_subprocess(&entry_path) {
-                        Ok(info) => {
-                            tracing::debug!(
-                                "Found plugin: {} at {}",
-                                info.name,
-                                entry_path.display()
-                            );
-                            plugins.push(info);
+                    // Found a VST3 bundle - scan it (normally using the subprocess)
+                    match scan_bundle(&entry_path) {
+                        Ok(scanned) => {
+                            for plugin in &scanned {
+                                tracing::debug!(
+                                    "Found plugin: {} at {}",
+                                    plugin.info.name,
+                                    entry_path.display()
+                                );
+                            }
+                            plugins.extend(scanned);
                         }
                         Err(e) => {
                             tracing::warn!(
@@ -600,6 +1193,10 @@ impl Vst3Loader {
                                 entry_path.display(),
                                 e
                             );
+                            errors.push(ScanError {
+                                path: entry_path,
+                                message: e.to_string(),
+                            });
                         }
                     }
                     // Don't recurse into .vst3 bundles
@@ -607,13 +1204,17 @@ impl Vst3Loader {
                 }
 
                 // Recurse into subdirectories (but not .vst3 bundles)
-                if let Err(e) = Self::walk_directory(&entry_path, plugins) {
+                if let Err(e) = Self::walk_directory(&entry_path, scan_bundle, plugins, errors) {
                     tracing::warn!(
                         "Failed to scan subdirectory {}: {}",
                         entry_path.display(),
                         e
                     );
                     // Continue scanning other directories
+                    errors.push(ScanError {
+                        path: entry_path,
+                        message: e.to_string(),
+                    });
                 }
             }
         }
@@ -627,16 +1228,89 @@ impl Vst3Loader {
     /// It does NOT create `IComponent` or `IAudioProcessor` instances, making it much faster
     /// and avoiding potential conflicts from loading multiple plugins.
     ///
+    /// Returns every audio processor class in the bundle with its factory index.
+    ///
     /// This is public for use by the plugin-scanner subprocess.
-    #[allow(unsafe_code)] // Required for FFI
-    pub fn load_plugin_info_internal<P: AsRef<Path>>(path: P) -> Result<PluginInfo, PluginError> {
+    pub fn load_plugin_info_internal<P: AsRef<Path>>(
+        path: P,
+    ) -> Result<Vec<(i32, PluginInfo)>, PluginError> {
         let path = path.as_ref();
         Self::load_plugin_info(path)
     }
 
+    /// Load a plugin fully and capture its parameter list
+    ///
+    /// Unlike [`load_plugin_info_internal`](Self::load_plugin_info_internal), this
+    /// instantiates and initializes the plugin, since parameters are only available
+    /// from a live edit controller. The plugin is deactivated and unloaded afterwards.
+    ///
+    /// This is public for use by the plugin-scanner subprocess (`--parameters`).
+    pub fn load_plugin_parameters<P: AsRef<Path>>(
+        path: P,
+        class_index: i32,
+    ) -> Result<Vec<ParameterInfo>, PluginError> {
+        use vvdaw_plugin::Plugin;
+
+        let mut plugin = Self::load_class(path, ClassSelector::Index(class_index))?;
+        // Controllers with a separate class only receive component state on initialize
+        plugin.initialize(48000, 512)?;
+        let parameters = plugin.parameters();
+        plugin.deactivate();
+
+        Ok(parameters)
+    }
+
+    /// Build `PluginInfo` for a factory class, using the richest metadata available
+    ///
+    /// Vendor and version come from `PClassInfo2` when the factory implements
+    /// `IPluginFactory2`/`IPluginFactory3`, falling back to the factory-wide
+    /// vendor from `PFactoryInfo`, and finally to placeholders. The category is
+    /// the `PClassInfo2` subcategory string (e.g. `Fx|Delay`), if any.
+    fn plugin_info_for_class(
+        factory: &PluginFactory,
+        index: i32,
+        class_info: &ClassInfo,
+    ) -> PluginInfo {
+        let class_info2 = factory
+            .get_class_info2(index)
+            .inspect_err(|e| tracing::debug!("No PClassInfo2 for class {index}: {e}"))
+            .ok();
+
+        let vendor = class_info2
+            .as_ref()
+            .map(|info| info.vendor.clone())
+            .filter(|vendor| !vendor.is_empty())
+            .or_else(|| {
+                factory
+                    .get_factory_info()
+                    .ok()
+                    .map(|info| info.vendor)
+                    .filter(|vendor| !vendor.is_empty())
+            })
+            .unwrap_or_else(|| "Unknown".to_string());
+
+        let version = class_info2
+            .as_ref()
+            .map(|info| info.version.clone())
+            .filter(|version| !version.is_empty())
+            .unwrap_or_else(|| "1.0.0".to_string());
+
+        let category = class_info2
+            .map(|info| info.sub_categories)
+            .filter(|sub_categories| !sub_categories.is_empty());
+
+        PluginInfo {
+            name: class_info.name.clone(),
+            vendor,
+            version,
+            unique_id: format!("{:?}", class_info.class_id),
+            category,
+        }
+    }
+
     /// Internal implementation of plugin info loading
     #[allow(unsafe_code)] // Required for FFI
-    fn load_plugin_info(path: &Path) -> Result<PluginInfo, PluginError> {
+    fn load_plugin_info(path: &Path) -> Result<Vec<(i32, PluginInfo)>, PluginError> {
         tracing::info!("Loading VST3 plugin from: {}", path.display());
 
         // Security: Validate path
@@ -677,33 +1351,24 @@ impl Vst3Loader {
         })?;
 
         // Step 5: Query the factory for plugin information (no component creation!)
-        let class_count = factory.count_classes();
-        if class_count == 0 {
-            return Err(PluginError::FormatError(
-                "Plugin has no exported classes".to_string(),
-            ));
-        }
-
-        // Get info from first class (most plugins only have one)
-        let class_info = factory.get_class_info(0)?;
-        tracing::info!("Loading plugin class: {}", class_info.name);
-
-        // Create PluginInfo from factory metadata
-        // Note: Vendor and version would require querying IPluginFactory3 interface
-        // which most plugins don't implement, so we use defaults for now
-        let info = PluginInfo {
-            name: class_info.name.clone(),
-            vendor: "Unknown".to_string(), // TODO: Query IPluginFactory3 if available
-            version: "1.0.0".to_string(),  // TODO: Parse from class info if available
-            unique_id: format!("{:?}", class_info.class_id),
-        };
+        // Create PluginInfo from factory metadata for every audio processor class
+        let infos: Vec<_> = Self::audio_classes(&factory)?
+            .iter()
+            .map(|(index, class_info)| {
+                tracing::info!("Found plugin class {index}: {}", class_info.name);
+                (
+                    *index,
+                    Self::plugin_info_for_class(&factory, *index, class_info),
+                )
+            })
+            .collect();
 
         // Explicitly drop to ensure cleanup order and verify unloading
         drop(factory);
         drop(library);
 
-        tracing::info!("Unloaded plugin library for: {}", info.name);
-        Ok(info)
+        tracing::info!("Unloaded plugin library for: {}", path.display());
+        Ok(infos)
     }
 
     /// Get the platform-specific library path within a VST3 bundle
@@ -713,7 +1378,7 @@ impl Vst3Loader {
     /// - Windows: `Contents/x86_64-win/<name>.vst3`
     /// - Linux: `Contents/x86_64-linux/<name>.so`
     #[cfg(target_os = "macos")]
-    fn get_library_path(bundle_path: &Path) -> Result<PathBuf, PluginError> {
+    pub(crate) fn get_library_path(bundle_path: &Path) -> Result<PathBuf, PluginError> {
         let name = bundle_path
             .file_stem()
             .and_then(|s| s.to_str())
@@ -723,7 +1388,7 @@ impl Vst3Loader {
     }
 
     #[cfg(target_os = "windows")]
-    fn get_library_path(bundle_path: &Path) -> Result<PathBuf, PluginError> {
+    pub(crate) fn get_library_path(bundle_path: &Path) -> Result<PathBuf, PluginError> {
         let name = bundle_path
             .file_stem()
             .and_then(|s| s.to_str())
@@ -736,7 +1401,7 @@ impl Vst3Loader {
     }
 
     #[cfg(target_os = "linux")]
-    fn get_library_path(bundle_path: &Path) -> Result<PathBuf, PluginError> {
+    pub(crate) fn get_library_path(bundle_path: &Path) -> Result<PathBuf, PluginError> {
         let name = bundle_path
             .file_stem()
             .and_then(|s| s.to_str())
@@ -796,8 +1461,27 @@ impl Vst3Loader {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_fixtures::example_plugin_path;
     use vvdaw_plugin::Plugin;
 
+    /// Name reported by the fixture plugin
+    const FIXTURE_NAME: &str = "vvdaw Fixture";
+
+    /// Parameters the fixture plugin declares, in order
+    const FIXTURE_PARAMETERS: [&str; 3] = ["Gain", "Mix", "Bypass"];
+
+    /// Default values of the fixture's parameters, in order
+    const FIXTURE_DEFAULTS: [f32; 3] = [0.5, 1.0, 0.0];
+
+    /// ID of the fixture's Gain parameter
+    const FIXTURE_GAIN: u32 = 0;
+
+    /// Latency the fixture plugin reports (samples)
+    const FIXTURE_LATENCY: usize = 64;
+
+    /// Tail the fixture plugin reports (samples)
+    const FIXTURE_TAIL: usize = 4800;
+
     #[test]
     fn test_scan_nonexistent_directory() {
         // Scanning a nonexistent directory should return empty list, not error
@@ -813,6 +1497,114 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_search_paths_merge_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugins = dir.path().join("plugins");
+        std::fs::create_dir(&plugins).unwrap();
+
+        let env = std::env::join_paths([
+            PathBuf::from("/opt/plugins"),
+            plugins.join("."),
+            PathBuf::from("/usr/lib/vst3"),
+        ])
+        .unwrap();
+        let merged = merge_search_paths(
+            std::slice::from_ref(&plugins),
+            Some(&env),
+            vec![
+                PathBuf::from("/usr/lib/vst3"),
+                PathBuf::from("/usr/local/lib/vst3"),
+            ],
+        );
+
+        // Repeats (including `plugins/.`) are dropped, keeping the first
+        assert_eq!(
+            merged,
+            [
+                plugins,
+                PathBuf::from("/opt/plugins"),
+                PathBuf::from("/usr/lib/vst3"),
+                PathBuf::from("/usr/local/lib/vst3"),
+            ]
+        );
+
+        // An empty entry doesn't mean the working directory
+        let merged = merge_search_paths(&[], Some(std::ffi::OsStr::new("")), Vec::new());
+        assert!(merged.is_empty());
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_extra_search_paths_come_first() {
+        let extra = PathBuf::from("/opt/plugins");
+        Vst3Loader::set_extra_search_paths(vec![extra.clone()]);
+        let paths = Vst3Loader::search_paths();
+        Vst3Loader::set_extra_search_paths(Vec::new());
+
+        assert_eq!(paths.first(), Some(&extra));
+        assert!(!Vst3Loader::search_paths().contains(&extra));
+    }
+
+    #[test]
+    fn test_scan_paths_skips_missing_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugins_dir = dir.path().join("plugins");
+        std::fs::create_dir(&plugins_dir).unwrap();
+        let cache_path = dir.path().join("cache.json");
+
+        let report = Vst3Loader::scan_paths_with_cache(
+            &[plugins_dir, dir.path().join("missing")],
+            &cache_path,
+        );
+        assert!(report.plugins.is_empty());
+        assert!(
+            report.errors.is_empty(),
+            "Missing directories aren't errors"
+        );
+        assert!(ScanCache::load(&cache_path).is_empty());
+    }
+
+    #[test]
+    fn test_scan_reports_failed_bundles() {
+        let dir = tempfile::tempdir().unwrap();
+        for bundle in ["Good.vst3", "Vendor/Broken.vst3"] {
+            std::fs::create_dir_all(dir.path().join(bundle)).unwrap();
+        }
+
+        let report = Vst3Loader::scan_search_paths(
+            &[dir.path().to_path_buf(), PathBuf::from("/etc/hosts")],
+            &mut |bundle: &Path| {
+                if bundle.ends_with("Broken.vst3") {
+                    return Err(PluginError::FormatError("no factory".to_string()));
+                }
+                Ok(vec![ScannedPlugin {
+                    path: bundle.to_path_buf(),
+                    class_index: 0,
+                    info: PluginInfo {
+                        name: "Good".to_string(),
+                        vendor: String::new(),
+                        version: String::new(),
+                        unique_id: String::new(),
+                        category: None,
+                    },
+                    parameters: Vec::new(),
+                }])
+            },
+        );
+
+        assert_eq!(report.plugins.len(), 1);
+        assert_eq!(report.plugins[0].name, "Good");
+        assert_eq!(
+            report.bundle_of(&report.plugins[0]),
+            Some(dir.path().join("Good.vst3").as_path())
+        );
+        assert_eq!(report.errors.len(), 2);
+        assert_eq!(report.errors[0].path, dir.path().join("Vendor/Broken.vst3"));
+        assert!(report.errors[0].message.contains("no factory"));
+        assert_eq!(report.errors[1].path, PathBuf::from("/etc/hosts"));
+    }
+
     /// Integration test: Scan all system plugins
     ///
     /// This test loads all installed VST3 plugins.
@@ -823,15 +1615,18 @@ mod tests {
         // Scan system-wide VST3 directories
         // This test is informational - it shows what plugins are installed
         // but doesn't fail if no plugins are found (e.g., CI environment)
-        let plugins = Vst3Loader::scan_system();
+        let report = Vst3Loader::scan_system();
 
-        eprintln!("Found {} VST3 plugins on system", plugins.len());
-        for plugin in &plugins {
+        eprintln!("Found {} VST3 plugins on system", report.plugins.len());
+        for plugin in &report.plugins {
             eprintln!(
                 "  - {} by {} (v{})",
                 plugin.name, plugin.vendor, plugin.version
             );
         }
+        for error in &report.errors {
+            eprintln!("  ! {error}");
+        }
 
         // Test passes regardless of how many plugins found
         // (some systems may have 0 plugins installed)
@@ -874,6 +1669,366 @@ mod tests {
         }
     }
 
+    fn param(id: u32, name: &str) -> ParameterInfo {
+        ParameterInfo {
+            id,
+            name: name.to_string(),
+            min_value: 0.0,
+            max_value: 1.0,
+            default_value: 0.5,
+            step_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_reconcile_parameters_keeps_matching_cache() {
+        let mut scanned = ScannedPlugin {
+            path: PathBuf::from("/tmp/Test.vst3"),
+            class_index: 0,
+            info: PluginInfo {
+                name: "Test".to_string(),
+                vendor: "Test".to_string(),
+                version: "1.0.0".to_string(),
+                unique_id: "test".to_string(),
+                category: None,
+            },
+            parameters: vec![param(0, "Gain"), param(1, "Mix")],
+        };
+
+        let live = vec![param(0, "Gain"), param(1, "Mix")];
+        assert!(!scanned.reconcile_parameters(&live));
+        assert_eq!(scanned.parameters.len(), 2);
+    }
+
+    #[test]
+    fn test_reconcile_parameters_replaces_stale_cache() {
+        let mut scanned = ScannedPlugin {
+            path: PathBuf::from("/tmp/Test.vst3"),
+            class_index: 0,
+            info: PluginInfo {
+                name: "Test".to_string(),
+                vendor: "Test".to_string(),
+                version: "1.0.0".to_string(),
+                unique_id: "test".to_string(),
+                category: None,
+            },
+            parameters: vec![param(0, "Gain")],
+        };
+
+        let live = vec![param(0, "Gain"), param(7, "Drive")];
+        assert!(scanned.reconcile_parameters(&live));
+        assert_eq!(scanned.parameters.len(), 2);
+        assert_eq!(scanned.parameters[1].name, "Drive");
+    }
+
+    #[test]
+    fn test_in_category() {
+        let mut plugin = PluginInfo {
+            name: "Synth".to_string(),
+            vendor: "Test".to_string(),
+            version: "1.0.0".to_string(),
+            unique_id: "test".to_string(),
+            category: Some("Instrument|Synth".to_string()),
+        };
+        assert!(in_category(&plugin, "Instrument"));
+        assert!(!in_category(&plugin, "Fx"));
+
+        plugin.category = None;
+        assert!(!in_category(&plugin, "Instrument"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_hung_scanner_is_killed() {
+        let child = Command::new("sleep")
+            .arg("30")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let start = Instant::now();
+        let result = Vst3Loader::wait_with_timeout(child, Duration::from_millis(100));
+        assert!(result.unwrap_err().to_string().contains("timed out"));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_scanner_output_is_collected() {
+        let child = Command::new("echo")
+            .arg("{}")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let output = Vst3Loader::wait_with_timeout(child, Duration::from_secs(5)).unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"{}\n");
+    }
+
+    fn audio_class(name: &str) -> ClassInfo {
+        ClassInfo {
+            class_id: [0; 16],
+            cardinality: 0x7FFF_FFFF,
+            category: AUDIO_MODULE_CATEGORY.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_select_class() {
+        // Class 1 (an edit controller) has already been filtered out
+        let classes = vec![(0, audio_class("Equalizer")), (2, audio_class("Limiter"))];
+
+        let (index, info) = Vst3Loader::select_class(&classes, None).unwrap();
+        assert_eq!((*index, info.name.as_str()), (0, "Equalizer"));
+
+        let selector = ClassSelector::Index(2);
+        let (_, info) = Vst3Loader::select_class(&classes, Some(&selector)).unwrap();
+        assert_eq!(info.name, "Limiter");
+
+        let selector = ClassSelector::Name("Limiter".to_string());
+        let (index, _) = Vst3Loader::select_class(&classes, Some(&selector)).unwrap();
+        assert_eq!(*index, 2);
+    }
+
+    #[test]
+    fn test_select_missing_class_lists_available() {
+        let classes = vec![(0, audio_class("Equalizer")), (2, audio_class("Limiter"))];
+
+        let selector = ClassSelector::Name("Reverb".to_string());
+        let message = Vst3Loader::select_class(&classes, Some(&selector))
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("Reverb"));
+        assert!(message.contains("0: 'Equalizer', 2: 'Limiter'"));
+
+        // Index 1 exists in the factory but isn't an audio processor
+        let selector = ClassSelector::Index(1);
+        assert!(Vst3Loader::select_class(&classes, Some(&selector)).is_err());
+    }
+
+    /// Integration test: Scanned parameter metadata matches the loaded plugin
+    ///
+    /// Uses #[serial] to prevent conflicts with other tests loading plugins.
+    #[test]
+    #[serial_test::serial]
+    fn test_scanned_parameters_match_loaded_plugin() {
+        let plugin_path = example_plugin_path();
+        let plugin_path = plugin_path.as_path();
+
+        let scanned = match Vst3Loader::scan_plugin_subprocess(plugin_path, true) {
+            Ok(s) => s[0].clone(),
+            Err(e) => {
+                eprintln!("Skipping test: Scanner unavailable - {e}");
+                return;
+            }
+        };
+
+        let live = Vst3Loader::load_plugin_parameters(plugin_path, scanned.class_index)
+            .expect("Failed to load the fixture's parameters");
+
+        assert_eq!(scanned.parameters.len(), live.len());
+        for (cached, live) in scanned.parameters.iter().zip(&live) {
+            assert_eq!(cached.id, live.id);
+            assert_eq!(cached.name, live.name);
+            assert_eq!(cached.default_value, live.default_value);
+        }
+    }
+
+    /// Integration test: Parameter enumeration on the fixture plugin
+    ///
+    /// Uses #[serial] to prevent conflicts with other tests loading plugins.
+    #[test]
+    #[serial_test::serial]
+    fn test_fixture_parameter_enumeration() {
+        let plugin_path = example_plugin_path();
+
+        let parameters = Vst3Loader::load_plugin_info_internal(&plugin_path)
+            .and_then(|classes| Vst3Loader::load_plugin_parameters(&plugin_path, classes[0].0))
+            .expect("Failed to load the fixture's parameters");
+
+        let mut ids: Vec<u32> = parameters.iter().map(|p| p.id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), parameters.len(), "Parameter IDs must be unique");
+
+        for param in &parameters {
+            assert!(
+                param.min_value <= param.default_value && param.default_value <= param.max_value,
+                "Default for '{}' is outside its range",
+                param.name
+            );
+        }
+
+        let names: Vec<&str> = parameters.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, FIXTURE_PARAMETERS);
+        let defaults: Vec<f32> = parameters.iter().map(|p| p.default_value).collect();
+        assert_eq!(defaults, FIXTURE_DEFAULTS);
+    }
+
+    /// Load and initialize the fixture plugin
+    fn initialized_fixture() -> Vst3Plugin {
+        let mut plugin =
+            Vst3Loader::load(example_plugin_path()).expect("Failed to load the fixture");
+        plugin
+            .initialize(48000, 512)
+            .expect("Failed to initialize the fixture");
+        plugin
+    }
+
+    /// Run one stereo block through `plugin`, returning the output
+    fn process_block(plugin: &mut Vst3Plugin, input: &[Vec<f32>]) -> Vec<Vec<f32>> {
+        let frames = input[0].len();
+        let mut output = vec![vec![f32::NAN; frames]; 2];
+        let input_refs: Vec<&[f32]> = input.iter().map(Vec::as_slice).collect();
+        let mut output_refs: Vec<&mut [f32]> = output.iter_mut().map(Vec::as_mut_slice).collect();
+        let mut audio = vvdaw_plugin::AudioBuffer {
+            inputs: &input_refs,
+            outputs: &mut output_refs,
+            frames,
+        };
+        plugin
+            .process(&mut audio, &vvdaw_plugin::EventBuffer::new())
+            .expect("Failed to process a block");
+        output
+    }
+
+    /// Integration test: Parameter values round-trip through `IEditController`
+    ///
+    /// Uses #[serial] to prevent conflicts with other tests loading plugins.
+    #[test]
+    #[serial_test::serial]
+    fn test_set_get_parameter_round_trip() {
+        let mut plugin = initialized_fixture();
+
+        plugin.set_parameter(FIXTURE_GAIN, 0.25).unwrap();
+        let value = plugin.get_parameter(FIXTURE_GAIN).unwrap();
+        assert!(
+            (value - 0.25).abs() < 1e-4,
+            "Gain read back as {value}, expected 0.25"
+        );
+
+        plugin.deactivate();
+    }
+
+    /// Integration test: Display strings round-trip through `IEditController`
+    ///
+    /// Uses #[serial] to prevent conflicts with other tests loading plugins.
+    #[test]
+    #[serial_test::serial]
+    fn test_parameter_display_round_trip() {
+        let mut plugin = initialized_fixture();
+
+        let text = plugin.parameter_display(FIXTURE_GAIN, 0.5).unwrap();
+        assert_eq!(text, "0.50");
+
+        // Display text is usually rounded, so only expect to land nearby
+        let value = plugin.parameter_from_string(FIXTURE_GAIN, &text).unwrap();
+        assert!(
+            (value - 0.5).abs() < 0.05,
+            "'{text}' parsed back as {value}, expected about 0.5"
+        );
+
+        plugin.deactivate();
+    }
+
+    /// Integration test: Latency and tail are queried from `IAudioProcessor` on activation
+    ///
+    /// Uses #[serial] to prevent conflicts with other tests loading plugins.
+    #[test]
+    #[serial_test::serial]
+    fn test_plugin_reports_latency() {
+        let mut plugin =
+            Vst3Loader::load(example_plugin_path()).expect("Failed to load the fixture");
+        assert_eq!(plugin.latency(), 0, "Latency is unknown until activated");
+
+        plugin
+            .initialize(48000, 512)
+            .expect("Failed to initialize the fixture");
+        assert_eq!(plugin.latency(), FIXTURE_LATENCY);
+        assert_eq!(plugin.tail_samples(), FIXTURE_TAIL);
+
+        plugin.deactivate();
+    }
+
+    /// Integration test: Every audio bus is activated, aux buses after main
+    ///
+    /// Uses #[serial] to prevent conflicts with other tests loading plugins.
+    #[test]
+    #[serial_test::serial]
+    fn test_plugin_exposes_sidechain_bus() {
+        let mut plugin = initialized_fixture();
+
+        // Main stereo input (channels 0-1) followed by the stereo sidechain (2-3)
+        assert_eq!(plugin.input_channels(), 4);
+        assert_eq!(plugin.output_channels(), 2);
+
+        // Processing with only the main bus connected must still work (the
+        // fixture rejects blocks whose bus buffers don't match its buses)
+        let output = process_block(&mut plugin, &vec![vec![0.0f32; 512]; 2]);
+        assert!(output.iter().flatten().all(|&s| s == 0.0));
+
+        plugin.deactivate();
+    }
+
+    /// Integration test: Audio and parameter changes reach the processor
+    ///
+    /// The fixture delays its input by its latency and scales it by Gain.
+    /// Uses #[serial] to prevent conflicts with other tests loading plugins.
+    #[test]
+    #[serial_test::serial]
+    fn test_fixture_processing() {
+        let mut plugin = initialized_fixture();
+        let frames = 256;
+        let ramp: Vec<f32> = (1..=frames).map(|i| i as f32).collect();
+        let input = vec![ramp.clone(), ramp];
+
+        // Default Gain is 0.5
+        let output = process_block(&mut plugin, &input);
+        for channel in &output {
+            assert!(channel[..FIXTURE_LATENCY].iter().all(|&s| s == 0.0));
+            assert_eq!(channel[FIXTURE_LATENCY], 0.5);
+            assert_eq!(channel[frames - 1], (frames - FIXTURE_LATENCY) as f32 * 0.5);
+        }
+
+        // A new Gain only reaches the processor through the next block's changes
+        plugin.set_parameter(FIXTURE_GAIN, 1.0).unwrap();
+        let output = process_block(&mut plugin, &input);
+        for channel in &output {
+            let delayed = (frames - FIXTURE_LATENCY + 1) as f32;
+            assert_eq!(channel[0], delayed);
+        }
+
+        plugin.deactivate();
+    }
+
+    /// Integration test: Component state round-trips and reaches the controller
+    ///
+    /// Uses #[serial] to prevent conflicts with other tests loading plugins.
+    #[test]
+    #[serial_test::serial]
+    fn test_fixture_state_round_trip() {
+        let mut plugin = initialized_fixture();
+        let silence = vec![vec![0.0f32; 64]; 2];
+
+        plugin.set_parameter(FIXTURE_GAIN, 0.25).unwrap();
+        process_block(&mut plugin, &silence);
+        let state = plugin.save_state().unwrap();
+
+        plugin.set_parameter(FIXTURE_GAIN, 0.75).unwrap();
+        process_block(&mut plugin, &silence);
+        assert_ne!(plugin.save_state().unwrap(), state);
+
+        plugin.load_state(&state).unwrap();
+        assert_eq!(plugin.save_state().unwrap(), state);
+        assert_eq!(plugin.get_parameter(FIXTURE_GAIN).unwrap(), 0.25);
+
+        plugin.deactivate();
+    }
+
     #[test]
     fn test_path_validation_rejects_parent_dir() {
         // Test that directory traversal attempts are rejected
@@ -897,60 +2052,30 @@ mod tests {
 
     /// Integration test: Load and initialize a real VST3 plugin
     ///
-    /// This test is only run if Noises.vst3 is available (common on macOS).
     /// It verifies the VST3 integration by:
-    /// 1. Loading a real plugin from filesystem
+    /// 1. Loading the fixture plugin from the filesystem
     /// 2. Initializing it with valid parameters
     /// 3. Verifying plugin info is correct
     /// 4. Cleanly shutting down
     ///
     /// Uses #[serial] to prevent conflicts with other tests loading plugins.
-    ///
-    /// Note: Audio processing is tested in the examples (`play_vst3`, `process_wav`)
-    /// which run in a full audio context. Unit tests don't have that context.
     #[test]
     #[serial_test::serial]
     fn test_integration_load_and_initialize() {
-        // Skip if plugin not available (e.g., CI environment)
-        let plugin_path = "/Library/Audio/Plug-Ins/VST3/Noises.vst3";
-        if !Path::new(plugin_path).exists() {
-            eprintln!("Skipping integration test: {plugin_path} not found");
-            return;
-        }
-
         // Load the plugin
-        let mut plugin = match Vst3Loader::load(plugin_path) {
-            Ok(p) => p,
-            Err(e) => {
-                eprintln!("Skipping integration test: Failed to load plugin - {e}");
-                return;
-            }
-        };
+        let mut plugin =
+            Vst3Loader::load(example_plugin_path()).expect("Failed to load the fixture");
 
         // Verify plugin info
-        assert_eq!(plugin.info().name, "Noises");
-
-        eprintln!("✓ Loaded plugin: {}", plugin.info().name);
-        eprintln!(
-            "  Channels: {} in, {} out",
-            plugin.input_channels(),
-            plugin.output_channels()
-        );
+        assert_eq!(plugin.info().name, FIXTURE_NAME);
+        assert_eq!(plugin.info().vendor, "vvdaw");
 
         // Initialize the plugin
-        let sample_rate = 48000;
-        let block_size = 512;
-        match plugin.initialize(sample_rate, block_size) {
-            Ok(()) => eprintln!("✓ Plugin initialized successfully"),
-            Err(e) => {
-                eprintln!("Skipping integration test: Failed to initialize - {e}");
-                return;
-            }
-        }
+        plugin
+            .initialize(48000, 512)
+            .expect("Failed to initialize the fixture");
 
         // Clean up
         plugin.deactivate();
-        eprintln!("✓ Plugin deactivated successfully");
-        eprintln!("✓ Integration test passed!");
     }
 }
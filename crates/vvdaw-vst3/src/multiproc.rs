@@ -20,6 +20,7 @@
 
 #![allow(clippy::doc_markdown)] // Allow technical terms without backticks in module docs
 
+use crate::ipc::MAX_CHANNELS;
 use crate::{ControlMessage, ResponseMessage, SharedAudioBuffer, SharedMemory};
 use std::io::{BufRead, BufReader, Write};
 use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
@@ -107,6 +108,12 @@ pub struct MultiProcessPlugin {
 
     /// Cached parameter list (queried once at startup)
     cached_parameters: Vec<ParameterInfo>,
+
+    /// Processing latency reported by the plugin after initialization
+    latency: Frames,
+
+    /// Tail length reported by the plugin after initialization
+    tail: Frames,
 }
 
 impl MultiProcessPlugin {
@@ -190,6 +197,7 @@ impl MultiProcessPlugin {
                 vendor: String::new(),
                 version: String::new(),
                 unique_id: String::new(),
+                category: None,
             },
             child_process: Mutex::new(child),
             stdin: Mutex::new(stdin),
@@ -204,16 +212,17 @@ impl MultiProcessPlugin {
             input_channels: 0,
             output_channels: 0,
             cached_parameters: Vec::new(),
+            latency: 0,
+            tail: 0,
         };
 
         // Wait for Ready message
         match plugin.wait_for_response()? {
             ResponseMessage::Ready { info } => {
                 plugin.info = info;
-                // TODO: Get channel counts from plugin info
-                plugin.input_channels = 2;
-                plugin.output_channels = 2;
 
+                // NOTE: Channel counts arrive with the Initialized response,
+                // once the plugin has read its bus layout.
                 // NOTE: Parameters are queried AFTER initialization in Plugin::initialize()
                 // because some plugins only populate parameter info after being initialized
 
@@ -337,10 +346,20 @@ impl Plugin for MultiProcessPlugin {
         })?;
 
         match self.wait_for_response()? {
-            ResponseMessage::Initialized => {
+            ResponseMessage::Initialized {
+                latency,
+                tail,
+                input_channels,
+                output_channels,
+            } => {
                 self.initialized = true;
                 self.sample_rate = sample_rate;
                 self.max_block_size = max_block_size;
+                self.latency = latency;
+                self.tail = tail;
+                // Shared memory only carries MAX_CHANNELS per direction
+                self.input_channels = input_channels.min(MAX_CHANNELS);
+                self.output_channels = output_channels.min(MAX_CHANNELS);
 
                 // Query parameters AFTER initialization
                 tracing::debug!("Querying plugin parameters after initialization...");
@@ -526,6 +545,109 @@ impl Plugin for MultiProcessPlugin {
         self.cached_parameters.clone()
     }
 
+    fn parameter_display(&self, id: u32, value: f32) -> Result<String, PluginError> {
+        if !self.is_alive() {
+            return Err(PluginError::ProcessingFailed(
+                "Subprocess has died".to_string(),
+            ));
+        }
+
+        self.send_message(&ControlMessage::GetParameterDisplay { id, value })?;
+
+        match self.wait_for_response()? {
+            ResponseMessage::ParameterDisplay {
+                id: response_id,
+                text,
+            } if response_id == id => Ok(text),
+            ResponseMessage::Error { message } => Err(PluginError::InvalidParameter(message)),
+            _ => Err(PluginError::InvalidParameter(format!(
+                "Unexpected response to parameter_display({id})"
+            ))),
+        }
+    }
+
+    fn parameter_from_string(&self, id: u32, text: &str) -> Result<f32, PluginError> {
+        if !self.is_alive() {
+            return Err(PluginError::ProcessingFailed(
+                "Subprocess has died".to_string(),
+            ));
+        }
+
+        self.send_message(&ControlMessage::ParseParameterValue {
+            id,
+            text: text.to_string(),
+        })?;
+
+        match self.wait_for_response()? {
+            ResponseMessage::ParameterValue {
+                id: response_id,
+                value,
+            } if response_id == id => Ok(value),
+            ResponseMessage::Error { message } => Err(PluginError::InvalidParameter(message)),
+            _ => Err(PluginError::InvalidParameter(format!(
+                "Unexpected response to parameter_from_string({id})"
+            ))),
+        }
+    }
+
+    fn load_preset(&mut self, path: &std::path::Path) -> Result<(), PluginError> {
+        if !self.is_alive() {
+            return Err(PluginError::ProcessingFailed(
+                "Subprocess has died".to_string(),
+            ));
+        }
+
+        self.send_message(&ControlMessage::LoadPreset {
+            path: path.to_string_lossy().to_string(),
+        })?;
+
+        match self.wait_for_response()? {
+            ResponseMessage::PresetLoaded => Ok(()),
+            ResponseMessage::Error { message } => Err(PluginError::FormatError(message)),
+            _ => Err(PluginError::FormatError(
+                "Unexpected response to load_preset".to_string(),
+            )),
+        }
+    }
+
+    fn save_state(&self) -> Result<Option<Vec<u8>>, PluginError> {
+        if !self.is_alive() {
+            return Err(PluginError::ProcessingFailed(
+                "Subprocess has died".to_string(),
+            ));
+        }
+
+        self.send_message(&ControlMessage::SaveState)?;
+
+        match self.wait_for_response()? {
+            ResponseMessage::State { data } => Ok(Some(data)),
+            ResponseMessage::Error { message } => Err(PluginError::FormatError(message)),
+            _ => Err(PluginError::FormatError(
+                "Unexpected response to save_state".to_string(),
+            )),
+        }
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> Result<(), PluginError> {
+        if !self.is_alive() {
+            return Err(PluginError::ProcessingFailed(
+                "Subprocess has died".to_string(),
+            ));
+        }
+
+        self.send_message(&ControlMessage::LoadState {
+            data: state.to_vec(),
+        })?;
+
+        match self.wait_for_response()? {
+            ResponseMessage::StateLoaded => Ok(()),
+            ResponseMessage::Error { message } => Err(PluginError::FormatError(message)),
+            _ => Err(PluginError::FormatError(
+                "Unexpected response to load_state".to_string(),
+            )),
+        }
+    }
+
     fn input_channels(&self) -> ChannelCount {
         self.input_channels
     }
@@ -534,6 +656,14 @@ impl Plugin for MultiProcessPlugin {
         self.output_channels
     }
 
+    fn latency(&self) -> Frames {
+        self.latency
+    }
+
+    fn tail_samples(&self) -> Frames {
+        self.tail
+    }
+
     fn deactivate(&mut self) {
         if !self.is_alive() {
             return; // Already dead
@@ -1,7 +1,8 @@
 //! VST3 parameter change queue implementations
 //!
 //! Implements `IParamValueQueue` and `IParameterChanges` interfaces for transmitting
-//! parameter changes from the host to the audio processor.
+//! parameter changes from the host to the audio processor, and for reading back
+//! the changes a processor outputs.
 
 use std::ffi::c_void;
 use std::sync::atomic::{AtomicU32, Ordering};
@@ -296,6 +297,19 @@ impl ParameterChanges {
         self.queues.push(Box::into_raw(queue));
     }
 
+    /// Every queued change as `(param_id, sample_offset, value)`, queue by queue
+    #[allow(unsafe_code)]
+    pub fn points(&self) -> impl Iterator<Item = (u32, i32, f64)> + '_ {
+        self.queues.iter().flat_map(|&queue_ptr| {
+            // SAFETY: We own all queue pointers; they stay valid until clear()
+            let queue = unsafe { &*queue_ptr };
+            queue
+                .points
+                .iter()
+                .map(|point| (queue.param_id, point.sample_offset, point.value))
+        })
+    }
+
     /// Clear all queues (for reuse)
     #[allow(unsafe_code)]
     pub fn clear(&mut self) {
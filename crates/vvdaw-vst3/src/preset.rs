@@ -0,0 +1,253 @@
+//! `.vstpreset` file parsing.
+//!
+//! A VST3 preset is a small chunk container:
+//!
+//! ```text
+//! Header (48 bytes)
+//!   "VST3"           4 bytes  file identifier
+//!   version          i32      format version (1)
+//!   class ID         32 bytes ASCII hex of the component's FUID
+//!   list offset      i64      position of the chunk list
+//! Chunk data ...
+//! Chunk list
+//!   "List"           4 bytes
+//!   entry count      i32
+//!   entries          id (4 bytes), offset (i64), size (i64)
+//! ```
+//!
+//! All integers are little-endian. The `Comp` chunk holds the component
+//! (processor) state and the optional `Cont` chunk the edit controller state.
+//! Other chunks (e.g. `Info` metadata) are ignored.
+
+use std::path::Path;
+use vvdaw_plugin::PluginError;
+
+/// File identifier at the start of every preset
+const HEADER_ID: &[u8; 4] = b"VST3";
+
+/// Identifier of the chunk list
+const LIST_ID: &[u8; 4] = b"List";
+
+/// Chunk holding the component state
+const COMPONENT_CHUNK_ID: &[u8; 4] = b"Comp";
+
+/// Chunk holding the edit controller state
+const CONTROLLER_CHUNK_ID: &[u8; 4] = b"Cont";
+
+/// Size of the class ID field (32 hex characters)
+const CLASS_ID_SIZE: usize = 32;
+
+/// Size of the header: identifier, version, class ID and list offset
+const HEADER_SIZE: usize = 4 + 4 + CLASS_ID_SIZE + 8;
+
+/// Size of one chunk list entry: identifier, offset and size
+const LIST_ENTRY_SIZE: usize = 4 + 8 + 8;
+
+/// The states stored in a `.vstpreset` file
+#[derive(Debug, Clone)]
+pub struct Vst3Preset {
+    /// Class ID of the component the preset was saved from
+    pub class_id: [u8; 16],
+    /// State for `IComponent::setState`
+    pub component_state: Vec<u8>,
+    /// State for `IEditController::setState`, if the preset has one
+    pub controller_state: Option<Vec<u8>>,
+}
+
+impl Vst3Preset {
+    /// Read a `.vstpreset` file
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, PluginError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|e| {
+            PluginError::FormatError(format!("Failed to read preset {}: {e}", path.display()))
+        })?;
+        Self::parse(&bytes)
+    }
+
+    /// Parse the contents of a `.vstpreset` file
+    pub fn parse(bytes: &[u8]) -> Result<Self, PluginError> {
+        if bytes.len() < HEADER_SIZE || &bytes[..4] != HEADER_ID {
+            return Err(invalid("not a VST3 preset file"));
+        }
+
+        let class_id = parse_class_id(&bytes[8..8 + CLASS_ID_SIZE])?;
+        let list_offset = read_offset(bytes, 8 + CLASS_ID_SIZE)?;
+
+        let list = bytes
+            .get(list_offset..list_offset + 8)
+            .ok_or_else(|| invalid("chunk list is out of bounds"))?;
+        if &list[..4] != LIST_ID {
+            return Err(invalid("chunk list not found"));
+        }
+        let entry_count = i32::from_le_bytes(list[4..8].try_into().unwrap_or_default());
+        let entry_count =
+            usize::try_from(entry_count).map_err(|_| invalid("negative chunk count"))?;
+
+        let mut component_state = None;
+        let mut controller_state = None;
+        for index in 0..entry_count {
+            let entry_start = list_offset + 8 + index * LIST_ENTRY_SIZE;
+            let entry = bytes
+                .get(entry_start..entry_start + LIST_ENTRY_SIZE)
+                .ok_or_else(|| invalid("chunk list is truncated"))?;
+
+            let offset = read_offset(entry, 4)?;
+            let size = read_offset(entry, 12)?;
+            let data = offset
+                .checked_add(size)
+                .and_then(|end| bytes.get(offset..end))
+                .ok_or_else(|| invalid("chunk data is out of bounds"))?;
+
+            match &entry[..4] {
+                id if id == COMPONENT_CHUNK_ID => component_state = Some(data.to_vec()),
+                id if id == CONTROLLER_CHUNK_ID => controller_state = Some(data.to_vec()),
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            class_id,
+            component_state: component_state
+                .ok_or_else(|| invalid("preset has no component state"))?,
+            controller_state,
+        })
+    }
+}
+
+/// Format a class ID the way the VST3 SDK's `FUID::toString` does
+///
+/// The string is the same on every platform, but Windows stores the first
+/// 8 bytes of a TUID in COM (GUID) byte order.
+pub fn class_id_string(class_id: &[u8; 16]) -> String {
+    use std::fmt::Write;
+
+    canonical_order(class_id)
+        .iter()
+        .fold(String::with_capacity(CLASS_ID_SIZE), |mut text, byte| {
+            let _ = write!(text, "{byte:02X}");
+            text
+        })
+}
+
+/// Reorder TUID bytes into the canonical (string) order, or back
+///
+/// The reordering is its own inverse.
+fn canonical_order(class_id: &[u8; 16]) -> [u8; 16] {
+    if cfg!(target_os = "windows") {
+        let d = class_id;
+        [
+            d[3], d[2], d[1], d[0], d[5], d[4], d[7], d[6], d[8], d[9], d[10], d[11], d[12], d[13],
+            d[14], d[15],
+        ]
+    } else {
+        *class_id
+    }
+}
+
+/// Parse the 32 hex character class ID field into TUID bytes
+fn parse_class_id(field: &[u8]) -> Result<[u8; 16], PluginError> {
+    let text = std::str::from_utf8(field).map_err(|_| invalid("class ID is not ASCII"))?;
+    let mut canonical = [0u8; 16];
+    for (index, byte) in canonical.iter_mut().enumerate() {
+        *byte = text
+            .get(index * 2..index * 2 + 2)
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            .ok_or_else(|| invalid("class ID is not hexadecimal"))?;
+    }
+    Ok(canonical_order(&canonical))
+}
+
+/// Read a little-endian i64 file offset or size at `at`
+fn read_offset(bytes: &[u8], at: usize) -> Result<usize, PluginError> {
+    let raw = bytes
+        .get(at..at + 8)
+        .and_then(|raw| raw.try_into().ok())
+        .map(i64::from_le_bytes)
+        .ok_or_else(|| invalid("file is truncated"))?;
+    usize::try_from(raw).map_err(|_| invalid("negative offset"))
+}
+
+fn invalid(reason: &str) -> PluginError {
+    PluginError::FormatError(format!("Invalid .vstpreset: {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLASS_ID: [u8; 16] = [
+        0x56, 0x56, 0x44, 0x41, 0x57, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09,
+        0x0A,
+    ];
+
+    /// Build a preset file with the given chunks
+    fn preset_bytes(class_id: &[u8; 16], chunks: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(HEADER_ID);
+        bytes.extend_from_slice(&1_i32.to_le_bytes());
+        bytes.extend_from_slice(class_id_string(class_id).as_bytes());
+        bytes.extend_from_slice(&0_i64.to_le_bytes()); // Patched below
+
+        let mut entries = Vec::new();
+        for (id, data) in chunks {
+            entries.push((*id, bytes.len(), data.len()));
+            bytes.extend_from_slice(data);
+        }
+
+        let list_offset = bytes.len() as i64;
+        bytes[8 + CLASS_ID_SIZE..HEADER_SIZE].copy_from_slice(&list_offset.to_le_bytes());
+        bytes.extend_from_slice(LIST_ID);
+        bytes.extend_from_slice(&(entries.len() as i32).to_le_bytes());
+        for (id, offset, size) in entries {
+            bytes.extend_from_slice(id);
+            bytes.extend_from_slice(&(offset as i64).to_le_bytes());
+            bytes.extend_from_slice(&(size as i64).to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_parse_component_and_controller_chunks() {
+        let bytes = preset_bytes(
+            &CLASS_ID,
+            &[
+                (COMPONENT_CHUNK_ID, b"component"),
+                (b"Info", b"<MetaInfo/>"),
+                (CONTROLLER_CHUNK_ID, b"controller"),
+            ],
+        );
+        let preset = Vst3Preset::parse(&bytes).unwrap();
+        assert_eq!(preset.class_id, CLASS_ID);
+        assert_eq!(preset.component_state, b"component");
+        assert_eq!(preset.controller_state.as_deref(), Some(&b"controller"[..]));
+    }
+
+    #[test]
+    fn test_controller_chunk_is_optional() {
+        let bytes = preset_bytes(&CLASS_ID, &[(COMPONENT_CHUNK_ID, b"state")]);
+        let preset = Vst3Preset::parse(&bytes).unwrap();
+        assert!(preset.controller_state.is_none());
+    }
+
+    #[test]
+    fn test_class_id_string_is_hex() {
+        let text = class_id_string(&CLASS_ID);
+        assert_eq!(text.len(), CLASS_ID_SIZE);
+        assert_eq!(parse_class_id(text.as_bytes()).unwrap(), CLASS_ID);
+        #[cfg(not(target_os = "windows"))]
+        assert_eq!(text, "5656444157000102030405060708090A");
+    }
+
+    #[test]
+    fn test_invalid_presets_are_rejected() {
+        assert!(Vst3Preset::parse(b"not a preset").is_err());
+
+        // No component state
+        let bytes = preset_bytes(&CLASS_ID, &[(CONTROLLER_CHUNK_ID, b"controller")]);
+        assert!(Vst3Preset::parse(&bytes).is_err());
+
+        // Chunk list truncated
+        let bytes = preset_bytes(&CLASS_ID, &[(COMPONENT_CHUNK_ID, b"state")]);
+        assert!(Vst3Preset::parse(&bytes[..bytes.len() - 4]).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+//! Persistent cache of plugin scan results.
+//!
+//! Scanning runs a subprocess per bundle, which adds up to seconds on a
+//! system with many plugins. [`ScanCache`] remembers what each bundle
+//! reported, keyed by bundle path and the modification time of its binary,
+//! so [`Vst3Loader::scan_system`](crate::Vst3Loader::scan_system) only runs
+//! the scanner for new or updated bundles.
+//!
+//! The cache is a JSON file in the platform's cache directory (see
+//! [`ScanCache::default_path`]). A missing, unreadable or outdated file is
+//! treated as an empty cache.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use vvdaw_core::paths::{self, UserDir};
+use vvdaw_plugin::{PluginError, PluginInfo};
+
+/// Cache file format version, bumped when the layout changes
+const CACHE_VERSION: u32 = 1;
+
+/// File name of the cache inside the cache directory
+const CACHE_FILE: &str = "plugin_scan.json";
+
+/// One audio processor class found in a bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedClass {
+    /// Factory class index (for `ClassSelector::Index`)
+    pub class_index: i32,
+    /// Plugin metadata reported by the scanner
+    pub info: PluginInfo,
+}
+
+/// Scan result of one bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    /// Modification time of the bundle's binary when it was scanned
+    modified: SystemTime,
+    /// Classes the bundle exported
+    classes: Vec<CachedClass>,
+}
+
+/// Scan results keyed by bundle path and modification time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanCache {
+    version: u32,
+    bundles: HashMap<PathBuf, CacheEntry>,
+}
+
+impl ScanCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self {
+            version: CACHE_VERSION,
+            bundles: HashMap::new(),
+        }
+    }
+
+    /// Standard location of the cache file for the current platform
+    ///
+    /// - macOS: `~/Library/Caches/vvdaw/plugin_scan.json`
+    /// - Windows: `%LOCALAPPDATA%\vvdaw\plugin_scan.json`
+    /// - Linux: `$XDG_CACHE_HOME/vvdaw/plugin_scan.json` (default `~/.cache`)
+    ///
+    /// Returns `None` if the home directory can't be determined.
+    pub fn default_path() -> Option<PathBuf> {
+        paths::user_dir(UserDir::Cache).map(|dir| dir.join(CACHE_FILE))
+    }
+
+    /// Read a cache file
+    ///
+    /// Never fails: a missing or unusable file gives an empty cache, so the
+    /// next scan simply rebuilds it.
+    pub fn load(path: &Path) -> Self {
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::debug!("No plugin scan cache at {}: {e}", path.display());
+                return Self::new();
+            }
+        };
+
+        match serde_json::from_str::<Self>(&json) {
+            Ok(cache) if cache.version == CACHE_VERSION => cache,
+            Ok(cache) => {
+                tracing::info!(
+                    "Ignoring plugin scan cache version {} (expected {CACHE_VERSION})",
+                    cache.version
+                );
+                Self::new()
+            }
+            Err(e) => {
+                tracing::warn!("Ignoring corrupt plugin scan cache {}: {e}", path.display());
+                Self::new()
+            }
+        }
+    }
+
+    /// Write the cache file, creating its directory if needed
+    pub fn save(&self, path: &Path) -> Result<(), PluginError> {
+        let io_error = |e: std::io::Error| {
+            PluginError::FormatError(format!(
+                "Failed to write plugin scan cache {}: {e}",
+                path.display()
+            ))
+        };
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(io_error)?;
+        }
+
+        let json = serde_json::to_string_pretty(self).map_err(|e| {
+            PluginError::FormatError(format!("Failed to serialize plugin scan cache: {e}"))
+        })?;
+        std::fs::write(path, json).map_err(io_error)
+    }
+
+    /// Classes cached for `bundle`, if it hasn't changed since it was scanned
+    pub fn get(&self, bundle: &Path, modified: SystemTime) -> Option<&[CachedClass]> {
+        self.bundles
+            .get(bundle)
+            .filter(|entry| entry.modified == modified)
+            .map(|entry| entry.classes.as_slice())
+    }
+
+    /// Record the classes `bundle` reported when its binary had time `modified`
+    pub fn insert(&mut self, bundle: &Path, modified: SystemTime, classes: Vec<CachedClass>) {
+        self.bundles
+            .insert(bundle.to_path_buf(), CacheEntry { modified, classes });
+    }
+
+    /// Drop every bundle inside `dir`, e.g. before rescanning it
+    pub fn forget_under(&mut self, dir: &Path) {
+        self.bundles.retain(|bundle, _| !bundle.starts_with(dir));
+    }
+
+    /// Number of cached bundles
+    pub fn len(&self) -> usize {
+        self.bundles.len()
+    }
+
+    /// Whether no bundles are cached
+    pub fn is_empty(&self) -> bool {
+        self.bundles.is_empty()
+    }
+}
+
+impl Default for ScanCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn class(name: &str) -> CachedClass {
+        CachedClass {
+            class_index: 0,
+            info: PluginInfo {
+                name: name.to_string(),
+                vendor: "Test".to_string(),
+                version: "1.0.0".to_string(),
+                unique_id: "test".to_string(),
+                category: Some("Fx".to_string()),
+            },
+        }
+    }
+
+    #[test]
+    fn test_round_trip_through_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("nested").join(CACHE_FILE);
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+
+        let mut cache = ScanCache::new();
+        cache.insert(
+            Path::new("/plugins/Delay.vst3"),
+            modified,
+            vec![class("Delay")],
+        );
+        cache.save(&file).unwrap();
+
+        let loaded = ScanCache::load(&file);
+        assert_eq!(loaded.len(), 1);
+        let classes = loaded
+            .get(Path::new("/plugins/Delay.vst3"), modified)
+            .unwrap();
+        assert_eq!(classes[0].info.name, "Delay");
+        assert_eq!(classes[0].info.category.as_deref(), Some("Fx"));
+    }
+
+    #[test]
+    fn test_changed_bundle_misses() {
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let mut cache = ScanCache::new();
+        cache.insert(
+            Path::new("/plugins/Delay.vst3"),
+            modified,
+            vec![class("Delay")],
+        );
+
+        let updated = modified + Duration::from_secs(1);
+        assert!(
+            cache
+                .get(Path::new("/plugins/Delay.vst3"), updated)
+                .is_none()
+        );
+        assert!(
+            cache
+                .get(Path::new("/plugins/Other.vst3"), modified)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_forget_under_keeps_other_directories() {
+        let modified = SystemTime::UNIX_EPOCH;
+        let mut cache = ScanCache::new();
+        cache.insert(
+            Path::new("/plugins/Delay.vst3"),
+            modified,
+            vec![class("Delay")],
+        );
+        cache.insert(
+            Path::new("/opt/plugins/Synth.vst3"),
+            modified,
+            vec![class("Synth")],
+        );
+
+        cache.forget_under(Path::new("/opt/plugins"));
+        assert_eq!(cache.len(), 1);
+        assert!(
+            cache
+                .get(Path::new("/plugins/Delay.vst3"), modified)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_unusable_files_give_empty_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(ScanCache::load(&dir.path().join("missing.json")).is_empty());
+
+        let corrupt = dir.path().join("corrupt.json");
+        std::fs::write(&corrupt, "{ not json").unwrap();
+        assert!(ScanCache::load(&corrupt).is_empty());
+
+        let future = dir.path().join("future.json");
+        std::fs::write(&future, r#"{"version": 99, "bundles": {}}"#).unwrap();
+        assert!(ScanCache::load(&future).is_empty());
+    }
+}
@@ -13,16 +13,15 @@
 //!
 //! ## Usage
 //!
-//! This module is not currently used during plugin initialization.
+//! This module is not used during plugin initialization.
 //! According to VST3 spec, `setComponentState()` is only called when
 //! loading presets or restoring saved sessions, not during fresh initialization.
 //!
-//! This will be used in the future for:
-//! - Loading plugin presets
-//! - Restoring session state
-//! - Saving/loading plugin configurations
+//! Used for:
+//! - Loading plugin presets (`Vst3Plugin::load_preset`)
+//! - Saving and restoring session state (`Vst3Plugin::save_state`/`load_state`)
 
-// Allow dead_code for the entire module - will be used for preset/session management
+// Allow dead_code for the entire module - parts will be used for session management
 #![allow(dead_code)]
 
 use std::ffi::c_void;
@@ -122,6 +121,18 @@ impl MemoryStream {
         }
     }
 
+    /// Create a memory stream holding `data`, positioned at the start
+    ///
+    /// Caller must use `Box::leak()` to transfer ownership to COM reference counting
+    pub fn from_bytes(data: Vec<u8>) -> Self {
+        Self {
+            vtable: &raw const VTABLE,
+            ref_count: AtomicU32::new(1),
+            data,
+            position: 0,
+        }
+    }
+
     /// Create a memory stream with initial capacity
     ///
     /// Caller must use `Box::leak()` to transfer ownership to COM reference counting
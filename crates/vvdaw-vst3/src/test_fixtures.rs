@@ -0,0 +1,112 @@
+//! Shared fixtures for VST3 integration tests.
+//!
+//! Integration tests load the deterministic plugin from the `vvdaw-fixture`
+//! crate, whose behaviour is described in that crate's README. The first
+//! call to [`example_plugin_path`] builds it with the cargo running the tests
+//! and lays the library out as a `vvdaw-fixture.vst3` bundle under the target
+//! directory, so the tests never depend on what happens to be installed.
+
+use crate::loader::Vst3Loader;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Package name of the fixture plugin crate
+const FIXTURE_PACKAGE: &str = "vvdaw-fixture";
+
+/// File name of the fixture bundle
+const FIXTURE_BUNDLE: &str = "vvdaw-fixture.vst3";
+
+/// Path to the fixture bundle, building it on first use
+///
+/// # Panics
+///
+/// Panics if the fixture can't be built or bundled, so tests that need a
+/// plugin fail instead of passing without having loaded one.
+pub fn example_plugin_path() -> PathBuf {
+    static BUNDLE: OnceLock<PathBuf> = OnceLock::new();
+
+    BUNDLE
+        .get_or_init(|| {
+            build_fixture().unwrap_or_else(|e| {
+                panic!("Failed to build the {FIXTURE_PACKAGE} test plugin: {e}")
+            })
+        })
+        .clone()
+}
+
+/// Directory the fixture is built and bundled in
+///
+/// Test binaries run from `<target>/<profile>/deps`, so this is
+/// `<target>/vvdaw-fixture`. It has its own cargo target directory because
+/// the cargo running the tests may still hold the lock on `<target>`.
+fn fixture_dir() -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.parent()?.parent()?.to_path_buf()))
+        .unwrap_or_else(std::env::temp_dir)
+        .join(FIXTURE_PACKAGE)
+}
+
+/// Build the fixture crate and install its library into a bundle
+fn build_fixture() -> Result<PathBuf, String> {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../Cargo.toml");
+    let target_dir = fixture_dir();
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+
+    let output = Command::new(cargo)
+        .arg("build")
+        .arg("--package")
+        .arg(FIXTURE_PACKAGE)
+        .arg("--manifest-path")
+        .arg(&manifest)
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .output()
+        .map_err(|e| format!("could not run cargo: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "cargo build exited with {}:\n{}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let library = target_dir.join("debug").join(format!(
+        "{}vvdaw_fixture{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    ));
+    let bundle = target_dir.join(FIXTURE_BUNDLE);
+    let installed = Vst3Loader::get_library_path(&bundle).map_err(|e| e.to_string())?;
+    install(&library, &installed)
+        .map_err(|e| format!("could not bundle {}: {e}", library.display()))?;
+
+    Ok(bundle)
+}
+
+/// Copy `library` to `installed`
+///
+/// The copy is renamed into place, so another test process loading the
+/// bundle never sees a partly written library.
+fn install(library: &Path, installed: &Path) -> std::io::Result<()> {
+    if let Some(parent) = installed.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let partial = installed.with_file_name(format!(".partial-{}", std::process::id()));
+    std::fs::copy(library, &partial)?;
+    std::fs::rename(&partial, installed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixture_is_built_into_a_bundle() {
+        let bundle = example_plugin_path();
+        assert!(bundle.ends_with(FIXTURE_BUNDLE));
+        assert!(Vst3Loader::get_library_path(&bundle).unwrap().is_file());
+    }
+}
@@ -4,11 +4,25 @@
 //! and implements our format-agnostic Plugin trait.
 
 use crate::com::PluginFactory;
+use crate::component_handler::ParameterEdit;
+use crate::event_list::EventList;
 use crate::parameter_changes::ParameterChanges;
+use crate::preset::Vst3Preset;
+use crate::stream::MemoryStream;
 use libloading::Library;
+use rtrb::Consumer;
 use std::collections::HashMap;
-use vvdaw_core::{ChannelCount, Frames, SampleRate};
-use vvdaw_plugin::{AudioBuffer, EventBuffer, ParameterInfo, Plugin, PluginError, PluginInfo};
+use std::path::Path;
+use vvdaw_core::{ChannelCount, Frames, SampleRate, Transport};
+use vvdaw_plugin::{
+    AudioBuffer, EventBuffer, INFINITE_TAIL, ParameterInfo, Plugin, PluginError, PluginInfo,
+};
+
+/// Maximum audio buses per direction passed to `IAudioProcessor::process`
+///
+/// Bus buffers are built in a stack array each process call. Buses beyond
+/// this limit are left inactive.
+const MAX_BUSES: usize = 16;
 
 /// VST3 plugin wrapper
 ///
@@ -17,11 +31,15 @@ use vvdaw_plugin::{AudioBuffer, EventBuffer, ParameterInfo, Plugin, PluginError,
 pub struct Vst3Plugin {
     info: PluginInfo,
 
-    // VST3-specific fields
-    #[allow(dead_code)] // Will be used for COM calls
-    library: Library,
+    // Class ID of the component, checked against presets before loading them
+    class_id: [u8; 16],
+
+    // VST3-specific fields (dropped in this order: the factory has to be
+    // released while the library is still loaded)
     #[allow(dead_code)] // Will be used for COM calls
     factory: PluginFactory,
+    #[allow(dead_code)] // Will be used for COM calls
+    library: Library,
 
     // COM interface pointers
     component: *mut std::ffi::c_void,
@@ -34,21 +52,68 @@ pub struct Vst3Plugin {
     input_channels: ChannelCount,
     output_channels: ChannelCount,
 
+    // Channel count of each audio bus in VST3 bus index order (main bus first).
+    // Node channels are the buses' channels concatenated, so e.g. a sidechain
+    // compressor's key input is channels 2-3. Empty until initialize() reads
+    // the plugin's bus layout - and inputs stay empty for an instrument.
+    input_buses: Vec<ChannelCount>,
+    output_buses: Vec<ChannelCount>,
+
     // Pre-allocated buffers for VST3 process calls
     // These avoid allocations in the audio hot path
     input_channel_ptrs: Vec<*mut f32>,
     output_channel_ptrs: Vec<*mut f32>,
 
+    // Stand-ins for bus channels the caller doesn't provide
+    // (e.g. an unconnected sidechain)
+    silent_input: Vec<f32>,
+    discarded_output: Vec<f32>,
+
     // Track activation state to avoid double-deactivation
     is_active: bool,
 
+    // Processing latency reported by the plugin when it was last activated
+    latency: Frames,
+
+    // Tail length reported by the plugin when it was last activated
+    tail: Frames,
+
     // Track parameters that changed since last process() call
     // Map of parameter ID -> normalized value (0.0-1.0)
     dirty_parameters: HashMap<u32, f64>,
 
+    // Edits made in the plugin's own editor, queued by its component handler
+    parameter_edits: Consumer<ParameterEdit>,
+
     // Reusable parameter changes object for sending parameter updates to processor
     // This is populated from dirty_parameters before each process() call
     parameter_changes: ParameterChanges,
+
+    // Reusable event list for sending note events to processor
+    // This is populated from the EventBuffer before each process() call
+    event_list: EventList,
+
+    // What the processor emitted in the last process() call, read back by
+    // take_output_events(). Cleared before each call. The event list is only
+    // allocated for plugins with an event output bus (e.g. arpeggiators).
+    output_parameter_changes: ParameterChanges,
+    output_events: Option<EventList>,
+
+    // Host transport for the next process() call, set by set_transport()
+    // (state 0 = never set, sent as a null context)
+    process_context: crate::com::ProcessContext,
+
+    // 64-bit processing: allowed by the host via set_double_precision(), and
+    // active if the plugin also supports it (negotiated in initialize())
+    double_precision_allowed: bool,
+    double_precision: bool,
+
+    // Pre-allocated 64-bit channel buffers (empty unless double_precision).
+    // Audio is converted to and from the host's f32 buffers around process().
+    input_buffers_64: Vec<Vec<f64>>,
+    output_buffers_64: Vec<Vec<f64>>,
+    input_channel_ptrs_64: Vec<*mut f64>,
+    output_channel_ptrs_64: Vec<*mut f64>,
 }
 
 impl Vst3Plugin {
@@ -66,34 +131,359 @@ impl Vst3Plugin {
     /// Create a new VST3 plugin wrapper with library, factory, and COM interfaces
     ///
     /// This is the proper constructor called by the loader.
+    #[allow(clippy::too_many_arguments)] // One per COM object the loader hands over
     pub(crate) fn new_with_library(
         info: PluginInfo,
+        class_id: [u8; 16],
         library: Library,
         factory: PluginFactory,
         component: *mut std::ffi::c_void,
         processor: *mut std::ffi::c_void,
         edit_controller: Option<*mut std::ffi::c_void>,
+        parameter_edits: Consumer<ParameterEdit>,
     ) -> Self {
-        // Pre-allocate channel pointer vectors (will be resized during initialization)
-        let input_channels = 2;
-        let output_channels = 2;
-
         Self {
             info,
-            library,
+            class_id,
             factory,
+            library,
             component,
             processor,
             edit_controller,
             sample_rate: 48000,
             block_size: 512,
-            input_channels,
-            output_channels,
-            input_channel_ptrs: Vec::with_capacity(input_channels),
-            output_channel_ptrs: Vec::with_capacity(output_channels),
+            input_channels: 0,
+            output_channels: 0,
+            input_buses: Vec::new(),
+            output_buses: Vec::new(),
+            input_channel_ptrs: Vec::new(),
+            output_channel_ptrs: Vec::new(),
+            silent_input: Vec::new(),
+            discarded_output: Vec::new(),
             is_active: false,
+            latency: 0,
+            tail: 0,
             dirty_parameters: HashMap::new(),
+            parameter_edits,
             parameter_changes: ParameterChanges::new(),
+            event_list: EventList::new(),
+            output_parameter_changes: ParameterChanges::new(),
+            output_events: None,
+            process_context: crate::com::ProcessContext::default(),
+            double_precision_allowed: false,
+            double_precision: false,
+            input_buffers_64: Vec::new(),
+            output_buffers_64: Vec::new(),
+            input_channel_ptrs_64: Vec::new(),
+            output_channel_ptrs_64: Vec::new(),
+        }
+    }
+
+    /// Load a `.vstpreset` file into the plugin
+    ///
+    /// The preset must have been saved from this plugin's class. The component
+    /// state goes to `IComponent::setState` and is mirrored to the edit
+    /// controller, then any controller chunk goes to `IEditController::setState`.
+    /// The plugin must be initialized first.
+    pub fn load_preset<P: AsRef<Path>>(&mut self, path: P) -> Result<(), PluginError> {
+        let path = path.as_ref();
+        let preset = Vst3Preset::read(path)?;
+
+        if preset.class_id != self.class_id {
+            return Err(PluginError::FormatError(format!(
+                "Preset {} is for class {}, not '{}' ({})",
+                path.display(),
+                crate::preset::class_id_string(&preset.class_id),
+                self.info.name,
+                crate::preset::class_id_string(&self.class_id)
+            )));
+        }
+
+        self.require_active("loading a preset")?;
+
+        tracing::info!(
+            "Loading preset {} into '{}'",
+            path.display(),
+            self.info.name
+        );
+
+        self.apply_state(preset.component_state, preset.controller_state)
+    }
+
+    /// Format a normalized parameter value with `IEditController::getParamStringByValue`
+    ///
+    /// Gives the plugin's own text for the value, units included (e.g. "-6.0 dB", "440 Hz").
+    #[allow(unsafe_code)] // Required for FFI calls
+    pub fn parameter_display(&self, id: u32, normalized: f32) -> Result<String, PluginError> {
+        let edit_controller = self.require_edit_controller(id)?;
+        unsafe {
+            crate::com::edit_controller_get_param_string_by_value(
+                edit_controller,
+                id,
+                f64::from(normalized),
+            )
+        }
+    }
+
+    /// Parse display text into a normalized value with `IEditController::getParamValueByString`
+    #[allow(unsafe_code)] // Required for FFI calls
+    #[allow(clippy::cast_possible_truncation)] // Normalized values are in [0, 1]
+    pub fn parameter_from_string(&self, id: u32, text: &str) -> Result<f32, PluginError> {
+        let edit_controller = self.require_edit_controller(id)?;
+        let value = unsafe {
+            crate::com::edit_controller_get_param_value_by_string(edit_controller, id, text)?
+        };
+        Ok(value as f32)
+    }
+
+    /// The edit controller, or an error naming parameter `id` if there is none
+    fn require_edit_controller(&self, id: u32) -> Result<*mut std::ffi::c_void, PluginError> {
+        self.edit_controller.ok_or_else(|| {
+            PluginError::InvalidParameter(format!(
+                "Cannot access parameter {id}: plugin has no edit controller"
+            ))
+        })
+    }
+
+    /// Capture the component state with `IComponent::getState`
+    ///
+    /// The bytes are the same as the `Comp` chunk of a `.vstpreset` saved from
+    /// the plugin, and can be restored with [`Vst3Plugin::load_state`]. The
+    /// plugin must be initialized first.
+    #[allow(unsafe_code)] // Required for FFI calls
+    pub fn save_state(&self) -> Result<Vec<u8>, PluginError> {
+        self.require_active("saving its state")?;
+
+        unsafe {
+            Self::with_stream(Vec::new(), |stream| {
+                crate::com::component_get_state(self.component, stream)?;
+                // SAFETY: `stream` is the MemoryStream created by with_stream,
+                // alive until with_stream releases it after this closure
+                Ok((*stream.cast::<MemoryStream>()).data().to_vec())
+            })
+        }
+    }
+
+    /// Restore a component state captured by [`Vst3Plugin::save_state`]
+    ///
+    /// The state is mirrored to the edit controller, as for presets. The
+    /// plugin must be initialized first.
+    pub fn load_state(&mut self, state: &[u8]) -> Result<(), PluginError> {
+        self.require_active("loading its state")?;
+        self.apply_state(state.to_vec(), None)
+    }
+
+    /// Fail unless `initialize()` has created and activated the component
+    fn require_active(&self, action: &str) -> Result<(), PluginError> {
+        if self.is_active {
+            Ok(())
+        } else {
+            Err(PluginError::InitializationFailed(format!(
+                "Plugin must be initialized before {action}"
+            )))
+        }
+    }
+
+    /// Hand a component state (and optional controller state) to the plugin
+    ///
+    /// The component state goes to `IComponent::setState` and is mirrored to
+    /// the edit controller with `setComponentState`; the controller state goes
+    /// to `IEditController::setState`.
+    #[allow(unsafe_code)] // Required for FFI calls
+    fn apply_state(
+        &mut self,
+        component_state: Vec<u8>,
+        controller_state: Option<Vec<u8>>,
+    ) -> Result<(), PluginError> {
+        unsafe {
+            Self::with_stream(component_state.clone(), |stream| {
+                crate::com::component_set_state(self.component, stream)
+            })?;
+
+            if let Some(edit_controller) = self.edit_controller {
+                // Keep the controller's parameters in sync with the new component state
+                Self::with_stream(component_state, |stream| {
+                    crate::com::edit_controller_set_component_state(edit_controller, stream)
+                })?;
+
+                if let Some(controller_state) = controller_state {
+                    Self::with_stream(controller_state, |stream| {
+                        crate::com::edit_controller_set_state(edit_controller, stream)
+                    })?;
+                }
+            }
+        }
+
+        // Values set before the new state are stale now
+        self.dirty_parameters.clear();
+
+        Ok(())
+    }
+
+    /// Hand `data` to a plugin call as an `IBStream`
+    ///
+    /// The stream is released afterwards; plugins that keep a reference keep it alive.
+    ///
+    /// # Safety
+    ///
+    /// `f` must only use the stream pointer as an `IBStream`.
+    #[allow(unsafe_code)] // Required for COM reference counting
+    unsafe fn with_stream<T>(
+        data: Vec<u8>,
+        f: impl FnOnce(*mut std::ffi::c_void) -> Result<T, PluginError>,
+    ) -> Result<T, PluginError> {
+        // SAFETY: Box::leak hands ownership to COM reference counting - the
+        // stream frees itself in release() when the count reaches 0
+        let stream = Box::leak(Box::new(MemoryStream::from_bytes(data))).as_com_ptr();
+        let result = f(stream);
+        unsafe { crate::com::release_interface(stream) };
+        result
+    }
+
+    /// Query the audio buses in one direction and activate them all
+    ///
+    /// Returns each bus's channel count in bus index order, which is empty for
+    /// e.g. an instrument's inputs. A bus `getBusInfo` can't describe falls
+    /// back to its speaker arrangement. Failing to activate the main bus is an
+    /// error; auxiliary buses (e.g. a sidechain) are optional.
+    ///
+    /// # Safety
+    ///
+    /// `component` and `processor` must be valid, initialized `IComponent` and
+    /// `IAudioProcessor` pointers.
+    #[allow(unsafe_code)] // Required for FFI calls
+    #[allow(clippy::cast_sign_loss)] // Bus and channel counts are never negative
+    unsafe fn activate_audio_buses(
+        component: *mut std::ffi::c_void,
+        processor: *mut std::ffi::c_void,
+        direction: i32,
+    ) -> Result<Vec<ChannelCount>, PluginError> {
+        let bus_count = unsafe { crate::com::component_get_bus_count(component, 0, direction) };
+        let bus_count = (bus_count.max(0) as usize).min(MAX_BUSES);
+
+        let mut buses = Vec::with_capacity(bus_count);
+        for index in 0..bus_count as i32 {
+            let channels =
+                match unsafe { crate::com::component_get_bus_info(component, 0, direction, index) }
+                {
+                    Ok(info) => info.channel_count.max(0) as ChannelCount,
+                    Err(e) => {
+                        tracing::warn!("Failed to get info for bus {index}: {e}");
+                        match unsafe {
+                            crate::com::processor_get_bus_arrangement(processor, direction, index)
+                        } {
+                            Ok(arrangement) => arrangement.count_ones() as ChannelCount,
+                            Err(e) => {
+                                // Skip buses we can't describe at all
+                                tracing::warn!("Failed to get arrangement for bus {index}: {e}");
+                                0
+                            }
+                        }
+                    }
+                };
+
+            let activated =
+                unsafe { crate::com::component_activate_bus(component, 0, direction, index, true) };
+            match activated {
+                Ok(()) => buses.push(channels),
+                Err(e) if index == 0 => return Err(e),
+                Err(e) => {
+                    tracing::warn!("Failed to activate aux bus {index}: {e}");
+                    buses.push(0);
+                }
+            }
+        }
+
+        Ok(buses)
+    }
+
+    /// Per-bus buffer descriptors over a flattened channel pointer array
+    ///
+    /// Points at `channel_ptrs_64` when processing in 64-bit, otherwise at
+    /// `channel_ptrs` (the unused pointer array may be empty).
+    ///
+    /// REAL-TIME SAFE: Built on the stack. Only the first `buses.len()` entries
+    /// are meaningful.
+    #[allow(clippy::cast_possible_wrap)] // Channel counts are small
+    fn bus_buffers(
+        buses: &[ChannelCount],
+        channel_ptrs: &mut [*mut f32],
+        channel_ptrs_64: &mut [*mut f64],
+        double_precision: bool,
+    ) -> [crate::com::AudioBusBuffers; MAX_BUSES] {
+        let mut offset = 0;
+        std::array::from_fn(|index| {
+            let channels = buses.get(index).copied().unwrap_or(0);
+            let bus = crate::com::AudioBusBuffers {
+                num_channels: channels as i32,
+                silence_flags: 0,
+                channel_buffers: if double_precision {
+                    crate::com::ChannelBuffers {
+                        samples_64: channel_ptrs_64[offset..].as_mut_ptr(),
+                    }
+                } else {
+                    crate::com::ChannelBuffers {
+                        samples_32: channel_ptrs[offset..].as_mut_ptr(),
+                    }
+                },
+            };
+            offset += channels;
+            bus
+        })
+    }
+
+    /// Allocate the 64-bit channel buffers for the current bus layout
+    ///
+    /// Frees them instead when not processing in 64-bit.
+    fn allocate_buffers_64(&mut self, max_block_size: Frames) {
+        if self.double_precision {
+            self.input_buffers_64 = vec![vec![0.0; max_block_size]; self.input_channels];
+            self.output_buffers_64 = vec![vec![0.0; max_block_size]; self.output_channels];
+        } else {
+            self.input_buffers_64 = Vec::new();
+            self.output_buffers_64 = Vec::new();
+        }
+
+        // The inner buffers are never resized, so these pointers stay valid
+        self.input_channel_ptrs_64 = self
+            .input_buffers_64
+            .iter_mut()
+            .map(Vec::as_mut_ptr)
+            .collect();
+        self.output_channel_ptrs_64 = self
+            .output_buffers_64
+            .iter_mut()
+            .map(Vec::as_mut_ptr)
+            .collect();
+    }
+
+    /// Convert the caller's input channels into the 64-bit input buffers
+    ///
+    /// Channels the caller doesn't provide (e.g. an unconnected sidechain)
+    /// are silent. REAL-TIME SAFE: Buffers are pre-allocated.
+    fn load_inputs_64(&mut self, audio: &AudioBuffer, frames: Frames) {
+        for (i, buffer) in self.input_buffers_64.iter_mut().enumerate() {
+            let buffer = &mut buffer[..frames];
+            match audio.inputs.get(i) {
+                Some(input) => {
+                    for (dst, &src) in buffer.iter_mut().zip(input.iter()) {
+                        *dst = f64::from(src);
+                    }
+                }
+                None => buffer.fill(0.0),
+            }
+        }
+    }
+
+    /// Convert the 64-bit output buffers back into the caller's output channels
+    ///
+    /// REAL-TIME SAFE: No allocation.
+    #[allow(clippy::cast_possible_truncation)] // Back to the graph's f32 samples
+    fn store_outputs_64(&self, audio: &mut AudioBuffer, frames: Frames) {
+        for (output, buffer) in audio.outputs.iter_mut().zip(&self.output_buffers_64) {
+            for (dst, &src) in output.iter_mut().zip(&buffer[..frames]) {
+                *dst = src as f32;
+            }
         }
     }
 }
@@ -126,15 +516,6 @@ impl Plugin for Vst3Plugin {
         self.sample_rate = sample_rate;
         self.block_size = max_block_size;
 
-        // Resize channel pointer vectors to match configuration
-        // This happens once during initialization, not in the audio hot path
-        self.input_channel_ptrs.clear();
-        self.input_channel_ptrs
-            .resize(self.input_channels, std::ptr::null_mut());
-        self.output_channel_ptrs.clear();
-        self.output_channel_ptrs
-            .resize(self.output_channels, std::ptr::null_mut());
-
         unsafe {
             // Step 1: Create host application context and initialize the component
             tracing::debug!("Creating host application context...");
@@ -221,38 +602,69 @@ impl Plugin for Vst3Plugin {
             }
 
             // Step 2: Set up audio processing parameters
+            // Process in 64-bit only if both the host and the plugin allow it
+            self.double_precision = self.double_precision_allowed
+                && crate::com::processor_can_process_sample_size(
+                    self.processor,
+                    crate::com::SAMPLE_64,
+                );
             let process_setup = crate::com::ProcessSetup {
-                process_mode: 0,         // 0 = realtime
-                symbolic_sample_size: 0, // 0 = 32-bit float
+                process_mode: 0, // 0 = realtime
+                symbolic_sample_size: if self.double_precision {
+                    crate::com::SAMPLE_64
+                } else {
+                    crate::com::SAMPLE_32
+                },
                 max_samples_per_block: max_block_size as i32,
                 sample_rate: f64::from(sample_rate),
             };
             crate::com::processor_setup_processing(self.processor, &process_setup)?;
-            tracing::debug!("IAudioProcessor::setupProcessing succeeded");
+            tracing::debug!(
+                "IAudioProcessor::setupProcessing succeeded ({}-bit)",
+                if self.double_precision { 64 } else { 32 }
+            );
 
-            // Step 3: Check and activate audio buses
+            // Step 3: Query the audio bus layout and activate every bus
             // Media type: 0=audio, 1=event
             // Bus direction: 0=input, 1=output
-            let input_bus_count = crate::com::component_get_bus_count(self.component, 0, 0);
-            let output_bus_count = crate::com::component_get_bus_count(self.component, 0, 1);
+            self.input_buses = Self::activate_audio_buses(self.component, self.processor, 0)?;
+            self.output_buses = Self::activate_audio_buses(self.component, self.processor, 1)?;
+            self.input_channels = self.input_buses.iter().sum();
+            self.output_channels = self.output_buses.iter().sum();
 
             tracing::debug!(
-                "Bus counts: {} inputs, {} outputs",
-                input_bus_count,
-                output_bus_count
+                "Audio buses: inputs {:?}, outputs {:?}",
+                self.input_buses,
+                self.output_buses
             );
 
-            // Activate audio buses if they exist
-            if input_bus_count > 0 {
-                tracing::debug!("Activating input bus 0...");
-                crate::com::component_activate_bus(self.component, 0, 0, 0, true)?;
-                tracing::debug!("Input bus 0 activated");
+            // Size channel pointers and stand-in buffers to the bus layout
+            // This happens once during initialization, not in the audio hot path
+            self.input_channel_ptrs.clear();
+            self.input_channel_ptrs
+                .resize(self.input_channels, std::ptr::null_mut());
+            self.output_channel_ptrs.clear();
+            self.output_channel_ptrs
+                .resize(self.output_channels, std::ptr::null_mut());
+            self.silent_input = vec![0.0; max_block_size];
+            self.discarded_output = vec![0.0; max_block_size];
+            self.allocate_buffers_64(max_block_size);
+
+            // Activate the event input bus so instruments receive notes
+            let event_input_bus_count = crate::com::component_get_bus_count(self.component, 1, 0);
+            if event_input_bus_count > 0 {
+                tracing::debug!("Activating event input bus 0...");
+                crate::com::component_activate_bus(self.component, 1, 0, 0, true)?;
+                tracing::debug!("Event input bus 0 activated");
             }
 
-            if output_bus_count > 0 {
-                tracing::debug!("Activating output bus 0...");
-                crate::com::component_activate_bus(self.component, 0, 1, 0, true)?;
-                tracing::debug!("Output bus 0 activated");
+            // Activate the event output bus so arpeggiators can emit notes
+            self.output_events = None;
+            if crate::com::component_get_bus_count(self.component, 1, 1) > 0 {
+                tracing::debug!("Activating event output bus 0...");
+                crate::com::component_activate_bus(self.component, 1, 1, 0, true)?;
+                tracing::debug!("Event output bus 0 activated");
+                self.output_events = Some(EventList::new());
             }
 
             // Step 4: Activate the component
@@ -260,6 +672,15 @@ impl Plugin for Vst3Plugin {
             crate::com::component_set_active(self.component, true)?;
             tracing::debug!("IComponent::setActive(true) succeeded");
 
+            // Latency may depend on the processing setup, so query it once active
+            self.latency = crate::com::processor_get_latency_samples(self.processor) as Frames;
+            tracing::debug!("Plugin latency: {} samples", self.latency);
+            self.tail = match crate::com::processor_get_tail_samples(self.processor) {
+                crate::com::K_INFINITE_TAIL => INFINITE_TAIL,
+                tail => tail as Frames,
+            };
+            tracing::debug!("Plugin tail: {} samples", self.tail);
+
             // Step 4: Start audio processing
             tracing::debug!("Calling IAudioProcessor::setProcessing(true)...");
             crate::com::processor_set_processing(self.processor, true)?;
@@ -277,7 +698,7 @@ impl Plugin for Vst3Plugin {
     fn process(
         &mut self,
         audio: &mut AudioBuffer,
-        _events: &EventBuffer,
+        events: &EventBuffer,
     ) -> Result<(), PluginError> {
         // REAL-TIME SAFE: Only log first call to avoid flooding
         static FIRST_CALL: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(true);
@@ -289,63 +710,48 @@ impl Plugin for Vst3Plugin {
         }
 
         unsafe {
-            // Step 1: Validate buffer counts match expected channels
-            // This prevents accessing invalid pointers in the channel arrays
-            let actual_input_channels = audio.inputs.len().min(self.input_channels);
-            let actual_output_channels = audio.outputs.len().min(self.output_channels);
-
-            if audio.inputs.len() < self.input_channels {
-                tracing::warn!(
-                    "Audio buffer has {} input channels but plugin expects {}",
-                    audio.inputs.len(),
-                    self.input_channels
-                );
-            }
-
-            if audio.outputs.len() < self.output_channels {
-                tracing::warn!(
-                    "Audio buffer has {} output channels but plugin expects {}",
-                    audio.outputs.len(),
-                    self.output_channels
-                );
+            // Step 1: Point each plugin channel at the matching buffer channel
+            // Channels the caller doesn't provide (e.g. an unconnected sidechain)
+            // read silence and write to a discarded scratch buffer.
+            let frames = audio.frames.min(self.silent_input.len());
+            if self.double_precision {
+                // 64-bit: the plugin reads and writes our converted copies
+                self.load_inputs_64(audio, frames);
+            } else if audio.inputs.len() < self.input_channels {
+                self.silent_input[..frames].fill(0.0);
             }
-
-            // Step 2: Update input channel pointers (only for actual available channels)
-            // VST3 expects an array of pointers to channel buffers
-            for (i, input_slice) in audio.inputs.iter().enumerate().take(actual_input_channels) {
-                if i < self.input_channel_ptrs.len() {
-                    // Cast away const - VST3 may write to input buffers for in-place processing
-                    self.input_channel_ptrs[i] = input_slice.as_ptr().cast_mut();
-                }
+            for (i, ptr) in self.input_channel_ptrs.iter_mut().enumerate() {
+                // Cast away const - VST3 may write to input buffers for in-place processing
+                *ptr = audio
+                    .inputs
+                    .get(i)
+                    .map_or(self.silent_input.as_mut_ptr(), |input| {
+                        input.as_ptr().cast_mut()
+                    });
             }
-
-            // Step 3: Update output channel pointers (only for actual available channels)
-            for (i, output_slice) in audio
-                .outputs
-                .iter_mut()
-                .enumerate()
-                .take(actual_output_channels)
-            {
-                if i < self.output_channel_ptrs.len() {
-                    self.output_channel_ptrs[i] = output_slice.as_mut_ptr();
-                }
+            for (i, ptr) in self.output_channel_ptrs.iter_mut().enumerate() {
+                *ptr = audio
+                    .outputs
+                    .get_mut(i)
+                    .map_or(self.discarded_output.as_mut_ptr(), |output| {
+                        output.as_mut_ptr()
+                    });
             }
 
-            // Step 4: Create AudioBusBuffers for input and output
-            // IMPORTANT: Use actual channel counts to prevent VST3 from accessing invalid pointers
-            let mut input_bus = crate::com::AudioBusBuffers {
-                num_channels: actual_input_channels as i32,
-                silence_flags: 0,
-                channel_buffers_32: self.input_channel_ptrs.as_mut_ptr(),
-                channel_buffers_64: std::ptr::null_mut(),
-            };
-
-            let mut output_bus = crate::com::AudioBusBuffers {
-                num_channels: actual_output_channels as i32,
-                silence_flags: 0,
-                channel_buffers_32: self.output_channel_ptrs.as_mut_ptr(),
-                channel_buffers_64: std::ptr::null_mut(),
-            };
+            // Step 2: Build one AudioBusBuffers per bus, each pointing at its
+            // slice of the flattened channel pointer arrays
+            let mut input_buses = Self::bus_buffers(
+                &self.input_buses,
+                &mut self.input_channel_ptrs,
+                &mut self.input_channel_ptrs_64,
+                self.double_precision,
+            );
+            let mut output_buses = Self::bus_buffers(
+                &self.output_buses,
+                &mut self.output_channel_ptrs,
+                &mut self.output_channel_ptrs_64,
+                self.double_precision,
+            );
 
             // Step 4: Populate parameter changes from dirty parameters
             // Clear previous parameter changes and add current dirty parameters
@@ -362,20 +768,47 @@ impl Plugin for Vst3Plugin {
                 (&raw mut self.parameter_changes).cast::<std::ffi::c_void>()
             };
 
+            // Translate note events (null if there are none)
+            self.event_list.fill_from(events);
+            let events_ptr = if self.event_list.is_empty() {
+                std::ptr::null_mut()
+            } else {
+                (&raw mut self.event_list).cast::<std::ffi::c_void>()
+            };
+
+            // Empty lists for whatever the plugin emits this block
+            self.output_parameter_changes.clear();
+            let output_events_ptr =
+                self.output_events
+                    .as_mut()
+                    .map_or(std::ptr::null_mut(), |list| {
+                        list.clear();
+                        std::ptr::from_mut(list).cast::<std::ffi::c_void>()
+                    });
+
             // Step 5: Create ProcessData structure
             let mut process_data = crate::com::ProcessData {
-                process_mode: 0,         // 0 = realtime
-                symbolic_sample_size: 0, // 0 = 32-bit float
-                num_samples: audio.frames as i32,
-                num_inputs: 1,  // Single stereo bus for now
-                num_outputs: 1, // Single stereo bus for now
-                inputs: &raw mut input_bus,
-                outputs: &raw mut output_bus,
+                process_mode: 0, // 0 = realtime
+                symbolic_sample_size: if self.double_precision {
+                    crate::com::SAMPLE_64
+                } else {
+                    crate::com::SAMPLE_32
+                },
+                num_samples: frames as i32,
+                num_inputs: self.input_buses.len() as i32,
+                num_outputs: self.output_buses.len() as i32,
+                inputs: input_buses.as_mut_ptr(),
+                outputs: output_buses.as_mut_ptr(),
                 input_param_changes: param_changes_ptr,
-                output_param_changes: std::ptr::null_mut(),
-                input_events: std::ptr::null_mut(),
-                output_events: std::ptr::null_mut(),
-                process_context: std::ptr::null_mut(),
+                output_param_changes: (&raw mut self.output_parameter_changes)
+                    .cast::<std::ffi::c_void>(),
+                input_events: events_ptr,
+                output_events: output_events_ptr,
+                process_context: if self.process_context.state == 0 {
+                    std::ptr::null_mut()
+                } else {
+                    &raw mut self.process_context
+                },
             };
 
             // DEBUG: Check buffers before processing
@@ -401,6 +834,10 @@ impl Plugin for Vst3Plugin {
             // Step 6: Call VST3 processor->process()
             crate::com::processor_process(self.processor, &raw mut process_data)?;
 
+            if self.double_precision {
+                self.store_outputs_64(audio, frames);
+            }
+
             // Step 7: Clear dirty parameters now that they've been sent to processor
             self.dirty_parameters.clear();
 
@@ -449,9 +886,9 @@ impl Plugin for Vst3Plugin {
                 unsafe { crate::com::edit_controller_get_param_normalized(edit_controller, id)? };
             Ok(normalized_value as f32)
         } else {
-            Err(PluginError::FormatError(
-                "Edit controller not initialized".to_string(),
-            ))
+            Err(PluginError::InvalidParameter(format!(
+                "Cannot read parameter {id}: plugin has no edit controller"
+            )))
         }
     }
 
@@ -482,15 +919,15 @@ impl Plugin for Vst3Plugin {
                             .trim_end_matches('\0')
                             .to_string();
 
-                        // VST3 parameters are normalized to [0.0, 1.0]
-                        // For now, we'll use that range directly
-                        // TODO: Handle step_count for discrete parameters
+                        // VST3 parameters are normalized to [0.0, 1.0]; discrete
+                        // ones spread their steps evenly across that range
                         let param_info = ParameterInfo {
                             id: vst3_param_info.id,
                             name,
                             min_value: 0.0,
                             max_value: 1.0,
                             default_value: vst3_param_info.default_normalized_value as f32,
+                            step_count: u32::try_from(vst3_param_info.step_count).unwrap_or(0),
                         };
 
                         parameters.push(param_info);
@@ -513,6 +950,74 @@ impl Plugin for Vst3Plugin {
         self.output_channels
     }
 
+    fn latency(&self) -> Frames {
+        self.latency
+    }
+
+    fn tail_samples(&self) -> Frames {
+        self.tail
+    }
+
+    fn load_preset(&mut self, path: &Path) -> Result<(), PluginError> {
+        Self::load_preset(self, path)
+    }
+
+    fn parameter_display(&self, id: u32, value: f32) -> Result<String, PluginError> {
+        Self::parameter_display(self, id, value)
+    }
+
+    fn parameter_from_string(&self, id: u32, text: &str) -> Result<f32, PluginError> {
+        Self::parameter_from_string(self, id, text)
+    }
+
+    fn save_state(&self) -> Result<Option<Vec<u8>>, PluginError> {
+        Self::save_state(self).map(Some)
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> Result<(), PluginError> {
+        Self::load_state(self, state)
+    }
+
+    fn set_double_precision(&mut self, allowed: bool) {
+        self.double_precision_allowed = allowed;
+    }
+
+    fn double_precision(&self) -> bool {
+        self.double_precision
+    }
+
+    fn set_transport(&mut self, transport: &Transport) {
+        self.process_context = crate::com::ProcessContext::from_transport(transport);
+    }
+
+    fn poll_parameter_edit(&mut self) -> Option<(u32, f32)> {
+        let edit = self.parameter_edits.pop().ok()?;
+
+        // The controller already has the value - the processor still needs it
+        self.dirty_parameters.insert(edit.id, edit.value);
+        Some((edit.id, edit.value as f32))
+    }
+
+    fn emits_events(&self) -> bool {
+        self.output_events.is_some()
+    }
+
+    fn take_output_events(&mut self, events: &mut EventBuffer) {
+        if let Some(list) = &self.output_events {
+            list.append_to(events);
+        }
+        for (id, sample_offset, value) in self.output_parameter_changes.points() {
+            let event = vvdaw_plugin::Event::ParamChange {
+                id,
+                value: value as f32,
+                sample_offset: u32::try_from(sample_offset).unwrap_or(0),
+            };
+            if !events.try_push(event) {
+                break;
+            }
+        }
+    }
+
     #[allow(unsafe_code)] // Required for FFI calls
     fn deactivate(&mut self) {
         // Only deactivate if currently active (avoid double-deactivation)
@@ -533,20 +1038,31 @@ impl Plugin for Vst3Plugin {
                 tracing::error!("Failed to deactivate component: {}", e);
             }
 
-            // Step 3: Deactivate buses (only if they exist)
-            let input_bus_count = crate::com::component_get_bus_count(self.component, 0, 0);
-            let output_bus_count = crate::com::component_get_bus_count(self.component, 0, 1);
+            // Step 3: Deactivate the audio buses activated in initialize()
+            for (direction, buses) in [(0, &self.input_buses), (1, &self.output_buses)] {
+                for index in 0..buses.len() as i32 {
+                    if let Err(e) = crate::com::component_activate_bus(
+                        self.component,
+                        0,
+                        direction,
+                        index,
+                        false,
+                    ) {
+                        tracing::error!("Failed to deactivate audio bus {index}: {e}");
+                    }
+                }
+            }
 
-            if input_bus_count > 0
-                && let Err(e) = crate::com::component_activate_bus(self.component, 0, 0, 0, false)
+            if crate::com::component_get_bus_count(self.component, 1, 0) > 0
+                && let Err(e) = crate::com::component_activate_bus(self.component, 1, 0, 0, false)
             {
-                tracing::error!("Failed to deactivate input bus: {}", e);
+                tracing::error!("Failed to deactivate event input bus: {}", e);
             }
 
-            if output_bus_count > 0
-                && let Err(e) = crate::com::component_activate_bus(self.component, 0, 1, 0, false)
+            if self.output_events.is_some()
+                && let Err(e) = crate::com::component_activate_bus(self.component, 1, 1, 0, false)
             {
-                tracing::error!("Failed to deactivate output bus: {}", e);
+                tracing::error!("Failed to deactivate event output bus: {}", e);
             }
 
             // Note: COM interfaces (component, processor) are released when
@@ -594,9 +1110,37 @@ mod tests {
             vendor: "Test Vendor".to_string(),
             version: "1.0.0".to_string(),
             unique_id: "test123".to_string(),
+            category: None,
         };
 
         assert_eq!(info.name, "Test Plugin");
         assert_eq!(info.vendor, "Test Vendor");
     }
+
+    #[test]
+    #[allow(unsafe_code)] // Reading back the channel buffer union
+    fn test_bus_buffers_select_sample_size() {
+        // Stereo main bus plus a stereo sidechain
+        let buses = [2, 2];
+        let mut ptrs_32 = vec![std::ptr::null_mut::<f32>(); 4];
+        let mut ptrs_64 = vec![std::ptr::null_mut::<f64>(); 4];
+
+        let single = Vst3Plugin::bus_buffers(&buses, &mut ptrs_32, &mut [], false);
+        assert_eq!(single[1].num_channels, 2);
+        assert_eq!(
+            unsafe { single[1].channel_buffers.samples_32 },
+            ptrs_32[2..].as_mut_ptr()
+        );
+
+        let double = Vst3Plugin::bus_buffers(&buses, &mut ptrs_32, &mut ptrs_64, true);
+        assert_eq!(
+            unsafe { double[0].channel_buffers.samples_64 },
+            ptrs_64.as_mut_ptr()
+        );
+        assert_eq!(
+            unsafe { double[1].channel_buffers.samples_64 },
+            ptrs_64[2..].as_mut_ptr()
+        );
+        assert_eq!(double[2].num_channels, 0);
+    }
 }